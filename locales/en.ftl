@@ -0,0 +1,62 @@
+# English messages; the fallback for every other locale
+# `{ $name }` placeholders are filled in by the caller
+
+## recipe_importer
+importer-name-title = Recipe Name (Enter to Continue)
+importer-servings-title = Servings { $recipe } makes (Enter to skip)
+importer-servings-error = Servings { $recipe } makes - ERROR: { $error }
+importer-category-title = Which meal is { $recipe } for? (Up/Down or 1-5, Enter to choose)
+importer-category-none = (none)
+importer-equipment-title = Equipment { $recipe } needs, separated by commas (Enter to skip)
+importer-ingredients-title = Ingredients for { $recipe }
+importer-ingredient-input = Enter ingredients for { $recipe } (Enter on empty to continue)
+importer-ingredient-error = Enter ingredients for { $recipe } - ERROR: { $error }
+importer-confirm-title = Confirm New Ingredient
+importer-confirm-prompt = Add new ingredient '{ $ingredient }'?
+importer-confirm-keys = (Y)es / (N)o
+importer-quantity-title = Quantity for { $ingredient }
+importer-notes-title = Notes for { $ingredient } (Enter to skip)
+importer-instructions-title = Instructions
+importer-step-input = Enter step { $step } (Enter on empty to finish)
+importer-review-title = Review { $recipe }
+importer-review-summary = { $ingredients } ingredients, { $steps } steps
+importer-review-ok = Nothing looks off.
+importer-warnings-title = Warnings
+importer-review-keys = Enter to save, Backspace to add steps, Esc to cancel
+importer-resume-title = Unfinished Recipe
+importer-resume-prompt = Pick up where you left off on '{ $recipe }'?
+importer-resume-keys = (Y)es / (N)o, start a new recipe
+warning-missing-quantity = { $subject }: no quantity given
+warning-large-quantity = { $subject }: unusually large quantity
+warning-long-step = step { $step }: { $chars } characters long; consider splitting it
+
+## pantry
+pantry-title = Pantry (+/- adjust, s staple, a add, d delete, q quit)
+pantry-error-title = Pantry - ERROR: { $error }
+pantry-select = Select an item
+pantry-ingredient-input = Ingredient (Tab to complete, Esc to cancel)
+pantry-ingredient-suggestions = Ingredient (Tab: { $suggestions })
+pantry-quantity-input = Quantity of { $ingredient } (e.g. 2 kg, 6)
+pantry-bad-quantity = can't read quantity '{ $input }'
+pantry-unknown-barcode = unknown barcode { $barcode }
+
+## feedme
+dinner-none = No recipes match.
+dinner-minutes = ({ $minutes } min)
+dinner-last-made = - last made { $date }
+dinner-never-made = - never made
+similar-none = No similar recipes.
+similar-shares = - shares { $shared }
+report-empty = No planned or cooked meals between { $start } and { $end }.
+digest-nothing-planned = No meals planned from { $date } on.
+almost-cookable-none = No recipes are missing { $max } or fewer ingredients.
+unknown-ingredient = There is no ingredient called { $name }.
+fetch-nutrition-no-match = no match
+
+## cooking mode
+cook-title = { $recipe } - step { $step } of { $total } (←/→ step, t or 1-9 timer, x clear, q quit)
+cook-no-steps = This recipe has no instructions.
+cook-ingredients = Ingredients for this step
+cook-timers = Timers
+cook-timer-step = step { $step }
+cook-timer-done = DONE
@@ -0,0 +1,61 @@
+# Mensajes en español
+
+## recipe_importer
+importer-name-title = Nombre de la receta (Enter para continuar)
+importer-servings-title = Raciones de { $recipe } (Enter para omitir)
+importer-servings-error = Raciones de { $recipe } - ERROR: { $error }
+importer-category-title = ¿Para qué comida es { $recipe }? (Arriba/Abajo o 1-5, Enter para elegir)
+importer-category-none = (ninguna)
+importer-equipment-title = Utensilios que necesita { $recipe }, separados por comas (Enter para omitir)
+importer-ingredients-title = Ingredientes de { $recipe }
+importer-ingredient-input = Escribe los ingredientes de { $recipe } (Enter vacío para continuar)
+importer-ingredient-error = Escribe los ingredientes de { $recipe } - ERROR: { $error }
+importer-confirm-title = Confirmar ingrediente nuevo
+importer-confirm-prompt = ¿Añadir el ingrediente nuevo '{ $ingredient }'?
+importer-confirm-keys = (Y) sí / (N) no
+importer-quantity-title = Cantidad de { $ingredient }
+importer-notes-title = Notas para { $ingredient } (Enter para omitir)
+importer-instructions-title = Instrucciones
+importer-step-input = Escribe el paso { $step } (Enter vacío para terminar)
+importer-review-title = Revisar { $recipe }
+importer-review-summary = { $ingredients } ingredientes, { $steps } pasos
+importer-review-ok = Todo parece correcto.
+importer-warnings-title = Avisos
+importer-review-keys = Enter para guardar, Retroceso para añadir pasos, Esc para cancelar
+importer-resume-title = Receta sin terminar
+importer-resume-prompt = ¿Seguir con '{ $recipe }' donde lo dejaste?
+importer-resume-keys = (Y) sí / (N) no, empezar una receta nueva
+warning-missing-quantity = { $subject }: falta la cantidad
+warning-large-quantity = { $subject }: cantidad inusualmente grande
+warning-long-step = paso { $step }: { $chars } caracteres; considera dividirlo
+
+## pantry
+pantry-title = Despensa (+/- ajustar, s básico, a añadir, d borrar, q salir)
+pantry-error-title = Despensa - ERROR: { $error }
+pantry-select = Elige un artículo
+pantry-ingredient-input = Ingrediente (Tab para completar, Esc para cancelar)
+pantry-ingredient-suggestions = Ingrediente (Tab: { $suggestions })
+pantry-quantity-input = Cantidad de { $ingredient } (p. ej. 2 kg, 6)
+pantry-bad-quantity = no se entiende la cantidad '{ $input }'
+pantry-unknown-barcode = código de barras desconocido { $barcode }
+
+## feedme
+dinner-none = Ninguna receta coincide.
+dinner-minutes = ({ $minutes } min)
+dinner-last-made = - preparada por última vez el { $date }
+dinner-never-made = - nunca preparada
+similar-none = No hay recetas parecidas.
+similar-shares = - comparte { $shared }
+report-empty = No hay comidas planificadas ni cocinadas entre { $start } y { $end }.
+digest-nothing-planned = No hay comidas planificadas a partir del { $date }.
+almost-cookable-none = A ninguna receta le faltan { $max } ingredientes o menos.
+unknown-ingredient = No hay ningún ingrediente llamado { $name }.
+fetch-nutrition-no-match = sin coincidencias
+
+## cooking mode
+cook-title = { $recipe } - paso { $step } de { $total } (←/→ paso, t o 1-9 temporizador, x borrar, q salir)
+cook-no-steps = Esta receta no tiene instrucciones.
+cook-ingredients = Ingredientes de este paso
+cook-timers = Temporizadores
+cook-timer-step = paso { $step }
+cook-timer-done = LISTO
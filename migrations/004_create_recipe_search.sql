@@ -0,0 +1,64 @@
+-- Full-text index over recipe names, instructions, ingredient notes and
+-- ingredient names. rowid is the recipe id; triggers below keep it in sync.
+CREATE VIRTUAL TABLE IF NOT EXISTS recipe_search USING fts5(
+    name,
+    instructions,
+    notes,
+    ingredients,
+    tokenize = 'porter unicode61'
+);
+
+-- Backfill any recipes that already exist
+INSERT INTO recipe_search (rowid, name, instructions, notes, ingredients)
+SELECT
+    r.id,
+    r.name,
+    COALESCE(r.instructions, ''),
+    COALESCE((SELECT group_concat(ri.notes, ' ') FROM recipe_ingredients ri WHERE ri.recipe_id = r.id), ''),
+    COALESCE((SELECT group_concat(i.name, ' ') FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id WHERE ri.recipe_id = r.id), '')
+FROM recipes r;
+
+CREATE TRIGGER IF NOT EXISTS recipe_search_recipe_insert AFTER INSERT ON recipes BEGIN
+    INSERT INTO recipe_search (rowid, name, instructions, notes, ingredients)
+    VALUES (new.id, new.name, COALESCE(new.instructions, ''), '', '');
+END;
+
+CREATE TRIGGER IF NOT EXISTS recipe_search_recipe_update AFTER UPDATE OF name, instructions ON recipes BEGIN
+    UPDATE recipe_search
+    SET name = new.name, instructions = COALESCE(new.instructions, '')
+    WHERE rowid = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS recipe_search_recipe_delete AFTER DELETE ON recipes BEGIN
+    DELETE FROM recipe_search WHERE rowid = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS recipe_search_ingredient_insert AFTER INSERT ON recipe_ingredients BEGIN
+    UPDATE recipe_search
+    SET
+        notes = COALESCE((SELECT group_concat(ri.notes, ' ') FROM recipe_ingredients ri WHERE ri.recipe_id = new.recipe_id), ''),
+        ingredients = COALESCE((SELECT group_concat(i.name, ' ') FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id WHERE ri.recipe_id = new.recipe_id), '')
+    WHERE rowid = new.recipe_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS recipe_search_ingredient_update AFTER UPDATE ON recipe_ingredients BEGIN
+    UPDATE recipe_search
+    SET
+        notes = COALESCE((SELECT group_concat(ri.notes, ' ') FROM recipe_ingredients ri WHERE ri.recipe_id = new.recipe_id), ''),
+        ingredients = COALESCE((SELECT group_concat(i.name, ' ') FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id WHERE ri.recipe_id = new.recipe_id), '')
+    WHERE rowid = new.recipe_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS recipe_search_ingredient_delete AFTER DELETE ON recipe_ingredients BEGIN
+    UPDATE recipe_search
+    SET
+        notes = COALESCE((SELECT group_concat(ri.notes, ' ') FROM recipe_ingredients ri WHERE ri.recipe_id = old.recipe_id), ''),
+        ingredients = COALESCE((SELECT group_concat(i.name, ' ') FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id WHERE ri.recipe_id = old.recipe_id), '')
+    WHERE rowid = old.recipe_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS recipe_search_ingredient_rename AFTER UPDATE OF name ON ingredients BEGIN
+    UPDATE recipe_search
+    SET ingredients = COALESCE((SELECT group_concat(i.name, ' ') FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id WHERE ri.recipe_id = recipe_search.rowid), '')
+    WHERE rowid IN (SELECT recipe_id FROM recipe_ingredients WHERE ingredient_id = new.id);
+END;
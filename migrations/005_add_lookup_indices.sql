@@ -0,0 +1,8 @@
+-- Case-insensitive ingredient lookups ("Flour" vs "flour")
+CREATE INDEX IF NOT EXISTS idx_ingredients_name_nocase ON ingredients(name COLLATE NOCASE);
+
+-- The recipe_ingredients lookups used by get_recipe and generate_shopping_list
+-- were indexed in 003; repeated here so databases created before those
+-- indices existed pick them up too
+CREATE INDEX IF NOT EXISTS idx_recipe_ingredients_recipe_id ON recipe_ingredients(recipe_id);
+CREATE INDEX IF NOT EXISTS idx_recipe_ingredients_ingredient_id ON recipe_ingredients(ingredient_id);
@@ -0,0 +1,5 @@
+-- Structured form of quantity_unit ("1 1/2 cups" -> 1.5, "cups").
+-- NULL when the text couldn't be parsed; existing rows are backfilled by
+-- db::backfill_structured_quantities after migrations run.
+ALTER TABLE recipe_ingredients ADD COLUMN amount REAL;
+ALTER TABLE recipe_ingredients ADD COLUMN unit TEXT;
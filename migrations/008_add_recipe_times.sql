@@ -0,0 +1,3 @@
+-- Preparation and cooking time in minutes (NULL when unknown)
+ALTER TABLE recipes ADD COLUMN prep_minutes INTEGER;
+ALTER TABLE recipes ADD COLUMN cook_minutes INTEGER;
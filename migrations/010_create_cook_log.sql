@@ -0,0 +1,11 @@
+-- One row each time a recipe is actually cooked
+CREATE TABLE IF NOT EXISTS cook_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    recipe_id INTEGER NOT NULL,
+    cooked_on TEXT NOT NULL,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (recipe_id) REFERENCES recipes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_cook_log_recipe_id ON cook_log(recipe_id, cooked_on);
@@ -0,0 +1,22 @@
+-- A saved plan covering consecutive days starting at start_date
+CREATE TABLE IF NOT EXISTS meal_plans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT,
+    start_date TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A recipe scheduled for one meal; servings scales the recipe's quantities
+CREATE TABLE IF NOT EXISTS meal_plan_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    meal_plan_id INTEGER NOT NULL,
+    planned_on TEXT NOT NULL,
+    meal TEXT NOT NULL DEFAULT 'dinner',
+    recipe_id INTEGER NOT NULL,
+    servings REAL NOT NULL DEFAULT 1,
+    note TEXT,
+    FOREIGN KEY (meal_plan_id) REFERENCES meal_plans(id) ON DELETE CASCADE,
+    FOREIGN KEY (recipe_id) REFERENCES recipes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_meal_plan_entries_plan_id ON meal_plan_entries(meal_plan_id, planned_on);
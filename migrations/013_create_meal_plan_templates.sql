@@ -0,0 +1,21 @@
+-- A named, date-free copy of a plan for recurring rotations
+CREATE TABLE IF NOT EXISTS meal_plan_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- day_offset counts days from the start of the plan the template is applied to
+CREATE TABLE IF NOT EXISTS meal_plan_template_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    template_id INTEGER NOT NULL,
+    day_offset INTEGER NOT NULL,
+    meal TEXT NOT NULL DEFAULT 'dinner',
+    recipe_id INTEGER NOT NULL,
+    servings REAL NOT NULL DEFAULT 1,
+    note TEXT,
+    FOREIGN KEY (template_id) REFERENCES meal_plan_templates(id) ON DELETE CASCADE,
+    FOREIGN KEY (recipe_id) REFERENCES recipes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_meal_plan_template_entries_template_id ON meal_plan_template_entries(template_id);
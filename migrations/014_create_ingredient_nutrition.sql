@@ -0,0 +1,12 @@
+-- Nutrition facts per 100 g of an ingredient
+-- grams_per_unit converts bare counts ("2 eggs") to weight
+CREATE TABLE IF NOT EXISTS ingredient_nutrition (
+    ingredient_id INTEGER PRIMARY KEY,
+    calories REAL NOT NULL,
+    protein_g REAL NOT NULL DEFAULT 0,
+    carbohydrates_g REAL NOT NULL DEFAULT 0,
+    fat_g REAL NOT NULL DEFAULT 0,
+    grams_per_unit REAL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
@@ -0,0 +1,20 @@
+-- A saved shopping list, optionally generated from a meal plan
+CREATE TABLE IF NOT EXISTS shopping_lists (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT,
+    meal_plan_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (meal_plan_id) REFERENCES meal_plans(id) ON DELETE SET NULL
+);
+
+-- already_have marks items the household doesn't need to buy
+CREATE TABLE IF NOT EXISTS shopping_list_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    shopping_list_id INTEGER NOT NULL,
+    ingredient_id INTEGER NOT NULL,
+    quantity TEXT NOT NULL,
+    already_have BOOLEAN NOT NULL DEFAULT 0,
+    FOREIGN KEY (shopping_list_id) REFERENCES shopping_lists(id) ON DELETE CASCADE,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id),
+    UNIQUE (shopping_list_id, ingredient_id)
+);
@@ -0,0 +1,15 @@
+-- Stock on hand; an ingredient may have several rows (e.g. two cartons
+-- with different expiration dates)
+CREATE TABLE IF NOT EXISTS pantry_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ingredient_id INTEGER NOT NULL,
+    amount REAL NOT NULL,
+    unit TEXT,
+    expires_on TEXT,
+    is_staple BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_pantry_items_ingredient_id ON pantry_items(ingredient_id);
+CREATE INDEX IF NOT EXISTS idx_pantry_items_expires_on ON pantry_items(expires_on);
@@ -0,0 +1,4 @@
+-- purchased_on is set once the shopping trip is done; stocked records that
+-- the list's items have been added to the pantry so they aren't added twice
+ALTER TABLE shopping_lists ADD COLUMN purchased_on TEXT;
+ALTER TABLE shopping_lists ADD COLUMN stocked BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- USDA FoodData Central searches already made for an ingredient, so they
+-- aren't repeated. fdc_id is NULL when the search found nothing usable
+CREATE TABLE IF NOT EXISTS fdc_lookups (
+    ingredient_id INTEGER PRIMARY KEY,
+    fdc_id INTEGER,
+    description TEXT,
+    looked_up_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Number of servings a recipe makes; NULL when unknown
+ALTER TABLE recipes ADD COLUMN servings INTEGER;
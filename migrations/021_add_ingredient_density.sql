@@ -0,0 +1,3 @@
+-- Grams per millilitre, letting volumes and weights of the same ingredient
+-- be summed (cups of flour + grams of flour). NULL when unknown.
+ALTER TABLE ingredients ADD COLUMN grams_per_ml REAL;
@@ -0,0 +1,15 @@
+-- User-defined units such as a "stick" (of butter) = 113 g. Units with an
+-- ingredient_id only apply to that ingredient ("can" of tomatoes = 400 g);
+-- those without apply everywhere, with ingredient-scoped ones taking precedence.
+CREATE TABLE IF NOT EXISTS custom_units (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    ingredient_id INTEGER,
+    amount REAL NOT NULL,
+    unit TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_custom_units_name_ingredient
+    ON custom_units(name, IFNULL(ingredient_id, 0));
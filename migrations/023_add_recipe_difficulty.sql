@@ -0,0 +1,2 @@
+-- How demanding a recipe is; NULL when unknown
+ALTER TABLE recipes ADD COLUMN difficulty TEXT CHECK (difficulty IN ('easy', 'medium', 'hard'));
@@ -0,0 +1,20 @@
+-- Ways to stand in for an ingredient, e.g. buttermilk -> milk + lemon juice.
+-- Each substitution uses one or more other ingredients; the note records the
+-- ratio ("1 tbsp lemon juice per cup of milk, rest 5 minutes").
+CREATE TABLE IF NOT EXISTS substitutions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ingredient_id INTEGER NOT NULL,
+    note TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS substitution_ingredients (
+    substitution_id INTEGER NOT NULL,
+    ingredient_id INTEGER NOT NULL,
+    PRIMARY KEY (substitution_id, ingredient_id),
+    FOREIGN KEY (substitution_id) REFERENCES substitutions(id) ON DELETE CASCADE,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_substitutions_ingredient ON substitutions(ingredient_id);
@@ -0,0 +1,17 @@
+-- What an ingredient cost when bought: `price` paid for `amount` `unit`
+-- (e.g. 3.49 for 1 kg of flour). unit is NULL for counts. Old rows are kept
+-- so prices can be compared over time; estimates use the newest one.
+CREATE TABLE IF NOT EXISTS ingredient_prices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ingredient_id INTEGER NOT NULL,
+    price REAL NOT NULL CHECK (price >= 0),
+    amount REAL NOT NULL DEFAULT 1 CHECK (amount > 0),
+    unit TEXT,
+    store TEXT,
+    observed_on TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ingredient_id) REFERENCES ingredients(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_ingredient_prices_ingredient_observed
+    ON ingredient_prices(ingredient_id, observed_on);
@@ -0,0 +1,3 @@
+-- Lowercased store section an ingredient is found in ("produce", "dairy"),
+-- used to order shopping lists by a store's layout. NULL when unassigned.
+ALTER TABLE ingredients ADD COLUMN aisle TEXT;
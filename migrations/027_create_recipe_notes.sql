@@ -0,0 +1,14 @@
+-- Journal entries written after cooking ("use less salt next time"), each
+-- attached to the cook log entry it's about
+CREATE TABLE IF NOT EXISTS recipe_notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cook_log_id INTEGER NOT NULL,
+    noted_on TEXT NOT NULL,
+    text TEXT NOT NULL,
+    rating_delta INTEGER CHECK (rating_delta BETWEEN -4 AND 4),
+    modifications TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (cook_log_id) REFERENCES cook_log(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipe_notes_cook_log_id ON recipe_notes(cook_log_id);
@@ -0,0 +1,3 @@
+-- Emoji (or other short symbol) shown next to an ingredient in lists, e.g.
+-- "🥛" for milk. NULL when unset.
+ALTER TABLE ingredients ADD COLUMN icon TEXT;
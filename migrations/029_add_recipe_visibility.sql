@@ -0,0 +1,4 @@
+-- Who a recipe is shown to: only its author ('private'), everyone in the
+-- household ('household', the default) or anyone it's shared with ('public')
+ALTER TABLE recipes ADD COLUMN visibility TEXT NOT NULL DEFAULT 'household'
+    CHECK (visibility IN ('private', 'household', 'public'));
@@ -0,0 +1,5 @@
+-- Which meal a recipe is for; NULL when it hasn't been sorted
+ALTER TABLE recipes ADD COLUMN category TEXT
+    CHECK (category IN ('breakfast', 'lunch', 'dinner', 'dessert', 'snack'));
+
+CREATE INDEX IF NOT EXISTS idx_recipes_category ON recipes(category);
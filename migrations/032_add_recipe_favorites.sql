@@ -0,0 +1,2 @@
+-- Recipes starred as household favorites
+ALTER TABLE recipes ADD COLUMN is_favorite BOOLEAN NOT NULL DEFAULT 0;
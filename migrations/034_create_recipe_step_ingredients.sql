@@ -0,0 +1,10 @@
+-- Which recipe_ingredients each instruction step uses, so cooking mode can
+-- show exactly what goes in at each step. Steps are numbered from 0 over the
+-- non-blank lines of the instructions; links go when the ingredient row is
+-- replaced
+CREATE TABLE IF NOT EXISTS recipe_step_ingredients (
+    recipe_ingredient_id INTEGER NOT NULL,
+    step INTEGER NOT NULL CHECK (step >= 0),
+    PRIMARY KEY (recipe_ingredient_id, step),
+    FOREIGN KEY (recipe_ingredient_id) REFERENCES recipe_ingredients(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Items checked off one by one (from a phone) while shopping; each is added
+-- to the pantry when it is checked off
+ALTER TABLE shopping_list_items ADD COLUMN purchased BOOLEAN NOT NULL DEFAULT 0;
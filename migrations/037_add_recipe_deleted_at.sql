@@ -0,0 +1,5 @@
+-- Deleted recipes stay in the table until purged so they can be restored;
+-- every query over recipes skips rows with deleted_at set
+ALTER TABLE recipes ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_recipes_deleted_at ON recipes(deleted_at);
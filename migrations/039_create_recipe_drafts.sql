@@ -0,0 +1,7 @@
+-- Recipes still being entered, autosaved as JSON so an interrupted entry
+-- can be resumed
+CREATE TABLE IF NOT EXISTS recipe_drafts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    data TEXT NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,6 @@
+-- One-off data fixes run from code after the migrations (e.g. parsing old
+-- quantities into amount/unit); a row here means that fix has been done
+CREATE TABLE IF NOT EXISTS data_backfills (
+    name TEXT PRIMARY KEY,
+    completed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Create ingredients table
+CREATE TABLE IF NOT EXISTS ingredients (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+-- Create index on name for faster lookups
+CREATE INDEX IF NOT EXISTS idx_ingredients_name ON ingredients(name);
@@ -0,0 +1,7 @@
+-- Create recipes table
+CREATE TABLE IF NOT EXISTS recipes (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    instructions TEXT,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
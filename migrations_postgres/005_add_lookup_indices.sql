@@ -0,0 +1,2 @@
+-- Case-insensitive ingredient lookups ("Flour" vs "flour")
+CREATE INDEX IF NOT EXISTS idx_ingredients_name_lower ON ingredients(lower(name));
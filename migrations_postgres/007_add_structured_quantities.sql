@@ -0,0 +1,3 @@
+-- Structured form of quantity_unit ("1 1/2 cups" -> 1.5, "cups")
+ALTER TABLE recipe_ingredients ADD COLUMN IF NOT EXISTS amount DOUBLE PRECISION;
+ALTER TABLE recipe_ingredients ADD COLUMN IF NOT EXISTS unit TEXT;
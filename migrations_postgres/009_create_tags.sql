@@ -0,0 +1,17 @@
+-- Free-form labels ("vegetarian", "weeknight") attached to recipes
+CREATE TABLE IF NOT EXISTS tags (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+-- Tag names are unique ignoring case, like SQLite's COLLATE NOCASE
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tags_name_lower ON tags(lower(name));
+
+CREATE TABLE IF NOT EXISTS recipe_tags (
+    recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+    tag_id BIGINT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (recipe_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipe_tags_tag_id ON recipe_tags(tag_id);
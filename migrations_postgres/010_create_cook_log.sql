@@ -0,0 +1,10 @@
+-- One row each time a recipe is actually cooked
+CREATE TABLE IF NOT EXISTS cook_log (
+    id BIGSERIAL PRIMARY KEY,
+    recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+    cooked_on DATE NOT NULL,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE INDEX IF NOT EXISTS idx_cook_log_recipe_id ON cook_log(recipe_id, cooked_on);
@@ -0,0 +1,20 @@
+-- A saved plan covering consecutive days starting at start_date
+CREATE TABLE IF NOT EXISTS meal_plans (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT,
+    start_date DATE NOT NULL,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+-- A recipe scheduled for one meal; servings scales the recipe's quantities
+CREATE TABLE IF NOT EXISTS meal_plan_entries (
+    id BIGSERIAL PRIMARY KEY,
+    meal_plan_id BIGINT NOT NULL REFERENCES meal_plans(id) ON DELETE CASCADE,
+    planned_on DATE NOT NULL,
+    meal TEXT NOT NULL DEFAULT 'dinner',
+    recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+    servings DOUBLE PRECISION NOT NULL DEFAULT 1,
+    note TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_meal_plan_entries_plan_id ON meal_plan_entries(meal_plan_id, planned_on);
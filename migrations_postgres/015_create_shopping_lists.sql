@@ -0,0 +1,17 @@
+-- A saved shopping list, optionally generated from a meal plan
+CREATE TABLE IF NOT EXISTS shopping_lists (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT,
+    meal_plan_id BIGINT REFERENCES meal_plans(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+-- already_have marks items the household doesn't need to buy
+CREATE TABLE IF NOT EXISTS shopping_list_items (
+    id BIGSERIAL PRIMARY KEY,
+    shopping_list_id BIGINT NOT NULL REFERENCES shopping_lists(id) ON DELETE CASCADE,
+    ingredient_id BIGINT NOT NULL REFERENCES ingredients(id),
+    quantity TEXT NOT NULL,
+    already_have BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE (shopping_list_id, ingredient_id)
+);
@@ -0,0 +1,14 @@
+-- Stock on hand; an ingredient may have several rows (e.g. two cartons
+-- with different expiration dates)
+CREATE TABLE IF NOT EXISTS pantry_items (
+    id BIGSERIAL PRIMARY KEY,
+    ingredient_id BIGINT NOT NULL REFERENCES ingredients(id) ON DELETE CASCADE,
+    amount DOUBLE PRECISION NOT NULL,
+    unit TEXT,
+    expires_on DATE,
+    is_staple BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE INDEX IF NOT EXISTS idx_pantry_items_ingredient_id ON pantry_items(ingredient_id);
+CREATE INDEX IF NOT EXISTS idx_pantry_items_expires_on ON pantry_items(expires_on);
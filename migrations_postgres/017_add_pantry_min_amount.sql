@@ -0,0 +1,2 @@
+-- Restock threshold, in the item's own unit. NULL means no threshold
+ALTER TABLE pantry_items ADD COLUMN IF NOT EXISTS min_amount DOUBLE PRECISION;
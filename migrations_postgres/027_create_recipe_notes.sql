@@ -0,0 +1,13 @@
+-- Journal entries written after cooking ("use less salt next time"), each
+-- attached to the cook log entry it's about
+CREATE TABLE IF NOT EXISTS recipe_notes (
+    id BIGSERIAL PRIMARY KEY,
+    cook_log_id BIGINT NOT NULL REFERENCES cook_log(id) ON DELETE CASCADE,
+    noted_on DATE NOT NULL,
+    text TEXT NOT NULL,
+    rating_delta BIGINT CHECK (rating_delta BETWEEN -4 AND 4),
+    modifications TEXT,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipe_notes_cook_log_id ON recipe_notes(cook_log_id);
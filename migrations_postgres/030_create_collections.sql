@@ -0,0 +1,19 @@
+-- Named groups of recipes ("Thanksgiving 2025", "Camping") shared by the
+-- household, e.g. to shop for all of them at once
+CREATE TABLE IF NOT EXISTS collections (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS')
+);
+
+-- Collection names are unique ignoring case, like SQLite's COLLATE NOCASE
+CREATE UNIQUE INDEX IF NOT EXISTS idx_collections_name_lower ON collections(lower(name));
+
+CREATE TABLE IF NOT EXISTS collection_recipes (
+    collection_id BIGINT NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+    added_at TEXT NOT NULL DEFAULT to_char(now(), 'YYYY-MM-DD HH24:MI:SS'),
+    PRIMARY KEY (collection_id, recipe_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_recipes_recipe_id ON collection_recipes(recipe_id);
@@ -0,0 +1,3 @@
+-- Where a recipe came from: the page it was imported from and who wrote it
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS source_url TEXT;
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS source_name TEXT;
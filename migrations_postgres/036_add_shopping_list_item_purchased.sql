@@ -0,0 +1,2 @@
+-- Items checked off one by one (from a phone) while shopping
+ALTER TABLE shopping_list_items ADD COLUMN IF NOT EXISTS purchased BOOLEAN NOT NULL DEFAULT FALSE;
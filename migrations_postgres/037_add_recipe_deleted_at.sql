@@ -0,0 +1,5 @@
+-- Deleted recipes stay in the table so they can be restored; every query
+-- over recipes skips rows with deleted_at set
+ALTER TABLE recipes ADD COLUMN IF NOT EXISTS deleted_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_recipes_deleted_at ON recipes(deleted_at);
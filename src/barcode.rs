@@ -0,0 +1,129 @@
+//! Barcode lookup against Open Food Facts
+//!
+//! Lets the pantry be stocked by scanning (or typing) a product's barcode:
+//! the product name is fetched from the public Open Food Facts database and
+//! resolved to an ingredient, creating it if it's new.
+
+use serde::Deserialize;
+
+use crate::cache::IngredientCache;
+use crate::error::{FeedMeError, Result};
+
+/// Product endpoint; the barcode and `.json` are appended
+const PRODUCT_URL: &str = "https://world.openfoodfacts.org/api/v2/product/";
+
+#[derive(Debug, Deserialize)]
+struct ProductResponse {
+    status: i64,
+    product: Option<Product>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Product {
+    product_name: Option<String>,
+    generic_name: Option<String>,
+}
+
+/// Whether `code` looks like an EAN/UPC barcode (8 to 14 digits)
+pub fn is_barcode(code: &str) -> bool {
+    (8..=14).contains(&code.len()) && code.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Look up a barcode's product name, lowercased for use as an ingredient
+/// Returns None when Open Food Facts doesn't know the product. Blocks on the
+/// network request
+pub fn lookup_barcode(code: &str) -> Result<Option<String>> {
+    if !is_barcode(code) {
+        return Err(FeedMeError::BarcodeLookup(format!(
+            "'{}' is not a barcode",
+            code
+        )));
+    }
+
+    let url = format!(
+        "{}{}.json?fields=product_name,generic_name",
+        PRODUCT_URL, code
+    );
+    let response: ProductResponse = match ureq::get(&url).call() {
+        Ok(mut response) => response
+            .body_mut()
+            .read_json()
+            .map_err(|e| FeedMeError::BarcodeLookup(format!("{}: {}", code, e)))?,
+        // Unknown products come back as 404
+        Err(ureq::Error::StatusCode(404)) => return Ok(None),
+        Err(e) => return Err(FeedMeError::BarcodeLookup(format!("{}: {}", code, e))),
+    };
+
+    Ok(product_name(response))
+}
+
+/// Resolve a barcode to an ingredient, creating the ingredient if needed
+/// Returns the ingredient ID and name, or None for unknown products
+pub async fn ingredient_for_barcode(
+    ingredients: &IngredientCache,
+    code: &str,
+) -> Result<Option<(i64, String)>> {
+    let code = code.to_string();
+    let name = tokio::task::spawn_blocking(move || lookup_barcode(&code))
+        .await
+        .map_err(|e| FeedMeError::BarcodeLookup(e.to_string()))??;
+
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    let id = ingredients.get_or_create(&name).await?;
+
+    Ok(Some((id, name)))
+}
+
+/// Prefer the generic name ("milk") over the branded one ("Acme Whole Milk")
+fn product_name(response: ProductResponse) -> Option<String> {
+    if response.status != 1 {
+        return None;
+    }
+
+    let product = response.product?;
+    [product.generic_name, product.product_name]
+        .into_iter()
+        .flatten()
+        .map(|name| name.trim().to_lowercase())
+        .find(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("3017620422003", true)]
+    #[case("12345678", true)]
+    #[case("1234567", false)]
+    #[case("301762042200a", false)]
+    #[case("milk", false)]
+    fn test_is_barcode(#[case] code: &str, #[case] expected: bool) {
+        assert_eq!(is_barcode(code), expected);
+    }
+
+    #[rstest]
+    #[case(r#"{"status": 1, "product": {"product_name": "Acme Oat Milk", "generic_name": " Oat milk "}}"#, Some("oat milk"))]
+    #[case(
+        r#"{"status": 1, "product": {"product_name": "Nutella", "generic_name": ""}}"#,
+        Some("nutella")
+    )]
+    #[case(r#"{"status": 1, "product": {}}"#, None)]
+    #[case(r#"{"status": 0, "status_verbose": "product not found"}"#, None)]
+    fn test_product_name(#[case] json: &str, #[case] expected: Option<&str>) {
+        let response: ProductResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(product_name(response).as_deref(), expected);
+    }
+
+    #[test]
+    fn test_lookup_rejects_non_barcodes() {
+        assert!(matches!(
+            lookup_barcode("not a code"),
+            Err(FeedMeError::BarcodeLookup(_))
+        ));
+    }
+}
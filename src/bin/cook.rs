@@ -0,0 +1,83 @@
+use clap::Parser;
+use crossterm::{
+    event::{self, Event},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use feedme::{
+    cli::{self, Context, DatabaseArgs, Report},
+    config::Config,
+    controllers::{get_recipe_with, ingredient_icons},
+    i18n,
+    logging::{Verbosity, init_tracing, redirect_to_file, tui_log_path},
+    models::api::RecipeOptions,
+    tui::cooking::{CookingAction, CookingApp},
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// How often the screen is redrawn so timers count down
+const TICK: Duration = Duration::from_millis(250);
+
+/// Cook a recipe one step at a time, with timers
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    recipe_id: i64,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+
+    #[command(flatten)]
+    database: DatabaseArgs,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing(cli.verbosity);
+
+    cli::exit_status(run(cli).await)
+}
+
+async fn run(cli: Cli) -> Result<(), Report> {
+    let config = Config::load().context("loading the configuration")?;
+    i18n::set_locale(config.display.language);
+    let (pool, _) = cli.database.connect(&config.database).await?;
+
+    let options = RecipeOptions {
+        quantities: config.display.quantity_format(),
+        ..Default::default()
+    };
+    let recipe = get_recipe_with(&pool, cli.recipe_id, options).await?;
+    let mut app = CookingApp::new(recipe).with_icons(ingredient_icons(&pool).await?);
+
+    // Setup terminal, logging to a file while it's taken over
+    let log_path = tui_log_path("cook");
+    let _redirect =
+        redirect_to_file(&log_path).context(format!("opening {}", log_path.display()))?;
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Main loop, waking on every tick to update the countdowns
+    loop {
+        terminal.draw(|f| app.render(f, Instant::now()))?;
+
+        if event::poll(TICK)?
+            && let Event::Key(key) = event::read()?
+            && app.handle_key(key.code, Instant::now()) == CookingAction::Exit
+        {
+            break;
+        }
+    }
+
+    // Cleanup terminal
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use clap::Parser;
+use crossterm::{
+    event::{self, Event},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use feedme::{
+    cache::IngredientCache,
+    cli::{self, Context, DatabaseArgs, Report},
+    config::Config,
+    controllers::{
+        add_to_pantry, list_pantry, remove_from_pantry, set_pantry_staple, update_pantry_amount,
+    },
+    error::Result,
+    i18n,
+    logging::{Verbosity, init_tracing, redirect_to_file, tui_log_path},
+    models::api::PantryItem,
+    tui::pantry::{PantryAction, PantryApp},
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+use sqlx::SqlitePool;
+use std::process::ExitCode;
+
+/// Browse and update pantry stock
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(flatten)]
+    verbosity: Verbosity,
+
+    #[command(flatten)]
+    database: DatabaseArgs,
+}
+
+/// Apply one action from the app to the database
+async fn apply(
+    pool: &SqlitePool,
+    ingredients: &IngredientCache,
+    app: &mut PantryApp,
+    action: PantryAction,
+) -> Result<()> {
+    match action {
+        PantryAction::Continue | PantryAction::Exit => {}
+        PantryAction::SetAmount { id, amount } => update_pantry_amount(pool, id, amount).await?,
+        PantryAction::SetStaple { id, is_staple } => set_pantry_staple(pool, id, is_staple).await?,
+        PantryAction::Remove { id } => {
+            remove_from_pantry(pool, id).await?;
+        }
+        PantryAction::Add {
+            ingredient_name,
+            ingredient_id,
+            amount,
+            unit,
+        } => {
+            let ingredient_id = match ingredient_id {
+                Some(id) => id,
+                #[cfg(feature = "barcode")]
+                None if feedme::barcode::is_barcode(&ingredient_name) => {
+                    match feedme::barcode::ingredient_for_barcode(ingredients, &ingredient_name)
+                        .await?
+                    {
+                        Some((id, name)) => {
+                            tracing::info!(barcode = %ingredient_name, ingredient = %name, id, "resolved barcode");
+                            app.add_possible_ingredient(name, id);
+                            id
+                        }
+                        None => {
+                            app.set_message(feedme::t!(
+                                "pantry-unknown-barcode",
+                                barcode = ingredient_name
+                            ));
+                            return Ok(());
+                        }
+                    }
+                }
+                None => {
+                    let id = ingredients.get_or_create(&ingredient_name).await?;
+                    tracing::info!(ingredient = %ingredient_name, id, "created ingredient");
+                    app.add_possible_ingredient(ingredient_name, id);
+                    id
+                }
+            };
+            let item = PantryItem {
+                id: 0,
+                ingredient_id,
+                ingredient_name: String::new(),
+                amount,
+                unit,
+                expires_on: None,
+                is_staple: false,
+                icon: None,
+                min_amount: None,
+            };
+            add_to_pantry(pool, &item).await?;
+        }
+    }
+
+    app.set_items(list_pantry(pool).await?);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing(cli.verbosity);
+
+    cli::exit_status(run(cli).await)
+}
+
+async fn run(cli: Cli) -> std::result::Result<(), Report> {
+    let config = Config::load().context("loading the configuration")?;
+    i18n::set_locale(config.display.language);
+    let (pool, _) = cli.database.connect(&config.database).await?;
+    let ingredients = IngredientCache::new(pool.clone());
+
+    let mut app = PantryApp::new(list_pantry(&pool).await?, ingredients.snapshot().await?);
+
+    // Setup terminal, logging to a file while it's taken over
+    let log_path = tui_log_path("pantry");
+    let _redirect =
+        redirect_to_file(&log_path).context(format!("opening {}", log_path.display()))?;
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Main loop
+    loop {
+        terminal.draw(|f| app.render(f))?;
+
+        if let Event::Key(key) = event::read()? {
+            let action = app.handle_key(key.code);
+            if action == PantryAction::Exit {
+                break;
+            }
+            // Keep the screen up on failure so the change can be retried
+            if let Err(e) = apply(&pool, &ingredients, &mut app, action).await {
+                app.set_message(e.user_message());
+            }
+        }
+    }
+
+    // Cleanup terminal
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+
+    Ok(())
+}
@@ -1,53 +1,78 @@
+use clap::Parser;
 use crossterm::{
     event::{self, Event},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use feedme::{
-    controllers::{create_ingredient, create_recipe, get_all_ingredients},
-    models::api::{Recipe, RecipeIngredient},
-    tui::app::{AppAction, IngredientStatus, RecipeApp},
+    cache::IngredientCache,
+    cli::{self, Context, DatabaseArgs, Report},
+    config::Config,
+    controllers::{discard_draft, latest_draft, save_draft},
+    i18n,
+    logging::{Verbosity, init_tracing, redirect_to_file, tui_log_path},
+    tui::app::{AppAction, RecipeApp, save_recipe_context},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
-use sqlx::migrate::MigrateDatabase;
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+use std::process::ExitCode;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Database setup
-    let database_url = "sqlite://feedme.db";
+/// Keystrokes between autosaves of the recipe being entered
+const AUTOSAVE_EVERY: usize = 5;
 
-    // Create database if it doesn't exist
-    if !sqlx::Sqlite::database_exists(database_url).await? {
-        sqlx::Sqlite::create_database(database_url).await?;
-    }
+/// Interactively enter a recipe into the FeedMe database
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Page the recipe comes from
+    #[arg(long)]
+    source_url: Option<String>,
 
-    // Create connection pool
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(database_url)
-        .await?;
+    /// Who wrote the recipe (a blog, book or person)
+    #[arg(long)]
+    source_name: Option<String>,
 
-    // Run migrations
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    #[command(flatten)]
+    verbosity: Verbosity,
+
+    #[command(flatten)]
+    database: DatabaseArgs,
+}
 
-    // Setup terminal
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing(cli.verbosity);
+
+    cli::exit_status(run(cli).await)
+}
+
+async fn run(cli: Cli) -> Result<(), Report> {
+    let config = Config::load().context("loading the configuration")?;
+    i18n::set_locale(config.display.language);
+    let (pool, _) = cli.database.connect(&config.database).await?;
+    let ingredients = IngredientCache::new(pool.clone());
+
+    // Load ingredients as name -> id mapping, offering to resume an entry
+    // left unfinished last time
+    let possible_ingredients = ingredients.snapshot().await?;
+    let (mut draft_id, mut app) = match latest_draft(&pool).await? {
+        Some((id, draft)) => (Some(id), RecipeApp::resume(possible_ingredients, draft)),
+        None => (None, RecipeApp::new(possible_ingredients)),
+    };
+
+    // Setup terminal, logging to a file while it's taken over
+    let log_path = tui_log_path("recipe_importer");
+    let redirect =
+        redirect_to_file(&log_path).context(format!("opening {}", log_path.display()))?;
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Load ingredients as name -> id mapping
-    let mut app = RecipeApp::new(
-        get_all_ingredients(&pool)
-            .await?
-            .into_iter()
-            .map(|i| (i.name, i.id))
-            .collect(),
-    );
-
     // Main loop
+    let mut keystrokes = 0;
     let action = loop {
         // Draw UI
         terminal.draw(|f| app.render(f))?;
@@ -60,64 +85,72 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     break action;
                 }
             }
+
+            keystrokes += 1;
+            // The terminal is still taken over, so a failed autosave is
+            // logged rather than ending the entry
+            if keystrokes % AUTOSAVE_EVERY == 0
+                && let Err(err) = autosave(&pool, &mut draft_id, &app).await
+            {
+                tracing::warn!(error = %err, "couldn't autosave the draft");
+            }
         }
     };
 
     // Cleanup terminal
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    drop(redirect);
 
     // Save recipe if user finished (not cancelled)
     if matches!(action, AppAction::SaveAndExit) {
-        let context = app.into_context();
+        let mut context = app.into_context();
+        context.source_url = cli.source_url;
+        context.source_name = cli.source_name;
 
         if !context.name.is_empty() {
-            println!("Saving recipe: {}", context.name);
-
-            // Create new ingredients and collect all IDs
-            let mut recipe_ingredients = Vec::new();
-
-            for (name, info) in context.ingredients {
-                let ingredient_id = match info.status {
-                    IngredientStatus::New => {
-                        // Create new ingredient
-                        create_ingredient(&pool, &name).await?
-                    }
-                    IngredientStatus::Existing(id) => id,
-                };
-
-                recipe_ingredients.push(RecipeIngredient {
-                    ingredient_id,
-                    ingredient_name: name,
-                    quantity_unit: info.quantity_unit,
-                    notes: if info.notes.is_empty() {
-                        None
-                    } else {
-                        Some(info.notes)
-                    },
-                });
-            }
+            tracing::info!(recipe = %context.name, "saving recipe");
 
-            // Create recipe
-            let recipe = Recipe {
-                id: 0, // Ignored
-                name: context.name,
-                instructions: if context.instructions.is_empty() {
-                    None
-                } else {
-                    Some(context.instructions.join("\n"))
-                },
-                ingredients: recipe_ingredients,
-                created_at: String::new(), // Ignored
-            };
-
-            let recipe_id = create_recipe(&pool, &recipe).await?;
-            println!("Recipe saved with ID: {}", recipe_id);
+            // Warnings are logged while saving
+            let recipe_id = save_recipe_context(&pool, &context).await?;
+            ingredients.invalidate();
+            tracing::info!(recipe_id, "recipe saved");
         } else {
-            println!("No recipe name provided, not saving.");
+            tracing::warn!("no recipe name provided, not saving");
+        }
+
+        if let Some(id) = draft_id {
+            discard_draft(&pool, id).await?;
+        }
+    } else {
+        // Kept so an accidental Esc can be picked back up next time
+        autosave(&pool, &mut draft_id, &app).await?;
+        tracing::info!("recipe entry cancelled");
+    }
+
+    Ok(())
+}
+
+/// Save what's been entered as draft `draft_id`, starting one if needed;
+/// an entry with nothing in it yet drops the draft instead
+async fn autosave(
+    pool: &SqlitePool,
+    draft_id: &mut Option<i64>,
+    app: &RecipeApp,
+) -> Result<(), Report> {
+    // Still asking whether to resume, so the old draft is left alone
+    let Some(draft) = app.draft() else {
+        return Ok(());
+    };
+
+    if draft.is_empty() {
+        if let Some(id) = draft_id.take() {
+            discard_draft(pool, id).await?;
         }
     } else {
-        println!("Recipe entry cancelled.");
+        let id = save_draft(pool, *draft_id, &draft).await?;
+        tracing::debug!(draft_id = id, "draft autosaved");
+        *draft_id = Some(id);
     }
 
     Ok(())
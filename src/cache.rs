@@ -0,0 +1,207 @@
+//! In-memory ingredient name → id lookups
+//!
+//! The importer and autocomplete resolve ingredient names on every keystroke;
+//! `IngredientCache` loads the table once and keeps it in sync with the
+//! ingredients it creates, so those lookups never touch the database.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use sqlx::SqlitePool;
+
+use crate::error::{FeedMeError, Result};
+use crate::store::IngredientStore;
+
+/// Cached ingredient name → id map backed by an `IngredientStore`
+pub struct IngredientCache<S: IngredientStore = SqlitePool> {
+    store: S,
+    /// None until first use or after `invalidate`
+    names: RwLock<Option<HashMap<String, i64>>>,
+}
+
+impl<S: IngredientStore> IngredientCache<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            names: RwLock::new(None),
+        }
+    }
+
+    /// The store writes go through
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Look up an ingredient ID by exact name, loading the cache if needed
+    pub async fn get(&self, name: &str) -> Result<Option<i64>> {
+        self.read(|names| names.get(name).copied()).await
+    }
+
+    /// Look up an ingredient ID, creating the ingredient when it doesn't exist
+    /// If someone else created it since the cache loaded, the cache is
+    /// reloaded and their ingredient returned
+    pub async fn get_or_create(&self, name: &str) -> Result<i64> {
+        if let Some(id) = self.get(name).await? {
+            return Ok(id);
+        }
+
+        let id = match self.store.create_ingredient(name).await {
+            Err(FeedMeError::DuplicateIngredient(existing)) => {
+                self.invalidate();
+                return self
+                    .get(&existing)
+                    .await?
+                    .ok_or(FeedMeError::DuplicateIngredient(existing));
+            }
+            result => result?,
+        };
+        if let Some(names) = self.names.write().unwrap().as_mut() {
+            names.insert(name.to_string(), id);
+        }
+
+        Ok(id)
+    }
+
+    /// Names starting with `prefix` (case-insensitive), sorted, at most `limit`
+    pub async fn complete(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let prefix = prefix.to_lowercase();
+        let mut matches = self
+            .read(|names| {
+                names
+                    .keys()
+                    .filter(|name| name.to_lowercase().starts_with(&prefix))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .await?;
+        matches.sort();
+        matches.truncate(limit);
+
+        Ok(matches)
+    }
+
+    /// A copy of the whole name → id map
+    pub async fn snapshot(&self) -> Result<HashMap<String, i64>> {
+        self.read(HashMap::clone).await
+    }
+
+    /// Drop the cached map so the next lookup reloads it
+    /// Call after ingredients are written without going through this cache,
+    /// e.g. by `import_all`, `import_recipe`, `import_pack` or
+    /// `create_recipe_with` (and so `save_recipe_context`), which create the
+    /// ingredients they need in their own transaction
+    pub fn invalidate(&self) {
+        *self.names.write().unwrap() = None;
+    }
+
+    /// Run `f` on the map, loading it first if needed
+    /// The map is checked and read under one guard, so an `invalidate` from
+    /// another task can't empty it in between
+    async fn read<T>(&self, f: impl FnOnce(&HashMap<String, i64>) -> T) -> Result<T> {
+        {
+            let names = self.names.read().unwrap();
+            if let Some(names) = names.as_ref() {
+                return Ok(f(names));
+            }
+        }
+
+        let loaded: HashMap<String, i64> = self
+            .store
+            .get_all_ingredients()
+            .await?
+            .into_iter()
+            .map(|i| (i.name, i.id))
+            .collect();
+        tracing::debug!(count = loaded.len(), "loaded ingredient cache");
+
+        let mut names = self.names.write().unwrap();
+        Ok(f(names.get_or_insert(loaded)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_loads_existing_ingredients(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let id = crate::controllers::create_ingredient(&pool, "flour")
+            .await
+            .unwrap();
+
+        let cache = IngredientCache::new(pool);
+
+        assert_eq!(cache.get("flour").await.unwrap(), Some(id));
+        assert_eq!(cache.get("sugar").await.unwrap(), None);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_lookups_do_not_see_outside_writes_until_invalidated(
+        #[future] test_db: SqlitePool,
+    ) {
+        let pool = test_db.await;
+        let cache = IngredientCache::new(pool.clone());
+        assert_eq!(cache.get("salt").await.unwrap(), None);
+
+        let id = crate::controllers::create_ingredient(&pool, "salt")
+            .await
+            .unwrap();
+        assert_eq!(cache.get("salt").await.unwrap(), None);
+
+        cache.invalidate();
+        assert_eq!(cache.get("salt").await.unwrap(), Some(id));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_or_create(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let cache = IngredientCache::new(pool);
+
+        let id = cache.get_or_create("butter").await.unwrap();
+        assert_eq!(cache.get_or_create("butter").await.unwrap(), id);
+        assert_eq!(cache.get("butter").await.unwrap(), Some(id));
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ingredients")
+            .fetch_one(cache.store())
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_or_create_after_outside_write(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let cache = IngredientCache::new(pool.clone());
+        assert_eq!(cache.get("salt").await.unwrap(), None);
+
+        let id = crate::controllers::create_ingredient(&pool, "salt")
+            .await
+            .unwrap();
+
+        assert_eq!(cache.get_or_create("salt").await.unwrap(), id);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_complete(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let cache = IngredientCache::new(pool);
+        for name in ["garlic", "Garam masala", "ginger", "green onion"] {
+            cache.get_or_create(name).await.unwrap();
+        }
+
+        assert_eq!(
+            cache.complete("ga", 10).await.unwrap(),
+            vec!["Garam masala", "garlic"]
+        );
+        assert_eq!(cache.complete("g", 2).await.unwrap().len(), 2);
+        assert!(cache.complete("x", 10).await.unwrap().is_empty());
+    }
+}
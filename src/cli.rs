@@ -0,0 +1,233 @@
+use clap::Args;
+use sqlx::SqlitePool;
+use std::fmt;
+use std::io::IsTerminal;
+use std::process::ExitCode;
+
+use crate::config::DatabaseConfig;
+use crate::db;
+use crate::error::{FeedMeError, Result};
+use crate::seed::seed_demo_data;
+
+/// Shared flags choosing which database the binaries work against
+#[derive(Debug, Clone, Copy, Default, Args)]
+pub struct DatabaseArgs {
+    /// Use a throwaway in-memory database instead of the configured one
+    #[arg(long, global = true)]
+    pub in_memory: bool,
+
+    /// Populate a handful of demo recipes and ingredients
+    #[arg(long, global = true)]
+    pub demo: bool,
+}
+
+impl DatabaseArgs {
+    /// Open the database selected by the flags, seeding it if `--demo` was given
+    /// Returns the pool and the IDs of any demo recipes created
+    pub async fn open(&self, config: &DatabaseConfig) -> Result<(SqlitePool, Vec<i64>)> {
+        let url = if self.in_memory {
+            tracing::debug!("using an in-memory database");
+            db::IN_MEMORY_URL
+        } else {
+            &config.url
+        };
+        let pool = db::init(url, config).await?;
+
+        let seeded = if self.demo {
+            seed_demo_data(&pool).await?
+        } else {
+            Vec::new()
+        };
+
+        Ok((pool, seeded))
+    }
+
+    /// Like `open`, reporting failures along with the database in use
+    pub async fn connect(
+        &self,
+        config: &DatabaseConfig,
+    ) -> std::result::Result<(SqlitePool, Vec<i64>), Report> {
+        self.open(config).await.map_err(|error| {
+            Report::new(error)
+                .action("opening the database")
+                .database(self.describe(config))
+        })
+    }
+
+    /// The database the flags select, as shown to users
+    pub fn describe(&self, config: &DatabaseConfig) -> String {
+        if self.in_memory {
+            "in-memory".to_string()
+        } else {
+            config.url.clone()
+        }
+    }
+}
+
+/// A failed command: the error, what was being done, and where
+#[derive(Debug)]
+pub struct Report {
+    pub error: FeedMeError,
+    /// What failed, e.g. "opening the database"
+    pub action: Option<String>,
+    /// Database the command was using
+    pub database: Option<String>,
+}
+
+impl Report {
+    pub fn new(error: FeedMeError) -> Self {
+        Self {
+            error,
+            action: None,
+            database: None,
+        }
+    }
+
+    pub fn action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    pub fn database(mut self, database: impl Into<String>) -> Self {
+        self.database = Some(database.into());
+        self
+    }
+
+    /// The report as printed, with ANSI colors if `color`
+    pub fn render(&self, color: bool) -> String {
+        let paint = |code: &str, text: &str| {
+            if color {
+                format!("\x1b[{}m{}\x1b[0m", code, text)
+            } else {
+                text.to_string()
+            }
+        };
+
+        let mut lines = vec![format!(
+            "{}: {}",
+            paint("1;31", &format!("error[{}]", self.error.code())),
+            self.error.user_message()
+        )];
+        if let Some(action) = &self.action {
+            lines.push(format!("  while {}", action));
+        }
+        if let Some(database) = &self.database {
+            lines.push(format!("  database: {}", database));
+        }
+        let cause = self.error.to_string();
+        if cause != self.error.user_message() {
+            lines.push(format!("  cause: {}", cause));
+        }
+        if let Some(hint) = self.error.hint() {
+            lines.push(format!("  {}: {}", paint("36", "hint"), hint));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(false))
+    }
+}
+
+impl From<FeedMeError> for Report {
+    fn from(error: FeedMeError) -> Self {
+        Self::new(error)
+    }
+}
+
+impl From<std::io::Error> for Report {
+    fn from(error: std::io::Error) -> Self {
+        Self::new(error.into())
+    }
+}
+
+/// Attach what was being done to an error
+pub trait Context<T> {
+    fn context(self, action: impl Into<String>) -> std::result::Result<T, Report>;
+}
+
+impl<T, E: Into<FeedMeError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, action: impl Into<String>) -> std::result::Result<T, Report> {
+        self.map_err(|error| Report::new(error.into()).action(action))
+    }
+}
+
+/// Turn a binary's outcome into its exit status, printing any error as a
+/// `Report` on stderr (colored when stderr is a terminal)
+pub fn exit_status(result: std::result::Result<(), Report>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(report) => {
+            tracing::debug!(error = ?report.error, "command failed");
+            eprintln!("{}", report.render(std::io::stderr().is_terminal()));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_demo_database() {
+        let args = DatabaseArgs {
+            in_memory: true,
+            demo: true,
+        };
+
+        // The configured URL is ignored for in-memory databases
+        let config = DatabaseConfig {
+            url: "sqlite:///nonexistent/dir/feedme.db".to_string(),
+            ..DatabaseConfig::default()
+        };
+        let (pool, seeded) = args.open(&config).await.expect("Failed to open");
+
+        assert!(!seeded.is_empty());
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recipes")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to count recipes");
+        assert_eq!(count, seeded.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn test_connect_failure_report() {
+        let config = DatabaseConfig {
+            url: "sqlite:///nonexistent/dir/feedme.db".to_string(),
+            ..DatabaseConfig::default()
+        };
+
+        let report = DatabaseArgs::default()
+            .connect(&config)
+            .await
+            .expect_err("Directory doesn't exist");
+        let text = report.to_string();
+
+        assert_eq!(report.action.as_deref(), Some("opening the database"));
+        assert!(text.starts_with("error[database]: "), "{}", text);
+        assert!(text.contains("  database: sqlite:///nonexistent/dir/feedme.db"));
+        assert!(text.contains("  cause: Database error: "));
+        assert!(text.contains("  hint: check that the directory"));
+    }
+
+    #[test]
+    fn test_report_render() {
+        let report = Err::<(), _>(FeedMeError::RecipeNotFound(3))
+            .context("printing a label")
+            .unwrap_err();
+
+        assert_eq!(
+            report.render(false),
+            "error[recipe_not_found]: There is no recipe 3\n  while printing a label\n  cause: Recipe not found with id: 3"
+        );
+        assert!(
+            report
+                .render(true)
+                .starts_with("\x1b[1;31merror[recipe_not_found]\x1b[0m: ")
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::{FeedMeError, Result};
+use crate::i18n::Locale;
+use crate::models::api::{Nutrient, NutrientDeviation, NutritionFacts, StoreLayout};
+use crate::quantity::QuantityFormat;
+use crate::units::{UnitStyle, UnitSystem};
+
+/// Default location of the config file, relative to the working directory
+pub const DEFAULT_CONFIG_PATH: &str = "feedme.toml";
+
+/// Environment variable pointing at an alternative config file
+pub const CONFIG_PATH_ENV: &str = "FEEDME_CONFIG";
+
+/// Environment variable overriding `database.url`
+pub const DATABASE_URL_ENV: &str = "FEEDME_DATABASE_URL";
+
+/// Environment variable supplying the SQLCipher passphrase
+pub const DATABASE_PASSPHRASE_ENV: &str = "FEEDME_DB_PASSPHRASE";
+
+/// Environment variable supplying the USDA FoodData Central API key
+pub const FDC_API_KEY_ENV: &str = "FEEDME_FDC_API_KEY";
+
+/// Environment variable supplying the recipe pack signing key
+pub const PACK_KEY_ENV: &str = "FEEDME_PACK_KEY";
+
+/// Environment variable supplying the SMTP password for the weekly digest
+pub const SMTP_PASSWORD_ENV: &str = "FEEDME_SMTP_PASSWORD";
+
+/// Top-level FeedMe configuration, loaded from `feedme.toml`
+///
+/// Every section is optional; missing keys fall back to their defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub nutrition: NutritionTargets,
+    pub notify: NotifyConfig,
+    pub digest: DigestConfig,
+    pub usda: UsdaConfig,
+    pub display: DisplayConfig,
+    pub suggestions: SuggestionConfig,
+    pub sharing: SharingConfig,
+    pub shopping: ShoppingConfig,
+}
+
+/// Connection and pool settings for the database
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+    /// Seconds to wait for a free connection before giving up
+    pub acquire_timeout_secs: u64,
+    /// Seconds before a connection is closed and replaced (None keeps it forever)
+    pub max_lifetime_secs: Option<u64>,
+    /// SQLCipher key; requires the `sqlcipher` feature
+    pub passphrase: Option<String>,
+    /// Milliseconds SQLite waits on a locked database before reporting busy
+    pub busy_timeout_ms: u64,
+    /// Open without write access; migrations are skipped and writes fail with ReadOnly
+    pub read_only: bool,
+    /// Number of pre-migration backups to keep next to the database (0 disables them)
+    pub backup_retention: usize,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: "sqlite://feedme.db".to_string(),
+            max_connections: 5,
+            acquire_timeout_secs: 30,
+            max_lifetime_secs: Some(30 * 60),
+            passphrase: None,
+            busy_timeout_ms: 5_000,
+            read_only: false,
+            backup_retention: 5,
+        }
+    }
+}
+
+/// Daily nutrition targets used to flag meal plan days
+/// Unset targets aren't checked
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NutritionTargets {
+    pub calories: Option<f64>,
+    pub protein_g: Option<f64>,
+    pub carbohydrates_g: Option<f64>,
+    pub fat_g: Option<f64>,
+    /// How far (in percent) a day may stray from a target before it's flagged
+    pub tolerance_percent: f64,
+}
+
+impl Default for NutritionTargets {
+    fn default() -> Self {
+        Self {
+            calories: None,
+            protein_g: None,
+            carbohydrates_g: None,
+            fat_g: None,
+            tolerance_percent: 10.0,
+        }
+    }
+}
+
+impl NutritionTargets {
+    pub fn target(&self, nutrient: Nutrient) -> Option<f64> {
+        match nutrient {
+            Nutrient::Calories => self.calories,
+            Nutrient::Protein => self.protein_g,
+            Nutrient::Carbohydrates => self.carbohydrates_g,
+            Nutrient::Fat => self.fat_g,
+        }
+    }
+
+    /// Targets `facts` misses by more than the tolerance
+    pub fn deviations(&self, facts: &NutritionFacts) -> Vec<NutrientDeviation> {
+        Nutrient::ALL
+            .into_iter()
+            .filter_map(|nutrient| {
+                let target = self.target(nutrient)?;
+                let actual = facts.get(nutrient);
+                let allowed = target * self.tolerance_percent / 100.0;
+                ((actual - target).abs() > allowed).then_some(NutrientDeviation {
+                    nutrient,
+                    actual,
+                    target,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Where `feedme notify` sends meal reminders
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Show a desktop notification (notify-send / osascript)
+    pub desktop: bool,
+    /// POST reminders as JSON here; requires the `webhooks` feature
+    pub webhook_url: Option<String>,
+    /// Remind this many days before the meal ("defrost tonight" for tomorrow)
+    pub days_ahead: u64,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            desktop: true,
+            webhook_url: None,
+            days_ahead: 1,
+        }
+    }
+}
+
+/// Where `feedme digest --email` sends the weekly digest
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DigestConfig {
+    /// Addresses the digest goes to
+    pub to: Vec<String>,
+    /// Sender address, e.g. "FeedMe <feedme@example.com>"
+    pub from: Option<String>,
+    /// SMTP server, reached over STARTTLS; requires the `email` feature
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            to: Vec::new(),
+            from: None,
+            smtp_host: None,
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+        }
+    }
+}
+
+/// Access to USDA FoodData Central for importing nutrition data
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UsdaConfig {
+    /// api.data.gov key; requires the `usda` feature
+    pub api_key: Option<String>,
+}
+
+/// How recipes, shopping lists and exports are presented
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// Convert quantities to "metric" or "imperial"; unset shows them as written
+    pub units: Option<UnitSystem>,
+    /// Write units "abbreviated" ("tbsp") or "full" ("tablespoons"); unset
+    /// keeps them as written
+    pub unit_style: Option<UnitStyle>,
+    /// Language of TUI and CLI text: "en" (the default) or "es"
+    pub language: Locale,
+}
+
+/// How `feedme dinner` picks recipes
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SuggestionConfig {
+    /// Never suggest a recipe cooked within this many days
+    pub skip_cooked_within_days: Option<u64>,
+}
+
+/// Trading recipe packs with other households
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SharingConfig {
+    /// Secret shared with the people you trade packs with; exported packs
+    /// are signed with it and imported ones must carry a matching signature
+    pub pack_key: Option<String>,
+}
+
+/// How shopping lists are ordered
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShoppingConfig {
+    /// Store whose layout is used when none is picked; unset orders lists
+    /// alphabetically
+    pub default_store: Option<String>,
+    /// Each store's aisles in the order they're walked, e.g.
+    /// `aldi = ["produce", "bakery", "dairy"]`
+    pub stores: BTreeMap<String, Vec<String>>,
+}
+
+impl ShoppingConfig {
+    /// Layout of `store`, or of `default_store` when None
+    /// Naming a store without a layout is a `Config` error
+    pub fn layout(&self, store: Option<&str>) -> Result<Option<StoreLayout>> {
+        let Some(store) = store.or(self.default_store.as_deref()) else {
+            return Ok(None);
+        };
+
+        match self.stores.get(store) {
+            Some(aisles) => Ok(Some(StoreLayout::new(store, aisles))),
+            None => Err(FeedMeError::Config(format!(
+                "no layout for store '{}' in [shopping.stores]",
+                store
+            ))),
+        }
+    }
+}
+
+impl SharingConfig {
+    pub fn pack_key(&self) -> Option<&[u8]> {
+        self.pack_key.as_deref().map(str::as_bytes)
+    }
+}
+
+impl DisplayConfig {
+    pub fn quantity_format(&self) -> QuantityFormat {
+        QuantityFormat {
+            system: self.units,
+            style: self.unit_style,
+        }
+    }
+}
+
+impl DatabaseConfig {
+    pub fn acquire_timeout(&self) -> Duration {
+        Duration::from_secs(self.acquire_timeout_secs)
+    }
+
+    pub fn busy_timeout(&self) -> Duration {
+        Duration::from_millis(self.busy_timeout_ms)
+    }
+
+    pub fn max_lifetime(&self) -> Option<Duration> {
+        self.max_lifetime_secs.map(Duration::from_secs)
+    }
+}
+
+impl Config {
+    /// Parse a config from TOML text
+    pub fn from_toml(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| FeedMeError::Config(e.to_string()))
+    }
+
+    /// Read a config file from disk
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| FeedMeError::Config(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml(&text)
+    }
+
+    /// Load the config used by the binaries
+    ///
+    /// Reads `$FEEDME_CONFIG` if set, otherwise `feedme.toml` when present,
+    /// otherwise the defaults. `$FEEDME_DATABASE_URL` overrides the URL and
+    /// `$FEEDME_DB_PASSPHRASE` the SQLCipher passphrase, `$FEEDME_FDC_API_KEY`
+    /// the FoodData Central key, `$FEEDME_PACK_KEY` the recipe pack key and
+    /// `$FEEDME_SMTP_PASSWORD` the digest's SMTP password.
+    pub fn load() -> Result<Self> {
+        let mut config = match std::env::var_os(CONFIG_PATH_ENV) {
+            Some(path) => Self::from_file(&PathBuf::from(path))?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            None => Self::default(),
+        };
+
+        if let Ok(url) = std::env::var(DATABASE_URL_ENV) {
+            config.database.url = url;
+        }
+
+        if let Ok(passphrase) = std::env::var(DATABASE_PASSPHRASE_ENV) {
+            config.database.passphrase = Some(passphrase);
+        }
+
+        if let Ok(api_key) = std::env::var(FDC_API_KEY_ENV) {
+            config.usda.api_key = Some(api_key);
+        }
+
+        if let Ok(key) = std::env::var(PACK_KEY_ENV) {
+            config.sharing.pack_key = Some(key);
+        }
+
+        if let Ok(password) = std::env::var(SMTP_PASSWORD_ENV) {
+            config.digest.smtp_password = Some(password);
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let config = Config::from_toml("").expect("Failed to parse empty config");
+
+        assert_eq!(config.database.url, "sqlite://feedme.db");
+        assert_eq!(config.database.max_connections, 5);
+        assert_eq!(config.database.acquire_timeout(), Duration::from_secs(30));
+        assert_eq!(
+            config.database.max_lifetime(),
+            Some(Duration::from_secs(1800))
+        );
+    }
+
+    #[test]
+    fn test_partial_database_section() {
+        let config = Config::from_toml(
+            r#"
+            [database]
+            url = "sqlite://other.db"
+            max_connections = 2
+            "#,
+        )
+        .expect("Failed to parse config");
+
+        assert_eq!(config.database.url, "sqlite://other.db");
+        assert_eq!(config.database.max_connections, 2);
+        assert_eq!(config.database.acquire_timeout_secs, 30);
+    }
+
+    #[test]
+    fn test_store_layouts() {
+        let config = Config::from_toml(
+            r#"
+            [shopping]
+            default_store = "aldi"
+
+            [shopping.stores]
+            aldi = ["Produce", "Dairy"]
+            corner = ["dairy"]
+            "#,
+        )
+        .expect("Failed to parse config");
+
+        let layout = config.shopping.layout(None).unwrap().unwrap();
+        assert_eq!(layout.store, "aldi");
+        assert_eq!(layout.aisles, vec!["produce", "dairy"]);
+        assert_eq!(
+            config
+                .shopping
+                .layout(Some("corner"))
+                .unwrap()
+                .unwrap()
+                .aisles,
+            vec!["dairy"]
+        );
+        assert!(matches!(
+            config.shopping.layout(Some("costco")),
+            Err(FeedMeError::Config(_))
+        ));
+        assert!(Config::default().shopping.layout(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_display_units() {
+        let config = Config::from_toml(
+            r#"
+            [display]
+            units = "imperial"
+            unit_style = "full"
+            "#,
+        )
+        .expect("Failed to parse config");
+
+        assert_eq!(config.display.units, Some(UnitSystem::Imperial));
+        assert_eq!(config.display.unit_style, Some(UnitStyle::Full));
+        assert_eq!(Config::default().display.units, None);
+        assert!(Config::from_toml("[display]\nunits = \"cubits\"").is_err());
+    }
+
+    #[test]
+    fn test_nutrition_targets() {
+        let config = Config::from_toml(
+            r#"
+            [nutrition]
+            calories = 2000
+            protein_g = 100
+            "#,
+        )
+        .expect("Failed to parse config");
+        let targets = &config.nutrition;
+
+        let facts = NutritionFacts {
+            calories: 2150.0,
+            protein_g: 70.0,
+            carbohydrates_g: 500.0,
+            fat_g: 0.0,
+        };
+        let deviations = targets.deviations(&facts);
+
+        // Calories are within 10%; carbohydrates and fat have no target
+        assert_eq!(deviations.len(), 1);
+        assert_eq!(deviations[0].nutrient, Nutrient::Protein);
+        assert_eq!(deviations[0].target, 100.0);
+    }
+
+    #[test]
+    fn test_digest_section() {
+        let config = Config::from_toml(
+            r#"
+            [digest]
+            to = ["sam@example.com", "alex@example.com"]
+            from = "FeedMe <feedme@example.com>"
+            smtp_host = "smtp.example.com"
+            "#,
+        )
+        .expect("Failed to parse config");
+
+        assert_eq!(config.digest.to.len(), 2);
+        assert_eq!(config.digest.smtp_port, 587);
+        assert_eq!(config.digest.smtp_username, None);
+        assert!(Config::default().digest.smtp_host.is_none());
+    }
+
+    #[test]
+    fn test_unknown_key_is_rejected() {
+        let result = Config::from_toml("[database]\nmax_conections = 2\n");
+
+        assert!(matches!(result, Err(FeedMeError::Config(_))));
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::{Datelike, NaiveDate};
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+use super::price_controller::estimate_recipe_cost;
+use crate::error::Result;
+use crate::models::api::{Analytics, IngredientUsage, MonthlyTrend};
+
+/// Ingredient usage and monthly cooking trends over `days`, from the cook log
+/// Lists at most `limit` ingredients
+pub async fn analytics(
+    pool: &SqlitePool,
+    days: RangeInclusive<NaiveDate>,
+    limit: usize,
+) -> Result<Analytics> {
+    Ok(Analytics {
+        start: *days.start(),
+        end: *days.end(),
+        ingredients: ingredient_usage(pool, days.clone(), limit).await?,
+        months: cooking_trends(pool, days).await?,
+    })
+}
+
+/// The `limit` ingredients cooked most often over `days`, then those in the
+/// most recipes, then by name
+/// Ingredients in no recipe are left out
+pub async fn ingredient_usage(
+    pool: &SqlitePool,
+    days: RangeInclusive<NaiveDate>,
+    limit: usize,
+) -> Result<Vec<IngredientUsage>> {
+    let usage = sqlx::query(
+        r#"
+        SELECT
+            i.id,
+            i.name,
+            COUNT(DISTINCT ri.recipe_id) as recipes,
+            (
+                SELECT COUNT(*)
+                FROM cook_log c
+                WHERE c.cooked_on BETWEEN ? AND ?
+                AND c.recipe_id IN (
+                    SELECT ri.recipe_id FROM recipe_ingredients ri JOIN recipes r ON ri.recipe_id = r.id
+                    WHERE ri.ingredient_id = i.id AND r.deleted_at IS NULL
+                )
+            ) as cooks
+        FROM ingredients i
+        JOIN recipe_ingredients ri ON ri.ingredient_id = i.id
+        JOIN recipes r ON ri.recipe_id = r.id
+        WHERE r.deleted_at IS NULL
+        GROUP BY i.id
+        ORDER BY cooks DESC, recipes DESC, i.name
+        LIMIT ?
+        "#,
+    )
+    .bind(days.start())
+    .bind(days.end())
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| IngredientUsage {
+        ingredient_id: row.get("id"),
+        ingredient_name: row.get("name"),
+        recipes: row.get("recipes"),
+        cooks: row.get("cooks"),
+    })
+    .collect();
+
+    Ok(usage)
+}
+
+/// Cooks per calendar month over `days`, with what a cook cost and took on
+/// average
+/// Costs are estimated at the latest prices for the recipe's own servings,
+/// and only recipes with every ingredient priced count toward them
+pub async fn cooking_trends(
+    pool: &SqlitePool,
+    days: RangeInclusive<NaiveDate>,
+) -> Result<Vec<MonthlyTrend>> {
+    let cooks: Vec<(NaiveDate, i64, Option<i64>, Option<i64>)> = sqlx::query_as(
+        r#"
+        SELECT c.cooked_on, c.recipe_id, r.prep_minutes, r.cook_minutes
+        FROM cook_log c
+        JOIN recipes r ON c.recipe_id = r.id
+        WHERE c.cooked_on BETWEEN ? AND ? AND r.deleted_at IS NULL
+        ORDER BY c.cooked_on
+        "#,
+    )
+    .bind(days.start())
+    .bind(days.end())
+    .fetch_all(pool)
+    .await?;
+
+    let mut costs: HashMap<i64, Option<f64>> = HashMap::new();
+    for &(_, recipe_id, _, _) in &cooks {
+        if costs.contains_key(&recipe_id) {
+            continue;
+        }
+        let cost = estimate_recipe_cost(pool, recipe_id, None).await?;
+        let priced = !cost.lines.is_empty() && cost.unpriced.is_empty();
+        costs.insert(recipe_id, priced.then_some(cost.total));
+    }
+
+    #[derive(Default)]
+    struct Month {
+        cooks: i64,
+        recipes: HashSet<i64>,
+        costs: Vec<f64>,
+        minutes: Vec<f64>,
+    }
+    let mut months: BTreeMap<NaiveDate, Month> = BTreeMap::new();
+    for (cooked_on, recipe_id, prep_minutes, cook_minutes) in cooks {
+        let month = months.entry(cooked_on.with_day(1).unwrap()).or_default();
+        month.cooks += 1;
+        month.recipes.insert(recipe_id);
+        if let Some(cost) = costs[&recipe_id] {
+            month.costs.push(cost);
+        }
+        if prep_minutes.is_some() || cook_minutes.is_some() {
+            month
+                .minutes
+                .push((prep_minutes.unwrap_or(0) + cook_minutes.unwrap_or(0)) as f64);
+        }
+    }
+
+    let mean = |values: &[f64]| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    Ok(months
+        .into_iter()
+        .map(|(month, totals)| MonthlyTrend {
+            month,
+            cooks: totals.cooks,
+            recipes: totals.recipes.len() as i64,
+            average_cost: mean(&totals.costs),
+            average_minutes: mean(&totals.minutes),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{add_price, log_cook, set_recipe_times};
+    use crate::models::api::IngredientPrice;
+    use crate::models::test_fixtures::test_db;
+    use crate::seed::seed_demo_data;
+    use rstest::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_ingredient_usage(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.unwrap();
+
+        for day in [1, 2, 3] {
+            log_cook(&pool, ids[1], date(9, day), None).await.unwrap();
+        }
+        // Outside the range
+        log_cook(&pool, ids[0], date(8, 31), None).await.unwrap();
+
+        let usage = ingredient_usage(&pool, date(9, 1)..=date(9, 30), 3)
+            .await
+            .unwrap();
+        assert_eq!(usage.len(), 3);
+        assert!(usage.iter().all(|ingredient| ingredient.cooks == 3));
+        assert!(usage.is_sorted_by(|a, b| a.recipes >= b.recipes));
+
+        let all = ingredient_usage(&pool, date(9, 1)..=date(9, 30), 100)
+            .await
+            .unwrap();
+        assert!(all.iter().all(|ingredient| ingredient.recipes > 0));
+        assert!(all.is_sorted_by(|a, b| a.cooks >= b.cooks));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_cooking_trends(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.unwrap();
+        set_recipe_times(&pool, ids[0], Some(10), Some(20))
+            .await
+            .unwrap();
+        set_recipe_times(&pool, ids[1], None, Some(45))
+            .await
+            .unwrap();
+
+        log_cook(&pool, ids[0], date(9, 5), None).await.unwrap();
+        log_cook(&pool, ids[1], date(9, 20), None).await.unwrap();
+        log_cook(&pool, ids[0], date(9, 27), None).await.unwrap();
+        log_cook(&pool, ids[2], date(10, 2), None).await.unwrap();
+
+        let months = cooking_trends(&pool, date(1, 1)..=date(12, 31))
+            .await
+            .unwrap();
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].month, date(9, 1));
+        assert_eq!((months[0].cooks, months[0].recipes), (3, 2));
+        assert_eq!(months[0].average_minutes, Some(35.0));
+        assert_eq!(months[0].average_cost, None);
+        assert_eq!((months[1].cooks, months[1].average_minutes), (1, None));
+
+        // Price every ingredient of the October recipe
+        let recipe = crate::controllers::get_recipe(&pool, ids[2]).await.unwrap();
+        for ingredient in &recipe.ingredients {
+            let parsed = crate::quantity::parse_quantity(&ingredient.quantity_unit).unwrap();
+            add_price(
+                &pool,
+                &IngredientPrice {
+                    id: 0,
+                    ingredient_id: ingredient.ingredient_id,
+                    ingredient_name: String::new(),
+                    price: 1.0,
+                    amount: parsed.amount,
+                    unit: parsed.unit,
+                    store: None,
+                    observed_on: date(10, 1),
+                },
+            )
+            .await
+            .unwrap();
+        }
+        let months = cooking_trends(&pool, date(10, 1)..=date(10, 31))
+            .await
+            .unwrap();
+        assert_eq!(
+            months[0].average_cost,
+            Some(recipe.ingredients.len() as f64)
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_analytics(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.unwrap();
+        log_cook(&pool, ids[0], date(9, 5), None).await.unwrap();
+        log_cook(&pool, ids[0], date(10, 5), None).await.unwrap();
+
+        let analytics = analytics(&pool, date(9, 1)..=date(10, 31), 5)
+            .await
+            .unwrap();
+        assert_eq!(analytics.total_cooks(), 2);
+        assert_eq!(analytics.ingredients.len(), 5);
+        let text = analytics.to_string();
+        assert!(text.starts_with("Cooked 2 times from 2026-09-01 to 2026-10-31\n"));
+        assert!(text.contains("\n  2026-09    1 cooks    1 recipes\n"));
+    }
+}
@@ -0,0 +1,317 @@
+use serde_json::Value;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::error::Result;
+use crate::models::{AuditAction, AuditLogRecord};
+
+/// Environment variable naming who is making changes (falls back to $USER)
+pub const ACTOR_ENV: &str = "FEEDME_ACTOR";
+
+/// Who to attribute changes to
+/// Uses $FEEDME_ACTOR when set, otherwise the login name of the current user
+pub fn current_actor() -> Option<String> {
+    [ACTOR_ENV, "USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+}
+
+/// Append an entry to the audit log
+/// Takes a connection so it can run inside the caller's transaction
+pub(crate) async fn record_change(
+    conn: &mut SqliteConnection,
+    entity: &str,
+    entity_id: i64,
+    action: AuditAction,
+    old_value: Option<&Value>,
+    new_value: Option<&Value>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO audit_log (entity, entity_id, action, actor, old_value, new_value) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(entity)
+    .bind(entity_id)
+    .bind(action.as_str())
+    .bind(current_actor())
+    .bind(old_value.map(Value::to_string))
+    .bind(new_value.map(Value::to_string))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// A row's `columns` as a JSON object, for the old or new value of an
+/// audit entry; None when `table` has no row `id`
+/// Takes a connection so it reads inside the caller's transaction
+pub(crate) async fn row_snapshot(
+    conn: &mut SqliteConnection,
+    table: &str,
+    id: i64,
+    columns: &[&str],
+) -> Result<Option<Value>> {
+    let fields: Vec<String> = columns
+        .iter()
+        .map(|column| format!("'{column}', {column}"))
+        .collect();
+    let snapshot: Option<String> = sqlx::query_scalar(&format!(
+        "SELECT json_object({}) FROM {} WHERE id = ?",
+        fields.join(", "),
+        table
+    ))
+    .bind(id)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(snapshot
+        .map(|snapshot| serde_json::from_str(&snapshot))
+        .transpose()?)
+}
+
+/// Record the creation of `entity`'s row in `table`, with its `columns`
+pub(crate) async fn record_create(
+    conn: &mut SqliteConnection,
+    entity: &str,
+    table: &str,
+    id: i64,
+    columns: &[&str],
+) -> Result<()> {
+    let new = row_snapshot(conn, table, id, columns).await?;
+    record_change(conn, entity, id, AuditAction::Create, None, new.as_ref()).await
+}
+
+/// Delete `entity`'s row in `table`, recording its `columns` as they were
+/// Returns whether the row existed; nothing is recorded when it didn't
+pub(crate) async fn delete_audited(
+    conn: &mut SqliteConnection,
+    entity: &str,
+    table: &str,
+    id: i64,
+    columns: &[&str],
+) -> Result<bool> {
+    let Some(old) = row_snapshot(conn, table, id, columns).await? else {
+        return Ok(false);
+    };
+
+    sqlx::query(&format!("DELETE FROM {} WHERE id = ?", table))
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    record_change(conn, entity, id, AuditAction::Delete, Some(&old), None).await?;
+
+    Ok(true)
+}
+
+/// Record an update to some `columns` of `entity`'s row in `table`, from
+/// `old` (see `row_snapshot`) to what they hold now
+pub(crate) async fn record_update(
+    conn: &mut SqliteConnection,
+    entity: &str,
+    table: &str,
+    id: i64,
+    columns: &[&str],
+    old: Option<Value>,
+) -> Result<()> {
+    let new = row_snapshot(conn, table, id, columns).await?;
+    record_change(
+        conn,
+        entity,
+        id,
+        AuditAction::Update,
+        old.as_ref(),
+        new.as_ref(),
+    )
+    .await
+}
+
+/// Get the audit history of a single entity, oldest first
+pub async fn get_audit_log(
+    pool: &SqlitePool,
+    entity: &str,
+    entity_id: i64,
+) -> Result<Vec<AuditLogRecord>> {
+    let entries = sqlx::query_as::<_, AuditLogRecord>(
+        r#"
+        SELECT id, entity, entity_id, action, actor, old_value, new_value, created_at
+        FROM audit_log
+        WHERE entity = ? AND entity_id = ?
+        ORDER BY id
+        "#,
+    )
+    .bind(entity)
+    .bind(entity_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{
+        add_to_pantry, create_ingredient, create_recipe, remove_from_pantry, set_favorite,
+    };
+    use crate::models::api::{PantryItem, Recipe, Visibility};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_ingredient_is_audited(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let id = create_ingredient(&pool, "saffron")
+            .await
+            .expect("Failed to create ingredient");
+
+        let log = get_audit_log(&pool, "ingredient", id)
+            .await
+            .expect("Failed to fetch audit log");
+
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].action, "create");
+        assert_eq!(log[0].old_value, None);
+
+        let new_value: Value =
+            serde_json::from_str(log[0].new_value.as_deref().unwrap()).expect("Invalid JSON");
+        assert_eq!(new_value["name"], "saffron");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe_is_audited(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let recipe = Recipe {
+            id: 0,
+            name: "Toast".to_string(),
+            instructions: Some("Toast the bread".to_string()),
+            created_at: String::new(),
+            ingredients: vec![],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let id = create_recipe(&pool, &recipe)
+            .await
+            .expect("Failed to create recipe");
+
+        let log = get_audit_log(&pool, "recipe", id)
+            .await
+            .expect("Failed to fetch audit log");
+
+        assert_eq!(log.len(), 1);
+        let new_value: Value =
+            serde_json::from_str(log[0].new_value.as_deref().unwrap()).expect("Invalid JSON");
+        assert_eq!(new_value["id"], id);
+        assert_eq!(new_value["name"], "Toast");
+        assert_eq!(new_value["instructions"], "Toast the bread");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_audit_log_other_entity_is_empty(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let id = create_ingredient(&pool, "salt")
+            .await
+            .expect("Failed to create ingredient");
+
+        let log = get_audit_log(&pool, "recipe", id)
+            .await
+            .expect("Failed to fetch audit log");
+
+        assert!(log.is_empty());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_recipe_setter_is_audited(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let recipe = Recipe {
+            id: 0,
+            name: "Toast".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let id = create_recipe(&pool, &recipe)
+            .await
+            .expect("Failed to create recipe");
+        set_favorite(&pool, id, true)
+            .await
+            .expect("Failed to favorite recipe");
+
+        let log = get_audit_log(&pool, "recipe", id)
+            .await
+            .expect("Failed to fetch audit log");
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[1].action, "update");
+        let old_value: Value =
+            serde_json::from_str(log[1].old_value.as_deref().unwrap()).expect("Invalid JSON");
+        let new_value: Value =
+            serde_json::from_str(log[1].new_value.as_deref().unwrap()).expect("Invalid JSON");
+        assert_eq!(old_value["is_favorite"], 0);
+        assert_eq!(new_value["is_favorite"], 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_pantry_add_and_remove_are_audited(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let ingredient_id = create_ingredient(&pool, "rice")
+            .await
+            .expect("Failed to create ingredient");
+        let item = PantryItem {
+            id: 0,
+            ingredient_id,
+            ingredient_name: String::new(),
+            amount: 2.0,
+            unit: Some("cup".to_string()),
+            expires_on: None,
+            is_staple: false,
+            icon: None,
+            min_amount: None,
+        };
+        let id = add_to_pantry(&pool, &item)
+            .await
+            .expect("Failed to add to pantry");
+        assert!(
+            remove_from_pantry(&pool, id)
+                .await
+                .expect("Failed to remove from pantry")
+        );
+
+        let log = get_audit_log(&pool, "pantry_item", id)
+            .await
+            .expect("Failed to fetch audit log");
+
+        let actions: Vec<&str> = log.iter().map(|entry| entry.action.as_str()).collect();
+        assert_eq!(actions, ["create", "delete"]);
+        let old_value: Value =
+            serde_json::from_str(log[1].old_value.as_deref().unwrap()).expect("Invalid JSON");
+        assert_eq!(old_value["ingredient_id"], ingredient_id);
+        assert_eq!(old_value["unit"], "cup");
+        assert_eq!(log[1].new_value, None);
+    }
+}
@@ -0,0 +1,319 @@
+use serde_json::json;
+use sqlx::{Row, SqlitePool};
+
+use super::audit_controller::{delete_audited, record_change, record_create};
+use super::recipe_controller::generate_shopping_list;
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, OnDuplicate, Result};
+use crate::models::api::{Collection, CollectionRecipe, ShoppingListItem};
+use crate::models::{AuditAction, CollectionRecord};
+use crate::validation::Validator;
+
+/// Create an empty collection
+/// The name is stored trimmed and must be unique, ignoring case
+/// (`DuplicateCollection` otherwise). Returns the collection ID
+pub async fn create_collection(pool: &SqlitePool, name: &str) -> Result<i64> {
+    let mut validator = Validator::new();
+    let name = validator.name("name", name);
+    validator.finish()?;
+
+    with_busy_retry(|| insert_collection(pool, name)).await
+}
+
+async fn insert_collection(pool: &SqlitePool, name: &str) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let collection_id = sqlx::query("INSERT INTO collections (name) VALUES (?)")
+        .bind(name)
+        .execute(&mut *tx)
+        .await
+        .on_duplicate(|| FeedMeError::DuplicateCollection(name.to_string()))?
+        .last_insert_rowid();
+    record_create(
+        &mut tx,
+        "collection",
+        "collections",
+        collection_id,
+        &["name"],
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(collection_id)
+}
+
+/// All collections ordered by name
+pub async fn list_collections(pool: &SqlitePool) -> Result<Vec<CollectionRecord>> {
+    let collections = sqlx::query_as::<_, CollectionRecord>(
+        "SELECT id, name, created_at FROM collections ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(collections)
+}
+
+/// Fetch a collection with its recipes
+pub async fn get_collection(pool: &SqlitePool, collection_id: i64) -> Result<Collection> {
+    let name: String = sqlx::query_scalar("SELECT name FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(FeedMeError::CollectionNotFound(collection_id))?;
+
+    let recipes = sqlx::query(
+        r#"
+        SELECT r.id, r.name
+        FROM collection_recipes cr
+        JOIN recipes r ON cr.recipe_id = r.id
+        WHERE cr.collection_id = ? AND r.deleted_at IS NULL
+        ORDER BY r.name, r.id
+        "#,
+    )
+    .bind(collection_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| CollectionRecipe {
+        recipe_id: row.get("id"),
+        name: row.get("name"),
+    })
+    .collect();
+
+    Ok(Collection {
+        id: collection_id,
+        name,
+        recipes,
+    })
+}
+
+/// Delete a collection, leaving its recipes alone
+/// Returns whether the collection existed
+pub async fn delete_collection(pool: &SqlitePool, collection_id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_collection_row(pool, collection_id)).await
+}
+
+async fn delete_collection_row(pool: &SqlitePool, collection_id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let deleted = delete_audited(
+        &mut tx,
+        "collection",
+        "collections",
+        collection_id,
+        &["name"],
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(deleted)
+}
+
+/// Add a recipe to a collection; adding it twice is a no-op
+pub async fn add_to_collection(
+    pool: &SqlitePool,
+    collection_id: i64,
+    recipe_id: i64,
+) -> Result<()> {
+    with_busy_retry(|| insert_collection_recipe(pool, collection_id, recipe_id)).await
+}
+
+async fn insert_collection_recipe(
+    pool: &SqlitePool,
+    collection_id: i64,
+    recipe_id: i64,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let collection: Option<i64> = sqlx::query_scalar("SELECT id FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    if collection.is_none() {
+        return Err(FeedMeError::CollectionNotFound(collection_id));
+    }
+
+    let result = sqlx::query(
+        "INSERT OR IGNORE INTO collection_recipes (collection_id, recipe_id) SELECT ?, id FROM recipes WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(collection_id)
+    .bind(recipe_id)
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        let recipe: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM recipes WHERE id = ? AND deleted_at IS NULL")
+                .bind(recipe_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if recipe.is_none() {
+            return Err(FeedMeError::RecipeNotFound(recipe_id));
+        }
+    } else {
+        record_change(
+            &mut tx,
+            "collection",
+            collection_id,
+            AuditAction::Update,
+            None,
+            Some(&json!({ "added_recipe": recipe_id })),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Take a recipe out of a collection
+/// Returns whether the recipe was in it
+pub async fn remove_from_collection(
+    pool: &SqlitePool,
+    collection_id: i64,
+    recipe_id: i64,
+) -> Result<bool> {
+    with_busy_retry(|| delete_collection_recipe(pool, collection_id, recipe_id)).await
+}
+
+async fn delete_collection_recipe(
+    pool: &SqlitePool,
+    collection_id: i64,
+    recipe_id: i64,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let removed =
+        sqlx::query("DELETE FROM collection_recipes WHERE collection_id = ? AND recipe_id = ?")
+            .bind(collection_id)
+            .bind(recipe_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+            > 0;
+    if removed {
+        record_change(
+            &mut tx,
+            "collection",
+            collection_id,
+            AuditAction::Update,
+            Some(&json!({ "removed_recipe": recipe_id })),
+            None,
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(removed)
+}
+
+/// One shopping list covering every recipe in a collection, combined like
+/// `generate_shopping_list`
+pub async fn collection_shopping_list(
+    pool: &SqlitePool,
+    collection_id: i64,
+) -> Result<Vec<ShoppingListItem>> {
+    let collection = get_collection(pool, collection_id).await?;
+    generate_shopping_list(pool, &collection.recipe_ids()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_fixtures::test_db;
+    use crate::seed::seed_demo_data;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_collection_membership(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.unwrap();
+
+        let camping = create_collection(&pool, " Camping ").await.unwrap();
+        let holidays = create_collection(&pool, "Thanksgiving 2025").await.unwrap();
+        assert!(matches!(
+            create_collection(&pool, "camping").await,
+            Err(FeedMeError::DuplicateCollection(name)) if name == "camping"
+        ));
+
+        add_to_collection(&pool, camping, ids[2]).await.unwrap();
+        add_to_collection(&pool, camping, ids[0]).await.unwrap();
+        add_to_collection(&pool, camping, ids[0]).await.unwrap();
+        add_to_collection(&pool, holidays, ids[0]).await.unwrap();
+
+        let collection = get_collection(&pool, camping).await.unwrap();
+        assert_eq!(collection.name, "Camping");
+        let mut members = collection.recipe_ids();
+        members.sort_unstable();
+        assert_eq!(members, vec![ids[0], ids[2]]);
+        assert!(
+            collection
+                .recipes
+                .is_sorted_by_key(|recipe| recipe.name.clone())
+        );
+
+        let names: Vec<String> = list_collections(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|collection| collection.name)
+            .collect();
+        assert_eq!(names, vec!["Camping", "Thanksgiving 2025"]);
+
+        assert!(
+            remove_from_collection(&pool, camping, ids[2])
+                .await
+                .unwrap()
+        );
+        assert!(
+            !remove_from_collection(&pool, camping, ids[2])
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            get_collection(&pool, camping).await.unwrap().recipe_ids(),
+            vec![ids[0]]
+        );
+
+        assert!(matches!(
+            add_to_collection(&pool, 999, ids[0]).await,
+            Err(FeedMeError::CollectionNotFound(999))
+        ));
+        assert!(matches!(
+            add_to_collection(&pool, camping, 999).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+
+        assert!(delete_collection(&pool, holidays).await.unwrap());
+        assert!(matches!(
+            get_collection(&pool, holidays).await,
+            Err(FeedMeError::CollectionNotFound(_))
+        ));
+        assert!(crate::controllers::get_recipe(&pool, ids[0]).await.is_ok());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_collection_shopping_list(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.unwrap();
+        let camping = create_collection(&pool, "Camping").await.unwrap();
+        for &recipe_id in &ids[..2] {
+            add_to_collection(&pool, camping, recipe_id).await.unwrap();
+        }
+
+        let items = collection_shopping_list(&pool, camping).await.unwrap();
+        let expected = generate_shopping_list(&pool, &ids[..2]).await.unwrap();
+        assert_eq!(
+            items.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            expected.iter().map(ToString::to_string).collect::<Vec<_>>()
+        );
+        assert!(!items.is_empty());
+
+        let empty = create_collection(&pool, "Someday").await.unwrap();
+        assert!(
+            collection_shopping_list(&pool, empty)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
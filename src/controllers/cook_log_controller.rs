@@ -0,0 +1,326 @@
+use chrono::NaiveDate;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+
+use super::audit_controller::{delete_audited, record_create};
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, Result};
+use crate::models::CookLogRecord;
+use crate::models::api::RecipeNote;
+use crate::validation::Validator;
+
+const RECIPE_NOTE_COLUMNS: &str = r#"
+    n.id,
+    n.cook_log_id,
+    n.noted_on,
+    n.text,
+    n.rating_delta,
+    n.modifications
+"#;
+
+/// Columns recorded in a journal entry's audit entries
+const RECIPE_NOTE_AUDIT_COLUMNS: &[&str] = &[
+    "cook_log_id",
+    "noted_on",
+    "text",
+    "rating_delta",
+    "modifications",
+];
+
+fn recipe_note_from_row(row: &SqliteRow) -> RecipeNote {
+    RecipeNote {
+        id: row.get("id"),
+        cook_log_id: row.get("cook_log_id"),
+        noted_on: row.get("noted_on"),
+        text: row.get("text"),
+        rating_delta: row.get("rating_delta"),
+        modifications: row.get("modifications"),
+    }
+}
+
+/// Record that a recipe was cooked on a given day
+/// Returns the cook log entry ID
+pub async fn log_cook(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    cooked_on: NaiveDate,
+    notes: Option<&str>,
+) -> Result<i64> {
+    let mut validator = Validator::new();
+    validator.line("notes", notes);
+    validator.finish()?;
+
+    with_busy_retry(|| insert_cook(pool, recipe_id, cooked_on, notes)).await
+}
+
+async fn insert_cook(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    cooked_on: NaiveDate,
+    notes: Option<&str>,
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let id = sqlx::query("INSERT INTO cook_log (recipe_id, cooked_on, notes) VALUES (?, ?, ?)")
+        .bind(recipe_id)
+        .bind(cooked_on)
+        .bind(notes)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+    record_create(
+        &mut tx,
+        "cook_log",
+        "cook_log",
+        id,
+        &["recipe_id", "cooked_on", "notes"],
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Every time a recipe was cooked, most recent first
+pub async fn get_cook_log(pool: &SqlitePool, recipe_id: i64) -> Result<Vec<CookLogRecord>> {
+    let entries = sqlx::query_as::<_, CookLogRecord>(
+        r#"
+        SELECT id, recipe_id, cooked_on, notes, created_at
+        FROM cook_log
+        WHERE recipe_id = ?
+        ORDER BY cooked_on DESC, id DESC
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Write a journal entry about one time a recipe was cooked
+/// Returns the note ID, or `CookLogEntryNotFound` if the cook log entry
+/// doesn't exist
+pub async fn add_recipe_note(pool: &SqlitePool, note: &RecipeNote) -> Result<i64> {
+    let mut validator = Validator::new();
+    let text = validator.name("text", &note.text);
+    validator.line("modifications", note.modifications.as_deref());
+    validator.finish()?;
+
+    with_busy_retry(|| insert_recipe_note(pool, note, text)).await
+}
+
+async fn insert_recipe_note(pool: &SqlitePool, note: &RecipeNote, text: &str) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query(
+        r#"
+        INSERT INTO recipe_notes (cook_log_id, noted_on, text, rating_delta, modifications)
+        SELECT id, ?, ?, ?, ? FROM cook_log WHERE id = ?
+        "#,
+    )
+    .bind(note.noted_on)
+    .bind(text)
+    .bind(note.rating_delta)
+    .bind(&note.modifications)
+    .bind(note.cook_log_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::CookLogEntryNotFound(note.cook_log_id));
+    }
+    let id = result.last_insert_rowid();
+    record_create(
+        &mut tx,
+        "recipe_note",
+        "recipe_notes",
+        id,
+        RECIPE_NOTE_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Journal entries from every time a recipe was cooked, newest first
+pub async fn get_recipe_notes(pool: &SqlitePool, recipe_id: i64) -> Result<Vec<RecipeNote>> {
+    let notes = sqlx::query(&format!(
+        r#"
+        SELECT {RECIPE_NOTE_COLUMNS}
+        FROM recipe_notes n
+        JOIN cook_log c ON n.cook_log_id = c.id
+        WHERE c.recipe_id = ?
+        ORDER BY n.noted_on DESC, n.id DESC
+        "#
+    ))
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(recipe_note_from_row)
+    .collect();
+
+    Ok(notes)
+}
+
+/// Remove a journal entry
+/// Returns whether it existed
+pub async fn delete_recipe_note(pool: &SqlitePool, id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_recipe_note_row(pool, id)).await
+}
+
+async fn delete_recipe_note_row(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let deleted = delete_audited(
+        &mut tx,
+        "recipe_note",
+        "recipe_notes",
+        id,
+        RECIPE_NOTE_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(deleted)
+}
+
+/// Every recipe's journal entries, newest first, by recipe ID
+/// For exports, which can't query per recipe while streaming
+pub(crate) async fn all_recipe_notes(pool: &SqlitePool) -> Result<HashMap<i64, Vec<RecipeNote>>> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {RECIPE_NOTE_COLUMNS}, c.recipe_id
+        FROM recipe_notes n
+        JOIN cook_log c ON n.cook_log_id = c.id
+        ORDER BY c.recipe_id, n.noted_on DESC, n.id DESC
+        "#
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let mut notes: HashMap<i64, Vec<RecipeNote>> = HashMap::new();
+    for row in &rows {
+        notes
+            .entry(row.get("recipe_id"))
+            .or_default()
+            .push(recipe_note_from_row(row));
+    }
+
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_log_cook(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let recipe_id = sqlx::query("INSERT INTO recipes (name) VALUES ('Risotto')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        let first = NaiveDate::from_ymd_opt(2026, 9, 1).unwrap();
+        let second = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        log_cook(&pool, recipe_id, first, None).await.unwrap();
+        log_cook(&pool, recipe_id, second, Some("more parmesan"))
+            .await
+            .unwrap();
+
+        let log = get_cook_log(&pool, recipe_id).await.unwrap();
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].cooked_on, second);
+        assert_eq!(log[0].notes.as_deref(), Some("more parmesan"));
+        assert_eq!(log[1].cooked_on, first);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_recipe_journal(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = crate::seed::seed_demo_data(&pool).await.unwrap();
+        let first = NaiveDate::from_ymd_opt(2026, 9, 1).unwrap();
+        let second = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let september = log_cook(&pool, ids[0], first, None).await.unwrap();
+        let october = log_cook(&pool, ids[0], second, None).await.unwrap();
+
+        let note = |cook_log_id, noted_on, text: &str| RecipeNote {
+            id: 0,
+            cook_log_id,
+            noted_on,
+            text: text.to_string(),
+            rating_delta: None,
+            modifications: None,
+        };
+        let old = add_recipe_note(&pool, &note(september, first, "too sweet"))
+            .await
+            .unwrap();
+        add_recipe_note(
+            &pool,
+            &RecipeNote {
+                rating_delta: Some(1),
+                modifications: Some("half the sugar".to_string()),
+                ..note(october, second, " Much better ")
+            },
+        )
+        .await
+        .unwrap();
+
+        let journal: Vec<String> = get_recipe_notes(&pool, ids[0])
+            .await
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            journal,
+            vec![
+                "2026-10-01: Much better (+1) [changed: half the sugar]",
+                "2026-09-01: too sweet",
+            ]
+        );
+        assert!(get_recipe_notes(&pool, ids[1]).await.unwrap().is_empty());
+
+        let options = crate::models::api::RecipeOptions {
+            include_journal: true,
+            ..Default::default()
+        };
+        let recipe = crate::controllers::get_recipe_with(&pool, ids[0], options)
+            .await
+            .unwrap();
+        assert_eq!(recipe.journal.len(), 2);
+        assert!(
+            recipe
+                .to_string()
+                .contains("\nJournal:\n  2026-10-01: Much better")
+        );
+        let recipe = crate::controllers::get_recipe(&pool, ids[0]).await.unwrap();
+        assert!(recipe.journal.is_empty());
+
+        assert!(delete_recipe_note(&pool, old).await.unwrap());
+        assert!(!delete_recipe_note(&pool, old).await.unwrap());
+        assert!(matches!(
+            add_recipe_note(&pool, &note(999, first, "lost")).await,
+            Err(FeedMeError::CookLogEntryNotFound(999))
+        ));
+        assert!(matches!(
+            add_recipe_note(&pool, &note(october, first, "")).await,
+            Err(FeedMeError::Validation(_))
+        ));
+        let out_of_range = RecipeNote {
+            rating_delta: Some(7),
+            ..note(october, first, "wow")
+        };
+        assert!(matches!(
+            add_recipe_note(&pool, &out_of_range).await,
+            Err(FeedMeError::ConstraintViolation(_))
+        ));
+    }
+}
@@ -0,0 +1,276 @@
+use sqlx::SqlitePool;
+
+use super::audit_controller::{delete_audited, record_create};
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, OnDuplicate, Result};
+use crate::models::CustomUnitRecord;
+use crate::quantity::{IngredientUnits, parse_quantity};
+use crate::units::{Unit, UnitKind};
+use crate::validation::Validator;
+
+/// Columns recorded in a custom unit's audit entries
+const CUSTOM_UNIT_AUDIT_COLUMNS: &[&str] = &["name", "ingredient_id", "amount", "unit"];
+
+/// Define a custom unit as a quantity of a standard one, e.g. "stick" as
+/// "113 g", optionally only for one ingredient
+/// Replaces any earlier definition with the same name and scope. Returns the
+/// custom unit ID
+pub async fn define_custom_unit(
+    pool: &SqlitePool,
+    name: &str,
+    ingredient_id: Option<i64>,
+    equals: &str,
+) -> Result<i64> {
+    let mut validator = Validator::new();
+    let name = validator.name("name", name).to_lowercase();
+    validator.finish()?;
+
+    let parsed = parse_quantity(equals)
+        .filter(|parsed| parsed.amount > 0.0)
+        .ok_or_else(|| FeedMeError::InvalidQuantity(equals.to_string()))?;
+    let unit = Unit::parse(parsed.unit.as_deref().unwrap_or(""));
+    if !matches!(unit.kind(), UnitKind::Mass | UnitKind::Volume) {
+        return Err(FeedMeError::InvalidQuantity(format!(
+            "'{}' must be a weight or volume",
+            equals
+        )));
+    }
+
+    let unit = unit.to_string();
+    with_busy_retry(|| upsert_custom_unit(pool, &name, ingredient_id, parsed.amount, &unit)).await
+}
+
+async fn upsert_custom_unit(
+    pool: &SqlitePool,
+    name: &str,
+    ingredient_id: Option<i64>,
+    amount: f64,
+    unit: &str,
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let replaced: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM custom_units WHERE name = ? AND ingredient_id IS ?")
+            .bind(name)
+            .bind(ingredient_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if let Some(replaced) = replaced {
+        delete_audited(
+            &mut tx,
+            "custom_unit",
+            "custom_units",
+            replaced,
+            CUSTOM_UNIT_AUDIT_COLUMNS,
+        )
+        .await?;
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO custom_units (name, ingredient_id, amount, unit) VALUES (?, ?, ?, ?)",
+    )
+    .bind(name)
+    .bind(ingredient_id)
+    .bind(amount)
+    .bind(unit)
+    .execute(&mut *tx)
+    .await
+    .on_duplicate(|| FeedMeError::DuplicateCustomUnit(name.to_string()))?
+    .last_insert_rowid();
+    record_create(
+        &mut tx,
+        "custom_unit",
+        "custom_units",
+        id,
+        CUSTOM_UNIT_AUDIT_COLUMNS,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Every custom unit, ordered by name with global units first
+pub async fn list_custom_units(pool: &SqlitePool) -> Result<Vec<CustomUnitRecord>> {
+    let units = sqlx::query_as::<_, CustomUnitRecord>(
+        r#"
+        SELECT id, name, ingredient_id, amount, unit
+        FROM custom_units
+        ORDER BY name, ingredient_id IS NOT NULL, ingredient_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(units)
+}
+
+/// Delete a custom unit
+/// Returns whether it existed
+pub async fn delete_custom_unit(pool: &SqlitePool, id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_custom_unit_row(pool, id)).await
+}
+
+async fn delete_custom_unit_row(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let deleted = delete_audited(
+        &mut tx,
+        "custom_unit",
+        "custom_units",
+        id,
+        CUSTOM_UNIT_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(deleted)
+}
+
+/// Units that apply to one ingredient, its own custom units ahead of global ones
+pub(crate) fn ingredient_units(
+    custom_units: &[CustomUnitRecord],
+    ingredient_id: i64,
+    grams_per_ml: Option<f64>,
+) -> IngredientUnits {
+    let scoped = custom_units
+        .iter()
+        .filter(|unit| unit.ingredient_id == Some(ingredient_id));
+    let global = custom_units
+        .iter()
+        .filter(|unit| unit.ingredient_id.is_none());
+
+    IngredientUnits {
+        grams_per_ml,
+        custom: scoped
+            .chain(global)
+            .map(CustomUnitRecord::to_custom_unit)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{create_ingredient, create_recipe, generate_shopping_list};
+    use crate::models::api::{Recipe, RecipeIngredient, Visibility};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_custom_unit_crud(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let tomatoes = create_ingredient(&pool, "tomatoes").await.unwrap();
+
+        let stick = define_custom_unit(&pool, "Stick", None, "113 g")
+            .await
+            .unwrap();
+        define_custom_unit(&pool, "can", Some(tomatoes), "400 g")
+            .await
+            .unwrap();
+        // Redefining replaces the old definition
+        define_custom_unit(&pool, "can", Some(tomatoes), "14 oz")
+            .await
+            .unwrap();
+
+        let units = list_custom_units(&pool).await.unwrap();
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0].name, "can");
+        assert_eq!(units[0].ingredient_id, Some(tomatoes));
+        assert_eq!((units[0].amount, units[0].unit.as_str()), (14.0, "oz"));
+        assert_eq!(units[1].name, "stick");
+
+        assert!(delete_custom_unit(&pool, stick).await.unwrap());
+        assert!(!delete_custom_unit(&pool, stick).await.unwrap());
+        assert_eq!(list_custom_units(&pool).await.unwrap().len(), 1);
+    }
+
+    #[rstest]
+    #[case("a handful")]
+    #[case("2 pinches")]
+    #[case("0 g")]
+    #[tokio::test]
+    async fn test_define_custom_unit_invalid(#[future] test_db: SqlitePool, #[case] equals: &str) {
+        let pool = test_db.await;
+
+        assert!(matches!(
+            define_custom_unit(&pool, "scoop", None, equals).await,
+            Err(FeedMeError::InvalidQuantity(_))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_define_custom_unit_needs_name(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        assert!(matches!(
+            define_custom_unit(&pool, " ", None, "1 cup").await,
+            Err(FeedMeError::Validation(_))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_ingredient_units_prefers_scoped(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let tomatoes = create_ingredient(&pool, "tomatoes").await.unwrap();
+        let beans = create_ingredient(&pool, "beans").await.unwrap();
+        define_custom_unit(&pool, "can", None, "425 g")
+            .await
+            .unwrap();
+        define_custom_unit(&pool, "can", Some(tomatoes), "400 g")
+            .await
+            .unwrap();
+        let records = list_custom_units(&pool).await.unwrap();
+
+        let units = ingredient_units(&records, tomatoes, None);
+        assert_eq!(units.convert(2.0, Some("cans"), Some("g")), Some(800.0));
+
+        let units = ingredient_units(&records, beans, None);
+        assert_eq!(units.convert(1.0, Some("can"), Some("g")), Some(425.0));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_shopping_list_sums_custom_units(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let tomatoes = create_ingredient(&pool, "tomatoes").await.unwrap();
+        define_custom_unit(&pool, "can", Some(tomatoes), "400 g")
+            .await
+            .unwrap();
+
+        let mut ids = Vec::new();
+        for (name, quantity) in [("Chili", "1 can"), ("Sauce", "200 g")] {
+            let recipe = Recipe {
+                id: 0,
+                name: name.to_string(),
+                instructions: None,
+                servings: None,
+                ingredients: vec![RecipeIngredient {
+                    ingredient_id: tomatoes,
+                    ingredient_name: "tomatoes".to_string(),
+                    quantity_unit: quantity.to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                }],
+                created_at: String::new(),
+                timers: Vec::new(),
+                journal: Vec::new(),
+                tags: Vec::new(),
+                rating: None,
+                equipment: Vec::new(),
+                visibility: Visibility::default(),
+                source_url: None,
+                source_name: None,
+                nutrition: None,
+            };
+            ids.push(create_recipe(&pool, &recipe).await.unwrap());
+        }
+
+        let list = generate_shopping_list(&pool, &ids).await.unwrap();
+
+        assert_eq!(list[0].combined_quantity, "1 1/2 cans");
+    }
+}
@@ -0,0 +1,177 @@
+use sqlx::SqlitePool;
+
+use super::retry::with_busy_retry;
+use crate::error::Result;
+use crate::models::api::RecipeDraft;
+
+/// Save a recipe being entered, replacing draft `draft_id` if given (or if
+/// it has since been discarded, starting a new one); returns the draft ID
+pub async fn save_draft(
+    pool: &SqlitePool,
+    draft_id: Option<i64>,
+    draft: &RecipeDraft,
+) -> Result<i64> {
+    let data = serde_json::to_string(draft)?;
+
+    with_busy_retry(|| upsert_draft(pool, draft_id, &data)).await
+}
+
+async fn upsert_draft(pool: &SqlitePool, draft_id: Option<i64>, data: &str) -> Result<i64> {
+    let id = sqlx::query_scalar(
+        "INSERT INTO recipe_drafts (id, data) VALUES (?, ?) ON CONFLICT(id) DO UPDATE SET data = excluded.data, updated_at = CURRENT_TIMESTAMP RETURNING id",
+    )
+    .bind(draft_id)
+    .bind(data)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// The most recently saved draft with its ID, if any
+/// Drafts that no longer read (e.g. saved by a build with a different
+/// `RecipeDraft`) are discarded with a warning rather than failing
+pub async fn latest_draft(pool: &SqlitePool) -> Result<Option<(i64, RecipeDraft)>> {
+    let rows: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, data FROM recipe_drafts ORDER BY updated_at DESC, id DESC")
+            .fetch_all(pool)
+            .await?;
+
+    for (id, data) in rows {
+        match serde_json::from_str(&data) {
+            Ok(draft) => return Ok(Some((id, draft))),
+            Err(err) => {
+                tracing::warn!(draft_id = id, error = %err, "discarding unreadable draft");
+                discard_draft(pool, id).await?;
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Throw a draft away, once its recipe is saved or it isn't wanted
+/// Returns false if there was no such draft
+pub async fn discard_draft(pool: &SqlitePool, draft_id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_draft(pool, draft_id)).await
+}
+
+async fn delete_draft(pool: &SqlitePool, draft_id: i64) -> Result<bool> {
+    let removed = sqlx::query("DELETE FROM recipe_drafts WHERE id = ?")
+        .bind(draft_id)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+    Ok(removed > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::{DraftIngredient, DraftStep};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_drafts(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        assert_eq!(latest_draft(&pool).await.unwrap(), None);
+
+        let mut draft = RecipeDraft {
+            step: DraftStep::Ingredients,
+            name: "Brine".to_string(),
+            ..Default::default()
+        };
+        let id = save_draft(&pool, None, &draft).await.unwrap();
+        draft.ingredients.push(DraftIngredient {
+            name: "salt".to_string(),
+            quantity_unit: "1 cup".to_string(),
+            notes: String::new(),
+        });
+        assert_eq!(save_draft(&pool, Some(id), &draft).await.unwrap(), id);
+        assert_eq!(
+            latest_draft(&pool).await.unwrap(),
+            Some((id, draft.clone()))
+        );
+
+        assert!(discard_draft(&pool, id).await.unwrap());
+        assert!(!discard_draft(&pool, id).await.unwrap());
+        assert_eq!(latest_draft(&pool).await.unwrap(), None);
+
+        // A discarded draft's ID is taken up again rather than failing
+        assert_eq!(save_draft(&pool, Some(id), &draft).await.unwrap(), id);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_unreadable_draft_is_discarded(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let draft = RecipeDraft {
+            name: "Brine".to_string(),
+            ..Default::default()
+        };
+        let id = save_draft(&pool, None, &draft).await.unwrap();
+        // Saved later by a build with another draft format
+        sqlx::query("INSERT INTO recipe_drafts (data) VALUES ('{\"step\": \"plating\"}')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(latest_draft(&pool).await.unwrap(), Some((id, draft)));
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recipe_drafts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_save_draft_retries_when_locked() {
+        use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+        use sqlx::{Connection, SqliteConnection};
+        use std::str::FromStr;
+        use std::time::Duration;
+
+        let path =
+            std::env::temp_dir().join(format!("feedme-draft-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // No SQLite-level waiting, so only a retry can rescue the write
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))
+            .unwrap()
+            .create_if_missing(true)
+            .busy_timeout(Duration::ZERO);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options.clone())
+            .await
+            .expect("Failed to open pool");
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let mut other = SqliteConnection::connect_with(&options).await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut other)
+            .await
+            .unwrap();
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            sqlx::query("COMMIT").execute(&mut other).await.unwrap();
+            other.close().await.unwrap();
+        });
+
+        let draft = RecipeDraft {
+            name: "Brine".to_string(),
+            ..Default::default()
+        };
+        let id = save_draft(&pool, None, &draft)
+            .await
+            .expect("Save should succeed after retrying");
+        assert_eq!(latest_draft(&pool).await.unwrap(), Some((id, draft)));
+
+        release.await.unwrap();
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+}
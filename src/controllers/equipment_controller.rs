@@ -0,0 +1,238 @@
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::audit_controller::record_change;
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, Result};
+use crate::models::{AuditAction, RecipeRecord};
+use crate::validation::Validator;
+
+/// Replace the equipment a recipe needs, creating unknown items
+/// Names are trimmed and matched case-insensitively; an empty list clears it
+pub async fn set_recipe_equipment(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    equipment: &[String],
+) -> Result<()> {
+    let mut validator = Validator::new();
+    let equipment: Vec<&str> = equipment
+        .iter()
+        .enumerate()
+        .map(|(i, item)| validator.name(format!("equipment[{}]", i), item))
+        .collect();
+    validator.finish()?;
+
+    with_busy_retry(|| replace_recipe_equipment(pool, recipe_id, &equipment)).await
+}
+
+async fn replace_recipe_equipment(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    equipment: &[&str],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let recipe: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM recipes WHERE id = ? AND deleted_at IS NULL")
+            .bind(recipe_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if recipe.is_none() {
+        return Err(FeedMeError::RecipeNotFound(recipe_id));
+    }
+
+    let old = recipe_equipment_in(&mut tx, recipe_id).await?;
+    sqlx::query("DELETE FROM recipe_equipment WHERE recipe_id = ?")
+        .bind(recipe_id)
+        .execute(&mut *tx)
+        .await?;
+    for item in equipment {
+        equip_recipe_in(&mut tx, recipe_id, item).await?;
+    }
+    let new = recipe_equipment_in(&mut tx, recipe_id).await?;
+    if new != old {
+        record_change(
+            &mut tx,
+            "recipe",
+            recipe_id,
+            AuditAction::Update,
+            Some(&json!({ "equipment": old })),
+            Some(&json!({ "equipment": new })),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Link an already validated item to a recipe inside the caller's
+/// transaction; linking twice is a no-op
+pub(crate) async fn equip_recipe_in(
+    conn: &mut SqliteConnection,
+    recipe_id: i64,
+    item: &str,
+) -> Result<()> {
+    sqlx::query("INSERT OR IGNORE INTO equipment (name) VALUES (?)")
+        .bind(item)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        "INSERT OR IGNORE INTO recipe_equipment (recipe_id, equipment_id) SELECT ?, id FROM equipment WHERE name = ?",
+    )
+    .bind(recipe_id)
+    .bind(item)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Equipment a recipe needs, ordered by name
+pub async fn get_recipe_equipment(pool: &SqlitePool, recipe_id: i64) -> Result<Vec<String>> {
+    recipe_equipment_in(&mut *pool.acquire().await?, recipe_id).await
+}
+
+/// Equipment a recipe needs, read inside the caller's transaction
+async fn recipe_equipment_in(conn: &mut SqliteConnection, recipe_id: i64) -> Result<Vec<String>> {
+    let equipment = sqlx::query_scalar(
+        r#"
+        SELECT e.name
+        FROM recipe_equipment re
+        JOIN equipment e ON re.equipment_id = e.id
+        WHERE re.recipe_id = ?
+        ORDER BY e.name
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(equipment)
+}
+
+/// Every item some recipe needs, ordered by name
+pub async fn list_equipment(pool: &SqlitePool) -> Result<Vec<String>> {
+    let equipment = sqlx::query_scalar(
+        "SELECT name FROM equipment WHERE id IN (SELECT equipment_id FROM recipe_equipment) ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(equipment)
+}
+
+/// Recipes needing an item (ignoring case), ordered by name
+/// An unknown item has no recipes
+pub async fn get_recipes_by_equipment(pool: &SqlitePool, item: &str) -> Result<Vec<RecipeRecord>> {
+    let recipes = sqlx::query_as::<_, RecipeRecord>(
+        r#"
+        SELECT r.id, r.name, r.instructions, r.servings, r.source_url, r.source_name, r.created_at
+        FROM recipes r
+        JOIN recipe_equipment re ON re.recipe_id = r.id
+        JOIN equipment e ON re.equipment_id = e.id
+        WHERE e.name = ? AND r.deleted_at IS NULL
+        ORDER BY r.name, r.id
+        "#,
+    )
+    .bind(item.trim())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(recipes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::list_recipes_with;
+    use crate::models::api::RecipeFilter;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    async fn insert_recipe(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+            .bind(name)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    fn items(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_set_recipe_equipment(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let bread = insert_recipe(&pool, "Bread").await;
+        let stew = insert_recipe(&pool, "Stew").await;
+
+        set_recipe_equipment(&pool, bread, &items(&["Stand mixer", " oven ", "OVEN"]))
+            .await
+            .unwrap();
+        set_recipe_equipment(&pool, stew, &items(&["Dutch oven", "Oven"]))
+            .await
+            .unwrap();
+        assert_eq!(
+            get_recipe_equipment(&pool, bread).await.unwrap(),
+            vec!["oven", "Stand mixer"]
+        );
+        assert_eq!(
+            list_equipment(&pool).await.unwrap(),
+            vec!["Dutch oven", "oven", "Stand mixer"]
+        );
+        let baked: Vec<String> = get_recipes_by_equipment(&pool, "Oven")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|recipe| recipe.name)
+            .collect();
+        assert_eq!(baked, vec!["Bread", "Stew"]);
+
+        let salad = insert_recipe(&pool, "Salad").await;
+        let no_oven: Vec<i64> = list_recipes_with(
+            &pool,
+            &RecipeFilter {
+                without_equipment: items(&["OVEN"]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|recipe| recipe.id)
+        .collect();
+        assert_eq!(no_oven, vec![salad]);
+
+        // Replacing drops items no longer used from the listing
+        set_recipe_equipment(&pool, bread, &items(&["oven"]))
+            .await
+            .unwrap();
+        set_recipe_equipment(&pool, stew, &[]).await.unwrap();
+        assert_eq!(list_equipment(&pool).await.unwrap(), vec!["oven"]);
+        assert!(get_recipe_equipment(&pool, stew).await.unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_set_recipe_equipment_errors(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let id = insert_recipe(&pool, "Bread").await;
+
+        assert!(matches!(
+            set_recipe_equipment(&pool, 999, &items(&["oven"])).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+        let Err(FeedMeError::Validation(errors)) =
+            set_recipe_equipment(&pool, id, &items(&["oven", " "])).await
+        else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "equipment[1]");
+        assert!(list_equipment(&pool).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,712 @@
+use futures::{Stream, TryStreamExt, stream};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use super::cook_log_controller::all_recipe_notes;
+use super::ingredient_controller::resolve_ingredients_in;
+use super::recipe_controller::{
+    get_recipe_with, hydrate_recipes, insert_recipe_in, list_recipes_with, set_recipe_metadata_in,
+    validate_recipe,
+};
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, Result};
+use crate::models::RecipeRecord;
+use crate::models::api::{
+    Category, Difficulty, Recipe, RecipeFilter, RecipeIngredient, RecipeMetadata, RecipeOptions,
+    Visibility,
+};
+use crate::quantity::QuantityFormat;
+
+/// Stream every recipe row (without ingredients) ordered by id
+/// Rows are read from the database as the stream is polled rather than
+/// collected up front
+pub fn stream_recipe_records(pool: &SqlitePool) -> impl Stream<Item = Result<RecipeRecord>> + '_ {
+    sqlx::query_as::<_, RecipeRecord>(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE deleted_at IS NULL ORDER BY id",
+    )
+    .fetch(pool)
+    .map_err(Into::into)
+}
+
+/// Recipes read at a time by `stream_recipes`
+const STREAM_BATCH_SIZE: i64 = 100;
+
+/// Stream fully hydrated recipes ordered by id, for exporters and sync jobs
+/// over large databases
+/// Each recipe is as `get_recipe` returns it. Recipes are read
+/// `STREAM_BATCH_SIZE` at a time as the stream is polled, so only one batch
+/// is held in memory and no connection is held between batches
+pub fn stream_recipes(pool: &SqlitePool) -> impl Stream<Item = Result<Recipe>> + '_ {
+    stream::try_unfold(Some(0), move |after| recipe_batch(pool, after))
+        .map_ok(|recipes| stream::iter(recipes.into_iter().map(Ok::<_, FeedMeError>)))
+        .try_flatten()
+}
+
+/// The batch of recipes with IDs above `after`, and the ID the next batch
+/// starts after (None once the last batch is read)
+async fn recipe_batch(
+    pool: &SqlitePool,
+    after: Option<i64>,
+) -> Result<Option<(Vec<Recipe>, Option<i64>)>> {
+    let Some(after) = after else {
+        return Ok(None);
+    };
+    let records = sqlx::query_as::<_, RecipeRecord>(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE id > ? AND deleted_at IS NULL ORDER BY id LIMIT ?",
+    )
+    .bind(after)
+    .bind(STREAM_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+    let Some(last) = records.last() else {
+        return Ok(None);
+    };
+
+    let next = (records.len() as i64 == STREAM_BATCH_SIZE).then_some(last.id);
+    Ok(Some((hydrate_recipes(pool, records).await?, next)))
+}
+
+/// A line of an export: the recipe, carrying its tags, with its times,
+/// category and difficulty alongside
+#[derive(Serialize)]
+struct ExportedRecipe<'a> {
+    #[serde(flatten)]
+    recipe: &'a Recipe,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prep_minutes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cook_minutes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<Category>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    difficulty: Option<Difficulty>,
+}
+
+/// A line of an export as read back by `import_all`
+/// IDs, timers and the journal are left out: IDs belong to the exporting
+/// database and the rest is derived or tied to its cook log
+#[derive(Deserialize)]
+struct ImportedRecipe {
+    name: String,
+    instructions: Option<String>,
+    servings: Option<i64>,
+    #[serde(default)]
+    source_url: Option<String>,
+    #[serde(default)]
+    source_name: Option<String>,
+    #[serde(default)]
+    ingredients: Vec<ImportedIngredient>,
+    #[serde(default)]
+    visibility: Visibility,
+    #[serde(flatten)]
+    metadata: RecipeMetadata,
+}
+
+#[derive(Deserialize)]
+struct ImportedIngredient {
+    ingredient_name: String,
+    quantity_unit: String,
+    notes: Option<String>,
+}
+
+/// Write every recipe as one JSON object per line (JSON Lines), with its
+/// tags and times
+/// Recipes are streamed from the database, so memory use stays flat
+/// regardless of catalog size (apart from the journal notes and tags)
+/// Returns the number of recipes written
+pub async fn export_all<W: Write>(pool: &SqlitePool, writer: W) -> Result<usize> {
+    export_all_with(pool, writer, QuantityFormat::default()).await
+}
+
+/// Like `export_all`, rewriting ingredient quantities in `format`
+/// Each recipe carries its journal; the notes of every recipe are read in
+/// one query before streaming starts
+pub async fn export_all_with<W: Write>(
+    pool: &SqlitePool,
+    mut writer: W,
+    format: QuantityFormat,
+) -> Result<usize> {
+    let mut journals = all_recipe_notes(pool).await?;
+    let mut metadata = all_recipe_metadata(pool).await?;
+    let mut recipes = std::pin::pin!(stream_recipes(pool));
+    let mut count = 0;
+
+    while let Some(mut recipe) = recipes.try_next().await? {
+        recipe.journal = journals.remove(&recipe.id).unwrap_or_default();
+        recipe.localize_quantities(format);
+        let metadata = metadata.remove(&recipe.id).unwrap_or_default();
+        recipe.tags = metadata.tags;
+        let exported = ExportedRecipe {
+            recipe: &recipe,
+            prep_minutes: metadata.prep_minutes,
+            cook_minutes: metadata.cook_minutes,
+            category: metadata.category,
+            difficulty: metadata.difficulty,
+        };
+        serde_json::to_writer(&mut writer, &exported)?;
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Every recipe but the private ones, ordered by name, for publishing the
+/// cookbook (see `site::render`)
+/// Recipes are fully hydrated like `get_recipe_with`, with quantities and
+/// temperatures in `format`
+pub async fn shared_recipes(pool: &SqlitePool, format: QuantityFormat) -> Result<Vec<Recipe>> {
+    let filter = RecipeFilter {
+        audience: Some(Visibility::Household),
+        ..Default::default()
+    };
+    let mut records = list_recipes_with(pool, &filter).await?;
+    records.sort_by_key(|record| record.name.to_lowercase());
+
+    let options = RecipeOptions {
+        quantities: format,
+        ..Default::default()
+    };
+    let mut recipes = Vec::with_capacity(records.len());
+    for record in records {
+        recipes.push(get_recipe_with(pool, record.id, options).await?);
+    }
+
+    Ok(recipes)
+}
+
+/// Every recipe's tags, times, category and difficulty by recipe ID, read
+/// before streaming
+async fn all_recipe_metadata(pool: &SqlitePool) -> Result<HashMap<i64, RecipeMetadata>> {
+    let mut metadata: HashMap<i64, RecipeMetadata> = sqlx::query_as::<
+        _,
+        (i64, Option<i64>, Option<i64>, Option<String>, Option<String>),
+    >(
+        "SELECT id, prep_minutes, cook_minutes, category, difficulty FROM recipes WHERE deleted_at IS NULL",
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|(id, prep_minutes, cook_minutes, category, difficulty)| {
+        let details = RecipeMetadata {
+            tags: Vec::new(),
+            prep_minutes,
+            cook_minutes,
+            category: category.as_deref().and_then(Category::parse),
+            difficulty: difficulty.as_deref().and_then(Difficulty::parse),
+        };
+            (id, details)
+        })
+        .collect();
+
+    let tags: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT rt.recipe_id, t.name
+        FROM recipe_tags rt
+        JOIN tags t ON rt.tag_id = t.id
+        ORDER BY rt.recipe_id, t.name
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    for (recipe_id, tag) in tags {
+        metadata.entry(recipe_id).or_default().tags.push(tag);
+    }
+
+    Ok(metadata)
+}
+
+/// Add the recipes from an `export_all` file, with their tags and times
+/// Ingredients are matched by name, creating the ones that don't exist.
+/// Every line is parsed and validated before anything is written, and the
+/// recipes are added in one transaction, so a bad file changes nothing.
+/// Returns the number of recipes added
+pub async fn import_all<R: BufRead>(pool: &SqlitePool, reader: R) -> Result<usize> {
+    let mut recipes = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let imported: ImportedRecipe = serde_json::from_str(&line)?;
+        let recipe = Recipe {
+            id: 0,
+            name: imported.name,
+            instructions: imported.instructions,
+            servings: imported.servings,
+            ingredients: imported
+                .ingredients
+                .into_iter()
+                .map(|ingredient| RecipeIngredient {
+                    ingredient_id: 0,
+                    ingredient_name: ingredient.ingredient_name.trim().to_string(),
+                    quantity_unit: ingredient.quantity_unit,
+                    notes: ingredient.notes,
+                    steps: Vec::new(),
+                })
+                .collect(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: imported.visibility,
+            source_url: imported.source_url,
+            source_name: imported.source_name,
+            nutrition: None,
+        };
+        let recipe = validate_recipe(&recipe).map_err(|err| match err {
+            FeedMeError::Validation(errors) => FeedMeError::Validation(
+                errors
+                    .into_iter()
+                    .map(|mut error| {
+                        error.field = format!("line {}: {}", i + 1, error.field);
+                        error
+                    })
+                    .collect(),
+            ),
+            err => err,
+        })?;
+        recipes.push((recipe, imported.metadata));
+    }
+
+    with_busy_retry(|| insert_imported_recipes(pool, &recipes)).await?;
+
+    Ok(recipes.len())
+}
+
+async fn insert_imported_recipes(
+    pool: &SqlitePool,
+    recipes: &[(Recipe, RecipeMetadata)],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let mut ingredient_ids = HashMap::new();
+    for (recipe, metadata) in recipes {
+        let mut recipe = recipe.clone();
+        resolve_ingredients_in(&mut tx, &mut recipe.ingredients, &mut ingredient_ids).await?;
+        let recipe_id = insert_recipe_in(&mut tx, &recipe).await?;
+        set_recipe_metadata_in(&mut tx, recipe_id, metadata).await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Add one recipe whose ingredients are given by name rather than ID, such
+/// as one read from a web page
+/// Ingredients with ID 0 are matched by their trimmed name, creating the
+/// ones that don't exist, in the same transaction as the recipe (the rules
+/// `create_recipe_with` and `import_pack` follow too). Returns the new
+/// recipe's ID
+pub async fn import_recipe(pool: &SqlitePool, recipe: &Recipe) -> Result<i64> {
+    let recipe = validate_recipe(recipe)?;
+    // Pages are imported several at once, so the transaction can find the
+    // file locked when it turns from reading ingredients to writing
+    with_busy_retry(|| insert_imported_recipe(pool, &recipe)).await
+}
+
+async fn insert_imported_recipe(pool: &SqlitePool, recipe: &Recipe) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let mut recipe = recipe.clone();
+    resolve_ingredients_in(&mut tx, &mut recipe.ingredients, &mut HashMap::new()).await?;
+    let recipe_id = insert_recipe_in(&mut tx, &recipe).await?;
+    tx.commit().await?;
+
+    Ok(recipe_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_fixtures::test_db;
+    use crate::seed::seed_demo_data;
+    use crate::units::UnitSystem;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_stream_recipe_records(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.expect("Failed to seed");
+
+        let records: Vec<RecipeRecord> = stream_recipe_records(&pool)
+            .try_collect()
+            .await
+            .expect("Failed to stream");
+
+        assert_eq!(records.iter().map(|r| r.id).collect::<Vec<_>>(), ids);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_stream_recipes_matches_get_recipe(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        // A recipe with no ingredients ahead of the others in the stream
+        sqlx::query("INSERT INTO recipes (name) VALUES ('Water')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let ids = seed_demo_data(&pool).await.expect("Failed to seed");
+        crate::controllers::add_recipe_tag(&pool, ids[0], "breakfast")
+            .await
+            .unwrap();
+        crate::controllers::set_recipe_equipment(&pool, ids[0], &["Griddle".to_string()])
+            .await
+            .unwrap();
+        crate::controllers::rate_recipe(&pool, ids[0], 4)
+            .await
+            .unwrap();
+        crate::controllers::set_step_ingredients(&pool, ids[0], 0, &[0])
+            .await
+            .unwrap();
+
+        let streamed: Vec<Recipe> = stream_recipes(&pool)
+            .try_collect()
+            .await
+            .expect("Failed to stream");
+
+        assert_eq!(streamed.len(), 6);
+        for recipe in &streamed {
+            let fetched = crate::controllers::get_recipe(&pool, recipe.id)
+                .await
+                .unwrap();
+            assert_eq!(
+                serde_json::to_value(recipe).unwrap(),
+                serde_json::to_value(&fetched).unwrap()
+            );
+        }
+        assert_eq!(streamed[1].equipment, vec!["Griddle"]);
+        assert_eq!(streamed[1].ingredients[0].steps, vec![0]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_stream_recipes_reads_in_batches(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        for i in 0..STREAM_BATCH_SIZE * 2 + 1 {
+            sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+                .bind(format!("Recipe {}", i))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("UPDATE recipes SET deleted_at = CURRENT_TIMESTAMP WHERE id = 7")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let ids: Vec<i64> = stream_recipes(&pool)
+            .map_ok(|recipe| recipe.id)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(ids.len() as i64, STREAM_BATCH_SIZE * 2);
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(!ids.contains(&7));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_export_all_writes_json_lines(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        seed_demo_data(&pool).await.expect("Failed to seed");
+
+        let mut output = Vec::new();
+        let count = export_all(&pool, &mut output)
+            .await
+            .expect("Failed to export");
+
+        let lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+        assert_eq!(lines.len(), count);
+
+        let first: serde_json::Value = serde_json::from_str(lines[0]).expect("Invalid JSON");
+        assert_eq!(first["name"], "Pancakes");
+        assert_eq!(first["ingredients"].as_array().unwrap().len(), 7);
+        assert!(first.get("journal").is_none());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_export_includes_journal_and_timers(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.expect("Failed to seed");
+        let cooked_on = chrono::NaiveDate::from_ymd_opt(2026, 10, 3).unwrap();
+        let cook = crate::controllers::log_cook(&pool, ids[1], cooked_on, None)
+            .await
+            .unwrap();
+        let note = crate::models::api::RecipeNote {
+            id: 0,
+            cook_log_id: cook,
+            noted_on: cooked_on,
+            text: "use less salt next time".to_string(),
+            rating_delta: None,
+            modifications: None,
+        };
+        crate::controllers::add_recipe_note(&pool, &note)
+            .await
+            .unwrap();
+
+        let mut output = Vec::new();
+        export_all(&pool, &mut output).await.unwrap();
+
+        let recipes: Vec<serde_json::Value> = std::str::from_utf8(&output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert!(recipes[0].get("journal").is_none());
+        assert_eq!(recipes[2]["timers"][0]["seconds"], 1200);
+        assert_eq!(recipes[1]["journal"][0]["text"], "use less salt next time");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_export_import_round_trip(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.expect("Failed to seed");
+        crate::controllers::add_recipe_tag(&pool, ids[0], "breakfast")
+            .await
+            .unwrap();
+        crate::controllers::add_recipe_tag(&pool, ids[0], "brunch")
+            .await
+            .unwrap();
+        crate::controllers::set_recipe_times(&pool, ids[0], Some(10), None)
+            .await
+            .unwrap();
+        crate::controllers::set_recipe_category(&pool, ids[0], Some(Category::Breakfast))
+            .await
+            .unwrap();
+        crate::controllers::set_recipe_difficulty(&pool, ids[0], Some(Difficulty::Medium))
+            .await
+            .unwrap();
+        let mut sourced = crate::controllers::get_recipe(&pool, ids[1]).await.unwrap();
+        sourced.source_url = Some("https://example.com/recipe".to_string());
+        sourced.source_name = Some("Example Kitchen".to_string());
+        crate::controllers::update_recipe(&pool, ids[1], &sourced)
+            .await
+            .unwrap();
+        crate::controllers::set_recipe_visibility(&pool, ids[1], Visibility::Private)
+            .await
+            .unwrap();
+
+        let mut output = Vec::new();
+        let count = export_all(&pool, &mut output).await.unwrap();
+        let first: serde_json::Value = serde_json::from_str(
+            std::str::from_utf8(&output)
+                .unwrap()
+                .lines()
+                .next()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(first["tags"], serde_json::json!(["breakfast", "brunch"]));
+        assert_eq!(first["prep_minutes"], 10);
+        assert!(first.get("cook_minutes").is_none());
+        assert_eq!(first["category"], "breakfast");
+        assert_eq!(first["difficulty"], "medium");
+
+        let theirs = crate::db::connect_in_memory().await.unwrap();
+        assert_eq!(import_all(&theirs, output.as_slice()).await.unwrap(), count);
+
+        let originals: Vec<Recipe> = stream_recipes(&pool).try_collect().await.unwrap();
+        let imported: Vec<Recipe> = stream_recipes(&theirs).try_collect().await.unwrap();
+        for (original, copy) in originals.iter().zip(&imported) {
+            assert!(crate::diff::diff_recipes(original, copy).is_empty());
+            assert_eq!(
+                (
+                    &original.source_url,
+                    &original.source_name,
+                    original.visibility
+                ),
+                (&copy.source_url, &copy.source_name, copy.visibility)
+            );
+        }
+        assert_eq!(imported[1].visibility, Visibility::Private);
+        assert_eq!(imported[1].source_name.as_deref(), Some("Example Kitchen"));
+        let mut metadata = all_recipe_metadata(&theirs).await.unwrap();
+        assert_eq!(
+            metadata.remove(&imported[0].id).unwrap(),
+            RecipeMetadata {
+                tags: vec!["breakfast".to_string(), "brunch".to_string()],
+                prep_minutes: Some(10),
+                cook_minutes: None,
+                category: Some(Category::Breakfast),
+                difficulty: Some(Difficulty::Medium),
+            }
+        );
+        assert_eq!(
+            metadata.remove(&imported[1].id).unwrap(),
+            RecipeMetadata::default()
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_import_bad_line_writes_nothing(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let file = concat!(
+            r#"{"name": "Toast", "ingredients": [{"ingredient_name": "bread", "quantity_unit": "2 slices"}]}"#,
+            "\n\n",
+            r#"{"name": " ", "ingredients": []}"#,
+            "\n",
+        );
+
+        let Err(FeedMeError::Validation(errors)) = import_all(&pool, file.as_bytes()).await else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "line 3: name");
+        assert!(
+            crate::controllers::list_recipes(&pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_export_all_with_units(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        seed_demo_data(&pool).await.expect("Failed to seed");
+
+        let mut output = Vec::new();
+        let format = QuantityFormat {
+            system: Some(UnitSystem::Metric),
+            style: None,
+        };
+        export_all_with(&pool, &mut output, format)
+            .await
+            .expect("Failed to export");
+
+        let first: serde_json::Value = serde_json::from_str(
+            std::str::from_utf8(&output)
+                .unwrap()
+                .lines()
+                .next()
+                .unwrap(),
+        )
+        .expect("Invalid JSON");
+        let quantities: Vec<&str> = first["ingredients"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["quantity_unit"].as_str().unwrap())
+            .collect();
+        assert!(!quantities.iter().any(|q| q.contains("cup")));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_shared_recipes(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = seed_demo_data(&pool).await.expect("Failed to seed");
+        crate::controllers::set_recipe_visibility(&pool, ids[1], Visibility::Private)
+            .await
+            .unwrap();
+
+        let recipes = shared_recipes(&pool, QuantityFormat::default())
+            .await
+            .expect("Failed to gather recipes");
+        assert_eq!(recipes.len(), ids.len() - 1);
+        assert!(recipes.iter().all(|recipe| recipe.id != ids[1]));
+        assert!(
+            recipes
+                .windows(2)
+                .all(|pair| pair[0].name.to_lowercase() <= pair[1].name.to_lowercase())
+        );
+        let pancakes = get_recipe_with(&pool, ids[0], RecipeOptions::default())
+            .await
+            .unwrap();
+        let shared = recipes.iter().find(|recipe| recipe.id == ids[0]).unwrap();
+        assert_eq!(shared.ingredients.len(), pancakes.ingredients.len());
+        assert_eq!(shared.tags, pancakes.tags);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_export_all_empty(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let mut output = Vec::new();
+        let count = export_all(&pool, &mut output)
+            .await
+            .expect("Failed to export");
+
+        assert_eq!(count, 0);
+        assert!(output.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_recipe_retries_when_locked() {
+        use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+        use sqlx::{Connection, SqliteConnection};
+        use std::str::FromStr;
+        use std::time::Duration;
+
+        let path =
+            std::env::temp_dir().join(format!("feedme-import-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // No SQLite-level waiting, so only a retry can rescue the write
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))
+            .unwrap()
+            .create_if_missing(true)
+            .busy_timeout(Duration::ZERO);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options.clone())
+            .await
+            .expect("Failed to open pool");
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // Another writer holds the lock while the ingredients are read
+        let mut other = SqliteConnection::connect_with(&options).await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut other)
+            .await
+            .unwrap();
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            sqlx::query("COMMIT").execute(&mut other).await.unwrap();
+            other.close().await.unwrap();
+        });
+
+        let recipe = Recipe {
+            id: 0,
+            name: "Toast".to_string(),
+            instructions: None,
+            servings: None,
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: 0,
+                ingredient_name: "bread".to_string(),
+                quantity_unit: "2 slices".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let recipe_id = import_recipe(&pool, &recipe)
+            .await
+            .expect("Import should succeed after retrying");
+        assert!(recipe_id > 0);
+
+        release.await.unwrap();
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,215 @@
+use sqlx::{FromRow, Row, SqlitePool};
+
+use super::audit_controller::{delete_audited, record_create};
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, Result};
+use crate::models::RecipeImageRecord;
+use crate::validation::Validator;
+
+/// Columns recorded in an image's audit entries; the bytes are left out
+const IMAGE_AUDIT_COLUMNS: &[&str] = &["recipe_id", "file_name", "content_type"];
+
+/// Attach an image file to a recipe, returning the image ID
+/// The bytes are stored in the database; the type is told from them (PNG,
+/// JPEG, GIF or WebP), and `file_name` is kept for saving it back out
+pub async fn attach_recipe_image(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    file_name: &str,
+    data: &[u8],
+) -> Result<i64> {
+    let mut validator = Validator::new();
+    let file_name = validator.name("file_name", file_name);
+    let content_type = validator.image("data", data);
+    validator.finish()?;
+
+    with_busy_retry(|| insert_recipe_image(pool, recipe_id, file_name, content_type, data)).await
+}
+
+async fn insert_recipe_image(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    file_name: &str,
+    content_type: Option<&str>,
+    data: &[u8],
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query(
+        "INSERT INTO recipe_images (recipe_id, file_name, content_type, data) SELECT id, ?, ?, ? FROM recipes WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(file_name)
+    .bind(content_type)
+    .bind(data)
+    .bind(recipe_id)
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::RecipeNotFound(recipe_id));
+    }
+    let image_id = result.last_insert_rowid();
+    record_create(
+        &mut tx,
+        "recipe_image",
+        "recipe_images",
+        image_id,
+        IMAGE_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(image_id)
+}
+
+/// A recipe's images in the order they were attached
+pub async fn list_recipe_images(
+    pool: &SqlitePool,
+    recipe_id: i64,
+) -> Result<Vec<RecipeImageRecord>> {
+    let images = sqlx::query_as::<_, RecipeImageRecord>(
+        "SELECT id, recipe_id, file_name, content_type, length(data) as size, created_at FROM recipe_images WHERE recipe_id = ? ORDER BY id",
+    )
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(images)
+}
+
+/// An image with its bytes
+pub async fn get_recipe_image(
+    pool: &SqlitePool,
+    image_id: i64,
+) -> Result<(RecipeImageRecord, Vec<u8>)> {
+    let row = sqlx::query(
+        "SELECT id, recipe_id, file_name, content_type, length(data) as size, data, created_at FROM recipe_images WHERE id = ?",
+    )
+    .bind(image_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(FeedMeError::ImageNotFound(image_id))?;
+
+    Ok((RecipeImageRecord::from_row(&row)?, row.get("data")))
+}
+
+/// Remove an image from its recipe
+/// Returns false if there was no such image
+pub async fn remove_recipe_image(pool: &SqlitePool, image_id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_recipe_image(pool, image_id)).await
+}
+
+async fn delete_recipe_image(pool: &SqlitePool, image_id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let removed = delete_audited(
+        &mut tx,
+        "recipe_image",
+        "recipe_images",
+        image_id,
+        IMAGE_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::delete_recipe;
+    use crate::models::test_fixtures::test_db;
+    use crate::validation::{MAX_IMAGE_BYTES, Violation};
+    use rstest::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const JPEG: &[u8] = &[0xff, 0xd8, 0xff, 0xe0, 0, 0x10, b'J', b'F', b'I', b'F'];
+
+    async fn insert_recipe(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+            .bind(name)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_recipe_images(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let bread = insert_recipe(&pool, "Bread").await;
+
+        let crumb = attach_recipe_image(&pool, bread, " crumb.png ", PNG)
+            .await
+            .unwrap();
+        let loaf = attach_recipe_image(&pool, bread, "loaf.jpg", JPEG)
+            .await
+            .unwrap();
+
+        let images = list_recipe_images(&pool, bread).await.unwrap();
+        let listed: Vec<(i64, &str, &str, i64)> = images
+            .iter()
+            .map(|image| {
+                (
+                    image.id,
+                    image.file_name.as_str(),
+                    image.content_type.as_str(),
+                    image.size,
+                )
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (crumb, "crumb.png", "image/png", PNG.len() as i64),
+                (loaf, "loaf.jpg", "image/jpeg", JPEG.len() as i64),
+            ]
+        );
+
+        let (image, data) = get_recipe_image(&pool, loaf).await.unwrap();
+        assert_eq!(image.recipe_id, bread);
+        assert_eq!(data, JPEG);
+
+        assert!(remove_recipe_image(&pool, crumb).await.unwrap());
+        assert!(!remove_recipe_image(&pool, crumb).await.unwrap());
+        assert!(matches!(
+            get_recipe_image(&pool, crumb).await,
+            Err(FeedMeError::ImageNotFound(_))
+        ));
+        assert_eq!(list_recipe_images(&pool, bread).await.unwrap().len(), 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_attach_recipe_image_errors(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let bread = insert_recipe(&pool, "Bread").await;
+
+        let Err(FeedMeError::Validation(errors)) =
+            attach_recipe_image(&pool, bread, "notes.txt", b"not an image").await
+        else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "data");
+        assert_eq!(errors[0].violation, Violation::NotImage);
+
+        let mut huge = PNG.to_vec();
+        huge.resize(MAX_IMAGE_BYTES + 1, 0);
+        let Err(FeedMeError::Validation(errors)) =
+            attach_recipe_image(&pool, bread, "huge.png", &huge).await
+        else {
+            panic!("expected a validation error");
+        };
+        assert!(matches!(errors[0].violation, Violation::TooLarge { .. }));
+
+        assert!(matches!(
+            attach_recipe_image(&pool, 999, "crumb.png", PNG).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+        delete_recipe(&pool, bread).await.unwrap();
+        assert!(matches!(
+            attach_recipe_image(&pool, bread, "crumb.png", PNG).await,
+            Err(FeedMeError::RecipeNotFound(_))
+        ));
+        assert!(list_recipe_images(&pool, bread).await.unwrap().is_empty());
+    }
+}
@@ -1,21 +1,104 @@
-use sqlx::SqlitePool;
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashMap;
 
-use crate::error::Result;
-use crate::models::IngredientRecord;
+use super::audit_controller::{record_change, record_update, row_snapshot};
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, OnDuplicate, Result};
+use crate::models::api::RecipeIngredient;
+use crate::models::{AuditAction, IngredientRecord};
+use crate::validation::Validator;
 
 /// Create a new ingredient
-/// Returns the ingredient ID
-/// Note: This will fail if an ingredient with the same name already exists (UNIQUE constraint)
+/// The name is stored trimmed. Returns the ingredient ID, or
+/// `DuplicateIngredient` if an ingredient with the same name already exists
 pub async fn create_ingredient(pool: &SqlitePool, name: &str) -> Result<i64> {
+    let mut validator = Validator::new();
+    let name = validator.name("name", name);
+    validator.finish()?;
+
+    with_busy_retry(|| insert_ingredient(pool, name)).await
+}
+
+async fn insert_ingredient(pool: &SqlitePool, name: &str) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let ingredient_id = insert_ingredient_in(&mut tx, name).await?;
+    tx.commit().await?;
+
+    Ok(ingredient_id)
+}
+
+/// Insert an ingredient and its audit entry inside the caller's transaction
+pub(crate) async fn insert_ingredient_in(conn: &mut SqliteConnection, name: &str) -> Result<i64> {
     let ingredient_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
         .bind(name)
-        .execute(pool)
-        .await?
+        .execute(&mut *conn)
+        .await
+        .on_duplicate(|| FeedMeError::DuplicateIngredient(name.to_string()))?
         .last_insert_rowid();
 
+    record_change(
+        conn,
+        "ingredient",
+        ingredient_id,
+        AuditAction::Create,
+        None,
+        Some(&json!({ "id": ingredient_id, "name": name })),
+    )
+    .await?;
+
     Ok(ingredient_id)
 }
 
+/// Look an ingredient up by exact name inside the caller's transaction,
+/// creating it when missing
+/// Returns the ID and whether it was created
+pub(crate) async fn find_or_insert_ingredient(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> Result<(i64, bool)> {
+    let existing = sqlx::query_scalar("SELECT id FROM ingredients WHERE name = ?")
+        .bind(name)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+    match existing {
+        Some(id) => Ok((id, false)),
+        None => Ok((insert_ingredient_in(conn, name).await?, true)),
+    }
+}
+
+/// Give recipe lines without an ingredient (ID 0) one by name inside the
+/// caller's transaction, creating the ingredients that don't exist
+/// Names are trimmed first, in the lines as well; lines that already have an
+/// ID are left alone. `known` remembers names already looked up, so it can be
+/// shared across the recipes of one import. Returns the names created
+pub(crate) async fn resolve_ingredients_in(
+    conn: &mut SqliteConnection,
+    ingredients: &mut [RecipeIngredient],
+    known: &mut HashMap<String, i64>,
+) -> Result<Vec<String>> {
+    let mut created = Vec::new();
+
+    for ingredient in ingredients.iter_mut().filter(|i| i.ingredient_id == 0) {
+        let name = ingredient.ingredient_name.trim().to_string();
+        ingredient.ingredient_id = match known.get(&name) {
+            Some(&id) => id,
+            None => {
+                let (id, new) = find_or_insert_ingredient(conn, &name).await?;
+                if new {
+                    created.push(name.clone());
+                }
+                known.insert(name.clone(), id);
+                id
+            }
+        };
+        ingredient.ingredient_name = name;
+    }
+
+    Ok(created)
+}
+
 /// Get all ingredients from the database
 /// Returns a list of all ingredients ordered by name
 pub async fn get_all_ingredients(pool: &SqlitePool) -> Result<Vec<IngredientRecord>> {
@@ -28,6 +111,149 @@ pub async fn get_all_ingredients(pool: &SqlitePool) -> Result<Vec<IngredientReco
     Ok(ingredients)
 }
 
+/// Set (or clear) an ingredient's density in grams per millilitre, used to
+/// sum volumes and weights of it on shopping lists
+pub async fn set_ingredient_density(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    grams_per_ml: Option<f64>,
+) -> Result<()> {
+    with_busy_retry(|| update_ingredient_density(pool, ingredient_id, grams_per_ml)).await
+}
+
+async fn update_ingredient_density(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    grams_per_ml: Option<f64>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "ingredients", ingredient_id, &["grams_per_ml"]).await?;
+
+    let result = sqlx::query("UPDATE ingredients SET grams_per_ml = ? WHERE id = ?")
+        .bind(grams_per_ml)
+        .bind(ingredient_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::IngredientNotFound(ingredient_id));
+    }
+    record_update(
+        &mut tx,
+        "ingredient",
+        "ingredients",
+        ingredient_id,
+        &["grams_per_ml"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Set (or clear) the store section an ingredient is found in, used to order
+/// shopping lists by a store's layout
+/// The aisle is stored lowercased
+pub async fn set_ingredient_aisle(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    aisle: Option<&str>,
+) -> Result<()> {
+    let mut validator = Validator::new();
+    let aisle = aisle.map(|aisle| validator.name("aisle", aisle).to_lowercase());
+    validator.finish()?;
+
+    with_busy_retry(|| update_ingredient_aisle(pool, ingredient_id, aisle.as_deref())).await
+}
+
+async fn update_ingredient_aisle(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    aisle: Option<&str>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "ingredients", ingredient_id, &["aisle"]).await?;
+
+    let result = sqlx::query("UPDATE ingredients SET aisle = ? WHERE id = ?")
+        .bind(aisle)
+        .bind(ingredient_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::IngredientNotFound(ingredient_id));
+    }
+    record_update(
+        &mut tx,
+        "ingredient",
+        "ingredients",
+        ingredient_id,
+        &["aisle"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Set (or clear) the emoji or other short symbol shown next to an
+/// ingredient in lists
+pub async fn set_ingredient_icon(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    icon: Option<&str>,
+) -> Result<()> {
+    let mut validator = Validator::new();
+    let icon = icon.map(|icon| validator.icon("icon", icon));
+    validator.finish()?;
+
+    with_busy_retry(|| update_ingredient_icon(pool, ingredient_id, icon)).await
+}
+
+async fn update_ingredient_icon(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    icon: Option<&str>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "ingredients", ingredient_id, &["icon"]).await?;
+
+    let result = sqlx::query("UPDATE ingredients SET icon = ? WHERE id = ?")
+        .bind(icon)
+        .bind(ingredient_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::IngredientNotFound(ingredient_id));
+    }
+    record_update(
+        &mut tx,
+        "ingredient",
+        "ingredients",
+        ingredient_id,
+        &["icon"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Every ingredient icon that's set, by ingredient ID
+pub async fn ingredient_icons(pool: &SqlitePool) -> Result<HashMap<i64, String>> {
+    let icons = sqlx::query_as("SELECT id, icon FROM ingredients WHERE icon IS NOT NULL")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(icons)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,7 +294,31 @@ mod tests {
         // Try to create duplicate
         let result = create_ingredient(&pool, "flour").await;
 
-        assert!(result.is_err(), "Should fail with duplicate name");
+        assert!(
+            matches!(result, Err(FeedMeError::DuplicateIngredient(ref name)) if name == "flour"),
+            "Should fail with duplicate name"
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_ingredient_trims_and_validates_name(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let id = create_ingredient(&pool, " basil ").await.unwrap();
+        let ingredients = get_all_ingredients(&pool).await.unwrap();
+        assert_eq!(
+            (ingredients[0].id, ingredients[0].name.as_str()),
+            (id, "basil")
+        );
+
+        for name in ["", "   ", "bad\nname"] {
+            assert!(matches!(
+                create_ingredient(&pool, name).await,
+                Err(FeedMeError::Validation(_))
+            ));
+        }
+        assert_eq!(get_all_ingredients(&pool).await.unwrap().len(), 1);
     }
 
     #[rstest]
@@ -153,4 +403,100 @@ mod tests {
             assert!(!ingredient.created_at.is_empty());
         }
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_set_ingredient_density(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let flour = create_ingredient(&pool, "flour").await.unwrap();
+
+        set_ingredient_density(&pool, flour, Some(0.53))
+            .await
+            .expect("Failed to set density");
+        let density: Option<f64> =
+            sqlx::query_scalar("SELECT grams_per_ml FROM ingredients WHERE id = ?")
+                .bind(flour)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(density, Some(0.53));
+
+        assert!(matches!(
+            set_ingredient_density(&pool, 999, Some(1.0)).await,
+            Err(FeedMeError::IngredientNotFound(999))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_set_ingredient_icon(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let carrot = create_ingredient(&pool, "carrot").await.unwrap();
+        let milk = create_ingredient(&pool, "milk").await.unwrap();
+
+        set_ingredient_icon(&pool, carrot, Some(" 🥕 "))
+            .await
+            .unwrap();
+        set_ingredient_icon(&pool, milk, Some("🥛")).await.unwrap();
+        set_ingredient_icon(&pool, milk, None).await.unwrap();
+        assert_eq!(
+            ingredient_icons(&pool).await.unwrap(),
+            HashMap::from([(carrot, "🥕".to_string())])
+        );
+
+        for icon in ["", "not an emoji"] {
+            assert!(matches!(
+                set_ingredient_icon(&pool, carrot, Some(icon)).await,
+                Err(FeedMeError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            set_ingredient_icon(&pool, 999, None).await,
+            Err(FeedMeError::IngredientNotFound(999))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_resolve_ingredients_in(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let salt = create_ingredient(&pool, "salt").await.unwrap();
+        let pepper = create_ingredient(&pool, "pepper").await.unwrap();
+
+        let line = |ingredient_id, name: &str| RecipeIngredient {
+            ingredient_id,
+            ingredient_name: name.to_string(),
+            quantity_unit: "1 tsp".to_string(),
+            notes: None,
+            steps: Vec::new(),
+        };
+        let mut ingredients = vec![
+            line(0, " salt "),
+            line(0, "cumin"),
+            line(0, "cumin "),
+            line(pepper, "black pepper"),
+        ];
+
+        let mut tx = pool.begin().await.unwrap();
+        let created = resolve_ingredients_in(&mut tx, &mut ingredients, &mut HashMap::new())
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        assert_eq!(created, vec!["cumin"]);
+        let cumin = ingredients[1].ingredient_id;
+        let resolved: Vec<(i64, &str)> = ingredients
+            .iter()
+            .map(|i| (i.ingredient_id, i.ingredient_name.as_str()))
+            .collect();
+        assert_eq!(
+            resolved,
+            vec![
+                (salt, "salt"),
+                (cumin, "cumin"),
+                (cumin, "cumin"),
+                (pepper, "black pepper")
+            ]
+        );
+    }
 }
@@ -0,0 +1,872 @@
+use chrono::{Datelike, Days, NaiveDate};
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use std::collections::BTreeMap;
+
+use super::audit_controller::record_change;
+use super::custom_unit_controller::{ingredient_units, list_custom_units};
+use super::retry::with_busy_retry;
+use super::search_controller::push_recipe_filter;
+use super::shopping_list_controller::plan_shopping_list;
+use super::suggestion_controller::{last_used_before, load_candidates};
+use crate::error::{FeedMeError, Result};
+use crate::models::api::{
+    MealPlan, PlanConstraints, PlannedMeal, RecipeFilter, Reminder, SharedPrep, ShoppingListItem,
+    WeeklyDigest,
+};
+use crate::models::{AuditAction, MealPlanRecord};
+use crate::quantity::QuantityTotal;
+use crate::validation::Validator;
+
+/// Number of days `generate_week_plan` fills
+const WEEK_DAYS: u64 = 7;
+
+/// Save a meal plan with all of its entries
+/// Takes a MealPlan (ignoring id) and returns the new plan ID
+pub async fn save_meal_plan(pool: &SqlitePool, plan: &MealPlan) -> Result<i64> {
+    validate_meal_plan(plan)?;
+
+    with_busy_retry(|| insert_meal_plan(pool, plan)).await
+}
+
+async fn insert_meal_plan(pool: &SqlitePool, plan: &MealPlan) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let plan_id = sqlx::query("INSERT INTO meal_plans (name, start_date) VALUES (?, ?)")
+        .bind(&plan.name)
+        .bind(plan.start_date)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+    if !plan.entries.is_empty() {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO meal_plan_entries (meal_plan_id, planned_on, meal, recipe_id, servings, note) ",
+        );
+        builder.push_values(&plan.entries, |mut row, entry| {
+            row.push_bind(plan_id)
+                .push_bind(entry.date)
+                .push_bind(&entry.meal)
+                .push_bind(entry.recipe_id)
+                .push_bind(entry.servings)
+                .push_bind(&entry.note);
+        });
+        builder.build().execute(&mut *tx).await?;
+    }
+    let snapshot = serde_json::to_value(MealPlan {
+        id: plan_id,
+        ..plan.clone()
+    })?;
+    record_change(
+        &mut tx,
+        "meal_plan",
+        plan_id,
+        AuditAction::Create,
+        None,
+        Some(&snapshot),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(plan_id)
+}
+
+/// Check a plan's name, meals and notes
+pub(crate) fn validate_meal_plan(plan: &MealPlan) -> Result<()> {
+    let mut validator = Validator::new();
+    if let Some(name) = &plan.name {
+        validator.name("name", name);
+    }
+    for (i, entry) in plan.entries.iter().enumerate() {
+        validator.name(format!("entries[{}].meal", i), &entry.meal);
+        validator.line(format!("entries[{}].note", i), entry.note.as_deref());
+    }
+    validator.finish()
+}
+
+/// Fetch a saved meal plan with its entries ordered by date
+pub async fn get_meal_plan(pool: &SqlitePool, plan_id: i64) -> Result<MealPlan> {
+    let plan = sqlx::query_as::<_, MealPlanRecord>(
+        "SELECT id, name, start_date, created_at FROM meal_plans WHERE id = ?",
+    )
+    .bind(plan_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(FeedMeError::MealPlanNotFound(plan_id))?;
+
+    let entries = sqlx::query(
+        r#"
+        SELECT
+            e.planned_on,
+            e.meal,
+            e.recipe_id,
+            r.name as recipe_name,
+            e.servings,
+            e.note
+        FROM meal_plan_entries e
+        JOIN recipes r ON e.recipe_id = r.id
+        WHERE e.meal_plan_id = ? AND r.deleted_at IS NULL
+        ORDER BY e.planned_on, e.id
+        "#,
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| PlannedMeal {
+        date: row.get("planned_on"),
+        meal: row.get("meal"),
+        recipe_id: row.get("recipe_id"),
+        recipe_name: row.get("recipe_name"),
+        servings: row.get("servings"),
+        note: row.get("note"),
+    })
+    .collect();
+
+    Ok(MealPlan {
+        id: plan.id,
+        name: plan.name,
+        start_date: plan.start_date,
+        entries,
+    })
+}
+
+/// Shopping list for every recipe in a saved plan
+/// Each entry's quantities are multiplied by its servings before being summed
+/// per ingredient and unit; unparseable quantities are listed as written
+pub async fn shopping_list_for_plan(
+    pool: &SqlitePool,
+    plan_id: i64,
+) -> Result<Vec<ShoppingListItem>> {
+    Ok(plan_quantity_totals(pool, plan_id)
+        .await?
+        .into_iter()
+        .map(|(ingredient_name, (_, total))| ShoppingListItem {
+            ingredient_name,
+            combined_quantity: total.to_string(),
+        })
+        .collect())
+}
+
+/// Scaled quantity totals of a plan, keyed by ingredient name, with the ingredient ID
+pub(crate) async fn plan_quantity_totals(
+    pool: &SqlitePool,
+    plan_id: i64,
+) -> Result<BTreeMap<String, (i64, QuantityTotal)>> {
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM meal_plans WHERE id = ?")
+        .bind(plan_id)
+        .fetch_optional(pool)
+        .await?;
+    if exists.is_none() {
+        return Err(FeedMeError::MealPlanNotFound(plan_id));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            i.id as ingredient_id,
+            i.name as ingredient_name,
+            i.grams_per_ml,
+            ri.quantity_unit,
+            ri.amount,
+            ri.unit,
+            e.servings
+        FROM meal_plan_entries e
+        JOIN recipe_ingredients ri ON ri.recipe_id = e.recipe_id
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE e.meal_plan_id = ?
+        ORDER BY i.name, e.planned_on, e.id, ri.id
+        "#,
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?;
+
+    let custom_units = list_custom_units(pool).await?;
+
+    let mut totals: BTreeMap<String, (i64, QuantityTotal)> = BTreeMap::new();
+    for row in &rows {
+        let quantity_unit: String = row.get("quantity_unit");
+        let unit: Option<String> = row.get("unit");
+
+        totals
+            .entry(row.get("ingredient_name"))
+            .or_insert_with(|| {
+                let ingredient_id = row.get("ingredient_id");
+                let units = ingredient_units(&custom_units, ingredient_id, row.get("grams_per_ml"));
+                (ingredient_id, QuantityTotal::for_ingredient(units))
+            })
+            .1
+            .add(
+                &quantity_unit,
+                row.get("amount"),
+                unit.as_deref(),
+                row.get("servings"),
+            );
+    }
+
+    Ok(totals)
+}
+
+/// Prepared ingredients ("diced onion") that two or more planned recipes share
+/// Matches on ingredient plus its preparation note, so "diced" and "sliced"
+/// garlic are separate jobs; ingredients without a note aren't prep work.
+/// Ordered by the day the prep is first needed
+pub async fn shared_prep(pool: &SqlitePool, plan_id: i64) -> Result<Vec<SharedPrep>> {
+    let plan = get_meal_plan(pool, plan_id).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            e.planned_on,
+            e.servings,
+            r.name as recipe_name,
+            i.id as ingredient_id,
+            i.name as ingredient_name,
+            i.grams_per_ml,
+            lower(trim(ri.notes)) as preparation,
+            ri.quantity_unit,
+            ri.amount,
+            ri.unit
+        FROM meal_plan_entries e
+        JOIN recipes r ON e.recipe_id = r.id
+        JOIN recipe_ingredients ri ON ri.recipe_id = e.recipe_id
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE e.meal_plan_id = ? AND r.deleted_at IS NULL AND trim(ri.notes) != ''
+        ORDER BY e.planned_on, e.id, ri.id
+        "#,
+    )
+    .bind(plan.id)
+    .fetch_all(pool)
+    .await?;
+
+    let custom_units = list_custom_units(pool).await?;
+
+    // Keyed by (ingredient, preparation), in first-needed order
+    let mut groups: Vec<(SharedPrep, QuantityTotal)> = Vec::new();
+    for row in &rows {
+        let ingredient_name: String = row.get("ingredient_name");
+        let preparation: String = row.get("preparation");
+        let recipe_name: String = row.get("recipe_name");
+
+        let index = match groups.iter().position(|(prep, _)| {
+            prep.ingredient_name == ingredient_name && prep.preparation == preparation
+        }) {
+            Some(index) => index,
+            None => {
+                groups.push((
+                    SharedPrep {
+                        ingredient_name,
+                        preparation,
+                        recipes: Vec::new(),
+                        total_quantity: String::new(),
+                        first_needed: row.get("planned_on"),
+                    },
+                    QuantityTotal::for_ingredient(ingredient_units(
+                        &custom_units,
+                        row.get("ingredient_id"),
+                        row.get("grams_per_ml"),
+                    )),
+                ));
+                groups.len() - 1
+            }
+        };
+
+        let (prep, total) = &mut groups[index];
+        prep.recipes.push(recipe_name);
+
+        let quantity_unit: String = row.get("quantity_unit");
+        let unit: Option<String> = row.get("unit");
+        total.add(
+            &quantity_unit,
+            row.get("amount"),
+            unit.as_deref(),
+            row.get("servings"),
+        );
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|(prep, _)| prep.recipes.len() > 1)
+        .map(|(mut prep, total)| {
+            prep.total_quantity = total.to_string();
+            prep
+        })
+        .collect())
+}
+
+/// Notes on every meal planned for `date`, across all saved plans
+pub async fn reminders_for(pool: &SqlitePool, date: NaiveDate) -> Result<Vec<Reminder>> {
+    let reminders = sqlx::query(
+        r#"
+        SELECT
+            e.planned_on,
+            e.meal,
+            e.recipe_id,
+            r.name as recipe_name,
+            e.note
+        FROM meal_plan_entries e
+        JOIN recipes r ON e.recipe_id = r.id
+        WHERE e.planned_on = ? AND r.deleted_at IS NULL AND trim(e.note) != ''
+        ORDER BY e.id
+        "#,
+    )
+    .bind(date)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| Reminder {
+        date: row.get("planned_on"),
+        meal: row.get("meal"),
+        recipe_id: row.get("recipe_id"),
+        recipe_name: row.get("recipe_name"),
+        note: row.get("note"),
+    })
+    .collect();
+
+    Ok(reminders)
+}
+
+/// The plan holding the next meal planned on or after `today`, if any
+pub async fn upcoming_meal_plan(pool: &SqlitePool, today: NaiveDate) -> Result<Option<i64>> {
+    let plan_id = sqlx::query_scalar(
+        "SELECT meal_plan_id FROM meal_plan_entries WHERE planned_on >= ? ORDER BY planned_on, id LIMIT 1",
+    )
+    .bind(today)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(plan_id)
+}
+
+/// A saved plan with its shopping list, for `digest::to_html` and `digest::send`
+/// The list is alphabetical and priced like `plan_shopping_list`'s
+pub async fn weekly_digest(pool: &SqlitePool, plan_id: i64) -> Result<WeeklyDigest> {
+    weekly_digest_with(pool, plan_id, &RecipeFilter::default()).await
+}
+
+/// Like `weekly_digest`, leaving out meals whose recipe doesn't pass `filter`
+/// (a private recipe in a digest for the household)
+/// The shopping list still covers the whole plan, as those meals are cooked all the same
+pub async fn weekly_digest_with(
+    pool: &SqlitePool,
+    plan_id: i64,
+    filter: &RecipeFilter,
+) -> Result<WeeklyDigest> {
+    let mut plan = get_meal_plan(pool, plan_id).await?;
+
+    if !plan.entries.is_empty() {
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT r.id FROM recipes r WHERE r.id IN (");
+        let mut separated = builder.separated(", ");
+        for entry in &plan.entries {
+            separated.push_bind(entry.recipe_id);
+        }
+        separated.push_unseparated(")");
+        push_recipe_filter(&mut builder, filter);
+
+        let shown: Vec<i64> = builder.build_query_scalar().fetch_all(pool).await?;
+        plan.entries
+            .retain(|entry| shown.contains(&entry.recipe_id));
+    }
+
+    Ok(WeeklyDigest {
+        plan,
+        shopping_list: plan_shopping_list(pool, plan_id, &[]).await?,
+    })
+}
+
+/// Fill seven dinners starting at `constraints.start_date`
+/// Each day gets the eligible recipe with the best suggestion score, which
+/// favours recipes not made recently, so repeated calls rotate through the catalog.
+/// Days no recipe fits are left out of the plan. Nothing is saved; pass the
+/// (possibly edited) plan to `save_meal_plan` to keep it.
+pub async fn generate_week_plan(
+    pool: &SqlitePool,
+    constraints: &PlanConstraints,
+) -> Result<MealPlan> {
+    let filter = RecipeFilter {
+        tags: constraints.required_tags.clone(),
+        ..Default::default()
+    };
+    let candidates = load_candidates(pool, &filter).await?;
+    let mut last_used = last_used_before(pool, constraints.start_date).await?;
+
+    let mut entries = Vec::new();
+
+    for offset in 0..WEEK_DAYS {
+        let date = constraints.start_date + Days::new(offset);
+        let limit = constraints.max_minutes.get(&date.weekday()).copied();
+
+        let choice = candidates
+            .iter()
+            .filter(|c| c.fits(limit))
+            .filter(|c| {
+                last_used
+                    .get(&c.id)
+                    .is_none_or(|last| (date - *last).num_days() >= constraints.no_repeat_days)
+            })
+            .map(|c| (c.score(date, last_used.get(&c.id).copied(), &[]), c))
+            // Highest score, earliest recipe on ties
+            .max_by(|(a, a_recipe), (b, b_recipe)| {
+                a.total_cmp(b).then(b_recipe.id.cmp(&a_recipe.id))
+            })
+            .map(|(_, c)| c);
+
+        match choice {
+            Some(recipe) => {
+                last_used.insert(recipe.id, date);
+                entries.push(PlannedMeal {
+                    date,
+                    meal: "dinner".to_string(),
+                    recipe_id: recipe.id,
+                    recipe_name: recipe.name.clone(),
+                    servings: 1.0,
+                    note: None,
+                });
+            }
+            None => tracing::warn!(%date, "no recipe satisfies the plan constraints"),
+        }
+    }
+
+    Ok(MealPlan {
+        id: 0,
+        name: None,
+        start_date: constraints.start_date,
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{
+        add_recipe_tag, create_ingredient, create_recipe, log_cook, set_recipe_times,
+    };
+    use crate::models::api::{Recipe, RecipeIngredient, Visibility};
+    use crate::models::test_fixtures::test_db;
+    use chrono::Weekday;
+    use rstest::*;
+
+    /// Monday
+    fn start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+    }
+
+    async fn insert_recipes(pool: &SqlitePool, count: usize) -> Vec<i64> {
+        let mut ids = Vec::new();
+        for i in 0..count {
+            let id = sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+                .bind(format!("Recipe {}", i))
+                .execute(pool)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+            ids.push(id);
+        }
+        ids
+    }
+
+    fn planned_ids(plan: &MealPlan) -> Vec<i64> {
+        plan.entries.iter().map(|e| e.recipe_id).collect()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_week_plan_fills_seven_distinct_dinners(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = insert_recipes(&pool, 10).await;
+
+        let plan = generate_week_plan(&pool, &PlanConstraints::new(start()))
+            .await
+            .expect("Failed to generate plan");
+
+        assert_eq!(plan.entries.len(), 7);
+        assert_eq!(planned_ids(&plan), ids[..7]);
+        assert_eq!(plan.entries[6].date, start() + Days::new(6));
+        assert!(plan.entries.iter().all(|e| e.meal == "dinner"));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_week_plan_repeats_after_window(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = insert_recipes(&pool, 3).await;
+
+        let mut constraints = PlanConstraints::new(start());
+        constraints.no_repeat_days = 3;
+        let plan = generate_week_plan(&pool, &constraints).await.unwrap();
+
+        assert_eq!(
+            planned_ids(&plan),
+            vec![ids[0], ids[1], ids[2], ids[0], ids[1], ids[2], ids[0]]
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_week_plan_skips_days_without_candidates(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        insert_recipes(&pool, 2).await;
+
+        let plan = generate_week_plan(&pool, &PlanConstraints::new(start()))
+            .await
+            .unwrap();
+
+        assert_eq!(plan.entries.len(), 2);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_week_plan_respects_recent_cooking(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = insert_recipes(&pool, 6).await;
+
+        // Cooked two days before the plan starts, so not before Saturday
+        log_cook(&pool, ids[0], start() - Days::new(2), None)
+            .await
+            .unwrap();
+
+        let plan = generate_week_plan(&pool, &PlanConstraints::new(start()))
+            .await
+            .unwrap();
+
+        assert_eq!(plan.entries[0].recipe_id, ids[1]);
+        assert_eq!(plan.entries[5].recipe_id, ids[0]);
+        assert_eq!(plan.entries[5].date.weekday(), Weekday::Sat);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_week_plan_time_limits_and_tags(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = insert_recipes(&pool, 4).await;
+
+        set_recipe_times(&pool, ids[0], Some(10), Some(20))
+            .await
+            .unwrap();
+        set_recipe_times(&pool, ids[1], Some(30), Some(90))
+            .await
+            .unwrap();
+        set_recipe_times(&pool, ids[2], None, Some(15))
+            .await
+            .unwrap();
+        // ids[3] has no recorded time
+        for &id in &ids {
+            add_recipe_tag(&pool, id, "dinner").await.unwrap();
+        }
+        add_recipe_tag(&pool, ids[2], "kid-friendly").await.unwrap();
+        add_recipe_tag(&pool, ids[0], "kid-friendly").await.unwrap();
+
+        let mut constraints = PlanConstraints::new(start());
+        constraints.no_repeat_days = 0;
+        constraints.max_minutes.insert(Weekday::Mon, 20);
+        constraints.max_minutes.insert(Weekday::Tue, 45);
+        constraints.required_tags = vec!["Dinner".to_string(), "kid-friendly".to_string()];
+
+        let plan = generate_week_plan(&pool, &constraints).await.unwrap();
+        let ids_by_day = planned_ids(&plan);
+
+        // Monday only fits the 15 minute recipe
+        assert_eq!(ids_by_day[0], ids[2]);
+        // Tuesday prefers the one not used yet
+        assert_eq!(ids_by_day[1], ids[0]);
+        // Only tagged recipes are ever planned
+        assert!(ids_by_day.iter().all(|id| *id == ids[0] || *id == ids[2]));
+        assert_eq!(ids_by_day.len(), 7);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_save_and_get_meal_plan(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        insert_recipes(&pool, 7).await;
+
+        let mut plan = generate_week_plan(&pool, &PlanConstraints::new(start()))
+            .await
+            .unwrap();
+        plan.name = Some("Week 43".to_string());
+        plan.entries[2].servings = 2.0;
+        plan.entries[3].note = Some("defrost the chicken".to_string());
+        plan.entries.remove(6);
+
+        let plan_id = save_meal_plan(&pool, &plan).await.expect("Failed to save");
+        let saved = get_meal_plan(&pool, plan_id).await.expect("Failed to get");
+
+        assert_eq!(saved.id, plan_id);
+        assert_eq!(saved.name.as_deref(), Some("Week 43"));
+        assert_eq!(saved.start_date, start());
+        assert_eq!(saved.entries.len(), 6);
+        assert_eq!(saved.entries[2].servings, 2.0);
+        assert_eq!(
+            saved.entries[3].note.as_deref(),
+            Some("defrost the chicken")
+        );
+        assert_eq!(saved.entries[0].recipe_name, "Recipe 0");
+
+        // Saved plans count as "recently used" for the following week
+        let next = generate_week_plan(&pool, &PlanConstraints::new(start() + Days::new(7)))
+            .await
+            .unwrap();
+        assert_eq!(next.entries[0].recipe_name, "Recipe 6");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_shopping_list_for_plan_scales_and_sums(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = crate::seed::seed_demo_data(&pool).await.unwrap();
+        let (pancakes, pasta) = (ids[0], ids[3]);
+
+        let plan = MealPlan {
+            id: 0,
+            name: None,
+            start_date: start(),
+            entries: vec![
+                PlannedMeal {
+                    date: start(),
+                    meal: "breakfast".to_string(),
+                    recipe_id: pancakes,
+                    recipe_name: String::new(),
+                    servings: 2.0,
+                    note: None,
+                },
+                PlannedMeal {
+                    date: start() + Days::new(1),
+                    meal: "breakfast".to_string(),
+                    recipe_id: pancakes,
+                    recipe_name: String::new(),
+                    servings: 1.0,
+                    note: None,
+                },
+                PlannedMeal {
+                    date: start() + Days::new(1),
+                    meal: "dinner".to_string(),
+                    recipe_id: pasta,
+                    recipe_name: String::new(),
+                    servings: 0.5,
+                    note: None,
+                },
+            ],
+        };
+        let plan_id = save_meal_plan(&pool, &plan).await.unwrap();
+
+        let list = shopping_list_for_plan(&pool, plan_id)
+            .await
+            .expect("Failed to build list");
+        let quantity = |name: &str| {
+            list.iter()
+                .find(|item| item.ingredient_name == name)
+                .map(|item| item.combined_quantity.clone())
+        };
+
+        // 1.5 cups per batch, three batches
+        assert_eq!(quantity("flour").as_deref(), Some("4 1/2 cups"));
+        assert_eq!(quantity("pasta").as_deref(), Some("250 g"));
+        assert!(
+            list.windows(2)
+                .all(|w| w[0].ingredient_name <= w[1].ingredient_name)
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_shopping_list_for_missing_plan(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let result = shopping_list_for_plan(&pool, 7).await;
+
+        assert!(matches!(result, Err(FeedMeError::MealPlanNotFound(7))));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_shared_prep(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let onion = create_ingredient(&pool, "onion").await.unwrap();
+        let garlic = create_ingredient(&pool, "garlic").await.unwrap();
+
+        let mut recipe_ids = Vec::new();
+        for (name, onion_quantity, garlic_note) in [
+            ("Chili", "1 whole", "minced"),
+            ("Curry", "2 whole", "Minced "),
+            ("Soup", "1 whole", "sliced"),
+        ] {
+            let recipe = Recipe {
+                id: 0,
+                name: name.to_string(),
+                instructions: None,
+                created_at: String::new(),
+                ingredients: vec![
+                    RecipeIngredient {
+                        ingredient_id: onion,
+                        ingredient_name: String::new(),
+                        quantity_unit: onion_quantity.to_string(),
+                        notes: Some("diced".to_string()),
+                        steps: Vec::new(),
+                    },
+                    RecipeIngredient {
+                        ingredient_id: garlic,
+                        ingredient_name: String::new(),
+                        quantity_unit: "2 cloves".to_string(),
+                        notes: Some(garlic_note.to_string()),
+                        steps: Vec::new(),
+                    },
+                ],
+                servings: None,
+                timers: Vec::new(),
+                journal: Vec::new(),
+                tags: Vec::new(),
+                rating: None,
+                equipment: Vec::new(),
+                visibility: Visibility::default(),
+                source_url: None,
+                source_name: None,
+                nutrition: None,
+            };
+            recipe_ids.push(create_recipe(&pool, &recipe).await.unwrap());
+        }
+
+        let plan = MealPlan {
+            id: 0,
+            name: None,
+            start_date: start(),
+            entries: recipe_ids
+                .iter()
+                .enumerate()
+                .map(|(day, &recipe_id)| PlannedMeal {
+                    date: start() + Days::new(day as u64 + 1),
+                    meal: "dinner".to_string(),
+                    recipe_id,
+                    recipe_name: String::new(),
+                    servings: if day == 2 { 2.0 } else { 1.0 },
+                    note: None,
+                })
+                .collect(),
+        };
+        let plan_id = save_meal_plan(&pool, &plan).await.unwrap();
+
+        let prep = shared_prep(&pool, plan_id)
+            .await
+            .expect("Failed to analyze");
+
+        assert_eq!(prep.len(), 2);
+        assert_eq!(prep[0].ingredient_name, "onion");
+        assert_eq!(prep[0].preparation, "diced");
+        assert_eq!(prep[0].recipes, vec!["Chili", "Curry", "Soup"]);
+        assert_eq!(prep[0].total_quantity, "5 whole");
+        assert_eq!(prep[0].first_needed, start() + Days::new(1));
+
+        // Sliced garlic is only used once, so only the minced garlic is shared
+        assert_eq!(prep[1].ingredient_name, "garlic");
+        assert_eq!(prep[1].preparation, "minced");
+        assert_eq!(prep[1].recipes, vec!["Chili", "Curry"]);
+        assert_eq!(prep[1].total_quantity, "4 cloves");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_reminders_for(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        insert_recipes(&pool, 7).await;
+
+        let mut plan = generate_week_plan(&pool, &PlanConstraints::new(start()))
+            .await
+            .unwrap();
+        plan.entries[1].note = Some("defrost the chicken tonight".to_string());
+        plan.entries[2].note = Some("  ".to_string());
+        save_meal_plan(&pool, &plan).await.unwrap();
+
+        let reminders = reminders_for(&pool, start() + Days::new(1)).await.unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].recipe_name, "Recipe 1");
+        assert_eq!(
+            reminders[0].to_string(),
+            "Tue dinner Recipe 1: defrost the chicken tonight"
+        );
+
+        // Blank notes aren't reminders
+        assert!(
+            reminders_for(&pool, start() + Days::new(2))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_weekly_digest(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        insert_recipes(&pool, 7).await;
+        assert_eq!(upcoming_meal_plan(&pool, start()).await.unwrap(), None);
+
+        let plan = generate_week_plan(&pool, &PlanConstraints::new(start()))
+            .await
+            .unwrap();
+        let last_week = save_meal_plan(&pool, &plan).await.unwrap();
+        let mut plan = generate_week_plan(&pool, &PlanConstraints::new(start() + Days::new(7)))
+            .await
+            .unwrap();
+        plan.name = Some("Next week".to_string());
+        let next_week = save_meal_plan(&pool, &plan).await.unwrap();
+
+        assert_eq!(
+            upcoming_meal_plan(&pool, start() + Days::new(6))
+                .await
+                .unwrap(),
+            Some(last_week)
+        );
+        assert_eq!(
+            upcoming_meal_plan(&pool, start() + Days::new(7))
+                .await
+                .unwrap(),
+            Some(next_week)
+        );
+        assert_eq!(
+            upcoming_meal_plan(&pool, start() + Days::new(14))
+                .await
+                .unwrap(),
+            None
+        );
+
+        let digest = weekly_digest(&pool, next_week).await.unwrap();
+        assert_eq!(digest.plan.name.as_deref(), Some("Next week"));
+        assert_eq!(digest.plan.entries.len(), 7);
+        assert_eq!(digest.shopping_list.meal_plan_id, Some(next_week));
+
+        let private = plan.entries[0].recipe_id;
+        crate::controllers::set_recipe_visibility(&pool, private, Visibility::Private)
+            .await
+            .unwrap();
+        let household = RecipeFilter {
+            audience: Some(Visibility::Household),
+            ..Default::default()
+        };
+        let digest = weekly_digest_with(&pool, next_week, &household)
+            .await
+            .unwrap();
+        assert_eq!(digest.plan.entries.len(), 6);
+        assert!(
+            digest
+                .plan
+                .entries
+                .iter()
+                .all(|entry| entry.recipe_id != private)
+        );
+        assert!(matches!(
+            weekly_digest(&pool, 42).await,
+            Err(FeedMeError::MealPlanNotFound(42))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_meal_plan_not_found(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let result = get_meal_plan(&pool, 42).await;
+
+        assert!(matches!(result, Err(FeedMeError::MealPlanNotFound(42))));
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::{NaiveDate, TimeDelta};
+use serde_json::json;
+use sqlx::{Row, SqlitePool};
+
+use super::audit_controller::record_change;
+use super::meal_plan_controller::get_meal_plan;
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, OnDuplicate, Result};
+use crate::models::api::{MealPlan, PlannedMeal};
+use crate::models::{AuditAction, MealPlanTemplateRecord};
+use crate::validation::Validator;
+
+/// Save a plan's entries as a named template
+/// Dates are stored relative to the plan's start so the template can be
+/// applied to any week. The name is stored trimmed and must be unused
+/// (`DuplicateMealPlanTemplate` otherwise). Returns the template ID
+pub async fn save_plan_as_template(pool: &SqlitePool, plan_id: i64, name: &str) -> Result<i64> {
+    let mut validator = Validator::new();
+    let name = validator.name("name", name);
+    validator.finish()?;
+
+    let plan = get_meal_plan(pool, plan_id).await?;
+
+    with_busy_retry(|| insert_plan_template(pool, &plan, name)).await
+}
+
+async fn insert_plan_template(pool: &SqlitePool, plan: &MealPlan, name: &str) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let template_id = sqlx::query("INSERT INTO meal_plan_templates (name) VALUES (?)")
+        .bind(name)
+        .execute(&mut *tx)
+        .await
+        .on_duplicate(|| FeedMeError::DuplicateMealPlanTemplate(name.to_string()))?
+        .last_insert_rowid();
+
+    for entry in &plan.entries {
+        sqlx::query(
+            "INSERT INTO meal_plan_template_entries (template_id, day_offset, meal, recipe_id, servings, note) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(template_id)
+        .bind((entry.date - plan.start_date).num_days())
+        .bind(&entry.meal)
+        .bind(entry.recipe_id)
+        .bind(entry.servings)
+        .bind(&entry.note)
+        .execute(&mut *tx)
+        .await?;
+    }
+    record_change(
+        &mut tx,
+        "meal_plan_template",
+        template_id,
+        AuditAction::Create,
+        None,
+        Some(&json!({ "name": name, "from_plan": plan.id })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(template_id)
+}
+
+/// All templates ordered by name
+pub async fn list_templates(pool: &SqlitePool) -> Result<Vec<MealPlanTemplateRecord>> {
+    let templates = sqlx::query_as::<_, MealPlanTemplateRecord>(
+        "SELECT id, name, created_at FROM meal_plan_templates ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(templates)
+}
+
+/// Lay a template out as a plan starting on `start_date`
+/// The plan is named after the template and isn't saved; pass it to
+/// `save_meal_plan` (after any tweaks) to keep it
+pub async fn instantiate_template(
+    pool: &SqlitePool,
+    template_id: i64,
+    start_date: NaiveDate,
+) -> Result<MealPlan> {
+    let template = sqlx::query_as::<_, MealPlanTemplateRecord>(
+        "SELECT id, name, created_at FROM meal_plan_templates WHERE id = ?",
+    )
+    .bind(template_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(FeedMeError::MealPlanTemplateNotFound(template_id))?;
+
+    let entries = sqlx::query(
+        r#"
+        SELECT
+            e.day_offset,
+            e.meal,
+            e.recipe_id,
+            r.name as recipe_name,
+            e.servings,
+            e.note
+        FROM meal_plan_template_entries e
+        JOIN recipes r ON e.recipe_id = r.id
+        WHERE e.template_id = ? AND r.deleted_at IS NULL
+        ORDER BY e.day_offset, e.id
+        "#,
+    )
+    .bind(template_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| PlannedMeal {
+        date: start_date + TimeDelta::days(row.get("day_offset")),
+        meal: row.get("meal"),
+        recipe_id: row.get("recipe_id"),
+        recipe_name: row.get("recipe_name"),
+        servings: row.get("servings"),
+        note: row.get("note"),
+    })
+    .collect();
+
+    Ok(MealPlan {
+        id: 0,
+        name: Some(template.name),
+        start_date,
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::save_meal_plan;
+    use crate::models::test_fixtures::test_db;
+    use chrono::Days;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_template_round_trip_shifts_dates(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = crate::seed::seed_demo_data(&pool).await.unwrap();
+
+        let start = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let plan = MealPlan {
+            id: 0,
+            name: None,
+            start_date: start,
+            entries: vec![
+                PlannedMeal {
+                    date: start,
+                    meal: "dinner".to_string(),
+                    recipe_id: ids[2],
+                    recipe_name: String::new(),
+                    servings: 1.0,
+                    note: Some("defrost the chicken".to_string()),
+                },
+                PlannedMeal {
+                    date: start + Days::new(4),
+                    meal: "dinner".to_string(),
+                    recipe_id: ids[3],
+                    recipe_name: String::new(),
+                    servings: 2.0,
+                    note: None,
+                },
+            ],
+        };
+        let plan_id = save_meal_plan(&pool, &plan).await.unwrap();
+
+        let template_id = save_plan_as_template(&pool, plan_id, "Autumn rotation")
+            .await
+            .expect("Failed to save template");
+
+        let templates = list_templates(&pool).await.unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].name, "Autumn rotation");
+        assert!(matches!(
+            save_plan_as_template(&pool, plan_id, " Autumn rotation ").await,
+            Err(FeedMeError::DuplicateMealPlanTemplate(name)) if name == "Autumn rotation"
+        ));
+
+        let next_month = start + Days::new(28);
+        let instance = instantiate_template(&pool, template_id, next_month)
+            .await
+            .expect("Failed to instantiate");
+
+        assert_eq!(instance.id, 0);
+        assert_eq!(instance.name.as_deref(), Some("Autumn rotation"));
+        assert_eq!(instance.start_date, next_month);
+        assert_eq!(instance.entries.len(), 2);
+        assert_eq!(instance.entries[0].date, next_month);
+        assert_eq!(
+            instance.entries[0].note.as_deref(),
+            Some("defrost the chicken")
+        );
+        assert_eq!(instance.entries[1].date, next_month + Days::new(4));
+        assert_eq!(instance.entries[1].recipe_name, "Tomato Pasta");
+        assert_eq!(instance.entries[1].servings, 2.0);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_template_errors(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let start = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+
+        assert!(matches!(
+            instantiate_template(&pool, 3, start).await,
+            Err(FeedMeError::MealPlanTemplateNotFound(3))
+        ));
+        assert!(matches!(
+            save_plan_as_template(&pool, 9, "Nope").await,
+            Err(FeedMeError::MealPlanNotFound(9))
+        ));
+    }
+}
@@ -1,5 +1,99 @@
+mod analytics_controller;
+mod audit_controller;
+mod collection_controller;
+mod cook_log_controller;
+mod custom_unit_controller;
+mod draft_controller;
+mod equipment_controller;
+mod export_controller;
+mod image_controller;
 mod ingredient_controller;
+mod meal_plan_controller;
+mod meal_plan_template_controller;
+mod nutrition_controller;
+mod pack_controller;
+mod pantry_controller;
+mod price_controller;
+mod quality_controller;
+mod rating_controller;
 mod recipe_controller;
+mod retry;
+mod search_controller;
+mod shopping_list_controller;
+mod substitution_controller;
+mod suggestion_controller;
+mod tag_controller;
 
-pub use ingredient_controller::{create_ingredient, get_all_ingredients};
-pub use recipe_controller::{create_recipe, generate_shopping_list, get_recipe};
+pub use analytics_controller::{analytics, cooking_trends, ingredient_usage};
+pub use audit_controller::{current_actor, get_audit_log};
+pub use collection_controller::{
+    add_to_collection, collection_shopping_list, create_collection, delete_collection,
+    get_collection, list_collections, remove_from_collection,
+};
+pub use cook_log_controller::{
+    add_recipe_note, delete_recipe_note, get_cook_log, get_recipe_notes, log_cook,
+};
+pub use custom_unit_controller::{define_custom_unit, delete_custom_unit, list_custom_units};
+pub use draft_controller::{discard_draft, latest_draft, save_draft};
+pub use equipment_controller::{
+    get_recipe_equipment, get_recipes_by_equipment, list_equipment, set_recipe_equipment,
+};
+pub use export_controller::{
+    export_all, export_all_with, import_all, import_recipe, shared_recipes, stream_recipe_records,
+    stream_recipes,
+};
+pub use image_controller::{
+    attach_recipe_image, get_recipe_image, list_recipe_images, remove_recipe_image,
+};
+pub use ingredient_controller::{
+    create_ingredient, get_all_ingredients, ingredient_icons, set_ingredient_aisle,
+    set_ingredient_density, set_ingredient_icon,
+};
+pub use meal_plan_controller::{
+    generate_week_plan, get_meal_plan, reminders_for, save_meal_plan, shared_prep,
+    shopping_list_for_plan, upcoming_meal_plan, weekly_digest, weekly_digest_with,
+};
+pub use meal_plan_template_controller::{
+    instantiate_template, list_templates, save_plan_as_template,
+};
+pub use nutrition_controller::{
+    delete_ingredient_nutrition, get_ingredient_nutrition, nutrition_report, plan_nutrition,
+    set_ingredient_nutrition, set_ingredient_nutrition_per_unit,
+};
+pub use pack_controller::{export_pack, import_pack, pack_conflicts};
+#[cfg(feature = "postgres")]
+pub(crate) use pantry_controller::{CoverageRow, cover_recipes};
+pub use pantry_controller::{
+    add_to_pantry, almost_cookable_recipes, cookable_recipes, expiring_soon, list_pantry,
+    recipes_using_expiring, remove_from_pantry, restock_list, set_pantry_expiration,
+    set_pantry_minimum, set_pantry_staple, update_pantry_amount,
+};
+pub use price_controller::{add_price, delete_price, estimate_recipe_cost, list_prices};
+pub use quality_controller::data_quality_report;
+pub use rating_controller::{
+    get_average_rating, get_recipe_rating, list_recipes_by_rating, rate_recipe,
+};
+pub use recipe_controller::{
+    clone_recipe, create_recipe, create_recipe_with, delete_recipe, generate_shopping_list,
+    get_favorites, get_recipe, get_recipe_with, list_deleted_recipes, list_recipes,
+    list_recipes_with, purge_deleted, restore_recipe, set_favorite, set_recipe_category,
+    set_recipe_difficulty, set_recipe_times, set_recipe_visibility, set_step_ingredients,
+    update_recipe,
+};
+pub use search_controller::{
+    get_recipes_containing, get_recipes_containing_all, get_recipes_containing_with,
+    search_by_ingredients, search_by_ingredients_with, search_full_text, search_full_text_with,
+};
+pub use shopping_list_controller::{
+    get_shopping_list, mark_purchased, plan_shopping_list, save_shopping_list, set_already_have,
+    stock_from_shopping_list, sync_shopping_list,
+};
+pub use substitution_controller::{
+    add_substitution, delete_substitution, get_substitutions, list_substitutions,
+    pantry_substitutions,
+};
+pub use suggestion_controller::{similar_recipes, suggest_recipes};
+pub use tag_controller::{
+    add_recipe_tag, get_recipe_tags, get_recipes_by_tag, get_recipes_by_tag_with, list_tags,
+    merge_tags, remove_recipe_tag, rename_tag, tag_recipes, untag_recipes,
+};
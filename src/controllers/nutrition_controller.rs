@@ -0,0 +1,723 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeInclusive;
+
+use super::audit_controller::record_change;
+use super::meal_plan_controller::get_meal_plan;
+use super::retry::with_busy_retry;
+use crate::config::NutritionTargets;
+use crate::error::{FeedMeError, Result};
+use crate::models::api::{DailyNutrition, NutritionFacts, NutritionReport, RecipeNutrition};
+use crate::models::{AuditAction, IngredientNutritionRecord};
+use crate::quantity::to_grams;
+
+/// Store nutrition facts for an ingredient, replacing any existing ones
+pub async fn set_ingredient_nutrition(
+    pool: &SqlitePool,
+    nutrition: &IngredientNutritionRecord,
+) -> Result<()> {
+    with_busy_retry(|| upsert_ingredient_nutrition(pool, nutrition)).await
+}
+
+async fn upsert_ingredient_nutrition(
+    pool: &SqlitePool,
+    nutrition: &IngredientNutritionRecord,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = nutrition_snapshot(&mut tx, nutrition.ingredient_id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO ingredient_nutrition
+            (ingredient_id, calories, protein_g, carbohydrates_g, fat_g, grams_per_unit)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT (ingredient_id) DO UPDATE SET
+            calories = excluded.calories,
+            protein_g = excluded.protein_g,
+            carbohydrates_g = excluded.carbohydrates_g,
+            fat_g = excluded.fat_g,
+            grams_per_unit = excluded.grams_per_unit,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(nutrition.ingredient_id)
+    .bind(nutrition.calories)
+    .bind(nutrition.protein_g)
+    .bind(nutrition.carbohydrates_g)
+    .bind(nutrition.fat_g)
+    .bind(nutrition.grams_per_unit)
+    .execute(&mut *tx)
+    .await?;
+    let new = nutrition_snapshot(&mut tx, nutrition.ingredient_id).await?;
+    record_change(
+        &mut tx,
+        "ingredient_nutrition",
+        nutrition.ingredient_id,
+        if old.is_some() {
+            AuditAction::Update
+        } else {
+            AuditAction::Create
+        },
+        old.as_ref(),
+        new.as_ref(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Store nutrition facts given for one typical unit of an ingredient
+/// (e.g. one egg weighing 50 g), converting them to the per 100 g form
+pub async fn set_ingredient_nutrition_per_unit(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    grams_per_unit: f64,
+    per_unit: &NutritionFacts,
+) -> Result<()> {
+    if grams_per_unit <= 0.0 {
+        return Err(FeedMeError::Config(format!(
+            "grams per unit must be positive, got {}",
+            grams_per_unit
+        )));
+    }
+
+    let mut per_100g = NutritionFacts::default();
+    per_100g.add_scaled(per_unit, 100.0 / grams_per_unit);
+
+    set_ingredient_nutrition(
+        pool,
+        &IngredientNutritionRecord {
+            ingredient_id,
+            calories: per_100g.calories,
+            protein_g: per_100g.protein_g,
+            carbohydrates_g: per_100g.carbohydrates_g,
+            fat_g: per_100g.fat_g,
+            grams_per_unit: Some(grams_per_unit),
+        },
+    )
+    .await
+}
+
+/// Nutrition facts stored for an ingredient, if any
+pub async fn get_ingredient_nutrition(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+) -> Result<Option<IngredientNutritionRecord>> {
+    let nutrition = sqlx::query_as::<_, IngredientNutritionRecord>(
+        r#"
+        SELECT ingredient_id, calories, protein_g, carbohydrates_g, fat_g, grams_per_unit
+        FROM ingredient_nutrition
+        WHERE ingredient_id = ?
+        "#,
+    )
+    .bind(ingredient_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(nutrition)
+}
+
+/// Remove an ingredient's nutrition facts
+/// Returns whether there were any
+pub async fn delete_ingredient_nutrition(pool: &SqlitePool, ingredient_id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_ingredient_nutrition_row(pool, ingredient_id)).await
+}
+
+async fn delete_ingredient_nutrition_row(pool: &SqlitePool, ingredient_id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let Some(old) = nutrition_snapshot(&mut tx, ingredient_id).await? else {
+        return Ok(false);
+    };
+
+    sqlx::query("DELETE FROM ingredient_nutrition WHERE ingredient_id = ?")
+        .bind(ingredient_id)
+        .execute(&mut *tx)
+        .await?;
+    record_change(
+        &mut tx,
+        "ingredient_nutrition",
+        ingredient_id,
+        AuditAction::Delete,
+        Some(&old),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// An ingredient's nutrition facts as JSON for its audit entries, read
+/// inside the caller's transaction; None when it has none
+async fn nutrition_snapshot(
+    conn: &mut SqliteConnection,
+    ingredient_id: i64,
+) -> Result<Option<Value>> {
+    let snapshot: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT json_object(
+            'calories', calories,
+            'protein_g', protein_g,
+            'carbohydrates_g', carbohydrates_g,
+            'fat_g', fat_g,
+            'grams_per_unit', grams_per_unit
+        )
+        FROM ingredient_nutrition
+        WHERE ingredient_id = ?
+        "#,
+    )
+    .bind(ingredient_id)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(snapshot
+        .map(|snapshot| serde_json::from_str(&snapshot))
+        .transpose()?)
+}
+
+/// Nutrition of one recipe ingredient row for its quantity
+/// Expects the `amount`, `unit` and `ingredient_nutrition` columns. None when
+/// the ingredient has no nutrition data or its quantity can't be weighed
+fn ingredient_facts(row: &SqliteRow) -> Option<NutritionFacts> {
+    let calories: Option<f64> = row.get("calories");
+    let amount: Option<f64> = row.get("amount");
+    let unit: Option<String> = row.get("unit");
+
+    let grams = to_grams(amount?, unit.as_deref(), row.get("grams_per_unit"))?;
+    let per_100g = NutritionFacts {
+        calories: calories?,
+        protein_g: row.get("protein_g"),
+        carbohydrates_g: row.get("carbohydrates_g"),
+        fat_g: row.get("fat_g"),
+    };
+
+    let mut facts = NutritionFacts::default();
+    facts.add_scaled(&per_100g, grams / 100.0);
+    Some(facts)
+}
+
+/// Nutrition per serving of a recipe
+/// Recipes without a servings count are treated as a single serving
+pub(crate) async fn recipe_nutrition(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    servings: Option<i64>,
+) -> Result<RecipeNutrition> {
+    let servings = servings.filter(|&servings| servings > 0).unwrap_or(1);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            i.name as ingredient_name,
+            ri.amount,
+            ri.unit,
+            n.calories,
+            n.protein_g,
+            n.carbohydrates_g,
+            n.fat_g,
+            n.grams_per_unit
+        FROM recipe_ingredients ri
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        LEFT JOIN ingredient_nutrition n ON n.ingredient_id = i.id
+        WHERE ri.recipe_id = ?
+        ORDER BY ri.id
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut nutrition = RecipeNutrition {
+        servings,
+        per_serving: NutritionFacts::default(),
+        missing: Vec::new(),
+    };
+    for row in &rows {
+        match ingredient_facts(row) {
+            Some(facts) => nutrition
+                .per_serving
+                .add_scaled(&facts, 1.0 / servings as f64),
+            None => nutrition.missing.push(row.get("ingredient_name")),
+        }
+    }
+
+    Ok(nutrition)
+}
+
+/// Calorie and macro totals for each planned day, flagged against `targets`
+/// Quantities are scaled by each entry's servings. Ingredients without
+/// nutrition data, or whose quantity can't be weighed, are listed in `missing`
+pub async fn plan_nutrition(
+    pool: &SqlitePool,
+    plan_id: i64,
+    targets: &NutritionTargets,
+) -> Result<Vec<DailyNutrition>> {
+    let plan = get_meal_plan(pool, plan_id).await?;
+
+    let mut days: BTreeMap<NaiveDate, DailyNutrition> = plan
+        .entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.date,
+                DailyNutrition {
+                    date: entry.date,
+                    totals: NutritionFacts::default(),
+                    missing: Vec::new(),
+                    deviations: Vec::new(),
+                },
+            )
+        })
+        .collect();
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            e.planned_on,
+            e.servings,
+            i.name as ingredient_name,
+            ri.amount,
+            ri.unit,
+            n.calories,
+            n.protein_g,
+            n.carbohydrates_g,
+            n.fat_g,
+            n.grams_per_unit
+        FROM meal_plan_entries e
+        JOIN recipe_ingredients ri ON ri.recipe_id = e.recipe_id
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        LEFT JOIN ingredient_nutrition n ON n.ingredient_id = i.id
+        WHERE e.meal_plan_id = ?
+        ORDER BY e.planned_on, e.id, ri.id
+        "#,
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?;
+
+    for row in &rows {
+        let date: NaiveDate = row.get("planned_on");
+        let day = days.get_mut(&date).expect("every entry date has a day");
+
+        match ingredient_facts(row) {
+            Some(facts) => day.totals.add_scaled(&facts, row.get("servings")),
+            None => {
+                let name: String = row.get("ingredient_name");
+                if !day.missing.contains(&name) {
+                    day.missing.push(name);
+                }
+            }
+        }
+    }
+
+    Ok(days
+        .into_values()
+        .map(|mut day| {
+            day.deviations = targets.deviations(&day.totals);
+            day
+        })
+        .collect())
+}
+
+/// Daily nutrition over `dates` compared against `targets`
+/// Days with anything in the cook log count what was cooked (one batch of
+/// each recipe); other days fall back to what was planned, scaled by each
+/// entry's servings. Days with neither are left out
+pub async fn nutrition_report(
+    pool: &SqlitePool,
+    dates: RangeInclusive<NaiveDate>,
+    targets: &NutritionTargets,
+) -> Result<NutritionReport> {
+    let (start, end) = (*dates.start(), *dates.end());
+
+    let rows = sqlx::query(
+        r#"
+        WITH meals AS (
+            SELECT planned_on as day, 0 as cooked, recipe_id, servings
+            FROM meal_plan_entries
+            WHERE planned_on BETWEEN ? AND ?
+            UNION ALL
+            SELECT cooked_on, 1, recipe_id, 1.0
+            FROM cook_log
+            WHERE cooked_on BETWEEN ? AND ?
+        )
+        SELECT
+            m.day,
+            m.cooked,
+            m.servings,
+            i.name as ingredient_name,
+            ri.amount,
+            ri.unit,
+            n.calories,
+            n.protein_g,
+            n.carbohydrates_g,
+            n.fat_g,
+            n.grams_per_unit
+        FROM meals m
+        JOIN recipe_ingredients ri ON ri.recipe_id = m.recipe_id
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        LEFT JOIN ingredient_nutrition n ON n.ingredient_id = i.id
+        ORDER BY m.day, ri.id
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    let cooked_days: BTreeSet<NaiveDate> = rows
+        .iter()
+        .filter(|row| row.get::<bool, _>("cooked"))
+        .map(|row| row.get("day"))
+        .collect();
+
+    let mut days: BTreeMap<NaiveDate, DailyNutrition> = BTreeMap::new();
+    for row in &rows {
+        let date: NaiveDate = row.get("day");
+        if cooked_days.contains(&date) != row.get::<bool, _>("cooked") {
+            continue;
+        }
+
+        let day = days.entry(date).or_insert_with(|| DailyNutrition {
+            date,
+            totals: NutritionFacts::default(),
+            missing: Vec::new(),
+            deviations: Vec::new(),
+        });
+        match ingredient_facts(row) {
+            Some(facts) => day.totals.add_scaled(&facts, row.get("servings")),
+            None => {
+                let name: String = row.get("ingredient_name");
+                if !day.missing.contains(&name) {
+                    day.missing.push(name);
+                }
+            }
+        }
+    }
+
+    let days: Vec<DailyNutrition> = days
+        .into_values()
+        .map(|mut day| {
+            day.deviations = targets.deviations(&day.totals);
+            day
+        })
+        .collect();
+
+    let mut daily_average = NutritionFacts::default();
+    for day in &days {
+        daily_average.add_scaled(&day.totals, 1.0 / days.len() as f64);
+    }
+
+    Ok(NutritionReport {
+        start,
+        end,
+        deviations: if days.is_empty() {
+            Vec::new()
+        } else {
+            targets.deviations(&daily_average)
+        },
+        days,
+        daily_average,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{create_ingredient, create_recipe, get_recipe_with, save_meal_plan};
+    use crate::models::api::{
+        MealPlan, Nutrient, PlannedMeal, Recipe, RecipeIngredient, RecipeOptions, Visibility,
+    };
+    use crate::models::test_fixtures::test_db;
+    use chrono::Days;
+    use rstest::*;
+
+    async fn recipe_with(pool: &SqlitePool, name: &str, items: &[(i64, &str)]) -> i64 {
+        let recipe = Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: items
+                .iter()
+                .map(|(id, quantity)| RecipeIngredient {
+                    ingredient_id: *id,
+                    ingredient_name: String::new(),
+                    quantity_unit: quantity.to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                })
+                .collect(),
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        create_recipe(pool, &recipe).await.unwrap()
+    }
+
+    fn entry(date: NaiveDate, recipe_id: i64, servings: f64) -> PlannedMeal {
+        PlannedMeal {
+            date,
+            meal: "dinner".to_string(),
+            recipe_id,
+            recipe_name: String::new(),
+            servings,
+            note: None,
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_ingredient_nutrition_crud(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let egg = create_ingredient(&pool, "egg").await.unwrap();
+
+        assert_eq!(get_ingredient_nutrition(&pool, egg).await.unwrap(), None);
+
+        // One 50 g egg
+        let per_egg = NutritionFacts {
+            calories: 75.0,
+            protein_g: 6.0,
+            carbohydrates_g: 0.5,
+            fat_g: 5.0,
+        };
+        set_ingredient_nutrition_per_unit(&pool, egg, 50.0, &per_egg)
+            .await
+            .unwrap();
+
+        let stored = get_ingredient_nutrition(&pool, egg)
+            .await
+            .unwrap()
+            .expect("Nutrition was set");
+        assert_eq!(
+            stored,
+            IngredientNutritionRecord {
+                ingredient_id: egg,
+                calories: 150.0,
+                protein_g: 12.0,
+                carbohydrates_g: 1.0,
+                fat_g: 10.0,
+                grams_per_unit: Some(50.0),
+            }
+        );
+
+        assert!(matches!(
+            set_ingredient_nutrition_per_unit(&pool, egg, 0.0, &per_egg).await,
+            Err(FeedMeError::Config(_))
+        ));
+
+        assert!(delete_ingredient_nutrition(&pool, egg).await.unwrap());
+        assert!(!delete_ingredient_nutrition(&pool, egg).await.unwrap());
+        assert_eq!(get_ingredient_nutrition(&pool, egg).await.unwrap(), None);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_plan_nutrition(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let rice = create_ingredient(&pool, "rice").await.unwrap();
+        let egg = create_ingredient(&pool, "egg").await.unwrap();
+        let parsley = create_ingredient(&pool, "parsley").await.unwrap();
+        set_ingredient_nutrition(
+            &pool,
+            &IngredientNutritionRecord {
+                ingredient_id: rice,
+                calories: 130.0,
+                protein_g: 2.5,
+                carbohydrates_g: 28.0,
+                fat_g: 0.5,
+                grams_per_unit: None,
+            },
+        )
+        .await
+        .unwrap();
+        set_ingredient_nutrition(
+            &pool,
+            &IngredientNutritionRecord {
+                ingredient_id: egg,
+                calories: 150.0,
+                protein_g: 12.0,
+                carbohydrates_g: 1.0,
+                fat_g: 10.0,
+                grams_per_unit: Some(50.0),
+            },
+        )
+        .await
+        .unwrap();
+
+        let fried_rice = recipe_with(
+            &pool,
+            "Fried rice",
+            &[(rice, "400 g"), (egg, "2"), (parsley, "a handful")],
+        )
+        .await;
+        let omelette = recipe_with(&pool, "Omelette", &[(egg, "3")]).await;
+
+        let start = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let plan = MealPlan {
+            id: 0,
+            name: None,
+            start_date: start,
+            entries: vec![
+                entry(start, fried_rice, 1.0),
+                entry(start, omelette, 1.0),
+                entry(start + Days::new(1), omelette, 2.0),
+            ],
+        };
+        let plan_id = save_meal_plan(&pool, &plan).await.unwrap();
+
+        let targets = NutritionTargets {
+            calories: Some(900.0),
+            ..NutritionTargets::default()
+        };
+        let days = plan_nutrition(&pool, plan_id, &targets)
+            .await
+            .expect("Failed to compute nutrition");
+
+        assert_eq!(days.len(), 2);
+
+        // 400 g rice (520 kcal) + 5 eggs at 50 g (375 kcal)
+        assert_eq!(days[0].date, start);
+        assert!((days[0].totals.calories - 895.0).abs() < 1e-9);
+        assert!((days[0].totals.protein_g - 40.0).abs() < 1e-9);
+        assert_eq!(days[0].missing, vec!["parsley"]);
+        assert!(days[0].deviations.is_empty());
+
+        // 6 eggs: 450 kcal, well under target
+        assert!((days[1].totals.calories - 450.0).abs() < 1e-9);
+        assert_eq!(days[1].deviations.len(), 1);
+        assert_eq!(days[1].deviations[0].nutrient, Nutrient::Calories);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_recipe_nutrition_per_serving(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let rice = create_ingredient(&pool, "rice").await.unwrap();
+        let parsley = create_ingredient(&pool, "parsley").await.unwrap();
+        set_ingredient_nutrition(
+            &pool,
+            &IngredientNutritionRecord {
+                ingredient_id: rice,
+                calories: 130.0,
+                protein_g: 2.5,
+                carbohydrates_g: 28.0,
+                fat_g: 0.5,
+                grams_per_unit: None,
+            },
+        )
+        .await
+        .unwrap();
+        let recipe_id = recipe_with(&pool, "Rice", &[(rice, "400 g"), (parsley, "1 sprig")]).await;
+        sqlx::query("UPDATE recipes SET servings = 4 WHERE id = ?")
+            .bind(recipe_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let plain = get_recipe_with(&pool, recipe_id, RecipeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(plain.servings, Some(4));
+        assert!(plain.nutrition.is_none());
+
+        let recipe = get_recipe_with(
+            &pool,
+            recipe_id,
+            RecipeOptions {
+                include_nutrition: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to get recipe");
+        let nutrition = recipe.nutrition.expect("Nutrition was requested");
+
+        // 400 g of rice split four ways
+        assert_eq!(nutrition.servings, 4);
+        assert!((nutrition.per_serving.calories - 130.0).abs() < 1e-9);
+        assert!((nutrition.per_serving.carbohydrates_g - 28.0).abs() < 1e-9);
+        assert_eq!(nutrition.missing, vec!["parsley"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_nutrition_report_prefers_cooked_meals(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let egg = create_ingredient(&pool, "egg").await.unwrap();
+        set_ingredient_nutrition(
+            &pool,
+            &IngredientNutritionRecord {
+                ingredient_id: egg,
+                calories: 150.0,
+                protein_g: 12.0,
+                carbohydrates_g: 1.0,
+                fat_g: 10.0,
+                grams_per_unit: Some(50.0),
+            },
+        )
+        .await
+        .unwrap();
+        let omelette = recipe_with(&pool, "Omelette", &[(egg, "4")]).await;
+        let boiled = recipe_with(&pool, "Boiled egg", &[(egg, "2")]).await;
+
+        let start = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let plan = MealPlan {
+            id: 0,
+            name: None,
+            start_date: start,
+            entries: vec![
+                entry(start, omelette, 1.0),
+                entry(start + Days::new(1), omelette, 2.0),
+                entry(start + Days::new(9), omelette, 1.0),
+            ],
+        };
+        save_meal_plan(&pool, &plan).await.unwrap();
+        // Made boiled eggs instead of the planned omelette on the first day
+        crate::controllers::log_cook(&pool, boiled, start, None)
+            .await
+            .unwrap();
+
+        let targets = NutritionTargets {
+            calories: Some(450.0),
+            ..NutritionTargets::default()
+        };
+        let report = nutrition_report(&pool, start..=start + Days::new(6), &targets)
+            .await
+            .expect("Failed to build report");
+
+        assert_eq!(report.days.len(), 2);
+        // 2 eggs: 150 kcal, cooked overrides planned
+        assert!((report.days[0].totals.calories - 150.0).abs() < 1e-9);
+        assert_eq!(report.days[0].deviations.len(), 1);
+        // 8 eggs: 600 kcal from the doubled plan entry
+        assert!((report.days[1].totals.calories - 600.0).abs() < 1e-9);
+        assert!((report.daily_average.calories - 375.0).abs() < 1e-9);
+        assert_eq!(report.deviations.len(), 1);
+        assert!(report.to_string().contains("calories low"));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_plan_nutrition_missing_plan(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let result = plan_nutrition(&pool, 1, &NutritionTargets::default()).await;
+
+        assert!(matches!(result, Err(FeedMeError::MealPlanNotFound(1))));
+    }
+}
@@ -0,0 +1,570 @@
+use chrono::{SecondsFormat, Utc};
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::audit_controller::record_change;
+use super::ingredient_controller::resolve_ingredients_in;
+use super::recipe_controller::{
+    get_recipe, get_recipe_metadata, insert_recipe_in, insert_recipe_ingredients,
+    set_recipe_metadata_in,
+};
+use super::retry::with_busy_retry;
+use super::tag_controller::tag_recipe_in;
+use crate::diff::diff_recipes;
+use crate::error::Result;
+use crate::models::AuditAction;
+use crate::models::api::{
+    ConflictStrategy, PACK_VERSION, PackConflict, PackContents, PackImport, PackIngredient,
+    PackRecipe, Recipe, RecipeIngredient, RecipeMetadata, RecipePack, Visibility,
+};
+use crate::validation::{Validator, recipe_warnings};
+
+/// Bundle recipes, with their ingredients, tags and times, into a pack
+/// The pack is signed when `key` is given
+pub async fn export_pack(
+    pool: &SqlitePool,
+    recipe_ids: &[i64],
+    key: Option<&[u8]>,
+) -> Result<RecipePack> {
+    let mut recipes = Vec::new();
+    let mut ingredients = BTreeSet::new();
+
+    for &recipe_id in recipe_ids {
+        let recipe = get_recipe(pool, recipe_id).await?;
+        let metadata = get_recipe_metadata(pool, recipe_id).await?;
+        ingredients.extend(
+            recipe
+                .ingredients
+                .iter()
+                .map(|ingredient| ingredient.ingredient_name.clone()),
+        );
+        recipes.push(PackRecipe {
+            name: recipe.name,
+            instructions: recipe.instructions,
+            servings: recipe.servings,
+            ingredients: recipe
+                .ingredients
+                .into_iter()
+                .map(|ingredient| PackIngredient {
+                    name: ingredient.ingredient_name,
+                    quantity_unit: ingredient.quantity_unit,
+                    notes: ingredient.notes,
+                })
+                .collect(),
+            tags: metadata.tags,
+            prep_minutes: metadata.prep_minutes,
+            cook_minutes: metadata.cook_minutes,
+            category: metadata.category,
+            difficulty: metadata.difficulty,
+        });
+    }
+
+    let contents = PackContents {
+        version: PACK_VERSION,
+        exported_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        ingredients: ingredients.into_iter().collect(),
+        recipes,
+    };
+    RecipePack::seal(contents, key)
+}
+
+/// Pack recipes named like an existing recipe (ignoring case), with what
+/// differs between the two, so the user can pick a `ConflictStrategy`
+/// before importing
+pub async fn pack_conflicts(pool: &SqlitePool, pack: &RecipePack) -> Result<Vec<PackConflict>> {
+    let mut conn = pool.acquire().await?;
+    let mut taken = Vec::new();
+    for recipe in &pack.contents.recipes {
+        if let Some(existing_id) = recipe_named(&mut conn, recipe.name.trim()).await? {
+            taken.push((recipe, existing_id));
+        }
+    }
+    drop(conn);
+
+    let mut conflicts = Vec::new();
+    for (recipe, existing_id) in taken {
+        let existing = get_recipe(pool, existing_id).await?;
+        conflicts.push(PackConflict {
+            name: recipe.name.clone(),
+            existing_id,
+            changes: diff_recipes(&existing, &unresolved_recipe(recipe)),
+        });
+    }
+
+    Ok(conflicts)
+}
+
+/// A pack recipe as a `Recipe`, with ingredients not yet looked up (ID 0)
+fn unresolved_recipe(pack_recipe: &PackRecipe) -> Recipe {
+    Recipe {
+        id: 0,
+        name: pack_recipe.name.trim().to_string(),
+        instructions: pack_recipe.instructions.clone(),
+        servings: pack_recipe.servings,
+        ingredients: pack_recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| RecipeIngredient {
+                ingredient_id: 0,
+                ingredient_name: ingredient.name.trim().to_string(),
+                quantity_unit: ingredient.quantity_unit.clone(),
+                notes: ingredient.notes.clone(),
+                steps: Vec::new(),
+            })
+            .collect(),
+        created_at: String::new(),
+        timers: Vec::new(),
+        journal: Vec::new(),
+        tags: Vec::new(),
+        rating: None,
+        equipment: Vec::new(),
+        visibility: Visibility::default(),
+        source_url: None,
+        source_name: None,
+        nutrition: None,
+    }
+}
+
+/// Add a pack's recipes, creating ingredients that don't exist yet
+/// The pack is verified first (see `RecipePack::verify`) and every recipe is
+/// validated before anything is written; the import then runs in one
+/// transaction, so a failure leaves the database untouched
+pub async fn import_pack(
+    pool: &SqlitePool,
+    pack: &RecipePack,
+    strategy: ConflictStrategy,
+    key: Option<&[u8]>,
+) -> Result<PackImport> {
+    pack.verify(key)?;
+    validate_pack(&pack.contents)?;
+
+    with_busy_retry(|| insert_pack_recipes(pool, pack, strategy)).await
+}
+
+async fn insert_pack_recipes(
+    pool: &SqlitePool,
+    pack: &RecipePack,
+    strategy: ConflictStrategy,
+) -> Result<PackImport> {
+    let mut tx = pool.begin().await?;
+    let mut import = PackImport::default();
+    let mut ingredient_ids = HashMap::new();
+
+    for pack_recipe in &pack.contents.recipes {
+        let name = pack_recipe.name.trim();
+        let existing = recipe_named(&mut tx, name).await?;
+
+        match (existing, strategy) {
+            (Some(_), ConflictStrategy::Skip) => import.skipped.push(name.to_string()),
+            (Some(recipe_id), ConflictStrategy::Merge) => {
+                let ingredients =
+                    resolve_ingredients(&mut tx, pack_recipe, &mut ingredient_ids, &mut import)
+                        .await?;
+                note_warnings(&mut import, pack_recipe);
+                merge_recipe(&mut tx, recipe_id, pack_recipe, &ingredients).await?;
+                import.merged.push(recipe_id);
+            }
+            (existing, _) => {
+                let imported_name = match existing {
+                    Some(_) => {
+                        let free = free_name(&mut tx, name).await?;
+                        import.renamed.push((name.to_string(), free.clone()));
+                        free
+                    }
+                    None => name.to_string(),
+                };
+                let ingredients =
+                    resolve_ingredients(&mut tx, pack_recipe, &mut ingredient_ids, &mut import)
+                        .await?;
+                note_warnings(&mut import, pack_recipe);
+                let recipe = Recipe {
+                    id: 0,
+                    name: imported_name,
+                    instructions: pack_recipe.instructions.clone(),
+                    servings: pack_recipe.servings,
+                    ingredients,
+                    created_at: String::new(),
+                    timers: Vec::new(),
+                    journal: Vec::new(),
+                    tags: Vec::new(),
+                    rating: None,
+                    equipment: Vec::new(),
+                    visibility: Visibility::default(),
+                    source_url: None,
+                    source_name: None,
+                    nutrition: None,
+                };
+                let recipe_id = insert_recipe_in(&mut tx, &recipe).await?;
+                let metadata = RecipeMetadata {
+                    tags: pack_recipe.tags.clone(),
+                    prep_minutes: pack_recipe.prep_minutes,
+                    cook_minutes: pack_recipe.cook_minutes,
+                    category: pack_recipe.category,
+                    difficulty: pack_recipe.difficulty,
+                };
+                set_recipe_metadata_in(&mut tx, recipe_id, &metadata).await?;
+                import.created.push(recipe_id);
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(import)
+}
+
+fn validate_pack(contents: &PackContents) -> Result<()> {
+    let mut validator = Validator::new();
+
+    for (i, recipe) in contents.recipes.iter().enumerate() {
+        validator.name(format!("recipes[{}].name", i), &recipe.name);
+        validator.text(
+            format!("recipes[{}].instructions", i),
+            recipe.instructions.as_deref(),
+        );
+        for (j, ingredient) in recipe.ingredients.iter().enumerate() {
+            let field = format!("recipes[{}].ingredients[{}]", i, j);
+            validator.name(format!("{}.name", field), &ingredient.name);
+            validator.line(
+                format!("{}.quantity_unit", field),
+                Some(&ingredient.quantity_unit),
+            );
+            validator.line(format!("{}.notes", field), ingredient.notes.as_deref());
+        }
+        for (j, tag) in recipe.tags.iter().enumerate() {
+            validator.name(format!("recipes[{}].tags[{}]", i, j), tag);
+        }
+    }
+
+    validator.finish()
+}
+
+/// The pack recipe's ingredients with local IDs, creating missing ingredients
+async fn resolve_ingredients(
+    conn: &mut SqliteConnection,
+    recipe: &PackRecipe,
+    ids: &mut HashMap<String, i64>,
+    import: &mut PackImport,
+) -> Result<Vec<RecipeIngredient>> {
+    let mut ingredients: Vec<RecipeIngredient> = recipe
+        .ingredients
+        .iter()
+        .map(|ingredient| RecipeIngredient {
+            ingredient_id: 0,
+            ingredient_name: ingredient.name.clone(),
+            quantity_unit: ingredient.quantity_unit.clone(),
+            notes: ingredient.notes.clone(),
+            steps: Vec::new(),
+        })
+        .collect();
+    let created = resolve_ingredients_in(conn, &mut ingredients, ids).await?;
+    import.new_ingredients.extend(created);
+
+    Ok(ingredients)
+}
+
+/// Record likely mistakes (see `recipe_warnings`) in a recipe being imported
+fn note_warnings(import: &mut PackImport, pack_recipe: &PackRecipe) {
+    let warnings = recipe_warnings(&unresolved_recipe(pack_recipe));
+    if !warnings.is_empty() {
+        let name = pack_recipe.name.trim().to_string();
+        import.recipe_warnings.push((name, warnings));
+    }
+}
+
+/// Fill in what the existing recipe lacks from the pack's version
+async fn merge_recipe(
+    conn: &mut SqliteConnection,
+    recipe_id: i64,
+    pack_recipe: &PackRecipe,
+    ingredients: &[RecipeIngredient],
+) -> Result<()> {
+    let existing: HashSet<i64> =
+        sqlx::query_scalar("SELECT ingredient_id FROM recipe_ingredients WHERE recipe_id = ?")
+            .bind(recipe_id)
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .collect();
+    let missing: Vec<RecipeIngredient> = ingredients
+        .iter()
+        .filter(|ingredient| !existing.contains(&ingredient.ingredient_id))
+        .cloned()
+        .collect();
+    insert_recipe_ingredients(conn, recipe_id, &missing).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE recipes SET
+            instructions = COALESCE(instructions, ?),
+            servings = COALESCE(servings, ?),
+            prep_minutes = COALESCE(prep_minutes, ?),
+            cook_minutes = COALESCE(cook_minutes, ?),
+            category = COALESCE(category, ?),
+            difficulty = COALESCE(difficulty, ?)
+        WHERE id = ?
+        "#,
+    )
+    .bind(&pack_recipe.instructions)
+    .bind(pack_recipe.servings)
+    .bind(pack_recipe.prep_minutes)
+    .bind(pack_recipe.cook_minutes)
+    .bind(pack_recipe.category.map(|category| category.as_str()))
+    .bind(pack_recipe.difficulty.map(|difficulty| difficulty.as_str()))
+    .bind(recipe_id)
+    .execute(&mut *conn)
+    .await?;
+
+    for tag in &pack_recipe.tags {
+        tag_recipe_in(conn, recipe_id, tag.trim()).await?;
+    }
+
+    let added: Vec<&str> = missing
+        .iter()
+        .map(|ingredient| ingredient.ingredient_name.as_str())
+        .collect();
+    record_change(
+        conn,
+        "recipe",
+        recipe_id,
+        AuditAction::Update,
+        None,
+        Some(&json!({ "merged_from_pack": pack_recipe.name, "added_ingredients": added })),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// ID of the oldest recipe called `name`, ignoring case
+async fn recipe_named(conn: &mut SqliteConnection, name: &str) -> Result<Option<i64>> {
+    let id = sqlx::query_scalar(
+        "SELECT id FROM recipes WHERE name = ? COLLATE NOCASE AND deleted_at IS NULL ORDER BY id LIMIT 1",
+    )
+    .bind(name)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(id)
+}
+
+/// "Name (2)", "Name (3)", ... whichever is free first
+async fn free_name(conn: &mut SqliteConnection, name: &str) -> Result<String> {
+    for n in 2.. {
+        let candidate = format!("{} ({})", name, n);
+        if recipe_named(conn, &candidate).await?.is_none() {
+            return Ok(candidate);
+        }
+    }
+    unreachable!("some numbered name is always free")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{
+        add_recipe_tag, create_ingredient, create_recipe, get_all_ingredients, list_recipes,
+        set_recipe_category, set_recipe_difficulty, set_recipe_times,
+    };
+    use crate::error::FeedMeError;
+    use crate::models::api::{Category, Difficulty};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    async fn add_recipe(pool: &SqlitePool, name: &str, ingredients: &[(&str, &str)]) -> i64 {
+        let mut lines = Vec::new();
+        for (ingredient, quantity) in ingredients {
+            let id = match create_ingredient(pool, ingredient).await {
+                Ok(id) => id,
+                Err(FeedMeError::DuplicateIngredient(_)) => {
+                    sqlx::query_scalar("SELECT id FROM ingredients WHERE name = ?")
+                        .bind(ingredient)
+                        .fetch_one(pool)
+                        .await
+                        .unwrap()
+                }
+                Err(err) => panic!("{err}"),
+            };
+            lines.push(RecipeIngredient {
+                ingredient_id: id,
+                ingredient_name: ingredient.to_string(),
+                quantity_unit: quantity.to_string(),
+                notes: None,
+                steps: Vec::new(),
+            });
+        }
+        create_recipe(
+            pool,
+            &Recipe {
+                id: 0,
+                name: name.to_string(),
+                instructions: None,
+                servings: None,
+                ingredients: lines,
+                created_at: String::new(),
+                timers: Vec::new(),
+                journal: Vec::new(),
+                tags: Vec::new(),
+                rating: None,
+                equipment: Vec::new(),
+                visibility: Visibility::default(),
+                source_url: None,
+                source_name: None,
+                nutrition: None,
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    /// A pack exported from a separate database
+    async fn friends_pack() -> RecipePack {
+        let theirs = crate::db::connect_in_memory().await.unwrap();
+        let id = add_recipe(
+            &theirs,
+            "Pancakes",
+            &[("flour", "1 cup"), ("buttermilk", "1 cup")],
+        )
+        .await;
+        add_recipe_tag(&theirs, id, "breakfast").await.unwrap();
+        set_recipe_times(&theirs, id, Some(10), Some(15))
+            .await
+            .unwrap();
+        set_recipe_category(&theirs, id, Some(Category::Breakfast))
+            .await
+            .unwrap();
+        set_recipe_difficulty(&theirs, id, Some(Difficulty::Easy))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE recipes SET instructions = 'Whisk and fry' WHERE id = ?")
+            .bind(id)
+            .execute(&theirs)
+            .await
+            .unwrap();
+
+        export_pack(&theirs, &[id], Some(b"shared")).await.unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_import_into_empty_database(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let pack = friends_pack().await;
+        assert_eq!(pack.contents.ingredients, vec!["buttermilk", "flour"]);
+
+        let import = import_pack(&pool, &pack, ConflictStrategy::Skip, Some(b"shared"))
+            .await
+            .unwrap();
+
+        assert_eq!(import.created.len(), 1);
+        assert_eq!(import.new_ingredients, vec!["flour", "buttermilk"]);
+        let recipe = get_recipe(&pool, import.created[0]).await.unwrap();
+        assert_eq!(recipe.instructions.as_deref(), Some("Whisk and fry"));
+        assert_eq!(recipe.ingredients.len(), 2);
+        assert_eq!(
+            get_recipe_metadata(&pool, recipe.id).await.unwrap(),
+            RecipeMetadata {
+                tags: vec!["breakfast".to_string()],
+                prep_minutes: Some(10),
+                cook_minutes: Some(15),
+                category: Some(Category::Breakfast),
+                difficulty: Some(Difficulty::Easy),
+            }
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_conflict_strategies(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let existing = add_recipe(&pool, "pancakes", &[("flour", "2 cups"), ("eggs", "2")]).await;
+        let pack = friends_pack().await;
+
+        let conflicts = pack_conflicts(&pool, &pack).await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].name, "Pancakes");
+        assert_eq!(conflicts[0].existing_id, existing);
+        assert_eq!(
+            conflicts[0].changes.to_string(),
+            "Name: pancakes -> Pancakes\n- 2 eggs\n+ 1 cup buttermilk\n~ flour: 2 cups -> 1 cup\nInstructions:\n  + Whisk and fry\n"
+        );
+
+        let skipped = import_pack(&pool, &pack, ConflictStrategy::Skip, None)
+            .await
+            .unwrap();
+        assert_eq!(skipped.skipped, vec!["Pancakes"]);
+        assert!(skipped.new_ingredients.is_empty());
+        assert_eq!(list_recipes(&pool).await.unwrap().len(), 1);
+        assert_eq!(get_all_ingredients(&pool).await.unwrap().len(), 2);
+
+        let renamed = import_pack(&pool, &pack, ConflictStrategy::Rename, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            renamed.renamed,
+            vec![("Pancakes".to_string(), "Pancakes (2)".to_string())]
+        );
+        assert_eq!(
+            get_recipe(&pool, renamed.created[0]).await.unwrap().name,
+            "Pancakes (2)"
+        );
+
+        let merged = import_pack(&pool, &pack, ConflictStrategy::Merge, None)
+            .await
+            .unwrap();
+        assert_eq!(merged.merged, vec![existing]);
+        let recipe = get_recipe(&pool, existing).await.unwrap();
+        let names: Vec<&str> = recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| ingredient.ingredient_name.as_str())
+            .collect();
+        // The existing quantity of flour wins; buttermilk is added
+        assert_eq!(names, vec!["flour", "eggs", "buttermilk"]);
+        assert_eq!(recipe.ingredients[0].quantity_unit, "2 cups");
+        assert_eq!(recipe.instructions.as_deref(), Some("Whisk and fry"));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_bad_pack_writes_nothing(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let mut pack = friends_pack().await;
+
+        let result = import_pack(&pool, &pack, ConflictStrategy::Skip, Some(b"wrong")).await;
+        assert!(matches!(result, Err(FeedMeError::InvalidPack(_))));
+
+        pack.contents.recipes[0].ingredients[1].name = " ".to_string();
+        let pack = RecipePack::seal(pack.contents, None).unwrap();
+        let result = import_pack(&pool, &pack, ConflictStrategy::Skip, None).await;
+        assert!(matches!(result, Err(FeedMeError::Validation(_))));
+
+        assert!(list_recipes(&pool).await.unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_import_reports_warnings(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let mut pack = friends_pack().await;
+        pack.contents.recipes[0].ingredients[1].quantity_unit = String::new();
+        let pack = RecipePack::seal(pack.contents, None).unwrap();
+
+        let import = import_pack(&pool, &pack, ConflictStrategy::Skip, None)
+            .await
+            .unwrap();
+
+        assert_eq!(import.created.len(), 1);
+        assert_eq!(import.recipe_warnings.len(), 1);
+        let (recipe, warnings) = &import.recipe_warnings[0];
+        assert_eq!(recipe, "Pancakes");
+        assert_eq!(warnings[0].to_string(), "buttermilk: no quantity given");
+
+        // Skipped recipes aren't checked
+        let again = import_pack(&pool, &pack, ConflictStrategy::Skip, None)
+            .await
+            .unwrap();
+        assert!(again.recipe_warnings.is_empty());
+    }
+}
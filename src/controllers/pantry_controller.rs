@@ -0,0 +1,798 @@
+use chrono::{Days, NaiveDate};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{FromRow, Row, SqliteConnection, SqlitePool};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::audit_controller::{delete_audited, record_create, record_update, row_snapshot};
+use super::retry::with_busy_retry;
+use super::substitution_controller::load_substitutions;
+use crate::error::{FeedMeError, Result};
+use crate::models::api::{
+    CookableRecipe, ExpiringRecipe, PantryItem, ShoppingListItem, Substitution,
+};
+use crate::quantity::{convert, format_quantity};
+use crate::validation::Validator;
+
+const PANTRY_ITEM_COLUMNS: &str = r#"
+    p.id,
+    p.ingredient_id,
+    i.name as ingredient_name,
+    p.amount,
+    p.unit,
+    p.expires_on,
+    p.is_staple,
+    p.min_amount,
+    i.icon
+"#;
+
+/// Columns recorded in a pantry item's audit entries
+const PANTRY_AUDIT_COLUMNS: &[&str] = &[
+    "ingredient_id",
+    "amount",
+    "unit",
+    "expires_on",
+    "is_staple",
+    "min_amount",
+];
+
+fn pantry_item_from_row(row: &SqliteRow) -> PantryItem {
+    PantryItem {
+        id: row.get("id"),
+        ingredient_id: row.get("ingredient_id"),
+        ingredient_name: row.get("ingredient_name"),
+        amount: row.get("amount"),
+        unit: row.get("unit"),
+        expires_on: row.get("expires_on"),
+        is_staple: row.get("is_staple"),
+        min_amount: row.get("min_amount"),
+        icon: row.get("icon"),
+    }
+}
+
+/// Add stock to the pantry
+/// The unit is stored lowercased. Returns the new pantry item ID
+pub async fn add_to_pantry(pool: &SqlitePool, item: &PantryItem) -> Result<i64> {
+    let mut validator = Validator::new();
+    if let Some(unit) = &item.unit {
+        validator.name("unit", unit);
+    }
+    validator.finish()?;
+
+    with_busy_retry(|| insert_pantry_item(pool, item)).await
+}
+
+async fn insert_pantry_item(pool: &SqlitePool, item: &PantryItem) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let id = insert_pantry_item_in(&mut tx, item).await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Add stock inside the caller's transaction, lowercasing the unit like
+/// `add_to_pantry`. Returns the new pantry item ID
+pub(crate) async fn insert_pantry_item_in(
+    conn: &mut SqliteConnection,
+    item: &PantryItem,
+) -> Result<i64> {
+    let id = sqlx::query(
+        "INSERT INTO pantry_items (ingredient_id, amount, unit, expires_on, is_staple, min_amount) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(item.ingredient_id)
+    .bind(item.amount)
+    .bind(item.unit.as_deref().map(str::to_lowercase))
+    .bind(item.expires_on)
+    .bind(item.is_staple)
+    .bind(item.min_amount)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+    record_create(
+        conn,
+        "pantry_item",
+        "pantry_items",
+        id,
+        PANTRY_AUDIT_COLUMNS,
+    )
+    .await?;
+
+    Ok(id)
+}
+
+/// Everything in the pantry ordered by ingredient name, soonest to expire first
+pub async fn list_pantry(pool: &SqlitePool) -> Result<Vec<PantryItem>> {
+    let items = sqlx::query(&format!(
+        r#"
+        SELECT {PANTRY_ITEM_COLUMNS}
+        FROM pantry_items p
+        JOIN ingredients i ON p.ingredient_id = i.id
+        ORDER BY i.name, p.expires_on IS NULL, p.expires_on, p.id
+        "#
+    ))
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(pantry_item_from_row)
+    .collect();
+
+    Ok(items)
+}
+
+/// Change how much of a pantry item is left
+pub async fn update_pantry_amount(pool: &SqlitePool, id: i64, amount: f64) -> Result<()> {
+    with_busy_retry(|| update_pantry_item_amount(pool, id, amount)).await
+}
+
+async fn update_pantry_item_amount(pool: &SqlitePool, id: i64, amount: f64) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "pantry_items", id, &["amount"]).await?;
+
+    let result = sqlx::query("UPDATE pantry_items SET amount = ? WHERE id = ?")
+        .bind(amount)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::PantryItemNotFound(id));
+    }
+    record_update(&mut tx, "pantry_item", "pantry_items", id, &["amount"], old).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Set or clear a pantry item's expiration date
+pub async fn set_pantry_expiration(
+    pool: &SqlitePool,
+    id: i64,
+    expires_on: Option<NaiveDate>,
+) -> Result<()> {
+    with_busy_retry(|| update_pantry_expiration(pool, id, expires_on)).await
+}
+
+async fn update_pantry_expiration(
+    pool: &SqlitePool,
+    id: i64,
+    expires_on: Option<NaiveDate>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "pantry_items", id, &["expires_on"]).await?;
+
+    let result = sqlx::query("UPDATE pantry_items SET expires_on = ? WHERE id = ?")
+        .bind(expires_on)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::PantryItemNotFound(id));
+    }
+    record_update(
+        &mut tx,
+        "pantry_item",
+        "pantry_items",
+        id,
+        &["expires_on"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Flag or unflag a pantry item as a staple
+pub async fn set_pantry_staple(pool: &SqlitePool, id: i64, is_staple: bool) -> Result<()> {
+    with_busy_retry(|| update_pantry_staple(pool, id, is_staple)).await
+}
+
+async fn update_pantry_staple(pool: &SqlitePool, id: i64, is_staple: bool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "pantry_items", id, &["is_staple"]).await?;
+
+    let result = sqlx::query("UPDATE pantry_items SET is_staple = ? WHERE id = ?")
+        .bind(is_staple)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::PantryItemNotFound(id));
+    }
+    record_update(
+        &mut tx,
+        "pantry_item",
+        "pantry_items",
+        id,
+        &["is_staple"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Set or clear the amount below which a pantry item is restocked
+pub async fn set_pantry_minimum(pool: &SqlitePool, id: i64, min_amount: Option<f64>) -> Result<()> {
+    with_busy_retry(|| update_pantry_minimum(pool, id, min_amount)).await
+}
+
+async fn update_pantry_minimum(pool: &SqlitePool, id: i64, min_amount: Option<f64>) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "pantry_items", id, &["min_amount"]).await?;
+
+    let result = sqlx::query("UPDATE pantry_items SET min_amount = ? WHERE id = ?")
+        .bind(min_amount)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::PantryItemNotFound(id));
+    }
+    record_update(
+        &mut tx,
+        "pantry_item",
+        "pantry_items",
+        id,
+        &["min_amount"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Items whose stock has fallen below their restock threshold
+/// Stock is summed across every pantry row for the ingredient that can be
+/// converted to the threshold's unit. The quantity is what's needed to get
+/// back up to the minimum. Ordered by ingredient name
+pub async fn restock_list(pool: &SqlitePool) -> Result<Vec<ShoppingListItem>> {
+    let items = list_pantry(pool).await?;
+
+    let mut restock: Vec<ShoppingListItem> = Vec::new();
+    for threshold in &items {
+        let Some(min_amount) = threshold.min_amount else {
+            continue;
+        };
+        if restock
+            .iter()
+            .any(|item| item.ingredient_name == threshold.ingredient_name)
+        {
+            continue;
+        }
+
+        let on_hand: f64 = items
+            .iter()
+            .filter(|item| item.ingredient_id == threshold.ingredient_id)
+            .filter_map(|item| {
+                convert(item.amount, item.unit.as_deref(), threshold.unit.as_deref())
+            })
+            .sum();
+
+        if on_hand < min_amount {
+            restock.push(ShoppingListItem {
+                ingredient_name: threshold.ingredient_name.clone(),
+                combined_quantity: format_quantity(min_amount - on_hand, threshold.unit.as_deref()),
+            });
+        }
+    }
+
+    Ok(restock)
+}
+
+/// Remove a pantry item
+/// Returns whether the item existed
+pub async fn remove_from_pantry(pool: &SqlitePool, id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_pantry_item(pool, id)).await
+}
+
+async fn delete_pantry_item(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let removed = delete_audited(
+        &mut tx,
+        "pantry_item",
+        "pantry_items",
+        id,
+        PANTRY_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(removed)
+}
+
+/// Pantry items expiring within `within_days` of `today`, soonest first
+/// Items that have already expired are included so they can be thrown out
+pub async fn expiring_soon(
+    pool: &SqlitePool,
+    today: NaiveDate,
+    within_days: u64,
+) -> Result<Vec<PantryItem>> {
+    let items = sqlx::query(&format!(
+        r#"
+        SELECT {PANTRY_ITEM_COLUMNS}
+        FROM pantry_items p
+        JOIN ingredients i ON p.ingredient_id = i.id
+        WHERE p.expires_on IS NOT NULL AND p.expires_on <= ?
+        ORDER BY p.expires_on, i.name, p.id
+        "#
+    ))
+    .bind(today + Days::new(within_days))
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(pantry_item_from_row)
+    .collect();
+
+    Ok(items)
+}
+
+/// Recipes that use pantry items expiring within `within_days` of `today`
+/// Already expired items are skipped. Recipes using the most expiring
+/// ingredients come first, then those whose ingredients expire soonest
+pub async fn recipes_using_expiring(
+    pool: &SqlitePool,
+    today: NaiveDate,
+    within_days: u64,
+) -> Result<Vec<ExpiringRecipe>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            r.id as recipe_id,
+            r.name as recipe_name,
+            i.name as ingredient_name,
+            MIN(p.expires_on) as expires_on
+        FROM pantry_items p
+        JOIN ingredients i ON p.ingredient_id = i.id
+        JOIN recipe_ingredients ri ON ri.ingredient_id = p.ingredient_id
+        JOIN recipes r ON ri.recipe_id = r.id
+        WHERE p.expires_on >= ? AND p.expires_on <= ? AND r.deleted_at IS NULL
+        GROUP BY r.id, i.id
+        ORDER BY r.id, expires_on, i.name
+        "#,
+    )
+    .bind(today)
+    .bind(today + Days::new(within_days))
+    .fetch_all(pool)
+    .await?;
+
+    let mut recipes: BTreeMap<i64, ExpiringRecipe> = BTreeMap::new();
+    for row in &rows {
+        let expires_on: NaiveDate = row.get("expires_on");
+        recipes
+            .entry(row.get("recipe_id"))
+            .or_insert_with(|| ExpiringRecipe {
+                recipe_id: row.get("recipe_id"),
+                recipe_name: row.get("recipe_name"),
+                uses: Vec::new(),
+                soonest: expires_on,
+            })
+            .uses
+            .push(row.get("ingredient_name"));
+    }
+
+    let mut recipes: Vec<ExpiringRecipe> = recipes.into_values().collect();
+    recipes.sort_by(|a, b| {
+        b.uses
+            .len()
+            .cmp(&a.uses.len())
+            .then(a.soonest.cmp(&b.soonest))
+            .then(a.recipe_id.cmp(&b.recipe_id))
+    });
+
+    Ok(recipes)
+}
+
+/// Recipes whose every ingredient is covered by current pantry stock
+/// Ordered by name
+pub async fn cookable_recipes(pool: &SqlitePool) -> Result<Vec<CookableRecipe>> {
+    Ok(pantry_coverage(pool)
+        .await?
+        .into_iter()
+        .filter(|recipe| recipe.missing.is_empty())
+        .collect())
+}
+
+/// Recipes missing between one and `max_missing` ingredients from the pantry
+/// Fewest missing first, then by name
+pub async fn almost_cookable_recipes(
+    pool: &SqlitePool,
+    max_missing: usize,
+) -> Result<Vec<CookableRecipe>> {
+    let mut recipes: Vec<CookableRecipe> = pantry_coverage(pool)
+        .await?
+        .into_iter()
+        .filter(|recipe| (1..=max_missing).contains(&recipe.missing.len()))
+        .collect();
+    recipes.sort_by_key(|recipe| recipe.missing.len());
+
+    Ok(recipes)
+}
+
+/// Check every recipe against the pantry, ordered by recipe name
+/// Stock in a convertible unit is summed and compared with the recipe's
+/// amount. When the amounts can't be compared (no parsed amount, or units of
+/// different kinds like cups vs grams) having any stock counts as enough.
+/// Missing ingredients are offered substitutions the pantry can make
+async fn pantry_coverage(pool: &SqlitePool) -> Result<Vec<CookableRecipe>> {
+    let mut stock: HashMap<i64, Vec<(f64, Option<String>)>> = HashMap::new();
+    for row in sqlx::query("SELECT ingredient_id, amount, unit FROM pantry_items WHERE amount > 0")
+        .fetch_all(pool)
+        .await?
+    {
+        stock
+            .entry(row.get("ingredient_id"))
+            .or_default()
+            .push((row.get("amount"), row.get("unit")));
+    }
+
+    let stocked: HashSet<i64> = stock.keys().copied().collect();
+    let mut substitutions: HashMap<i64, Vec<Substitution>> = HashMap::new();
+    for substitution in load_substitutions(pool, None).await? {
+        if substitution.can_make(&stocked) {
+            substitutions
+                .entry(substitution.ingredient_id)
+                .or_default()
+                .push(substitution);
+        }
+    }
+
+    let rows = sqlx::query_as::<_, CoverageRow>(
+        r#"
+        SELECT
+            r.id as recipe_id,
+            r.name as recipe_name,
+            ri.ingredient_id,
+            i.name as ingredient_name,
+            ri.quantity_unit,
+            ri.amount,
+            ri.unit
+        FROM recipes r
+        LEFT JOIN recipe_ingredients ri ON ri.recipe_id = r.id
+        LEFT JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE r.deleted_at IS NULL
+        ORDER BY r.name, r.id, ri.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(cover_recipes(&rows, &stock, &substitutions))
+}
+
+/// A recipe joined with one of its ingredient lines; the ingredient columns
+/// are None for recipes without ingredients
+#[derive(Debug, FromRow)]
+pub(crate) struct CoverageRow {
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    pub ingredient_id: Option<i64>,
+    pub ingredient_name: Option<String>,
+    pub quantity_unit: Option<String>,
+    pub amount: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// Check recipes against pantry stock, as `pantry_coverage` does
+/// `rows` must be grouped by recipe; `stock` maps ingredient IDs to their
+/// amounts and units, and `substitutions` to the ones the pantry can make
+pub(crate) fn cover_recipes(
+    rows: &[CoverageRow],
+    stock: &HashMap<i64, Vec<(f64, Option<String>)>>,
+    substitutions: &HashMap<i64, Vec<Substitution>>,
+) -> Vec<CookableRecipe> {
+    let mut recipes: Vec<CookableRecipe> = Vec::new();
+    for row in rows {
+        if recipes
+            .last()
+            .is_none_or(|recipe| recipe.recipe_id != row.recipe_id)
+        {
+            recipes.push(CookableRecipe {
+                recipe_id: row.recipe_id,
+                recipe_name: row.recipe_name.clone(),
+                missing: Vec::new(),
+                to_buy: Vec::new(),
+                substitutions: Vec::new(),
+            });
+        }
+
+        let Some(ingredient_id) = row.ingredient_id else {
+            continue;
+        };
+        let unit = row.unit.as_deref();
+
+        // What to buy, None when the pantry covers it
+        let to_buy = match (stock.get(&ingredient_id), row.amount) {
+            (None, _) => Some(row.quantity_unit.clone().unwrap_or_default()),
+            (Some(_), None) => None,
+            (Some(on_hand), Some(needed)) => {
+                let comparable: Vec<f64> = on_hand
+                    .iter()
+                    .filter_map(|(amount, from)| convert(*amount, from.as_deref(), unit))
+                    .collect();
+                let have: f64 = comparable.iter().sum();
+                (!comparable.is_empty() && have < needed - 1e-9)
+                    .then(|| format_quantity(needed - have, unit))
+            }
+        };
+
+        if let Some(quantity) = to_buy {
+            let ingredient_name = row.ingredient_name.clone().unwrap_or_default();
+            let recipe = recipes.last_mut().expect("pushed above");
+            recipe.missing.push(ingredient_name.clone());
+            recipe.to_buy.push(ShoppingListItem {
+                ingredient_name,
+                combined_quantity: quantity,
+            });
+            if let Some(options) = substitutions.get(&ingredient_id) {
+                recipe.substitutions.extend(options.iter().cloned());
+            }
+        }
+    }
+
+    recipes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{add_substitution, create_ingredient, create_recipe};
+    use crate::models::api::{Recipe, RecipeIngredient, Visibility};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    async fn ingredient_id(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query_scalar("SELECT id FROM ingredients WHERE name = ?")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn stock(
+        pool: &SqlitePool,
+        ingredient_id: i64,
+        amount: f64,
+        unit: Option<&str>,
+        expires_on: Option<NaiveDate>,
+    ) -> i64 {
+        let item = PantryItem {
+            id: 0,
+            ingredient_id,
+            ingredient_name: String::new(),
+            amount,
+            unit: unit.map(str::to_string),
+            expires_on,
+            is_staple: false,
+            icon: None,
+            min_amount: None,
+        };
+        add_to_pantry(pool, &item).await.unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_pantry_crud(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        crate::seed::seed_demo_data(&pool).await.unwrap();
+        let milk = ingredient_id(&pool, "milk").await;
+        let flour = ingredient_id(&pool, "flour").await;
+
+        let carton = stock(&pool, milk, 1.0, Some("L"), Some(date(20))).await;
+        stock(&pool, milk, 2.0, Some("l"), Some(date(18))).await;
+        let bag = stock(&pool, flour, 1.0, Some("kg"), None).await;
+
+        update_pantry_amount(&pool, carton, 0.5).await.unwrap();
+        set_pantry_expiration(&pool, bag, Some(date(30)))
+            .await
+            .unwrap();
+        set_pantry_staple(&pool, bag, true).await.unwrap();
+
+        let pantry = list_pantry(&pool).await.unwrap();
+        let summary: Vec<String> = pantry.iter().map(|item| item.to_string()).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "flour 1 kg (expires 2026-10-30) [staple]",
+                "milk 2 l (expires 2026-10-18)",
+                "milk 0.5 l (expires 2026-10-20)",
+            ]
+        );
+
+        assert!(remove_from_pantry(&pool, carton).await.unwrap());
+        assert!(!remove_from_pantry(&pool, carton).await.unwrap());
+        assert_eq!(list_pantry(&pool).await.unwrap().len(), 2);
+        assert!(matches!(
+            update_pantry_amount(&pool, carton, 1.0).await,
+            Err(FeedMeError::PantryItemNotFound(id)) if id == carton
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_restock_list(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        crate::seed::seed_demo_data(&pool).await.unwrap();
+        let flour = ingredient_id(&pool, "flour").await;
+        let eggs = ingredient_id(&pool, "eggs").await;
+        let salt = ingredient_id(&pool, "salt").await;
+
+        // 1.2 kg of flour against a 2 kg minimum, counted across both bags
+        let bag = stock(&pool, flour, 1.0, Some("kg"), None).await;
+        stock(&pool, flour, 200.0, Some("g"), None).await;
+        set_pantry_minimum(&pool, bag, Some(2.0)).await.unwrap();
+
+        let carton = stock(&pool, eggs, 6.0, None, None).await;
+        set_pantry_minimum(&pool, carton, Some(6.0)).await.unwrap();
+
+        let jar = stock(&pool, salt, 0.0, Some("jar"), None).await;
+        set_pantry_minimum(&pool, jar, Some(1.0)).await.unwrap();
+
+        let restock = restock_list(&pool).await.unwrap();
+        let summary: Vec<(&str, &str)> = restock
+            .iter()
+            .map(|item| {
+                (
+                    item.ingredient_name.as_str(),
+                    item.combined_quantity.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(summary, vec![("flour", "0.8 kg"), ("salt", "1 jar")]);
+
+        set_pantry_minimum(&pool, bag, None).await.unwrap();
+        assert_eq!(restock_list(&pool).await.unwrap().len(), 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_cookable_recipes(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let flour = create_ingredient(&pool, "flour").await.unwrap();
+        let water = create_ingredient(&pool, "water").await.unwrap();
+        let salt = create_ingredient(&pool, "salt").await.unwrap();
+        let yeast = create_ingredient(&pool, "yeast").await.unwrap();
+        let recipe = |name: &str, items: &[(i64, &str)]| Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: items
+                .iter()
+                .map(|(id, quantity)| RecipeIngredient {
+                    ingredient_id: *id,
+                    ingredient_name: String::new(),
+                    quantity_unit: quantity.to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                })
+                .collect(),
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        create_recipe(
+            &pool,
+            &recipe(
+                "Flatbread",
+                &[(flour, "500 g"), (water, "1 cup"), (salt, "a pinch")],
+            ),
+        )
+        .await
+        .unwrap();
+        create_recipe(
+            &pool,
+            &recipe(
+                "Bread",
+                &[(flour, "2 kg"), (water, "2 cups"), (yeast, "1 tsp")],
+            ),
+        )
+        .await
+        .unwrap();
+        create_recipe(
+            &pool,
+            &recipe("Pizza", &[(flour, "500 g"), (yeast, "2 tsp")]),
+        )
+        .await
+        .unwrap();
+
+        // 1 kg of flour across two bags, plenty of water, salt by the jar
+        stock(&pool, flour, 0.6, Some("kg"), None).await;
+        stock(&pool, flour, 400.0, Some("g"), None).await;
+        stock(&pool, water, 1.0, Some("l"), None).await;
+        stock(&pool, salt, 1.0, Some("jar"), None).await;
+        stock(&pool, yeast, 0.0, Some("tsp"), None).await;
+
+        let cookable: Vec<String> = cookable_recipes(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|recipe| recipe.recipe_name)
+            .collect();
+        assert_eq!(cookable, vec!["Flatbread"]);
+
+        let almost = almost_cookable_recipes(&pool, 2).await.unwrap();
+        assert_eq!(almost.len(), 2);
+        assert_eq!(almost[0].recipe_name, "Pizza");
+        assert_eq!(almost[0].missing, vec!["yeast"]);
+        assert_eq!(almost[1].recipe_name, "Bread");
+        assert_eq!(almost[1].missing, vec!["flour", "yeast"]);
+        let to_buy: Vec<String> = almost[1]
+            .to_buy
+            .iter()
+            .map(|item| item.to_string())
+            .collect();
+        assert_eq!(to_buy, vec!["flour: 1 kg", "yeast: 1 tsp"]);
+        assert!(almost[0].substitutions.is_empty());
+
+        assert!(almost_cookable_recipes(&pool, 0).await.unwrap().is_empty());
+
+        let starter = create_ingredient(&pool, "sourdough starter").await.unwrap();
+        add_substitution(&pool, yeast, &[starter], Some("1 cup per 2 tsp"))
+            .await
+            .unwrap();
+        stock(&pool, starter, 1.0, Some("cup"), None).await;
+
+        let almost = almost_cookable_recipes(&pool, 1).await.unwrap();
+        assert_eq!(
+            almost[0].to_string(),
+            "Pizza - buy:\n  yeast: 2 tsp\n  or substitute yeast -> sourdough starter (1 cup per 2 tsp)\n"
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_expiring_soon(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        crate::seed::seed_demo_data(&pool).await.unwrap();
+        let milk = ingredient_id(&pool, "milk").await;
+        let eggs = ingredient_id(&pool, "eggs").await;
+        let basil = ingredient_id(&pool, "basil").await;
+        let salt = ingredient_id(&pool, "salt").await;
+
+        stock(&pool, milk, 1.0, Some("l"), Some(date(18))).await;
+        stock(&pool, eggs, 6.0, None, Some(date(16))).await;
+        stock(&pool, basil, 1.0, Some("bunch"), Some(date(25))).await;
+        stock(&pool, salt, 500.0, Some("g"), None).await;
+
+        let expiring: Vec<String> = expiring_soon(&pool, date(17), 3)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.ingredient_name)
+            .collect();
+        assert_eq!(expiring, vec!["eggs", "milk"]);
+
+        // The expired eggs aren't worth planning around
+        let recipes = recipes_using_expiring(&pool, date(17), 10).await.unwrap();
+        assert!(!recipes.is_empty());
+        assert!(
+            recipes
+                .iter()
+                .all(|recipe| !recipe.uses.contains(&"eggs".to_string()))
+        );
+        assert!(
+            recipes
+                .iter()
+                .any(|recipe| recipe.uses.contains(&"basil".to_string()))
+        );
+        for pair in recipes.windows(2) {
+            assert!(pair[0].uses.len() >= pair[1].uses.len());
+        }
+    }
+}
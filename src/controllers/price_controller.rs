@@ -0,0 +1,519 @@
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+
+use super::audit_controller::{delete_audited, record_create};
+use super::custom_unit_controller::{ingredient_units, list_custom_units};
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, Result};
+use crate::models::api::{CostLine, IngredientPrice, RecipeCost, ShoppingListEntry};
+use crate::quantity::{format_quantity, parse_quantity};
+use crate::validation::Validator;
+
+const PRICE_COLUMNS: &str = r#"
+    p.id,
+    p.ingredient_id,
+    i.name as ingredient_name,
+    p.price,
+    p.amount,
+    p.unit,
+    p.store,
+    p.observed_on
+"#;
+
+/// Columns recorded in a price's audit entries
+const PRICE_AUDIT_COLUMNS: &[&str] = &[
+    "ingredient_id",
+    "price",
+    "amount",
+    "unit",
+    "store",
+    "observed_on",
+];
+
+fn price_from_row(row: &SqliteRow) -> IngredientPrice {
+    IngredientPrice {
+        id: row.get("id"),
+        ingredient_id: row.get("ingredient_id"),
+        ingredient_name: row.get("ingredient_name"),
+        price: row.get("price"),
+        amount: row.get("amount"),
+        unit: row.get("unit"),
+        store: row.get("store"),
+        observed_on: row.get("observed_on"),
+    }
+}
+
+/// Record what an ingredient cost
+/// The unit is stored lowercased. Returns the new price ID
+pub async fn add_price(pool: &SqlitePool, price: &IngredientPrice) -> Result<i64> {
+    let mut validator = Validator::new();
+    if let Some(unit) = &price.unit {
+        validator.name("unit", unit);
+    }
+    let store = price
+        .store
+        .as_deref()
+        .map(|store| validator.name("store", store));
+    validator.finish()?;
+
+    with_busy_retry(|| insert_price(pool, price, store)).await
+}
+
+async fn insert_price(
+    pool: &SqlitePool,
+    price: &IngredientPrice,
+    store: Option<&str>,
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let id = sqlx::query(
+        "INSERT INTO ingredient_prices (ingredient_id, price, amount, unit, store, observed_on) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(price.ingredient_id)
+    .bind(price.price)
+    .bind(price.amount)
+    .bind(price.unit.as_deref().map(str::to_lowercase))
+    .bind(store)
+    .bind(price.observed_on)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+    record_create(
+        &mut tx,
+        "price",
+        "ingredient_prices",
+        id,
+        PRICE_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Every price recorded for an ingredient, newest first
+pub async fn list_prices(pool: &SqlitePool, ingredient_id: i64) -> Result<Vec<IngredientPrice>> {
+    let prices = sqlx::query(&format!(
+        r#"
+        SELECT {PRICE_COLUMNS}
+        FROM ingredient_prices p
+        JOIN ingredients i ON p.ingredient_id = i.id
+        WHERE p.ingredient_id = ?
+        ORDER BY p.observed_on DESC, p.id DESC
+        "#
+    ))
+    .bind(ingredient_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(price_from_row)
+    .collect();
+
+    Ok(prices)
+}
+
+/// Remove a recorded price
+/// Returns whether it existed
+pub async fn delete_price(pool: &SqlitePool, id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_price_row(pool, id)).await
+}
+
+async fn delete_price_row(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let deleted = delete_audited(
+        &mut tx,
+        "price",
+        "ingredient_prices",
+        id,
+        PRICE_AUDIT_COLUMNS,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(deleted)
+}
+
+/// The newest price of every ingredient that has one, by ingredient ID
+pub(crate) async fn latest_prices(pool: &SqlitePool) -> Result<HashMap<i64, IngredientPrice>> {
+    let mut latest = HashMap::new();
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {PRICE_COLUMNS}
+        FROM ingredient_prices p
+        JOIN ingredients i ON p.ingredient_id = i.id
+        ORDER BY p.ingredient_id, p.observed_on DESC, p.id DESC
+        "#
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    for row in &rows {
+        latest
+            .entry(row.get("ingredient_id"))
+            .or_insert_with(|| price_from_row(row));
+    }
+
+    Ok(latest)
+}
+
+/// Estimate what a recipe costs to cook at the latest recorded prices
+/// Quantities are scaled from the recipe's servings to `servings` when both
+/// are known. Ingredients without a price, or whose quantity can't be
+/// converted to the priced unit, are listed in `unpriced` rather than guessed
+pub async fn estimate_recipe_cost(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    servings: Option<i64>,
+) -> Result<RecipeCost> {
+    let recipe =
+        sqlx::query("SELECT name, servings FROM recipes WHERE id = ? AND deleted_at IS NULL")
+            .bind(recipe_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(FeedMeError::RecipeNotFound(recipe_id))?;
+    let makes: Option<i64> = recipe.get("servings");
+    let scale = match (servings, makes) {
+        (Some(wanted), Some(makes)) if makes > 0 => wanted as f64 / makes as f64,
+        _ => 1.0,
+    };
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            i.id as ingredient_id,
+            i.name as ingredient_name,
+            i.grams_per_ml,
+            ri.amount,
+            ri.unit
+        FROM recipe_ingredients ri
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE ri.recipe_id = ?
+        ORDER BY ri.id
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?;
+
+    let prices = latest_prices(pool).await?;
+    let custom_units = list_custom_units(pool).await?;
+
+    let mut lines = Vec::new();
+    let mut unpriced = Vec::new();
+    for row in &rows {
+        let ingredient_id: i64 = row.get("ingredient_id");
+        let ingredient_name: String = row.get("ingredient_name");
+        let amount: Option<f64> = row.get("amount");
+        let unit: Option<String> = row.get("unit");
+
+        let units = ingredient_units(&custom_units, ingredient_id, row.get("grams_per_ml"));
+        let cost = amount.map(|amount| amount * scale).and_then(|amount| {
+            let price = prices.get(&ingredient_id)?;
+            price
+                .cost_of(&units, amount, unit.as_deref())
+                .map(|cost| (amount, cost))
+        });
+
+        match cost {
+            Some((amount, cost)) => lines.push(CostLine {
+                ingredient_name,
+                quantity: format_quantity(amount, unit.as_deref()),
+                cost,
+            }),
+            None => unpriced.push(ingredient_name),
+        }
+    }
+
+    Ok(RecipeCost {
+        recipe_id,
+        recipe_name: recipe.get("name"),
+        servings: servings.or(makes),
+        total: lines.iter().fold(0.0, |total, line| total + line.cost),
+        lines,
+        unpriced,
+    })
+}
+
+/// Fill in `estimated_cost` on shopping list items from the latest prices
+/// Combined quantities ("2 cups + 100 g") are priced part by part; items with
+/// any part that can't be priced are left at None
+pub(crate) async fn estimate_item_costs(
+    pool: &SqlitePool,
+    items: &mut [ShoppingListEntry],
+) -> Result<()> {
+    let prices = latest_prices(pool).await?;
+    if prices.is_empty() {
+        return Ok(());
+    }
+
+    let densities: HashMap<i64, Option<f64>> =
+        sqlx::query("SELECT id, grams_per_ml FROM ingredients")
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| (row.get("id"), row.get("grams_per_ml")))
+            .collect();
+    let custom_units = list_custom_units(pool).await?;
+
+    for item in items {
+        let Some(price) = prices.get(&item.ingredient_id) else {
+            continue;
+        };
+        let units = ingredient_units(
+            &custom_units,
+            item.ingredient_id,
+            densities.get(&item.ingredient_id).copied().flatten(),
+        );
+
+        item.estimated_cost = item
+            .quantity
+            .split(" + ")
+            .map(|part| {
+                let parsed = parse_quantity(part)?;
+                price.cost_of(&units, parsed.amount, parsed.unit.as_deref())
+            })
+            .sum();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{
+        create_ingredient, create_recipe, get_shopping_list, plan_shopping_list, save_meal_plan,
+        save_shopping_list,
+    };
+    use crate::models::api::{MealPlan, PlannedMeal, Recipe, RecipeIngredient, Visibility};
+    use crate::models::test_fixtures::test_db;
+    use chrono::NaiveDate;
+    use rstest::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    async fn record(
+        pool: &SqlitePool,
+        ingredient_id: i64,
+        price: f64,
+        per: (f64, Option<&str>),
+        store: Option<&str>,
+        day: u32,
+    ) -> i64 {
+        let price = IngredientPrice {
+            id: 0,
+            ingredient_id,
+            ingredient_name: String::new(),
+            price,
+            amount: per.0,
+            unit: per.1.map(str::to_string),
+            store: store.map(str::to_string),
+            observed_on: date(day),
+        };
+        add_price(pool, &price).await.unwrap()
+    }
+
+    fn recipe(name: &str, servings: i64, ingredients: &[(i64, &str)]) -> Recipe {
+        Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: None,
+            servings: Some(servings),
+            ingredients: ingredients
+                .iter()
+                .map(|(ingredient_id, quantity)| RecipeIngredient {
+                    ingredient_id: *ingredient_id,
+                    ingredient_name: String::new(),
+                    quantity_unit: quantity.to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                })
+                .collect(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_price_crud(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let flour = create_ingredient(&pool, "flour").await.unwrap();
+
+        let old = record(&pool, flour, 2.0, (1.0, Some("KG")), Some(" Aldi "), 1).await;
+        record(&pool, flour, 2.5, (1.0, Some("kg")), None, 10).await;
+
+        let prices: Vec<String> = list_prices(&pool, flour)
+            .await
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            prices,
+            vec![
+                "flour 2.50 for 1 kg (2026-10-10)",
+                "flour 2.00 for 1 kg at Aldi (2026-10-01)",
+            ]
+        );
+
+        assert!(delete_price(&pool, old).await.unwrap());
+        assert!(!delete_price(&pool, old).await.unwrap());
+        assert_eq!(list_prices(&pool, flour).await.unwrap().len(), 1);
+
+        let negative = IngredientPrice {
+            price: -1.0,
+            ..list_prices(&pool, flour).await.unwrap().remove(0)
+        };
+        assert!(matches!(
+            add_price(&pool, &negative).await,
+            Err(FeedMeError::ConstraintViolation(_))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_estimate_recipe_cost(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let flour = create_ingredient(&pool, "flour").await.unwrap();
+        let milk = create_ingredient(&pool, "milk").await.unwrap();
+        let eggs = create_ingredient(&pool, "eggs").await.unwrap();
+        let salt = create_ingredient(&pool, "salt").await.unwrap();
+        let saffron = create_ingredient(&pool, "saffron").await.unwrap();
+
+        record(&pool, flour, 9.0, (1.0, Some("kg")), None, 1).await;
+        record(&pool, flour, 2.0, (1.0, Some("kg")), None, 5).await;
+        record(&pool, milk, 1.2, (1.0, Some("l")), None, 5).await;
+        record(&pool, eggs, 3.0, (6.0, None), None, 5).await;
+        record(&pool, saffron, 8.0, (1.0, Some("g")), None, 5).await;
+
+        let pancakes = create_recipe(
+            &pool,
+            &recipe(
+                "Pancakes",
+                4,
+                &[
+                    (flour, "250 g"),
+                    (milk, "500 ml"),
+                    (eggs, "2"),
+                    (salt, "a pinch"),
+                    (saffron, "1 tsp"),
+                ],
+            ),
+        )
+        .await
+        .unwrap();
+
+        let cost = estimate_recipe_cost(&pool, pancakes, None).await.unwrap();
+        assert!((cost.total - 2.1).abs() < 1e-9);
+        assert_eq!(cost.servings, Some(4));
+        assert!((cost.per_serving().unwrap() - 0.525).abs() < 1e-9);
+        assert_eq!(cost.unpriced, vec!["salt", "saffron"]);
+
+        let doubled = estimate_recipe_cost(&pool, pancakes, Some(8))
+            .await
+            .unwrap();
+        assert!((doubled.total - 4.2).abs() < 1e-9);
+        assert_eq!(doubled.lines[0].quantity, "500 g");
+        assert_eq!(
+            doubled.to_string(),
+            "Pancakes\n  500 g flour: 1.00\n  1000 ml milk: 1.20\n  4 eggs: 2.00\n\
+             Total: 4.20 (0.53 per serving, serves 8)\nNo price for: salt, saffron\n"
+        );
+
+        assert!(matches!(
+            estimate_recipe_cost(&pool, 999, None).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_shopping_list_costs(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let flour = create_ingredient(&pool, "flour").await.unwrap();
+        let butter = create_ingredient(&pool, "butter").await.unwrap();
+        let eggs = create_ingredient(&pool, "eggs").await.unwrap();
+        let basil = create_ingredient(&pool, "basil").await.unwrap();
+
+        record(&pool, flour, 2.0, (1.0, Some("kg")), None, 5).await;
+        record(&pool, butter, 3.0, (250.0, Some("g")), None, 5).await;
+        record(&pool, eggs, 3.0, (6.0, None), None, 5).await;
+
+        let bread = create_recipe(
+            &pool,
+            &recipe(
+                "Bread",
+                1,
+                &[
+                    (flour, "500 g"),
+                    (butter, "250 g"),
+                    (eggs, "2"),
+                    (basil, "1 bunch"),
+                ],
+            ),
+        )
+        .await
+        .unwrap();
+        let cake = create_recipe(
+            &pool,
+            &recipe(
+                "Cake",
+                1,
+                &[(flour, "0.5 kg"), (butter, "1 cup"), (eggs, "4")],
+            ),
+        )
+        .await
+        .unwrap();
+
+        let plan = MealPlan {
+            id: 0,
+            name: None,
+            start_date: date(12),
+            entries: [bread, cake]
+                .into_iter()
+                .map(|recipe_id| PlannedMeal {
+                    date: date(12),
+                    meal: "dinner".to_string(),
+                    recipe_id,
+                    recipe_name: String::new(),
+                    servings: 1.0,
+                    note: None,
+                })
+                .collect(),
+        };
+        let plan_id = save_meal_plan(&pool, &plan).await.unwrap();
+
+        // Butter's cup doesn't convert to grams without a density
+        let list = plan_shopping_list(&pool, plan_id, &[eggs]).await.unwrap();
+        let costs: Vec<(&str, Option<f64>)> = list
+            .items
+            .iter()
+            .map(|item| (item.ingredient_name.as_str(), item.estimated_cost))
+            .collect();
+        assert_eq!(
+            costs,
+            vec![
+                ("basil", None),
+                ("butter", None),
+                ("eggs", Some(3.0)),
+                ("flour", Some(2.0)),
+            ]
+        );
+        assert_eq!(list.estimated_total(), 2.0);
+
+        let saved = save_shopping_list(&pool, &list).await.unwrap();
+        let list = get_shopping_list(&pool, saved).await.unwrap();
+        assert_eq!(list.items[3].estimated_cost, Some(2.0));
+    }
+}
@@ -0,0 +1,182 @@
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+
+use crate::error::Result;
+use crate::models::api::{DataIssue, DataQualityReport, RecipeIssues};
+
+/// Recipes missing key data: instructions, servings or ingredients, or with
+/// quantities that couldn't be parsed
+/// Meant as a checklist after a bulk import; recipes are ordered by name and
+/// their unparseable quantities by ingredient order
+pub async fn data_quality_report(pool: &SqlitePool) -> Result<DataQualityReport> {
+    let mut unparseable: HashMap<i64, Vec<DataIssue>> = HashMap::new();
+    let rows = sqlx::query(
+        r#"
+        SELECT ri.recipe_id, i.name, ri.quantity_unit
+        FROM recipe_ingredients ri
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE ri.amount IS NULL
+        ORDER BY ri.recipe_id, ri.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    for row in &rows {
+        unparseable
+            .entry(row.get("recipe_id"))
+            .or_default()
+            .push(DataIssue::UnparseableQuantity {
+                ingredient_name: row.get("name"),
+                quantity_unit: row.get("quantity_unit"),
+            });
+    }
+
+    let recipes = sqlx::query(
+        r#"
+        SELECT
+            r.id,
+            r.name,
+            TRIM(COALESCE(r.instructions, '')) = '' as no_instructions,
+            r.servings IS NULL as no_servings,
+            NOT EXISTS (SELECT 1 FROM recipe_ingredients ri WHERE ri.recipe_id = r.id) as no_ingredients
+        FROM recipes r
+        WHERE r.deleted_at IS NULL
+        ORDER BY r.name, r.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let checked = recipes.len();
+    let recipes = recipes
+        .iter()
+        .filter_map(|row| {
+            let recipe_id: i64 = row.get("id");
+            let mut issues = Vec::new();
+            for (missing, issue) in [
+                ("no_instructions", DataIssue::NoInstructions),
+                ("no_servings", DataIssue::NoServings),
+                ("no_ingredients", DataIssue::NoIngredients),
+            ] {
+                if row.get::<bool, _>(missing) {
+                    issues.push(issue);
+                }
+            }
+            issues.extend(unparseable.remove(&recipe_id).unwrap_or_default());
+
+            (!issues.is_empty()).then(|| RecipeIssues {
+                recipe_id,
+                recipe_name: row.get("name"),
+                issues,
+            })
+        })
+        .collect();
+
+    Ok(DataQualityReport { checked, recipes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{create_ingredient, create_recipe};
+    use crate::models::api::{Recipe, RecipeIngredient, Visibility};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    async fn add_recipe(
+        pool: &SqlitePool,
+        name: &str,
+        instructions: Option<&str>,
+        servings: Option<i64>,
+        ingredients: &[(i64, &str)],
+    ) -> i64 {
+        let recipe = Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: instructions.map(str::to_string),
+            servings,
+            ingredients: ingredients
+                .iter()
+                .map(|&(ingredient_id, quantity)| RecipeIngredient {
+                    ingredient_id,
+                    ingredient_name: String::new(),
+                    quantity_unit: quantity.to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                })
+                .collect(),
+            tags: Vec::new(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        create_recipe(pool, &recipe).await.unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_data_quality_report(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let flour = create_ingredient(&pool, "flour").await.unwrap();
+        let salt = create_ingredient(&pool, "salt").await.unwrap();
+
+        add_recipe(&pool, "Bread", Some("Knead"), Some(1), &[(flour, "500 g")]).await;
+        let soup = add_recipe(
+            &pool,
+            "Soup",
+            Some("  "),
+            None,
+            &[(flour, "a handful"), (salt, "")],
+        )
+        .await;
+        let toast = add_recipe(&pool, "Toast", Some("Toast it"), Some(1), &[]).await;
+
+        let report = data_quality_report(&pool).await.unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(
+            report.recipes,
+            vec![
+                RecipeIssues {
+                    recipe_id: soup,
+                    recipe_name: "Soup".to_string(),
+                    issues: vec![
+                        DataIssue::NoInstructions,
+                        DataIssue::NoServings,
+                        DataIssue::UnparseableQuantity {
+                            ingredient_name: "flour".to_string(),
+                            quantity_unit: "a handful".to_string(),
+                        },
+                        DataIssue::UnparseableQuantity {
+                            ingredient_name: "salt".to_string(),
+                            quantity_unit: String::new(),
+                        },
+                    ],
+                },
+                RecipeIssues {
+                    recipe_id: toast,
+                    recipe_name: "Toast".to_string(),
+                    issues: vec![DataIssue::NoIngredients],
+                },
+            ]
+        );
+        assert!(report.to_string().contains(
+            "\n#2 Soup\n  - no instructions\n  - servings unknown\n  - flour: can't read 'a handful'\n  - salt: no quantity\n"
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_empty_database_is_clean(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let report = data_quality_report(&pool).await.unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.to_string(), "0 of 0 recipes need attention\n");
+    }
+}
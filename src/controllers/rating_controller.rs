@@ -0,0 +1,223 @@
+use serde_json::json;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+
+use super::audit_controller::record_change;
+use super::retry::with_busy_retry;
+use super::search_controller::push_recipe_filter;
+use crate::error::Result;
+use crate::models::AuditAction;
+use crate::models::api::{RatedRecipe, RecipeFilter, RecipeRating};
+
+/// Each rated recipe's average, latest rating and count, as
+/// `recipe_id, average, latest, count`
+const RATING_SUMMARY: &str = r#"
+    SELECT
+        rr.recipe_id,
+        AVG(rr.rating) as average,
+        (
+            SELECT l.rating FROM recipe_ratings l
+            WHERE l.recipe_id = rr.recipe_id
+            ORDER BY l.id DESC LIMIT 1
+        ) as latest,
+        COUNT(*) as count
+    FROM recipe_ratings rr
+    GROUP BY rr.recipe_id
+"#;
+
+/// Rate a recipe from 1 to 5
+/// Every rating is kept; the recipe's score is their average
+pub async fn rate_recipe(pool: &SqlitePool, recipe_id: i64, rating: u8) -> Result<()> {
+    with_busy_retry(|| insert_rating(pool, recipe_id, rating)).await
+}
+
+async fn insert_rating(pool: &SqlitePool, recipe_id: i64, rating: u8) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let rating_id = sqlx::query("INSERT INTO recipe_ratings (recipe_id, rating) VALUES (?, ?)")
+        .bind(recipe_id)
+        .bind(rating)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+    record_change(
+        &mut tx,
+        "rating",
+        rating_id,
+        AuditAction::Create,
+        None,
+        Some(&json!({ "recipe_id": recipe_id, "rating": rating })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Average rating of a recipe, None when it hasn't been rated
+pub async fn get_average_rating(pool: &SqlitePool, recipe_id: i64) -> Result<Option<f64>> {
+    let average = sqlx::query_scalar("SELECT AVG(rating) FROM recipe_ratings WHERE recipe_id = ?")
+        .bind(recipe_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(average)
+}
+
+/// Average, latest and number of ratings of a recipe, None when it hasn't
+/// been rated
+pub async fn get_recipe_rating(pool: &SqlitePool, recipe_id: i64) -> Result<Option<RecipeRating>> {
+    let rating = sqlx::query(&format!(
+        "SELECT average, latest, count FROM ({RATING_SUMMARY}) WHERE recipe_id = ?"
+    ))
+    .bind(recipe_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rating.as_ref().and_then(rating_from_row))
+}
+
+/// The recipes that pass `filter` with their ratings, best first: by
+/// average, then latest rating, then name, with unrated recipes last
+pub async fn list_recipes_by_rating(
+    pool: &SqlitePool,
+    filter: &RecipeFilter,
+) -> Result<Vec<RatedRecipe>> {
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+        r#"
+        SELECT r.id, r.name, r.is_favorite, s.average, s.latest, s.count
+        FROM recipes r
+        LEFT JOIN ({RATING_SUMMARY}) s ON s.recipe_id = r.id
+        WHERE 1 = 1"#
+    ));
+    push_recipe_filter(&mut builder, filter);
+    builder.push(" ORDER BY s.average IS NULL, s.average DESC, s.latest DESC, r.name, r.id");
+
+    let recipes = builder
+        .build()
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| RatedRecipe {
+            recipe_id: row.get("id"),
+            name: row.get("name"),
+            is_favorite: row.get("is_favorite"),
+            rating: rating_from_row(row),
+        })
+        .collect();
+
+    Ok(recipes)
+}
+
+fn rating_from_row(row: &SqliteRow) -> Option<RecipeRating> {
+    Some(RecipeRating {
+        average: row.get::<Option<f64>, _>("average")?,
+        latest: row.get("latest"),
+        count: row.get("count"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FeedMeError;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_rate_recipe(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let id = sqlx::query("INSERT INTO recipes (name) VALUES ('Tacos')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        assert_eq!(get_average_rating(&pool, id).await.unwrap(), None);
+
+        rate_recipe(&pool, id, 5).await.unwrap();
+        rate_recipe(&pool, id, 4).await.unwrap();
+
+        assert_eq!(get_average_rating(&pool, id).await.unwrap(), Some(4.5));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_recipe_rating_summary(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = crate::seed::seed_demo_data(&pool).await.unwrap();
+        assert_eq!(get_recipe_rating(&pool, ids[0]).await.unwrap(), None);
+
+        for (recipe_id, rating) in [
+            (ids[0], 5),
+            (ids[0], 2),
+            (ids[1], 4),
+            (ids[2], 3),
+            (ids[2], 5),
+        ] {
+            rate_recipe(&pool, recipe_id, rating).await.unwrap();
+        }
+
+        let rating = get_recipe_rating(&pool, ids[0]).await.unwrap();
+        assert_eq!(
+            rating,
+            Some(RecipeRating {
+                average: 3.5,
+                latest: 2,
+                count: 2,
+            })
+        );
+        let recipe = crate::controllers::get_recipe(&pool, ids[0]).await.unwrap();
+        assert_eq!(recipe.rating, rating);
+
+        let ranked: Vec<i64> = list_recipes_by_rating(&pool, &RecipeFilter::default())
+            .await
+            .unwrap()
+            .iter()
+            .map(|recipe| recipe.recipe_id)
+            .collect();
+        // Averages of 4 ordered by the latest rating, then 3.5, then unrated
+        assert_eq!(&ranked[..3], &[ids[2], ids[1], ids[0]]);
+        assert_eq!(ranked.len(), ids.len());
+
+        let rated = list_recipes_by_rating(
+            &pool,
+            &RecipeFilter {
+                min_rating: Some(4.0),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(rated.len(), 2);
+        assert!(rated.iter().all(|recipe| recipe.rating.is_some()));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_rate_recipe_out_of_range(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let id = sqlx::query("INSERT INTO recipes (name) VALUES ('Tacos')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        assert!(matches!(
+            rate_recipe(&pool, id, 6).await,
+            Err(FeedMeError::ConstraintViolation(_))
+        ));
+        assert!(rate_recipe(&pool, id, 0).await.is_err());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_rate_missing_recipe(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        assert!(matches!(
+            rate_recipe(&pool, 99, 3).await,
+            Err(FeedMeError::ConstraintViolation(_))
+        ));
+    }
+}
@@ -1,87 +1,354 @@
-use sqlx::{Row, SqlitePool};
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite, SqliteConnection, SqlitePool};
 use std::collections::HashMap;
 
+use super::audit_controller::{record_change, record_update, row_snapshot};
+use super::cook_log_controller::get_recipe_notes;
+use super::custom_unit_controller::{ingredient_units, list_custom_units};
+use super::equipment_controller::{equip_recipe_in, get_recipe_equipment};
+use super::ingredient_controller::resolve_ingredients_in;
+use super::nutrition_controller::recipe_nutrition;
+use super::pantry_controller::restock_list;
+use super::rating_controller::get_recipe_rating;
+use super::retry::with_busy_retry;
+use super::search_controller::push_recipe_filter;
+use super::tag_controller::{get_recipe_tags, tag_recipe_in};
 use crate::error::Result;
-use crate::models::RecipeRecord;
-use crate::models::api::{Recipe, RecipeIngredient, ShoppingListItem};
+use crate::models::api::{
+    Category, Difficulty, Recipe, RecipeFilter, RecipeIngredient, RecipeMetadata, RecipeOptions,
+    ShoppingListItem, Visibility, instruction_steps,
+};
+use crate::models::{AuditAction, RecipeRecord};
+use crate::quantity::{QuantityTotal, parse_quantity};
+use crate::timers::step_timers;
+use crate::validation::{Validator, recipe_warnings};
 
 /// Fetch a recipe by ID with all its ingredients
 pub async fn get_recipe(pool: &SqlitePool, recipe_id: i64) -> Result<Recipe> {
+    get_recipe_with(pool, recipe_id, RecipeOptions::default()).await
+}
+
+/// Fetch a recipe by ID, computing the extras asked for in `options`
+pub async fn get_recipe_with(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    options: RecipeOptions,
+) -> Result<Recipe> {
     // Fetch the recipe
-    let recipe = sqlx::query_as::<_, RecipeRecord>(
-        "SELECT id, name, instructions, created_at FROM recipes WHERE id = ?",
+    let row = sqlx::query(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE id = ? AND deleted_at IS NULL",
     )
     .bind(recipe_id)
     .fetch_optional(pool)
     .await?
     .ok_or(crate::error::FeedMeError::RecipeNotFound(recipe_id))?;
+    let recipe = RecipeRecord::from_row(&row)?;
+
+    let mut recipe = hydrate_recipes(pool, vec![recipe])
+        .await?
+        .pop()
+        .ok_or(crate::error::FeedMeError::RecipeNotFound(recipe_id))?;
+    if options.include_nutrition {
+        recipe.nutrition = Some(recipe_nutrition(pool, recipe_id, recipe.servings).await?);
+    }
+    if options.include_journal {
+        recipe.journal = get_recipe_notes(pool, recipe_id).await?;
+    }
+    recipe.localize_quantities(options.quantities);
+
+    Ok(recipe)
+}
+
+/// Complete recipe rows with their ingredients (and the steps using each),
+/// timers, tags, equipment and rating, as `get_recipe` returns them
+/// The ingredient lines and step links of all the recipes are read in one
+/// query each. Journals and nutrition are left empty
+pub(crate) async fn hydrate_recipes(
+    pool: &SqlitePool,
+    records: Vec<RecipeRecord>,
+) -> Result<Vec<Recipe>> {
+    let recipe_ids: Vec<i64> = records.iter().map(|record| record.id).collect();
+
+    let mut ingredients: HashMap<i64, Vec<IngredientRow>> = HashMap::new();
+    for row in ingredient_rows(pool, &recipe_ids).await? {
+        ingredients.entry(row.recipe_id).or_default().push(row);
+    }
+    let mut steps = step_links(pool, &recipe_ids).await?;
+    let visibilities = recipe_visibilities(pool, &recipe_ids).await?;
+
+    let mut recipes = Vec::with_capacity(records.len());
+    for record in records {
+        let recipe_ingredients = ingredients
+            .remove(&record.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|row| RecipeIngredient {
+                steps: steps.remove(&row.id).unwrap_or_default(),
+                ingredient_id: row.ingredient_id,
+                ingredient_name: row.ingredient_name,
+                quantity_unit: row.quantity_unit,
+                notes: row.notes,
+            })
+            .collect();
+
+        recipes.push(Recipe {
+            id: record.id,
+            name: record.name,
+            timers: step_timers(record.instructions.as_deref().unwrap_or("")),
+            instructions: record.instructions,
+            servings: record.servings,
+            created_at: record.created_at,
+            ingredients: recipe_ingredients,
+            journal: Vec::new(),
+            tags: get_recipe_tags(pool, record.id).await?,
+            equipment: get_recipe_equipment(pool, record.id).await?,
+            visibility: visibilities.get(&record.id).copied().unwrap_or_default(),
+            rating: get_recipe_rating(pool, record.id).await?,
+            source_url: record.source_url,
+            source_name: record.source_name,
+            nutrition: None,
+        });
+    }
+
+    Ok(recipes)
+}
 
-    // Fetch all recipe_ingredients for this recipe with ingredient details
-    // Using a JOIN to get ingredient data in a single query
-    let ingredients = sqlx::query(
+/// The steps each ingredient line of `recipe_ids` is linked to, in order,
+/// by recipe_ingredients ID
+async fn step_links(pool: &SqlitePool, recipe_ids: &[i64]) -> Result<HashMap<i64, Vec<usize>>> {
+    let mut steps: HashMap<i64, Vec<usize>> = HashMap::new();
+    if recipe_ids.is_empty() {
+        return Ok(steps);
+    }
+
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
         r#"
-        SELECT
-            i.id as ingredient_id,
-            i.name as ingredient_name,
-            ri.quantity_unit,
-            ri.notes
-        FROM recipe_ingredients ri
-        JOIN ingredients i ON ri.ingredient_id = i.id
-        WHERE ri.recipe_id = ?
-        ORDER BY ri.id
+        SELECT rsi.recipe_ingredient_id, rsi.step
+        FROM recipe_step_ingredients rsi
+        JOIN recipe_ingredients ri ON rsi.recipe_ingredient_id = ri.id
+        WHERE ri.recipe_id IN ("#,
+    );
+    let mut separated = builder.separated(", ");
+    for recipe_id in recipe_ids {
+        separated.push_bind(*recipe_id);
+    }
+    separated.push_unseparated(") ORDER BY rsi.step");
+
+    let links: Vec<(i64, i64)> = builder.build_query_as().fetch_all(pool).await?;
+    for (recipe_ingredient_id, step) in links {
+        steps
+            .entry(recipe_ingredient_id)
+            .or_default()
+            .push(step as usize);
+    }
+
+    Ok(steps)
+}
+
+/// Who each of `recipe_ids` is shown to
+pub(crate) async fn recipe_visibilities(
+    pool: &SqlitePool,
+    recipe_ids: &[i64],
+) -> Result<HashMap<i64, Visibility>> {
+    if recipe_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut builder: QueryBuilder<Sqlite> =
+        QueryBuilder::new("SELECT id, visibility FROM recipes WHERE id IN (");
+    let mut separated = builder.separated(", ");
+    for recipe_id in recipe_ids {
+        separated.push_bind(*recipe_id);
+    }
+    separated.push_unseparated(")");
+
+    let rows: Vec<(i64, String)> = builder.build_query_as().fetch_all(pool).await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, visibility)| Some((id, Visibility::parse(&visibility)?)))
+        .collect())
+}
+
+/// Create a new recipe with ingredients
+/// Takes a Recipe struct (ignoring id and created_at) and links it to existing ingredients by ID
+/// Ingredients must already exist in the database before creating the recipe.
+/// The name, tags and equipment are stored trimmed, and the recipe is linked
+/// to each tag and item (creating missing ones). Likely mistakes (see `recipe_warnings`) are
+/// logged but don't stop the save. A name another recipe has (ignoring case)
+/// is refused with `DuplicateRecipe`
+pub async fn create_recipe(pool: &SqlitePool, recipe: &Recipe) -> Result<i64> {
+    let recipe = validate_recipe(recipe)?;
+    for warning in recipe_warnings(&recipe) {
+        tracing::warn!(recipe = %recipe.name, field = %warning.field, "{}", warning);
+    }
+    with_busy_retry(|| insert_recipe(pool, &recipe)).await
+}
+
+/// Create a recipe along with the ingredients it introduces and the
+/// details `Recipe` doesn't carry, all in one transaction
+/// Ingredients with ID 0 are looked up by name and created when missing;
+/// if anything fails nothing is written, so a rejected recipe leaves no
+/// orphan ingredients behind. Tags in `metadata` are added to the recipe's
+/// own. Returns the recipe ID
+pub async fn create_recipe_with(
+    pool: &SqlitePool,
+    recipe: &Recipe,
+    metadata: &RecipeMetadata,
+) -> Result<i64> {
+    let recipe = validate_recipe(recipe)?;
+    let mut validator = Validator::new();
+    for (i, ingredient) in recipe.ingredients.iter().enumerate() {
+        if ingredient.ingredient_id == 0 {
+            validator.name(
+                format!("ingredients[{}].ingredient_name", i),
+                &ingredient.ingredient_name,
+            );
+        }
+    }
+    validator.finish()?;
+    for warning in recipe_warnings(&recipe) {
+        tracing::warn!(recipe = %recipe.name, field = %warning.field, "{}", warning);
+    }
+    with_busy_retry(|| insert_recipe_with(pool, &recipe, metadata)).await
+}
+
+async fn insert_recipe_with(
+    pool: &SqlitePool,
+    recipe: &Recipe,
+    metadata: &RecipeMetadata,
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let mut recipe = recipe.clone();
+    resolve_ingredients_in(&mut tx, &mut recipe.ingredients, &mut HashMap::new()).await?;
+    let recipe_id = insert_recipe_in(&mut tx, &recipe).await?;
+    set_recipe_metadata_in(&mut tx, recipe_id, metadata).await?;
+
+    tx.commit().await?;
+
+    Ok(recipe_id)
+}
+
+/// Copy a recipe under a new name, e.g. to start a variation ("Pancakes (GF)")
+/// The copy gets the instructions, ingredients with their notes and step
+/// links, tags, equipment, source, times, difficulty, category and
+/// visibility in one transaction. Ratings, cooks, journal entries and the
+/// favorite star stay with the original. Returns the copy's ID
+pub async fn clone_recipe(pool: &SqlitePool, recipe_id: i64, new_name: &str) -> Result<i64> {
+    let original = get_recipe(pool, recipe_id).await?;
+    let recipe = validate_recipe(&Recipe {
+        name: new_name.to_string(),
+        ..original
+    })?;
+    with_busy_retry(|| copy_recipe(pool, recipe_id, &recipe)).await
+}
+
+async fn copy_recipe(pool: &SqlitePool, recipe_id: i64, recipe: &Recipe) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let copy_id = insert_recipe_in(&mut tx, recipe).await?;
+    // Columns `Recipe` doesn't carry; no original row means it was deleted
+    // since it was read
+    let copied = sqlx::query(
+        r#"
+        UPDATE recipes AS copy
+        SET prep_minutes = original.prep_minutes,
+            cook_minutes = original.cook_minutes,
+            difficulty = original.difficulty,
+            category = original.category,
+            visibility = original.visibility
+        FROM recipes AS original
+        WHERE original.id = ? AND original.deleted_at IS NULL AND copy.id = ?
         "#,
     )
     .bind(recipe_id)
-    .fetch_all(pool)
-    .await?;
+    .bind(copy_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if copied == 0 {
+        return Err(crate::error::FeedMeError::Conflict(format!(
+            "recipe {} was deleted while it was being copied",
+            recipe_id
+        )));
+    }
+
+    tx.commit().await?;
 
-    // Map to RecipeIngredient structs
-    let recipe_ingredients: Vec<RecipeIngredient> = ingredients
+    Ok(copy_id)
+}
+
+/// Check a recipe's text fields, returning a copy with the name and source
+/// trimmed (blank sources become None)
+pub(crate) fn validate_recipe(recipe: &Recipe) -> Result<Recipe> {
+    let mut validator = Validator::new();
+    let name = validator.name("name", &recipe.name).to_string();
+    validator.text("instructions", recipe.instructions.as_deref());
+    let trimmed = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let source_url = trimmed(&recipe.source_url);
+    let source_name = trimmed(&recipe.source_name);
+    validator.url("source_url", source_url.as_deref());
+    validator.line("source_name", source_name.as_deref());
+    let step_count = recipe.steps().len();
+    let mut ingredients = recipe.ingredients.clone();
+    for (i, ingredient) in ingredients.iter_mut().enumerate() {
+        validator.line(
+            format!("ingredients[{}].quantity_unit", i),
+            Some(&ingredient.quantity_unit),
+        );
+        validator.line(
+            format!("ingredients[{}].notes", i),
+            ingredient.notes.as_deref(),
+        );
+        ingredient.steps.sort();
+        ingredient.steps.dedup();
+        for (j, &step) in ingredient.steps.iter().enumerate() {
+            validator.index(format!("ingredients[{}].steps[{}]", i, j), step, step_count);
+        }
+    }
+    let mut tags: Vec<String> = recipe
+        .tags
+        .iter()
+        .enumerate()
+        .map(|(i, tag)| validator.name(format!("tags[{}]", i), tag).to_string())
+        .collect();
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    tags.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    let mut equipment: Vec<String> = recipe
+        .equipment
         .iter()
-        .map(|row| RecipeIngredient {
-            ingredient_id: row.get("ingredient_id"),
-            ingredient_name: row.get("ingredient_name"),
-            quantity_unit: row.get("quantity_unit"),
-            notes: row.get("notes"),
+        .enumerate()
+        .map(|(i, item)| {
+            validator
+                .name(format!("equipment[{}]", i), item)
+                .to_string()
         })
         .collect();
+    equipment.sort_by_key(|item| item.to_lowercase());
+    equipment.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    validator.finish()?;
 
     Ok(Recipe {
-        id: recipe.id,
-        name: recipe.name,
-        instructions: recipe.instructions,
-        created_at: recipe.created_at,
-        ingredients: recipe_ingredients,
+        name,
+        source_url,
+        source_name,
+        ingredients,
+        tags,
+        equipment,
+        ..recipe.clone()
     })
 }
 
-/// Create a new recipe with ingredients
-/// Takes a Recipe struct (ignoring id and created_at) and links it to existing ingredients by ID
-/// Ingredients must already exist in the database before creating the recipe
-pub async fn create_recipe(pool: &SqlitePool, recipe: &Recipe) -> Result<i64> {
+async fn insert_recipe(pool: &SqlitePool, recipe: &Recipe) -> Result<i64> {
     // Start a transaction
     let mut tx = pool.begin().await?;
 
-    // Insert the recipe
-    let recipe_id = sqlx::query("INSERT INTO recipes (name, instructions) VALUES (?, ?)")
-        .bind(&recipe.name)
-        .bind(&recipe.instructions)
-        .execute(&mut *tx)
-        .await?
-        .last_insert_rowid();
-
-    // Insert recipe_ingredients using the provided ingredient IDs
-    for ingredient in &recipe.ingredients {
-        sqlx::query(
-            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit, notes) VALUES (?, ?, ?, ?)"
-        )
-        .bind(recipe_id)
-        .bind(ingredient.ingredient_id)
-        .bind(&ingredient.quantity_unit)
-        .bind(&ingredient.notes)
-        .execute(&mut *tx)
-        .await?;
-    }
+    let recipe_id = insert_recipe_in(&mut tx, recipe).await?;
 
     // Commit the transaction
     tx.commit().await?;
@@ -89,383 +356,2103 @@ pub async fn create_recipe(pool: &SqlitePool, recipe: &Recipe) -> Result<i64> {
     Ok(recipe_id)
 }
 
-/// Generate a shopping list from multiple recipes
-/// Combines ingredients with the same name, concatenating their quantities
-pub async fn generate_shopping_list(
-    pool: &SqlitePool,
-    recipe_ids: &[i64],
-) -> Result<Vec<ShoppingListItem>> {
-    if recipe_ids.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    // Build the IN clause with placeholders
-    let placeholders = recipe_ids
-        .iter()
-        .map(|_| "?")
-        .collect::<Vec<_>>()
-        .join(", ");
+/// Insert an already validated recipe, its ingredients and its audit entry
+/// inside the caller's transaction
+pub(crate) async fn insert_recipe_in(conn: &mut SqliteConnection, recipe: &Recipe) -> Result<i64> {
+    check_name_free(conn, &recipe.name, 0).await?;
 
-    let query = format!(
-        r#"
-        SELECT
-            i.name as ingredient_name,
-            ri.quantity_unit
-        FROM recipe_ingredients ri
-        JOIN ingredients i ON ri.ingredient_id = i.id
-        WHERE ri.recipe_id IN ({})
-        ORDER BY i.name, ri.id
-        "#,
-        placeholders
-    );
+    // Insert the recipe
+    let recipe_id = sqlx::query(
+        "INSERT INTO recipes (name, instructions, servings, source_url, source_name, visibility) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&recipe.name)
+    .bind(&recipe.instructions)
+    .bind(recipe.servings)
+    .bind(&recipe.source_url)
+    .bind(&recipe.source_name)
+    .bind(recipe.visibility.as_str())
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
 
-    // Build the query and bind all recipe_ids
-    let mut query_builder = sqlx::query(&query);
-    for recipe_id in recipe_ids {
-        query_builder = query_builder.bind(recipe_id);
+    // Insert recipe_ingredients using the provided ingredient IDs
+    insert_recipe_ingredients(conn, recipe_id, &recipe.ingredients).await?;
+    for tag in &recipe.tags {
+        tag_recipe_in(conn, recipe_id, tag).await?;
+    }
+    for item in &recipe.equipment {
+        equip_recipe_in(conn, recipe_id, item).await?;
     }
 
-    let rows = query_builder.fetch_all(pool).await?;
+    let snapshot = serde_json::to_value(Recipe {
+        id: recipe_id,
+        nutrition: None,
+        ..recipe.clone()
+    })?;
+    record_change(
+        conn,
+        "recipe",
+        recipe_id,
+        AuditAction::Create,
+        None,
+        Some(&snapshot),
+    )
+    .await?;
 
-    // Group by ingredient name and combine quantities
-    let mut ingredient_map: HashMap<String, Vec<String>> = HashMap::new();
+    Ok(recipe_id)
+}
 
-    for row in rows {
-        let ingredient_name: String = row.get("ingredient_name");
-        let quantity_unit: String = row.get("quantity_unit");
+/// Refuse a name another recipe outside the trash already has, ignoring case
+/// `recipe_id` is the recipe being renamed, 0 for a new one
+async fn check_name_free(conn: &mut SqliteConnection, name: &str, recipe_id: i64) -> Result<()> {
+    let taken: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM recipes WHERE name = ? COLLATE NOCASE AND deleted_at IS NULL AND id != ? LIMIT 1",
+    )
+    .bind(name)
+    .bind(recipe_id)
+    .fetch_optional(conn)
+    .await?;
 
-        ingredient_map
-            .entry(ingredient_name)
-            .or_insert_with(Vec::new)
-            .push(quantity_unit);
+    match taken {
+        Some(_) => Err(crate::error::FeedMeError::DuplicateRecipe(name.to_string())),
+        None => Ok(()),
     }
+}
 
-    // Convert to ShoppingListItem, combining quantities with " + "
-    let mut shopping_list: Vec<ShoppingListItem> = ingredient_map
-        .into_iter()
-        .map(|(ingredient_name, quantities)| ShoppingListItem {
-            ingredient_name,
-            combined_quantity: quantities.join(" + "),
-        })
-        .collect();
-
-    // Sort by ingredient name for consistent output
-    shopping_list.sort_by(|a, b| a.ingredient_name.cmp(&b.ingredient_name));
-
-    Ok(shopping_list)
+/// Replace a recipe's name, instructions, servings and ingredients
+/// Takes a Recipe struct (ignoring id, created_at, tags and equipment) like
+/// `create_recipe`; the ingredient rows are swapped in the same transaction
+/// so readers never see a half-updated recipe. Tags and equipment are left
+/// alone, change them with `add_recipe_tag`, `remove_recipe_tag` and
+/// `set_recipe_equipment`. Renaming to another recipe's name is refused
+/// like in `create_recipe`
+pub async fn update_recipe(pool: &SqlitePool, recipe_id: i64, recipe: &Recipe) -> Result<()> {
+    let recipe = validate_recipe(recipe)?;
+    for warning in recipe_warnings(&recipe) {
+        tracing::warn!(recipe = %recipe.name, field = %warning.field, "{}", warning);
+    }
+    with_busy_retry(|| replace_recipe(pool, recipe_id, &recipe)).await
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::test_fixtures::test_db;
-    use rstest::*;
+async fn replace_recipe(pool: &SqlitePool, recipe_id: i64, recipe: &Recipe) -> Result<()> {
+    let mut tx = pool.begin().await?;
 
-    #[rstest]
-    #[tokio::test]
-    async fn test_get_recipe(#[future] test_db: SqlitePool) {
-        let pool = test_db.await;
+    let old = sqlx::query(
+        "SELECT name, instructions, servings, source_url, source_name FROM recipes WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(recipe_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(crate::error::FeedMeError::RecipeNotFound(recipe_id))?;
+    check_name_free(&mut tx, &recipe.name, recipe_id).await?;
+    let old = serde_json::json!({
+        "name": old.get::<String, _>("name"),
+        "instructions": old.get::<Option<String>, _>("instructions"),
+        "servings": old.get::<Option<i64>, _>("servings"),
+        "source_url": old.get::<Option<String>, _>("source_url"),
+        "source_name": old.get::<Option<String>, _>("source_name"),
+    });
+
+    sqlx::query(
+        "UPDATE recipes SET name = ?, instructions = ?, servings = ?, source_url = ?, source_name = ? WHERE id = ?",
+    )
+    .bind(&recipe.name)
+    .bind(&recipe.instructions)
+    .bind(recipe.servings)
+    .bind(&recipe.source_url)
+    .bind(&recipe.source_name)
+    .bind(recipe_id)
+    .execute(&mut *tx)
+    .await?;
 
-        // Insert a recipe
-        let recipe_id = sqlx::query("INSERT INTO recipes (name, instructions) VALUES (?, ?)")
-            .bind("Pancakes")
-            .bind("Mix and cook on griddle")
-            .execute(&pool)
-            .await
-            .expect("Failed to insert recipe")
-            .last_insert_rowid();
+    sqlx::query("DELETE FROM recipe_ingredients WHERE recipe_id = ?")
+        .bind(recipe_id)
+        .execute(&mut *tx)
+        .await?;
+    insert_recipe_ingredients(&mut tx, recipe_id, &recipe.ingredients).await?;
+
+    let snapshot = serde_json::to_value(Recipe {
+        id: recipe_id,
+        nutrition: None,
+        ..recipe.clone()
+    })?;
+    record_change(
+        &mut tx,
+        "recipe",
+        recipe_id,
+        AuditAction::Update,
+        Some(&old),
+        Some(&snapshot),
+    )
+    .await?;
 
-        // Insert ingredients
-        let flour_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
-            .bind("flour")
-            .execute(&pool)
-            .await
-            .expect("Failed to insert flour")
-            .last_insert_rowid();
+    tx.commit().await?;
 
-        let milk_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
-            .bind("milk")
-            .execute(&pool)
-            .await
-            .expect("Failed to insert milk")
-            .last_insert_rowid();
+    Ok(())
+}
 
-        // Insert recipe_ingredients
-        sqlx::query(
-            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit, notes) VALUES (?, ?, ?, ?)",
-        )
-        .bind(recipe_id)
-        .bind(flour_id)
-        .bind("2 cups")
-        .bind("all-purpose")
-        .execute(&pool)
-        .await
-        .expect("Failed to insert recipe_ingredient");
+/// Move a recipe to the trash
+/// It disappears from every listing, search, plan and shopping list but
+/// keeps its ingredients, tags, ratings and history until `purge_deleted`,
+/// and `restore_recipe` brings it back as it was
+pub async fn delete_recipe(pool: &SqlitePool, recipe_id: i64) -> Result<()> {
+    with_busy_retry(|| trash_recipe(pool, recipe_id)).await
+}
 
-        sqlx::query(
-            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit) VALUES (?, ?, ?)",
-        )
-        .bind(recipe_id)
-        .bind(milk_id)
-        .bind("1 cup")
-        .execute(&pool)
-        .await
-        .expect("Failed to insert recipe_ingredient");
+async fn trash_recipe(pool: &SqlitePool, recipe_id: i64) -> Result<()> {
+    let mut tx = pool.begin().await?;
 
-        // Fetch the recipe
-        let recipe = get_recipe(&pool, recipe_id)
-            .await
-            .expect("Failed to fetch recipe");
+    let name: String = sqlx::query_scalar(
+        "UPDATE recipes SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL RETURNING name",
+    )
+    .bind(recipe_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(crate::error::FeedMeError::RecipeNotFound(recipe_id))?;
+    record_change(
+        &mut tx,
+        "recipe",
+        recipe_id,
+        AuditAction::Delete,
+        Some(&serde_json::json!({ "id": recipe_id, "name": name })),
+        None,
+    )
+    .await?;
 
-        // Verify the recipe
-        assert_eq!(recipe.id, recipe_id);
-        assert_eq!(recipe.name, "Pancakes");
-        assert_eq!(
-            recipe.instructions,
-            Some("Mix and cook on griddle".to_string())
-        );
+    tx.commit().await?;
 
-        // Verify ingredients
-        assert_eq!(recipe.ingredients.len(), 2);
+    Ok(())
+}
 
-        let flour_ingredient = &recipe.ingredients[0];
-        assert_eq!(flour_ingredient.ingredient_name, "flour");
-        assert_eq!(flour_ingredient.quantity_unit, "2 cups");
-        assert_eq!(flour_ingredient.notes, Some("all-purpose".to_string()));
+/// Take a recipe back out of the trash
+/// A recipe that isn't in the trash is not found
+pub async fn restore_recipe(pool: &SqlitePool, recipe_id: i64) -> Result<()> {
+    with_busy_retry(|| untrash_recipe(pool, recipe_id)).await
+}
 
-        let milk_ingredient = &recipe.ingredients[1];
-        assert_eq!(milk_ingredient.ingredient_name, "milk");
-        assert_eq!(milk_ingredient.quantity_unit, "1 cup");
-        assert_eq!(milk_ingredient.notes, None);
+async fn untrash_recipe(pool: &SqlitePool, recipe_id: i64) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let restored =
+        sqlx::query("UPDATE recipes SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(recipe_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    if restored == 0 {
+        return Err(crate::error::FeedMeError::RecipeNotFound(recipe_id));
     }
+    record_change(
+        &mut tx,
+        "recipe",
+        recipe_id,
+        AuditAction::Update,
+        Some(&serde_json::json!({ "deleted": true })),
+        Some(&serde_json::json!({ "deleted": false })),
+    )
+    .await?;
 
-    #[rstest]
-    #[tokio::test]
-    async fn test_get_recipe_not_found(#[future] test_db: SqlitePool) {
-        let pool = test_db.await;
+    tx.commit().await?;
 
-        // Try to fetch a non-existent recipe
-        let result = get_recipe(&pool, 999).await;
+    Ok(())
+}
 
-        assert!(result.is_err());
+/// Recipes in the trash, most recently deleted first
+pub async fn list_deleted_recipes(pool: &SqlitePool) -> Result<Vec<RecipeRecord>> {
+    let recipes = sqlx::query_as::<_, RecipeRecord>(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC",
+    )
+    .fetch_all(pool)
+    .await?;
 
-        // Verify it's the correct error type
-        match result {
-            Err(crate::error::FeedMeError::RecipeNotFound(id)) => {
+    Ok(recipes)
+}
+
+/// Empty the trash for good, returning how many recipes went
+/// Their ingredients, tags, ratings, cooks and plan entries go with them;
+/// each purged recipe gets a final delete entry in the audit log
+pub async fn purge_deleted(pool: &SqlitePool) -> Result<u64> {
+    with_busy_retry(|| purge_deleted_recipes(pool)).await
+}
+
+async fn purge_deleted_recipes(pool: &SqlitePool) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let purged: Vec<(i64, String)> =
+        sqlx::query_as("DELETE FROM recipes WHERE deleted_at IS NOT NULL RETURNING id, name")
+            .fetch_all(&mut *tx)
+            .await?;
+    for (recipe_id, name) in &purged {
+        record_change(
+            &mut tx,
+            "recipe",
+            *recipe_id,
+            AuditAction::Delete,
+            Some(&serde_json::json!({ "id": recipe_id, "name": name, "deleted": true })),
+            None,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(purged.len() as u64)
+}
+
+/// Link ingredients to a recipe with a single multi-row INSERT, then link
+/// them to the steps they're used in
+pub(crate) async fn insert_recipe_ingredients(
+    conn: &mut SqliteConnection,
+    recipe_id: i64,
+    ingredients: &[RecipeIngredient],
+) -> Result<()> {
+    if ingredients.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit, amount, unit, notes) ",
+    );
+    builder.push_values(ingredients, |mut row, ingredient| {
+        let parsed = parse_quantity(&ingredient.quantity_unit);
+        row.push_bind(recipe_id)
+            .push_bind(ingredient.ingredient_id)
+            .push_bind(ingredient.quantity_unit.clone())
+            .push_bind(parsed.as_ref().map(|p| p.amount))
+            .push_bind(parsed.and_then(|p| p.unit))
+            .push_bind(ingredient.notes.clone());
+    });
+    builder.build().execute(&mut *conn).await?;
+
+    if ingredients
+        .iter()
+        .all(|ingredient| ingredient.steps.is_empty())
+    {
+        return Ok(());
+    }
+    // The rows just inserted are the recipe's newest
+    let mut ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM recipe_ingredients WHERE recipe_id = ? ORDER BY id DESC LIMIT ?",
+    )
+    .bind(recipe_id)
+    .bind(ingredients.len() as i64)
+    .fetch_all(&mut *conn)
+    .await?;
+    ids.reverse();
+    for (id, ingredient) in ids.into_iter().zip(ingredients) {
+        link_steps_in(conn, id, &ingredient.steps).await?;
+    }
+
+    Ok(())
+}
+
+/// Link one recipe_ingredients row to steps inside the caller's transaction
+async fn link_steps_in(
+    conn: &mut SqliteConnection,
+    recipe_ingredient_id: i64,
+    steps: &[usize],
+) -> Result<()> {
+    for &step in steps {
+        sqlx::query(
+            "INSERT OR IGNORE INTO recipe_step_ingredients (recipe_ingredient_id, step) VALUES (?, ?)",
+        )
+        .bind(recipe_ingredient_id)
+        .bind(step as i64)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Say which of a recipe's ingredients (positions in `Recipe::ingredients`,
+/// from 0) go into one of its steps (from 0, see `Recipe::steps`), replacing
+/// the step's previous links; an empty list unlinks them all
+/// Links follow the ingredient rows, so `update_recipe` keeps only the ones
+/// in the `steps` it's given
+pub async fn set_step_ingredients(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    step: usize,
+    ingredients: &[usize],
+) -> Result<()> {
+    with_busy_retry(|| link_step_ingredients(pool, recipe_id, step, ingredients)).await
+}
+
+async fn link_step_ingredients(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    step: usize,
+    ingredients: &[usize],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let instructions: Option<String> =
+        sqlx::query_scalar("SELECT instructions FROM recipes WHERE id = ? AND deleted_at IS NULL")
+            .bind(recipe_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(crate::error::FeedMeError::RecipeNotFound(recipe_id))?;
+    let step_count = instruction_steps(instructions.as_deref().unwrap_or("")).len();
+    let ids: Vec<i64> =
+        sqlx::query_scalar("SELECT id FROM recipe_ingredients WHERE recipe_id = ? ORDER BY id")
+            .bind(recipe_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+    let mut validator = Validator::new();
+    validator.index("step", step, step_count);
+    for (i, &position) in ingredients.iter().enumerate() {
+        validator.index(format!("ingredients[{}]", i), position, ids.len());
+    }
+    validator.finish()?;
+
+    let linked: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT rsi.recipe_ingredient_id
+        FROM recipe_step_ingredients rsi
+        JOIN recipe_ingredients ri ON rsi.recipe_ingredient_id = ri.id
+        WHERE rsi.step = ? AND ri.recipe_id = ?
+        "#,
+    )
+    .bind(step as i64)
+    .bind(recipe_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let mut old: Vec<usize> = linked
+        .iter()
+        .filter_map(|id| ids.iter().position(|row| row == id))
+        .collect();
+    old.sort_unstable();
+
+    sqlx::query(
+        r#"
+        DELETE FROM recipe_step_ingredients
+        WHERE step = ?
+        AND recipe_ingredient_id IN (SELECT id FROM recipe_ingredients WHERE recipe_id = ?)
+        "#,
+    )
+    .bind(step as i64)
+    .bind(recipe_id)
+    .execute(&mut *tx)
+    .await?;
+    for &position in ingredients {
+        link_steps_in(&mut tx, ids[position], &[step]).await?;
+    }
+    record_change(
+        &mut tx,
+        "recipe",
+        recipe_id,
+        AuditAction::Update,
+        Some(&serde_json::json!({ "step": step, "ingredients": old })),
+        Some(&serde_json::json!({ "step": step, "ingredients": ingredients })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Record how long a recipe takes, in minutes (None when unknown)
+pub async fn set_recipe_times(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    prep_minutes: Option<i64>,
+    cook_minutes: Option<i64>,
+) -> Result<()> {
+    with_busy_retry(|| update_recipe_times(pool, recipe_id, prep_minutes, cook_minutes)).await
+}
+
+async fn update_recipe_times(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    prep_minutes: Option<i64>,
+    cook_minutes: Option<i64>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let columns = ["prep_minutes", "cook_minutes"];
+    let old = row_snapshot(&mut tx, "recipes", recipe_id, &columns).await?;
+
+    let result = sqlx::query(
+        "UPDATE recipes SET prep_minutes = ?, cook_minutes = ? WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(prep_minutes)
+    .bind(cook_minutes)
+    .bind(recipe_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(crate::error::FeedMeError::RecipeNotFound(recipe_id));
+    }
+    record_update(&mut tx, "recipe", "recipes", recipe_id, &columns, old).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// A recipe's tags, times, category and difficulty, for exports
+pub(crate) async fn get_recipe_metadata(
+    pool: &SqlitePool,
+    recipe_id: i64,
+) -> Result<RecipeMetadata> {
+    let (prep_minutes, cook_minutes, category, difficulty): (_, _, Option<String>, Option<String>) =
+        sqlx::query_as(
+            "SELECT prep_minutes, cook_minutes, category, difficulty FROM recipes WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(recipe_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(crate::error::FeedMeError::RecipeNotFound(recipe_id))?;
+
+    Ok(RecipeMetadata {
+        tags: get_recipe_tags(pool, recipe_id).await?,
+        prep_minutes,
+        cook_minutes,
+        category: category.as_deref().and_then(Category::parse),
+        difficulty: difficulty.as_deref().and_then(Difficulty::parse),
+    })
+}
+
+/// Apply imported tags, times and category to a recipe inside the caller's
+/// transaction; tags are added to any the recipe already has and the rest
+/// is only set when given
+pub(crate) async fn set_recipe_metadata_in(
+    conn: &mut SqliteConnection,
+    recipe_id: i64,
+    metadata: &RecipeMetadata,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE recipes SET prep_minutes = COALESCE(?, prep_minutes), cook_minutes = COALESCE(?, cook_minutes), category = COALESCE(?, category), difficulty = COALESCE(?, difficulty) WHERE id = ?",
+    )
+    .bind(metadata.prep_minutes)
+    .bind(metadata.cook_minutes)
+    .bind(metadata.category.map(|category| category.as_str()))
+    .bind(metadata.difficulty.map(|difficulty| difficulty.as_str()))
+    .bind(recipe_id)
+    .execute(&mut *conn)
+    .await?;
+
+    for tag in &metadata.tags {
+        tag_recipe_in(conn, recipe_id, tag.trim()).await?;
+    }
+
+    Ok(())
+}
+
+/// List every recipe (without ingredients) ordered by id
+/// Use `stream_recipe_records` when the catalog may be large
+pub async fn list_recipes(pool: &SqlitePool) -> Result<Vec<RecipeRecord>> {
+    let recipes = sqlx::query_as::<_, RecipeRecord>(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE deleted_at IS NULL ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(recipes)
+}
+
+/// List the recipes (without ingredients) that pass `filter`, ordered by id
+pub async fn list_recipes_with(
+    pool: &SqlitePool,
+    filter: &RecipeFilter,
+) -> Result<Vec<RecipeRecord>> {
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "SELECT r.id, r.name, r.instructions, r.servings, r.source_url, r.source_name, r.created_at FROM recipes r WHERE 1 = 1",
+    );
+    push_recipe_filter(&mut builder, filter);
+    builder.push(" ORDER BY r.id");
+
+    Ok(builder
+        .build_query_as::<RecipeRecord>()
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Star or unstar a recipe as a favorite
+pub async fn set_favorite(pool: &SqlitePool, recipe_id: i64, is_favorite: bool) -> Result<()> {
+    with_busy_retry(|| update_favorite(pool, recipe_id, is_favorite)).await
+}
+
+async fn update_favorite(pool: &SqlitePool, recipe_id: i64, is_favorite: bool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "recipes", recipe_id, &["is_favorite"]).await?;
+
+    let result =
+        sqlx::query("UPDATE recipes SET is_favorite = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(is_favorite)
+            .bind(recipe_id)
+            .execute(&mut *tx)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(crate::error::FeedMeError::RecipeNotFound(recipe_id));
+    }
+    record_update(
+        &mut tx,
+        "recipe",
+        "recipes",
+        recipe_id,
+        &["is_favorite"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Favorite recipes (without ingredients) ordered by name
+pub async fn get_favorites(pool: &SqlitePool) -> Result<Vec<RecipeRecord>> {
+    let recipes = sqlx::query_as::<_, RecipeRecord>(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE is_favorite AND deleted_at IS NULL ORDER BY name, id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(recipes)
+}
+
+/// Set (or clear) which meal a recipe is for
+pub async fn set_recipe_category(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    category: Option<Category>,
+) -> Result<()> {
+    with_busy_retry(|| update_recipe_category(pool, recipe_id, category)).await
+}
+
+async fn update_recipe_category(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    category: Option<Category>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "recipes", recipe_id, &["category"]).await?;
+
+    let result = sqlx::query("UPDATE recipes SET category = ? WHERE id = ? AND deleted_at IS NULL")
+        .bind(category.map(|category| category.as_str()))
+        .bind(recipe_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(crate::error::FeedMeError::RecipeNotFound(recipe_id));
+    }
+    record_update(&mut tx, "recipe", "recipes", recipe_id, &["category"], old).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Set (or clear) how demanding a recipe is
+pub async fn set_recipe_difficulty(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    difficulty: Option<Difficulty>,
+) -> Result<()> {
+    with_busy_retry(|| update_recipe_difficulty(pool, recipe_id, difficulty)).await
+}
+
+async fn update_recipe_difficulty(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    difficulty: Option<Difficulty>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "recipes", recipe_id, &["difficulty"]).await?;
+
+    let result =
+        sqlx::query("UPDATE recipes SET difficulty = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(difficulty.map(|difficulty| difficulty.as_str()))
+            .bind(recipe_id)
+            .execute(&mut *tx)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(crate::error::FeedMeError::RecipeNotFound(recipe_id));
+    }
+    record_update(
+        &mut tx,
+        "recipe",
+        "recipes",
+        recipe_id,
+        &["difficulty"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Choose who a recipe is shown to when listing and searching with a
+/// `RecipeFilter::audience`
+pub async fn set_recipe_visibility(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    visibility: Visibility,
+) -> Result<()> {
+    with_busy_retry(|| update_recipe_visibility(pool, recipe_id, visibility)).await
+}
+
+async fn update_recipe_visibility(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    visibility: Visibility,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "recipes", recipe_id, &["visibility"]).await?;
+
+    let result =
+        sqlx::query("UPDATE recipes SET visibility = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(visibility.as_str())
+            .bind(recipe_id)
+            .execute(&mut *tx)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(crate::error::FeedMeError::RecipeNotFound(recipe_id));
+    }
+    record_update(
+        &mut tx,
+        "recipe",
+        "recipes",
+        recipe_id,
+        &["visibility"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Generate a shopping list from multiple recipes
+/// Combines ingredients with the same name, summing quantities whose units
+/// convert into each other and listing the rest side by side. Pantry items
+/// below their restock threshold are added even when no recipe needs them
+pub async fn generate_shopping_list(
+    pool: &SqlitePool,
+    recipe_ids: &[i64],
+) -> Result<Vec<ShoppingListItem>> {
+    let mut shopping_list = recipe_shopping_list(pool, recipe_ids).await?;
+
+    for item in restock_list(pool).await? {
+        if !shopping_list
+            .iter()
+            .any(|listed| listed.ingredient_name == item.ingredient_name)
+        {
+            shopping_list.push(item);
+        }
+    }
+    shopping_list.sort_by(|a, b| a.ingredient_name.cmp(&b.ingredient_name));
+
+    Ok(shopping_list)
+}
+
+/// Ingredients needed by the recipes alone, sorted by name
+async fn recipe_shopping_list(
+    pool: &SqlitePool,
+    recipe_ids: &[i64],
+) -> Result<Vec<ShoppingListItem>> {
+    if recipe_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rows = ingredient_rows(pool, recipe_ids).await?;
+    let custom_units = list_custom_units(pool).await?;
+
+    // Group by ingredient name and sum quantities
+    let mut totals: HashMap<String, QuantityTotal> = HashMap::new();
+
+    for row in rows {
+        totals
+            .entry(row.ingredient_name)
+            .or_insert_with(|| {
+                QuantityTotal::for_ingredient(ingredient_units(
+                    &custom_units,
+                    row.ingredient_id,
+                    row.grams_per_ml,
+                ))
+            })
+            .add(&row.quantity_unit, row.amount, row.unit.as_deref(), 1.0);
+    }
+
+    let mut shopping_list: Vec<ShoppingListItem> = totals
+        .into_iter()
+        .map(|(ingredient_name, total)| ShoppingListItem {
+            ingredient_name,
+            combined_quantity: total.to_string(),
+        })
+        .collect();
+
+    // Sort by ingredient name for consistent output
+    shopping_list.sort_by(|a, b| a.ingredient_name.cmp(&b.ingredient_name));
+
+    Ok(shopping_list)
+}
+
+/// A recipe's ingredient line joined with the ingredient it names
+/// Both `get_recipe` and the shopping list read ingredients through
+/// `ingredient_rows`, so a column added here reaches both
+#[derive(Debug, FromRow)]
+struct IngredientRow {
+    /// The recipe_ingredients row
+    id: i64,
+    recipe_id: i64,
+    ingredient_id: i64,
+    ingredient_name: String,
+    grams_per_ml: Option<f64>,
+    quantity_unit: String,
+    amount: Option<f64>,
+    unit: Option<String>,
+    notes: Option<String>,
+}
+
+/// The ingredient lines of `recipe_ids`, in the order they were added;
+/// recipes in the trash have none
+async fn ingredient_rows(pool: &SqlitePool, recipe_ids: &[i64]) -> Result<Vec<IngredientRow>> {
+    if recipe_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        r#"
+        SELECT
+            ri.id,
+            ri.recipe_id,
+            i.id as ingredient_id,
+            i.name as ingredient_name,
+            i.grams_per_ml,
+            ri.quantity_unit,
+            ri.amount,
+            ri.unit,
+            ri.notes
+        FROM recipe_ingredients ri
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        JOIN recipes r ON ri.recipe_id = r.id
+        WHERE r.deleted_at IS NULL AND ri.recipe_id IN ("#,
+    );
+    let mut separated = builder.separated(", ");
+    for recipe_id in recipe_ids {
+        separated.push_bind(*recipe_id);
+    }
+    separated.push_unseparated(") ORDER BY ri.id");
+
+    let rows = builder.build_query_as().fetch_all(pool).await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FeedMeError;
+    use crate::models::api::DietaryFlag;
+    use crate::models::test_fixtures::test_db;
+    use crate::quantity::QuantityFormat;
+    use crate::units::UnitSystem;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_recipe(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // Insert a recipe
+        let recipe_id = sqlx::query("INSERT INTO recipes (name, instructions) VALUES (?, ?)")
+            .bind("Pancakes")
+            .bind("Mix and cook on griddle")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert recipe")
+            .last_insert_rowid();
+
+        // Insert ingredients
+        let flour_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("flour")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert flour")
+            .last_insert_rowid();
+
+        let milk_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("milk")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert milk")
+            .last_insert_rowid();
+
+        // Insert recipe_ingredients
+        sqlx::query(
+            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit, notes) VALUES (?, ?, ?, ?)",
+        )
+        .bind(recipe_id)
+        .bind(flour_id)
+        .bind("2 cups")
+        .bind("all-purpose")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert recipe_ingredient");
+
+        sqlx::query(
+            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit) VALUES (?, ?, ?)",
+        )
+        .bind(recipe_id)
+        .bind(milk_id)
+        .bind("1 cup")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert recipe_ingredient");
+
+        // Fetch the recipe
+        let recipe = get_recipe(&pool, recipe_id)
+            .await
+            .expect("Failed to fetch recipe");
+
+        // Verify the recipe
+        assert_eq!(recipe.id, recipe_id);
+        assert_eq!(recipe.name, "Pancakes");
+        assert_eq!(
+            recipe.instructions,
+            Some("Mix and cook on griddle".to_string())
+        );
+
+        // Verify ingredients
+        assert_eq!(recipe.ingredients.len(), 2);
+
+        let flour_ingredient = &recipe.ingredients[0];
+        assert_eq!(flour_ingredient.ingredient_name, "flour");
+        assert_eq!(flour_ingredient.quantity_unit, "2 cups");
+        assert_eq!(flour_ingredient.notes, Some("all-purpose".to_string()));
+
+        let milk_ingredient = &recipe.ingredients[1];
+        assert_eq!(milk_ingredient.ingredient_name, "milk");
+        assert_eq!(milk_ingredient.quantity_unit, "1 cup");
+        assert_eq!(milk_ingredient.notes, None);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_recipe_metric_units(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let recipe_id = sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+            .bind("Pancakes")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert recipe")
+            .last_insert_rowid();
+        for (name, quantity_unit) in [("flour", "2 cups"), ("milk", "1 cup")] {
+            let ingredient_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+                .bind(name)
+                .execute(&pool)
+                .await
+                .expect("Failed to insert ingredient")
+                .last_insert_rowid();
+            sqlx::query(
+                "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit) VALUES (?, ?, ?)",
+            )
+            .bind(recipe_id)
+            .bind(ingredient_id)
+            .bind(quantity_unit)
+            .execute(&pool)
+            .await
+            .expect("Failed to insert recipe_ingredient");
+        }
+
+        let options = RecipeOptions {
+            quantities: QuantityFormat {
+                system: Some(UnitSystem::Metric),
+                style: None,
+            },
+            ..Default::default()
+        };
+        let metric = get_recipe_with(&pool, recipe_id, options)
+            .await
+            .expect("Failed to fetch recipe");
+
+        assert_eq!(metric.ingredients[0].quantity_unit, "473 ml");
+        assert_eq!(metric.ingredients[1].quantity_unit, "237 ml");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_recipe_not_found(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // Try to fetch a non-existent recipe
+        let result = get_recipe(&pool, 999).await;
+
+        assert!(result.is_err());
+
+        // Verify it's the correct error type
+        match result {
+            Err(crate::error::FeedMeError::RecipeNotFound(id)) => {
                 assert_eq!(id, 999);
             }
-            _ => panic!("Expected RecipeNotFound error"),
+            _ => panic!("Expected RecipeNotFound error"),
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_recipe_no_ingredients(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // Insert a recipe without ingredients
+        let recipe_id = sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+            .bind("Empty Recipe")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert recipe")
+            .last_insert_rowid();
+
+        // Fetch the recipe
+        let recipe = get_recipe(&pool, recipe_id)
+            .await
+            .expect("Failed to fetch recipe");
+
+        // Verify the recipe has no ingredients
+        assert_eq!(recipe.name, "Empty Recipe");
+        assert_eq!(recipe.ingredients.len(), 0);
+        assert_eq!(recipe.instructions, None);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // First, create ingredients in the database
+        let pasta_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("pasta")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert pasta")
+            .last_insert_rowid();
+
+        let bacon_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("bacon")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert bacon")
+            .last_insert_rowid();
+
+        let eggs_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("eggs")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert eggs")
+            .last_insert_rowid();
+
+        // Create a recipe
+        let new_recipe = Recipe {
+            id: 0, // Will be ignored
+            name: "Pasta Carbonara".to_string(),
+            instructions: Some("Cook pasta, fry bacon, mix with eggs".to_string()),
+            created_at: String::new(), // Will be ignored
+            ingredients: vec![
+                RecipeIngredient {
+                    ingredient_id: pasta_id,
+                    ingredient_name: "pasta".to_string(),
+                    quantity_unit: "500g".to_string(),
+                    notes: Some("spaghetti".to_string()),
+                    steps: Vec::new(),
+                },
+                RecipeIngredient {
+                    ingredient_id: bacon_id,
+                    ingredient_name: "bacon".to_string(),
+                    quantity_unit: "200g".to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                },
+                RecipeIngredient {
+                    ingredient_id: eggs_id,
+                    ingredient_name: "eggs".to_string(),
+                    quantity_unit: "3 whole".to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                },
+            ],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+
+        let recipe_id = create_recipe(&pool, &new_recipe)
+            .await
+            .expect("Failed to create recipe");
+
+        // Verify the recipe was created
+        assert!(recipe_id > 0);
+
+        // Fetch the recipe back and verify
+        let fetched_recipe = get_recipe(&pool, recipe_id)
+            .await
+            .expect("Failed to fetch created recipe");
+
+        assert_eq!(fetched_recipe.name, "Pasta Carbonara");
+        assert_eq!(
+            fetched_recipe.instructions,
+            Some("Cook pasta, fry bacon, mix with eggs".to_string())
+        );
+        assert_eq!(fetched_recipe.ingredients.len(), 3);
+
+        // Verify ingredients
+        assert_eq!(fetched_recipe.ingredients[0].ingredient_name, "pasta");
+        assert_eq!(fetched_recipe.ingredients[0].quantity_unit, "500g");
+        assert_eq!(
+            fetched_recipe.ingredients[0].notes,
+            Some("spaghetti".to_string())
+        );
+
+        assert_eq!(fetched_recipe.ingredients[1].ingredient_name, "bacon");
+        assert_eq!(fetched_recipe.ingredients[2].ingredient_name, "eggs");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe_reuses_existing_ingredients(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // Create ingredient first
+        let flour_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("flour")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert flour")
+            .last_insert_rowid();
+
+        // Create first recipe with flour
+        let recipe1 = Recipe {
+            id: 0,
+            name: "Pancakes".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: flour_id,
+                ingredient_name: "flour".to_string(),
+                quantity_unit: "2 cups".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+
+        create_recipe(&pool, &recipe1)
+            .await
+            .expect("Failed to create first recipe");
+
+        // Count how many times "flour" exists in ingredients table
+        let flour_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM ingredients WHERE name = ?")
+                .bind("flour")
+                .fetch_one(&pool)
+                .await
+                .expect("Failed to count flour");
+
+        assert_eq!(flour_count, 1);
+
+        // Create second recipe also with flour (reusing the same ingredient_id)
+        let recipe2 = Recipe {
+            id: 0,
+            name: "Bread".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: flour_id,
+                ingredient_name: "flour".to_string(),
+                quantity_unit: "3 cups".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+
+        create_recipe(&pool, &recipe2)
+            .await
+            .expect("Failed to create second recipe");
+
+        // Flour should still only exist once in ingredients table
+        let flour_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM ingredients WHERE name = ?")
+                .bind("flour")
+                .fetch_one(&pool)
+                .await
+                .expect("Failed to count flour");
+
+        assert_eq!(
+            flour_count, 1,
+            "Flour ingredient should be reused, not duplicated"
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe_empty_ingredients(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // Create a recipe with no ingredients
+        let recipe = Recipe {
+            id: 0,
+            name: "Simple Recipe".to_string(),
+            instructions: Some("Just do it".to_string()),
+            created_at: String::new(),
+            ingredients: vec![],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+
+        let recipe_id = create_recipe(&pool, &recipe)
+            .await
+            .expect("Failed to create recipe");
+
+        // Fetch it back
+        let fetched = get_recipe(&pool, recipe_id)
+            .await
+            .expect("Failed to fetch recipe");
+
+        assert_eq!(fetched.name, "Simple Recipe");
+        assert_eq!(fetched.ingredients.len(), 0);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe_with_tags(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let mut recipe = Recipe {
+            id: 0,
+            name: "Toast".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: vec![
+                " weeknight ".to_string(),
+                "Breakfast".to_string(),
+                "WEEKNIGHT".to_string(),
+            ],
+            rating: None,
+            equipment: vec!["toaster".to_string(), " Toaster".to_string()],
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let recipe_id = create_recipe(&pool, &recipe).await.unwrap();
+        let saved = get_recipe(&pool, recipe_id).await.unwrap();
+        assert_eq!(saved.tags, vec!["Breakfast", "weeknight"]);
+        assert_eq!(saved.equipment, vec!["toaster"]);
+        assert!(saved.to_string().contains("\nEquipment: toaster\n"));
+
+        // Updating leaves the tags and equipment alone
+        recipe.tags = Vec::new();
+        recipe.equipment = Vec::new();
+        update_recipe(&pool, recipe_id, &recipe).await.unwrap();
+        let updated = get_recipe(&pool, recipe_id).await.unwrap();
+        assert_eq!((updated.tags.len(), updated.equipment.len()), (2, 1));
+
+        recipe.tags = vec!["".to_string()];
+        let Err(FeedMeError::Validation(errors)) = create_recipe(&pool, &recipe).await else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "tags[0]");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe_validates_fields(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let salt = crate::controllers::create_ingredient(&pool, "salt")
+            .await
+            .unwrap();
+
+        let mut recipe = Recipe {
+            id: 0,
+            name: "  Brine\t".to_string(),
+            instructions: Some("Dissolve.\nChill.".to_string()),
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: salt,
+                ingredient_name: "salt".to_string(),
+                quantity_unit: "50 g".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let recipe_id = create_recipe(&pool, &recipe).await.unwrap();
+        assert_eq!(get_recipe(&pool, recipe_id).await.unwrap().name, "Brine");
+
+        recipe.name = " ".to_string();
+        recipe.ingredients[0].notes = Some("coarse\u{1b}[31m".to_string());
+        let Err(FeedMeError::Validation(errors)) = create_recipe(&pool, &recipe).await else {
+            panic!("expected a validation error");
+        };
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "ingredients[0].notes"]);
+        assert_eq!(list_recipes(&pool).await.unwrap().len(), 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_update_recipe(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let rice = crate::controllers::create_ingredient(&pool, "rice")
+            .await
+            .unwrap();
+        let beans = crate::controllers::create_ingredient(&pool, "beans")
+            .await
+            .unwrap();
+        let ingredient = |ingredient_id, name: &str, quantity: &str| RecipeIngredient {
+            ingredient_id,
+            ingredient_name: name.to_string(),
+            quantity_unit: quantity.to_string(),
+            notes: None,
+            steps: Vec::new(),
+        };
+
+        let mut recipe = Recipe {
+            id: 0,
+            name: "Rice".to_string(),
+            instructions: Some("Boil.".to_string()),
+            created_at: String::new(),
+            ingredients: vec![ingredient(rice, "rice", "1 cup")],
+            servings: Some(2),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let recipe_id = create_recipe(&pool, &recipe).await.unwrap();
+
+        recipe.name = " Rice and Beans ".to_string();
+        recipe.instructions = Some("Simmer 20 minutes.".to_string());
+        recipe.ingredients = vec![
+            ingredient(beans, "beans", "1 can"),
+            ingredient(rice, "rice", "2 cups"),
+        ];
+        update_recipe(&pool, recipe_id, &recipe).await.unwrap();
+
+        let fetched = get_recipe(&pool, recipe_id).await.unwrap();
+        assert_eq!(fetched.name, "Rice and Beans");
+        assert_eq!(fetched.instructions.as_deref(), Some("Simmer 20 minutes."));
+        assert_eq!(fetched.servings, Some(2));
+        let quantities: Vec<(&str, &str)> = fetched
+            .ingredients
+            .iter()
+            .map(|i| (i.ingredient_name.as_str(), i.quantity_unit.as_str()))
+            .collect();
+        assert_eq!(quantities, vec![("beans", "1 can"), ("rice", "2 cups")]);
+        assert_eq!(fetched.timers.len(), 1);
+
+        let actions: Vec<String> = sqlx::query_scalar(
+            "SELECT action FROM audit_log WHERE entity = 'recipe' AND entity_id = ? ORDER BY id",
+        )
+        .bind(recipe_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(actions, vec!["create", "update"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_update_recipe_not_found(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let recipe = Recipe {
+            id: 0,
+            name: "Ghost".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+
+        assert!(matches!(
+            update_recipe(&pool, 99, &recipe).await,
+            Err(FeedMeError::RecipeNotFound(99))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_recipe_names_are_unique(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let recipe = |name: &str| Recipe {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let rice = create_recipe(&pool, &recipe("Rice")).await.unwrap();
+        let beans = create_recipe(&pool, &recipe("Beans")).await.unwrap();
+
+        assert!(matches!(
+            create_recipe(&pool, &recipe(" rice ")).await,
+            Err(FeedMeError::DuplicateRecipe(name)) if name == "rice"
+        ));
+        assert!(matches!(
+            crate::controllers::import_recipe(&pool, &recipe("RICE")).await,
+            Err(FeedMeError::DuplicateRecipe(_))
+        ));
+        assert!(matches!(
+            update_recipe(&pool, beans, &recipe("Rice")).await,
+            Err(FeedMeError::DuplicateRecipe(_))
+        ));
+        update_recipe(&pool, rice, &recipe("rice")).await.unwrap();
+
+        // Recipes in the trash don't hold on to their names
+        delete_recipe(&pool, rice).await.unwrap();
+        create_recipe(&pool, &recipe("Rice")).await.unwrap();
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe_stores_structured_quantity(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let sugar_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("sugar")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert sugar")
+            .last_insert_rowid();
+
+        let recipe = Recipe {
+            id: 0,
+            name: "Lemonade".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: sugar_id,
+                ingredient_name: "sugar".to_string(),
+                quantity_unit: "1 1/2 cups".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let recipe_id = create_recipe(&pool, &recipe)
+            .await
+            .expect("Failed to create recipe");
+
+        let (amount, unit): (Option<f64>, Option<String>) =
+            sqlx::query_as("SELECT amount, unit FROM recipe_ingredients WHERE recipe_id = ?")
+                .bind(recipe_id)
+                .fetch_one(&pool)
+                .await
+                .expect("Failed to fetch recipe_ingredient");
+
+        assert_eq!(amount, Some(1.5));
+        assert_eq!(unit, Some("cups".to_string()));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_recipe_many_ingredients_keeps_order(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let mut ingredients = Vec::new();
+        for i in 0..40 {
+            let name = format!("spice {:02}", i);
+            let id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+                .bind(&name)
+                .execute(&pool)
+                .await
+                .expect("Failed to insert ingredient")
+                .last_insert_rowid();
+            ingredients.push(RecipeIngredient {
+                ingredient_id: id,
+                ingredient_name: name,
+                quantity_unit: format!("{} tsp", i + 1),
+                notes: (i % 2 == 0).then(|| "ground".to_string()),
+                steps: Vec::new(),
+            });
+        }
+
+        let recipe = Recipe {
+            id: 0,
+            name: "Garam Masala".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: ingredients.clone(),
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let recipe_id = create_recipe(&pool, &recipe)
+            .await
+            .expect("Failed to create recipe");
+
+        let fetched = get_recipe(&pool, recipe_id)
+            .await
+            .expect("Failed to fetch recipe");
+
+        assert_eq!(fetched.ingredients.len(), 40);
+        for (expected, actual) in ingredients.iter().zip(&fetched.ingredients) {
+            assert_eq!(actual.ingredient_id, expected.ingredient_id);
+            assert_eq!(actual.quantity_unit, expected.quantity_unit);
+            assert_eq!(actual.notes, expected.notes);
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_list_recipes_with_filter(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let ids = crate::seed::seed_demo_data(&pool).await.unwrap();
+        let (quick, slow, unknown) = (ids[0], ids[1], ids[2]);
+
+        set_recipe_times(&pool, quick, Some(5), Some(10))
+            .await
+            .unwrap();
+        set_recipe_times(&pool, slow, Some(30), Some(60))
+            .await
+            .unwrap();
+        set_recipe_difficulty(&pool, quick, Some(Difficulty::Easy))
+            .await
+            .unwrap();
+        set_recipe_difficulty(&pool, slow, Some(Difficulty::Hard))
+            .await
+            .unwrap();
+        for (recipe_id, tag) in [
+            (quick, "Vegetarian"),
+            (slow, "vegetarian"),
+            (unknown, "weeknight"),
+        ] {
+            crate::controllers::add_recipe_tag(&pool, recipe_id, tag)
+                .await
+                .unwrap();
+        }
+        for (recipe_id, rating) in [(quick, 4), (quick, 5), (slow, 5), (unknown, 2)] {
+            crate::controllers::rate_recipe(&pool, recipe_id, rating)
+                .await
+                .unwrap();
+        }
+
+        let ids_for = |filter: RecipeFilter| {
+            let pool = pool.clone();
+            async move {
+                list_recipes_with(&pool, &filter)
+                    .await
+                    .unwrap()
+                    .iter()
+                    .map(|r| r.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(ids_for(RecipeFilter::default()).await, ids);
+        assert_eq!(
+            ids_for(RecipeFilter {
+                dietary: vec![DietaryFlag::Vegetarian],
+                ..Default::default()
+            })
+            .await,
+            vec![quick, slow]
+        );
+        assert_eq!(
+            ids_for(RecipeFilter {
+                max_total_minutes: Some(20),
+                ..Default::default()
+            })
+            .await,
+            vec![quick]
+        );
+        assert_eq!(
+            ids_for(RecipeFilter {
+                max_difficulty: Some(Difficulty::Medium),
+                ..Default::default()
+            })
+            .await,
+            vec![quick]
+        );
+        assert_eq!(
+            ids_for(RecipeFilter {
+                min_rating: Some(4.75),
+                ..Default::default()
+            })
+            .await,
+            vec![slow]
+        );
+        assert_eq!(
+            ids_for(RecipeFilter {
+                tags: vec!["vegetarian".to_string()],
+                min_rating: Some(4.0),
+                max_difficulty: Some(Difficulty::Hard),
+                ..Default::default()
+            })
+            .await,
+            vec![quick, slow]
+        );
+
+        set_recipe_category(&pool, quick, Some(Category::Breakfast))
+            .await
+            .unwrap();
+        set_recipe_category(&pool, slow, Some(Category::Dinner))
+            .await
+            .unwrap();
+        assert_eq!(
+            ids_for(RecipeFilter {
+                categories: vec![Category::Dinner, Category::Lunch],
+                ..Default::default()
+            })
+            .await,
+            vec![slow]
+        );
+        assert!(matches!(
+            set_recipe_category(&pool, 999, None).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+
+        set_recipe_visibility(&pool, slow, Visibility::Private)
+            .await
+            .unwrap();
+        set_recipe_visibility(&pool, unknown, Visibility::Public)
+            .await
+            .unwrap();
+        for (audience, expected) in [
+            (Visibility::Private, ids.clone()),
+            (
+                Visibility::Household,
+                ids.iter().copied().filter(|&id| id != slow).collect(),
+            ),
+            (Visibility::Public, vec![unknown]),
+        ] {
+            let filter = RecipeFilter {
+                audience: Some(audience),
+                ..Default::default()
+            };
+            assert_eq!(ids_for(filter).await, expected);
         }
+        let slow_recipe = get_recipe(&pool, slow).await.unwrap();
+        assert_eq!(slow_recipe.visibility, Visibility::Private);
+        let slow_name = slow_recipe.name;
+        let hidden = crate::controllers::search_full_text_with(
+            &pool,
+            &slow_name,
+            &RecipeFilter {
+                audience: Some(Visibility::Household),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(hidden.is_empty());
+        assert!(matches!(
+            set_recipe_visibility(&pool, 999, Visibility::Public).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+        let secret = create_recipe(
+            &pool,
+            &Recipe {
+                name: "Secret Sauce".to_string(),
+                visibility: Visibility::Private,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_recipe(&pool, secret).await.unwrap().visibility,
+            Visibility::Private
+        );
+
+        assert!(matches!(
+            set_recipe_difficulty(&pool, 999, None).await,
+            Err(crate::error::FeedMeError::RecipeNotFound(999))
+        ));
     }
 
     #[rstest]
     #[tokio::test]
-    async fn test_get_recipe_no_ingredients(#[future] test_db: SqlitePool) {
+    async fn test_recipe_source(#[future] test_db: SqlitePool) {
         let pool = test_db.await;
+        let mut recipe = Recipe {
+            id: 0,
+            name: "Pie".to_string(),
+            instructions: None,
+            servings: None,
+            source_url: Some(" https://example.com/pie ".to_string()),
+            source_name: Some("  ".to_string()),
+            ingredients: Vec::new(),
+            tags: Vec::new(),
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            rating: None,
+            nutrition: None,
+        };
+        let recipe_id = create_recipe(&pool, &recipe).await.unwrap();
+        let saved = get_recipe(&pool, recipe_id).await.unwrap();
+        assert_eq!(saved.source_url.as_deref(), Some("https://example.com/pie"));
+        assert_eq!(saved.source_name, None);
+        assert!(
+            saved
+                .to_string()
+                .contains("\nSource: https://example.com/pie\n")
+        );
 
-        // Insert a recipe without ingredients
-        let recipe_id = sqlx::query("INSERT INTO recipes (name) VALUES (?)")
-            .bind("Empty Recipe")
-            .execute(&pool)
-            .await
-            .expect("Failed to insert recipe")
-            .last_insert_rowid();
-
-        // Fetch the recipe
-        let recipe = get_recipe(&pool, recipe_id)
-            .await
-            .expect("Failed to fetch recipe");
+        recipe.source_name = Some("Grandma's blog".to_string());
+        update_recipe(&pool, recipe_id, &recipe).await.unwrap();
+        let record = list_recipes(&pool).await.unwrap().remove(0);
+        assert_eq!(record.source_name.as_deref(), Some("Grandma's blog"));
+        assert!(
+            get_recipe(&pool, recipe_id)
+                .await
+                .unwrap()
+                .to_string()
+                .contains("\nSource: Grandma's blog (https://example.com/pie)\n")
+        );
 
-        // Verify the recipe has no ingredients
-        assert_eq!(recipe.name, "Empty Recipe");
-        assert_eq!(recipe.ingredients.len(), 0);
-        assert_eq!(recipe.instructions, None);
+        recipe.source_url = Some("example.com/pie".to_string());
+        let Err(FeedMeError::Validation(errors)) = update_recipe(&pool, recipe_id, &recipe).await
+        else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "source_url");
+        assert_eq!(
+            errors[0].to_string(),
+            "source_url must be an http:// or https:// link"
+        );
     }
 
     #[rstest]
     #[tokio::test]
-    async fn test_create_recipe(#[future] test_db: SqlitePool) {
+    async fn test_step_ingredients(#[future] test_db: SqlitePool) {
         let pool = test_db.await;
-
-        // First, create ingredients in the database
-        let pasta_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
-            .bind("pasta")
-            .execute(&pool)
-            .await
-            .expect("Failed to insert pasta")
-            .last_insert_rowid();
-
-        let bacon_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
-            .bind("bacon")
-            .execute(&pool)
+        let flour = crate::controllers::create_ingredient(&pool, "flour")
             .await
-            .expect("Failed to insert bacon")
-            .last_insert_rowid();
-
-        let eggs_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
-            .bind("eggs")
-            .execute(&pool)
+            .unwrap();
+        let butter = crate::controllers::create_ingredient(&pool, "butter")
             .await
-            .expect("Failed to insert eggs")
-            .last_insert_rowid();
-
-        // Create a recipe
-        let new_recipe = Recipe {
-            id: 0, // Will be ignored
-            name: "Pasta Carbonara".to_string(),
-            instructions: Some("Cook pasta, fry bacon, mix with eggs".to_string()),
-            created_at: String::new(), // Will be ignored
+            .unwrap();
+        let ingredient = |ingredient_id, steps: Vec<usize>| RecipeIngredient {
+            ingredient_id,
+            ingredient_name: String::new(),
+            quantity_unit: "100 g".to_string(),
+            notes: None,
+            steps,
+        };
+        let mut recipe = Recipe {
+            id: 0,
+            name: "Shortbread".to_string(),
+            instructions: Some(
+                "Rub the butter into the flour\n\nPress into a tin\nBake".to_string(),
+            ),
+            servings: None,
             ingredients: vec![
-                RecipeIngredient {
-                    ingredient_id: pasta_id,
-                    ingredient_name: "pasta".to_string(),
-                    quantity_unit: "500g".to_string(),
-                    notes: Some("spaghetti".to_string()),
-                },
-                RecipeIngredient {
-                    ingredient_id: bacon_id,
-                    ingredient_name: "bacon".to_string(),
-                    quantity_unit: "200g".to_string(),
-                    notes: None,
-                },
-                RecipeIngredient {
-                    ingredient_id: eggs_id,
-                    ingredient_name: "eggs".to_string(),
-                    quantity_unit: "3 whole".to_string(),
-                    notes: None,
-                },
+                ingredient(flour, vec![0]),
+                ingredient(butter, vec![2, 0, 2]),
             ],
+            tags: Vec::new(),
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            rating: None,
+            source_url: None,
+            source_name: None,
+            nutrition: None,
         };
+        let recipe_id = create_recipe(&pool, &recipe).await.unwrap();
 
-        let recipe_id = create_recipe(&pool, &new_recipe)
-            .await
-            .expect("Failed to create recipe");
-
-        // Verify the recipe was created
-        assert!(recipe_id > 0);
+        let steps = |recipe: &Recipe| -> Vec<Vec<usize>> {
+            recipe.ingredients.iter().map(|i| i.steps.clone()).collect()
+        };
+        let saved = get_recipe(&pool, recipe_id).await.unwrap();
+        assert_eq!(steps(&saved), vec![vec![0], vec![0, 2]]);
+        assert!(saved.to_string().contains("  - 100 g butter [step 1, 3]\n"));
 
-        // Fetch the recipe back and verify
-        let fetched_recipe = get_recipe(&pool, recipe_id)
+        // Replacing one step's links leaves the other steps alone
+        set_step_ingredients(&pool, recipe_id, 1, &[0, 1])
             .await
-            .expect("Failed to fetch created recipe");
-
-        assert_eq!(fetched_recipe.name, "Pasta Carbonara");
-        assert_eq!(
-            fetched_recipe.instructions,
-            Some("Cook pasta, fry bacon, mix with eggs".to_string())
-        );
-        assert_eq!(fetched_recipe.ingredients.len(), 3);
+            .unwrap();
+        set_step_ingredients(&pool, recipe_id, 0, &[1])
+            .await
+            .unwrap();
+        let saved = get_recipe(&pool, recipe_id).await.unwrap();
+        assert_eq!(steps(&saved), vec![vec![1], vec![0, 1, 2]]);
+
+        // Updating keeps the links the recipe carries
+        recipe.ingredients = saved.ingredients.clone();
+        recipe.ingredients[0].steps = Vec::new();
+        update_recipe(&pool, recipe_id, &recipe).await.unwrap();
+        let saved = get_recipe(&pool, recipe_id).await.unwrap();
+        assert_eq!(steps(&saved), vec![vec![], vec![0, 1, 2]]);
+
+        let Err(FeedMeError::Validation(errors)) =
+            set_step_ingredients(&pool, recipe_id, 3, &[0, 2]).await
+        else {
+            panic!("expected a validation error");
+        };
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(fields, vec!["step", "ingredients[1]"]);
+        assert_eq!(errors[0].to_string(), "step must be below 3");
 
-        // Verify ingredients
-        assert_eq!(fetched_recipe.ingredients[0].ingredient_name, "pasta");
-        assert_eq!(fetched_recipe.ingredients[0].quantity_unit, "500g");
-        assert_eq!(
-            fetched_recipe.ingredients[0].notes,
-            Some("spaghetti".to_string())
-        );
+        recipe.ingredients[1].steps = vec![3];
+        let Err(FeedMeError::Validation(errors)) = create_recipe(&pool, &recipe).await else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "ingredients[1].steps[0]");
 
-        assert_eq!(fetched_recipe.ingredients[1].ingredient_name, "bacon");
-        assert_eq!(fetched_recipe.ingredients[2].ingredient_name, "eggs");
+        assert!(matches!(
+            set_step_ingredients(&pool, 999, 0, &[]).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
     }
 
     #[rstest]
     #[tokio::test]
-    async fn test_create_recipe_reuses_existing_ingredients(#[future] test_db: SqlitePool) {
+    async fn test_clone_recipe(#[future] test_db: SqlitePool) {
         let pool = test_db.await;
-
-        // Create ingredient first
-        let flour_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
-            .bind("flour")
-            .execute(&pool)
+        let flour = crate::controllers::create_ingredient(&pool, "flour")
             .await
-            .expect("Failed to insert flour")
-            .last_insert_rowid();
-
-        // Create first recipe with flour
-        let recipe1 = Recipe {
+            .unwrap();
+        let recipe = Recipe {
             id: 0,
             name: "Pancakes".to_string(),
-            instructions: None,
-            created_at: String::new(),
+            instructions: Some("Whisk\nFry".to_string()),
+            servings: Some(4),
             ingredients: vec![RecipeIngredient {
-                ingredient_id: flour_id,
-                ingredient_name: "flour".to_string(),
-                quantity_unit: "2 cups".to_string(),
-                notes: None,
+                ingredient_id: flour,
+                ingredient_name: String::new(),
+                quantity_unit: "1 1/2 cups".to_string(),
+                notes: Some("sifted".to_string()),
+                steps: vec![0],
             }],
+            tags: vec!["breakfast".to_string()],
+            equipment: vec!["Griddle".to_string()],
+            visibility: Visibility::default(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            rating: None,
+            source_url: Some("https://example.com/pancakes".to_string()),
+            source_name: None,
+            nutrition: None,
         };
-
-        create_recipe(&pool, &recipe1)
+        let original_id = create_recipe(&pool, &recipe).await.unwrap();
+        set_recipe_times(&pool, original_id, Some(5), Some(15))
             .await
-            .expect("Failed to create first recipe");
+            .unwrap();
+        set_recipe_difficulty(&pool, original_id, Some(Difficulty::Easy))
+            .await
+            .unwrap();
+        set_recipe_category(&pool, original_id, Some(Category::Breakfast))
+            .await
+            .unwrap();
+        set_favorite(&pool, original_id, true).await.unwrap();
+        crate::controllers::rate_recipe(&pool, original_id, 5)
+            .await
+            .unwrap();
 
-        // Count how many times "flour" exists in ingredients table
-        let flour_count: i64 =
-            sqlx::query_scalar("SELECT COUNT(*) FROM ingredients WHERE name = ?")
-                .bind("flour")
+        let copy_id = clone_recipe(&pool, original_id, " Pancakes (GF) ")
+            .await
+            .expect("Failed to clone");
+        assert_ne!(copy_id, original_id);
+
+        let original = get_recipe(&pool, original_id).await.unwrap();
+        let copy = get_recipe(&pool, copy_id).await.unwrap();
+        assert_eq!(copy.name, "Pancakes (GF)");
+        assert_eq!(original.name, "Pancakes");
+        assert_eq!(copy.instructions, original.instructions);
+        assert_eq!(copy.servings, Some(4));
+        assert_eq!(copy.source_url, original.source_url);
+        assert_eq!(copy.tags, original.tags);
+        assert_eq!(copy.equipment, original.equipment);
+        assert_eq!(copy.ingredients[0].quantity_unit, "1 1/2 cups");
+        assert_eq!(copy.ingredients[0].notes.as_deref(), Some("sifted"));
+        assert_eq!(copy.ingredients[0].steps, vec![0]);
+        assert!(copy.rating.is_none());
+
+        let metadata = get_recipe_metadata(&pool, copy_id).await.unwrap();
+        assert_eq!(metadata.prep_minutes, Some(5));
+        assert_eq!(metadata.category, Some(Category::Breakfast));
+        let (difficulty, is_favorite): (Option<String>, bool) =
+            sqlx::query_as("SELECT difficulty, is_favorite FROM recipes WHERE id = ?")
+                .bind(copy_id)
                 .fetch_one(&pool)
                 .await
-                .expect("Failed to count flour");
+                .unwrap();
+        assert_eq!(difficulty.as_deref(), Some("easy"));
+        assert!(!is_favorite);
+
+        // The copy is independent of the original
+        update_recipe(
+            &pool,
+            copy_id,
+            &Recipe {
+                ingredients: Vec::new(),
+                ..copy
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_recipe(&pool, original_id)
+                .await
+                .unwrap()
+                .ingredients
+                .len(),
+            1
+        );
 
-        assert_eq!(flour_count, 1);
+        let Err(FeedMeError::Validation(errors)) = clone_recipe(&pool, original_id, " ").await
+        else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "name");
+        assert!(matches!(
+            clone_recipe(&pool, 999, "Copy").await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+    }
 
-        // Create second recipe also with flour (reusing the same ingredient_id)
-        let recipe2 = Recipe {
+    #[rstest]
+    #[tokio::test]
+    async fn test_delete_and_restore_recipe(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let flour = crate::controllers::create_ingredient(&pool, "flour")
+            .await
+            .unwrap();
+        let recipe = |name: &str| Recipe {
             id: 0,
-            name: "Bread".to_string(),
-            instructions: None,
-            created_at: String::new(),
+            name: name.to_string(),
+            instructions: Some("Whisk and fry".to_string()),
+            servings: Some(4),
             ingredients: vec![RecipeIngredient {
-                ingredient_id: flour_id,
-                ingredient_name: "flour".to_string(),
-                quantity_unit: "3 cups".to_string(),
+                ingredient_id: flour,
+                ingredient_name: String::new(),
+                quantity_unit: "2 cups".to_string(),
                 notes: None,
+                steps: Vec::new(),
             }],
+            tags: vec!["breakfast".to_string()],
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            rating: None,
+            source_url: None,
+            source_name: None,
+            nutrition: None,
         };
-
-        create_recipe(&pool, &recipe2)
+        let pancakes = create_recipe(&pool, &recipe("Pancakes")).await.unwrap();
+        let waffles = create_recipe(&pool, &recipe("Waffles")).await.unwrap();
+
+        delete_recipe(&pool, pancakes).await.unwrap();
+        assert!(matches!(
+            get_recipe(&pool, pancakes).await,
+            Err(FeedMeError::RecipeNotFound(_))
+        ));
+        let listed: Vec<i64> = list_recipes(&pool)
             .await
-            .expect("Failed to create second recipe");
-
-        // Flour should still only exist once in ingredients table
-        let flour_count: i64 =
-            sqlx::query_scalar("SELECT COUNT(*) FROM ingredients WHERE name = ?")
-                .bind("flour")
+            .unwrap()
+            .iter()
+            .map(|recipe| recipe.id)
+            .collect();
+        assert_eq!(listed, vec![waffles]);
+        let found: Vec<i64> = crate::controllers::search_full_text(&pool, "whisk")
+            .await
+            .unwrap()
+            .iter()
+            .map(|result| result.recipe_id)
+            .collect();
+        assert_eq!(found, vec![waffles]);
+        // Only the remaining recipe's flour is on the list
+        assert_eq!(
+            generate_shopping_list(&pool, &[pancakes, waffles])
+                .await
+                .unwrap()[0]
+                .combined_quantity,
+            "2 cups"
+        );
+        assert!(matches!(
+            set_favorite(&pool, pancakes, true).await,
+            Err(FeedMeError::RecipeNotFound(_))
+        ));
+        assert!(matches!(
+            delete_recipe(&pool, pancakes).await,
+            Err(FeedMeError::RecipeNotFound(_))
+        ));
+        let trash: Vec<i64> = list_deleted_recipes(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|recipe| recipe.id)
+            .collect();
+        assert_eq!(trash, vec![pancakes]);
+
+        // Restoring brings it back whole
+        restore_recipe(&pool, pancakes).await.unwrap();
+        let restored = get_recipe(&pool, pancakes).await.unwrap();
+        assert_eq!(restored.ingredients.len(), 1);
+        assert_eq!(restored.tags, vec!["breakfast"]);
+        assert!(list_deleted_recipes(&pool).await.unwrap().is_empty());
+        assert!(matches!(
+            restore_recipe(&pool, pancakes).await,
+            Err(FeedMeError::RecipeNotFound(_))
+        ));
+
+        // Purging only removes what's in the trash
+        delete_recipe(&pool, waffles).await.unwrap();
+        assert_eq!(purge_deleted(&pool).await.unwrap(), 1);
+        assert!(matches!(
+            restore_recipe(&pool, waffles).await,
+            Err(FeedMeError::RecipeNotFound(_))
+        ));
+        let lines: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM recipe_ingredients WHERE recipe_id = ?")
+                .bind(waffles)
                 .fetch_one(&pool)
                 .await
-                .expect("Failed to count flour");
-
-        assert_eq!(
-            flour_count, 1,
-            "Flour ingredient should be reused, not duplicated"
+                .unwrap();
+        assert_eq!(lines, 0);
+        assert_eq!(list_recipes(&pool).await.unwrap().len(), 1);
+        // The purge is the last entry in the recipe's history
+        let history = crate::controllers::get_audit_log(&pool, "recipe", waffles)
+            .await
+            .unwrap();
+        let last = history.last().unwrap();
+        assert_eq!(last.action, "delete");
+        assert!(
+            last.old_value
+                .as_deref()
+                .unwrap()
+                .contains("\"deleted\":true")
         );
+        assert_eq!(history.len(), 3);
     }
 
     #[rstest]
     #[tokio::test]
-    async fn test_create_recipe_empty_ingredients(#[future] test_db: SqlitePool) {
+    async fn test_favorites(#[future] test_db: SqlitePool) {
         let pool = test_db.await;
-
-        // Create a recipe with no ingredients
-        let recipe = Recipe {
-            id: 0,
-            name: "Simple Recipe".to_string(),
-            instructions: Some("Just do it".to_string()),
-            created_at: String::new(),
-            ingredients: vec![],
+        let ids = crate::seed::seed_demo_data(&pool).await.unwrap();
+        assert!(get_favorites(&pool).await.unwrap().is_empty());
+
+        set_favorite(&pool, ids[2], true).await.unwrap();
+        set_favorite(&pool, ids[0], true).await.unwrap();
+        set_favorite(&pool, ids[1], true).await.unwrap();
+        set_favorite(&pool, ids[1], false).await.unwrap();
+
+        let mut expected = vec![
+            get_recipe(&pool, ids[0]).await.unwrap().name,
+            get_recipe(&pool, ids[2]).await.unwrap().name,
+        ];
+        expected.sort();
+        let favorites: Vec<String> = get_favorites(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|recipe| recipe.name)
+            .collect();
+        assert_eq!(favorites, expected);
+
+        let filter = RecipeFilter {
+            favorites_only: true,
+            ..Default::default()
         };
-
-        let recipe_id = create_recipe(&pool, &recipe)
+        let listed: Vec<i64> = list_recipes_with(&pool, &filter)
             .await
-            .expect("Failed to create recipe");
+            .unwrap()
+            .iter()
+            .map(|recipe| recipe.id)
+            .collect();
+        assert_eq!(listed, vec![ids[0], ids[2]]);
 
-        // Fetch it back
-        let fetched = get_recipe(&pool, recipe_id)
+        let starred = crate::controllers::list_recipes_by_rating(&pool, &filter)
             .await
-            .expect("Failed to fetch recipe");
-
-        assert_eq!(fetched.name, "Simple Recipe");
-        assert_eq!(fetched.ingredients.len(), 0);
+            .unwrap();
+        assert!(starred.iter().all(|recipe| recipe.is_favorite));
+        assert!(starred[0].to_string().contains(" ★ "));
+
+        assert!(matches!(
+            set_favorite(&pool, 999, true).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
     }
 
     #[rstest]
@@ -481,6 +2468,37 @@ mod tests {
         assert_eq!(shopping_list.len(), 0);
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_shopping_list_adds_restock(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let rice = crate::controllers::create_ingredient(&pool, "rice")
+            .await
+            .unwrap();
+        let item = crate::models::api::PantryItem {
+            id: 0,
+            ingredient_id: rice,
+            ingredient_name: String::new(),
+            amount: 0.5,
+            unit: Some("kg".to_string()),
+            expires_on: None,
+            is_staple: true,
+            icon: None,
+            min_amount: Some(2.0),
+        };
+        crate::controllers::add_to_pantry(&pool, &item)
+            .await
+            .unwrap();
+
+        let shopping_list = generate_shopping_list(&pool, &[])
+            .await
+            .expect("Failed to generate shopping list");
+
+        assert_eq!(shopping_list.len(), 1);
+        assert_eq!(shopping_list[0].ingredient_name, "rice");
+        assert_eq!(shopping_list[0].combined_quantity, "1.5 kg");
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_generate_shopping_list_single_recipe(#[future] test_db: SqlitePool) {
@@ -513,14 +2531,26 @@ mod tests {
                     ingredient_name: "pasta".to_string(),
                     quantity_unit: "500g".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
                 RecipeIngredient {
                     ingredient_id: sauce_id,
                     ingredient_name: "tomato sauce".to_string(),
                     quantity_unit: "1 jar".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
             ],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
         };
 
         let recipe_id = create_recipe(&pool, &recipe)
@@ -539,7 +2569,7 @@ mod tests {
             .iter()
             .find(|item| item.ingredient_name == "pasta")
             .expect("Pasta not found");
-        assert_eq!(pasta.combined_quantity, "500g");
+        assert_eq!(pasta.combined_quantity, "500 g");
 
         // Check tomato sauce
         let sauce = shopping_list
@@ -604,20 +2634,33 @@ mod tests {
                     ingredient_name: "flour".to_string(),
                     quantity_unit: "2 cups".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
                 RecipeIngredient {
                     ingredient_id: milk_id,
                     ingredient_name: "milk".to_string(),
                     quantity_unit: "1 cup".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
                 RecipeIngredient {
                     ingredient_id: eggs_id,
                     ingredient_name: "eggs".to_string(),
                     quantity_unit: "2 whole".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
             ],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
         };
 
         let recipe1_id = create_recipe(&pool, &recipe1)
@@ -636,20 +2679,33 @@ mod tests {
                     ingredient_name: "flour".to_string(),
                     quantity_unit: "3 cups".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
                 RecipeIngredient {
                     ingredient_id: sugar_id,
                     ingredient_name: "sugar".to_string(),
                     quantity_unit: "1 cup".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
                 RecipeIngredient {
                     ingredient_id: butter_id,
                     ingredient_name: "butter".to_string(),
                     quantity_unit: "1 stick".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
             ],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
         };
 
         let recipe2_id = create_recipe(&pool, &recipe2)
@@ -669,7 +2725,7 @@ mod tests {
             .iter()
             .find(|item| item.ingredient_name == "flour")
             .expect("Flour not found");
-        assert_eq!(flour.combined_quantity, "2 cups + 3 cups");
+        assert_eq!(flour.combined_quantity, "5 cups");
 
         // Check milk (only in pancakes)
         let milk = shopping_list
@@ -685,4 +2741,85 @@ mod tests {
             .expect("Sugar not found");
         assert_eq!(sugar.combined_quantity, "1 cup");
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_shopping_list_sums_units_by_density(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let flour_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+            .bind("flour")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert flour")
+            .last_insert_rowid();
+
+        // Cups and grams of flour are summed once its density is known
+        let pancakes = Recipe {
+            id: 0,
+            name: "Pancakes".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: flour_id,
+                ingredient_name: "flour".to_string(),
+                quantity_unit: "2 cups".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let pancakes_id = create_recipe(&pool, &pancakes).await.unwrap();
+        let bread = Recipe {
+            id: 0,
+            name: "Bread".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: flour_id,
+                ingredient_name: "flour".to_string(),
+                quantity_unit: "500 g".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let bread_id = create_recipe(&pool, &bread).await.unwrap();
+        let flour_quantity = |list: Vec<ShoppingListItem>| {
+            list.into_iter()
+                .find(|item| item.ingredient_name == "flour")
+                .map(|item| item.combined_quantity)
+        };
+
+        let list = generate_shopping_list(&pool, &[pancakes_id, bread_id])
+            .await
+            .unwrap();
+        assert_eq!(flour_quantity(list).as_deref(), Some("2 cups + 500 g"));
+
+        crate::controllers::set_ingredient_density(&pool, flour_id, Some(0.53))
+            .await
+            .unwrap();
+        let list = generate_shopping_list(&pool, &[pancakes_id, bread_id])
+            .await
+            .unwrap();
+        assert_eq!(flour_quantity(list).as_deref(), Some("5.99 cups"));
+    }
 }
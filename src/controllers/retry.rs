@@ -0,0 +1,99 @@
+use std::future::Future;
+use std::time::Duration;
+
+use crate::error::Result;
+
+/// Attempts made before a busy error is returned to the caller
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry; doubled on each subsequent one
+const INITIAL_BACKOFF: Duration = Duration::from_millis(25);
+
+/// Run a write operation, retrying with exponential backoff while the
+/// database is locked by another connection (e.g. the TUI and the server
+/// sharing one file). Other errors are returned immediately.
+/// The operation must be safe to re-run, i.e. do all its work in one transaction
+pub(crate) async fn with_busy_retry<T, F, Fut>(mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        match operation().await {
+            Err(err) if err.is_busy() && attempt < MAX_ATTEMPTS => {
+                tracing::debug!(attempt, ?backoff, "database busy, retrying");
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::create_ingredient;
+    use crate::error::FeedMeError;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use sqlx::{Connection, SqliteConnection};
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_non_busy_errors_are_not_retried() {
+        let mut attempts = 0;
+
+        let result: Result<()> = with_busy_retry(|| {
+            attempts += 1;
+            async { Err(FeedMeError::RecipeNotFound(1)) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(FeedMeError::RecipeNotFound(1))));
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_write_succeeds_once_lock_is_released() {
+        let path =
+            std::env::temp_dir().join(format!("feedme-retry-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // No SQLite-level waiting, so only the retry loop can rescue the write
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))
+            .unwrap()
+            .create_if_missing(true)
+            .busy_timeout(Duration::ZERO);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options.clone())
+            .await
+            .expect("Failed to open pool");
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // Another writer holds the lock for a moment
+        let mut other = SqliteConnection::connect_with(&options).await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut other)
+            .await
+            .unwrap();
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            sqlx::query("COMMIT").execute(&mut other).await.unwrap();
+            other.close().await.unwrap();
+        });
+
+        let id = create_ingredient(&pool, "salt")
+            .await
+            .expect("Write should succeed after retrying");
+        assert!(id > 0);
+
+        release.await.unwrap();
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+}
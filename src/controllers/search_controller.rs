@@ -0,0 +1,493 @@
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+
+use crate::error::Result;
+use crate::models::RecipeRecord;
+use crate::models::api::{Difficulty, IngredientQuery, RecipeFilter, SearchResult, Visibility};
+
+/// Search recipe names, instructions, ingredient notes and ingredient names
+/// Every whitespace-separated term must match somewhere in the recipe; results
+/// are ordered best match first
+pub async fn search_full_text(pool: &SqlitePool, query: &str) -> Result<Vec<SearchResult>> {
+    search_full_text_with(pool, query, &RecipeFilter::default()).await
+}
+
+/// Like `search_full_text`, returning only recipes that pass `filter`
+pub async fn search_full_text_with(
+    pool: &SqlitePool,
+    query: &str,
+    filter: &RecipeFilter,
+) -> Result<Vec<SearchResult>> {
+    let Some(match_expr) = to_match_expression(query) else {
+        return Ok(Vec::new());
+    };
+
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        r#"
+        SELECT
+            r.id as recipe_id,
+            r.name,
+            bm25(recipe_search, 10.0, 1.0, 1.0, 5.0) as rank
+        FROM recipe_search
+        JOIN recipes r ON r.id = recipe_search.rowid
+        WHERE recipe_search MATCH "#,
+    );
+    builder.push_bind(match_expr);
+    push_recipe_filter(&mut builder, filter);
+    builder.push(" ORDER BY rank, r.id");
+
+    let rows = builder.build().fetch_all(pool).await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SearchResult {
+            recipe_id: row.get("recipe_id"),
+            name: row.get("name"),
+            rank: row.get("rank"),
+        })
+        .collect())
+}
+
+/// Recipes whose ingredients satisfy `query`, ordered by name
+/// An empty query matches every recipe
+pub async fn search_by_ingredients(
+    pool: &SqlitePool,
+    query: &IngredientQuery,
+) -> Result<Vec<RecipeRecord>> {
+    search_by_ingredients_with(pool, query, &RecipeFilter::default()).await
+}
+
+/// Like `search_by_ingredients`, returning only recipes that pass `filter`
+pub async fn search_by_ingredients_with(
+    pool: &SqlitePool,
+    query: &IngredientQuery,
+    filter: &RecipeFilter,
+) -> Result<Vec<RecipeRecord>> {
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "SELECT r.id, r.name, r.instructions, r.servings, r.source_url, r.source_name, r.created_at FROM recipes r WHERE 1 = 1",
+    );
+
+    let groups = query
+        .required
+        .iter()
+        .map(|group| (" AND r.id IN ", group))
+        .chain((!query.excluded.is_empty()).then_some((" AND r.id NOT IN ", &query.excluded)));
+    for (condition, names) in groups {
+        builder.push(condition);
+        builder.push(
+            "(SELECT ri.recipe_id FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id WHERE i.name COLLATE NOCASE IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for name in names {
+            separated.push_bind(name.clone());
+        }
+        separated.push_unseparated("))");
+    }
+
+    push_recipe_filter(&mut builder, filter);
+    builder.push(" ORDER BY r.name, r.id");
+
+    Ok(builder
+        .build_query_as::<RecipeRecord>()
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Recipes using any of the given ingredients, ordered by name
+/// The by-ID counterpart of `search_by_ingredients`, for callers that
+/// already hold ingredient IDs ("what can I cook with chicken")
+pub async fn get_recipes_containing(
+    pool: &SqlitePool,
+    ingredient_ids: &[i64],
+) -> Result<Vec<RecipeRecord>> {
+    get_recipes_containing_with(pool, ingredient_ids, &RecipeFilter::default()).await
+}
+
+/// Like `get_recipes_containing`, returning only recipes that pass `filter`
+pub async fn get_recipes_containing_with(
+    pool: &SqlitePool,
+    ingredient_ids: &[i64],
+    filter: &RecipeFilter,
+) -> Result<Vec<RecipeRecord>> {
+    recipes_containing(pool, ingredient_ids, 1, filter).await
+}
+
+/// Recipes using every one of the given ingredients, ordered by name
+pub async fn get_recipes_containing_all(
+    pool: &SqlitePool,
+    ingredient_ids: &[i64],
+) -> Result<Vec<RecipeRecord>> {
+    let mut distinct = ingredient_ids.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+    recipes_containing(pool, &distinct, distinct.len(), &RecipeFilter::default()).await
+}
+
+/// Recipes passing `filter` and using at least `at_least` of the given ingredients
+async fn recipes_containing(
+    pool: &SqlitePool,
+    ingredient_ids: &[i64],
+    at_least: usize,
+    filter: &RecipeFilter,
+) -> Result<Vec<RecipeRecord>> {
+    if ingredient_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        r#"
+        SELECT r.id, r.name, r.instructions, r.servings, r.source_url, r.source_name, r.created_at
+        FROM recipes r
+        WHERE r.id IN (
+            SELECT recipe_id FROM recipe_ingredients
+            WHERE ingredient_id IN ("#,
+    );
+    let mut separated = builder.separated(", ");
+    for &id in ingredient_ids {
+        separated.push_bind(id);
+    }
+    separated.push_unseparated(") GROUP BY recipe_id HAVING COUNT(DISTINCT ingredient_id) >= ");
+    builder.push_bind(at_least as i64);
+    builder.push(")");
+    push_recipe_filter(&mut builder, filter);
+    builder.push(" ORDER BY r.name, r.id");
+
+    Ok(builder
+        .build_query_as::<RecipeRecord>()
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Append `filter` to a query over `recipes r` as "AND ..." conditions
+/// Recipes in the trash never match
+pub(crate) fn push_recipe_filter(builder: &mut QueryBuilder<'_, Sqlite>, filter: &RecipeFilter) {
+    builder.push(" AND r.deleted_at IS NULL");
+    for tag in filter.required_tags() {
+        builder.push(
+            " AND r.id IN (SELECT rt.recipe_id FROM recipe_tags rt JOIN tags t ON rt.tag_id = t.id WHERE t.name = ",
+        );
+        builder.push_bind(tag);
+        builder.push(")");
+    }
+
+    if let Some(max_minutes) = filter.max_total_minutes {
+        builder.push(
+            " AND (r.prep_minutes IS NOT NULL OR r.cook_minutes IS NOT NULL) AND COALESCE(r.prep_minutes, 0) + COALESCE(r.cook_minutes, 0) <= ",
+        );
+        builder.push_bind(max_minutes);
+    }
+
+    if let Some(max_difficulty) = filter.max_difficulty {
+        builder.push(" AND r.difficulty IN (");
+        let mut separated = builder.separated(", ");
+        for difficulty in Difficulty::ALL
+            .into_iter()
+            .filter(|difficulty| *difficulty <= max_difficulty)
+        {
+            separated.push_bind(difficulty.as_str());
+        }
+        separated.push_unseparated(")");
+    }
+
+    if !filter.categories.is_empty() {
+        builder.push(" AND r.category IN (");
+        let mut separated = builder.separated(", ");
+        for category in &filter.categories {
+            separated.push_bind(category.as_str());
+        }
+        separated.push_unseparated(")");
+    }
+
+    if let Some(audience) = filter.audience {
+        builder.push(" AND r.visibility IN (");
+        let mut separated = builder.separated(", ");
+        for visibility in Visibility::ALL
+            .into_iter()
+            .filter(|visibility| *visibility >= audience)
+        {
+            separated.push_bind(visibility.as_str());
+        }
+        separated.push_unseparated(")");
+    }
+
+    if filter.favorites_only {
+        builder.push(" AND r.is_favorite");
+    }
+
+    if !filter.without_equipment.is_empty() {
+        builder.push(
+            " AND NOT EXISTS (SELECT 1 FROM recipe_equipment re JOIN equipment e ON re.equipment_id = e.id WHERE re.recipe_id = r.id AND e.name IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for item in &filter.without_equipment {
+            separated.push_bind(item.trim().to_string());
+        }
+        separated.push_unseparated("))");
+    }
+
+    if let Some(min_rating) = filter.min_rating {
+        builder.push(
+            " AND (SELECT AVG(rr.rating) FROM recipe_ratings rr WHERE rr.recipe_id = r.id) >= ",
+        );
+        builder.push_bind(min_rating);
+    }
+}
+
+/// Quote each term so user input can't be interpreted as FTS5 query syntax
+fn to_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{create_ingredient, create_recipe, set_recipe_visibility};
+    use crate::models::api::{Recipe, RecipeIngredient};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    async fn add_recipe(
+        pool: &SqlitePool,
+        name: &str,
+        instructions: Option<&str>,
+        ingredients: &[(i64, &str, Option<&str>)],
+    ) -> i64 {
+        let recipe = Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: instructions.map(str::to_string),
+            created_at: String::new(),
+            ingredients: ingredients
+                .iter()
+                .map(|(id, name, notes)| RecipeIngredient {
+                    ingredient_id: *id,
+                    ingredient_name: name.to_string(),
+                    quantity_unit: "1 whole".to_string(),
+                    notes: notes.map(str::to_string),
+                    steps: Vec::new(),
+                })
+                .collect(),
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        create_recipe(pool, &recipe)
+            .await
+            .expect("Failed to create recipe")
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_search_full_text_matches_across_fields(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let lemon = create_ingredient(&pool, "lemon").await.unwrap();
+        let chicken = create_ingredient(&pool, "chicken").await.unwrap();
+        let pasta = create_ingredient(&pool, "pasta").await.unwrap();
+
+        // "lemon" as an ingredient, "garlic" only in the instructions
+        let roast_id = add_recipe(
+            &pool,
+            "Roast Chicken",
+            Some("Rub with garlic and roast"),
+            &[(chicken, "chicken", None), (lemon, "lemon", Some("zested"))],
+        )
+        .await;
+        // "garlic" only in ingredient notes, no lemon
+        add_recipe(
+            &pool,
+            "Pasta",
+            None,
+            &[(pasta, "pasta", Some("tossed with garlic oil"))],
+        )
+        .await;
+
+        let results = search_full_text(&pool, "lemon garlic")
+            .await
+            .expect("Failed to search");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].recipe_id, roast_id);
+
+        let results = search_full_text(&pool, "garlic")
+            .await
+            .expect("Failed to search");
+        assert_eq!(results.len(), 2);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_search_by_ingredients(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let chicken = create_ingredient(&pool, "chicken").await.unwrap();
+        let rice = create_ingredient(&pool, "rice").await.unwrap();
+        let quinoa = create_ingredient(&pool, "quinoa").await.unwrap();
+        let cilantro = create_ingredient(&pool, "cilantro").await.unwrap();
+
+        let burrito = add_recipe(
+            &pool,
+            "Burrito Bowl",
+            None,
+            &[
+                (chicken, "chicken", None),
+                (rice, "rice", None),
+                (cilantro, "cilantro", None),
+            ],
+        )
+        .await;
+        let fried_rice = add_recipe(
+            &pool,
+            "Chicken Fried Rice",
+            None,
+            &[(chicken, "chicken", None), (rice, "rice", None)],
+        )
+        .await;
+        let salad = add_recipe(
+            &pool,
+            "Quinoa Salad",
+            None,
+            &[(chicken, "chicken", None), (quinoa, "quinoa", None)],
+        )
+        .await;
+        let plain_rice = add_recipe(&pool, "Plain Rice", None, &[(rice, "rice", None)]).await;
+
+        let ids = |recipes: Vec<RecipeRecord>| recipes.iter().map(|r| r.id).collect::<Vec<_>>();
+
+        let query = IngredientQuery::new()
+            .with("Chicken")
+            .with("rice")
+            .without("cilantro");
+        let results = search_by_ingredients(&pool, &query).await.unwrap();
+        assert_eq!(ids(results), vec![fried_rice]);
+
+        let query = IngredientQuery::new()
+            .with("chicken")
+            .with_any(["rice", "quinoa"]);
+        let results = search_by_ingredients(&pool, &query).await.unwrap();
+        assert_eq!(ids(results), vec![burrito, fried_rice, salad]);
+
+        let query = IngredientQuery::new().without("chicken");
+        let results = search_by_ingredients(&pool, &query).await.unwrap();
+        assert_eq!(ids(results), vec![plain_rice]);
+
+        let results = search_by_ingredients(&pool, &IngredientQuery::new())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 4);
+
+        let results = get_recipes_containing(&pool, &[quinoa, cilantro])
+            .await
+            .unwrap();
+        assert_eq!(ids(results), vec![burrito, salad]);
+        let results = get_recipes_containing_all(&pool, &[rice, chicken, rice])
+            .await
+            .unwrap();
+        assert_eq!(ids(results), vec![burrito, fried_rice]);
+        let results = get_recipes_containing(&pool, &[rice]).await.unwrap();
+        assert_eq!(ids(results), vec![burrito, fried_rice, plain_rice]);
+        assert!(get_recipes_containing(&pool, &[]).await.unwrap().is_empty());
+        assert!(
+            get_recipes_containing_all(&pool, &[])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        set_recipe_visibility(&pool, fried_rice, Visibility::Private)
+            .await
+            .unwrap();
+        let household = RecipeFilter {
+            audience: Some(Visibility::Household),
+            ..Default::default()
+        };
+        let query = IngredientQuery::new().with("rice");
+        let results = search_by_ingredients_with(&pool, &query, &household)
+            .await
+            .unwrap();
+        assert_eq!(ids(results), vec![burrito, plain_rice]);
+        let results = get_recipes_containing_with(&pool, &[rice], &household)
+            .await
+            .unwrap();
+        assert_eq!(ids(results), vec![burrito, plain_rice]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_search_full_text_ranks_name_matches_first(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        add_recipe(
+            &pool,
+            "Weeknight Stew",
+            Some("Serve with lemon wedges"),
+            &[],
+        )
+        .await;
+        let tart_id = add_recipe(&pool, "Lemon Tart", Some("Bake"), &[]).await;
+
+        let results = search_full_text(&pool, "lemon")
+            .await
+            .expect("Failed to search");
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].recipe_id, tart_id);
+        assert!(results[0].rank <= results[1].rank);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_search_full_text_tracks_ingredient_renames(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let id = create_ingredient(&pool, "scallion").await.unwrap();
+        add_recipe(&pool, "Fried Rice", None, &[(id, "scallion", None)]).await;
+
+        sqlx::query("UPDATE ingredients SET name = 'green onion' WHERE id = ?")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .expect("Failed to rename ingredient");
+
+        assert!(
+            search_full_text(&pool, "scallion")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(search_full_text(&pool, "onion").await.unwrap().len(), 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_search_full_text_handles_syntax_characters(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        add_recipe(&pool, "Mom's \"Famous\" Chili", None, &[]).await;
+
+        assert!(search_full_text(&pool, "   ").await.unwrap().is_empty());
+        assert_eq!(
+            search_full_text(&pool, "\"famous\" chili(")
+                .await
+                .expect("Syntax characters should be quoted")
+                .len(),
+            1
+        );
+        assert_eq!(search_full_text(&pool, "chili").await.unwrap().len(), 1);
+    }
+}
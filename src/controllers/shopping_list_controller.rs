@@ -0,0 +1,733 @@
+use chrono::NaiveDate;
+use serde_json::json;
+use sqlx::{QueryBuilder, Row, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+
+use super::audit_controller::{record_change, record_update, row_snapshot};
+use super::meal_plan_controller::plan_quantity_totals;
+use super::pantry_controller::insert_pantry_item_in;
+use super::price_controller::estimate_item_costs;
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, Result};
+use crate::models::api::{
+    PantryItem, ShoppingCheckOff, ShoppingList, ShoppingListEntry, ShoppingSync,
+};
+use crate::models::{AuditAction, ShoppingListRecord};
+use crate::quantity::{parse_quantity, quantity_parts};
+use crate::validation::Validator;
+
+/// Build the shopping list for a saved plan, marking what's already at hand
+/// Items whose ingredient is in `already_have` are kept but flagged, so the
+/// list still shows the full picture; use `ShoppingList::to_buy` to omit them.
+/// Items are priced at the latest recorded prices. The list isn't saved; pass it to `save_shopping_list` to keep it
+pub async fn plan_shopping_list(
+    pool: &SqlitePool,
+    plan_id: i64,
+    already_have: &[i64],
+) -> Result<ShoppingList> {
+    let details: HashMap<i64, (Option<String>, Option<String>)> = sqlx::query(
+        "SELECT id, aisle, icon FROM ingredients WHERE aisle IS NOT NULL OR icon IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| (row.get("id"), (row.get("aisle"), row.get("icon"))))
+    .collect();
+
+    let mut items: Vec<ShoppingListEntry> = plan_quantity_totals(pool, plan_id)
+        .await?
+        .into_iter()
+        .map(
+            |(ingredient_name, (ingredient_id, total))| ShoppingListEntry {
+                ingredient_id,
+                ingredient_name,
+                quantity: total.to_string(),
+                already_have: already_have.contains(&ingredient_id),
+                purchased: false,
+                estimated_cost: None,
+                aisle: details
+                    .get(&ingredient_id)
+                    .and_then(|(aisle, _)| aisle.clone()),
+                icon: details
+                    .get(&ingredient_id)
+                    .and_then(|(_, icon)| icon.clone()),
+            },
+        )
+        .collect();
+    estimate_item_costs(pool, &mut items).await?;
+
+    Ok(ShoppingList {
+        id: 0,
+        name: None,
+        meal_plan_id: Some(plan_id),
+        purchased_on: None,
+        store: None,
+        items,
+    })
+}
+
+/// Save a shopping list with its items and "already have" marks
+/// Returns the new list ID
+pub async fn save_shopping_list(pool: &SqlitePool, list: &ShoppingList) -> Result<i64> {
+    let mut validator = Validator::new();
+    if let Some(name) = &list.name {
+        validator.name("name", name);
+    }
+    for (i, item) in list.items.iter().enumerate() {
+        validator.line(format!("items[{}].quantity", i), Some(&item.quantity));
+    }
+    validator.finish()?;
+
+    with_busy_retry(|| insert_shopping_list(pool, list)).await
+}
+
+async fn insert_shopping_list(pool: &SqlitePool, list: &ShoppingList) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let list_id = sqlx::query(
+        "INSERT INTO shopping_lists (name, meal_plan_id, purchased_on) VALUES (?, ?, ?)",
+    )
+    .bind(&list.name)
+    .bind(list.meal_plan_id)
+    .bind(list.purchased_on)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    if !list.items.is_empty() {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO shopping_list_items (shopping_list_id, ingredient_id, quantity, already_have) ",
+        );
+        builder.push_values(&list.items, |mut row, item| {
+            row.push_bind(list_id)
+                .push_bind(item.ingredient_id)
+                .push_bind(&item.quantity)
+                .push_bind(item.already_have);
+        });
+        builder.build().execute(&mut *tx).await?;
+    }
+    let items: Vec<_> = list
+        .items
+        .iter()
+        .map(|item| {
+            json!({
+                "ingredient_id": item.ingredient_id,
+                "quantity": item.quantity,
+                "already_have": item.already_have,
+            })
+        })
+        .collect();
+    record_change(
+        &mut tx,
+        "shopping_list",
+        list_id,
+        AuditAction::Create,
+        None,
+        Some(&json!({
+            "name": list.name,
+            "meal_plan_id": list.meal_plan_id,
+            "purchased_on": list.purchased_on,
+            "items": items,
+        })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(list_id)
+}
+
+/// Fetch a saved shopping list with items ordered by ingredient name, priced
+/// at the latest recorded prices
+pub async fn get_shopping_list(pool: &SqlitePool, list_id: i64) -> Result<ShoppingList> {
+    let list = sqlx::query_as::<_, ShoppingListRecord>(
+        "SELECT id, name, meal_plan_id, purchased_on, stocked, created_at FROM shopping_lists WHERE id = ?",
+    )
+    .bind(list_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(FeedMeError::ShoppingListNotFound(list_id))?;
+
+    let mut items: Vec<ShoppingListEntry> = sqlx::query(
+        r#"
+        SELECT
+            i.id as ingredient_id,
+            i.name as ingredient_name,
+            sli.quantity,
+            sli.already_have,
+            sli.purchased,
+            i.aisle,
+            i.icon
+        FROM shopping_list_items sli
+        JOIN ingredients i ON sli.ingredient_id = i.id
+        WHERE sli.shopping_list_id = ?
+        ORDER BY i.name
+        "#,
+    )
+    .bind(list_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| ShoppingListEntry {
+        ingredient_id: row.get("ingredient_id"),
+        ingredient_name: row.get("ingredient_name"),
+        quantity: row.get("quantity"),
+        already_have: row.get("already_have"),
+        purchased: row.get("purchased"),
+        estimated_cost: None,
+        aisle: row.get("aisle"),
+        icon: row.get("icon"),
+    })
+    .collect();
+    estimate_item_costs(pool, &mut items).await?;
+
+    Ok(ShoppingList {
+        id: list.id,
+        name: list.name,
+        meal_plan_id: list.meal_plan_id,
+        purchased_on: list.purchased_on,
+        store: None,
+        items,
+    })
+}
+
+/// Mark or unmark an item on a saved list as already at hand
+/// Returns whether the list contained the ingredient
+pub async fn set_already_have(
+    pool: &SqlitePool,
+    list_id: i64,
+    ingredient_id: i64,
+    already_have: bool,
+) -> Result<bool> {
+    with_busy_retry(|| update_already_have(pool, list_id, ingredient_id, already_have)).await
+}
+
+async fn update_already_have(
+    pool: &SqlitePool,
+    list_id: i64,
+    ingredient_id: i64,
+    already_have: bool,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let old: Option<bool> = sqlx::query_scalar(
+        "SELECT already_have FROM shopping_list_items WHERE shopping_list_id = ? AND ingredient_id = ?",
+    )
+    .bind(list_id)
+    .bind(ingredient_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(old) = old else {
+        return Ok(false);
+    };
+
+    sqlx::query(
+        "UPDATE shopping_list_items SET already_have = ? WHERE shopping_list_id = ? AND ingredient_id = ?",
+    )
+    .bind(already_have)
+    .bind(list_id)
+    .bind(ingredient_id)
+    .execute(&mut *tx)
+    .await?;
+    record_change(
+        &mut tx,
+        "shopping_list",
+        list_id,
+        AuditAction::Update,
+        Some(&json!({ "ingredient_id": ingredient_id, "already_have": old })),
+        Some(&json!({ "ingredient_id": ingredient_id, "already_have": already_have })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// Record that the shopping for a saved list was done on `purchased_on`
+pub async fn mark_purchased(
+    pool: &SqlitePool,
+    list_id: i64,
+    purchased_on: NaiveDate,
+) -> Result<()> {
+    with_busy_retry(|| update_purchased_on(pool, list_id, purchased_on)).await
+}
+
+async fn update_purchased_on(
+    pool: &SqlitePool,
+    list_id: i64,
+    purchased_on: NaiveDate,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = row_snapshot(&mut tx, "shopping_lists", list_id, &["purchased_on"]).await?;
+
+    let result = sqlx::query("UPDATE shopping_lists SET purchased_on = ? WHERE id = ?")
+        .bind(purchased_on)
+        .bind(list_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::ShoppingListNotFound(list_id));
+    }
+    record_update(
+        &mut tx,
+        "shopping_list",
+        "shopping_lists",
+        list_id,
+        &["purchased_on"],
+        old,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Add everything bought on a purchased list to the pantry
+/// Items marked "already have", or already checked off and stocked by
+/// `sync_shopping_list`, are skipped, and combined quantities are split
+/// back into their parts ("250 g + 1 cup"); parts that can't be parsed
+/// ("a pinch") are left out. A list is only stocked once: later calls add
+/// nothing. Returns the number of pantry items added
+pub async fn stock_from_shopping_list(pool: &SqlitePool, list_id: i64) -> Result<usize> {
+    let list = get_shopping_list(pool, list_id).await?;
+    if list.purchased_on.is_none() {
+        return Err(FeedMeError::ShoppingListNotPurchased(list_id));
+    }
+
+    with_busy_retry(|| stock_shopping_list(pool, &list)).await
+}
+
+async fn stock_shopping_list(pool: &SqlitePool, list: &ShoppingList) -> Result<usize> {
+    let list_id = list.id;
+    let mut tx = pool.begin().await?;
+
+    // Claim the list first so concurrent calls can't stock it twice
+    let claimed = sqlx::query("UPDATE shopping_lists SET stocked = 1 WHERE id = ? AND stocked = 0")
+        .bind(list_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if claimed == 0 {
+        return Ok(0);
+    }
+    record_change(
+        &mut tx,
+        "shopping_list",
+        list_id,
+        AuditAction::Update,
+        Some(&json!({ "stocked": false })),
+        Some(&json!({ "stocked": true })),
+    )
+    .await?;
+
+    let mut added = 0;
+    for item in list.to_buy() {
+        added += stock_item_in(&mut tx, item.ingredient_id, &item.quantity).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(added)
+}
+
+/// Apply check-offs posted back from a phone: mark the named items bought,
+/// add them to the pantry and, when given, record the day of the trip, all
+/// at once
+/// Posting the same items again changes nothing, so a client can resend its
+/// whole state. Items aren't stocked again if `stock_from_shopping_list`
+/// already stocked the list
+pub async fn sync_shopping_list(
+    pool: &SqlitePool,
+    check_off: &ShoppingCheckOff,
+) -> Result<ShoppingSync> {
+    let mut validator = Validator::new();
+    let bought: Vec<&str> = check_off
+        .bought
+        .iter()
+        .enumerate()
+        .map(|(i, name)| validator.name(format!("bought[{}]", i), name))
+        .collect();
+    validator.finish()?;
+
+    with_busy_retry(|| apply_shopping_list_sync(pool, check_off, &bought)).await
+}
+
+async fn apply_shopping_list_sync(
+    pool: &SqlitePool,
+    check_off: &ShoppingCheckOff,
+    bought: &[&str],
+) -> Result<ShoppingSync> {
+    let mut tx = pool.begin().await?;
+
+    let stocked: bool = sqlx::query_scalar("SELECT stocked FROM shopping_lists WHERE id = ?")
+        .bind(check_off.list)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(FeedMeError::ShoppingListNotFound(check_off.list))?;
+
+    let mut sync = ShoppingSync::default();
+    for name in bought {
+        let item = sqlx::query(
+            r#"
+            SELECT sli.id, sli.ingredient_id, sli.quantity, sli.purchased, i.name
+            FROM shopping_list_items sli
+            JOIN ingredients i ON sli.ingredient_id = i.id
+            WHERE sli.shopping_list_id = ? AND LOWER(i.name) = LOWER(?)
+            "#,
+        )
+        .bind(check_off.list)
+        .bind(name)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(item) = item else {
+            sync.not_on_list.push(name.to_string());
+            continue;
+        };
+        if item.get::<bool, _>("purchased") {
+            continue;
+        }
+
+        sqlx::query("UPDATE shopping_list_items SET purchased = 1 WHERE id = ?")
+            .bind(item.get::<i64, _>("id"))
+            .execute(&mut *tx)
+            .await?;
+        let ingredient_id: i64 = item.get("ingredient_id");
+        record_change(
+            &mut tx,
+            "shopping_list",
+            check_off.list,
+            AuditAction::Update,
+            Some(&json!({ "ingredient_id": ingredient_id, "purchased": false })),
+            Some(&json!({ "ingredient_id": ingredient_id, "purchased": true })),
+        )
+        .await?;
+        if !stocked {
+            sync.stocked +=
+                stock_item_in(&mut tx, item.get("ingredient_id"), item.get("quantity")).await?;
+        }
+        sync.checked_off.push(item.get("name"));
+    }
+
+    if let Some(purchased_on) = check_off.purchased_on {
+        let old =
+            row_snapshot(&mut tx, "shopping_lists", check_off.list, &["purchased_on"]).await?;
+        sqlx::query("UPDATE shopping_lists SET purchased_on = ? WHERE id = ?")
+            .bind(purchased_on)
+            .bind(check_off.list)
+            .execute(&mut *tx)
+            .await?;
+        record_update(
+            &mut tx,
+            "shopping_list",
+            "shopping_lists",
+            check_off.list,
+            &["purchased_on"],
+            old,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(sync)
+}
+
+/// Add a list item's quantity to the pantry inside the caller's transaction
+/// Combined quantities are split back into their parts ("250 g + 1 cup");
+/// parts that can't be parsed ("a pinch") are left out. Returns the number
+/// of pantry items added
+async fn stock_item_in(
+    conn: &mut SqliteConnection,
+    ingredient_id: i64,
+    quantity: &str,
+) -> Result<usize> {
+    let mut added = 0;
+    for quantity in quantity_parts(quantity).filter_map(parse_quantity) {
+        let item = PantryItem {
+            id: 0,
+            ingredient_id,
+            ingredient_name: String::new(),
+            amount: quantity.amount,
+            unit: quantity.unit,
+            expires_on: None,
+            is_staple: false,
+            min_amount: None,
+            icon: None,
+        };
+        insert_pantry_item_in(conn, &item).await?;
+        added += 1;
+    }
+
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::list_pantry;
+    use crate::controllers::{save_meal_plan, set_ingredient_aisle, set_ingredient_icon};
+    use crate::models::api::{MealPlan, PlannedMeal, StoreLayout};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    /// A plan with Pancakes and Tomato Pasta from the demo data
+    async fn demo_plan(pool: &SqlitePool) -> i64 {
+        let ids = crate::seed::seed_demo_data(pool).await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let plan = MealPlan {
+            id: 0,
+            name: None,
+            start_date: date,
+            entries: [ids[0], ids[3]]
+                .into_iter()
+                .map(|recipe_id| PlannedMeal {
+                    date,
+                    meal: "dinner".to_string(),
+                    recipe_id,
+                    recipe_name: String::new(),
+                    servings: 1.0,
+                    note: None,
+                })
+                .collect(),
+        };
+        save_meal_plan(pool, &plan).await.unwrap()
+    }
+
+    async fn ingredient_id(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query_scalar("SELECT id FROM ingredients WHERE name = ?")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_plan_shopping_list_marks_already_have(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let plan_id = demo_plan(&pool).await;
+        let salt = ingredient_id(&pool, "salt").await;
+        let flour = ingredient_id(&pool, "flour").await;
+
+        let list = plan_shopping_list(&pool, plan_id, &[salt, flour])
+            .await
+            .expect("Failed to build list");
+
+        let marked: Vec<&str> = list
+            .items
+            .iter()
+            .filter(|item| item.already_have)
+            .map(|item| item.ingredient_name.as_str())
+            .collect();
+        assert_eq!(marked, vec!["flour", "salt"]);
+        assert_eq!(list.to_buy().count(), list.items.len() - 2);
+        assert!(list.to_buy().all(|item| item.ingredient_id != salt));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_saved_list_keeps_marks(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let plan_id = demo_plan(&pool).await;
+        let salt = ingredient_id(&pool, "salt").await;
+        let basil = ingredient_id(&pool, "basil").await;
+
+        let mut list = plan_shopping_list(&pool, plan_id, &[salt]).await.unwrap();
+        list.name = Some("Week 43".to_string());
+        let list_id = save_shopping_list(&pool, &list)
+            .await
+            .expect("Failed to save");
+
+        assert!(set_already_have(&pool, list_id, basil, true).await.unwrap());
+        assert!(!set_already_have(&pool, list_id, 9999, true).await.unwrap());
+
+        let saved = get_shopping_list(&pool, list_id)
+            .await
+            .expect("Failed to get");
+        assert_eq!(saved.name.as_deref(), Some("Week 43"));
+        assert_eq!(saved.meal_plan_id, Some(plan_id));
+        assert_eq!(saved.items.len(), list.items.len());
+        let marked: Vec<i64> = saved
+            .items
+            .iter()
+            .filter(|item| item.already_have)
+            .map(|item| item.ingredient_id)
+            .collect();
+        assert_eq!(marked.len(), 2);
+        assert!(marked.contains(&salt) && marked.contains(&basil));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_arrange_by_store_layout(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let plan_id = demo_plan(&pool).await;
+        for (name, aisle) in [
+            ("milk", "Dairy"),
+            ("butter", "dairy"),
+            ("garlic", "produce"),
+            ("basil", "produce"),
+            ("flour", "baking"),
+        ] {
+            let id = ingredient_id(&pool, name).await;
+            set_ingredient_aisle(&pool, id, Some(aisle)).await.unwrap();
+        }
+        let garlic = ingredient_id(&pool, "garlic").await;
+        set_ingredient_icon(&pool, garlic, Some("🧄"))
+            .await
+            .unwrap();
+
+        let mut list = plan_shopping_list(&pool, plan_id, &[]).await.unwrap();
+        assert_eq!(list.items[0].ingredient_name, "baking powder");
+        list.arrange(&StoreLayout::new(
+            "corner",
+            &["Produce".to_string(), "Dairy".to_string()],
+        ));
+
+        let order: Vec<(Option<&str>, &str)> = list
+            .items
+            .iter()
+            .take(5)
+            .map(|item| (item.aisle.as_deref(), item.ingredient_name.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (Some("produce"), "basil"),
+                (Some("produce"), "garlic"),
+                (Some("dairy"), "butter"),
+                (Some("dairy"), "milk"),
+                (Some("baking"), "flour"),
+            ]
+        );
+        assert!(list.items[5..].iter().all(|item| item.aisle.is_none()));
+
+        let text = list.to_string();
+        assert!(text.starts_with("produce\n  basil: "));
+        assert!(text.contains("\n  🧄 garlic: "));
+        assert!(text.contains("\nbaking\n  flour: "));
+        assert!(text.contains("\nother\n"));
+
+        assert!(matches!(
+            set_ingredient_aisle(&pool, 9999, None).await,
+            Err(FeedMeError::IngredientNotFound(9999))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_stock_from_shopping_list(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let plan_id = demo_plan(&pool).await;
+        let salt = ingredient_id(&pool, "salt").await;
+        let pasta = ingredient_id(&pool, "pasta").await;
+
+        let list = plan_shopping_list(&pool, plan_id, &[salt]).await.unwrap();
+        let list_id = save_shopping_list(&pool, &list).await.unwrap();
+
+        assert!(matches!(
+            stock_from_shopping_list(&pool, list_id).await,
+            Err(FeedMeError::ShoppingListNotPurchased(id)) if id == list_id
+        ));
+
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        mark_purchased(&pool, list_id, today).await.unwrap();
+        assert_eq!(
+            get_shopping_list(&pool, list_id)
+                .await
+                .unwrap()
+                .purchased_on,
+            Some(today)
+        );
+
+        let added = stock_from_shopping_list(&pool, list_id)
+            .await
+            .expect("Failed to stock");
+        let pantry = list_pantry(&pool).await.unwrap();
+        assert_eq!(added, pantry.len());
+        assert!(added > 0);
+        assert!(pantry.iter().all(|item| item.ingredient_id != salt));
+        let bought_pasta = pantry
+            .iter()
+            .find(|item| item.ingredient_id == pasta)
+            .expect("pasta was bought");
+        assert_eq!(bought_pasta.amount, 500.0);
+        assert_eq!(bought_pasta.unit.as_deref(), Some("g"));
+
+        // Stocking again is a no-op
+        assert_eq!(stock_from_shopping_list(&pool, list_id).await.unwrap(), 0);
+        assert_eq!(list_pantry(&pool).await.unwrap().len(), added);
+
+        assert!(matches!(
+            mark_purchased(&pool, 99, today).await,
+            Err(FeedMeError::ShoppingListNotFound(99))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_sync_shopping_list(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let plan_id = demo_plan(&pool).await;
+        let pasta = ingredient_id(&pool, "pasta").await;
+
+        let list = plan_shopping_list(&pool, plan_id, &[]).await.unwrap();
+        let list_id = save_shopping_list(&pool, &list).await.unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let check_off: ShoppingCheckOff = serde_json::from_str(&format!(
+            r#"{{"list": {}, "bought": ["Pasta", "basil", "caviar"], "purchased_on": "2026-10-17"}}"#,
+            list_id
+        ))
+        .unwrap();
+
+        let sync = sync_shopping_list(&pool, &check_off)
+            .await
+            .expect("Failed to sync");
+        assert_eq!(sync.checked_off, vec!["pasta", "basil"]);
+        assert_eq!(sync.not_on_list, vec!["caviar"]);
+        let pantry = list_pantry(&pool).await.unwrap();
+        assert_eq!(sync.stocked, pantry.len());
+        assert!(pantry.iter().any(|item| item.ingredient_id == pasta));
+
+        let saved = get_shopping_list(&pool, list_id).await.unwrap();
+        assert_eq!(saved.purchased_on, Some(today));
+        assert_eq!(saved.to_buy().count(), saved.items.len() - 2);
+
+        // Resending is a no-op, and stocking the list only adds the rest
+        let sync = sync_shopping_list(&pool, &check_off).await.unwrap();
+        assert!(sync.checked_off.is_empty());
+        assert_eq!(sync.stocked, 0);
+        stock_from_shopping_list(&pool, list_id).await.unwrap();
+        let stocked_pasta = list_pantry(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .filter(|item| item.ingredient_id == pasta)
+            .count();
+        assert_eq!(stocked_pasta, 1);
+
+        assert!(matches!(
+            sync_shopping_list(
+                &pool,
+                &ShoppingCheckOff {
+                    list: 99,
+                    bought: Vec::new(),
+                    purchased_on: None,
+                }
+            )
+            .await,
+            Err(FeedMeError::ShoppingListNotFound(99))
+        ));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_shopping_list_not_found(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        assert!(matches!(
+            get_shopping_list(&pool, 5).await,
+            Err(FeedMeError::ShoppingListNotFound(5))
+        ));
+    }
+}
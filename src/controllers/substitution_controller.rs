@@ -0,0 +1,316 @@
+use serde_json::json;
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use std::collections::HashSet;
+
+use super::audit_controller::{delete_audited, record_change};
+use super::retry::with_busy_retry;
+use crate::error::{FeedMeError, Result};
+use crate::models::AuditAction;
+use crate::models::api::Substitution;
+use crate::validation::Validator;
+
+/// Record that `ingredient_id` can be replaced by `substitute_ids` together,
+/// with an optional note on the ratio
+/// Returns the new substitution ID
+pub async fn add_substitution(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    substitute_ids: &[i64],
+    note: Option<&str>,
+) -> Result<i64> {
+    if substitute_ids.is_empty() {
+        return Err(FeedMeError::InvalidSubstitution(
+            "needs at least one substitute".to_string(),
+        ));
+    }
+    if substitute_ids.contains(&ingredient_id) {
+        return Err(FeedMeError::InvalidSubstitution(
+            "an ingredient can't substitute for itself".to_string(),
+        ));
+    }
+
+    let mut validator = Validator::new();
+    validator.line("note", note);
+    validator.finish()?;
+
+    with_busy_retry(|| insert_substitution(pool, ingredient_id, substitute_ids, note)).await
+}
+
+async fn insert_substitution(
+    pool: &SqlitePool,
+    ingredient_id: i64,
+    substitute_ids: &[i64],
+    note: Option<&str>,
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    for id in std::iter::once(&ingredient_id).chain(substitute_ids) {
+        sqlx::query_scalar::<_, i64>("SELECT id FROM ingredients WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(FeedMeError::IngredientNotFound(*id))?;
+    }
+
+    let note = note.map(str::trim).filter(|note| !note.is_empty());
+    let id = sqlx::query("INSERT INTO substitutions (ingredient_id, note) VALUES (?, ?)")
+        .bind(ingredient_id)
+        .bind(note)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+    for substitute_id in substitute_ids {
+        sqlx::query(
+            "INSERT OR IGNORE INTO substitution_ingredients (substitution_id, ingredient_id) VALUES (?, ?)",
+        )
+        .bind(id)
+        .bind(substitute_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    record_change(
+        &mut tx,
+        "substitution",
+        id,
+        AuditAction::Create,
+        None,
+        Some(&json!({
+            "ingredient_id": ingredient_id,
+            "substitute_ids": substitute_ids,
+            "note": note,
+        })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Every substitution, ordered by the name of the ingredient replaced
+pub async fn list_substitutions(pool: &SqlitePool) -> Result<Vec<Substitution>> {
+    load_substitutions(pool, None).await
+}
+
+/// Ways to replace one ingredient, oldest first
+pub async fn get_substitutions(pool: &SqlitePool, ingredient_id: i64) -> Result<Vec<Substitution>> {
+    load_substitutions(pool, Some(&[ingredient_id])).await
+}
+
+/// Delete a substitution
+/// Returns whether it existed
+pub async fn delete_substitution(pool: &SqlitePool, id: i64) -> Result<bool> {
+    with_busy_retry(|| delete_substitution_row(pool, id)).await
+}
+
+async fn delete_substitution_row(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let deleted = delete_audited(
+        &mut tx,
+        "substitution",
+        "substitutions",
+        id,
+        &["ingredient_id", "note"],
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(deleted)
+}
+
+/// Substitutions for any of `ingredient_ids` that current pantry stock can
+/// make, i.e. whose every substitute is on hand
+/// Pass the items still to buy on a shopping list to see what can be skipped
+pub async fn pantry_substitutions(
+    pool: &SqlitePool,
+    ingredient_ids: &[i64],
+) -> Result<Vec<Substitution>> {
+    if ingredient_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let substitutions = load_substitutions(pool, Some(ingredient_ids)).await?;
+    let stocked = in_stock(pool).await?;
+
+    Ok(substitutions
+        .into_iter()
+        .filter(|substitution| substitution.can_make(&stocked))
+        .collect())
+}
+
+/// Ingredients with a positive amount in the pantry
+pub(crate) async fn in_stock(pool: &SqlitePool) -> Result<HashSet<i64>> {
+    let ids: Vec<i64> =
+        sqlx::query_scalar("SELECT DISTINCT ingredient_id FROM pantry_items WHERE amount > 0")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(ids.into_iter().collect())
+}
+
+/// Substitutions for `ingredient_ids`, or for every ingredient when None
+pub(crate) async fn load_substitutions(
+    pool: &SqlitePool,
+    ingredient_ids: Option<&[i64]>,
+) -> Result<Vec<Substitution>> {
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        r#"
+        SELECT
+            s.id,
+            s.ingredient_id,
+            i.name as ingredient_name,
+            s.note,
+            sub.id as substitute_id,
+            sub.name as substitute_name
+        FROM substitutions s
+        JOIN ingredients i ON s.ingredient_id = i.id
+        JOIN substitution_ingredients si ON si.substitution_id = s.id
+        JOIN ingredients sub ON si.ingredient_id = sub.id
+        "#,
+    );
+
+    if let Some(ids) = ingredient_ids {
+        builder.push("WHERE s.ingredient_id IN (");
+        let mut separated = builder.separated(", ");
+        for id in ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(") ");
+    }
+
+    builder.push("ORDER BY i.name, s.id, sub.name");
+
+    let mut substitutions: Vec<Substitution> = Vec::new();
+    for row in builder.build().fetch_all(pool).await? {
+        let id: i64 = row.get("id");
+        if substitutions.last().is_none_or(|last| last.id != id) {
+            substitutions.push(Substitution {
+                id,
+                ingredient_id: row.get("ingredient_id"),
+                ingredient_name: row.get("ingredient_name"),
+                substitute_ids: Vec::new(),
+                substitutes: Vec::new(),
+                note: row.get("note"),
+            });
+        }
+
+        let substitution = substitutions.last_mut().expect("pushed above");
+        substitution.substitute_ids.push(row.get("substitute_id"));
+        substitution.substitutes.push(row.get("substitute_name"));
+    }
+
+    Ok(substitutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{add_to_pantry, create_ingredient};
+    use crate::models::api::PantryItem;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    async fn stock(pool: &SqlitePool, ingredient_id: i64) {
+        let item = PantryItem {
+            id: 0,
+            ingredient_id,
+            ingredient_name: String::new(),
+            amount: 1.0,
+            unit: None,
+            expires_on: None,
+            is_staple: false,
+            icon: None,
+            min_amount: None,
+        };
+        add_to_pantry(pool, &item).await.unwrap();
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_substitution_crud(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let buttermilk = create_ingredient(&pool, "buttermilk").await.unwrap();
+        let milk = create_ingredient(&pool, "milk").await.unwrap();
+        let lemon = create_ingredient(&pool, "lemon juice").await.unwrap();
+        let yogurt = create_ingredient(&pool, "yogurt").await.unwrap();
+
+        let soured = add_substitution(
+            &pool,
+            buttermilk,
+            &[milk, lemon],
+            Some("1 tbsp lemon juice per cup of milk"),
+        )
+        .await
+        .unwrap();
+        add_substitution(&pool, buttermilk, &[yogurt], Some("  "))
+            .await
+            .unwrap();
+
+        let substitutions = get_substitutions(&pool, buttermilk).await.unwrap();
+        assert_eq!(substitutions.len(), 2);
+        assert_eq!(
+            substitutions[0].to_string(),
+            "buttermilk -> lemon juice + milk (1 tbsp lemon juice per cup of milk)"
+        );
+        assert_eq!(substitutions[1].to_string(), "buttermilk -> yogurt");
+        assert_eq!(list_substitutions(&pool).await.unwrap(), substitutions);
+        assert!(get_substitutions(&pool, milk).await.unwrap().is_empty());
+
+        assert!(delete_substitution(&pool, soured).await.unwrap());
+        assert!(!delete_substitution(&pool, soured).await.unwrap());
+        assert_eq!(list_substitutions(&pool).await.unwrap().len(), 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_add_substitution_rejects_bad_input(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let buttermilk = create_ingredient(&pool, "buttermilk").await.unwrap();
+
+        assert!(matches!(
+            add_substitution(&pool, buttermilk, &[], None).await,
+            Err(FeedMeError::InvalidSubstitution(_))
+        ));
+        assert!(matches!(
+            add_substitution(&pool, buttermilk, &[buttermilk], None).await,
+            Err(FeedMeError::InvalidSubstitution(_))
+        ));
+        assert!(matches!(
+            add_substitution(&pool, buttermilk, &[999], None).await,
+            Err(FeedMeError::IngredientNotFound(999))
+        ));
+        assert!(list_substitutions(&pool).await.unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_pantry_substitutions(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let buttermilk = create_ingredient(&pool, "buttermilk").await.unwrap();
+        let milk = create_ingredient(&pool, "milk").await.unwrap();
+        let lemon = create_ingredient(&pool, "lemon juice").await.unwrap();
+        let yogurt = create_ingredient(&pool, "yogurt").await.unwrap();
+        add_substitution(&pool, buttermilk, &[milk, lemon], None)
+            .await
+            .unwrap();
+        let plain = add_substitution(&pool, buttermilk, &[yogurt], None)
+            .await
+            .unwrap();
+
+        stock(&pool, milk).await;
+        assert!(
+            pantry_substitutions(&pool, &[buttermilk])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        stock(&pool, yogurt).await;
+        let offered = pantry_substitutions(&pool, &[buttermilk, milk])
+            .await
+            .unwrap();
+        assert_eq!(offered.len(), 1);
+        assert_eq!(offered[0].id, plain);
+    }
+}
@@ -0,0 +1,508 @@
+use chrono::{Days, NaiveDate};
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::search_controller::push_recipe_filter;
+use crate::error::{FeedMeError, Result};
+use crate::models::api::{RecipeFilter, SimilarRecipe, Suggestion, SuggestionCriteria};
+
+/// Days since last use after which a recipe counts as fully "fresh"
+const RECENCY_HORIZON_DAYS: f64 = 30.0;
+
+/// Rating assumed for recipes nobody has rated (middle of 1-5)
+const NEUTRAL_RATING: f64 = 3.0;
+
+/// How much shared tags count towards similarity, relative to ingredients
+const TAG_SIMILARITY_WEIGHT: f64 = 0.5;
+
+/// A recipe the planner or suggester may pick, with what scoring needs
+pub(crate) struct Candidate {
+    pub id: i64,
+    pub name: String,
+    /// prep + cook minutes, None when neither is recorded
+    pub total_minutes: Option<i64>,
+    pub average_rating: Option<f64>,
+    /// Lowercased tag names
+    pub tags: Vec<String>,
+}
+
+impl Candidate {
+    /// Whether the recipe is known to take no longer than `limit` minutes
+    pub fn fits(&self, limit: Option<i64>) -> bool {
+        match limit {
+            Some(limit) => self.total_minutes.is_some_and(|minutes| minutes <= limit),
+            None => true,
+        }
+    }
+
+    /// Score between 0 and 3: one point each for how long ago it was last
+    /// made, how many preferred tags it has, and its rating
+    pub fn score(
+        &self,
+        date: NaiveDate,
+        last_used: Option<NaiveDate>,
+        preferred: &[String],
+    ) -> f64 {
+        let recency = match last_used {
+            Some(last) => ((date - last).num_days() as f64 / RECENCY_HORIZON_DAYS).clamp(0.0, 1.0),
+            None => 1.0,
+        };
+
+        let tags = if preferred.is_empty() {
+            0.0
+        } else {
+            let matching = preferred
+                .iter()
+                .filter(|tag| self.tags.contains(&tag.to_lowercase()))
+                .count();
+            matching as f64 / preferred.len() as f64
+        };
+
+        let rating = (self.average_rating.unwrap_or(NEUTRAL_RATING) - 1.0) / 4.0;
+
+        recency + tags + rating
+    }
+}
+
+/// Suggest recipes for a meal, best first
+/// Favours recipes not made recently, matching the preferred tags, and rated
+/// highly; `max_minutes`, `required_tags` and `skip_cooked_within_days` rule
+/// recipes out entirely
+pub async fn suggest_recipes(
+    pool: &SqlitePool,
+    criteria: &SuggestionCriteria,
+) -> Result<Vec<Suggestion>> {
+    let filter = RecipeFilter {
+        tags: criteria.required_tags.clone(),
+        audience: criteria.audience,
+        ..Default::default()
+    };
+    let candidates = load_candidates(pool, &filter).await?;
+    let last_used = last_used_before(pool, criteria.date).await?;
+    let recently_cooked = match criteria.skip_cooked_within_days {
+        Some(days) => cooked_within(pool, criteria.date, days).await?,
+        None => HashSet::new(),
+    };
+
+    let mut suggestions: Vec<Suggestion> = candidates
+        .into_iter()
+        .filter(|c| c.fits(criteria.max_minutes))
+        .filter(|c| !recently_cooked.contains(&c.id))
+        .map(|c| {
+            let last_used = last_used.get(&c.id).copied();
+            Suggestion {
+                score: c.score(criteria.date, last_used, &criteria.preferred_tags),
+                recipe_id: c.id,
+                name: c.name,
+                last_used,
+                total_minutes: c.total_minutes,
+                average_rating: c.average_rating,
+            }
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.recipe_id.cmp(&b.recipe_id))
+    });
+    suggestions.truncate(criteria.limit);
+
+    Ok(suggestions)
+}
+
+/// Up to `n` other recipes most like `recipe_id`, most similar first
+/// Similarity is the Jaccard index of the two recipes' ingredients plus half
+/// that of their tags; recipes sharing neither are left out
+pub async fn similar_recipes(
+    pool: &SqlitePool,
+    recipe_id: i64,
+    n: usize,
+) -> Result<Vec<SimilarRecipe>> {
+    let names: HashMap<i64, String> =
+        sqlx::query_as("SELECT id, name FROM recipes WHERE deleted_at IS NULL")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+    if !names.contains_key(&recipe_id) {
+        return Err(FeedMeError::RecipeNotFound(recipe_id));
+    }
+
+    let ingredients = names_by_recipe(
+        pool,
+        "SELECT ri.recipe_id, i.name FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id",
+    )
+    .await?;
+    let tags = names_by_recipe(
+        pool,
+        "SELECT rt.recipe_id, lower(t.name) as name FROM recipe_tags rt JOIN tags t ON rt.tag_id = t.id",
+    )
+    .await?;
+
+    let empty = BTreeSet::new();
+    let target_ingredients = ingredients.get(&recipe_id).unwrap_or(&empty);
+    let target_tags = tags.get(&recipe_id).unwrap_or(&empty);
+
+    let mut similar: Vec<SimilarRecipe> = names
+        .into_iter()
+        .filter(|(id, _)| *id != recipe_id)
+        .filter_map(|(id, name)| {
+            let other_ingredients = ingredients.get(&id).unwrap_or(&empty);
+            let other_tags = tags.get(&id).unwrap_or(&empty);
+
+            let shared_ingredients: Vec<String> = target_ingredients
+                .intersection(other_ingredients)
+                .cloned()
+                .collect();
+            let shared_tags: Vec<String> = target_tags.intersection(other_tags).cloned().collect();
+            if shared_ingredients.is_empty() && shared_tags.is_empty() {
+                return None;
+            }
+
+            let score = jaccard(
+                shared_ingredients.len(),
+                target_ingredients,
+                other_ingredients,
+            ) + TAG_SIMILARITY_WEIGHT
+                * jaccard(shared_tags.len(), target_tags, other_tags);
+
+            Some(SimilarRecipe {
+                recipe_id: id,
+                name,
+                score,
+                shared_ingredients,
+                shared_tags,
+            })
+        })
+        .collect();
+
+    similar.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.recipe_id.cmp(&b.recipe_id))
+    });
+    similar.truncate(n);
+
+    Ok(similar)
+}
+
+/// Size of the intersection over size of the union, 0 when both are empty
+fn jaccard(shared: usize, a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        0.0
+    } else {
+        shared as f64 / union as f64
+    }
+}
+
+/// Group the `name` column of `sql` by its `recipe_id` column
+async fn names_by_recipe(
+    pool: &SqlitePool,
+    sql: &'static str,
+) -> Result<HashMap<i64, BTreeSet<String>>> {
+    let mut names: HashMap<i64, BTreeSet<String>> = HashMap::new();
+    for (recipe_id, name) in sqlx::query_as::<_, (i64, String)>(sql)
+        .fetch_all(pool)
+        .await?
+    {
+        names.entry(recipe_id).or_default().insert(name);
+    }
+
+    Ok(names)
+}
+
+/// Recipes passing `filter`, ordered by id
+pub(crate) async fn load_candidates(
+    pool: &SqlitePool,
+    filter: &RecipeFilter,
+) -> Result<Vec<Candidate>> {
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        r#"
+        SELECT
+            r.id,
+            r.name,
+            CASE WHEN r.prep_minutes IS NULL AND r.cook_minutes IS NULL THEN NULL
+                 ELSE COALESCE(r.prep_minutes, 0) + COALESCE(r.cook_minutes, 0)
+            END as total_minutes,
+            (SELECT AVG(rating) FROM recipe_ratings rr WHERE rr.recipe_id = r.id) as average_rating
+        FROM recipes r
+        WHERE 1 = 1"#,
+    );
+    push_recipe_filter(&mut builder, filter);
+
+    builder.push(" ORDER BY r.id");
+
+    let rows = builder.build().fetch_all(pool).await?;
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in sqlx::query(
+        "SELECT rt.recipe_id, lower(t.name) as name FROM recipe_tags rt JOIN tags t ON rt.tag_id = t.id",
+    )
+    .fetch_all(pool)
+    .await?
+    {
+        tags.entry(row.get("recipe_id"))
+            .or_default()
+            .push(row.get("name"));
+    }
+
+    let candidates = rows
+        .iter()
+        .map(|row| {
+            let id: i64 = row.get("id");
+            Candidate {
+                id,
+                name: row.get("name"),
+                total_minutes: row.get("total_minutes"),
+                average_rating: row.get("average_rating"),
+                tags: tags.remove(&id).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(candidates)
+}
+
+/// Recipes in the cook log on `date` or up to `days` days before it
+async fn cooked_within(pool: &SqlitePool, date: NaiveDate, days: u64) -> Result<HashSet<i64>> {
+    let since = date
+        .checked_sub_days(Days::new(days))
+        .unwrap_or(NaiveDate::MIN);
+    let ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT DISTINCT recipe_id FROM cook_log WHERE cooked_on BETWEEN ? AND ?",
+    )
+    .bind(since)
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ids.into_iter().collect())
+}
+
+/// Most recent day before `date` each recipe was cooked or planned
+pub(crate) async fn last_used_before(
+    pool: &SqlitePool,
+    date: NaiveDate,
+) -> Result<HashMap<i64, NaiveDate>> {
+    let rows: Vec<(i64, NaiveDate)> = sqlx::query_as(
+        r#"
+        SELECT recipe_id, MAX(day) as last_day
+        FROM (
+            SELECT recipe_id, cooked_on as day FROM cook_log
+            UNION ALL
+            SELECT recipe_id, planned_on as day FROM meal_plan_entries
+        )
+        WHERE day < ?
+        GROUP BY recipe_id
+        "#,
+    )
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{
+        add_recipe_tag, create_ingredient, log_cook, rate_recipe, set_recipe_times,
+        set_recipe_visibility,
+    };
+    use crate::models::api::Visibility;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+    }
+
+    async fn insert_recipe(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+            .bind(name)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    fn names(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_suggest_recipes_prefers_not_recently_cooked(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let soup = insert_recipe(&pool, "Soup").await;
+        let stew = insert_recipe(&pool, "Stew").await;
+        insert_recipe(&pool, "Curry").await;
+
+        log_cook(&pool, soup, today() - Days::new(1), None)
+            .await
+            .unwrap();
+        log_cook(&pool, stew, today() - Days::new(20), None)
+            .await
+            .unwrap();
+
+        let suggestions = suggest_recipes(&pool, &SuggestionCriteria::new(today()))
+            .await
+            .unwrap();
+
+        assert_eq!(names(&suggestions), vec!["Curry", "Stew", "Soup"]);
+        assert_eq!(suggestions[1].last_used, Some(today() - Days::new(20)));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_suggest_recipes_skips_recently_cooked(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let soup = insert_recipe(&pool, "Soup").await;
+        let stew = insert_recipe(&pool, "Stew").await;
+        insert_recipe(&pool, "Curry").await;
+
+        log_cook(&pool, soup, today() - Days::new(2), None)
+            .await
+            .unwrap();
+        log_cook(&pool, stew, today() - Days::new(10), None)
+            .await
+            .unwrap();
+
+        let mut criteria = SuggestionCriteria::new(today());
+        criteria.skip_cooked_within_days = Some(7);
+        let suggestions = suggest_recipes(&pool, &criteria).await.unwrap();
+        assert_eq!(names(&suggestions), vec!["Curry", "Stew"]);
+
+        criteria.skip_cooked_within_days = Some(14);
+        let suggestions = suggest_recipes(&pool, &criteria).await.unwrap();
+        assert_eq!(names(&suggestions), vec!["Curry"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_suggest_recipes_rating_and_preferred_tags(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let loved = insert_recipe(&pool, "Loved").await;
+        let disliked = insert_recipe(&pool, "Disliked").await;
+        let cozy = insert_recipe(&pool, "Cozy").await;
+
+        rate_recipe(&pool, loved, 5).await.unwrap();
+        rate_recipe(&pool, disliked, 1).await.unwrap();
+        add_recipe_tag(&pool, cozy, "Comfort").await.unwrap();
+
+        let suggestions = suggest_recipes(&pool, &SuggestionCriteria::new(today()))
+            .await
+            .unwrap();
+        assert_eq!(names(&suggestions), vec!["Loved", "Cozy", "Disliked"]);
+        assert_eq!(suggestions[0].average_rating, Some(5.0));
+
+        let mut criteria = SuggestionCriteria::new(today());
+        criteria.preferred_tags = vec!["comfort".to_string()];
+        let suggestions = suggest_recipes(&pool, &criteria).await.unwrap();
+        assert_eq!(names(&suggestions), vec!["Cozy", "Loved", "Disliked"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_suggest_recipes_filters(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let quick = insert_recipe(&pool, "Quick").await;
+        let slow = insert_recipe(&pool, "Slow").await;
+        insert_recipe(&pool, "Unknown time").await;
+
+        set_recipe_times(&pool, quick, Some(5), Some(10))
+            .await
+            .unwrap();
+        set_recipe_times(&pool, slow, Some(30), Some(120))
+            .await
+            .unwrap();
+        add_recipe_tag(&pool, slow, "weekend").await.unwrap();
+
+        let mut criteria = SuggestionCriteria::new(today());
+        criteria.max_minutes = Some(30);
+        let suggestions = suggest_recipes(&pool, &criteria).await.unwrap();
+        assert_eq!(names(&suggestions), vec!["Quick"]);
+        assert_eq!(suggestions[0].total_minutes, Some(15));
+
+        let mut criteria = SuggestionCriteria::new(today());
+        criteria.required_tags = vec!["Weekend".to_string()];
+        let suggestions = suggest_recipes(&pool, &criteria).await.unwrap();
+        assert_eq!(names(&suggestions), vec!["Slow"]);
+
+        let mut criteria = SuggestionCriteria::new(today());
+        criteria.limit = 2;
+        assert_eq!(suggest_recipes(&pool, &criteria).await.unwrap().len(), 2);
+
+        set_recipe_visibility(&pool, slow, Visibility::Private)
+            .await
+            .unwrap();
+        let mut criteria = SuggestionCriteria::new(today());
+        criteria.audience = Some(Visibility::Household);
+        let suggestions = suggest_recipes(&pool, &criteria).await.unwrap();
+        assert_eq!(names(&suggestions), vec!["Quick", "Unknown time"]);
+    }
+
+    async fn add_ingredients(pool: &SqlitePool, recipe_id: i64, names: &[&str]) {
+        for name in names {
+            let ingredient_id =
+                match sqlx::query_scalar("SELECT id FROM ingredients WHERE name = ?")
+                    .bind(name)
+                    .fetch_optional(pool)
+                    .await
+                    .unwrap()
+                {
+                    Some(id) => id,
+                    None => create_ingredient(pool, name).await.unwrap(),
+                };
+            sqlx::query("INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit) VALUES (?, ?, '1')")
+                .bind(recipe_id)
+                .bind(ingredient_id)
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_similar_recipes(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let carbonara = insert_recipe(&pool, "Carbonara").await;
+        let cacio = insert_recipe(&pool, "Cacio e Pepe").await;
+        let omelette = insert_recipe(&pool, "Omelette").await;
+        let salad = insert_recipe(&pool, "Salad").await;
+        let lasagna = insert_recipe(&pool, "Lasagna").await;
+
+        add_ingredients(&pool, carbonara, &["pasta", "egg", "pecorino", "pepper"]).await;
+        add_ingredients(&pool, cacio, &["pasta", "pecorino", "pepper"]).await;
+        add_ingredients(&pool, omelette, &["egg", "butter"]).await;
+        add_ingredients(&pool, salad, &["lettuce"]).await;
+        add_ingredients(&pool, lasagna, &["sheets", "beef"]).await;
+        add_recipe_tag(&pool, carbonara, "Italian").await.unwrap();
+        add_recipe_tag(&pool, lasagna, "italian").await.unwrap();
+
+        let similar = similar_recipes(&pool, carbonara, 5).await.unwrap();
+
+        assert_eq!(
+            similar.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            vec!["Cacio e Pepe", "Lasagna", "Omelette"]
+        );
+        assert_eq!(similar[0].score, 0.75);
+        assert_eq!(
+            similar[0].shared_ingredients,
+            vec!["pasta", "pecorino", "pepper"]
+        );
+        assert_eq!(similar[1].score, 0.5);
+        assert_eq!(similar[1].shared_tags, vec!["italian"]);
+        assert_eq!(similar[2].score, 0.2);
+
+        assert_eq!(similar_recipes(&pool, carbonara, 1).await.unwrap().len(), 1);
+        assert!(matches!(
+            similar_recipes(&pool, 999, 5).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+    }
+}
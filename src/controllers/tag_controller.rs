@@ -0,0 +1,541 @@
+use serde_json::json;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+
+use super::audit_controller::record_change;
+use super::retry::with_busy_retry;
+use super::search_controller::push_recipe_filter;
+use crate::error::{FeedMeError, OnDuplicate, Result};
+use crate::models::api::RecipeFilter;
+use crate::models::{AuditAction, RecipeRecord};
+use crate::validation::Validator;
+
+/// Attach a tag to a recipe, creating the tag if needed
+/// Tag names are trimmed and matched case-insensitively; tagging twice is a no-op
+pub async fn add_recipe_tag(pool: &SqlitePool, recipe_id: i64, tag: &str) -> Result<()> {
+    let mut validator = Validator::new();
+    let tag = validator.name("tag", tag);
+    validator.finish()?;
+
+    with_busy_retry(|| insert_recipe_tag(pool, recipe_id, tag)).await
+}
+
+async fn insert_recipe_tag(pool: &SqlitePool, recipe_id: i64, tag: &str) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let old = recipe_tags_in(&mut tx, recipe_id).await?;
+    if tag_recipe_in(&mut tx, recipe_id, tag).await? {
+        audit_tags_in(&mut tx, recipe_id, old).await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Attach an already validated tag inside the caller's transaction
+/// Returns whether the recipe didn't have it yet
+pub(crate) async fn tag_recipe_in(
+    conn: &mut SqliteConnection,
+    recipe_id: i64,
+    tag: &str,
+) -> Result<bool> {
+    sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+        .bind(tag)
+        .execute(&mut *conn)
+        .await?;
+
+    let tagged = sqlx::query(
+        "INSERT OR IGNORE INTO recipe_tags (recipe_id, tag_id) SELECT ?, id FROM tags WHERE name = ?",
+    )
+    .bind(recipe_id)
+    .bind(tag)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    Ok(tagged > 0)
+}
+
+/// Detach a tag from a recipe
+/// Returns whether the recipe had the tag
+pub async fn remove_recipe_tag(pool: &SqlitePool, recipe_id: i64, tag: &str) -> Result<bool> {
+    with_busy_retry(|| delete_recipe_tag(pool, recipe_id, tag)).await
+}
+
+async fn delete_recipe_tag(pool: &SqlitePool, recipe_id: i64, tag: &str) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let old = recipe_tags_in(&mut tx, recipe_id).await?;
+
+    let removed = sqlx::query(
+        "DELETE FROM recipe_tags WHERE recipe_id = ? AND tag_id = (SELECT id FROM tags WHERE name = ?)",
+    )
+    .bind(recipe_id)
+    .bind(tag)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+    if removed {
+        audit_tags_in(&mut tx, recipe_id, old).await?;
+    }
+    tx.commit().await?;
+
+    Ok(removed)
+}
+
+/// Attach a tag to many recipes at once, creating the tag if needed
+/// Fails with RecipeNotFound, tagging nothing, if any recipe doesn't exist.
+/// Returns how many recipes gained the tag
+pub async fn tag_recipes(pool: &SqlitePool, tag: &str, recipe_ids: &[i64]) -> Result<u64> {
+    let mut validator = Validator::new();
+    let tag = validator.name("tag", tag);
+    validator.finish()?;
+
+    with_busy_retry(|| insert_recipe_tags(pool, tag, recipe_ids)).await
+}
+
+async fn insert_recipe_tags(pool: &SqlitePool, tag: &str, recipe_ids: &[i64]) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    if let Some(&missing) = missing_recipes(&mut tx, recipe_ids).await?.first() {
+        return Err(FeedMeError::RecipeNotFound(missing));
+    }
+
+    sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+        .bind(tag)
+        .execute(&mut *tx)
+        .await?;
+    let mut before = Vec::new();
+    for &recipe_id in recipe_ids {
+        before.push((recipe_id, recipe_tags_in(&mut tx, recipe_id).await?));
+    }
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "INSERT OR IGNORE INTO recipe_tags (recipe_id, tag_id) SELECT r.id, t.id FROM recipes r JOIN tags t ON t.name = ",
+    );
+    builder.push_bind(tag);
+    push_ids(&mut builder, " WHERE r.id IN ", recipe_ids);
+    let tagged = builder.build().execute(&mut *tx).await?.rows_affected();
+    for (recipe_id, old) in before {
+        audit_tags_in(&mut tx, recipe_id, old).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(tagged)
+}
+
+/// Detach a tag from many recipes at once
+/// Returns how many of them had it
+pub async fn untag_recipes(pool: &SqlitePool, tag: &str, recipe_ids: &[i64]) -> Result<u64> {
+    with_busy_retry(|| delete_recipe_tags(pool, tag, recipe_ids)).await
+}
+
+async fn delete_recipe_tags(pool: &SqlitePool, tag: &str, recipe_ids: &[i64]) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let mut before = Vec::new();
+    for &recipe_id in recipe_ids {
+        before.push((recipe_id, recipe_tags_in(&mut tx, recipe_id).await?));
+    }
+
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "DELETE FROM recipe_tags WHERE tag_id = (SELECT id FROM tags WHERE name = ",
+    );
+    builder.push_bind(tag.trim());
+    builder.push(")");
+    push_ids(&mut builder, " AND recipe_id IN ", recipe_ids);
+    let untagged = builder.build().execute(&mut *tx).await?.rows_affected();
+    for (recipe_id, old) in before {
+        audit_tags_in(&mut tx, recipe_id, old).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(untagged)
+}
+
+/// Rename a tag on every recipe that has it
+/// Changing only the case is allowed; renaming onto another existing tag is
+/// a DuplicateTag, use `merge_tags` for that
+pub async fn rename_tag(pool: &SqlitePool, tag: &str, new_name: &str) -> Result<()> {
+    let mut validator = Validator::new();
+    let new_name = validator.name("new_name", new_name);
+    validator.finish()?;
+
+    with_busy_retry(|| update_tag_name(pool, tag, new_name)).await
+}
+
+async fn update_tag_name(pool: &SqlitePool, tag: &str, new_name: &str) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let (tag_id, old_name): (i64, String) =
+        sqlx::query_as("SELECT id, name FROM tags WHERE name = ?")
+            .bind(tag.trim())
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| FeedMeError::TagNotFound(tag.trim().to_string()))?;
+    sqlx::query("UPDATE tags SET name = ? WHERE id = ?")
+        .bind(new_name)
+        .bind(tag_id)
+        .execute(&mut *tx)
+        .await
+        .on_duplicate(|| FeedMeError::DuplicateTag(new_name.to_string()))?;
+    record_change(
+        &mut tx,
+        "tag",
+        tag_id,
+        AuditAction::Update,
+        Some(&json!({ "name": old_name })),
+        Some(&json!({ "name": new_name })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Fold `tags` into `into`: their recipes get `into` (created if needed) and
+/// the merged tags are deleted
+/// Fails with TagNotFound, changing nothing, if any of `tags` doesn't exist.
+/// Returns how many recipes gained `into`
+pub async fn merge_tags(pool: &SqlitePool, tags: &[&str], into: &str) -> Result<u64> {
+    let mut validator = Validator::new();
+    let into = validator.name("into", into);
+    validator.finish()?;
+
+    with_busy_retry(|| merge_tags_into(pool, tags, into)).await
+}
+
+async fn merge_tags_into(pool: &SqlitePool, tags: &[&str], into: &str) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+        .bind(into)
+        .execute(&mut *tx)
+        .await?;
+    let into_id: i64 = sqlx::query_scalar("SELECT id FROM tags WHERE name = ?")
+        .bind(into)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let mut merged = 0;
+    for tag in tags {
+        let tag = tag.trim();
+        let tag_id: i64 = sqlx::query_scalar("SELECT id FROM tags WHERE name = ?")
+            .bind(tag)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| FeedMeError::TagNotFound(tag.to_string()))?;
+        if tag_id == into_id {
+            continue;
+        }
+
+        merged += sqlx::query(
+            "INSERT OR IGNORE INTO recipe_tags (recipe_id, tag_id) SELECT recipe_id, ? FROM recipe_tags WHERE tag_id = ?",
+        )
+        .bind(into_id)
+        .bind(tag_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(tag_id)
+            .execute(&mut *tx)
+            .await?;
+        record_change(
+            &mut tx,
+            "tag",
+            tag_id,
+            AuditAction::Delete,
+            Some(&json!({ "name": tag, "merged_into": into })),
+            None,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(merged)
+}
+
+/// IDs in `recipe_ids` with no recipe, in the order given
+async fn missing_recipes(conn: &mut SqliteConnection, recipe_ids: &[i64]) -> Result<Vec<i64>> {
+    if recipe_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut builder: QueryBuilder<Sqlite> =
+        QueryBuilder::new("SELECT id FROM recipes WHERE deleted_at IS NULL");
+    push_ids(&mut builder, " AND id IN ", recipe_ids);
+    let found: Vec<i64> = builder.build_query_scalar().fetch_all(&mut *conn).await?;
+
+    Ok(recipe_ids
+        .iter()
+        .copied()
+        .filter(|id| !found.contains(id))
+        .collect())
+}
+
+/// Append `condition` followed by a parenthesized list of `ids`
+/// An empty list matches nothing
+fn push_ids(builder: &mut QueryBuilder<'_, Sqlite>, condition: &str, ids: &[i64]) {
+    builder.push(condition);
+    if ids.is_empty() {
+        builder.push("(NULL)");
+        return;
+    }
+
+    builder.push("(");
+    let mut separated = builder.separated(", ");
+    for &id in ids {
+        separated.push_bind(id);
+    }
+    separated.push_unseparated(")");
+}
+
+/// Record a change to a recipe's tags, from `old` to the ones it has now,
+/// inside the caller's transaction; nothing is recorded if they're the same
+async fn audit_tags_in(
+    conn: &mut SqliteConnection,
+    recipe_id: i64,
+    old: Vec<String>,
+) -> Result<()> {
+    let new = recipe_tags_in(conn, recipe_id).await?;
+    if new == old {
+        return Ok(());
+    }
+
+    record_change(
+        conn,
+        "recipe",
+        recipe_id,
+        AuditAction::Update,
+        Some(&json!({ "tags": old })),
+        Some(&json!({ "tags": new })),
+    )
+    .await
+}
+
+/// Tags on a recipe, ordered by name
+pub async fn get_recipe_tags(pool: &SqlitePool, recipe_id: i64) -> Result<Vec<String>> {
+    recipe_tags_in(&mut *pool.acquire().await?, recipe_id).await
+}
+
+/// Tags on a recipe, read inside the caller's transaction
+async fn recipe_tags_in(conn: &mut SqliteConnection, recipe_id: i64) -> Result<Vec<String>> {
+    let tags = sqlx::query_scalar(
+        r#"
+        SELECT t.name
+        FROM recipe_tags rt
+        JOIN tags t ON rt.tag_id = t.id
+        WHERE rt.recipe_id = ?
+        ORDER BY t.name
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(tags)
+}
+
+/// Every tag in use, ordered by name
+pub async fn list_tags(pool: &SqlitePool) -> Result<Vec<String>> {
+    let tags = sqlx::query_scalar(
+        "SELECT name FROM tags WHERE id IN (SELECT tag_id FROM recipe_tags) ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tags)
+}
+
+/// Recipes with a tag (ignoring case), ordered by name
+/// An unknown tag has no recipes
+pub async fn get_recipes_by_tag(pool: &SqlitePool, tag: &str) -> Result<Vec<RecipeRecord>> {
+    get_recipes_by_tag_with(pool, tag, &RecipeFilter::default()).await
+}
+
+/// Like `get_recipes_by_tag`, returning only recipes that pass `filter`
+pub async fn get_recipes_by_tag_with(
+    pool: &SqlitePool,
+    tag: &str,
+    filter: &RecipeFilter,
+) -> Result<Vec<RecipeRecord>> {
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        r#"
+        SELECT r.id, r.name, r.instructions, r.servings, r.source_url, r.source_name, r.created_at
+        FROM recipes r
+        JOIN recipe_tags rt ON rt.recipe_id = r.id
+        JOIN tags t ON rt.tag_id = t.id
+        WHERE t.name = "#,
+    );
+    builder.push_bind(tag.trim().to_string());
+    push_recipe_filter(&mut builder, filter);
+    builder.push(" ORDER BY r.name, r.id");
+
+    Ok(builder
+        .build_query_as::<RecipeRecord>()
+        .fetch_all(pool)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::Visibility;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    async fn insert_recipe(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query("INSERT INTO recipes (name) VALUES (?)")
+            .bind(name)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_add_and_remove_tags(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let id = insert_recipe(&pool, "Chili").await;
+
+        add_recipe_tag(&pool, id, "weeknight").await.unwrap();
+        add_recipe_tag(&pool, id, "Spicy").await.unwrap();
+        // Same tag regardless of case, and no duplicate link
+        add_recipe_tag(&pool, id, "WEEKNIGHT").await.unwrap();
+
+        assert_eq!(
+            get_recipe_tags(&pool, id).await.unwrap(),
+            vec!["Spicy", "weeknight"]
+        );
+
+        assert!(remove_recipe_tag(&pool, id, "spicy").await.unwrap());
+        assert!(!remove_recipe_tag(&pool, id, "spicy").await.unwrap());
+        assert_eq!(get_recipe_tags(&pool, id).await.unwrap(), vec!["weeknight"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_tag_and_untag_many_recipes(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let a = insert_recipe(&pool, "A").await;
+        let b = insert_recipe(&pool, "B").await;
+        let c = insert_recipe(&pool, "C").await;
+        add_recipe_tag(&pool, a, "Quick").await.unwrap();
+
+        assert_eq!(tag_recipes(&pool, " quick ", &[a, b, c]).await.unwrap(), 2);
+        assert_eq!(get_recipe_tags(&pool, c).await.unwrap(), vec!["Quick"]);
+        assert_eq!(tag_recipes(&pool, "quick", &[]).await.unwrap(), 0);
+
+        assert!(matches!(
+            tag_recipes(&pool, "holiday", &[a, 999]).await,
+            Err(FeedMeError::RecipeNotFound(999))
+        ));
+        assert_eq!(get_recipe_tags(&pool, a).await.unwrap(), vec!["Quick"]);
+
+        assert_eq!(untag_recipes(&pool, "QUICK", &[a, b]).await.unwrap(), 2);
+        assert_eq!(untag_recipes(&pool, "QUICK", &[a, b]).await.unwrap(), 0);
+        assert_eq!(untag_recipes(&pool, "unknown", &[c]).await.unwrap(), 0);
+        assert_eq!(get_recipe_tags(&pool, c).await.unwrap(), vec!["Quick"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_rename_and_merge_tags(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let a = insert_recipe(&pool, "A").await;
+        let b = insert_recipe(&pool, "B").await;
+        add_recipe_tag(&pool, a, "veggie").await.unwrap();
+        add_recipe_tag(&pool, b, "veg").await.unwrap();
+        add_recipe_tag(&pool, b, "vegetarian").await.unwrap();
+
+        rename_tag(&pool, "VEGGIE", "Veggie").await.unwrap();
+        assert_eq!(get_recipe_tags(&pool, a).await.unwrap(), vec!["Veggie"]);
+        assert!(matches!(
+            rename_tag(&pool, "veggie", "veg").await,
+            Err(FeedMeError::DuplicateTag(name)) if name == "veg"
+        ));
+        assert!(matches!(
+            rename_tag(&pool, "vegan", "plant-based").await,
+            Err(FeedMeError::TagNotFound(name)) if name == "vegan"
+        ));
+
+        assert!(matches!(
+            merge_tags(&pool, &["veggie", "vegan"], "vegetarian").await,
+            Err(FeedMeError::TagNotFound(_))
+        ));
+        assert_eq!(
+            merge_tags(&pool, &["veggie", "veg", "Vegetarian"], "vegetarian")
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(get_recipe_tags(&pool, a).await.unwrap(), vec!["vegetarian"]);
+        assert_eq!(get_recipe_tags(&pool, b).await.unwrap(), vec!["vegetarian"]);
+        let tags: Vec<String> = sqlx::query_scalar("SELECT name FROM tags")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(tags, vec!["vegetarian"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_tags_are_shared_between_recipes(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let a = insert_recipe(&pool, "A").await;
+        let b = insert_recipe(&pool, "B").await;
+
+        add_recipe_tag(&pool, a, "vegetarian").await.unwrap();
+        add_recipe_tag(&pool, b, "vegetarian").await.unwrap();
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tags")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_list_tags_and_recipes_by_tag(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let stew = insert_recipe(&pool, "Stew").await;
+        let chili = insert_recipe(&pool, "Chili").await;
+        let salad = insert_recipe(&pool, "Salad").await;
+
+        tag_recipes(&pool, "Winter", &[stew, chili]).await.unwrap();
+        add_recipe_tag(&pool, salad, "summer").await.unwrap();
+        add_recipe_tag(&pool, salad, "lunch").await.unwrap();
+        remove_recipe_tag(&pool, salad, "lunch").await.unwrap();
+
+        // Tags no recipe uses any more aren't listed
+        assert_eq!(list_tags(&pool).await.unwrap(), vec!["summer", "Winter"]);
+
+        let names: Vec<String> = get_recipes_by_tag(&pool, " winter ")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|recipe| recipe.name)
+            .collect();
+        assert_eq!(names, vec!["Chili", "Stew"]);
+        crate::controllers::set_recipe_visibility(&pool, chili, Visibility::Private)
+            .await
+            .unwrap();
+        let household = RecipeFilter {
+            audience: Some(Visibility::Household),
+            ..Default::default()
+        };
+        let names: Vec<String> = get_recipes_by_tag_with(&pool, "winter", &household)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|recipe| recipe.name)
+            .collect();
+        assert_eq!(names, vec!["Stew"]);
+        assert!(
+            get_recipes_by_tag(&pool, "autumn")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,630 @@
+use sqlx::Row;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::DatabaseConfig;
+use crate::error::Result;
+use crate::quantity::parse_quantity;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// URL that makes [`init`] open a throwaway in-memory database
+pub const IN_MEMORY_URL: &str = "sqlite::memory:";
+
+/// `data_backfills` name of [`backfill_structured_quantities`]
+const STRUCTURED_QUANTITIES_BACKFILL: &str = "structured_quantities";
+
+/// A recipe_ingredients row whose quantity_unit couldn't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnparsedQuantity {
+    pub recipe_ingredient_id: i64,
+    pub recipe_id: i64,
+    pub quantity_unit: String,
+}
+
+/// Outcome of backfilling structured quantities
+#[derive(Debug, Clone, Default)]
+pub struct BackfillReport {
+    /// Rows that gained an amount/unit
+    pub updated: usize,
+    /// Rows left without one, for manual cleanup
+    pub unparseable: Vec<UnparsedQuantity>,
+}
+
+/// Open the configured database, creating it and running migrations as needed
+pub async fn connect(config: &DatabaseConfig) -> Result<SqlitePool> {
+    init(&config.url, config).await
+}
+
+/// Open the database at `url`, creating it and running migrations as needed
+///
+/// `config` supplies the pool, locking, backup and encryption settings; its
+/// own `url` is ignored. An in-memory URL such as [`IN_MEMORY_URL`] gets a
+/// fresh database from [`connect_in_memory`] and ignores `config` altogether.
+/// This is the single init path shared by every binary and embedder.
+pub async fn init(url: &str, config: &DatabaseConfig) -> Result<SqlitePool> {
+    if is_in_memory(url) {
+        return connect_in_memory().await;
+    }
+
+    let options = connect_options(url, config)?;
+    let existing_file = Some(options.get_filename())
+        .filter(|path| path.is_file())
+        .map(Path::to_path_buf);
+
+    tracing::debug!(
+        url,
+        max_connections = config.max_connections,
+        encrypted = config.passphrase.is_some(),
+        "connecting"
+    );
+    let pool = SqlitePoolOptions::new()
+        .max_connections(config.max_connections)
+        .acquire_timeout(config.acquire_timeout())
+        .max_lifetime(config.max_lifetime())
+        .connect_with(options)
+        .await?;
+
+    if config.read_only {
+        if has_pending_migrations(&pool).await? {
+            tracing::warn!("database has unapplied migrations but is open read-only");
+        }
+        return Ok(pool);
+    }
+
+    if let Some(path) = existing_file
+        && config.backup_retention > 0
+        && has_pending_migrations(&pool).await?
+    {
+        backup_database(&pool, &path, config.backup_retention).await?;
+    }
+
+    tracing::debug!("running migrations");
+    MIGRATOR.run(&pool).await.map_err(sqlx::Error::from)?;
+
+    if !backfill_done(&pool, STRUCTURED_QUANTITIES_BACKFILL).await? {
+        let report = backfill_structured_quantities(&pool).await?;
+        tracing::info!(
+            rows = report.updated,
+            unparseable = report.unparseable.len(),
+            "backfilled structured quantities"
+        );
+    }
+
+    Ok(pool)
+}
+
+/// Whether the one-off backfill `name` has already run on this database
+async fn backfill_done(pool: &SqlitePool, name: &str) -> Result<bool> {
+    let done = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM data_backfills WHERE name = ?)")
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(done)
+}
+
+/// Fill amount/unit for recipe_ingredients rows that predate those columns
+///
+/// [`init`] runs this once per database and records that it has; rows
+/// written since parse their quantities as they're saved. Only rows with no
+/// amount are considered, so running it again by hand is cheap.
+pub async fn backfill_structured_quantities(pool: &SqlitePool) -> Result<BackfillReport> {
+    let rows = unparsed_quantities(pool).await?;
+
+    let mut report = BackfillReport::default();
+    let mut tx = pool.begin().await?;
+
+    for row in rows {
+        match parse_quantity(&row.quantity_unit) {
+            Some(parsed) => {
+                sqlx::query("UPDATE recipe_ingredients SET amount = ?, unit = ? WHERE id = ?")
+                    .bind(parsed.amount)
+                    .bind(parsed.unit)
+                    .bind(row.recipe_ingredient_id)
+                    .execute(&mut *tx)
+                    .await?;
+                report.updated += 1;
+            }
+            None => report.unparseable.push(row),
+        }
+    }
+    sqlx::query("INSERT OR IGNORE INTO data_backfills (name) VALUES (?)")
+        .bind(STRUCTURED_QUANTITIES_BACKFILL)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+/// recipe_ingredients rows without an amount/unit, for manual cleanup
+/// Once [`init`] has backfilled a database these are the quantities that
+/// couldn't be parsed ("a pinch")
+pub async fn unparsed_quantities(pool: &SqlitePool) -> Result<Vec<UnparsedQuantity>> {
+    let rows = sqlx::query(
+        "SELECT id, recipe_id, quantity_unit FROM recipe_ingredients WHERE amount IS NULL ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| UnparsedQuantity {
+        recipe_ingredient_id: row.get("id"),
+        recipe_id: row.get("recipe_id"),
+        quantity_unit: row.get("quantity_unit"),
+    })
+    .collect();
+
+    Ok(rows)
+}
+
+/// Open a fresh, fully migrated in-memory database
+///
+/// The pool holds exactly one connection that is never recycled, since each
+/// SQLite connection to `:memory:` would otherwise see its own empty database.
+pub async fn connect_in_memory() -> Result<SqlitePool> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .min_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await?;
+
+    MIGRATOR.run(&pool).await.map_err(sqlx::Error::from)?;
+
+    Ok(pool)
+}
+
+fn is_in_memory(url: &str) -> bool {
+    let path = url
+        .strip_prefix("sqlite://")
+        .or_else(|| url.strip_prefix("sqlite:"))
+        .unwrap_or(url);
+    path == ":memory:"
+}
+
+/// Whether any bundled migration hasn't been applied to this database yet
+async fn has_pending_migrations(pool: &SqlitePool) -> Result<bool> {
+    let tracked: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await?;
+    if !tracked {
+        return Ok(true);
+    }
+
+    let applied: HashSet<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    Ok(MIGRATOR.iter().any(|m| !applied.contains(&m.version)))
+}
+
+/// Snapshot the database to `<file>.bak-<unix seconds>` and prune old snapshots
+///
+/// Uses `VACUUM INTO` so the copy is consistent even with other connections open.
+pub async fn backup_database(pool: &SqlitePool, path: &Path, retention: usize) -> Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let backup = backup_path(path, timestamp);
+
+    tracing::info!(backup = %backup.display(), "backing up database before migrating");
+    sqlx::query("VACUUM INTO ?")
+        .bind(backup.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+
+    prune_backups(path, retention)?;
+
+    Ok(backup)
+}
+
+fn backup_path(path: &Path, timestamp: u64) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".bak-{}", timestamp));
+    path.with_file_name(name)
+}
+
+/// Delete all but the newest `retention` backups of `path`
+fn prune_backups(path: &Path, retention: usize) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = format!(
+        "{}.bak-",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    );
+
+    let mut backups: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let timestamp = name.strip_prefix(&prefix)?.parse().ok()?;
+            Some((timestamp, entry.path()))
+        })
+        .collect();
+
+    backups.sort();
+    let excess = backups.len().saturating_sub(retention);
+    for (_, old) in backups.into_iter().take(excess) {
+        tracing::debug!(backup = %old.display(), "removing old backup");
+        let _ = std::fs::remove_file(old);
+    }
+
+    Ok(())
+}
+
+/// Per-connection options: create the file if missing and apply the key
+fn connect_options(url: &str, config: &DatabaseConfig) -> Result<SqliteConnectOptions> {
+    let options = SqliteConnectOptions::from_str(url)?
+        .create_if_missing(!config.read_only)
+        .busy_timeout(config.busy_timeout())
+        .read_only(config.read_only);
+
+    match &config.passphrase {
+        None => Ok(options),
+        #[cfg(feature = "sqlcipher")]
+        // `key` must be the first statement on every connection; sqlx
+        // reserves the first pragma slot for it
+        Some(passphrase) => {
+            Ok(options.pragma("key", format!("'{}'", passphrase.replace('\'', "''"))))
+        }
+        #[cfg(not(feature = "sqlcipher"))]
+        Some(_) => Err(crate::error::FeedMeError::Config(
+            "a database passphrase is set but feedme was built without the `sqlcipher` feature"
+                .to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "sqlcipher"))]
+    use crate::error::FeedMeError;
+
+    fn temp_db_config(name: &str) -> (std::path::PathBuf, DatabaseConfig) {
+        let path =
+            std::env::temp_dir().join(format!("feedme-db-test-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let config = DatabaseConfig {
+            url: format!("sqlite://{}", path.display()),
+            max_connections: 2,
+            ..DatabaseConfig::default()
+        };
+        (path, config)
+    }
+
+    #[tokio::test]
+    async fn test_connect_creates_and_migrates_database() {
+        let (path, config) = temp_db_config("create");
+
+        let pool = connect(&config).await.expect("Failed to connect");
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recipes")
+            .fetch_one(&pool)
+            .await
+            .expect("Migrations should have created recipes");
+        assert_eq!(count, 0);
+        assert_eq!(pool.options().get_max_connections(), 2);
+
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_backup_before_pending_migrations() {
+        let (path, config) = temp_db_config("backup");
+        let dir = path.parent().unwrap().to_path_buf();
+        let prefix = format!("{}.bak-", path.file_name().unwrap().to_string_lossy());
+        let count_backups = || {
+            std::fs::read_dir(&dir)
+                .unwrap()
+                .filter(|e| {
+                    e.as_ref()
+                        .unwrap()
+                        .file_name()
+                        .to_string_lossy()
+                        .starts_with(&prefix)
+                })
+                .count()
+        };
+
+        // A brand-new database has nothing worth backing up
+        connect(&config)
+            .await
+            .expect("Failed to connect")
+            .close()
+            .await;
+        assert_eq!(count_backups(), 0);
+
+        // Nothing pending, so no backup either
+        connect(&config)
+            .await
+            .expect("Failed to reconnect")
+            .close()
+            .await;
+        assert_eq!(count_backups(), 0);
+
+        // Simulate a database from before the latest migration
+        let pool = connect(&config).await.unwrap();
+        assert!(!has_pending_migrations(&pool).await.unwrap());
+        let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap();
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = ?")
+            .bind(latest)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(has_pending_migrations(&pool).await.unwrap());
+
+        let backup = backup_database(&pool, &path, config.backup_retention)
+            .await
+            .expect("Failed to back up");
+        pool.close().await;
+        assert_eq!(count_backups(), 1);
+
+        // The backup is a usable copy of the database
+        let copy = SqlitePool::connect(&format!("sqlite://{}", backup.display()))
+            .await
+            .expect("Failed to open backup");
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recipes")
+            .fetch_one(&copy)
+            .await
+            .expect("Backup should contain recipes table");
+        assert_eq!(count, 0);
+        copy.close().await;
+
+        let _ = std::fs::remove_file(&path);
+        for entry in std::fs::read_dir(&dir).unwrap().flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_in_memory_is_migrated() {
+        let pool = connect_in_memory()
+            .await
+            .expect("Failed to open in-memory db");
+
+        crate::controllers::create_ingredient(&pool, "salt")
+            .await
+            .expect("Failed to write");
+        let ingredients = crate::controllers::get_all_ingredients(&pool)
+            .await
+            .expect("Failed to read");
+
+        assert_eq!(ingredients.len(), 1);
+        assert!(!has_pending_migrations(&pool).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_init_opens_url_with_config_settings() {
+        let (path, config) = temp_db_config("init");
+        let url = format!("sqlite://{}", path.display());
+        let config = DatabaseConfig {
+            url: "sqlite://ignored.db".to_string(),
+            ..config
+        };
+
+        let pool = init(&url, &config).await.expect("Failed to init");
+        assert_eq!(pool.options().get_max_connections(), 2);
+        assert!(path.is_file());
+        assert!(!Path::new("ignored.db").exists());
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+
+        for url in [IN_MEMORY_URL, "sqlite://:memory:", ":memory:"] {
+            let pool = init(url, &config).await.expect("Failed to init in memory");
+            assert!(!has_pending_migrations(&pool).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_writes() {
+        let (path, mut config) = temp_db_config("readonly");
+        connect(&config)
+            .await
+            .expect("Failed to create db")
+            .close()
+            .await;
+
+        config.read_only = true;
+        let pool = connect(&config).await.expect("Failed to open read-only");
+
+        let result = crate::controllers::create_ingredient(&pool, "salt").await;
+        assert!(matches!(result, Err(crate::error::FeedMeError::ReadOnly)));
+
+        // Reads still work
+        let ingredients = crate::controllers::get_all_ingredients(&pool)
+            .await
+            .expect("Failed to read");
+        assert!(ingredients.is_empty());
+
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_does_not_create_database() {
+        let (path, mut config) = temp_db_config("readonly-missing");
+        config.read_only = true;
+
+        assert!(connect(&config).await.is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_prune_backups_keeps_newest() {
+        let dir = std::env::temp_dir().join(format!("feedme-prune-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("feedme.db");
+
+        for timestamp in [100, 300, 200, 400] {
+            std::fs::write(backup_path(&db, timestamp), b"").unwrap();
+        }
+        std::fs::write(dir.join("unrelated.db.bak-1"), b"").unwrap();
+
+        prune_backups(&db, 2).expect("Failed to prune");
+
+        let mut remaining: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "feedme.db.bak-300".to_string(),
+                "feedme.db.bak-400".to_string(),
+                "unrelated.db.bak-1".to_string(),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_structured_quantities() {
+        let pool = crate::models::test_fixtures::test_db().await;
+
+        let recipe_id = sqlx::query("INSERT INTO recipes (name) VALUES ('Old Recipe')")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert recipe")
+            .last_insert_rowid();
+
+        // Rows as an old database would have them, without amount/unit
+        for (i, quantity_unit) in ["2 cups", "1/2 tsp", "3 whole", "a pinch"]
+            .iter()
+            .enumerate()
+        {
+            let ingredient_id = sqlx::query("INSERT INTO ingredients (name) VALUES (?)")
+                .bind(format!("ingredient {}", i))
+                .execute(&pool)
+                .await
+                .expect("Failed to insert ingredient")
+                .last_insert_rowid();
+            sqlx::query(
+                "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit) VALUES (?, ?, ?)",
+            )
+            .bind(recipe_id)
+            .bind(ingredient_id)
+            .bind(quantity_unit)
+            .execute(&pool)
+            .await
+            .expect("Failed to insert recipe_ingredient");
+        }
+
+        let report = backfill_structured_quantities(&pool)
+            .await
+            .expect("Failed to backfill");
+
+        assert_eq!(report.updated, 3);
+        assert_eq!(report.unparseable.len(), 1);
+        assert_eq!(report.unparseable[0].quantity_unit, "a pinch");
+        assert_eq!(report.unparseable[0].recipe_id, recipe_id);
+
+        let half: (f64, String) = sqlx::query_as(
+            "SELECT amount, unit FROM recipe_ingredients WHERE quantity_unit = '1/2 tsp'",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch backfilled row");
+        assert_eq!(half, (0.5, "tsp".to_string()));
+
+        // Re-running only revisits the unparseable row
+        let again = backfill_structured_quantities(&pool)
+            .await
+            .expect("Failed to backfill again");
+        assert_eq!(again.updated, 0);
+        assert_eq!(again.unparseable, report.unparseable);
+        assert_eq!(
+            unparsed_quantities(&pool).await.unwrap(),
+            report.unparseable
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_backfills_once() {
+        let (path, config) = temp_db_config("backfill-once");
+        let pool = connect(&config).await.expect("Failed to connect");
+
+        // A row written without amount/unit after the backfill has run
+        let recipe_id = sqlx::query("INSERT INTO recipes (name) VALUES ('Old Recipe')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        let ingredient_id = sqlx::query("INSERT INTO ingredients (name) VALUES ('flour')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        sqlx::query(
+            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit) VALUES (?, ?, '2 cups')",
+        )
+        .bind(recipe_id)
+        .bind(ingredient_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let pool = connect(&config).await.expect("Failed to reconnect");
+        let amount: Option<f64> = sqlx::query_scalar("SELECT amount FROM recipe_ingredients")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(amount, None);
+
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    #[tokio::test]
+    async fn test_passphrase_without_sqlcipher_is_rejected() {
+        let (_path, mut config) = temp_db_config("nocipher");
+        config.passphrase = Some("secret".to_string());
+
+        let result = connect(&config).await;
+
+        assert!(matches!(result, Err(FeedMeError::Config(_))));
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn test_encrypted_database_requires_passphrase() {
+        let (path, mut config) = temp_db_config("cipher");
+        config.passphrase = Some("correct horse".to_string());
+
+        let pool = connect(&config)
+            .await
+            .expect("Failed to create encrypted db");
+        pool.close().await;
+
+        config.passphrase = Some("wrong".to_string());
+        assert!(connect(&config).await.is_err());
+
+        config.passphrase = None;
+        assert!(connect(&config).await.is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,306 @@
+//! What changed between two versions of a recipe
+//!
+//! Ingredients are matched by name (ignoring case) rather than ID, so a
+//! recipe can be compared with one from another database, such as a pack
+//! being imported. Instructions are compared line by line, one step per line.
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::models::api::{Recipe, RecipeIngredient};
+
+/// A field's value before and after
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// An ingredient in both recipes with a different quantity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuantityChange {
+    pub ingredient_name: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// One instruction step added or removed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", content = "text", rename_all = "lowercase")]
+pub enum StepChange {
+    Removed(String),
+    Added(String),
+}
+
+/// Everything that differs between two recipes, see `diff_recipes`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RecipeDiff {
+    pub name: Option<Change<String>>,
+    pub servings: Option<Change<Option<i64>>>,
+    /// Ingredients only in the newer recipe, in its order
+    pub added: Vec<RecipeIngredient>,
+    /// Ingredients only in the older recipe, in its order
+    pub removed: Vec<RecipeIngredient>,
+    pub changed: Vec<QuantityChange>,
+    /// Steps removed and added, in instruction order; unchanged steps are
+    /// left out
+    pub instructions: Vec<StepChange>,
+}
+
+impl RecipeDiff {
+    /// Whether the recipes are the same
+    pub fn is_empty(&self) -> bool {
+        *self == RecipeDiff::default()
+    }
+}
+
+/// Compare an older and a newer version of a recipe
+/// IDs, creation dates and derived fields (timers, journal, nutrition) are
+/// ignored
+pub fn diff_recipes(before: &Recipe, after: &Recipe) -> RecipeDiff {
+    let name = (before.name != after.name).then(|| Change {
+        before: before.name.clone(),
+        after: after.name.clone(),
+    });
+    let servings = (before.servings != after.servings).then_some(Change {
+        before: before.servings,
+        after: after.servings,
+    });
+
+    let find = |ingredients: &[RecipeIngredient], name: &str| {
+        ingredients
+            .iter()
+            .find(|ingredient| ingredient.ingredient_name.eq_ignore_ascii_case(name))
+            .cloned()
+    };
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+    for old in &before.ingredients {
+        match find(&after.ingredients, &old.ingredient_name) {
+            None => removed.push(old.clone()),
+            Some(new) if new.quantity_unit.trim() != old.quantity_unit.trim() => {
+                changed.push(QuantityChange {
+                    ingredient_name: new.ingredient_name,
+                    before: old.quantity_unit.clone(),
+                    after: new.quantity_unit,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    let added = after
+        .ingredients
+        .iter()
+        .filter(|new| find(&before.ingredients, &new.ingredient_name).is_none())
+        .cloned()
+        .collect();
+
+    RecipeDiff {
+        name,
+        servings,
+        added,
+        removed,
+        changed,
+        instructions: diff_steps(
+            &steps(before.instructions.as_deref()),
+            &steps(after.instructions.as_deref()),
+        ),
+    }
+}
+
+/// Non-blank instruction lines, trimmed
+fn steps(instructions: Option<&str>) -> Vec<&str> {
+    instructions
+        .unwrap_or("")
+        .lines()
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .collect()
+}
+
+/// Line diff from the longest common subsequence of steps
+/// Recipes have tens of steps at most, so the quadratic table is fine
+fn diff_steps(before: &[&str], after: &[&str]) -> Vec<StepChange> {
+    // common[i][j]: steps shared by before[i..] and after[j..]
+    let mut common = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            i += 1;
+            j += 1;
+        } else if j == after.len() || (i < before.len() && common[i + 1][j] >= common[i][j + 1]) {
+            changes.push(StepChange::Removed(before[i].to_string()));
+            i += 1;
+        } else {
+            changes.push(StepChange::Added(after[j].to_string()));
+            j += 1;
+        }
+    }
+
+    changes
+}
+
+impl fmt::Display for RecipeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        if let Some(name) = &self.name {
+            writeln!(f, "Name: {} -> {}", name.before, name.after)?;
+        }
+        if let Some(servings) = &self.servings {
+            let show = |servings: Option<i64>| servings.map_or("-".to_string(), |s| s.to_string());
+            writeln!(
+                f,
+                "Servings: {} -> {}",
+                show(servings.before),
+                show(servings.after)
+            )?;
+        }
+
+        for ingredient in &self.removed {
+            writeln!(
+                f,
+                "- {} {}",
+                ingredient.quantity_unit, ingredient.ingredient_name
+            )?;
+        }
+        for ingredient in &self.added {
+            writeln!(
+                f,
+                "+ {} {}",
+                ingredient.quantity_unit, ingredient.ingredient_name
+            )?;
+        }
+        for change in &self.changed {
+            writeln!(
+                f,
+                "~ {}: {} -> {}",
+                change.ingredient_name, change.before, change.after
+            )?;
+        }
+
+        if !self.instructions.is_empty() {
+            writeln!(f, "Instructions:")?;
+            for change in &self.instructions {
+                match change {
+                    StepChange::Removed(step) => writeln!(f, "  - {}", step)?,
+                    StepChange::Added(step) => writeln!(f, "  + {}", step)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::Visibility;
+
+    fn recipe(name: &str, instructions: &str, ingredients: &[(&str, &str)]) -> Recipe {
+        Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: Some(instructions.to_string()),
+            servings: Some(4),
+            ingredients: ingredients
+                .iter()
+                .map(|(name, quantity)| RecipeIngredient {
+                    ingredient_id: 0,
+                    ingredient_name: name.to_string(),
+                    quantity_unit: quantity.to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                })
+                .collect(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_recipes() {
+        let before = recipe(
+            "Pancakes",
+            "Mix.\nRest 10 minutes.\nFry.",
+            &[("flour", "2 cups"), ("eggs", "2"), ("sugar", "1 tbsp")],
+        );
+        let mut after = recipe(
+            "Fluffy Pancakes",
+            "Mix.\nFold in the whites.\nFry.",
+            &[("Flour", "2 cups"), ("eggs", "3"), ("buttermilk", "1 cup")],
+        );
+        after.servings = None;
+
+        let diff = diff_recipes(&before, &after);
+        assert_eq!(diff.servings.as_ref().unwrap().after, None);
+        assert_eq!(
+            diff.changed,
+            vec![QuantityChange {
+                ingredient_name: "eggs".to_string(),
+                before: "2".to_string(),
+                after: "3".to_string(),
+            }]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "Name: Pancakes -> Fluffy Pancakes\n\
+             Servings: 4 -> -\n\
+             - 1 tbsp sugar\n\
+             + 1 cup buttermilk\n\
+             ~ eggs: 2 -> 3\n\
+             Instructions:\n  \
+             - Rest 10 minutes.\n  \
+             + Fold in the whites.\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_identical_recipes() {
+        let pasta = recipe("Pasta", "Boil.\n\nDrain.", &[("spaghetti", "200 g")]);
+        let mut copy = pasta.clone();
+        copy.id = 7;
+        copy.instructions = Some("Boil.\nDrain.\n".to_string());
+
+        let diff = diff_recipes(&pasta, &copy);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No changes\n");
+    }
+
+    #[test]
+    fn test_diff_steps_keeps_order() {
+        assert_eq!(
+            diff_steps(&["a", "b", "c"], &["b", "c", "d"]),
+            vec![
+                StepChange::Removed("a".to_string()),
+                StepChange::Added("d".to_string())
+            ]
+        );
+        assert_eq!(
+            diff_steps(&[], &["a"]),
+            vec![StepChange::Added("a".to_string())]
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! The weekly digest: a meal plan and its shopping list in one message
+//!
+//! `feedme digest` is meant to be run from cron on Sunday. It renders the
+//! upcoming plan (see `weekly_digest`) as a self-contained HTML page, written
+//! to a file or, with the `email` feature and a `[digest]` SMTP server
+//! configured, mailed to the household alongside a plain text copy.
+
+use chrono::Datelike;
+use std::fmt::Write;
+
+use crate::config::DigestConfig;
+use crate::error::{FeedMeError, Result};
+use crate::label::escape;
+use crate::models::api::WeeklyDigest;
+
+/// "Meal plan for the week of 2026-10-19", or the plan's name when it has one
+pub fn subject(digest: &WeeklyDigest) -> String {
+    match &digest.plan.name {
+        Some(name) => format!("Meal plan: {}", name),
+        None => format!("Meal plan for the week of {}", digest.plan.start_date),
+    }
+}
+
+/// Plain text version, for mail clients that don't show HTML
+pub fn to_text(digest: &WeeklyDigest) -> String {
+    format!("{}\nShopping list\n{}", digest.plan, digest.shopping_list)
+}
+
+/// HTML page with inline styles, readable in a browser or a mail client
+pub fn to_html(digest: &WeeklyDigest) -> String {
+    let subject = escape(&subject(digest));
+    let list = &digest.shopping_list;
+    let mut out = String::new();
+
+    // Writing to a String can't fail
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(
+        out,
+        "<html><head><meta charset=\"utf-8\"><title>{}</title></head>",
+        subject
+    );
+    let _ = writeln!(out, "<body style=\"font-family:sans-serif\">");
+    let _ = writeln!(out, "<h1>{}</h1>", subject);
+
+    let _ = writeln!(out, "<table style=\"border-collapse:collapse\">");
+    for entry in &digest.plan.entries {
+        let mut meal = format!("<b>{}</b>", escape(&entry.recipe_name));
+        if entry.servings != 1.0 {
+            let _ = write!(meal, " x{}", entry.servings);
+        }
+        if let Some(note) = &entry.note {
+            let _ = write!(meal, "<br><i>{}</i>", escape(note));
+        }
+        let _ = writeln!(
+            out,
+            "<tr><td style=\"padding:2px 8px\">{} {}</td><td style=\"padding:2px 8px\">{}</td><td style=\"padding:2px 8px\">{}</td></tr>",
+            entry.date.weekday(),
+            entry.date,
+            escape(&entry.meal),
+            meal
+        );
+    }
+    let _ = writeln!(out, "</table>");
+
+    let _ = writeln!(out, "<h2>Shopping list</h2>");
+    // Grouped under a heading per aisle when arranged for a store
+    let grouped = list.store.is_some();
+    let mut aisle = None;
+    if !grouped {
+        let _ = writeln!(out, "<ul>");
+    }
+    for item in &list.items {
+        if grouped && aisle != Some(&item.aisle) {
+            if aisle.is_some() {
+                let _ = writeln!(out, "</ul>");
+            }
+            aisle = Some(&item.aisle);
+            let _ = writeln!(
+                out,
+                "<h3>{}</h3>\n<ul>",
+                escape(item.aisle.as_deref().unwrap_or("other"))
+            );
+        }
+
+        let mut line = String::new();
+        if let Some(icon) = &item.icon {
+            let _ = write!(line, "{} ", escape(icon));
+        }
+        let _ = write!(
+            line,
+            "{}: {}",
+            escape(&item.ingredient_name),
+            escape(&item.quantity)
+        );
+        if item.already_have {
+            let _ = writeln!(
+                out,
+                "<li style=\"color:#888\"><s>{}</s> (already have)</li>",
+                line
+            );
+        } else if let Some(cost) = item.estimated_cost {
+            let _ = writeln!(out, "<li>{} (~{:.2})</li>", line, cost);
+        } else {
+            let _ = writeln!(out, "<li>{}</li>", line);
+        }
+    }
+    if !grouped || aisle.is_some() {
+        let _ = writeln!(out, "</ul>");
+    }
+    if list.to_buy().any(|item| item.estimated_cost.is_some()) {
+        let _ = writeln!(out, "<p>Estimated total: {:.2}</p>", list.estimated_total());
+    }
+
+    let _ = writeln!(out, "</body></html>");
+    out
+}
+
+/// Email the digest to `config.to` over SMTP
+#[cfg(feature = "email")]
+pub fn send(config: &DigestConfig, digest: &WeeklyDigest) -> Result<()> {
+    use lettre::message::{Mailbox, MultiPart};
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{Message, SmtpTransport, Transport};
+
+    let (host, from) = check_config(config)?;
+    let mailbox = |address: &str| {
+        address
+            .parse::<Mailbox>()
+            .map_err(|e| FeedMeError::Config(format!("digest address '{}': {}", address, e)))
+    };
+
+    let mut message = Message::builder()
+        .from(mailbox(from)?)
+        .subject(subject(digest));
+    for to in &config.to {
+        message = message.to(mailbox(to)?);
+    }
+    let message = message
+        .multipart(MultiPart::alternative_plain_html(
+            to_text(digest),
+            to_html(digest),
+        ))
+        .map_err(|e| FeedMeError::Notification(e.to_string()))?;
+
+    let mut transport = SmtpTransport::starttls_relay(host)
+        .map_err(|e| FeedMeError::Notification(format!("{}: {}", host, e)))?
+        .port(config.smtp_port);
+    if let Some(username) = &config.smtp_username {
+        transport = transport.credentials(Credentials::new(
+            username.clone(),
+            config.smtp_password.clone().unwrap_or_default(),
+        ));
+    }
+    transport
+        .build()
+        .send(&message)
+        .map_err(|e| FeedMeError::Notification(format!("{}: {}", host, e)))?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "email"))]
+pub fn send(config: &DigestConfig, _digest: &WeeklyDigest) -> Result<()> {
+    check_config(config)?;
+    Err(FeedMeError::Config(
+        "emailing the digest requires building with `--features email`".to_string(),
+    ))
+}
+
+/// The SMTP host and sender address, once everything needed to send is set
+fn check_config(config: &DigestConfig) -> Result<(&str, &str)> {
+    let missing =
+        |key: &str| FeedMeError::Config(format!("set digest.{} to email the digest", key));
+
+    let host = config
+        .smtp_host
+        .as_deref()
+        .ok_or_else(|| missing("smtp_host"))?;
+    let from = config.from.as_deref().ok_or_else(|| missing("from"))?;
+    if config.to.is_empty() {
+        return Err(missing("to"));
+    }
+
+    Ok((host, from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::{MealPlan, PlannedMeal, ShoppingList, ShoppingListEntry, StoreLayout};
+    use chrono::NaiveDate;
+
+    fn digest() -> WeeklyDigest {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let entry = |ingredient_name: &str, aisle: &str, estimated_cost| ShoppingListEntry {
+            ingredient_id: 0,
+            ingredient_name: ingredient_name.to_string(),
+            quantity: "2".to_string(),
+            already_have: false,
+            purchased: false,
+            estimated_cost,
+            aisle: Some(aisle.to_string()),
+            icon: None,
+        };
+
+        WeeklyDigest {
+            plan: MealPlan {
+                id: 1,
+                name: None,
+                start_date: date,
+                entries: vec![PlannedMeal {
+                    date,
+                    meal: "dinner".to_string(),
+                    recipe_id: 1,
+                    recipe_name: "Mac & Cheese".to_string(),
+                    servings: 2.0,
+                    note: Some("grate the <cheddar>".to_string()),
+                }],
+            },
+            shopping_list: ShoppingList {
+                id: 0,
+                name: None,
+                meal_plan_id: Some(1),
+                purchased_on: None,
+                store: None,
+                items: vec![
+                    entry("cheddar", "dairy", Some(4.5)),
+                    entry("macaroni", "pasta", None),
+                ],
+            },
+        }
+    }
+
+    #[test]
+    fn test_html_digest() {
+        let mut digest = digest();
+        digest.shopping_list.items[1].already_have = true;
+
+        let html = to_html(&digest);
+        assert!(html.contains("<h1>Meal plan for the week of 2026-10-19</h1>"));
+        assert!(html.contains(
+            "<td style=\"padding:2px 8px\"><b>Mac &amp; Cheese</b> x2<br><i>grate the &lt;cheddar&gt;</i></td>"
+        ));
+        assert!(html.contains("<li>cheddar: 2 (~4.50)</li>"));
+        assert!(html.contains("<s>macaroni: 2</s> (already have)"));
+        assert!(html.contains("<p>Estimated total: 4.50</p>"));
+        assert!(!html.contains("<h3>"));
+
+        digest
+            .shopping_list
+            .arrange(&StoreLayout::new("corner", &["pasta".to_string()]));
+        let html = to_html(&digest);
+        assert!(html.find("<h3>pasta</h3>").unwrap() < html.find("<h3>dairy</h3>").unwrap());
+    }
+
+    #[test]
+    fn test_text_digest() {
+        let mut digest = digest();
+        digest.plan.name = Some("Back to school".to_string());
+
+        assert_eq!(subject(&digest), "Meal plan: Back to school");
+        let text = to_text(&digest);
+        assert!(text.starts_with("Meal plan: Back to school (from 2026-10-19)\n"));
+        assert!(text.contains("\nShopping list\n  cheddar: 2 (~4.50)\n"));
+    }
+
+    #[test]
+    fn test_send_needs_smtp_settings() {
+        let config = DigestConfig {
+            to: vec!["sam@example.com".to_string()],
+            ..DigestConfig::default()
+        };
+
+        assert!(matches!(
+            send(&config, &digest()),
+            Err(FeedMeError::Config(message)) if message.contains("smtp_host")
+        ));
+    }
+}
@@ -1,15 +1,371 @@
+use serde::Serialize;
+use sqlx::error::ErrorKind;
 use thiserror::Error;
 
+use crate::validation::{FieldError, describe};
+
 #[derive(Error, Debug)]
 pub enum FeedMeError {
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
+    Database(sqlx::Error),
+
+    #[error("Database is open read-only")]
+    ReadOnly,
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
 
     #[error("Recipe not found with id: {0}")]
     RecipeNotFound(i64),
 
     #[error("Ingredient not found with id: {0}")]
     IngredientNotFound(i64),
+
+    #[error("An ingredient named '{0}' already exists")]
+    DuplicateIngredient(String),
+
+    #[error("A recipe named '{0}' already exists")]
+    DuplicateRecipe(String),
+
+    #[error("A tag named '{0}' already exists")]
+    DuplicateTag(String),
+
+    #[error("A collection named '{0}' already exists")]
+    DuplicateCollection(String),
+
+    #[error("A meal plan template named '{0}' already exists")]
+    DuplicateMealPlanTemplate(String),
+
+    #[error("A custom unit named '{0}' already exists")]
+    DuplicateCustomUnit(String),
+
+    /// A row broke a database rule other than a duplicate name, such as a
+    /// reference to a missing row or an out-of-range value
+    #[error("Constraint violated: {0}")]
+    ConstraintViolation(String),
+
+    /// The data changed since it was read, so the write was refused
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Collection not found with id: {0}")]
+    CollectionNotFound(i64),
+
+    #[error("Cook log entry not found with id: {0}")]
+    CookLogEntryNotFound(i64),
+
+    #[error("Meal plan not found with id: {0}")]
+    MealPlanNotFound(i64),
+
+    #[error("Meal plan template not found with id: {0}")]
+    MealPlanTemplateNotFound(i64),
+
+    #[error("Pantry item not found with id: {0}")]
+    PantryItemNotFound(i64),
+
+    #[error("Shopping list not found with id: {0}")]
+    ShoppingListNotFound(i64),
+
+    #[error("Shopping list {0} hasn't been marked purchased")]
+    ShoppingListNotPurchased(i64),
+
+    #[error("Recipe image not found with id: {0}")]
+    ImageNotFound(i64),
+
+    #[error("Tag not found: '{0}'")]
+    TagNotFound(String),
+
+    #[error("Invalid input: {}", describe(.0))]
+    Validation(Vec<FieldError>),
+
+    #[error("Invalid quantity: {0}")]
+    InvalidQuantity(String),
+
+    #[error("Invalid substitution: {0}")]
+    InvalidSubstitution(String),
+
+    #[error("Invalid recipe pack: {0}")]
+    InvalidPack(String),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Barcode lookup failed: {0}")]
+    BarcodeLookup(String),
+
+    #[error("FoodData Central lookup failed: {0}")]
+    FdcLookup(String),
+
+    #[error("Notification failed: {0}")]
+    Notification(String),
+
+    #[error("Web import failed: {0}")]
+    WebImport(String),
+}
+
+/// SQLite primary result codes we react to
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+const SQLITE_READONLY: i32 = 8;
+const SQLITE_CANTOPEN: i32 = 14;
+const SQLITE_NOTADB: i32 = 26;
+
+/// What an error looks like to clients: the CLI prints it and an HTTP API
+/// returns it as the JSON body
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl FeedMeError {
+    /// Stable machine-readable identifier of the kind of failure
+    /// Codes never change once released, unlike the Display text
+    pub fn code(&self) -> &'static str {
+        match self {
+            FeedMeError::Database(_) => "database",
+            FeedMeError::ReadOnly => "read_only",
+            FeedMeError::Io(_) => "io",
+            FeedMeError::Serialization(_) => "serialization",
+            FeedMeError::RecipeNotFound(_) => "recipe_not_found",
+            FeedMeError::IngredientNotFound(_) => "ingredient_not_found",
+            FeedMeError::DuplicateIngredient(_) => "duplicate_ingredient",
+            FeedMeError::DuplicateRecipe(_) => "duplicate_recipe",
+            FeedMeError::DuplicateTag(_) => "duplicate_tag",
+            FeedMeError::DuplicateCollection(_) => "duplicate_collection",
+            FeedMeError::DuplicateMealPlanTemplate(_) => "duplicate_meal_plan_template",
+            FeedMeError::DuplicateCustomUnit(_) => "duplicate_custom_unit",
+            FeedMeError::ConstraintViolation(_) => "constraint_violation",
+            FeedMeError::Conflict(_) => "conflict",
+            FeedMeError::CollectionNotFound(_) => "collection_not_found",
+            FeedMeError::CookLogEntryNotFound(_) => "cook_log_entry_not_found",
+            FeedMeError::MealPlanNotFound(_) => "meal_plan_not_found",
+            FeedMeError::MealPlanTemplateNotFound(_) => "meal_plan_template_not_found",
+            FeedMeError::PantryItemNotFound(_) => "pantry_item_not_found",
+            FeedMeError::ShoppingListNotFound(_) => "shopping_list_not_found",
+            FeedMeError::ShoppingListNotPurchased(_) => "shopping_list_not_purchased",
+            FeedMeError::ImageNotFound(_) => "image_not_found",
+            FeedMeError::TagNotFound(_) => "tag_not_found",
+            FeedMeError::Validation(_) => "validation",
+            FeedMeError::InvalidQuantity(_) => "invalid_quantity",
+            FeedMeError::InvalidSubstitution(_) => "invalid_substitution",
+            FeedMeError::InvalidPack(_) => "invalid_pack",
+            FeedMeError::Config(_) => "config",
+            FeedMeError::BarcodeLookup(_) => "barcode_lookup",
+            FeedMeError::FdcLookup(_) => "fdc_lookup",
+            FeedMeError::Notification(_) => "notification",
+            FeedMeError::WebImport(_) => "web_import",
+        }
+    }
+
+    /// Short message for the person using FeedMe
+    /// Leaves out internals such as SQL errors, which the Display text keeps
+    /// for logs
+    pub fn user_message(&self) -> String {
+        match self {
+            FeedMeError::Database(_) => "The database couldn't complete the request".to_string(),
+            FeedMeError::ReadOnly => "The database is open read-only".to_string(),
+            FeedMeError::Io(err) => format!("Couldn't read or write a file: {}", err),
+            FeedMeError::Serialization(_) => "The data isn't valid JSON".to_string(),
+            FeedMeError::RecipeNotFound(id) => format!("There is no recipe {}", id),
+            FeedMeError::IngredientNotFound(id) => format!("There is no ingredient {}", id),
+            FeedMeError::DuplicateIngredient(name) => {
+                format!("There is already an ingredient called '{}'", name)
+            }
+            FeedMeError::DuplicateRecipe(name) => {
+                format!("There is already a recipe called '{}'", name)
+            }
+            FeedMeError::DuplicateTag(name) => format!("There is already a tag called '{}'", name),
+            FeedMeError::DuplicateCollection(name) => {
+                format!("There is already a collection called '{}'", name)
+            }
+            FeedMeError::DuplicateMealPlanTemplate(name) => {
+                format!("There is already a meal plan template called '{}'", name)
+            }
+            FeedMeError::DuplicateCustomUnit(name) => {
+                format!("There is already a custom unit called '{}'", name)
+            }
+            FeedMeError::ConstraintViolation(_) => {
+                "That change would leave the data inconsistent".to_string()
+            }
+            FeedMeError::Conflict(_) => {
+                "Someone else changed this in the meantime; reload and try again".to_string()
+            }
+            FeedMeError::CollectionNotFound(id) => format!("There is no collection {}", id),
+            FeedMeError::CookLogEntryNotFound(id) => {
+                format!("There is no cook log entry {}", id)
+            }
+            FeedMeError::MealPlanNotFound(id) => format!("There is no meal plan {}", id),
+            FeedMeError::MealPlanTemplateNotFound(id) => {
+                format!("There is no meal plan template {}", id)
+            }
+            FeedMeError::PantryItemNotFound(id) => format!("There is no pantry item {}", id),
+            FeedMeError::ShoppingListNotFound(id) => format!("There is no shopping list {}", id),
+            FeedMeError::ShoppingListNotPurchased(id) => {
+                format!("Mark shopping list {} purchased first", id)
+            }
+            FeedMeError::ImageNotFound(id) => format!("There is no image {}", id),
+            FeedMeError::TagNotFound(name) => format!("There is no tag '{}'", name),
+            FeedMeError::Validation(errors) => format!("Please fix: {}", describe(errors)),
+            FeedMeError::InvalidQuantity(quantity) => {
+                format!("Not a usable quantity: {}", quantity)
+            }
+            FeedMeError::InvalidSubstitution(reason) => {
+                format!("Not a usable substitution: {}", reason)
+            }
+            FeedMeError::InvalidPack(reason) => format!("Can't use that recipe pack: {}", reason),
+            FeedMeError::Config(reason) => format!("Check your configuration: {}", reason),
+            FeedMeError::BarcodeLookup(_) => "Couldn't look up that barcode".to_string(),
+            FeedMeError::FdcLookup(_) => "Couldn't reach FoodData Central".to_string(),
+            FeedMeError::Notification(_) => "Couldn't send the notification".to_string(),
+            FeedMeError::WebImport(reason) => {
+                format!("Couldn't import a recipe from that page: {}", reason)
+            }
+        }
+    }
+
+    /// A suggestion for fixing the problem, when there is an obvious one
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            FeedMeError::Database(sqlx::Error::Migrate(_)) => Some(
+                "the schema couldn't be migrated; restore the <file>.bak-<time> copy made before migrating, or upgrade FeedMe",
+            ),
+            FeedMeError::Database(err) => match sqlite_primary_code(err) {
+                Some(SQLITE_BUSY) | Some(SQLITE_LOCKED) => {
+                    Some("another program is using the database; try again once it's done")
+                }
+                Some(SQLITE_CANTOPEN) => {
+                    Some("check that the directory in database.url exists and is writable")
+                }
+                Some(SQLITE_NOTADB) => Some(
+                    "the file isn't a FeedMe database, or the passphrase ($FEEDME_DB_PASSPHRASE) is wrong",
+                ),
+                _ => None,
+            },
+            FeedMeError::ReadOnly => {
+                Some("unset database.read_only, or check the database file's permissions")
+            }
+            FeedMeError::Config(_) => Some("fix feedme.toml, or the file $FEEDME_CONFIG points at"),
+            FeedMeError::FdcLookup(_) => {
+                Some("check usda.api_key ($FEEDME_FDC_API_KEY) and the network connection")
+            }
+            FeedMeError::ShoppingListNotPurchased(_) => {
+                Some("record the shopping trip with mark_purchased first")
+            }
+            _ => None,
+        }
+    }
+
+    /// The code and user message together
+    pub fn to_body(&self) -> ErrorBody {
+        ErrorBody {
+            code: self.code(),
+            message: self.user_message(),
+        }
+    }
+
+    /// Whether another connection held the lock ("database is locked")
+    /// These failures are transient and safe to retry
+    pub fn is_busy(&self) -> bool {
+        match self {
+            FeedMeError::Database(err) => matches!(
+                sqlite_primary_code(err),
+                Some(SQLITE_BUSY) | Some(SQLITE_LOCKED)
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl From<sqlx::Error> for FeedMeError {
+    fn from(err: sqlx::Error) -> Self {
+        if let Some(code) = sqlite_primary_code(&err)
+            && code == SQLITE_READONLY
+        {
+            return FeedMeError::ReadOnly;
+        }
+
+        if let Some(db_err) = err.as_database_error()
+            && is_constraint(db_err.kind())
+        {
+            return FeedMeError::ConstraintViolation(db_err.message().to_string());
+        }
+
+        FeedMeError::Database(err)
+    }
+}
+
+fn is_constraint(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::UniqueViolation
+            | ErrorKind::ForeignKeyViolation
+            | ErrorKind::NotNullViolation
+            | ErrorKind::CheckViolation
+    )
+}
+
+/// Turns a failed UNIQUE constraint into a specific error, such as
+/// `DuplicateIngredient`, where the caller knows which name clashed
+/// Every other error converts as usual, constraint failures becoming
+/// `ConstraintViolation`
+pub(crate) trait OnDuplicate<T> {
+    fn on_duplicate(self, duplicate: impl FnOnce() -> FeedMeError) -> Result<T>;
+}
+
+impl<T> OnDuplicate<T> for std::result::Result<T, sqlx::Error> {
+    fn on_duplicate(self, duplicate: impl FnOnce() -> FeedMeError) -> Result<T> {
+        self.map_err(|err| match err.as_database_error() {
+            Some(db_err) if db_err.is_unique_violation() => duplicate(),
+            _ => err.into(),
+        })
+    }
+}
+
+/// The primary SQLite result code (extended code with the high bits masked off)
+fn sqlite_primary_code(err: &sqlx::Error) -> Option<i32> {
+    let code: i32 = err.as_database_error()?.code()?.parse().ok()?;
+    Some(code & 0xff)
 }
 
 pub type Result<T> = std::result::Result<T, FeedMeError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::Validator;
+
+    #[test]
+    fn test_user_message_hides_internals() {
+        let err = FeedMeError::Database(sqlx::Error::RowNotFound);
+
+        assert_eq!(err.code(), "database");
+        assert!(err.to_string().contains("no rows returned"));
+        assert!(!err.user_message().contains("no rows returned"));
+    }
+
+    #[test]
+    fn test_error_body() {
+        let mut validator = Validator::new();
+        validator.name("name", " ");
+        let err = validator.finish().unwrap_err();
+
+        assert_eq!(
+            serde_json::to_value(err.to_body()).unwrap(),
+            serde_json::json!({
+                "code": "validation",
+                "message": "Please fix: name must not be empty",
+            })
+        );
+        assert_eq!(
+            FeedMeError::RecipeNotFound(7).to_body(),
+            ErrorBody {
+                code: "recipe_not_found",
+                message: "There is no recipe 7".to_string(),
+            }
+        );
+    }
+}
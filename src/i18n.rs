@@ -0,0 +1,154 @@
+//! Translated user-facing text for the TUIs and CLI output
+//!
+//! Messages live in Fluent-style catalogs, `locales/<locale>.ftl`, one
+//! `id = text` per line with `{ $name }` placeholders. Binaries pick the
+//! locale from `display.language` with [`set_locale`] and look messages up
+//! through [`t!`](crate::t). A message missing from a catalog falls back to
+//! English. Log lines and error messages stay in English.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// A language FeedMe has messages for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Choose the locale for the rest of the process; later calls are ignored
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// The locale chosen with `set_locale`, English if none was
+pub fn current() -> Locale {
+    LOCALE.get().copied().unwrap_or_default()
+}
+
+impl Locale {
+    /// Message `id` with each `{ $name }` replaced by its value in `args`
+    /// Unknown ids come back unchanged so a missing message is easy to spot
+    pub fn message(self, id: &str, args: &[(&str, &dyn Display)]) -> String {
+        let text = self
+            .catalog()
+            .get(id)
+            .or_else(|| Locale::En.catalog().get(id))
+            .copied()
+            .unwrap_or(id);
+
+        args.iter().fold(text.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{ ${} }}", name), &value.to_string())
+        })
+    }
+
+    fn catalog(self) -> &'static HashMap<&'static str, &'static str> {
+        static EN: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+        static ES: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+
+        match self {
+            Locale::En => EN.get_or_init(|| parse(include_str!("../locales/en.ftl"))),
+            Locale::Es => ES.get_or_init(|| parse(include_str!("../locales/es.ftl"))),
+        }
+    }
+}
+
+/// `id = text` lines, skipping blanks and `#` comments
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(id, text)| (id.trim(), text.trim()))
+        .collect()
+}
+
+/// Look up a message in the current locale: `t!("id")` or
+/// `t!("id", name = value, ...)`
+#[macro_export]
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::current().message($id, &[])
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::current().message(
+            $id,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split("{ $")
+            .skip(1)
+            .filter_map(|rest| rest.split_once(" }"))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn test_catalogs_have_the_same_messages() {
+        let english = Locale::En.catalog();
+
+        for locale in [Locale::Es] {
+            let catalog = locale.catalog();
+            let mut ids: Vec<_> = english.keys().collect();
+            ids.sort();
+            assert_eq!(
+                ids,
+                {
+                    let mut ids: Vec<_> = catalog.keys().collect();
+                    ids.sort();
+                    ids
+                },
+                "{:?} catalog differs from English",
+                locale
+            );
+            for (id, text) in english {
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(catalog[id]),
+                    "{:?} placeholders differ for {}",
+                    locale,
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_fills_placeholders() {
+        assert_eq!(
+            Locale::En.message(
+                "importer-review-summary",
+                &[("ingredients", &3), ("steps", &2)]
+            ),
+            "3 ingredients, 2 steps"
+        );
+        assert_eq!(
+            Locale::Es.message("importer-quantity-title", &[("ingredient", &"harina")]),
+            "Cantidad de harina"
+        );
+        assert_eq!(
+            Locale::Es.message("no-such-message", &[]),
+            "no-such-message"
+        );
+    }
+
+    #[test]
+    fn test_t_uses_english_by_default() {
+        assert_eq!(t!("dinner-minutes", minutes = 20), "(20 min)");
+    }
+}
@@ -0,0 +1,245 @@
+//! Nutrition-facts-style labels for recipes
+//!
+//! Renders a recipe's per-serving nutrition (see `get_recipe_with`) in the
+//! familiar US label layout, as plain text for terminals and receipts or as a
+//! self-contained HTML block for printing.
+
+use std::fmt::Write;
+
+use crate::models::api::{Nutrient, Recipe, RecipeNutrition};
+
+/// Width of the text label in characters
+const TEXT_WIDTH: usize = 32;
+
+/// FDA reference daily values used for the "% Daily Value" column
+fn daily_value(nutrient: Nutrient) -> f64 {
+    match nutrient {
+        Nutrient::Calories => 2000.0,
+        Nutrient::Protein => 50.0,
+        Nutrient::Carbohydrates => 275.0,
+        Nutrient::Fat => 78.0,
+    }
+}
+
+/// Rows below calories, in label order
+const ROWS: [(Nutrient, &str); 3] = [
+    (Nutrient::Fat, "Total Fat"),
+    (Nutrient::Carbohydrates, "Total Carbohydrate"),
+    (Nutrient::Protein, "Protein"),
+];
+
+const FOOTNOTE: &str = "* Percent Daily Values are based on a 2,000 calorie diet.";
+
+/// A nutrition label for one recipe
+pub struct NutritionLabel<'a> {
+    name: &'a str,
+    nutrition: &'a RecipeNutrition,
+}
+
+impl<'a> NutritionLabel<'a> {
+    /// None unless the recipe was fetched with nutrition included
+    pub fn for_recipe(recipe: &'a Recipe) -> Option<Self> {
+        Some(Self {
+            name: &recipe.name,
+            nutrition: recipe.nutrition.as_ref()?,
+        })
+    }
+
+    fn percent_daily_value(&self, nutrient: Nutrient) -> f64 {
+        (self.nutrition.per_serving.get(nutrient) / daily_value(nutrient) * 100.0).round()
+    }
+
+    fn servings_line(&self) -> String {
+        match self.nutrition.servings {
+            1 => "1 serving per recipe".to_string(),
+            servings => format!("{} servings per recipe", servings),
+        }
+    }
+
+    /// Plain text label, `TEXT_WIDTH` characters wide
+    pub fn to_text(&self) -> String {
+        let facts = &self.nutrition.per_serving;
+        let heavy = "=".repeat(TEXT_WIDTH);
+        let light = "-".repeat(TEXT_WIDTH);
+        let mut out = String::new();
+
+        // Writing to a String can't fail
+        let _ = writeln!(out, "Nutrition Facts");
+        let _ = writeln!(out, "{}", self.name);
+        let _ = writeln!(out, "{}", self.servings_line());
+        let _ = writeln!(out, "{}", heavy);
+        let _ = writeln!(out, "Amount per serving");
+        let _ = writeln!(
+            out,
+            "{:<width$}{:>5.0}",
+            "Calories",
+            facts.calories,
+            width = TEXT_WIDTH - 5
+        );
+        let _ = writeln!(out, "{}", light);
+        let _ = writeln!(out, "{:>width$}", "% Daily Value*", width = TEXT_WIDTH);
+        for (nutrient, label) in ROWS {
+            let amount = format!("{} {:.0} g", label, facts.get(nutrient));
+            let _ = writeln!(
+                out,
+                "{:<width$}{:>4}%",
+                amount,
+                self.percent_daily_value(nutrient),
+                width = TEXT_WIDTH - 5
+            );
+        }
+        let _ = writeln!(out, "{}", light);
+        if !self.nutrition.missing.is_empty() {
+            let _ = writeln!(out, "Not counted: {}", self.nutrition.missing.join(", "));
+        }
+        let _ = writeln!(out, "{}", FOOTNOTE);
+
+        out
+    }
+
+    /// HTML label with inline styles, ready to drop into a page
+    pub fn to_html(&self) -> String {
+        let facts = &self.nutrition.per_serving;
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            r#"<section class="nutrition-label" style="border:1px solid #000;padding:4px 8px;width:18em;font-family:sans-serif">"#
+        );
+        let _ = writeln!(
+            out,
+            r#"<h2 style="margin:0;font-size:1.8em">Nutrition Facts</h2>"#
+        );
+        let _ = writeln!(out, "<p style=\"margin:0\">{}</p>", escape(self.name));
+        let _ = writeln!(
+            out,
+            "<p style=\"margin:0;border-bottom:8px solid #000\">{}</p>",
+            self.servings_line()
+        );
+        let _ = writeln!(
+            out,
+            "<p style=\"margin:0;font-weight:bold\">Amount per serving</p>"
+        );
+        let _ = writeln!(
+            out,
+            "<p style=\"margin:0;font-size:1.5em;font-weight:bold;border-bottom:4px solid #000\">Calories <span style=\"float:right\">{:.0}</span></p>",
+            facts.calories
+        );
+        let _ = writeln!(out, "<table style=\"width:100%;border-collapse:collapse\">");
+        let _ = writeln!(
+            out,
+            "<tr><th></th><th style=\"text-align:right\">% Daily Value*</th></tr>"
+        );
+        for (nutrient, label) in ROWS {
+            let _ = writeln!(
+                out,
+                "<tr><td><b>{}</b> {:.0} g</td><td style=\"text-align:right\">{:.0}%</td></tr>",
+                label,
+                facts.get(nutrient),
+                self.percent_daily_value(nutrient)
+            );
+        }
+        let _ = writeln!(out, "</table>");
+        if !self.nutrition.missing.is_empty() {
+            let missing: Vec<String> = self.nutrition.missing.iter().map(|m| escape(m)).collect();
+            let _ = writeln!(
+                out,
+                "<p style=\"margin:0;font-size:0.8em\">Not counted: {}</p>",
+                missing.join(", ")
+            );
+        }
+        let _ = writeln!(
+            out,
+            "<p style=\"margin:0;font-size:0.8em\">{}</p>",
+            FOOTNOTE
+        );
+        let _ = writeln!(out, "</section>");
+
+        out
+    }
+}
+
+/// Escape text for use inside HTML elements
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::{NutritionFacts, Visibility};
+
+    fn recipe(name: &str, missing: Vec<String>) -> Recipe {
+        Recipe {
+            id: 1,
+            name: name.to_string(),
+            instructions: None,
+            servings: Some(4),
+            ingredients: Vec::new(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: Some(RecipeNutrition {
+                servings: 4,
+                per_serving: NutritionFacts {
+                    calories: 352.4,
+                    protein_g: 10.0,
+                    carbohydrates_g: 55.0,
+                    fat_g: 11.7,
+                },
+                missing,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_text_label() {
+        let recipe = recipe("Pancakes", Vec::new());
+        let label = NutritionLabel::for_recipe(&recipe).unwrap().to_text();
+        let lines: Vec<&str> = label.lines().collect();
+
+        assert_eq!(lines[0], "Nutrition Facts");
+        assert_eq!(lines[1], "Pancakes");
+        assert_eq!(lines[2], "4 servings per recipe");
+        assert_eq!(lines[5], "Calories                     352");
+        assert_eq!(lines[8], "Total Fat 12 g               15%");
+        assert_eq!(lines[9], "Total Carbohydrate 55 g      20%");
+        assert_eq!(lines[10], "Protein 10 g                 20%");
+        assert!(!label.contains("Not counted"));
+        assert!(
+            lines[3..11]
+                .iter()
+                .all(|line| line.len() == TEXT_WIDTH || line.starts_with("Amount"))
+        );
+    }
+
+    #[test]
+    fn test_html_label_escapes_text() {
+        let recipe = recipe("Mac & Cheese <deluxe>", vec!["salt & pepper".to_string()]);
+        let label = NutritionLabel::for_recipe(&recipe).unwrap().to_html();
+
+        assert!(label.contains("Mac &amp; Cheese &lt;deluxe&gt;"));
+        assert!(label.contains("Not counted: salt &amp; pepper"));
+        assert!(label.contains("<b>Protein</b> 10 g"));
+        assert!(label.trim_end().ends_with("</section>"));
+    }
+
+    #[test]
+    fn test_label_needs_nutrition() {
+        let recipe = Recipe {
+            nutrition: None,
+            ..recipe("Toast", Vec::new())
+        };
+
+        assert!(NutritionLabel::for_recipe(&recipe).is_none());
+    }
+}
@@ -1,4 +1,36 @@
+#[cfg(feature = "barcode")]
+pub mod barcode;
+pub mod cache;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod config;
 pub mod controllers;
+pub mod db;
+pub mod diff;
+pub mod digest;
 pub mod error;
+pub mod i18n;
+pub mod label;
+#[cfg(feature = "cli")]
+pub mod logging;
 pub mod models;
+pub mod notify;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod prelude;
+pub mod quantity;
+pub mod seed;
+pub mod service;
+pub mod site;
+pub mod store;
+pub mod temperature;
+pub mod timers;
+#[cfg(feature = "tui")]
 pub mod tui;
+pub mod units;
+#[cfg(feature = "usda")]
+pub mod usda;
+pub mod validation;
+pub mod web_import;
+
+pub use service::FeedMe;
@@ -0,0 +1,183 @@
+use clap::Args;
+use std::fs::{File, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Shared `-v`/`-q` flags for the binaries
+///
+/// Nothing is logged by default; failures still reach the user through the
+/// binary's error report. Each `-v` raises the level one step (info → debug →
+/// trace) and `-q` keeps it off even when `RUST_LOG` is set. Without either
+/// flag, `RUST_LOG` directives are used when set.
+#[derive(Debug, Clone, Copy, Default, Args)]
+pub struct Verbosity {
+    /// Increase log output (-v status, -vv debug incl. SQL timings, -vvv trace)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Suppress all log output
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+}
+
+impl Verbosity {
+    /// The maximum level that should be emitted for these flags
+    pub fn level_filter(&self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::OFF;
+        }
+
+        match self.verbose {
+            0 => LevelFilter::OFF,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+
+    /// Which events to emit: `rust_log` (the `RUST_LOG` value) when no flag
+    /// was given and it parses, otherwise `level_filter` for everything
+    pub fn env_filter(&self, rust_log: Option<&str>) -> EnvFilter {
+        if !self.quiet
+            && self.verbose == 0
+            && let Some(directives) = rust_log
+            && let Ok(filter) = EnvFilter::try_new(directives)
+        {
+            return filter;
+        }
+
+        EnvFilter::default().add_directive(self.level_filter().into())
+    }
+}
+
+/// Install a global tracing subscriber writing to stderr, or to the file
+/// given to [`redirect_to_file`] while one is active
+///
+/// sqlx reports executed statements and their timings under the
+/// `sqlx::query` target at debug level, so `-vv` surfaces them.
+pub fn init_tracing(verbosity: Verbosity) {
+    let rust_log = std::env::var("RUST_LOG").ok();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(verbosity.env_filter(rust_log.as_deref()))
+        .with_writer(LogWriter)
+        .with_ansi(io::stderr().is_terminal())
+        .without_time()
+        .with_target(verbosity.verbose > 1)
+        .init();
+}
+
+/// Open log file, set while a full-screen TUI owns the terminal
+static LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+/// Routes log lines to stderr, or to `LOG_FILE` when it is set
+struct LogWriter;
+
+impl<'a> MakeWriter<'a> for LogWriter {
+    type Writer = LogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogWriter
+    }
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match LOG_FILE.lock().unwrap().as_mut() {
+            Some(file) => file.write(buf),
+            None => io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match LOG_FILE.lock().unwrap().as_mut() {
+            Some(file) => file.flush(),
+            None => io::stderr().flush(),
+        }
+    }
+}
+
+/// Where a TUI binary writes its log: `$FEEDME_LOG_FILE`, or `<name>.log` in
+/// the temp directory
+pub fn tui_log_path(name: &str) -> PathBuf {
+    match std::env::var_os("FEEDME_LOG_FILE") {
+        Some(path) => PathBuf::from(path),
+        None => std::env::temp_dir().join(format!("{}.log", name)),
+    }
+}
+
+/// Append log lines to `path` instead of stderr until the guard is dropped,
+/// so they don't draw over a TUI's alternate screen
+pub fn redirect_to_file(path: &Path) -> io::Result<FileRedirect> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    *LOG_FILE.lock().unwrap() = Some(file);
+
+    Ok(FileRedirect)
+}
+
+/// Restores logging to stderr when dropped
+#[must_use = "logging returns to stderr as soon as this is dropped"]
+pub struct FileRedirect;
+
+impl Drop for FileRedirect {
+    fn drop(&mut self) {
+        LOG_FILE.lock().unwrap().take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, false, LevelFilter::OFF)]
+    #[case(1, false, LevelFilter::INFO)]
+    #[case(2, false, LevelFilter::DEBUG)]
+    #[case(3, false, LevelFilter::TRACE)]
+    #[case(7, false, LevelFilter::TRACE)]
+    #[case(0, true, LevelFilter::OFF)]
+    fn test_level_filter(#[case] verbose: u8, #[case] quiet: bool, #[case] expected: LevelFilter) {
+        let verbosity = Verbosity { verbose, quiet };
+
+        assert_eq!(verbosity.level_filter(), expected);
+    }
+
+    #[rstest]
+    #[case(0, false, Some("sqlx=debug"), "sqlx=debug")]
+    #[case(0, false, None, "off")]
+    #[case(1, false, Some("sqlx=debug"), "info")]
+    #[case(2, false, None, "debug")]
+    #[case(0, true, Some("sqlx=debug"), "off")]
+    fn test_env_filter(
+        #[case] verbose: u8,
+        #[case] quiet: bool,
+        #[case] rust_log: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let verbosity = Verbosity { verbose, quiet };
+
+        assert_eq!(verbosity.env_filter(rust_log).to_string(), expected);
+    }
+
+    #[test]
+    fn test_redirect_to_file() {
+        let path = std::env::temp_dir().join(format!("feedme-log-test-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let redirect = redirect_to_file(&path).unwrap();
+        LogWriter.write_all(b"while the TUI is up\n").unwrap();
+        drop(redirect);
+        assert!(LOG_FILE.lock().unwrap().is_none());
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "while the TUI is up\n"
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
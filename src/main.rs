@@ -1,32 +1,1226 @@
-mod controllers;
-mod error;
-mod models;
+use chrono::{Days, NaiveDate};
+use clap::{Parser, Subcommand};
+use feedme::cli::{self, Context, DatabaseArgs, Report};
+use feedme::config::Config;
+use feedme::controllers::{
+    add_price, add_recipe_note, add_to_collection, almost_cookable_recipes, analytics,
+    attach_recipe_image, clone_recipe, collection_shopping_list, create_collection,
+    data_quality_report, delete_collection, delete_recipe, estimate_recipe_cost, export_all_with,
+    export_pack, get_all_ingredients, get_collection, get_recipe_image, get_recipe_with,
+    get_recipes_by_equipment, get_recipes_by_tag_with, import_all, import_pack, list_collections,
+    list_deleted_recipes, list_equipment, list_recipe_images, list_recipes_by_rating, list_tags,
+    log_cook, merge_tags, nutrition_report, pack_conflicts, plan_shopping_list, purge_deleted,
+    rate_recipe, reminders_for, remove_from_collection, remove_recipe_image, rename_tag,
+    restore_recipe, save_shopping_list, set_favorite, set_ingredient_aisle, set_ingredient_icon,
+    set_recipe_category, set_recipe_difficulty, set_recipe_equipment, set_recipe_visibility,
+    set_step_ingredients, shared_recipes, similar_recipes, suggest_recipes, sync_shopping_list,
+    tag_recipes, untag_recipes, upcoming_meal_plan, weekly_digest_with,
+};
+use feedme::db;
+use feedme::digest;
+use feedme::error::FeedMeError;
+use feedme::i18n;
+use feedme::label::NutritionLabel;
+use feedme::logging::{Verbosity, init_tracing};
+use feedme::models::IngredientRecord;
+use feedme::models::api::{
+    Category, ConflictStrategy, Difficulty, IngredientPrice, RecipeFilter, RecipeNote,
+    RecipeOptions, RecipePack, ShoppingCheckOff, SuggestionCriteria, Visibility,
+};
+use feedme::notify;
+use feedme::quantity::parse_quantity;
+use feedme::site;
+use feedme::t;
+use feedme::web_import;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 
-use sqlx::migrate::MigrateDatabase;
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+/// Manage the FeedMe database
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(flatten)]
+    verbosity: Verbosity,
+
+    #[command(flatten)]
+    database: DatabaseArgs,
+
+    /// Only show recipes shared at least this widely: household hides
+    /// private recipes, public shows only public ones (applies to list,
+    /// dinner, digest and tag recipes)
+    #[arg(long, global = true)]
+    audience: Option<Visibility>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the database and run migrations (the default)
+    Init,
+    /// Write every recipe as JSON Lines, streaming rows from the database
+    Export {
+        /// File to write to (defaults to stdout)
+        output: Option<PathBuf>,
+    },
+    /// Add the recipes from a file written by `export`
+    Import { file: PathBuf },
+    /// Import the recipes on a list of web pages (needs the `web-import` feature)
+    ImportUrls {
+        /// One URL per line, or a browser's exported bookmarks file
+        file: PathBuf,
+
+        /// Pages fetched at the same time
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Seconds between two requests to the same site
+        #[arg(long, default_value_t = 1.0)]
+        delay: f64,
+    },
+    /// Render every recipe but the private ones as a static HTML site
+    Site {
+        /// Directory to write the pages to (created if missing)
+        dir: PathBuf,
+
+        /// Heading of the index page
+        #[arg(long, default_value = "Cookbook")]
+        title: String,
+    },
+    /// Print a recipe with its journal
+    Show {
+        recipe_id: i64,
+
+        /// Color, bold headers and aligned quantities
+        #[cfg(feature = "tui")]
+        #[arg(long)]
+        pretty: bool,
+    },
+    /// Record cooking a recipe, optionally with a journal entry
+    Cooked {
+        recipe_id: i64,
+
+        /// Day it was cooked (defaults to today)
+        #[arg(long)]
+        on: Option<NaiveDate>,
+
+        /// Journal entry, e.g. "use less salt next time"
+        #[arg(long)]
+        note: Option<String>,
+
+        /// What was done differently this time
+        #[arg(long, requires = "note")]
+        changed: Option<String>,
+
+        /// Stars better (positive) or worse than usual
+        #[arg(long, requires = "note", allow_hyphen_values = true)]
+        rating_delta: Option<i64>,
+    },
+    /// Suggest what to cook today
+    Dinner {
+        /// Only recipes that take at most this many minutes
+        #[arg(long)]
+        max_minutes: Option<i64>,
+
+        /// Prefer recipes with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Skip recipes cooked within this many days (overrides
+        /// suggestions.skip_cooked_within_days)
+        #[arg(long)]
+        skip_recent: Option<u64>,
+
+        /// Number of suggestions
+        #[arg(long, default_value_t = 3)]
+        limit: usize,
+    },
+    /// List recipes that share ingredients or tags with a recipe
+    Similar {
+        recipe_id: i64,
+
+        /// Number of recipes to list
+        #[arg(long, default_value_t = 5)]
+        limit: usize,
+    },
+    /// Send reminders for upcoming planned meals (run daily from cron)
+    Notify {
+        /// Meal date to remind about (defaults to today plus notify.days_ahead)
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+    /// Render the upcoming meal plan and its shopping list as an HTML digest (run weekly from cron)
+    Digest {
+        /// Plan to render (defaults to the one with the next planned meal)
+        #[arg(long)]
+        plan: Option<i64>,
+
+        /// File to write the HTML to (defaults to stdout)
+        #[arg(long, short, conflicts_with = "email")]
+        output: Option<PathBuf>,
+
+        /// Email it to digest.to instead; requires the `email` feature
+        #[arg(long)]
+        email: bool,
+    },
+    /// Summarize nutrition of planned and cooked meals against the [nutrition] targets
+    Report {
+        /// First day (defaults to six days before --end)
+        #[arg(long)]
+        start: Option<NaiveDate>,
+
+        /// Last day (defaults to today)
+        #[arg(long)]
+        end: Option<NaiveDate>,
+    },
+    /// List recipes, best rated first
+    List {
+        /// Only recipes rated at least this on average
+        #[arg(long)]
+        min_rating: Option<f64>,
+
+        /// Only recipes for these meals (repeatable)
+        #[arg(long = "category")]
+        categories: Vec<Category>,
+
+        /// Only recipes at most this demanding (easy, medium or hard)
+        #[arg(long)]
+        max_difficulty: Option<Difficulty>,
+
+        /// Only favorite recipes
+        #[arg(long)]
+        favorites: bool,
+
+        /// Leave out recipes needing this equipment (repeatable)
+        #[arg(long = "without")]
+        without_equipment: Vec<String>,
+    },
+    /// Copy a recipe under a new name, to start a variation; prints the copy's ID
+    Clone { recipe_id: i64, name: String },
+    /// Move a recipe to the trash; `feedme restore` brings it back
+    Delete { recipe_id: i64 },
+    /// Take a recipe back out of the trash
+    Restore { recipe_id: i64 },
+    /// List the recipes in the trash, most recently deleted first
+    Trash {
+        /// Delete them for good instead
+        #[arg(long)]
+        empty: bool,
+    },
+    /// Star a recipe as a favorite
+    Favorite {
+        recipe_id: i64,
+
+        /// Unstar it instead
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Say which ingredients go into a step, for cooking mode
+    Step {
+        recipe_id: i64,
+
+        /// Step number, from 1
+        #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+        step: u64,
+
+        /// Ingredient numbers, from 1 in recipe order (none unlinks the step)
+        #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+        ingredients: Vec<u64>,
+    },
+    /// Rate a recipe from 1 to 5
+    Rate {
+        recipe_id: i64,
+
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=5))]
+        rating: u8,
+    },
+    /// Show the most used ingredients and how much was cooked each month
+    Stats {
+        /// First day (defaults to a year before --end)
+        #[arg(long)]
+        start: Option<NaiveDate>,
+
+        /// Last day (defaults to today)
+        #[arg(long)]
+        end: Option<NaiveDate>,
+
+        /// Number of ingredients to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// List recipes missing instructions, servings or ingredients, or with
+    /// unreadable quantities
+    Check,
+    /// List recipes the pantry almost covers, with what to buy for each
+    AlmostCookable {
+        /// Most ingredients a recipe may be missing
+        #[arg(long, default_value_t = 2)]
+        max_missing: usize,
+    },
+    /// Print a nutrition facts label for a recipe
+    Label {
+        recipe_id: i64,
+
+        /// Print an HTML block instead of text
+        #[arg(long)]
+        html: bool,
+    },
+    /// Record what an ingredient cost
+    Price {
+        ingredient: String,
+        price: f64,
+
+        /// Quantity the price is for, e.g. "1 kg" or "6"
+        #[arg(long, default_value = "1")]
+        per: String,
+
+        #[arg(long)]
+        store: Option<String>,
+
+        /// Day of purchase (defaults to today)
+        #[arg(long)]
+        on: Option<NaiveDate>,
+    },
+    /// Estimate what a recipe costs at the latest recorded prices
+    Cost {
+        recipe_id: i64,
+
+        /// Scale to this many servings
+        #[arg(long)]
+        servings: Option<i64>,
+    },
+    /// Print the shopping list for a meal plan, ordered by a store's aisles
+    Shop {
+        plan_id: i64,
+
+        /// Store layout from [shopping.stores] (defaults to shopping.default_store)
+        #[arg(long)]
+        store: Option<String>,
+
+        /// Save the list and print its ID, for checking items off with sync-shopping
+        #[arg(long)]
+        save: bool,
+
+        /// Print the list as JSON, e.g. for a phone
+        #[arg(long)]
+        json: bool,
+    },
+    /// Apply check-offs posted back from a phone: JSON like
+    /// {"list": 7, "bought": ["flour"], "purchased_on": "2026-10-17"}
+    /// Marks the items bought and adds them to the pantry; prints what changed as JSON
+    SyncShopping {
+        /// File to read (defaults to stdin)
+        file: Option<PathBuf>,
+    },
+    /// Set the store aisle an ingredient is found in
+    Aisle {
+        ingredient: String,
+
+        /// Aisle name, e.g. "produce" (omit to clear it)
+        aisle: Option<String>,
+    },
+    /// Set the emoji shown next to an ingredient in lists
+    Icon {
+        ingredient: String,
+
+        /// Emoji, e.g. "🥕" (omit to clear it)
+        icon: Option<String>,
+    },
+    /// Choose who a recipe is shown to
+    Visibility {
+        recipe_id: i64,
+
+        /// private, household or public
+        visibility: Visibility,
+    },
+    /// Set which meal a recipe is for
+    Category {
+        recipe_id: i64,
+
+        /// breakfast, lunch, dinner, dessert or snack (omit to clear it)
+        category: Option<Category>,
+    },
+    /// Set how demanding a recipe is
+    Difficulty {
+        recipe_id: i64,
+
+        /// easy, medium or hard (omit to clear it)
+        difficulty: Option<Difficulty>,
+    },
+    /// Trade recipes with other households as single files
+    Pack {
+        #[command(subcommand)]
+        command: PackCommand,
+    },
+    /// Tag, untag, rename and merge tags across many recipes
+    Tag {
+        #[command(subcommand)]
+        command: TagCommand,
+    },
+    /// Record and look up the equipment recipes need
+    Equipment {
+        #[command(subcommand)]
+        command: EquipmentCommand,
+    },
+    /// Attach photos to recipes and save them back out
+    Image {
+        #[command(subcommand)]
+        command: ImageCommand,
+    },
+    /// Group recipes into named collections
+    Collection {
+        #[command(subcommand)]
+        command: CollectionCommand,
+    },
+    /// Import ingredient nutrition from USDA FoodData Central
+    #[cfg(feature = "usda")]
+    FetchNutrition {
+        /// Ingredients to look up (defaults to every ingredient)
+        ingredients: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PackCommand {
+    /// Write recipes and the ingredients they use to a pack file
+    Export {
+        #[arg(required = true)]
+        recipe_ids: Vec<i64>,
+
+        /// File to write to (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Add the recipes in a pack file
+    Import {
+        file: PathBuf,
+
+        /// What to do with recipes named like existing ones: skip, rename or merge
+        #[arg(long, default_value = "skip")]
+        on_conflict: ConflictStrategy,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagCommand {
+    /// List every tag in use
+    List,
+    /// List the recipes with a tag
+    Recipes { tag: String },
+    /// Tag recipes, creating the tag if needed
+    Add {
+        tag: String,
+
+        #[arg(required = true)]
+        recipe_ids: Vec<i64>,
+    },
+    /// Take a tag off recipes
+    Remove {
+        tag: String,
+
+        #[arg(required = true)]
+        recipe_ids: Vec<i64>,
+    },
+    /// Rename a tag everywhere it's used
+    Rename { tag: String, new_name: String },
+    /// Fold tags into another, deleting them
+    Merge {
+        #[arg(required = true)]
+        tags: Vec<String>,
+
+        #[arg(long)]
+        into: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum EquipmentCommand {
+    /// List every item some recipe needs
+    List,
+    /// List the recipes needing an item
+    Recipes { item: String },
+    /// Replace the equipment a recipe needs (none clears it)
+    Set { recipe_id: i64, items: Vec<String> },
+}
+
+#[derive(Subcommand)]
+enum ImageCommand {
+    /// Attach a PNG, JPEG, GIF or WebP file to a recipe and print its ID
+    Add { recipe_id: i64, file: PathBuf },
+    /// List a recipe's images
+    List { recipe_id: i64 },
+    /// Write an image to a file
+    Save { image_id: i64, file: PathBuf },
+    /// Remove an image from its recipe
+    Remove { image_id: i64 },
+}
+
+#[derive(Subcommand)]
+enum CollectionCommand {
+    /// Create an empty collection and print its ID
+    New { name: String },
+    /// List every collection
+    List,
+    /// Show the recipes in a collection
+    Show { collection_id: i64 },
+    /// Add recipes to a collection
+    Add {
+        collection_id: i64,
+
+        #[arg(required = true)]
+        recipe_ids: Vec<i64>,
+    },
+    /// Take a recipe out of a collection
+    Remove { collection_id: i64, recipe_id: i64 },
+    /// Delete a collection, keeping its recipes
+    Delete { collection_id: i64 },
+    /// Print one shopping list for every recipe in a collection
+    Shop { collection_id: i64 },
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Database URL - in production, you'd use an environment variable
-    let database_url = "sqlite://feedme.db";
-
-    // Create database if it doesn't exist
-    if !sqlx::Sqlite::database_exists(database_url).await? {
-        println!("Creating database {}", database_url);
-        sqlx::Sqlite::create_database(database_url).await?;
-    }
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing(cli.verbosity);
+
+    cli::exit_status(run(cli).await)
+}
+
+async fn run(cli: Cli) -> Result<(), Report> {
+    let config = Config::load().context("loading the configuration")?;
+    i18n::set_locale(config.display.language);
+
+    match cli.command.unwrap_or(Command::Init) {
+        Command::Init => {
+            let database = cli.database.describe(&config.database);
+            tracing::info!(%database, "setting up database");
+            let (pool, seeded) = cli.database.connect(&config.database).await?;
+
+            for row in &db::unparsed_quantities(&pool).await? {
+                tracing::warn!(
+                    recipe_id = row.recipe_id,
+                    quantity_unit = %row.quantity_unit,
+                    "quantity could not be parsed into amount/unit"
+                );
+            }
+
+            if cli.database.demo {
+                tracing::info!(recipes = seeded.len(), "added demo recipes");
+            }
+
+            tracing::info!("database setup complete");
+        }
+        Command::Export { output } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let count = match output {
+                Some(path) => {
+                    let file =
+                        File::create(&path).context(format!("creating {}", path.display()))?;
+                    let writer = BufWriter::new(file);
+                    export_all_with(&pool, writer, config.display.quantity_format()).await?
+                }
+                None => {
+                    let writer = BufWriter::new(io::stdout().lock());
+                    export_all_with(&pool, writer, config.display.quantity_format()).await?
+                }
+            };
+            tracing::info!(count, "exported recipes");
+        }
+        Command::Import { file } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let reader =
+                BufReader::new(File::open(&file).context(format!("reading {}", file.display()))?);
+            let count = import_all(&pool, reader).await?;
+            tracing::info!(count, "imported recipes");
+        }
+        Command::ImportUrls {
+            file,
+            concurrency,
+            delay,
+        } => {
+            let text =
+                std::fs::read_to_string(&file).context(format!("reading {}", file.display()))?;
+            let urls = web_import::read_url_list(&text);
+            let limits = web_import::ImportLimits {
+                concurrency,
+                per_domain_delay: Duration::from_secs_f64(delay.max(0.0)),
+            };
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let report =
+                web_import::import_urls(&pool, &urls, limits, web_import::fetch_page).await?;
+            print!("{}", report);
+            tracing::info!(
+                imported = report.imported.len(),
+                skipped = report.skipped.len(),
+                failed = report.failed.len(),
+                "imported recipe pages"
+            );
+        }
+        Command::Site { dir, title } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let recipes = shared_recipes(&pool, config.display.quantity_format()).await?;
+            let pages = site::render(&title, &recipes);
+            site::write(&dir, &pages).context(format!("writing {}", dir.display()))?;
+            tracing::info!(recipes = recipes.len(), pages = pages.len(), dir = %dir.display(), "wrote site");
+        }
+        Command::Show {
+            recipe_id,
+            #[cfg(feature = "tui")]
+            pretty,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let options = RecipeOptions {
+                include_journal: true,
+                quantities: config.display.quantity_format(),
+                ..Default::default()
+            };
+            let recipe = get_recipe_with(&pool, recipe_id, options).await?;
+            #[cfg(feature = "tui")]
+            if pretty {
+                use feedme::tui::print::{recipe_lines, to_ansi};
+
+                print!("{}", to_ansi(&recipe_lines(&recipe)));
+                return Ok(());
+            }
+            print!("{}", recipe);
+        }
+        Command::Cooked {
+            recipe_id,
+            on,
+            note,
+            changed,
+            rating_delta,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let cooked_on = on.unwrap_or_else(|| chrono::Local::now().date_naive());
+            let cook_log_id = log_cook(&pool, recipe_id, cooked_on, None).await?;
+            if let Some(text) = note {
+                let note = RecipeNote {
+                    id: 0,
+                    cook_log_id,
+                    noted_on: chrono::Local::now().date_naive(),
+                    text,
+                    rating_delta,
+                    modifications: changed,
+                };
+                add_recipe_note(&pool, &note).await?;
+            }
+            tracing::info!(recipe_id, %cooked_on, "logged cook");
+        }
+        Command::Dinner {
+            max_minutes,
+            tags,
+            skip_recent,
+            limit,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let mut criteria = SuggestionCriteria::new(chrono::Local::now().date_naive());
+            criteria.max_minutes = max_minutes;
+            criteria.preferred_tags = tags;
+            criteria.skip_cooked_within_days =
+                skip_recent.or(config.suggestions.skip_cooked_within_days);
+            criteria.limit = limit;
+            criteria.audience = cli.audience;
+
+            let suggestions = suggest_recipes(&pool, &criteria).await?;
+            if suggestions.is_empty() {
+                println!("{}", t!("dinner-none"));
+            }
+            for suggestion in suggestions {
+                print!("{:>4}  {}", suggestion.recipe_id, suggestion.name);
+                if let Some(minutes) = suggestion.total_minutes {
+                    print!(" {}", t!("dinner-minutes", minutes = minutes));
+                }
+                match suggestion.last_used {
+                    Some(date) => println!(" {}", t!("dinner-last-made", date = date)),
+                    None => println!(" {}", t!("dinner-never-made")),
+                }
+            }
+        }
+        Command::Similar { recipe_id, limit } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
 
-    // Create connection pool
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(database_url)
-        .await?;
+            let similar = similar_recipes(&pool, recipe_id, limit).await?;
+            if similar.is_empty() {
+                println!("{}", t!("similar-none"));
+            }
+            for recipe in similar {
+                let shared: Vec<String> = recipe
+                    .shared_ingredients
+                    .into_iter()
+                    .chain(
+                        recipe
+                            .shared_tags
+                            .into_iter()
+                            .map(|tag| format!("#{}", tag)),
+                    )
+                    .collect();
+                println!(
+                    "{:>4}  {} {}",
+                    recipe.recipe_id,
+                    recipe.name,
+                    t!("similar-shares", shared = shared.join(", "))
+                );
+            }
+        }
+        Command::Notify { date } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
 
-    // Run migrations
-    println!("Running migrations...");
-    sqlx::migrate!("./migrations").run(&pool).await?;
+            let date = date.unwrap_or_else(|| {
+                chrono::Local::now().date_naive() + Days::new(config.notify.days_ahead)
+            });
+            let reminders = reminders_for(&pool, date).await?;
+            for reminder in &reminders {
+                println!("{}", reminder);
+            }
 
-    println!("Database setup complete!");
+            let channels = notify::deliver(&config.notify, &reminders)?;
+            tracing::info!(count = reminders.len(), channels, %date, "sent reminders");
+        }
+        Command::Digest {
+            plan,
+            output,
+            email,
+        } => {
+            let layout = config.shopping.layout(None)?;
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let today = chrono::Local::now().date_naive();
+            let plan_id = match plan {
+                Some(plan_id) => plan_id,
+                None => match upcoming_meal_plan(&pool, today).await? {
+                    Some(plan_id) => plan_id,
+                    None => {
+                        println!("{}", t!("digest-nothing-planned", date = today));
+                        return Ok(());
+                    }
+                },
+            };
+
+            let filter = RecipeFilter {
+                audience: cli.audience,
+                ..Default::default()
+            };
+            let mut digest = weekly_digest_with(&pool, plan_id, &filter).await?;
+            if let Some(layout) = &layout {
+                digest.shopping_list.arrange(layout);
+            }
+            digest
+                .shopping_list
+                .localize_quantities(config.display.quantity_format());
+
+            if email {
+                digest::send(&config.digest, &digest)?;
+                tracing::info!(
+                    plan_id,
+                    recipients = config.digest.to.len(),
+                    "emailed digest"
+                );
+            } else {
+                let html = digest::to_html(&digest);
+                match output {
+                    Some(path) => std::fs::write(&path, html)
+                        .context(format!("writing {}", path.display()))?,
+                    None => print!("{}", html),
+                }
+            }
+        }
+        Command::Report { start, end } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let end = end.unwrap_or_else(|| chrono::Local::now().date_naive());
+            let start = start.unwrap_or(end - Days::new(6));
+            let report = nutrition_report(&pool, start..=end, &config.nutrition).await?;
+            if report.days.is_empty() {
+                println!("{}", t!("report-empty", start = start, end = end));
+            } else {
+                print!("{}", report);
+            }
+        }
+        Command::List {
+            min_rating,
+            categories,
+            max_difficulty,
+            favorites,
+            without_equipment,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let filter = RecipeFilter {
+                min_rating,
+                categories,
+                max_difficulty,
+                favorites_only: favorites,
+                without_equipment,
+                audience: cli.audience,
+                ..Default::default()
+            };
+            for recipe in list_recipes_by_rating(&pool, &filter).await? {
+                println!("{}", recipe);
+            }
+        }
+        Command::Clone { recipe_id, name } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            println!("{}", clone_recipe(&pool, recipe_id, &name).await?);
+        }
+        Command::Delete { recipe_id } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            delete_recipe(&pool, recipe_id).await?;
+        }
+        Command::Restore { recipe_id } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            restore_recipe(&pool, recipe_id).await?;
+        }
+        Command::Trash { empty } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            if empty {
+                let purged = purge_deleted(&pool).await?;
+                tracing::info!(purged, "emptied the trash");
+            } else {
+                for recipe in list_deleted_recipes(&pool).await? {
+                    println!("{:>4}  {}", recipe.id, recipe.name);
+                }
+            }
+        }
+        Command::Favorite { recipe_id, remove } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            set_favorite(&pool, recipe_id, !remove).await?;
+        }
+        Command::Step {
+            recipe_id,
+            step,
+            ingredients,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            let ingredients: Vec<usize> = ingredients
+                .iter()
+                .map(|number| *number as usize - 1)
+                .collect();
+            set_step_ingredients(&pool, recipe_id, step as usize - 1, &ingredients).await?;
+        }
+        Command::Rate { recipe_id, rating } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            rate_recipe(&pool, recipe_id, rating).await?;
+        }
+        Command::Stats {
+            start,
+            end,
+            top,
+            json,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let end = end.unwrap_or_else(|| chrono::Local::now().date_naive());
+            let start = start.unwrap_or(end - Days::new(365));
+            let analytics = analytics(&pool, start..=end, top).await?;
+            if json {
+                serde_json::to_writer_pretty(io::stdout().lock(), &analytics)
+                    .map_err(FeedMeError::from)?;
+                println!();
+            } else {
+                print!("{}", analytics);
+            }
+        }
+        Command::Check => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            print!("{}", data_quality_report(&pool).await?);
+        }
+        Command::AlmostCookable { max_missing } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let recipes = almost_cookable_recipes(&pool, max_missing).await?;
+            if recipes.is_empty() {
+                println!("{}", t!("almost-cookable-none", max = max_missing));
+            }
+            for recipe in recipes {
+                print!("{}", recipe);
+            }
+        }
+        Command::Label { recipe_id, html } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let options = RecipeOptions {
+                include_nutrition: true,
+                ..Default::default()
+            };
+            let recipe = get_recipe_with(&pool, recipe_id, options).await?;
+            let label = NutritionLabel::for_recipe(&recipe).expect("nutrition was requested");
+            if html {
+                print!("{}", label.to_html());
+            } else {
+                print!("{}", label.to_text());
+            }
+        }
+        Command::Price {
+            ingredient,
+            price,
+            per,
+            store,
+            on,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let parsed =
+                parse_quantity(&per).ok_or_else(|| FeedMeError::InvalidQuantity(per.clone()))?;
+            let Some(record) = find_ingredient(&pool, &ingredient).await? else {
+                println!("{}", t!("unknown-ingredient", name = ingredient));
+                return Ok(());
+            };
+            let price = IngredientPrice {
+                id: 0,
+                ingredient_id: record.id,
+                ingredient_name: record.name,
+                price,
+                amount: parsed.amount,
+                unit: parsed.unit,
+                store,
+                observed_on: on.unwrap_or_else(|| chrono::Local::now().date_naive()),
+            };
+            add_price(&pool, &price).await?;
+            println!("{}", price);
+        }
+        Command::Cost {
+            recipe_id,
+            servings,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            print!(
+                "{}",
+                estimate_recipe_cost(&pool, recipe_id, servings).await?
+            );
+        }
+        Command::Shop {
+            plan_id,
+            store,
+            save,
+            json,
+        } => {
+            let layout = config.shopping.layout(store.as_deref())?;
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let mut list = plan_shopping_list(&pool, plan_id, &[]).await?;
+            if let Some(layout) = &layout {
+                list.arrange(layout);
+            }
+            if save {
+                list.id = save_shopping_list(&pool, &list).await?;
+            }
+            list.localize_quantities(config.display.quantity_format());
+            if json {
+                serde_json::to_writer_pretty(io::stdout().lock(), &list)
+                    .map_err(FeedMeError::from)?;
+                println!();
+            } else if save {
+                println!("{}", list.id);
+            } else {
+                print!("{}", list);
+            }
+        }
+        Command::SyncShopping { file } => {
+            let text = match &file {
+                Some(path) => {
+                    std::fs::read_to_string(path).context(format!("reading {}", path.display()))?
+                }
+                None => io::read_to_string(io::stdin()).context("reading stdin")?,
+            };
+            let check_off: ShoppingCheckOff =
+                serde_json::from_str(&text).map_err(FeedMeError::from)?;
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let sync = sync_shopping_list(&pool, &check_off).await?;
+            serde_json::to_writer_pretty(io::stdout().lock(), &sync).map_err(FeedMeError::from)?;
+            println!();
+            tracing::info!(
+                list = check_off.list,
+                checked_off = sync.checked_off.len(),
+                stocked = sync.stocked,
+                "synced shopping list"
+            );
+        }
+        Command::Aisle { ingredient, aisle } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let Some(record) = find_ingredient(&pool, &ingredient).await? else {
+                println!("{}", t!("unknown-ingredient", name = ingredient));
+                return Ok(());
+            };
+            set_ingredient_aisle(&pool, record.id, aisle.as_deref()).await?;
+        }
+        Command::Icon { ingredient, icon } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            let Some(record) = find_ingredient(&pool, &ingredient).await? else {
+                println!("{}", t!("unknown-ingredient", name = ingredient));
+                return Ok(());
+            };
+            set_ingredient_icon(&pool, record.id, icon.as_deref()).await?;
+        }
+        Command::Visibility {
+            recipe_id,
+            visibility,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            set_recipe_visibility(&pool, recipe_id, visibility).await?;
+        }
+        Command::Category {
+            recipe_id,
+            category,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            set_recipe_category(&pool, recipe_id, category).await?;
+        }
+        Command::Difficulty {
+            recipe_id,
+            difficulty,
+        } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            set_recipe_difficulty(&pool, recipe_id, difficulty).await?;
+        }
+        Command::Pack { command } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+            let key = config.sharing.pack_key();
+
+            match command {
+                PackCommand::Export { recipe_ids, output } => {
+                    let pack = export_pack(&pool, &recipe_ids, key).await?;
+                    match output {
+                        Some(path) => {
+                            let file = File::create(&path)
+                                .context(format!("creating {}", path.display()))?;
+                            serde_json::to_writer_pretty(BufWriter::new(file), &pack)
+                                .map_err(FeedMeError::from)?;
+                        }
+                        None => {
+                            serde_json::to_writer_pretty(io::stdout().lock(), &pack)
+                                .map_err(FeedMeError::from)?;
+                            println!();
+                        }
+                    }
+                    tracing::info!(recipes = pack.contents.recipes.len(), "exported pack");
+                }
+                PackCommand::Import { file, on_conflict } => {
+                    let text = std::fs::read_to_string(&file)
+                        .context(format!("reading {}", file.display()))?;
+                    let pack: RecipePack = serde_json::from_str(&text)
+                        .map_err(|err| FeedMeError::InvalidPack(err.to_string()))?;
+
+                    for conflict in pack_conflicts(&pool, &pack).await? {
+                        tracing::warn!(
+                            recipe = %conflict.name,
+                            existing_id = conflict.existing_id,
+                            strategy = ?on_conflict,
+                            "recipe name already taken"
+                        );
+                        eprint!("{}", conflict.changes);
+                    }
+
+                    let import = import_pack(&pool, &pack, on_conflict, key).await?;
+                    for (from, to) in &import.renamed {
+                        tracing::info!(recipe = %from, renamed = %to, "renamed recipe");
+                    }
+                    for (recipe, warnings) in &import.recipe_warnings {
+                        println!("Check {}:", recipe);
+                        for warning in warnings {
+                            println!("  {}", warning);
+                        }
+                    }
+                    tracing::info!(
+                        created = import.created.len(),
+                        merged = import.merged.len(),
+                        skipped = import.skipped.len(),
+                        new_ingredients = import.new_ingredients.len(),
+                        "imported pack"
+                    );
+                }
+            }
+        }
+        Command::Equipment { command } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            match command {
+                EquipmentCommand::List => {
+                    for item in list_equipment(&pool).await? {
+                        println!("{}", item);
+                    }
+                }
+                EquipmentCommand::Recipes { item } => {
+                    for recipe in get_recipes_by_equipment(&pool, &item).await? {
+                        println!("{:>4}  {}", recipe.id, recipe.name);
+                    }
+                }
+                EquipmentCommand::Set { recipe_id, items } => {
+                    set_recipe_equipment(&pool, recipe_id, &items).await?;
+                }
+            }
+        }
+        Command::Image { command } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            match command {
+                ImageCommand::Add { recipe_id, file } => {
+                    let data =
+                        std::fs::read(&file).context(format!("reading {}", file.display()))?;
+                    let file_name = file
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    println!(
+                        "{}",
+                        attach_recipe_image(&pool, recipe_id, &file_name, &data).await?
+                    );
+                }
+                ImageCommand::List { recipe_id } => {
+                    for image in list_recipe_images(&pool, recipe_id).await? {
+                        println!(
+                            "{:>4}  {}  {}, {} KB",
+                            image.id,
+                            image.file_name,
+                            image.content_type,
+                            (image.size + 1023) / 1024
+                        );
+                    }
+                }
+                ImageCommand::Save { image_id, file } => {
+                    let (_, data) = get_recipe_image(&pool, image_id).await?;
+                    std::fs::write(&file, data).context(format!("writing {}", file.display()))?;
+                }
+                ImageCommand::Remove { image_id } => {
+                    if !remove_recipe_image(&pool, image_id).await? {
+                        return Err(FeedMeError::ImageNotFound(image_id).into());
+                    }
+                }
+            }
+        }
+        Command::Tag { command } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            match command {
+                TagCommand::List => {
+                    for tag in list_tags(&pool).await? {
+                        println!("{}", tag);
+                    }
+                }
+                TagCommand::Recipes { tag } => {
+                    let filter = RecipeFilter {
+                        audience: cli.audience,
+                        ..Default::default()
+                    };
+                    for recipe in get_recipes_by_tag_with(&pool, &tag, &filter).await? {
+                        println!("{:>4}  {}", recipe.id, recipe.name);
+                    }
+                }
+                TagCommand::Add { tag, recipe_ids } => {
+                    let tagged = tag_recipes(&pool, &tag, &recipe_ids).await?;
+                    tracing::info!(%tag, tagged, "tagged recipes");
+                }
+                TagCommand::Remove { tag, recipe_ids } => {
+                    let untagged = untag_recipes(&pool, &tag, &recipe_ids).await?;
+                    tracing::info!(%tag, untagged, "untagged recipes");
+                }
+                TagCommand::Rename { tag, new_name } => {
+                    rename_tag(&pool, &tag, &new_name).await?;
+                }
+                TagCommand::Merge { tags, into } => {
+                    let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+                    let retagged = merge_tags(&pool, &tags, &into).await?;
+                    tracing::info!(%into, merged = tags.len(), retagged, "merged tags");
+                }
+            }
+        }
+        Command::Collection { command } => {
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            match command {
+                CollectionCommand::New { name } => {
+                    println!("{}", create_collection(&pool, &name).await?);
+                }
+                CollectionCommand::List => {
+                    for collection in list_collections(&pool).await? {
+                        println!("{:>4}  {}", collection.id, collection.name);
+                    }
+                }
+                CollectionCommand::Show { collection_id } => {
+                    print!("{}", get_collection(&pool, collection_id).await?);
+                }
+                CollectionCommand::Add {
+                    collection_id,
+                    recipe_ids,
+                } => {
+                    for recipe_id in recipe_ids {
+                        add_to_collection(&pool, collection_id, recipe_id).await?;
+                    }
+                }
+                CollectionCommand::Remove {
+                    collection_id,
+                    recipe_id,
+                } => {
+                    if !remove_from_collection(&pool, collection_id, recipe_id).await? {
+                        tracing::warn!(collection_id, recipe_id, "recipe not in collection");
+                    }
+                }
+                CollectionCommand::Delete { collection_id } => {
+                    if !delete_collection(&pool, collection_id).await? {
+                        return Err(FeedMeError::CollectionNotFound(collection_id).into());
+                    }
+                }
+                CollectionCommand::Shop { collection_id } => {
+                    let format = config.display.quantity_format();
+                    for mut item in collection_shopping_list(&pool, collection_id).await? {
+                        item.localize_quantities(format);
+                        println!("{}", item);
+                    }
+                }
+            }
+        }
+        #[cfg(feature = "usda")]
+        Command::FetchNutrition { ingredients } => {
+            use feedme::controllers::get_all_ingredients;
+            use feedme::usda::{FdcClient, import_ingredient_nutrition};
+
+            let api_key = config.usda.api_key.clone().ok_or_else(|| {
+                FeedMeError::Config("set usda.api_key or $FEEDME_FDC_API_KEY".to_string())
+            })?;
+            let client = FdcClient::new(api_key);
+            let (pool, _) = cli.database.connect(&config.database).await?;
+
+            for ingredient in get_all_ingredients(&pool).await? {
+                if !ingredients.is_empty() && !ingredients.contains(&ingredient.name) {
+                    continue;
+                }
+                match import_ingredient_nutrition(&pool, &client, ingredient.id).await? {
+                    Some(fdc_id) => println!("{}: FDC {}", ingredient.name, fdc_id),
+                    None => println!("{}: {}", ingredient.name, t!("fetch-nutrition-no-match")),
+                }
+            }
+        }
+    }
 
     Ok(())
 }
+
+/// The ingredient called `name`, ignoring case
+async fn find_ingredient(
+    pool: &sqlx::SqlitePool,
+    name: &str,
+) -> Result<Option<IngredientRecord>, Report> {
+    Ok(get_all_ingredients(pool)
+        .await?
+        .into_iter()
+        .find(|record| record.name.eq_ignore_ascii_case(name)))
+}
@@ -0,0 +1,110 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+use crate::quantity::format_amount;
+
+/// How much an ingredient gets used
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngredientUsage {
+    pub ingredient_id: i64,
+    pub ingredient_name: String,
+    /// Recipes calling for it
+    pub recipes: i64,
+    /// Cooks in the period of a recipe calling for it
+    pub cooks: i64,
+}
+
+/// What was cooked in one calendar month
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthlyTrend {
+    /// First day of the month
+    pub month: NaiveDate,
+    pub cooks: i64,
+    /// Different recipes cooked
+    pub recipes: i64,
+    /// Mean estimated cost of a cook, over the cooks of fully priced
+    /// recipes; None when none were
+    pub average_cost: Option<f64>,
+    /// Mean prep plus cook time of a cook, over the cooks of recipes with
+    /// times recorded; None when none had them
+    pub average_minutes: Option<f64>,
+}
+
+/// Ingredient usage and cooking trends over a range of days
+#[derive(Debug, Clone, Serialize)]
+pub struct Analytics {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Most cooked ingredients first, then most used
+    pub ingredients: Vec<IngredientUsage>,
+    /// Months with cooks, in date order
+    pub months: Vec<MonthlyTrend>,
+}
+
+impl Analytics {
+    /// Cooks over the whole range
+    pub fn total_cooks(&self) -> i64 {
+        self.months.iter().map(|month| month.cooks).sum()
+    }
+}
+
+impl fmt::Display for IngredientUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<20} cooked {:>3}x, in {} recipe{}",
+            self.ingredient_name,
+            self.cooks,
+            self.recipes,
+            if self.recipes == 1 { "" } else { "s" }
+        )
+    }
+}
+
+impl fmt::Display for MonthlyTrend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {:>3} cooks  {:>3} recipes",
+            self.month.format("%Y-%m"),
+            self.cooks,
+            self.recipes
+        )?;
+        if let Some(cost) = self.average_cost {
+            write!(f, "  ~{:.2} a cook", cost)?;
+        }
+        if let Some(minutes) = self.average_minutes {
+            write!(f, "  {} min", format_amount(minutes.round()))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Analytics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Cooked {} times from {} to {}",
+            self.total_cooks(),
+            self.start,
+            self.end
+        )?;
+
+        if !self.months.is_empty() {
+            writeln!(f, "\nBy month:")?;
+            for month in &self.months {
+                writeln!(f, "  {}", month)?;
+            }
+        }
+
+        if !self.ingredients.is_empty() {
+            writeln!(f, "\nMost used ingredients:")?;
+            for usage in &self.ingredients {
+                writeln!(f, "  {}", usage)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use serde::Serialize;
+use std::fmt;
+
+/// A named group of recipes with its members
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    /// Members ordered by name
+    pub recipes: Vec<CollectionRecipe>,
+}
+
+/// A recipe in a `Collection`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CollectionRecipe {
+    pub recipe_id: i64,
+    pub name: String,
+}
+
+impl Collection {
+    /// IDs of the member recipes, e.g. to shop for all of them
+    pub fn recipe_ids(&self) -> Vec<i64> {
+        self.recipes.iter().map(|recipe| recipe.recipe_id).collect()
+    }
+}
+
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        for recipe in &self.recipes {
+            writeln!(f, "  {}: {}", recipe.recipe_id, recipe.name)?;
+        }
+
+        Ok(())
+    }
+}
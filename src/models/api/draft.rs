@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use super::Category;
+
+/// Where in the entry a draft was left, so resuming picks up there
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DraftStep {
+    #[default]
+    Name,
+    Servings,
+    Category,
+    Equipment,
+    Ingredients,
+    Instructions,
+    Review,
+}
+
+/// A recipe as entered so far, autosaved while it's typed
+/// Only what's been confirmed is kept, not a half-typed line; the source
+/// comes from the command line, so isn't either
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecipeDraft {
+    pub step: DraftStep,
+    pub name: String,
+    pub servings: Option<i64>,
+    pub category: Option<Category>,
+    pub equipment: Vec<String>,
+    pub ingredients: Vec<DraftIngredient>,
+    pub instructions: Vec<String>,
+}
+
+/// An ingredient line in a draft; whether the ingredient exists is looked up
+/// again on resuming
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DraftIngredient {
+    pub name: String,
+    pub quantity_unit: String,
+    pub notes: String,
+}
+
+impl RecipeDraft {
+    /// Whether nothing has been entered yet
+    pub fn is_empty(&self) -> bool {
+        self.step == DraftStep::Name && self.name.is_empty()
+    }
+}
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How demanding a recipe is, easiest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    /// Parse the stored form ("easy", "medium", "hard")
+    pub fn parse(text: &str) -> Option<Difficulty> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.as_str().eq_ignore_ascii_case(text.trim()))
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Difficulty::parse(s)
+            .ok_or_else(|| format!("unknown difficulty '{}' (expected easy, medium or hard)", s))
+    }
+}
+
+/// Who a recipe is shown to, narrowest first
+/// New recipes are shared with the household; experiments can be kept
+/// private until they're ready
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Private,
+    #[default]
+    Household,
+    Public,
+}
+
+impl Visibility {
+    pub const ALL: [Visibility; 3] = [
+        Visibility::Private,
+        Visibility::Household,
+        Visibility::Public,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Private => "private",
+            Visibility::Household => "household",
+            Visibility::Public => "public",
+        }
+    }
+
+    /// Parse the stored form ("private", "household", "public")
+    pub fn parse(text: &str) -> Option<Visibility> {
+        Visibility::ALL
+            .into_iter()
+            .find(|visibility| visibility.as_str().eq_ignore_ascii_case(text.trim()))
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Visibility::parse(s).ok_or_else(|| {
+            format!(
+                "unknown visibility '{}' (expected private, household or public)",
+                s
+            )
+        })
+    }
+}
+
+/// Which meal a recipe is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Breakfast,
+    Lunch,
+    Dinner,
+    Dessert,
+    Snack,
+}
+
+impl Category {
+    pub const ALL: [Category; 5] = [
+        Category::Breakfast,
+        Category::Lunch,
+        Category::Dinner,
+        Category::Dessert,
+        Category::Snack,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::Breakfast => "breakfast",
+            Category::Lunch => "lunch",
+            Category::Dinner => "dinner",
+            Category::Dessert => "dessert",
+            Category::Snack => "snack",
+        }
+    }
+
+    /// Parse the stored form ("breakfast", "lunch", ...)
+    pub fn parse(text: &str) -> Option<Category> {
+        Category::ALL
+            .into_iter()
+            .find(|category| category.as_str().eq_ignore_ascii_case(text.trim()))
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Category::parse(s).ok_or_else(|| {
+            format!(
+                "unknown category '{}' (expected breakfast, lunch, dinner, dessert or snack)",
+                s
+            )
+        })
+    }
+}
+
+/// Dietary properties, recorded on recipes as tags of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DietaryFlag {
+    Vegetarian,
+    Vegan,
+    GlutenFree,
+    DairyFree,
+    NutFree,
+}
+
+impl DietaryFlag {
+    /// The tag that marks a recipe with this flag
+    pub fn tag(&self) -> &'static str {
+        match self {
+            DietaryFlag::Vegetarian => "vegetarian",
+            DietaryFlag::Vegan => "vegan",
+            DietaryFlag::GlutenFree => "gluten-free",
+            DietaryFlag::DairyFree => "dairy-free",
+            DietaryFlag::NutFree => "nut-free",
+        }
+    }
+}
+
+/// Restrictions applied when listing or searching recipes
+/// Every set field must hold. Recipes whose time, difficulty, category or
+/// rating is unknown don't pass a filter on that field
+#[derive(Debug, Clone, Default)]
+pub struct RecipeFilter {
+    /// Tags a recipe must all carry
+    pub tags: Vec<String>,
+    /// Prep + cook minutes at most this
+    pub max_total_minutes: Option<i64>,
+    /// No harder than this
+    pub max_difficulty: Option<Difficulty>,
+    /// In one of these categories; empty allows any
+    pub categories: Vec<Category>,
+    /// Average rating at least this (1 to 5)
+    pub min_rating: Option<f64>,
+    /// Only recipes starred as favorites
+    pub favorites_only: bool,
+    /// Equipment a recipe must not need ("oven" for no-oven recipes),
+    /// matched ignoring case
+    pub without_equipment: Vec<String>,
+    /// Dietary flags a recipe must all have
+    pub dietary: Vec<DietaryFlag>,
+    /// Who is looking: only recipes shared at least this widely pass
+    /// (`Household` hides private recipes, `Public` shows only public ones).
+    /// None shows everything, as the author sees it
+    pub audience: Option<Visibility>,
+}
+
+impl RecipeFilter {
+    /// Tags required by `tags` and `dietary`, lowercased and deduplicated
+    pub fn required_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .chain(self.dietary.iter().map(|flag| flag.tag().to_string()))
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+/// A journal entry written after cooking a recipe
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecipeNote {
+    /// Ignored when adding
+    pub id: i64,
+    /// Cook log entry (and so recipe) the note is about
+    pub cook_log_id: i64,
+    pub noted_on: NaiveDate,
+    pub text: String,
+    /// How many stars better (positive) or worse it turned out than usual
+    pub rating_delta: Option<i64>,
+    /// What was done differently this time ("halved the chili")
+    pub modifications: Option<String>,
+}
+
+impl fmt::Display for RecipeNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.noted_on, self.text)?;
+
+        if let Some(delta) = self.rating_delta {
+            write!(f, " ({:+})", delta)?;
+        }
+
+        if let Some(modifications) = &self.modifications {
+            write!(f, " [changed: {}]", modifications)?;
+        }
+
+        Ok(())
+    }
+}
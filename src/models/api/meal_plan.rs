@@ -0,0 +1,192 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::ShoppingList;
+
+/// A meal plan with its scheduled recipes, ordered by date
+#[derive(Debug, Clone, Serialize)]
+pub struct MealPlan {
+    /// 0 for plans that haven't been saved yet
+    pub id: i64,
+    pub name: Option<String>,
+    pub start_date: NaiveDate,
+    pub entries: Vec<PlannedMeal>,
+}
+
+/// One recipe scheduled for one meal of a plan
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedMeal {
+    pub date: NaiveDate,
+    /// "dinner", "lunch", ...
+    pub meal: String,
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    /// Multiplier applied to the recipe's quantities
+    pub servings: f64,
+    pub note: Option<String>,
+}
+
+/// The same prepared ingredient needed by several recipes in a plan
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedPrep {
+    pub ingredient_name: String,
+    /// How it's prepared ("diced"), from the recipe ingredient notes
+    pub preparation: String,
+    /// Names of the planned recipes that need it, in plan order
+    pub recipes: Vec<String>,
+    /// Combined quantity across those recipes, scaled by servings
+    pub total_quantity: String,
+    /// First day it's needed, so it can be prepped by then
+    pub first_needed: NaiveDate,
+}
+
+impl fmt::Display for SharedPrep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} recipes need {} {} ({}): prep {} by {} {}",
+            self.recipes.len(),
+            self.preparation,
+            self.ingredient_name,
+            self.recipes.join(", "),
+            self.total_quantity,
+            self.first_needed.weekday(),
+            self.first_needed
+        )
+    }
+}
+
+/// A note on a planned meal that someone needs to act on
+#[derive(Debug, Clone, Serialize)]
+pub struct Reminder {
+    pub date: NaiveDate,
+    pub meal: String,
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    pub note: String,
+}
+
+impl fmt::Display for Reminder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}: {}",
+            self.date.weekday(),
+            self.meal,
+            self.recipe_name,
+            self.note
+        )
+    }
+}
+
+/// A plan and what to buy for it, as sent out by `feedme digest`
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyDigest {
+    pub plan: MealPlan,
+    pub shopping_list: ShoppingList,
+}
+
+/// Rules for `generate_week_plan`
+#[derive(Debug, Clone)]
+pub struct PlanConstraints {
+    /// First of the seven planned days
+    pub start_date: NaiveDate,
+    /// Maximum prep + cook minutes per weekday; days without an entry are unlimited
+    /// Recipes with no recorded time only fit unlimited days
+    pub max_minutes: HashMap<Weekday, i64>,
+    /// Minimum days between two servings of the same recipe, counting cooked
+    /// and previously planned meals as well as the new plan
+    pub no_repeat_days: i64,
+    /// Tags every planned recipe must carry
+    pub required_tags: Vec<String>,
+}
+
+impl PlanConstraints {
+    /// No time limits or tags, and no recipe twice within a week
+    pub fn new(start_date: NaiveDate) -> Self {
+        Self {
+            start_date,
+            max_minutes: HashMap::new(),
+            no_repeat_days: 7,
+            required_tags: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for MealPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => writeln!(f, "Meal plan: {} (from {})", name, self.start_date)?,
+            None => writeln!(f, "Meal plan from {}", self.start_date)?,
+        }
+
+        for entry in &self.entries {
+            write!(
+                f,
+                "  {} {} {:<9} {}",
+                entry.date.weekday(),
+                entry.date,
+                entry.meal,
+                entry.recipe_name
+            )?;
+
+            if entry.servings != 1.0 {
+                write!(f, " x{}", entry.servings)?;
+            }
+
+            if let Some(note) = &entry.note {
+                write!(f, " ({})", note)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_prep_display() {
+        let prep = SharedPrep {
+            ingredient_name: "onion".to_string(),
+            preparation: "diced".to_string(),
+            recipes: vec!["Chili".to_string(), "Curry".to_string()],
+            total_quantity: "3 whole".to_string(),
+            first_needed: NaiveDate::from_ymd_opt(2026, 10, 20).unwrap(),
+        };
+
+        assert_eq!(
+            prep.to_string(),
+            "2 recipes need diced onion (Chili, Curry): prep 3 whole by Tue 2026-10-20"
+        );
+    }
+
+    #[test]
+    fn test_meal_plan_display() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let plan = MealPlan {
+            id: 0,
+            name: Some("Week 43".to_string()),
+            start_date: date,
+            entries: vec![PlannedMeal {
+                date,
+                meal: "dinner".to_string(),
+                recipe_id: 1,
+                recipe_name: "Tomato Pasta".to_string(),
+                servings: 2.0,
+                note: Some("defrost sauce".to_string()),
+            }],
+        };
+
+        assert_eq!(
+            plan.to_string(),
+            "Meal plan: Week 43 (from 2026-10-19)\n  Mon 2026-10-19 dinner    Tomato Pasta x2 (defrost sauce)\n"
+        );
+    }
+}
@@ -1,5 +1,43 @@
+mod analytics;
+mod collection;
+mod draft;
+mod filter;
+mod journal;
+mod meal_plan;
+mod nutrition;
+mod pack;
+mod pantry;
+mod price;
+mod quality;
 mod recipe;
+mod search;
 mod shopping_list;
+mod substitution;
+mod suggestion;
 
-pub use recipe::{Recipe, RecipeIngredient};
-pub use shopping_list::ShoppingListItem;
+pub use analytics::{Analytics, IngredientUsage, MonthlyTrend};
+pub use collection::{Collection, CollectionRecipe};
+pub use draft::{DraftIngredient, DraftStep, RecipeDraft};
+pub use filter::{Category, DietaryFlag, Difficulty, RecipeFilter, Visibility};
+pub use journal::RecipeNote;
+pub use meal_plan::{MealPlan, PlanConstraints, PlannedMeal, Reminder, SharedPrep, WeeklyDigest};
+pub use nutrition::{
+    DailyNutrition, Nutrient, NutrientDeviation, NutritionFacts, NutritionReport, RecipeNutrition,
+};
+pub use pack::{
+    ConflictStrategy, PACK_FORMAT, PACK_VERSION, PackConflict, PackContents, PackImport,
+    PackIngredient, PackRecipe, RecipePack,
+};
+pub use pantry::{CookableRecipe, ExpiringRecipe, PantryItem};
+pub use price::{CostLine, IngredientPrice, RecipeCost};
+pub use quality::{DataIssue, DataQualityReport, RecipeIssues};
+pub use recipe::{
+    RatedRecipe, Recipe, RecipeIngredient, RecipeMetadata, RecipeOptions, RecipeRating,
+    instruction_steps,
+};
+pub use search::{IngredientQuery, SearchResult};
+pub use shopping_list::{
+    ShoppingCheckOff, ShoppingList, ShoppingListEntry, ShoppingListItem, ShoppingSync, StoreLayout,
+};
+pub use substitution::Substitution;
+pub use suggestion::{SimilarRecipe, Suggestion, SuggestionCriteria};
@@ -0,0 +1,146 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+/// Calories and macronutrients for some amount of food
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct NutritionFacts {
+    pub calories: f64,
+    pub protein_g: f64,
+    pub carbohydrates_g: f64,
+    pub fat_g: f64,
+}
+
+impl NutritionFacts {
+    /// Add `other` multiplied by `factor`
+    pub fn add_scaled(&mut self, other: &NutritionFacts, factor: f64) {
+        self.calories += other.calories * factor;
+        self.protein_g += other.protein_g * factor;
+        self.carbohydrates_g += other.carbohydrates_g * factor;
+        self.fat_g += other.fat_g * factor;
+    }
+
+    pub fn get(&self, nutrient: Nutrient) -> f64 {
+        match nutrient {
+            Nutrient::Calories => self.calories,
+            Nutrient::Protein => self.protein_g,
+            Nutrient::Carbohydrates => self.carbohydrates_g,
+            Nutrient::Fat => self.fat_g,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Nutrient {
+    Calories,
+    Protein,
+    Carbohydrates,
+    Fat,
+}
+
+impl Nutrient {
+    pub const ALL: [Nutrient; 4] = [
+        Nutrient::Calories,
+        Nutrient::Protein,
+        Nutrient::Carbohydrates,
+        Nutrient::Fat,
+    ];
+}
+
+impl fmt::Display for Nutrient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Nutrient::Calories => "calories",
+            Nutrient::Protein => "protein",
+            Nutrient::Carbohydrates => "carbohydrates",
+            Nutrient::Fat => "fat",
+        })
+    }
+}
+
+/// A nutrient whose daily total falls outside its target range
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NutrientDeviation {
+    pub nutrient: Nutrient,
+    pub actual: f64,
+    pub target: f64,
+}
+
+/// Nutrition totals for one day of a meal plan
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyNutrition {
+    pub date: NaiveDate,
+    pub totals: NutritionFacts,
+    /// Ingredients left out of the totals for lack of nutrition data or a
+    /// quantity that can't be converted to grams
+    pub missing: Vec<String>,
+    /// Nutrients outside the configured targets
+    pub deviations: Vec<NutrientDeviation>,
+}
+
+/// A recipe's nutrition divided by its servings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecipeNutrition {
+    /// Servings the totals were divided by (1 when the recipe doesn't say)
+    pub servings: i64,
+    pub per_serving: NutritionFacts,
+    /// Ingredients left out for lack of nutrition data or a weighable quantity
+    pub missing: Vec<String>,
+}
+
+/// Nutrition over a range of days, against the configured targets
+#[derive(Debug, Clone, Serialize)]
+pub struct NutritionReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Days with planned or cooked meals, in date order
+    pub days: Vec<DailyNutrition>,
+    /// Mean of the daily totals over `days`
+    pub daily_average: NutritionFacts,
+    /// Nutrients whose daily average is outside the targets
+    pub deviations: Vec<NutrientDeviation>,
+}
+
+impl fmt::Display for NutrientDeviation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = if self.actual < self.target {
+            "low"
+        } else {
+            "high"
+        };
+        write!(f, "{} {}", self.nutrient, direction)
+    }
+}
+
+impl fmt::Display for NutritionFacts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>5.0} kcal  protein {:>4.0} g  carbs {:>4.0} g  fat {:>4.0} g",
+            self.calories, self.protein_g, self.carbohydrates_g, self.fat_g
+        )
+    }
+}
+
+impl fmt::Display for NutritionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nutrition {} to {}", self.start, self.end)?;
+
+        for day in &self.days {
+            write!(f, "  {}  {}", day.date, day.totals)?;
+            if !day.deviations.is_empty() {
+                let flags: Vec<String> = day.deviations.iter().map(|d| d.to_string()).collect();
+                write!(f, "  [{}]", flags.join(", "))?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, "  average     {}", self.daily_average)?;
+        if !self.deviations.is_empty() {
+            let flags: Vec<String> = self.deviations.iter().map(|d| d.to_string()).collect();
+            write!(f, "  [{}]", flags.join(", "))?;
+        }
+        writeln!(f)
+    }
+}
@@ -0,0 +1,330 @@
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+use super::{Category, Difficulty};
+use crate::diff::RecipeDiff;
+use crate::error::{FeedMeError, Result};
+use crate::validation::Warning;
+
+/// Marks a file as a FeedMe recipe pack
+pub const PACK_FORMAT: &str = "feedme-recipe-pack";
+
+/// Version of the pack contents written by this build
+/// Packs with a newer version are refused rather than half-read. Fields
+/// added with a default don't need a new version, since older builds check
+/// the contents as written and ignore what they don't know
+pub const PACK_VERSION: u32 = 2;
+
+/// One or more recipes with the ingredients they use, as a single file to
+/// hand to someone else
+///
+/// Ingredients are referenced by name, since IDs only mean something in the
+/// database they came from. `checksum` is the SHA-256 of the contents' JSON
+/// exactly as written and catches damaged files; `signature`, an HMAC-SHA256
+/// keyed with a secret shared between sender and receiver, shows who made the
+/// pack. Both cover the bytes in the file rather than a re-serialization, so
+/// a pack from a newer build with extra fields still checks out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PackFile", into = "PackFile")]
+pub struct RecipePack {
+    pub format: String,
+    pub contents: PackContents,
+    pub checksum: String,
+    pub signature: Option<String>,
+    /// `contents` as written or read, which the checksum and signature cover
+    raw_contents: String,
+}
+
+/// A pack as laid out in the file
+#[derive(Serialize, Deserialize)]
+struct PackFile {
+    format: String,
+    contents: Box<RawValue>,
+    checksum: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+/// The part of a pack covered by its checksum and signature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackContents {
+    pub version: u32,
+    pub exported_at: String,
+    /// Every ingredient the recipes use, sorted by name
+    pub ingredients: Vec<String>,
+    pub recipes: Vec<PackRecipe>,
+}
+
+/// A recipe as stored in a pack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackRecipe {
+    pub name: String,
+    pub instructions: Option<String>,
+    pub servings: Option<i64>,
+    pub ingredients: Vec<PackIngredient>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prep_minutes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cook_minutes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+}
+
+/// An ingredient line of a pack recipe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackIngredient {
+    pub name: String,
+    pub quantity_unit: String,
+    pub notes: Option<String>,
+}
+
+/// What to do with a pack recipe named like one already in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// Keep the existing recipe and leave the pack's out
+    Skip,
+    /// Import the pack's recipe under a free name, "Name (2)"
+    Rename,
+    /// Add the pack's missing ingredients and tags to the existing recipe,
+    /// and its instructions, servings and times where the existing one has none
+    Merge,
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(ConflictStrategy::Skip),
+            "rename" => Ok(ConflictStrategy::Rename),
+            "merge" => Ok(ConflictStrategy::Merge),
+            other => Err(format!(
+                "unknown conflict strategy '{}' (expected skip, rename or merge)",
+                other
+            )),
+        }
+    }
+}
+
+/// A pack recipe whose name is already taken
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PackConflict {
+    pub name: String,
+    pub existing_id: i64,
+    /// How the pack's recipe differs from the existing one
+    pub changes: RecipeDiff,
+}
+
+/// What importing a pack did
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PackImport {
+    /// IDs of recipes added, including renamed ones
+    pub created: Vec<i64>,
+    /// (pack name, name it was imported as)
+    pub renamed: Vec<(String, String)>,
+    /// IDs of existing recipes the pack was merged into
+    pub merged: Vec<i64>,
+    /// Names of pack recipes left out
+    pub skipped: Vec<String>,
+    /// Ingredients that didn't exist before the import
+    pub new_ingredients: Vec<String>,
+    /// Likely mistakes in the recipes imported or merged, by pack name
+    pub recipe_warnings: Vec<(String, Vec<Warning>)>,
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+impl RecipePack {
+    /// Wrap `contents` with its checksum, signing it when `key` is given
+    pub fn seal(contents: PackContents, key: Option<&[u8]>) -> Result<Self> {
+        let raw_contents = serde_json::to_string(&contents)?;
+        let bytes = raw_contents.as_bytes();
+        Ok(Self {
+            format: PACK_FORMAT.to_string(),
+            checksum: hex::encode(Sha256::digest(bytes)),
+            signature: key.map(|key| hex::encode(mac(key, bytes).finalize().into_bytes())),
+            contents,
+            raw_contents,
+        })
+    }
+
+    /// Check the format, version and checksum, and the signature when `key`
+    /// is given; packs must then be signed with the same key
+    pub fn verify(&self, key: Option<&[u8]>) -> Result<()> {
+        if self.format != PACK_FORMAT {
+            return Err(invalid("not a FeedMe recipe pack"));
+        }
+        if self.contents.version > PACK_VERSION {
+            return Err(invalid(format!(
+                "made by a newer FeedMe (pack version {})",
+                self.contents.version
+            )));
+        }
+
+        let bytes = self.raw_contents.as_bytes();
+        if hex::encode(Sha256::digest(bytes)) != self.checksum {
+            return Err(invalid("checksum mismatch; the file is damaged"));
+        }
+
+        if let Some(key) = key {
+            let signature = self
+                .signature
+                .as_deref()
+                .ok_or_else(|| invalid("the pack isn't signed"))?;
+            let signature = hex::decode(signature).map_err(|_| invalid("malformed signature"))?;
+            mac(key, bytes)
+                .verify_slice(&signature)
+                .map_err(|_| invalid("signed with a different key"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<PackFile> for RecipePack {
+    type Error = FeedMeError;
+
+    fn try_from(file: PackFile) -> Result<Self> {
+        /// Read on its own so a newer pack is refused by version rather than
+        /// by whatever in its contents no longer parses
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        let raw_contents = file.contents.get();
+        let Version { version } = serde_json::from_str(raw_contents)?;
+        if version > PACK_VERSION {
+            return Err(invalid(format!(
+                "made by a newer FeedMe (pack version {})",
+                version
+            )));
+        }
+
+        Ok(Self {
+            format: file.format,
+            contents: serde_json::from_str(raw_contents)?,
+            checksum: file.checksum,
+            signature: file.signature,
+            raw_contents: raw_contents.to_string(),
+        })
+    }
+}
+
+impl From<RecipePack> for PackFile {
+    fn from(pack: RecipePack) -> Self {
+        Self {
+            format: pack.format,
+            contents: RawValue::from_string(pack.raw_contents)
+                .expect("pack contents are written as JSON"),
+            checksum: pack.checksum,
+            signature: pack.signature,
+        }
+    }
+}
+
+fn mac(key: &[u8], bytes: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(bytes);
+    mac
+}
+
+fn invalid(reason: impl Into<String>) -> FeedMeError {
+    FeedMeError::InvalidPack(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents() -> PackContents {
+        PackContents {
+            version: PACK_VERSION,
+            exported_at: "2025-03-01T12:00:00Z".to_string(),
+            ingredients: vec!["salt".to_string()],
+            recipes: vec![PackRecipe {
+                name: "Brine".to_string(),
+                instructions: None,
+                servings: None,
+                ingredients: vec![PackIngredient {
+                    name: "salt".to_string(),
+                    quantity_unit: "1 tbsp".to_string(),
+                    notes: None,
+                }],
+                tags: vec![],
+                prep_minutes: None,
+                cook_minutes: None,
+                category: None,
+                difficulty: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_pack_round_trips_through_json() {
+        let pack = RecipePack::seal(contents(), Some(b"secret")).unwrap();
+
+        // Pretty, as `feedme pack export` writes it
+        let json = serde_json::to_string_pretty(&pack).unwrap();
+        let read: RecipePack = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(read, pack);
+        read.verify(Some(b"secret")).unwrap();
+        read.verify(None).unwrap();
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let json = serde_json::to_string(&RecipePack::seal(contents(), None).unwrap()).unwrap();
+        let pack: RecipePack = serde_json::from_str(&json.replace("Brine", "Pickles")).unwrap();
+        assert!(matches!(
+            pack.verify(None),
+            Err(FeedMeError::InvalidPack(_))
+        ));
+
+        let pack = RecipePack::seal(contents(), None).unwrap();
+        assert!(matches!(
+            pack.verify(Some(b"secret")),
+            Err(FeedMeError::InvalidPack(reason)) if reason.contains("isn't signed")
+        ));
+
+        let pack = RecipePack::seal(contents(), Some(b"other")).unwrap();
+        assert!(pack.verify(Some(b"secret")).is_err());
+
+        let mut newer = contents();
+        newer.version = PACK_VERSION + 1;
+        let pack = RecipePack::seal(newer, None).unwrap();
+        assert!(pack.verify(None).is_err());
+    }
+
+    #[test]
+    fn test_verify_checks_contents_as_written() {
+        // A newer build's pack with a field this one doesn't know
+        let mut raw: serde_json::Value = serde_json::to_value(contents()).unwrap();
+        raw["recipes"][0]["cuisine"] = "Nordic".into();
+        let raw = raw.to_string();
+        let json = serde_json::json!({
+            "format": PACK_FORMAT,
+            "contents": serde_json::from_str::<serde_json::Value>(&raw).unwrap(),
+            "checksum": hex::encode(Sha256::digest(raw.as_bytes())),
+            "signature": hex::encode(mac(b"secret", raw.as_bytes()).finalize().into_bytes()),
+        });
+
+        let pack: RecipePack = serde_json::from_str(&json.to_string()).unwrap();
+        pack.verify(Some(b"secret")).unwrap();
+        assert_eq!(pack.contents.recipes[0].name, "Brine");
+
+        // Written back out unchanged, so it still checks out when passed on
+        let read: RecipePack =
+            serde_json::from_str(&serde_json::to_string(&pack).unwrap()).unwrap();
+        read.verify(Some(b"secret")).unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+use super::{ShoppingListItem, Substitution};
+use crate::quantity::{format_amount, format_quantity};
+
+/// Stock of one ingredient in the pantry
+#[derive(Debug, Clone, Serialize)]
+pub struct PantryItem {
+    /// Ignored when adding
+    pub id: i64,
+    pub ingredient_id: i64,
+    /// Ignored when adding
+    pub ingredient_name: String,
+    pub amount: f64,
+    /// None for counts ("6 eggs")
+    pub unit: Option<String>,
+    pub expires_on: Option<NaiveDate>,
+    pub is_staple: bool,
+    /// Restock threshold in `unit`, None to never restock
+    pub min_amount: Option<f64>,
+    /// The ingredient's emoji, None when unset; ignored when adding
+    pub icon: Option<String>,
+}
+
+/// A recipe that would use up pantry items nearing their expiration date
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpiringRecipe {
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    /// Expiring ingredients the recipe uses, soonest first
+    pub uses: Vec<String>,
+    /// Earliest expiration date among `uses`
+    pub soonest: NaiveDate,
+}
+
+impl fmt::Display for PantryItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(icon) = &self.icon {
+            write!(f, "{} ", icon)?;
+        }
+        write!(
+            f,
+            "{} {}",
+            self.ingredient_name,
+            format_quantity(self.amount, self.unit.as_deref())
+        )?;
+
+        if let Some(expires_on) = self.expires_on {
+            write!(f, " (expires {})", expires_on)?;
+        }
+
+        if let Some(min_amount) = self.min_amount {
+            write!(f, " (min {})", format_amount(min_amount))?;
+        }
+
+        if self.is_staple {
+            write!(f, " [staple]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How well current pantry stock covers a recipe
+#[derive(Debug, Clone, Serialize)]
+pub struct CookableRecipe {
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    /// Ingredients that are out of stock or short, empty when cookable now
+    pub missing: Vec<String>,
+    /// How much of each missing ingredient to buy: the recipe's whole
+    /// quantity when out of stock, the shortfall when short
+    pub to_buy: Vec<ShoppingListItem>,
+    /// Ways to replace missing ingredients using what's in the pantry
+    pub substitutions: Vec<Substitution>,
+}
+
+impl fmt::Display for CookableRecipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.recipe_name)?;
+        if self.to_buy.is_empty() {
+            return writeln!(f, " (cookable now)");
+        }
+
+        writeln!(f, " - buy:")?;
+        for item in &self.to_buy {
+            writeln!(f, "  {}", item)?;
+        }
+        for substitution in &self.substitutions {
+            writeln!(f, "  or substitute {}", substitution)?;
+        }
+
+        Ok(())
+    }
+}
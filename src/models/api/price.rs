@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+use crate::quantity::{IngredientUnits, format_quantity};
+
+/// What an ingredient cost when bought
+#[derive(Debug, Clone, Serialize)]
+pub struct IngredientPrice {
+    /// Ignored when adding
+    pub id: i64,
+    pub ingredient_id: i64,
+    /// Ignored when adding
+    pub ingredient_name: String,
+    /// Amount paid for `amount` `unit`
+    pub price: f64,
+    pub amount: f64,
+    /// None for counts ("2.50 for 6 eggs")
+    pub unit: Option<String>,
+    pub store: Option<String>,
+    pub observed_on: NaiveDate,
+}
+
+impl IngredientPrice {
+    /// Cost of `amount` `unit` at this price, None when the units don't
+    /// convert into the one the price was recorded in
+    pub fn cost_of(&self, units: &IngredientUnits, amount: f64, unit: Option<&str>) -> Option<f64> {
+        units
+            .convert(amount, unit, self.unit.as_deref())
+            .map(|converted| converted / self.amount * self.price)
+    }
+}
+
+impl fmt::Display for IngredientPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.2} for {}",
+            self.ingredient_name,
+            self.price,
+            format_quantity(self.amount, self.unit.as_deref())
+        )?;
+
+        if let Some(store) = &self.store {
+            write!(f, " at {}", store)?;
+        }
+
+        write!(f, " ({})", self.observed_on)
+    }
+}
+
+/// Estimated cost of cooking a recipe at the latest recorded prices
+#[derive(Debug, Clone, Serialize)]
+pub struct RecipeCost {
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    /// Servings the estimate is for, None when the recipe doesn't say
+    pub servings: Option<i64>,
+    /// Priced ingredients in recipe order
+    pub lines: Vec<CostLine>,
+    /// Ingredients left out of `total`: no price recorded, or a quantity
+    /// that doesn't convert to the priced unit
+    pub unpriced: Vec<String>,
+    pub total: f64,
+}
+
+/// One priced ingredient of a `RecipeCost`
+#[derive(Debug, Clone, Serialize)]
+pub struct CostLine {
+    pub ingredient_name: String,
+    /// Scaled quantity the cost is for
+    pub quantity: String,
+    pub cost: f64,
+}
+
+impl RecipeCost {
+    /// Cost of one serving, None when the number of servings is unknown
+    pub fn per_serving(&self) -> Option<f64> {
+        self.servings
+            .filter(|&servings| servings > 0)
+            .map(|servings| self.total / servings as f64)
+    }
+}
+
+impl fmt::Display for RecipeCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.recipe_name)?;
+        for line in &self.lines {
+            writeln!(
+                f,
+                "  {} {}: {:.2}",
+                line.quantity, line.ingredient_name, line.cost
+            )?;
+        }
+
+        write!(f, "Total: {:.2}", self.total)?;
+        if let (Some(servings), Some(per_serving)) = (self.servings, self.per_serving()) {
+            write!(f, " ({:.2} per serving, serves {})", per_serving, servings)?;
+        }
+        writeln!(f)?;
+
+        if !self.unpriced.is_empty() {
+            writeln!(f, "No price for: {}", self.unpriced.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
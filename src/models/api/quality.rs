@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Something missing or unreadable in a saved recipe
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum DataIssue {
+    NoInstructions,
+    NoServings,
+    NoIngredients,
+    /// A quantity that couldn't be read as an amount and unit, so it's left
+    /// out of scaling, costs and nutrition
+    UnparseableQuantity {
+        ingredient_name: String,
+        quantity_unit: String,
+    },
+}
+
+impl fmt::Display for DataIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataIssue::NoInstructions => write!(f, "no instructions"),
+            DataIssue::NoServings => write!(f, "servings unknown"),
+            DataIssue::NoIngredients => write!(f, "no ingredients"),
+            DataIssue::UnparseableQuantity {
+                ingredient_name,
+                quantity_unit,
+            } if quantity_unit.trim().is_empty() => {
+                write!(f, "{}: no quantity", ingredient_name)
+            }
+            DataIssue::UnparseableQuantity {
+                ingredient_name,
+                quantity_unit,
+            } => write!(f, "{}: can't read '{}'", ingredient_name, quantity_unit),
+        }
+    }
+}
+
+/// A recipe with at least one `DataIssue`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecipeIssues {
+    pub recipe_id: i64,
+    pub recipe_name: String,
+    pub issues: Vec<DataIssue>,
+}
+
+/// Recipes needing cleanup, see `data_quality_report`
+#[derive(Debug, Clone, Serialize)]
+pub struct DataQualityReport {
+    /// Every recipe looked at
+    pub checked: usize,
+    /// Recipes with issues, ordered by name
+    pub recipes: Vec<RecipeIssues>,
+}
+
+impl DataQualityReport {
+    /// Whether every recipe has all its data
+    pub fn is_clean(&self) -> bool {
+        self.recipes.is_empty()
+    }
+}
+
+impl fmt::Display for DataQualityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} recipes need attention",
+            self.recipes.len(),
+            self.checked
+        )?;
+
+        for recipe in &self.recipes {
+            writeln!(f, "\n#{} {}", recipe.recipe_id, recipe.recipe_name)?;
+            for issue in &recipe.issues {
+                writeln!(f, "  - {}", issue)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,50 +1,245 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{Category, Difficulty, RecipeNote, RecipeNutrition, Visibility};
+use crate::quantity::{QuantityFormat, format_amount, localize_quantity};
+use crate::temperature::localize_temperatures;
+use crate::timers::StepTimer;
+
 /// Complete recipe with all ingredients for API responses
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Recipe {
     pub id: i64,
     pub name: String,
     pub instructions: Option<String>,
+    /// Number of servings the recipe makes, None when unknown
+    pub servings: Option<i64>,
+    /// Page the recipe was imported from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    /// Who wrote it (a blog, book or person)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<String>,
     pub ingredients: Vec<RecipeIngredient>,
+    /// Ordered by name
+    pub tags: Vec<String>,
+    /// Tools it needs ("stand mixer"), ordered by name
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub equipment: Vec<String>,
+    /// Who it's shown to when listing and searching for an audience
+    pub visibility: Visibility,
     pub created_at: String,
+    /// How it's been rated, None until it has been
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<RecipeRating>,
+    /// Computed nutrition per serving, only filled in when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nutrition: Option<RecipeNutrition>,
+    /// Durations found in the instructions, in step order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timers: Vec<StepTimer>,
+    /// Notes from past cooks, newest first; only filled in when requested
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub journal: Vec<RecipeNote>,
+}
+
+/// Details of a recipe kept outside `Recipe`, written by exports and packs
+/// so they survive moving between databases
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecipeMetadata {
+    /// Ordered by name
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prep_minutes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cook_minutes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+}
+
+/// Optional extras to compute when fetching a recipe
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecipeOptions {
+    /// Fill in `Recipe::nutrition`
+    pub include_nutrition: bool,
+    /// Fill in `Recipe::journal`
+    pub include_journal: bool,
+    /// Rewrite ingredient quantities for display
+    pub quantities: QuantityFormat,
+}
+
+/// Summary of the 1 to 5 ratings a recipe has been given
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RecipeRating {
+    pub average: f64,
+    /// The most recent rating
+    pub latest: i64,
+    /// How many times it's been rated
+    pub count: i64,
+}
+
+/// A recipe in a listing, with its rating
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RatedRecipe {
+    pub recipe_id: i64,
+    pub name: String,
+    pub is_favorite: bool,
+    pub rating: Option<RecipeRating>,
 }
 
 /// A single ingredient within a recipe
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct RecipeIngredient {
     pub ingredient_id: i64,
     pub ingredient_name: String,
     pub quantity_unit: String,
     pub notes: Option<String>,
+    /// Instruction steps using it, numbered from 0 as in `Recipe::steps`;
+    /// empty when it hasn't been linked to any
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<usize>,
+}
+
+impl fmt::Display for RecipeRating {
+    /// "4.5/5 (latest 4, rated 2 times)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/5 (latest {}, rated {} time{})",
+            format_amount((self.average * 10.0).round() / 10.0),
+            self.latest,
+            self.count,
+            if self.count == 1 { "" } else { "s" }
+        )
+    }
+}
+
+impl fmt::Display for RatedRecipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let star = if self.is_favorite { "★" } else { " " };
+        write!(f, "{:>4} {} {}", self.recipe_id, star, self.name)?;
+        if let Some(rating) = &self.rating {
+            write!(f, "  {}", rating)?;
+        }
+        Ok(())
+    }
+}
+
+/// The non-blank lines of instructions, trimmed; step references count
+/// from 0 over these
+pub fn instruction_steps(instructions: &str) -> Vec<&str> {
+    instructions
+        .lines()
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .collect()
 }
 
 impl Recipe {
-    /// Format the recipe as a human-readable string
-    pub fn to_string(&self) -> String {
-        let mut output = String::new();
+    /// The instruction steps, see `instruction_steps`
+    pub fn steps(&self) -> Vec<&str> {
+        instruction_steps(self.instructions.as_deref().unwrap_or(""))
+    }
 
-        output.push_str(&format!("Recipe: {}\n", self.name));
-        output.push_str(&format!("ID: {}\n", self.id));
-        output.push_str(&format!("Created: {}\n", self.created_at));
-        output.push_str("\nIngredients:\n");
+    /// Rewrite ingredient quantities ("2 cups" becomes "473 ml") and
+    /// temperatures in the instructions ("350°F" becomes "175°C") for display
+    pub fn localize_quantities(&mut self, format: QuantityFormat) {
+        for ingredient in &mut self.ingredients {
+            ingredient.quantity_unit = localize_quantity(&ingredient.quantity_unit, format);
+        }
+        if let Some(system) = format.system
+            && let Some(instructions) = &self.instructions
+        {
+            self.instructions = Some(localize_temperatures(instructions, system));
+        }
+    }
+}
+
+impl fmt::Display for Recipe {
+    /// Format the recipe as a human-readable string
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Recipe: {}", self.name)?;
+        writeln!(f, "ID: {}", self.id)?;
+        writeln!(f, "Created: {}", self.created_at)?;
+        if let Some(servings) = self.servings {
+            writeln!(f, "Serves: {}", servings)?;
+        }
+        match (&self.source_name, &self.source_url) {
+            (Some(name), Some(url)) => writeln!(f, "Source: {} ({})", name, url)?,
+            (Some(source), None) | (None, Some(source)) => writeln!(f, "Source: {}", source)?,
+            (None, None) => {}
+        }
+        if !self.tags.is_empty() {
+            writeln!(f, "Tags: {}", self.tags.join(", "))?;
+        }
+        if !self.equipment.is_empty() {
+            writeln!(f, "Equipment: {}", self.equipment.join(", "))?;
+        }
+        if let Some(rating) = &self.rating {
+            writeln!(f, "Rating: {}", rating)?;
+        }
+        writeln!(f, "\nIngredients:")?;
 
         for ingredient in &self.ingredients {
-            output.push_str(&format!(
+            write!(
+                f,
                 "  - {} {}",
                 ingredient.quantity_unit, ingredient.ingredient_name
-            ));
+            )?;
 
             if let Some(notes) = &ingredient.notes {
-                output.push_str(&format!(" ({})", notes));
+                write!(f, " ({})", notes)?;
+            }
+            if !ingredient.steps.is_empty() {
+                let steps: Vec<String> = ingredient
+                    .steps
+                    .iter()
+                    .map(|step| (step + 1).to_string())
+                    .collect();
+                write!(f, " [step {}]", steps.join(", "))?;
             }
 
-            output.push('\n');
+            writeln!(f)?;
         }
 
         if let Some(instructions) = &self.instructions {
-            output.push_str(&format!("\nInstructions:\n{}\n", instructions));
+            writeln!(f, "\nInstructions:\n{}", instructions)?;
+        }
+
+        if !self.timers.is_empty() {
+            writeln!(f, "\nTimers:")?;
+            for timer in &self.timers {
+                writeln!(f, "  {}", timer)?;
+            }
+        }
+
+        if let Some(nutrition) = &self.nutrition {
+            writeln!(f, "\nNutrition per serving:")?;
+            let facts = &nutrition.per_serving;
+            writeln!(f, "  Calories: {} kcal", format_amount(facts.calories))?;
+            writeln!(f, "  Protein: {} g", format_amount(facts.protein_g))?;
+            writeln!(
+                f,
+                "  Carbohydrates: {} g",
+                format_amount(facts.carbohydrates_g)
+            )?;
+            writeln!(f, "  Fat: {} g", format_amount(facts.fat_g))?;
+            if !nutrition.missing.is_empty() {
+                writeln!(f, "  (not counted: {})", nutrition.missing.join(", "))?;
+            }
         }
 
-        output
+        if !self.journal.is_empty() {
+            writeln!(f, "\nJournal:")?;
+            for note in &self.journal {
+                writeln!(f, "  {}", note)?;
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -65,14 +260,26 @@ mod tests {
                     ingredient_name: "flour".to_string(),
                     quantity_unit: "2 cups".to_string(),
                     notes: Some("all-purpose".to_string()),
+                    steps: Vec::new(),
                 },
                 RecipeIngredient {
                     ingredient_id: 2,
                     ingredient_name: "sugar".to_string(),
                     quantity_unit: "1 cup".to_string(),
                     notes: None,
+                    steps: Vec::new(),
                 },
             ],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
         };
 
         let output = recipe.to_string();
@@ -82,6 +289,51 @@ mod tests {
         assert!(output.contains("2 cups flour (all-purpose)"));
         assert!(output.contains("1 cup sugar"));
         assert!(output.contains("Mix and bake at 350°F for 12 minutes"));
+
+        let mut metric = recipe.clone();
+        metric.localize_quantities(QuantityFormat {
+            system: Some(crate::units::UnitSystem::Metric),
+            ..Default::default()
+        });
+        let output = metric.to_string();
+        assert!(output.contains("Mix and bake at 175°C for 12 minutes"));
+        assert!(!output.contains("2 cups flour"));
+    }
+
+    #[test]
+    fn test_recipe_to_string_with_nutrition() {
+        let recipe = Recipe {
+            id: 3,
+            name: "Porridge".to_string(),
+            created_at: "2024-01-15 08:00:00".to_string(),
+            servings: Some(2),
+            tags: vec!["breakfast".to_string(), "quick".to_string()],
+            rating: Some(RecipeRating {
+                average: 4.0 + 2.0 / 3.0,
+                latest: 5,
+                count: 3,
+            }),
+            nutrition: Some(RecipeNutrition {
+                servings: 2,
+                per_serving: crate::models::api::NutritionFacts {
+                    calories: 190.0,
+                    protein_g: 6.5,
+                    carbohydrates_g: 33.0,
+                    fat_g: 3.25,
+                },
+                missing: vec!["cinnamon".to_string()],
+            }),
+            ..Default::default()
+        };
+
+        let output = recipe.to_string();
+
+        assert!(output.contains("Serves: 2"));
+        assert!(output.contains("Tags: breakfast, quick\n"));
+        assert!(output.contains("Rating: 4.7/5 (latest 5, rated 3 times)\n"));
+        assert!(output.contains("Calories: 190 kcal"));
+        assert!(output.contains("Fat: 3.25 g"));
+        assert!(output.contains("(not counted: cinnamon)"));
     }
 
     #[test]
@@ -96,7 +348,18 @@ mod tests {
                 ingredient_name: "lettuce".to_string(),
                 quantity_unit: "1 head".to_string(),
                 notes: None,
+                steps: Vec::new(),
             }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
         };
 
         let output = recipe.to_string();
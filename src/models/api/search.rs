@@ -0,0 +1,55 @@
+/// A recipe matched by full-text search
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub recipe_id: i64,
+    pub name: String,
+    /// bm25 score; lower is a better match
+    pub rank: f64,
+}
+
+/// Which ingredients recipes must and mustn't contain
+/// Requirements are ANDed together; each one is satisfied by any of its
+/// ingredients, so `with("chicken").with_any(["rice", "quinoa"]).without("cilantro")`
+/// reads "chicken AND (rice OR quinoa) AND NOT cilantro". Names are matched
+/// whole, ignoring case
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IngredientQuery {
+    pub(crate) required: Vec<Vec<String>>,
+    pub(crate) excluded: Vec<String>,
+}
+
+impl IngredientQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recipes must contain this ingredient
+    pub fn with(mut self, ingredient: impl Into<String>) -> Self {
+        self.required.push(vec![ingredient.into()]);
+        self
+    }
+
+    /// Recipes must contain at least one of these ingredients
+    pub fn with_any<I, S>(mut self, ingredients: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let group: Vec<String> = ingredients.into_iter().map(Into::into).collect();
+        if !group.is_empty() {
+            self.required.push(group);
+        }
+        self
+    }
+
+    /// Recipes must not contain this ingredient
+    pub fn without(mut self, ingredient: impl Into<String>) -> Self {
+        self.excluded.push(ingredient.into());
+        self
+    }
+
+    /// Whether the query places no restrictions at all
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.excluded.is_empty()
+    }
+}
@@ -1,11 +1,184 @@
-#[derive(Debug, Clone)]
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::quantity::{QuantityFormat, localize_quantity};
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ShoppingListItem {
     pub ingredient_name: String,
     pub combined_quantity: String,
 }
 
 impl ShoppingListItem {
-    pub fn to_string(&self) -> String {
-        format!("{}: {}", self.ingredient_name, self.combined_quantity)
+    /// Rewrite the quantity for display
+    pub fn localize_quantities(&mut self, format: QuantityFormat) {
+        self.combined_quantity = localize_quantity(&self.combined_quantity, format);
+    }
+}
+
+impl fmt::Display for ShoppingListItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.ingredient_name, self.combined_quantity)
+    }
+}
+
+/// A saved (or about to be saved) shopping list
+#[derive(Debug, Clone, Serialize)]
+pub struct ShoppingList {
+    /// 0 for lists that haven't been saved yet
+    pub id: i64,
+    pub name: Option<String>,
+    /// Plan the list was generated from
+    pub meal_plan_id: Option<i64>,
+    /// Day the shopping was done, None while still to buy
+    pub purchased_on: Option<NaiveDate>,
+    /// Store whose layout the items are ordered by, None for alphabetical
+    pub store: Option<String>,
+    pub items: Vec<ShoppingListEntry>,
+}
+
+/// One ingredient on a saved shopping list
+#[derive(Debug, Clone, Serialize)]
+pub struct ShoppingListEntry {
+    pub ingredient_id: i64,
+    pub ingredient_name: String,
+    pub quantity: String,
+    /// Already in the kitchen; shown for reference but not to be bought
+    pub already_have: bool,
+    /// Checked off while shopping, and already added to the pantry
+    pub purchased: bool,
+    /// Cost at the latest recorded price, None when unpriced
+    pub estimated_cost: Option<f64>,
+    /// Lowercased store section the ingredient is in, None when unassigned
+    pub aisle: Option<String>,
+    /// Emoji shown before the name, None when unset
+    pub icon: Option<String>,
+}
+
+/// What a phone posts back while or after shopping, e.g.
+/// `{"list": 7, "bought": ["flour", "basil"], "purchased_on": "2026-10-17"}`
+/// Items are named as on the list, ignoring case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShoppingCheckOff {
+    /// Saved shopping list ID
+    pub list: i64,
+    pub bought: Vec<String>,
+    /// Day of the trip, None to leave the list's date as it is
+    #[serde(default)]
+    pub purchased_on: Option<NaiveDate>,
+}
+
+/// What applying a [`ShoppingCheckOff`] changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ShoppingSync {
+    /// Items newly checked off; ones checked off by an earlier sync aren't repeated
+    pub checked_off: Vec<String>,
+    /// Pantry items added for them
+    pub stocked: usize,
+    /// Names that aren't on the list
+    pub not_on_list: Vec<String>,
+}
+
+/// The order a store's aisles are walked in
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoreLayout {
+    pub store: String,
+    /// Lowercased aisle names, first visited first
+    pub aisles: Vec<String>,
+}
+
+impl StoreLayout {
+    /// Lowercases the aisle names
+    pub fn new(store: impl Into<String>, aisles: &[String]) -> Self {
+        Self {
+            store: store.into(),
+            aisles: aisles.iter().map(|aisle| aisle.to_lowercase()).collect(),
+        }
+    }
+
+    /// Where an aisle comes in the walk; aisles the store doesn't list come
+    /// after the listed ones, and items without an aisle last
+    fn position(&self, aisle: Option<&str>) -> usize {
+        match aisle {
+            Some(aisle) => self
+                .aisles
+                .iter()
+                .position(|listed| listed == aisle)
+                .unwrap_or(self.aisles.len()),
+            None => self.aisles.len() + 1,
+        }
+    }
+}
+
+impl ShoppingList {
+    /// Items that still need to be bought
+    pub fn to_buy(&self) -> impl Iterator<Item = &ShoppingListEntry> {
+        self.items
+            .iter()
+            .filter(|item| !item.already_have && !item.purchased)
+    }
+
+    /// Order the items by a store's aisles, keeping them alphabetical within
+    /// an aisle
+    pub fn arrange(&mut self, layout: &StoreLayout) {
+        self.items.sort_by(|a, b| {
+            let a_position = layout.position(a.aisle.as_deref());
+            let b_position = layout.position(b.aisle.as_deref());
+            a_position
+                .cmp(&b_position)
+                .then_with(|| a.aisle.cmp(&b.aisle))
+                .then_with(|| a.ingredient_name.cmp(&b.ingredient_name))
+        });
+        self.store = Some(layout.store.clone());
+    }
+
+    /// Estimated cost of the items still to buy, leaving out unpriced ones
+    pub fn estimated_total(&self) -> f64 {
+        self.to_buy()
+            .filter_map(|item| item.estimated_cost)
+            .fold(0.0, |total, cost| total + cost)
+    }
+
+    /// Rewrite every item's quantity for display
+    pub fn localize_quantities(&mut self, format: QuantityFormat) {
+        for item in &mut self.items {
+            item.quantity = localize_quantity(&item.quantity, format);
+        }
+    }
+}
+
+impl fmt::Display for ShoppingList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "{}", name)?;
+        }
+
+        let mut aisle = None;
+        for item in &self.items {
+            if self.store.is_some() && aisle != Some(&item.aisle) {
+                aisle = Some(&item.aisle);
+                writeln!(f, "{}", item.aisle.as_deref().unwrap_or("other"))?;
+            }
+            write!(f, "  ")?;
+            if let Some(icon) = &item.icon {
+                write!(f, "{} ", icon)?;
+            }
+            write!(f, "{}: {}", item.ingredient_name, item.quantity)?;
+            if item.already_have {
+                write!(f, " (already have)")?;
+            } else if item.purchased {
+                write!(f, " (bought)")?;
+            } else if let Some(cost) = item.estimated_cost {
+                write!(f, " (~{:.2})", cost)?;
+            }
+            writeln!(f)?;
+        }
+
+        if self.to_buy().any(|item| item.estimated_cost.is_some()) {
+            writeln!(f, "  Estimated total: {:.2}", self.estimated_total())?;
+        }
+
+        Ok(())
     }
 }
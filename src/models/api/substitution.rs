@@ -0,0 +1,43 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A way to stand in for an ingredient with others
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Substitution {
+    pub id: i64,
+    pub ingredient_id: i64,
+    pub ingredient_name: String,
+    /// Ingredients used instead
+    pub substitute_ids: Vec<i64>,
+    /// Names of `substitute_ids`, in the same order
+    pub substitutes: Vec<String>,
+    /// Ratio or method, e.g. "1 tbsp lemon juice per cup of milk"
+    pub note: Option<String>,
+}
+
+impl Substitution {
+    /// Whether every substitute is among `ingredient_ids`
+    pub fn can_make(&self, ingredient_ids: &HashSet<i64>) -> bool {
+        self.substitute_ids
+            .iter()
+            .all(|id| ingredient_ids.contains(id))
+    }
+}
+
+impl fmt::Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {}",
+            self.ingredient_name,
+            self.substitutes.join(" + ")
+        )?;
+
+        if let Some(note) = &self.note {
+            write!(f, " ({})", note)?;
+        }
+
+        Ok(())
+    }
+}
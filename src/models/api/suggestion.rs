@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::Visibility;
+
+/// What to optimise for in `suggest_recipes`
+#[derive(Debug, Clone)]
+pub struct SuggestionCriteria {
+    /// Day the meal is for; recency is measured back from here
+    pub date: NaiveDate,
+    /// Only recipes whose prep + cook minutes are known and within this
+    pub max_minutes: Option<i64>,
+    /// Tags a recipe must carry to be considered
+    pub required_tags: Vec<String>,
+    /// Tags that raise a recipe's score ("what we're in the mood for")
+    pub preferred_tags: Vec<String>,
+    /// Leave out recipes cooked on `date` or this many days before it
+    pub skip_cooked_within_days: Option<u64>,
+    /// Maximum number of suggestions returned
+    pub limit: usize,
+    /// Who is looking, as in `RecipeFilter::audience`; None suggests
+    /// every recipe
+    pub audience: Option<Visibility>,
+}
+
+impl SuggestionCriteria {
+    /// Five suggestions for `date` with no other restrictions
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            max_minutes: None,
+            required_tags: Vec::new(),
+            preferred_tags: Vec::new(),
+            skip_cooked_within_days: None,
+            limit: 5,
+            audience: None,
+        }
+    }
+}
+
+/// A recipe recommended by `suggest_recipes`
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub recipe_id: i64,
+    pub name: String,
+    /// Higher is better; between 0 and 3
+    pub score: f64,
+    /// Most recent day it was cooked or planned
+    pub last_used: Option<NaiveDate>,
+    pub total_minutes: Option<i64>,
+    pub average_rating: Option<f64>,
+}
+
+/// A recipe resembling another, found by `similar_recipes`
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarRecipe {
+    pub recipe_id: i64,
+    pub name: String,
+    /// Higher is more alike; between 0 and 1.5
+    pub score: f64,
+    /// Ingredients both recipes use, by name
+    pub shared_ingredients: Vec<String>,
+    /// Tags both recipes carry, lowercased
+    pub shared_tags: Vec<String>,
+}
@@ -0,0 +1,86 @@
+use sqlx::prelude::FromRow;
+
+/// The kind of change recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditLogRecord {
+    pub id: i64,
+    pub entity: String,
+    pub entity_id: i64,
+    pub action: String,
+    pub actor: Option<String>,
+    /// JSON snapshot before the change (None for creates)
+    pub old_value: Option<String>,
+    /// JSON snapshot after the change (None for deletes)
+    pub new_value: Option<String>,
+    pub created_at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+    use sqlx::SqlitePool;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_audit_log_model_compatibility(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        sqlx::query(
+            "INSERT INTO audit_log (entity, entity_id, action, actor, new_value) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind("recipe")
+        .bind(1)
+        .bind(AuditAction::Create.as_str())
+        .bind("alice")
+        .bind(r#"{"name":"Soup"}"#)
+        .execute(&pool)
+        .await
+        .expect("Failed to insert audit entry");
+
+        let entry = sqlx::query_as::<_, AuditLogRecord>(
+            "SELECT id, entity, entity_id, action, actor, old_value, new_value, created_at FROM audit_log",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch audit entry");
+
+        assert_eq!(entry.entity, "recipe");
+        assert_eq!(entry.action, "create");
+        assert_eq!(entry.actor, Some("alice".to_string()));
+        assert_eq!(entry.old_value, None);
+        assert!(!entry.created_at.is_empty());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_audit_log_rejects_unknown_action(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let result = sqlx::query(
+            "INSERT INTO audit_log (entity, entity_id, action) VALUES ('recipe', 1, 'rename')",
+        )
+        .execute(&pool)
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,8 @@
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct CollectionRecord {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+}
@@ -0,0 +1,12 @@
+use chrono::NaiveDate;
+use sqlx::prelude::FromRow;
+
+/// One occasion a recipe was cooked
+#[derive(Debug, Clone, FromRow)]
+pub struct CookLogRecord {
+    pub id: i64,
+    pub recipe_id: i64,
+    pub cooked_on: NaiveDate,
+    pub notes: Option<String>,
+    pub created_at: String,
+}
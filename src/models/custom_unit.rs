@@ -0,0 +1,25 @@
+use sqlx::prelude::FromRow;
+
+use crate::units::{CustomUnit, Unit};
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct CustomUnitRecord {
+    pub id: i64,
+    /// Lowercased singular name
+    pub name: String,
+    /// Ingredient the unit is scoped to, None for every ingredient
+    pub ingredient_id: Option<i64>,
+    /// How much of `unit` one of these is
+    pub amount: f64,
+    pub unit: String,
+}
+
+impl CustomUnitRecord {
+    pub fn to_custom_unit(&self) -> CustomUnit {
+        CustomUnit {
+            name: self.name.clone(),
+            amount: self.amount,
+            unit: Unit::parse(&self.unit),
+        }
+    }
+}
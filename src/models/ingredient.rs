@@ -12,7 +12,7 @@ mod tests {
     use super::*;
     use crate::models::test_fixtures::test_db;
     use rstest::*;
-    use sqlx::SqlitePool;
+    use sqlx::{Row, SqlitePool};
 
     #[rstest]
     #[tokio::test]
@@ -40,4 +40,28 @@ mod tests {
         assert!(ingredient.id > 0);
         assert!(!ingredient.created_at.is_empty());
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_case_insensitive_name_lookup_uses_index(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let plan: Vec<String> = sqlx::query(
+            "EXPLAIN QUERY PLAN SELECT id FROM ingredients WHERE name = ? COLLATE NOCASE",
+        )
+        .bind("Flour")
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to explain query")
+        .iter()
+        .map(|row| row.get("detail"))
+        .collect();
+
+        assert!(
+            plan.iter()
+                .any(|d| d.contains("idx_ingredients_name_nocase")),
+            "Expected index usage, got {:?}",
+            plan
+        );
+    }
 }
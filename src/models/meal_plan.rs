@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct MealPlanRecord {
+    pub id: i64,
+    pub name: Option<String>,
+    pub start_date: NaiveDate,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct MealPlanEntryRecord {
+    pub id: i64,
+    pub meal_plan_id: i64,
+    pub planned_on: NaiveDate,
+    pub meal: String,
+    pub recipe_id: i64,
+    /// Multiplier applied to the recipe's quantities
+    pub servings: f64,
+    pub note: Option<String>,
+}
+
+/// A reusable plan whose entries are stored as offsets from day one
+#[derive(Debug, Clone, FromRow)]
+pub struct MealPlanTemplateRecord {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+    use sqlx::SqlitePool;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_meal_plan_entry_model_compatibility(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let recipe_id = sqlx::query("INSERT INTO recipes (name) VALUES ('Soup')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        let plan_id = sqlx::query("INSERT INTO meal_plans (start_date) VALUES ('2026-10-19')")
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        sqlx::query(
+            "INSERT INTO meal_plan_entries (meal_plan_id, planned_on, recipe_id) VALUES (?, '2026-10-20', ?)",
+        )
+        .bind(plan_id)
+        .bind(recipe_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let plan = sqlx::query_as::<_, MealPlanRecord>(
+            "SELECT id, name, start_date, created_at FROM meal_plans WHERE id = ?",
+        )
+        .bind(plan_id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch plan");
+        assert_eq!(
+            plan.start_date,
+            NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+        );
+        assert_eq!(plan.name, None);
+
+        let entry = sqlx::query_as::<_, MealPlanEntryRecord>(
+            "SELECT id, meal_plan_id, planned_on, meal, recipe_id, servings, note FROM meal_plan_entries",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch entry");
+
+        // Column defaults
+        assert_eq!(entry.meal, "dinner");
+        assert_eq!(entry.servings, 1.0);
+        assert_eq!(
+            entry.planned_on,
+            NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()
+        );
+    }
+}
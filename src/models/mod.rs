@@ -1,12 +1,32 @@
+mod audit_log;
+mod collection;
+mod cook_log;
+mod custom_unit;
 mod ingredient;
+mod meal_plan;
+mod nutrition;
+mod pantry;
 mod recipe;
+mod recipe_image;
 mod recipe_ingredient;
+mod shopping_list;
+mod tag;
 
 pub mod api;
 
 #[cfg(test)]
 pub mod test_fixtures;
 
+pub use audit_log::{AuditAction, AuditLogRecord};
+pub use collection::CollectionRecord;
+pub use cook_log::CookLogRecord;
+pub use custom_unit::CustomUnitRecord;
 pub use ingredient::IngredientRecord;
+pub use meal_plan::{MealPlanEntryRecord, MealPlanRecord, MealPlanTemplateRecord};
+pub use nutrition::IngredientNutritionRecord;
+pub use pantry::PantryItemRecord;
 pub use recipe::RecipeRecord;
+pub use recipe_image::RecipeImageRecord;
 pub use recipe_ingredient::RecipeIngredientRecord;
+pub use shopping_list::ShoppingListRecord;
+pub use tag::TagRecord;
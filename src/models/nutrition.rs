@@ -0,0 +1,13 @@
+use sqlx::prelude::FromRow;
+
+/// Nutrition facts for an ingredient, per 100 g
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct IngredientNutritionRecord {
+    pub ingredient_id: i64,
+    pub calories: f64,
+    pub protein_g: f64,
+    pub carbohydrates_g: f64,
+    pub fat_g: f64,
+    /// Weight of one item, for quantities without a unit ("2 eggs")
+    pub grams_per_unit: Option<f64>,
+}
@@ -0,0 +1,17 @@
+use chrono::NaiveDate;
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PantryItemRecord {
+    pub id: i64,
+    pub ingredient_id: i64,
+    pub amount: f64,
+    /// Lowercased unit, None for counts
+    pub unit: Option<String>,
+    pub expires_on: Option<NaiveDate>,
+    /// Always kept in stock
+    pub is_staple: bool,
+    /// Restock when stock falls below this, in `unit`
+    pub min_amount: Option<f64>,
+    pub created_at: String,
+}
@@ -5,6 +5,12 @@ pub struct RecipeRecord {
     pub id: i64,
     pub name: String,
     pub instructions: Option<String>,
+    /// Number of servings the recipe makes, None when unknown
+    pub servings: Option<i64>,
+    /// Page the recipe was imported from
+    pub source_url: Option<String>,
+    /// Who wrote it (a blog, book or person)
+    pub source_name: Option<String>,
     pub created_at: String,
 }
 
@@ -21,16 +27,17 @@ mod tests {
         let pool = test_db.await;
 
         // Insert a test recipe with instructions
-        sqlx::query("INSERT INTO recipes (name, instructions) VALUES (?, ?)")
+        sqlx::query("INSERT INTO recipes (name, instructions, servings) VALUES (?, ?, ?)")
             .bind("Test Recipe")
             .bind("Cook it well")
+            .bind(4)
             .execute(&pool)
             .await
             .expect("Failed to insert recipe");
 
         // Query and map to RecipeRecord struct
         let recipe = sqlx::query_as::<_, RecipeRecord>(
-            "SELECT id, name, instructions, created_at FROM recipes WHERE name = ?",
+            "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE name = ?",
         )
         .bind("Test Recipe")
         .fetch_one(&pool)
@@ -40,6 +47,7 @@ mod tests {
         // Verify the model fields match
         assert_eq!(recipe.name, "Test Recipe");
         assert_eq!(recipe.instructions, Some("Cook it well".to_string()));
+        assert_eq!(recipe.servings, Some(4));
         assert!(recipe.id > 0);
         assert!(!recipe.created_at.is_empty());
     }
@@ -58,7 +66,7 @@ mod tests {
 
         // Query and map to RecipeRecord struct
         let recipe = sqlx::query_as::<_, RecipeRecord>(
-            "SELECT id, name, instructions, created_at FROM recipes WHERE name = ?",
+            "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE name = ?",
         )
         .bind("Simple Recipe")
         .fetch_one(&pool)
@@ -68,6 +76,7 @@ mod tests {
         // Verify the model handles NULL instructions
         assert_eq!(recipe.name, "Simple Recipe");
         assert_eq!(recipe.instructions, None);
+        assert_eq!(recipe.servings, None);
         assert!(recipe.id > 0);
         assert!(!recipe.created_at.is_empty());
     }
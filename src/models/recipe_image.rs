@@ -0,0 +1,14 @@
+use sqlx::prelude::FromRow;
+
+/// An image attached to a recipe, without its bytes
+#[derive(Debug, Clone, FromRow)]
+pub struct RecipeImageRecord {
+    pub id: i64,
+    pub recipe_id: i64,
+    pub file_name: String,
+    /// MIME type, e.g. "image/jpeg"
+    pub content_type: String,
+    /// Size in bytes
+    pub size: i64,
+    pub created_at: String,
+}
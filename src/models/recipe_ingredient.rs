@@ -6,6 +6,9 @@ pub struct RecipeIngredientRecord {
     pub recipe_id: i64,
     pub ingredient_id: i64,
     pub quantity_unit: String,
+    /// Parsed from quantity_unit, None if it couldn't be parsed
+    pub amount: Option<f64>,
+    pub unit: Option<String>,
     pub notes: Option<String>,
     pub created_at: String,
 }
@@ -51,7 +54,7 @@ mod tests {
 
         // Query and map to RecipeIngredientRecord struct
         let recipe_ingredient = sqlx::query_as::<_, RecipeIngredientRecord>(
-            "SELECT id, recipe_id, ingredient_id, quantity_unit, notes, created_at FROM recipe_ingredients WHERE recipe_id = ?"
+            "SELECT id, recipe_id, ingredient_id, quantity_unit, amount, unit, notes, created_at FROM recipe_ingredients WHERE recipe_id = ?"
         )
         .bind(recipe_id)
         .fetch_one(&pool)
@@ -62,6 +65,8 @@ mod tests {
         assert_eq!(recipe_ingredient.recipe_id, recipe_id);
         assert_eq!(recipe_ingredient.ingredient_id, ingredient_id);
         assert_eq!(recipe_ingredient.quantity_unit, "2 cups");
+        assert_eq!(recipe_ingredient.amount, None);
+        assert_eq!(recipe_ingredient.unit, None);
         assert_eq!(recipe_ingredient.notes, Some("diced".to_string()));
         assert!(recipe_ingredient.id > 0);
         assert!(!recipe_ingredient.created_at.is_empty());
@@ -100,7 +105,7 @@ mod tests {
 
         // Query and map to RecipeIngredientRecord struct
         let recipe_ingredient = sqlx::query_as::<_, RecipeIngredientRecord>(
-            "SELECT id, recipe_id, ingredient_id, quantity_unit, notes, created_at FROM recipe_ingredients WHERE recipe_id = ?"
+            "SELECT id, recipe_id, ingredient_id, quantity_unit, amount, unit, notes, created_at FROM recipe_ingredients WHERE recipe_id = ?"
         )
         .bind(recipe_id)
         .fetch_one(&pool)
@@ -0,0 +1,13 @@
+use chrono::NaiveDate;
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ShoppingListRecord {
+    pub id: i64,
+    pub name: Option<String>,
+    pub meal_plan_id: Option<i64>,
+    pub purchased_on: Option<NaiveDate>,
+    /// Items have been added to the pantry
+    pub stocked: bool,
+    pub created_at: String,
+}
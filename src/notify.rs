@@ -0,0 +1,132 @@
+//! Delivery of meal plan reminders
+//!
+//! `feedme notify` is meant to be run from cron once a day: it looks up the
+//! notes on upcoming planned meals and hands them to every configured channel.
+
+use std::process::Command;
+
+use crate::config::NotifyConfig;
+use crate::error::{FeedMeError, Result};
+use crate::models::api::Reminder;
+
+/// Title shown on desktop notifications
+const NOTIFICATION_TITLE: &str = "FeedMe";
+
+/// Send reminders to every channel enabled in `config`
+/// Returns the number of channels that were notified
+pub fn deliver(config: &NotifyConfig, reminders: &[Reminder]) -> Result<usize> {
+    if reminders.is_empty() {
+        return Ok(0);
+    }
+
+    let mut channels = 0;
+
+    if config.desktop {
+        for reminder in reminders {
+            notify_desktop(&reminder.to_string())?;
+        }
+        channels += 1;
+    }
+
+    if let Some(url) = &config.webhook_url {
+        post_webhook(url, reminders)?;
+        channels += 1;
+    }
+
+    Ok(channels)
+}
+
+/// Show a desktop notification using the platform's command-line tool
+fn notify_desktop(message: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {:?} with title {:?}",
+            message, NOTIFICATION_TITLE
+        ));
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.arg(NOTIFICATION_TITLE).arg(message);
+        command
+    };
+
+    let status = command.status()?;
+    if !status.success() {
+        return Err(FeedMeError::Notification(format!(
+            "desktop notifier exited with {}",
+            status
+        )));
+    }
+
+    Ok(())
+}
+
+/// POST the reminders as a JSON array
+#[cfg(feature = "webhooks")]
+fn post_webhook(url: &str, reminders: &[Reminder]) -> Result<()> {
+    ureq::post(url)
+        .send_json(reminders)
+        .map_err(|e| FeedMeError::Notification(format!("{}: {}", url, e)))?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "webhooks"))]
+fn post_webhook(_url: &str, _reminders: &[Reminder]) -> Result<()> {
+    Err(FeedMeError::Config(
+        "notify.webhook_url requires building with `--features webhooks`".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn reminder() -> Reminder {
+        Reminder {
+            date: NaiveDate::from_ymd_opt(2026, 10, 20).unwrap(),
+            meal: "dinner".to_string(),
+            recipe_id: 1,
+            recipe_name: "Lemon Garlic Chicken".to_string(),
+            note: "defrost the chicken".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_nothing_to_deliver() {
+        let config = NotifyConfig {
+            webhook_url: Some("http://localhost:1/unused".to_string()),
+            ..NotifyConfig::default()
+        };
+
+        assert_eq!(deliver(&config, &[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_no_channels_enabled() {
+        let config = NotifyConfig {
+            desktop: false,
+            webhook_url: None,
+            days_ahead: 1,
+        };
+
+        assert_eq!(deliver(&config, &[reminder()]).unwrap(), 0);
+    }
+
+    #[cfg(not(feature = "webhooks"))]
+    #[test]
+    fn test_webhook_needs_feature() {
+        let config = NotifyConfig {
+            desktop: false,
+            webhook_url: Some("http://localhost:1/hook".to_string()),
+            days_ahead: 1,
+        };
+
+        assert!(matches!(
+            deliver(&config, &[reminder()]),
+            Err(FeedMeError::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,976 @@
+//! PostgreSQL backing for the `crate::store` traits
+//!
+//! A starting point for households running feedme as a shared server, not a
+//! mirror of `crate::controllers`: it covers what `FeedMeStore` does
+//! (ingredients, recipes with their ingredient lines, tags, the pantry, meal
+//! plans, saved shopping lists, collections and the cook log) and nothing
+//! else. The trash can only be filled, not restored or purged, and search is a
+//! plain substring match in place of the FTS5 index. Compared to the SQLite
+//! controllers, writes here aren't validated or recorded in an audit log;
+//! recipes come back without steps, journal, tags, rating, equipment or
+//! nutrition; there are no aisles, icons or prices on shopping lists and the
+//! pantry, no substitutions in `cookable_recipes`, and no densities or custom
+//! units when summing quantities. Reports, imports and exports have no
+//! Postgres version. Schema lives in `migrations_postgres/` and only holds the
+//! tables used here.
+
+use chrono::NaiveDate;
+use sqlx::{FromRow, PgConnection, PgPool, Postgres, QueryBuilder, Row};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::controllers::{CoverageRow, cover_recipes};
+use crate::error::{FeedMeError, OnDuplicate, Result};
+use crate::models::api::{
+    Collection, CollectionRecipe, CookableRecipe, MealPlan, PantryItem, PlannedMeal, Recipe,
+    RecipeIngredient, RecipeNote, SearchResult, ShoppingList, ShoppingListEntry, ShoppingListItem,
+    Visibility,
+};
+use crate::models::{
+    CollectionRecord, CookLogRecord, IngredientRecord, MealPlanRecord, PantryItemRecord,
+    RecipeRecord, ShoppingListRecord,
+};
+use crate::quantity::{QuantityTotal, parse_quantity};
+use crate::timers::step_timers;
+
+/// Run the PostgreSQL migration set against the pool
+pub async fn run_migrations(pool: &PgPool) -> Result<()> {
+    sqlx::migrate!("./migrations_postgres")
+        .run(pool)
+        .await
+        .map_err(sqlx::Error::from)?;
+
+    Ok(())
+}
+
+/// Create a new ingredient
+/// Returns the ingredient ID
+pub async fn create_ingredient(pool: &PgPool, name: &str) -> Result<i64> {
+    let ingredient_id =
+        sqlx::query_scalar("INSERT INTO ingredients (name) VALUES ($1) RETURNING id")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .on_duplicate(|| FeedMeError::DuplicateIngredient(name.to_string()))?;
+
+    Ok(ingredient_id)
+}
+
+/// Get all ingredients from the database ordered by name
+pub async fn get_all_ingredients(pool: &PgPool) -> Result<Vec<IngredientRecord>> {
+    let ingredients = sqlx::query_as::<_, IngredientRecord>(
+        "SELECT id, name, created_at FROM ingredients ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ingredients)
+}
+
+/// Fetch a recipe by ID with all its ingredients
+/// Only the recipe row, its ingredient lines and the timers read from its
+/// instructions are filled in; the rest of `Recipe` is left empty
+pub async fn get_recipe(pool: &PgPool, recipe_id: i64) -> Result<Recipe> {
+    let recipe = sqlx::query_as::<_, RecipeRecord>(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(recipe_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(FeedMeError::RecipeNotFound(recipe_id))?;
+
+    let ingredients = ingredient_rows(pool, &[recipe_id])
+        .await?
+        .into_iter()
+        .map(|row| RecipeIngredient {
+            ingredient_id: row.ingredient_id,
+            ingredient_name: row.ingredient_name,
+            quantity_unit: row.quantity_unit,
+            notes: row.notes,
+            steps: Vec::new(),
+        })
+        .collect();
+
+    Ok(Recipe {
+        id: recipe.id,
+        name: recipe.name,
+        timers: step_timers(recipe.instructions.as_deref().unwrap_or("")),
+        instructions: recipe.instructions,
+        created_at: recipe.created_at,
+        ingredients,
+        servings: recipe.servings,
+        journal: Vec::new(),
+        tags: Vec::new(),
+        rating: None,
+        equipment: Vec::new(),
+        visibility: Visibility::default(),
+        source_url: recipe.source_url,
+        source_name: recipe.source_name,
+        nutrition: None,
+    })
+}
+
+/// Create a new recipe linked to existing ingredients by ID
+/// Stored as given: unlike `controllers::create_recipe` nothing is validated
+/// or parsed and no audit entry is written
+pub async fn create_recipe(pool: &PgPool, recipe: &Recipe) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let recipe_id: i64 = sqlx::query_scalar(
+        "INSERT INTO recipes (name, instructions, servings, source_url, source_name) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+    )
+    .bind(&recipe.name)
+    .bind(&recipe.instructions)
+    .bind(recipe.servings)
+    .bind(&recipe.source_url)
+    .bind(&recipe.source_name)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    insert_recipe_ingredients(&mut tx, recipe_id, &recipe.ingredients).await?;
+
+    tx.commit().await?;
+
+    Ok(recipe_id)
+}
+
+/// Replace a recipe's name, instructions, servings, source and ingredients
+/// in one transaction. Stored as given, like `create_recipe`
+pub async fn update_recipe(pool: &PgPool, recipe_id: i64, recipe: &Recipe) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        "UPDATE recipes SET name = $1, instructions = $2, servings = $3, source_url = $4, source_name = $5 WHERE id = $6 AND deleted_at IS NULL",
+    )
+    .bind(&recipe.name)
+    .bind(&recipe.instructions)
+    .bind(recipe.servings)
+    .bind(&recipe.source_url)
+    .bind(&recipe.source_name)
+    .bind(recipe_id)
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::RecipeNotFound(recipe_id));
+    }
+
+    sqlx::query("DELETE FROM recipe_ingredients WHERE recipe_id = $1")
+        .bind(recipe_id)
+        .execute(&mut *tx)
+        .await?;
+    insert_recipe_ingredients(&mut tx, recipe_id, &recipe.ingredients).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Move a recipe to the trash, hiding it from `get_recipe`, `list_recipes`
+/// and `search_recipes`
+pub async fn delete_recipe(pool: &PgPool, recipe_id: i64) -> Result<()> {
+    let result = sqlx::query(
+        "UPDATE recipes SET deleted_at = to_char(now(), 'YYYY-MM-DD HH24:MI:SS') WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(recipe_id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::RecipeNotFound(recipe_id));
+    }
+
+    Ok(())
+}
+
+/// Every recipe outside the trash, ordered by ID
+pub async fn list_recipes(pool: &PgPool) -> Result<Vec<RecipeRecord>> {
+    let recipes = sqlx::query_as::<_, RecipeRecord>(
+        "SELECT id, name, instructions, servings, source_url, source_name, created_at FROM recipes WHERE deleted_at IS NULL ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(recipes)
+}
+
+/// Recipes whose name, instructions, ingredient names or notes contain every
+/// whitespace-separated term of `query`, ignoring case
+/// There's no relevance score like SQLite's bm25: `rank` counts the terms
+/// missing from the name, so name matches come first
+pub async fn search_recipes(pool: &PgPool, query: &str) -> Result<Vec<SearchResult>> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut builder: QueryBuilder<Postgres> =
+        QueryBuilder::new("SELECT r.id, r.name FROM recipes r WHERE r.deleted_at IS NULL");
+    for term in &terms {
+        let pattern = format!(
+            "%{}%",
+            term.replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        builder.push(" AND (r.name ILIKE ");
+        builder.push_bind(pattern.clone());
+        builder.push(" OR r.instructions ILIKE ");
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " OR EXISTS (SELECT 1 FROM recipe_ingredients ri JOIN ingredients i ON ri.ingredient_id = i.id WHERE ri.recipe_id = r.id AND (i.name ILIKE ",
+        );
+        builder.push_bind(pattern.clone());
+        builder.push(" OR ri.notes ILIKE ");
+        builder.push_bind(pattern);
+        builder.push(")))");
+    }
+
+    let rows: Vec<(i64, String)> = builder.build_query_as().fetch_all(pool).await?;
+    let mut results: Vec<SearchResult> = rows
+        .into_iter()
+        .map(|(recipe_id, name)| {
+            let lowered = name.to_lowercase();
+            SearchResult {
+                rank: terms.iter().filter(|term| !lowered.contains(*term)).count() as f64,
+                recipe_id,
+                name,
+            }
+        })
+        .collect();
+    results.sort_by(|a, b| {
+        a.rank
+            .total_cmp(&b.rank)
+            .then(a.recipe_id.cmp(&b.recipe_id))
+    });
+
+    Ok(results)
+}
+
+/// Add a recipe's ingredient lines, in order, filling in the parsed
+/// `amount`/`unit` columns like the SQLite controllers do
+async fn insert_recipe_ingredients(
+    conn: &mut PgConnection,
+    recipe_id: i64,
+    ingredients: &[RecipeIngredient],
+) -> Result<()> {
+    for ingredient in ingredients {
+        let parsed = parse_quantity(&ingredient.quantity_unit);
+        sqlx::query(
+            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, quantity_unit, amount, unit, notes) VALUES ($1, $2, $3, $4, $5, $6)"
+        )
+        .bind(recipe_id)
+        .bind(ingredient.ingredient_id)
+        .bind(&ingredient.quantity_unit)
+        .bind(parsed.as_ref().map(|p| p.amount))
+        .bind(parsed.and_then(|p| p.unit))
+        .bind(&ingredient.notes)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Generate a shopping list from multiple recipes
+/// Combines ingredients with the same name, concatenating their quantities
+pub async fn generate_shopping_list(
+    pool: &PgPool,
+    recipe_ids: &[i64],
+) -> Result<Vec<ShoppingListItem>> {
+    if recipe_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rows = ingredient_rows(pool, recipe_ids).await?;
+
+    let mut ingredient_map: HashMap<String, Vec<String>> = HashMap::new();
+
+    for row in rows {
+        ingredient_map
+            .entry(row.ingredient_name)
+            .or_default()
+            .push(row.quantity_unit);
+    }
+
+    let mut shopping_list: Vec<ShoppingListItem> = ingredient_map
+        .into_iter()
+        .map(|(ingredient_name, quantities)| ShoppingListItem {
+            ingredient_name,
+            combined_quantity: quantities.join(" + "),
+        })
+        .collect();
+
+    shopping_list.sort_by(|a, b| a.ingredient_name.cmp(&b.ingredient_name));
+
+    Ok(shopping_list)
+}
+
+/// Attach a tag to a recipe, creating the tag if needed
+/// Tag names are trimmed and matched case-insensitively; tagging twice is a no-op
+pub async fn add_recipe_tag(pool: &PgPool, recipe_id: i64, tag: &str) -> Result<()> {
+    let tag = tag.trim();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("INSERT INTO tags (name) VALUES ($1) ON CONFLICT DO NOTHING")
+        .bind(tag)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO recipe_tags (recipe_id, tag_id) SELECT $1, id FROM tags WHERE lower(name) = lower($2) ON CONFLICT DO NOTHING",
+    )
+    .bind(recipe_id)
+    .bind(tag)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Detach a tag from a recipe
+/// Returns whether the recipe had the tag
+pub async fn remove_recipe_tag(pool: &PgPool, recipe_id: i64, tag: &str) -> Result<bool> {
+    let result = sqlx::query(
+        "DELETE FROM recipe_tags WHERE recipe_id = $1 AND tag_id = (SELECT id FROM tags WHERE lower(name) = lower($2))",
+    )
+    .bind(recipe_id)
+    .bind(tag.trim())
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Tags on a recipe, ordered by name ignoring case
+pub async fn get_recipe_tags(pool: &PgPool, recipe_id: i64) -> Result<Vec<String>> {
+    let tags = sqlx::query_scalar(
+        r#"
+        SELECT t.name
+        FROM recipe_tags rt
+        JOIN tags t ON rt.tag_id = t.id
+        WHERE rt.recipe_id = $1
+        ORDER BY lower(t.name)
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tags)
+}
+
+/// Record that a recipe was cooked on a given day
+/// Returns the cook log entry ID
+pub async fn log_cook(
+    pool: &PgPool,
+    recipe_id: i64,
+    cooked_on: NaiveDate,
+    notes: Option<&str>,
+) -> Result<i64> {
+    let id = sqlx::query_scalar(
+        "INSERT INTO cook_log (recipe_id, cooked_on, notes) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(recipe_id)
+    .bind(cooked_on)
+    .bind(notes)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Every time a recipe was cooked, most recent first
+pub async fn get_cook_log(pool: &PgPool, recipe_id: i64) -> Result<Vec<CookLogRecord>> {
+    let entries = sqlx::query_as::<_, CookLogRecord>(
+        r#"
+        SELECT id, recipe_id, cooked_on, notes, created_at
+        FROM cook_log
+        WHERE recipe_id = $1
+        ORDER BY cooked_on DESC, id DESC
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Write a journal entry about one time a recipe was cooked
+/// Returns the note ID, or `CookLogEntryNotFound` if the cook log entry
+/// doesn't exist
+pub async fn add_recipe_note(pool: &PgPool, note: &RecipeNote) -> Result<i64> {
+    let id: Option<i64> = sqlx::query_scalar(
+        r#"
+        INSERT INTO recipe_notes (cook_log_id, noted_on, text, rating_delta, modifications)
+        SELECT id, $1, $2, $3, $4 FROM cook_log WHERE id = $5
+        RETURNING id
+        "#,
+    )
+    .bind(note.noted_on)
+    .bind(note.text.trim())
+    .bind(note.rating_delta)
+    .bind(&note.modifications)
+    .bind(note.cook_log_id)
+    .fetch_optional(pool)
+    .await?;
+
+    id.ok_or(FeedMeError::CookLogEntryNotFound(note.cook_log_id))
+}
+
+/// Journal entries from every time a recipe was cooked, newest first
+pub async fn get_recipe_notes(pool: &PgPool, recipe_id: i64) -> Result<Vec<RecipeNote>> {
+    let notes = sqlx::query(
+        r#"
+        SELECT n.id, n.cook_log_id, n.noted_on, n.text, n.rating_delta, n.modifications
+        FROM recipe_notes n
+        JOIN cook_log c ON n.cook_log_id = c.id
+        WHERE c.recipe_id = $1
+        ORDER BY n.noted_on DESC, n.id DESC
+        "#,
+    )
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| RecipeNote {
+        id: row.get("id"),
+        cook_log_id: row.get("cook_log_id"),
+        noted_on: row.get("noted_on"),
+        text: row.get("text"),
+        rating_delta: row.get("rating_delta"),
+        modifications: row.get("modifications"),
+    })
+    .collect();
+
+    Ok(notes)
+}
+
+/// Rate a recipe from 1 to 5
+/// Every rating is kept; the recipe's score is their average
+pub async fn rate_recipe(pool: &PgPool, recipe_id: i64, rating: u8) -> Result<()> {
+    sqlx::query("INSERT INTO recipe_ratings (recipe_id, rating) VALUES ($1, $2)")
+        .bind(recipe_id)
+        .bind(i16::from(rating))
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Average rating of a recipe, None when it hasn't been rated
+pub async fn get_average_rating(pool: &PgPool, recipe_id: i64) -> Result<Option<f64>> {
+    let average =
+        sqlx::query_scalar("SELECT AVG(rating)::float8 FROM recipe_ratings WHERE recipe_id = $1")
+            .bind(recipe_id)
+            .fetch_one(pool)
+            .await?;
+
+    Ok(average)
+}
+
+/// Save a meal plan with all of its entries
+/// Takes a MealPlan (ignoring id) and returns the new plan ID
+pub async fn save_meal_plan(pool: &PgPool, plan: &MealPlan) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let plan_id: i64 = sqlx::query_scalar(
+        "INSERT INTO meal_plans (name, start_date) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(&plan.name)
+    .bind(plan.start_date)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if !plan.entries.is_empty() {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO meal_plan_entries (meal_plan_id, planned_on, meal, recipe_id, servings, note) ",
+        );
+        builder.push_values(&plan.entries, |mut row, entry| {
+            row.push_bind(plan_id)
+                .push_bind(entry.date)
+                .push_bind(&entry.meal)
+                .push_bind(entry.recipe_id)
+                .push_bind(entry.servings)
+                .push_bind(&entry.note);
+        });
+        builder.build().execute(&mut *tx).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(plan_id)
+}
+
+/// Fetch a saved meal plan with its entries ordered by date
+pub async fn get_meal_plan(pool: &PgPool, plan_id: i64) -> Result<MealPlan> {
+    let plan = sqlx::query_as::<_, MealPlanRecord>(
+        "SELECT id, name, start_date, created_at FROM meal_plans WHERE id = $1",
+    )
+    .bind(plan_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(FeedMeError::MealPlanNotFound(plan_id))?;
+
+    let rows: Vec<(NaiveDate, String, i64, String, f64, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT e.planned_on, e.meal, e.recipe_id, r.name, e.servings, e.note
+        FROM meal_plan_entries e
+        JOIN recipes r ON e.recipe_id = r.id
+        WHERE e.meal_plan_id = $1 AND r.deleted_at IS NULL
+        ORDER BY e.planned_on, e.id
+        "#,
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(MealPlan {
+        id: plan.id,
+        name: plan.name,
+        start_date: plan.start_date,
+        entries: rows
+            .into_iter()
+            .map(
+                |(date, meal, recipe_id, recipe_name, servings, note)| PlannedMeal {
+                    date,
+                    meal,
+                    recipe_id,
+                    recipe_name,
+                    servings,
+                    note,
+                },
+            )
+            .collect(),
+    })
+}
+
+/// Shopping list for every recipe in a saved plan
+/// Each entry's quantities are multiplied by its servings before being summed
+/// per ingredient and unit; unparseable quantities are listed as written.
+/// Unlike the SQLite controller, volumes and weights aren't converted into
+/// each other, as there are no densities or custom units here
+pub async fn shopping_list_for_plan(pool: &PgPool, plan_id: i64) -> Result<Vec<ShoppingListItem>> {
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM meal_plans WHERE id = $1")
+        .bind(plan_id)
+        .fetch_optional(pool)
+        .await?;
+    if exists.is_none() {
+        return Err(FeedMeError::MealPlanNotFound(plan_id));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            i.name as ingredient_name,
+            ri.quantity_unit,
+            ri.amount,
+            ri.unit,
+            e.servings
+        FROM meal_plan_entries e
+        JOIN recipe_ingredients ri ON ri.recipe_id = e.recipe_id
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE e.meal_plan_id = $1
+        ORDER BY i.name, e.planned_on, e.id, ri.id
+        "#,
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut totals: BTreeMap<String, QuantityTotal> = BTreeMap::new();
+    for row in &rows {
+        let quantity_unit: String = row.get("quantity_unit");
+        let unit: Option<String> = row.get("unit");
+
+        totals.entry(row.get("ingredient_name")).or_default().add(
+            &quantity_unit,
+            row.get("amount"),
+            unit.as_deref(),
+            row.get("servings"),
+        );
+    }
+
+    Ok(totals
+        .into_iter()
+        .map(|(ingredient_name, total)| ShoppingListItem {
+            ingredient_name,
+            combined_quantity: total.to_string(),
+        })
+        .collect())
+}
+
+/// Save a shopping list with its items and "already have" marks
+/// Returns the new list ID
+pub async fn save_shopping_list(pool: &PgPool, list: &ShoppingList) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let list_id: i64 = sqlx::query_scalar(
+        "INSERT INTO shopping_lists (name, meal_plan_id, purchased_on) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(&list.name)
+    .bind(list.meal_plan_id)
+    .bind(list.purchased_on)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if !list.items.is_empty() {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO shopping_list_items (shopping_list_id, ingredient_id, quantity, already_have) ",
+        );
+        builder.push_values(&list.items, |mut row, item| {
+            row.push_bind(list_id)
+                .push_bind(item.ingredient_id)
+                .push_bind(&item.quantity)
+                .push_bind(item.already_have);
+        });
+        builder.build().execute(&mut *tx).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(list_id)
+}
+
+/// Fetch a saved shopping list with items ordered by ingredient name
+/// Items come back without prices, aisles or icons
+pub async fn get_shopping_list(pool: &PgPool, list_id: i64) -> Result<ShoppingList> {
+    let list = sqlx::query_as::<_, ShoppingListRecord>(
+        "SELECT id, name, meal_plan_id, purchased_on, stocked, created_at FROM shopping_lists WHERE id = $1",
+    )
+    .bind(list_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(FeedMeError::ShoppingListNotFound(list_id))?;
+
+    let rows: Vec<(i64, String, String, bool, bool)> = sqlx::query_as(
+        r#"
+        SELECT i.id, i.name, sli.quantity, sli.already_have, sli.purchased
+        FROM shopping_list_items sli
+        JOIN ingredients i ON sli.ingredient_id = i.id
+        WHERE sli.shopping_list_id = $1
+        ORDER BY i.name
+        "#,
+    )
+    .bind(list_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ShoppingList {
+        id: list.id,
+        name: list.name,
+        meal_plan_id: list.meal_plan_id,
+        purchased_on: list.purchased_on,
+        store: None,
+        items: rows
+            .into_iter()
+            .map(
+                |(ingredient_id, ingredient_name, quantity, already_have, purchased)| {
+                    ShoppingListEntry {
+                        ingredient_id,
+                        ingredient_name,
+                        quantity,
+                        already_have,
+                        purchased,
+                        estimated_cost: None,
+                        aisle: None,
+                        icon: None,
+                    }
+                },
+            )
+            .collect(),
+    })
+}
+
+/// Record that the shopping for a saved list was done on `purchased_on`
+pub async fn mark_purchased(pool: &PgPool, list_id: i64, purchased_on: NaiveDate) -> Result<()> {
+    let result = sqlx::query("UPDATE shopping_lists SET purchased_on = $1 WHERE id = $2")
+        .bind(purchased_on)
+        .bind(list_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::ShoppingListNotFound(list_id));
+    }
+
+    Ok(())
+}
+
+/// Create an empty collection
+/// The name is stored trimmed and must be unique, ignoring case
+/// (`DuplicateCollection` otherwise). Returns the collection ID
+pub async fn create_collection(pool: &PgPool, name: &str) -> Result<i64> {
+    let name = name.trim();
+    let collection_id =
+        sqlx::query_scalar("INSERT INTO collections (name) VALUES ($1) RETURNING id")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .on_duplicate(|| FeedMeError::DuplicateCollection(name.to_string()))?;
+
+    Ok(collection_id)
+}
+
+/// All collections ordered by name
+pub async fn list_collections(pool: &PgPool) -> Result<Vec<CollectionRecord>> {
+    let collections = sqlx::query_as::<_, CollectionRecord>(
+        "SELECT id, name, created_at FROM collections ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(collections)
+}
+
+/// Fetch a collection with its recipes
+pub async fn get_collection(pool: &PgPool, collection_id: i64) -> Result<Collection> {
+    let name: String = sqlx::query_scalar("SELECT name FROM collections WHERE id = $1")
+        .bind(collection_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(FeedMeError::CollectionNotFound(collection_id))?;
+
+    let rows: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT r.id, r.name
+        FROM collection_recipes cr
+        JOIN recipes r ON cr.recipe_id = r.id
+        WHERE cr.collection_id = $1 AND r.deleted_at IS NULL
+        ORDER BY r.name, r.id
+        "#,
+    )
+    .bind(collection_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Collection {
+        id: collection_id,
+        name,
+        recipes: rows
+            .into_iter()
+            .map(|(recipe_id, name)| CollectionRecipe { recipe_id, name })
+            .collect(),
+    })
+}
+
+/// Delete a collection, leaving its recipes alone
+/// Returns whether the collection existed
+pub async fn delete_collection(pool: &PgPool, collection_id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM collections WHERE id = $1")
+        .bind(collection_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Add a recipe to a collection; adding it twice is a no-op
+pub async fn add_to_collection(pool: &PgPool, collection_id: i64, recipe_id: i64) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let collection: Option<i64> = sqlx::query_scalar("SELECT id FROM collections WHERE id = $1")
+        .bind(collection_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    if collection.is_none() {
+        return Err(FeedMeError::CollectionNotFound(collection_id));
+    }
+
+    let recipe: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM recipes WHERE id = $1 AND deleted_at IS NULL")
+            .bind(recipe_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if recipe.is_none() {
+        return Err(FeedMeError::RecipeNotFound(recipe_id));
+    }
+
+    sqlx::query(
+        "INSERT INTO collection_recipes (collection_id, recipe_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+    )
+    .bind(collection_id)
+    .bind(recipe_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Take a recipe out of a collection
+/// Returns whether the recipe was in it
+pub async fn remove_from_collection(
+    pool: &PgPool,
+    collection_id: i64,
+    recipe_id: i64,
+) -> Result<bool> {
+    let result =
+        sqlx::query("DELETE FROM collection_recipes WHERE collection_id = $1 AND recipe_id = $2")
+            .bind(collection_id)
+            .bind(recipe_id)
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Add stock to the pantry
+/// The unit is stored lowercased. Returns the new pantry item ID
+pub async fn add_to_pantry(pool: &PgPool, item: &PantryItem) -> Result<i64> {
+    let id = sqlx::query_scalar(
+        "INSERT INTO pantry_items (ingredient_id, amount, unit, expires_on, is_staple, min_amount) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+    )
+    .bind(item.ingredient_id)
+    .bind(item.amount)
+    .bind(item.unit.as_deref().map(str::to_lowercase))
+    .bind(item.expires_on)
+    .bind(item.is_staple)
+    .bind(item.min_amount)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Everything in the pantry ordered by ingredient name, soonest to expire
+/// first. Items come back without icons
+pub async fn list_pantry(pool: &PgPool) -> Result<Vec<PantryItem>> {
+    let rows = sqlx::query_as::<_, PantryRow>(
+        r#"
+        SELECT p.id, p.ingredient_id, i.name as ingredient_name, p.amount, p.unit,
+               p.expires_on, p.is_staple, p.min_amount, p.created_at
+        FROM pantry_items p
+        JOIN ingredients i ON p.ingredient_id = i.id
+        ORDER BY i.name, p.expires_on NULLS LAST, p.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |PantryRow {
+                 item,
+                 ingredient_name,
+             }| PantryItem {
+                id: item.id,
+                ingredient_id: item.ingredient_id,
+                ingredient_name,
+                amount: item.amount,
+                unit: item.unit,
+                expires_on: item.expires_on,
+                is_staple: item.is_staple,
+                min_amount: item.min_amount,
+                icon: None,
+            },
+        )
+        .collect())
+}
+
+/// A pantry item with the name of its ingredient, read by `list_pantry`
+#[derive(Debug, FromRow)]
+struct PantryRow {
+    #[sqlx(flatten)]
+    item: PantryItemRecord,
+    ingredient_name: String,
+}
+
+/// Change how much of a pantry item is left
+pub async fn update_pantry_amount(pool: &PgPool, id: i64, amount: f64) -> Result<()> {
+    let result = sqlx::query("UPDATE pantry_items SET amount = $1 WHERE id = $2")
+        .bind(amount)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(FeedMeError::PantryItemNotFound(id));
+    }
+
+    Ok(())
+}
+
+/// Remove a pantry item
+/// Returns whether the item existed
+pub async fn remove_from_pantry(pool: &PgPool, id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM pantry_items WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Recipes whose every ingredient is covered by current pantry stock
+/// Ordered by name. Stock is compared as in `controllers::cookable_recipes`,
+/// but no substitutions are offered
+pub async fn cookable_recipes(pool: &PgPool) -> Result<Vec<CookableRecipe>> {
+    let mut stock: HashMap<i64, Vec<(f64, Option<String>)>> = HashMap::new();
+    let pantry: Vec<(i64, f64, Option<String>)> =
+        sqlx::query_as("SELECT ingredient_id, amount, unit FROM pantry_items WHERE amount > 0")
+            .fetch_all(pool)
+            .await?;
+    for (ingredient_id, amount, unit) in pantry {
+        stock.entry(ingredient_id).or_default().push((amount, unit));
+    }
+
+    let rows = sqlx::query_as::<_, CoverageRow>(
+        r#"
+        SELECT
+            r.id as recipe_id,
+            r.name as recipe_name,
+            ri.ingredient_id,
+            i.name as ingredient_name,
+            ri.quantity_unit,
+            ri.amount,
+            ri.unit
+        FROM recipes r
+        LEFT JOIN recipe_ingredients ri ON ri.recipe_id = r.id
+        LEFT JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE r.deleted_at IS NULL
+        ORDER BY r.name, r.id, ri.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(cover_recipes(&rows, &stock, &HashMap::new())
+        .into_iter()
+        .filter(|recipe| recipe.missing.is_empty())
+        .collect())
+}
+
+/// A recipe's ingredient line joined with the ingredient it names, shared
+/// by `get_recipe` and `generate_shopping_list`
+#[derive(Debug, FromRow)]
+struct IngredientRow {
+    ingredient_id: i64,
+    ingredient_name: String,
+    quantity_unit: String,
+    notes: Option<String>,
+}
+
+/// The ingredient lines of `recipe_ids`, in the order they were added
+async fn ingredient_rows(pool: &PgPool, recipe_ids: &[i64]) -> Result<Vec<IngredientRow>> {
+    let rows = sqlx::query_as::<_, IngredientRow>(
+        r#"
+        SELECT
+            i.id as ingredient_id,
+            i.name as ingredient_name,
+            ri.quantity_unit,
+            ri.notes
+        FROM recipe_ingredients ri
+        JOIN ingredients i ON ri.ingredient_id = i.id
+        WHERE ri.recipe_id = ANY($1)
+        ORDER BY ri.id
+        "#,
+    )
+    .bind(recipe_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,66 @@
+//! The types most embedders need, in one import
+//!
+//! ```no_run
+//! use feedme::prelude::*;
+//!
+//! # async fn run() -> Result<()> {
+//! let pool = init_database("sqlite://feedme.db", &DatabaseConfig::default()).await?;
+//! let recipe: Recipe = pool.get_recipe(1).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::FeedMe;
+pub use crate::config::DatabaseConfig;
+pub use crate::db::{IN_MEMORY_URL, init as init_database};
+pub use crate::error::{FeedMeError, Result};
+pub use crate::models::IngredientRecord;
+pub use crate::models::api::{Recipe, RecipeIngredient};
+pub use crate::store::{
+    CollectionStore, CookLogStore, FeedMeStore, IngredientStore, MealPlanStore, PantryStore,
+    RecipeStore, ShoppingListStore, TagStore,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::Visibility;
+
+    #[tokio::test]
+    async fn test_prelude_is_enough_to_get_started() {
+        let pool = init_database(IN_MEMORY_URL, &DatabaseConfig::default())
+            .await
+            .unwrap();
+
+        let salt = pool.create_ingredient("salt").await.unwrap();
+        let recipe = Recipe {
+            id: 0,
+            name: "Brine".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: salt,
+                ingredient_name: "salt".to_string(),
+                quantity_unit: "1 tbsp".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let id = pool.create_recipe(&recipe).await.unwrap();
+
+        assert!(matches!(
+            pool.get_recipe(id + 1).await,
+            Err(FeedMeError::RecipeNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,688 @@
+use std::fmt;
+
+use crate::units::{CustomUnit, Unit, UnitStyle, UnitSystem};
+
+/// Unicode vulgar fractions and their values
+const UNICODE_FRACTIONS: [(char, i64, i64); 15] = [
+    ('½', 1, 2),
+    ('⅓', 1, 3),
+    ('⅔', 2, 3),
+    ('¼', 1, 4),
+    ('¾', 3, 4),
+    ('⅕', 1, 5),
+    ('⅖', 2, 5),
+    ('⅗', 3, 5),
+    ('⅘', 4, 5),
+    ('⅙', 1, 6),
+    ('⅚', 5, 6),
+    ('⅛', 1, 8),
+    ('⅜', 3, 8),
+    ('⅝', 5, 8),
+    ('⅞', 7, 8),
+];
+
+/// Denominators a kitchen measure is likely to use, in order of preference
+const KITCHEN_DENOMINATORS: [i64; 4] = [2, 3, 4, 8];
+
+/// How far an amount may be from a kitchen fraction and still be shown as one
+const KITCHEN_TOLERANCE: f64 = 0.01;
+
+/// An exact non-negative rational amount, always in lowest terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fraction {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Fraction {
+    /// None for a zero denominator or a negative value
+    pub fn new(numerator: i64, denominator: i64) -> Option<Self> {
+        if denominator <= 0 || numerator < 0 {
+            return None;
+        }
+
+        let divisor = gcd(numerator, denominator);
+        Some(Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    /// Parse "2", "1/2", "1 1/2", "½" or "1½"
+    /// Decimals aren't fractions and return None
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = expand_unicode_fractions(text);
+        let mut total = Fraction::new(0, 1)?;
+        let mut parts = 0;
+
+        for part in text.split_whitespace() {
+            parts += 1;
+            let value = match part.split_once('/') {
+                Some((numerator, denominator)) => {
+                    Fraction::new(numerator.parse().ok()?, denominator.parse().ok()?)?
+                }
+                None => Fraction::new(part.parse().ok()?, 1)?,
+            };
+            total = total.checked_add(value)?;
+        }
+
+        if parts == 0 || parts > 2 {
+            return None;
+        }
+
+        Some(total)
+    }
+
+    /// The nearest fraction with a kitchen-friendly denominator (halves,
+    /// thirds, quarters, eighths), if `amount` is close enough to one
+    pub fn approximate(amount: f64) -> Option<Self> {
+        if !amount.is_finite() || amount < 0.0 {
+            return None;
+        }
+
+        KITCHEN_DENOMINATORS.iter().find_map(|&denominator| {
+            let numerator = (amount * denominator as f64).round();
+            let value = numerator / denominator as f64;
+            ((value - amount).abs() <= KITCHEN_TOLERANCE)
+                .then(|| Fraction::new(numerator as i64, denominator))
+                .flatten()
+        })
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    fn checked_add(self, other: Fraction) -> Option<Fraction> {
+        Fraction::new(
+            self.numerator
+                .checked_mul(other.denominator)?
+                .checked_add(other.numerator.checked_mul(self.denominator)?)?,
+            self.denominator.checked_mul(other.denominator)?,
+        )
+    }
+}
+
+impl fmt::Display for Fraction {
+    /// Mixed-number form: "2", "1/2", "1 1/2"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.numerator / self.denominator;
+        let remainder = self.numerator % self.denominator;
+
+        match (whole, remainder) {
+            (whole, 0) => write!(f, "{}", whole),
+            (0, remainder) => write!(f, "{}/{}", remainder, self.denominator),
+            (whole, remainder) => write!(f, "{} {}/{}", whole, remainder, self.denominator),
+        }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.max(1) } else { gcd(b, a % b) }
+}
+
+/// Rewrite unicode fractions as ASCII ("1½" becomes "1 1/2")
+fn expand_unicode_fractions(text: &str) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match UNICODE_FRACTIONS.iter().find(|(glyph, _, _)| *glyph == c) {
+            Some((_, numerator, denominator)) => {
+                expanded.push_str(&format!(" {}/{}", numerator, denominator))
+            }
+            None => expanded.push(c),
+        }
+    }
+    expanded
+}
+
+fn is_unicode_fraction(c: char) -> bool {
+    UNICODE_FRACTIONS.iter().any(|(glyph, _, _)| *glyph == c)
+}
+
+/// A `quantity_unit` string split into a numeric amount and a unit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedQuantity {
+    pub amount: f64,
+    /// The amount as an exact fraction, when it was written as one
+    pub exact: Option<Fraction>,
+    /// Lowercased unit text, None for bare counts like "3"
+    pub unit: Option<String>,
+}
+
+/// Parse a free-form quantity such as "2 cups", "1/2 tsp", "1 1/2 cups",
+/// "½ cup" or "500g"
+/// Returns None when the text doesn't start with a number ("a pinch", "to taste")
+pub fn parse_quantity(text: &str) -> Option<ParsedQuantity> {
+    let text = text.trim();
+
+    // Split the leading numeric part from the unit, allowing "500g"
+    let number_end = text
+        .find(|c: char| {
+            !(c.is_ascii_digit() || c == '.' || c == '/' || c == ' ' || is_unicode_fraction(c))
+        })
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(number_end);
+
+    let number = expand_unicode_fractions(number);
+    let amount = parse_amount(number.trim())?;
+    let unit = unit.trim().to_lowercase();
+
+    Some(ParsedQuantity {
+        amount,
+        exact: Fraction::parse(&number),
+        unit: if unit.is_empty() { None } else { Some(unit) },
+    })
+}
+
+/// Parse "2", "2.5", "1/2" or a mixed number "1 1/2"
+fn parse_amount(text: &str) -> Option<f64> {
+    let mut total = 0.0;
+    let mut parts = 0;
+
+    for part in text.split_whitespace() {
+        parts += 1;
+        total += match part.split_once('/') {
+            Some((numerator, denominator)) => {
+                let denominator: f64 = denominator.parse().ok()?;
+                if denominator == 0.0 {
+                    return None;
+                }
+                numerator.parse::<f64>().ok()? / denominator
+            }
+            None => part.parse::<f64>().ok()?,
+        };
+    }
+
+    // Only "whole fraction" pairs are meaningful beyond a single number
+    if parts == 0 || parts > 2 {
+        return None;
+    }
+
+    Some(total)
+}
+
+/// Convert a structured quantity to grams
+/// Handles metric and imperial weights; bare counts use `grams_per_unit`.
+/// Volumes and other units return None
+pub fn to_grams(amount: f64, unit: Option<&str>, grams_per_unit: Option<f64>) -> Option<f64> {
+    match unit {
+        None => grams_per_unit.map(|g| amount * g),
+        Some(unit) => Unit::parse(unit).grams().map(|g| amount * g),
+    }
+}
+
+/// Convert an amount between two units of the same kind
+/// Identical units (including two bare counts) pass through; weights convert
+/// to weights and volumes to volumes. Anything else returns None
+pub fn convert(amount: f64, from: Option<&str>, to: Option<&str>) -> Option<f64> {
+    convert_with_density(amount, from, to, None)
+}
+
+/// Like `convert`, but also converts between volumes and weights when the
+/// ingredient's density (grams per millilitre) is known
+pub fn convert_with_density(
+    amount: f64,
+    from: Option<&str>,
+    to: Option<&str>,
+    grams_per_ml: Option<f64>,
+) -> Option<f64> {
+    if from == to {
+        return Some(amount);
+    }
+
+    Unit::parse(from?).convert_with_density(amount, &Unit::parse(to?), grams_per_ml)
+}
+
+/// Format an amount without trailing zeros ("2", "1.5", "0.33")
+pub fn format_amount(amount: f64) -> String {
+    let rounded = (amount * 100.0).round() / 100.0;
+    let text = format!("{:.2}", rounded);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Format an amount as a kitchen fraction ("1 1/2", "1/3") when it is close
+/// to one, falling back to `format_amount` otherwise
+pub fn format_fraction(amount: f64) -> String {
+    match Fraction::approximate(amount) {
+        Some(fraction) => fraction.to_string(),
+        None => format_amount(amount),
+    }
+}
+
+/// Format an amount and its unit as written, pluralizing known units in
+/// the style they were written in ("1 cup" but "2 cups", "2 tablespoons")
+pub fn format_quantity(amount: f64, unit: Option<&str>) -> String {
+    let Some(unit) = unit else {
+        return format_fraction(amount);
+    };
+
+    match Unit::parse(unit) {
+        Unit::Count | Unit::Freeform(_) => format!("{} {}", format_fraction(amount), unit),
+        parsed => format!(
+            "{} {}",
+            format_number(amount, &parsed),
+            parsed.name(amount, UnitStyle::of(unit))
+        ),
+    }
+}
+
+/// Metric amounts are written as decimals ("0.5 l"), others as kitchen
+/// fractions ("1/2 cup")
+fn format_number(amount: f64, unit: &Unit) -> String {
+    match unit.system() {
+        Some(UnitSystem::Metric) => format_amount(amount),
+        _ => format_fraction(amount),
+    }
+}
+
+/// How quantities are rewritten for display
+/// The default keeps them exactly as written
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QuantityFormat {
+    /// Convert to this measurement system
+    pub system: Option<UnitSystem>,
+    /// Write unit names in this style
+    pub style: Option<UnitStyle>,
+}
+
+/// Rewrite a quantity for display, e.g. "2 cups" as "473 ml" in metric
+/// Combined quantities ("1 cup + 2 tbsp") are rewritten part by part. Parts
+/// that don't parse, or that the format doesn't change, are kept as written
+pub fn localize_quantity(text: &str, format: QuantityFormat) -> String {
+    if format == QuantityFormat::default() {
+        return text.to_string();
+    }
+
+    quantity_parts(text)
+        .map(|part| localize_part(part, format))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// The parts of a combined quantity as `QuantityTotal` writes it, e.g.
+/// "250 g + 1 cup" as "250 g" and "1 cup"
+pub fn quantity_parts(text: &str) -> impl Iterator<Item = &str> {
+    text.split(" + ")
+}
+
+fn localize_part(text: &str, format: QuantityFormat) -> String {
+    let Some(parsed) = parse_quantity(text) else {
+        return text.to_string();
+    };
+    let unit = Unit::parse(parsed.unit.as_deref().unwrap_or(""));
+    if matches!(unit, Unit::Count | Unit::Freeform(_)) {
+        return text.to_string();
+    }
+
+    let (amount, converted) = match format.system {
+        Some(system) => unit.in_system(parsed.amount, system),
+        None => (parsed.amount, unit.clone()),
+    };
+    let Some(style) = format
+        .style
+        .or((converted != unit).then_some(UnitStyle::Abbreviated))
+    else {
+        return text.to_string();
+    };
+
+    // Converted grams and millilitres aren't worth measuring more finely
+    // than whole numbers
+    let number = match converted {
+        Unit::Gram | Unit::Millilitre if converted != unit => format_amount(amount.round()),
+        _ => format_number(amount, &converted),
+    };
+    format!("{} {}", number, converted.name(amount, style))
+}
+
+/// What's known about measuring one ingredient beyond the standard units
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IngredientUnits {
+    /// Density in grams per millilitre
+    pub grams_per_ml: Option<f64>,
+    /// Custom units that apply to the ingredient, most specific first
+    pub custom: Vec<CustomUnit>,
+}
+
+impl IngredientUnits {
+    pub fn custom_unit(&self, text: &str) -> Option<&CustomUnit> {
+        self.custom.iter().find(|custom| custom.matches(text))
+    }
+
+    /// Convert an amount between units, going through custom unit
+    /// definitions and the density as needed
+    pub fn convert(&self, amount: f64, from: Option<&str>, to: Option<&str>) -> Option<f64> {
+        if from == to {
+            return Some(amount);
+        }
+
+        let (from_amount, from_unit) = self.resolve(amount, from?);
+        let (per_to, to_unit) = self.resolve(1.0, to?);
+        if from_unit == to_unit {
+            return Some(from_amount / per_to);
+        }
+
+        from_unit
+            .convert_with_density(from_amount, &to_unit, self.grams_per_ml)
+            .map(|converted| converted / per_to)
+    }
+
+    /// Express `amount` of a unit in a standard unit where it's custom
+    fn resolve(&self, amount: f64, unit: &str) -> (f64, Unit) {
+        match self.custom_unit(unit) {
+            Some(custom) => (amount * custom.amount, custom.unit.clone()),
+            None => (amount, Unit::parse(unit)),
+        }
+    }
+}
+
+/// Running total of one ingredient's quantities across several recipes
+/// Parsed quantities are summed in the first-seen unit they convert to
+/// (volumes and weights convert into each other when the ingredient's density
+/// is known, custom units into what they're defined as); others are listed
+/// side by side. Anything unparseable is kept as text so nothing silently
+/// disappears from a list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuantityTotal {
+    /// Summed amounts per unit, in first-seen order
+    amounts: Vec<(Option<String>, f64)>,
+    /// Quantities that couldn't be parsed, as written
+    unparsed: Vec<String>,
+    units: IngredientUnits,
+}
+
+impl QuantityTotal {
+    /// An empty total for an ingredient with the given density
+    pub fn with_density(grams_per_ml: Option<f64>) -> Self {
+        Self::for_ingredient(IngredientUnits {
+            grams_per_ml,
+            custom: Vec::new(),
+        })
+    }
+
+    /// An empty total converting with everything known about the ingredient
+    pub fn for_ingredient(units: IngredientUnits) -> Self {
+        Self {
+            units,
+            ..Self::default()
+        }
+    }
+
+    /// Add a quantity multiplied by `scale`
+    /// `amount`/`unit` are the stored structured columns; None amount means unparseable
+    pub fn add(
+        &mut self,
+        quantity_unit: &str,
+        amount: Option<f64>,
+        unit: Option<&str>,
+        scale: f64,
+    ) {
+        let Some(amount) = amount else {
+            self.unparsed.push(if scale == 1.0 {
+                quantity_unit.to_string()
+            } else {
+                format!("{} (x{})", quantity_unit, format_amount(scale))
+            });
+            return;
+        };
+
+        let amount = amount * scale;
+        let units = &self.units;
+        let existing = self.amounts.iter_mut().find_map(|(existing, total)| {
+            units
+                .convert(amount, unit, existing.as_deref())
+                .map(|converted| (total, converted))
+        });
+
+        match existing {
+            Some((total, converted)) => *total += converted,
+            None => self.amounts.push((unit.map(str::to_string), amount)),
+        }
+    }
+}
+
+impl fmt::Display for QuantityTotal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .amounts
+            .iter()
+            .map(|(unit, amount)| {
+                match unit
+                    .as_deref()
+                    .and_then(|unit| self.units.custom_unit(unit))
+                {
+                    Some(custom) => {
+                        format!("{} {}", format_fraction(*amount), custom.name(*amount))
+                    }
+                    None => format_quantity(*amount, unit.as_deref()),
+                }
+            })
+            .chain(self.unparsed.iter().cloned())
+            .collect();
+
+        write!(f, "{}", parts.join(" + "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("2 cups", 2.0, Some("cups"))]
+    #[case("2.5 cups", 2.5, Some("cups"))]
+    #[case("1/2 tsp", 0.5, Some("tsp"))]
+    #[case("1 1/2 cups", 1.5, Some("cups"))]
+    #[case("3 whole", 3.0, Some("whole"))]
+    #[case("500g", 500.0, Some("g"))]
+    #[case("  1 Pinch ", 1.0, Some("pinch"))]
+    #[case("4", 4.0, None)]
+    #[case("½ cup", 0.5, Some("cup"))]
+    #[case("1½ cups", 1.5, Some("cups"))]
+    #[case("2 ¾ tsp", 2.75, Some("tsp"))]
+    fn test_parse_quantity(#[case] input: &str, #[case] amount: f64, #[case] unit: Option<&str>) {
+        let parsed = parse_quantity(input).expect("Should parse");
+
+        assert_eq!(parsed.amount, amount);
+        assert_eq!(parsed.unit.as_deref(), unit);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("a pinch")]
+    #[case("to taste")]
+    #[case("1/0 cup")]
+    #[case("1 2 3 cups")]
+    #[case("1..5 cups")]
+    fn test_parse_quantity_unparseable(#[case] input: &str) {
+        assert_eq!(parse_quantity(input), None);
+    }
+
+    #[rstest]
+    #[case("1/2 cup", Some((1, 2)))]
+    #[case("1 1/2 cups", Some((3, 2)))]
+    #[case("2/4 tsp", Some((1, 2)))]
+    #[case("1⅓ cups", Some((4, 3)))]
+    #[case("3 eggs", Some((3, 1)))]
+    #[case("2.5 cups", None)]
+    fn test_parse_quantity_exact(#[case] input: &str, #[case] expected: Option<(i64, i64)>) {
+        let parsed = parse_quantity(input).expect("Should parse");
+
+        assert_eq!(
+            parsed.exact.map(|f| (f.numerator(), f.denominator())),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(2.0, "2")]
+    #[case(0.5, "1/2")]
+    #[case(1.5, "1 1/2")]
+    #[case(1.0 / 3.0, "1/3")]
+    #[case(0.66, "2/3")]
+    #[case(2.375, "2 3/8")]
+    #[case(0.2, "0.2")]
+    #[case(1.43, "1.43")]
+    fn test_format_fraction(#[case] amount: f64, #[case] expected: &str) {
+        assert_eq!(format_fraction(amount), expected);
+    }
+
+    #[rstest]
+    #[case(2.0, "2")]
+    #[case(1.5, "1.5")]
+    #[case(1.0 / 3.0, "0.33")]
+    #[case(0.999, "1")]
+    fn test_format_amount(#[case] amount: f64, #[case] expected: &str) {
+        assert_eq!(format_amount(amount), expected);
+    }
+
+    #[rstest]
+    #[case(500.0, Some("g"), None, Some(500.0))]
+    #[case(1.5, Some("kg"), None, Some(1500.0))]
+    #[case(2.0, None, Some(50.0), Some(100.0))]
+    #[case(2.0, None, None, None)]
+    #[case(1.0, Some("cup"), Some(50.0), None)]
+    fn test_to_grams(
+        #[case] amount: f64,
+        #[case] unit: Option<&str>,
+        #[case] grams_per_unit: Option<f64>,
+        #[case] expected: Option<f64>,
+    ) {
+        assert_eq!(to_grams(amount, unit, grams_per_unit), expected);
+    }
+
+    #[rstest]
+    #[case(1.0, Some("kg"), Some("g"), Some(1000.0))]
+    #[case(2.0, Some("cups"), Some("cup"), Some(2.0))]
+    #[case(1.0, Some("l"), Some("ml"), Some(1000.0))]
+    #[case(3.0, None, None, Some(3.0))]
+    #[case(1.0, Some("cup"), Some("g"), None)]
+    #[case(2.0, None, Some("g"), None)]
+    #[case(1.0, Some("pinch"), Some("tsp"), None)]
+    fn test_convert(
+        #[case] amount: f64,
+        #[case] from: Option<&str>,
+        #[case] to: Option<&str>,
+        #[case] expected: Option<f64>,
+    ) {
+        let converted = convert(amount, from, to);
+
+        assert_eq!(converted.is_some(), expected.is_some());
+        if let (Some(converted), Some(expected)) = (converted, expected) {
+            assert!((converted - expected).abs() < 1e-9);
+        }
+    }
+
+    #[rstest]
+    #[case("2 cups", Some(UnitSystem::Metric), None, "473 ml")]
+    #[case("250 g", Some(UnitSystem::Imperial), None, "8.82 oz")]
+    #[case("3 lbs", Some(UnitSystem::Metric), None, "1.36 kg")]
+    #[case("1 cup + 2 tbsp", Some(UnitSystem::Metric), None, "237 ml + 2 tbsp")]
+    #[case("500 ml", Some(UnitSystem::Metric), None, "500 ml")]
+    #[case("3", Some(UnitSystem::Imperial), None, "3")]
+    #[case("a pinch", Some(UnitSystem::Metric), None, "a pinch")]
+    #[case("1 litre", Some(UnitSystem::Imperial), None, "4.23 cups")]
+    #[case("1/2 pint", Some(UnitSystem::Imperial), None, "1/2 pint")]
+    #[case("2 tbsp", None, Some(UnitStyle::Full), "2 tablespoons")]
+    #[case("1 tablespoon", None, Some(UnitStyle::Abbreviated), "1 tbsp")]
+    #[case("0.5 cups", None, Some(UnitStyle::Full), "1/2 cup")]
+    #[case(
+        "2 cups",
+        Some(UnitSystem::Metric),
+        Some(UnitStyle::Full),
+        "473 millilitres"
+    )]
+    #[case("2 tbsp", None, None, "2 tbsp")]
+    fn test_localize_quantity(
+        #[case] text: &str,
+        #[case] system: Option<UnitSystem>,
+        #[case] style: Option<UnitStyle>,
+        #[case] expected: &str,
+    ) {
+        let format = QuantityFormat { system, style };
+
+        assert_eq!(localize_quantity(text, format), expected);
+    }
+
+    #[rstest]
+    #[case(1.0, Some("cup"), "1 cup")]
+    #[case(2.5, Some("cup"), "2 1/2 cups")]
+    #[case(2.0, Some("tablespoon"), "2 tablespoons")]
+    #[case(2.0, Some("tbsp"), "2 tbsp")]
+    #[case(3.0, Some("cloves"), "3 cloves")]
+    #[case(3.0, None, "3")]
+    #[case(1.5, Some("kg"), "1.5 kg")]
+    fn test_format_quantity(
+        #[case] amount: f64,
+        #[case] unit: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(format_quantity(amount, unit), expected);
+    }
+
+    #[test]
+    fn test_quantity_total() {
+        let mut total = QuantityTotal::default();
+        total.add("1 cup", Some(1.0), Some("cup"), 2.0);
+        total.add("2 tbsp", Some(2.0), Some("tbsp"), 1.0);
+        total.add("1/2 cup", Some(0.5), Some("cup"), 1.0);
+        total.add("a pinch", None, None, 1.0);
+        total.add("to taste", None, None, 3.0);
+
+        total.add("1 pinch", Some(1.0), Some("pinch"), 1.0);
+
+        assert_eq!(
+            total.to_string(),
+            "2 5/8 cups + 1 pinch + a pinch + to taste (x3)"
+        );
+    }
+
+    #[test]
+    fn test_quantity_total_with_custom_units() {
+        let units = IngredientUnits {
+            grams_per_ml: None,
+            custom: vec![CustomUnit {
+                name: "stick".to_string(),
+                amount: 113.0,
+                unit: Unit::Gram,
+            }],
+        };
+
+        let mut total = QuantityTotal::for_ingredient(units.clone());
+        total.add("1 stick", Some(1.0), Some("stick"), 1.0);
+        total.add("226 g", Some(226.0), Some("g"), 1.0);
+        total.add("1/2 sticks", Some(0.5), Some("sticks"), 1.0);
+        assert_eq!(total.to_string(), "3 1/2 sticks");
+
+        let mut total = QuantityTotal::for_ingredient(units);
+        total.add("100 g", Some(100.0), Some("g"), 1.0);
+        total.add("1 stick", Some(1.0), Some("stick"), 1.0);
+        assert_eq!(total.to_string(), "213 g");
+    }
+
+    #[test]
+    fn test_quantity_total_with_density() {
+        // Flour, about 0.53 g/ml
+        let mut total = QuantityTotal::with_density(Some(0.53));
+        total.add("1 cup", Some(1.0), Some("cup"), 1.0);
+        total.add("250 g", Some(250.0), Some("g"), 1.0);
+
+        assert_eq!(total.to_string(), "3 cups");
+
+        // Without a density weights and volumes stay on parallel lines
+        let mut total = QuantityTotal::default();
+        total.add("1 cup", Some(1.0), Some("cup"), 1.0);
+        total.add("250 g", Some(250.0), Some("g"), 1.0);
+        total.add("1 kg", Some(1.0), Some("kg"), 1.0);
+
+        assert_eq!(total.to_string(), "1 cup + 1250 g");
+    }
+}
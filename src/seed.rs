@@ -0,0 +1,197 @@
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::controllers::{create_ingredient, create_recipe, get_all_ingredients};
+use crate::error::Result;
+use crate::models::api::{Recipe, RecipeIngredient, Visibility};
+
+/// (ingredient name, quantity_unit, notes)
+type DemoIngredient = (&'static str, &'static str, Option<&'static str>);
+
+/// (recipe name, instructions, ingredients)
+type DemoRecipe = (&'static str, &'static str, &'static [DemoIngredient]);
+
+const DEMO_RECIPES: &[DemoRecipe] = &[
+    (
+        "Pancakes",
+        "Whisk the dry ingredients\nBeat in milk, eggs and melted butter\nCook on a hot griddle until bubbles form, then flip",
+        &[
+            ("flour", "1.5 cups", Some("all-purpose")),
+            ("sugar", "2 tbsp", None),
+            ("baking powder", "2 tsp", None),
+            ("salt", "1 pinch", None),
+            ("milk", "1.25 cups", None),
+            ("eggs", "1 whole", None),
+            ("butter", "3 tbsp", Some("melted")),
+        ],
+    ),
+    (
+        "Chocolate Chip Cookies",
+        "Cream butter and sugar\nMix in eggs, then flour and salt\nFold in chocolate chips\nBake at 350°F for 12 minutes",
+        &[
+            ("flour", "2.25 cups", Some("all-purpose")),
+            ("butter", "1 cup", Some("softened")),
+            ("sugar", "0.75 cup", None),
+            ("eggs", "2 whole", None),
+            ("salt", "1 tsp", None),
+            ("chocolate chips", "2 cups", Some("semi-sweet")),
+        ],
+    ),
+    (
+        "Lemon Garlic Chicken",
+        "Season the chicken with salt and pepper\nSear skin-side down until golden\nAdd garlic and lemon juice, then roast at 425°F for 20 minutes",
+        &[
+            ("chicken thighs", "6 whole", Some("bone-in")),
+            ("garlic", "4 cloves", Some("smashed")),
+            ("lemon", "1 whole", Some("juiced")),
+            ("olive oil", "2 tbsp", None),
+            ("salt", "1 tsp", None),
+            ("black pepper", "0.5 tsp", None),
+        ],
+    ),
+    (
+        "Tomato Pasta",
+        "Boil the pasta until al dente\nSimmer garlic in olive oil, add tomatoes and cook 15 minutes\nToss pasta with the sauce and basil",
+        &[
+            ("pasta", "500g", Some("spaghetti")),
+            ("canned tomatoes", "1 can", Some("crushed")),
+            ("garlic", "3 cloves", Some("sliced")),
+            ("olive oil", "3 tbsp", None),
+            ("basil", "1 handful", Some("fresh")),
+            ("salt", "1 tsp", None),
+        ],
+    ),
+    (
+        "Greek Salad",
+        "Chop the vegetables\nToss with olive oil and oregano\nTop with feta",
+        &[
+            ("tomatoes", "4 whole", Some("cut into wedges")),
+            ("cucumber", "1 whole", None),
+            ("red onion", "0.5 whole", Some("thinly sliced")),
+            ("feta", "200g", None),
+            ("olive oil", "3 tbsp", None),
+            ("oregano", "1 tsp", Some("dried")),
+        ],
+    ),
+];
+
+/// Populate the database with a handful of realistic recipes
+/// Ingredients that already exist are reused and recipes whose name is
+/// already present are skipped, so seeding twice is harmless
+/// Returns the IDs of the recipes that were created
+pub async fn seed_demo_data(pool: &SqlitePool) -> Result<Vec<i64>> {
+    let mut ingredient_ids: HashMap<String, i64> = get_all_ingredients(pool)
+        .await?
+        .into_iter()
+        .map(|i| (i.name, i.id))
+        .collect();
+
+    let mut created = Vec::new();
+
+    for (name, instructions, ingredients) in DEMO_RECIPES {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM recipes WHERE name = ?)")
+                .bind(name)
+                .fetch_one(pool)
+                .await?;
+        if exists {
+            tracing::debug!(recipe = name, "demo recipe already present, skipping");
+            continue;
+        }
+
+        let mut recipe_ingredients = Vec::new();
+        for (ingredient_name, quantity_unit, notes) in ingredients.iter() {
+            let ingredient_id = match ingredient_ids.get(*ingredient_name) {
+                Some(&id) => id,
+                None => {
+                    let id = create_ingredient(pool, ingredient_name).await?;
+                    ingredient_ids.insert(ingredient_name.to_string(), id);
+                    id
+                }
+            };
+
+            recipe_ingredients.push(RecipeIngredient {
+                ingredient_id,
+                ingredient_name: ingredient_name.to_string(),
+                quantity_unit: quantity_unit.to_string(),
+                notes: notes.map(str::to_string),
+                steps: Vec::new(),
+            });
+        }
+
+        let recipe = Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: Some(instructions.to_string()),
+            created_at: String::new(),
+            ingredients: recipe_ingredients,
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        created.push(create_recipe(pool, &recipe).await?);
+    }
+
+    tracing::info!(recipes = created.len(), "seeded demo data");
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::get_recipe;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_seed_demo_data(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let ids = seed_demo_data(&pool).await.expect("Failed to seed");
+
+        assert_eq!(ids.len(), DEMO_RECIPES.len());
+
+        let pancakes = get_recipe(&pool, ids[0]).await.expect("Failed to fetch");
+        assert_eq!(pancakes.name, "Pancakes");
+        assert_eq!(pancakes.ingredients.len(), 7);
+
+        // Shared ingredients are created once
+        let salt_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM ingredients WHERE name = 'salt'")
+                .fetch_one(&pool)
+                .await
+                .expect("Failed to count salt");
+        assert_eq!(salt_count, 1);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_seed_demo_data_is_idempotent(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // A pre-existing ingredient is reused rather than duplicated
+        create_ingredient(&pool, "flour")
+            .await
+            .expect("Failed to create flour");
+
+        seed_demo_data(&pool).await.expect("Failed to seed");
+        let second = seed_demo_data(&pool).await.expect("Failed to seed again");
+
+        assert!(second.is_empty());
+
+        let recipe_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recipes")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to count recipes");
+        assert_eq!(recipe_count, DEMO_RECIPES.len() as i64);
+    }
+}
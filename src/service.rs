@@ -0,0 +1,236 @@
+//! `FeedMe`, a single entrypoint for embedders
+//!
+//! Owns the connection pool and exposes the controllers as methods, so
+//! callers don't need to thread a `&SqlitePool` through every call:
+//!
+//! ```no_run
+//! # async fn run() -> feedme::error::Result<()> {
+//! let feedme = feedme::FeedMe::open(&Default::default()).await?;
+//! let recipe = feedme.recipes().get(1).await?;
+//! let list = feedme.shopping_list(&[recipe.id]).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use futures::Stream;
+use sqlx::SqlitePool;
+
+use crate::cache::IngredientCache;
+use crate::config::DatabaseConfig;
+use crate::controllers;
+use crate::db;
+use crate::error::Result;
+use crate::models::api::{Recipe, SearchResult, ShoppingListItem};
+use crate::models::{AuditLogRecord, IngredientRecord, RecipeRecord};
+
+/// Handle to a FeedMe database
+pub struct FeedMe {
+    pool: SqlitePool,
+    ingredients: IngredientCache,
+}
+
+impl FeedMe {
+    /// Wrap an already-connected (and migrated) pool
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            ingredients: IngredientCache::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Connect to the configured database, running migrations
+    pub async fn open(config: &DatabaseConfig) -> Result<Self> {
+        Ok(Self::new(db::connect(config).await?))
+    }
+
+    /// A throwaway in-memory database
+    pub async fn open_in_memory() -> Result<Self> {
+        Ok(Self::new(
+            db::init(db::IN_MEMORY_URL, &DatabaseConfig::default()).await?,
+        ))
+    }
+
+    /// The underlying pool, for queries not covered here
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    pub fn recipes(&self) -> Recipes<'_> {
+        Recipes { feedme: self }
+    }
+
+    pub fn ingredients(&self) -> Ingredients<'_> {
+        Ingredients { feedme: self }
+    }
+
+    /// Aggregate the ingredients of several recipes into one list
+    pub async fn shopping_list(&self, recipe_ids: &[i64]) -> Result<Vec<ShoppingListItem>> {
+        controllers::generate_shopping_list(&self.pool, recipe_ids).await
+    }
+
+    /// Write every recipe as JSON Lines, returning how many were written
+    pub async fn export_all<W: Write>(&self, writer: W) -> Result<usize> {
+        controllers::export_all(&self.pool, writer).await
+    }
+
+    /// Add the recipes from an `export_all` file, returning how many were added
+    pub async fn import_all<R: BufRead>(&self, reader: R) -> Result<usize> {
+        let count = controllers::import_all(&self.pool, reader).await?;
+        // The import creates the ingredients it needs itself
+        self.ingredients.invalidate();
+
+        Ok(count)
+    }
+
+    /// Audit history of one entity, oldest first
+    pub async fn audit_log(&self, entity: &str, entity_id: i64) -> Result<Vec<AuditLogRecord>> {
+        controllers::get_audit_log(&self.pool, entity, entity_id).await
+    }
+}
+
+/// Recipe operations, from `FeedMe::recipes`
+pub struct Recipes<'a> {
+    feedme: &'a FeedMe,
+}
+
+impl<'a> Recipes<'a> {
+    pub async fn get(&self, recipe_id: i64) -> Result<Recipe> {
+        controllers::get_recipe(&self.feedme.pool, recipe_id).await
+    }
+
+    /// Create a recipe linked to existing ingredients, returning its ID
+    pub async fn create(&self, recipe: &Recipe) -> Result<i64> {
+        controllers::create_recipe(&self.feedme.pool, recipe).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<RecipeRecord>> {
+        controllers::list_recipes(&self.feedme.pool).await
+    }
+
+    pub fn stream(&self) -> impl Stream<Item = Result<RecipeRecord>> + 'a {
+        controllers::stream_recipe_records(&self.feedme.pool)
+    }
+
+    /// Every recipe with its ingredients, read lazily as the stream is polled
+    pub fn stream_hydrated(&self) -> impl Stream<Item = Result<Recipe>> + 'a {
+        controllers::stream_recipes(&self.feedme.pool)
+    }
+
+    pub async fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
+        controllers::search_full_text(&self.feedme.pool, query).await
+    }
+
+    /// Recipes using any of the given ingredients, ordered by name
+    pub async fn containing(&self, ingredient_ids: &[i64]) -> Result<Vec<RecipeRecord>> {
+        controllers::get_recipes_containing(&self.feedme.pool, ingredient_ids).await
+    }
+}
+
+/// Ingredient operations, from `FeedMe::ingredients`
+/// Name lookups are served from a shared `IngredientCache`
+pub struct Ingredients<'a> {
+    feedme: &'a FeedMe,
+}
+
+impl Ingredients<'_> {
+    pub async fn all(&self) -> Result<Vec<IngredientRecord>> {
+        controllers::get_all_ingredients(&self.feedme.pool).await
+    }
+
+    /// Look up an ingredient ID by exact name
+    pub async fn find(&self, name: &str) -> Result<Option<i64>> {
+        self.feedme.ingredients.get(name).await
+    }
+
+    /// Look up an ingredient ID, creating the ingredient when it doesn't exist
+    pub async fn get_or_create(&self, name: &str) -> Result<i64> {
+        self.feedme.ingredients.get_or_create(name).await
+    }
+
+    /// Autocomplete ingredient names by prefix
+    pub async fn complete(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        self.feedme.ingredients.complete(prefix, limit).await
+    }
+
+    /// Name → id map of every ingredient
+    pub async fn names(&self) -> Result<HashMap<String, i64>> {
+        self.feedme.ingredients.snapshot().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::{RecipeIngredient, Visibility};
+
+    #[tokio::test]
+    async fn test_recipe_round_trip_through_facade() {
+        let feedme = FeedMe::open_in_memory().await.unwrap();
+
+        let flour = feedme.ingredients().get_or_create("flour").await.unwrap();
+        let recipe = Recipe {
+            id: 0,
+            name: "Flatbread".to_string(),
+            instructions: Some("Mix and griddle".to_string()),
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: flour,
+                ingredient_name: "flour".to_string(),
+                quantity_unit: "2 cups".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let id = feedme.recipes().create(&recipe).await.unwrap();
+
+        assert_eq!(feedme.recipes().get(id).await.unwrap().name, "Flatbread");
+        assert_eq!(feedme.recipes().list().await.unwrap().len(), 1);
+        assert_eq!(
+            feedme.ingredients().find("flour").await.unwrap(),
+            Some(flour)
+        );
+
+        let list = feedme.shopping_list(&[id]).await.unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].combined_quantity, "2 cups");
+    }
+
+    #[tokio::test]
+    async fn test_import_all_refreshes_ingredient_lookups() {
+        let theirs = FeedMe::open_in_memory().await.unwrap();
+        let butter = theirs.ingredients().get_or_create("butter").await.unwrap();
+        let recipe = Recipe {
+            name: "Toast".to_string(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: butter,
+                ingredient_name: "butter".to_string(),
+                quantity_unit: "1 tbsp".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        theirs.recipes().create(&recipe).await.unwrap();
+        let mut export = Vec::new();
+        theirs.export_all(&mut export).await.unwrap();
+
+        let feedme = FeedMe::open_in_memory().await.unwrap();
+        assert_eq!(feedme.ingredients().find("butter").await.unwrap(), None);
+        feedme.import_all(export.as_slice()).await.unwrap();
+
+        assert!(feedme.ingredients().find("butter").await.unwrap().is_some());
+    }
+}
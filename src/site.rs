@@ -0,0 +1,353 @@
+//! The cookbook as a static website
+//!
+//! `feedme site <dir>` renders the shared recipes (see `shared_recipes`) as
+//! plain HTML pages: an index, a page per recipe and a page per tag. Pages
+//! link to each other with relative paths, so the directory can be dropped
+//! on any web host or opened straight from disk.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::label::escape;
+use crate::models::api::Recipe;
+
+/// Styles shared by every page
+const STYLE: &str = "body{font-family:sans-serif;max-width:40em;margin:2em auto;padding:0 1em;line-height:1.5}\
+a{color:#a33}.meta{color:#666}.tags a{margin-right:.5em}li{margin:.2em 0}";
+
+/// One file of the site
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitePage {
+    /// Relative to the site's root, e.g. "recipes/3-pancakes.html"
+    pub path: String,
+    pub html: String,
+}
+
+/// Every page of the site for `recipes`, index first
+pub fn render(title: &str, recipes: &[Recipe]) -> Vec<SitePage> {
+    // Tags that only differ in punctuation share a page
+    let mut tags: BTreeMap<String, (&str, Vec<&Recipe>)> = BTreeMap::new();
+    for recipe in recipes {
+        for tag in &recipe.tags {
+            tags.entry(slug(tag))
+                .or_insert_with(|| (tag, Vec::new()))
+                .1
+                .push(recipe);
+        }
+    }
+
+    let mut pages = vec![SitePage {
+        path: "index.html".to_string(),
+        html: index_page(title, recipes, &tags),
+    }];
+    pages.extend(recipes.iter().map(|recipe| SitePage {
+        path: recipe_path(recipe),
+        html: recipe_page(title, recipe),
+    }));
+    pages.extend(tags.iter().map(|(slug, (tag, tagged))| SitePage {
+        path: format!("tags/{}.html", slug),
+        html: tag_page(title, tag, tagged),
+    }));
+
+    pages
+}
+
+/// Write the pages under `dir`, creating it and its subdirectories
+/// Existing files with the same names are replaced; others are left alone
+pub fn write(dir: &Path, pages: &[SitePage]) -> Result<()> {
+    for page in pages {
+        let path = dir.join(&page.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, &page.html)?;
+    }
+
+    Ok(())
+}
+
+fn index_page(
+    title: &str,
+    recipes: &[Recipe],
+    tags: &BTreeMap<String, (&str, Vec<&Recipe>)>,
+) -> String {
+    let mut body = String::new();
+
+    // Writing to a String can't fail
+    let _ = writeln!(body, "<h1>{}</h1>", escape(title));
+    let _ = writeln!(body, "<ul>");
+    for recipe in recipes {
+        let _ = writeln!(body, "<li>{}</li>", recipe_link(recipe, ""));
+    }
+    let _ = writeln!(body, "</ul>");
+
+    if !tags.is_empty() {
+        let _ = writeln!(body, "<h2>Tags</h2>");
+        let _ = writeln!(body, "<ul>");
+        for (slug, (tag, tagged)) in tags {
+            let _ = writeln!(
+                body,
+                "<li><a href=\"tags/{}.html\">{}</a> <span class=\"meta\">({})</span></li>",
+                slug,
+                escape(tag),
+                tagged.len()
+            );
+        }
+        let _ = writeln!(body, "</ul>");
+    }
+
+    page(title, title, &body)
+}
+
+fn recipe_page(title: &str, recipe: &Recipe) -> String {
+    let mut body = String::new();
+
+    let _ = writeln!(
+        body,
+        "<p><a href=\"../index.html\">{}</a></p>",
+        escape(title)
+    );
+    let _ = writeln!(body, "<h1>{}</h1>", escape(&recipe.name));
+
+    let mut meta = Vec::new();
+    if let Some(servings) = recipe.servings {
+        meta.push(format!("Serves {}", servings));
+    }
+    if let Some(rating) = &recipe.rating {
+        meta.push(format!("Rated {:.1}/5", rating.average));
+    }
+    if let Some(url) = &recipe.source_url {
+        let name = recipe.source_name.as_deref().unwrap_or(url);
+        meta.push(format!(
+            "From <a href=\"{}\">{}</a>",
+            escape(url),
+            escape(name)
+        ));
+    } else if let Some(name) = &recipe.source_name {
+        meta.push(format!("From {}", escape(name)));
+    }
+    if !meta.is_empty() {
+        let _ = writeln!(body, "<p class=\"meta\">{}</p>", meta.join(" · "));
+    }
+
+    if !recipe.tags.is_empty() {
+        let links: Vec<String> = recipe
+            .tags
+            .iter()
+            .map(|tag| format!("<a href=\"../tags/{}.html\">{}</a>", slug(tag), escape(tag)))
+            .collect();
+        let _ = writeln!(body, "<p class=\"tags\">{}</p>", links.join(""));
+    }
+
+    if !recipe.ingredients.is_empty() {
+        let _ = writeln!(body, "<h2>Ingredients</h2>");
+        let _ = writeln!(body, "<ul>");
+        for ingredient in &recipe.ingredients {
+            let _ = write!(
+                body,
+                "<li>{} {}",
+                escape(&ingredient.quantity_unit),
+                escape(&ingredient.ingredient_name)
+            );
+            if let Some(notes) = &ingredient.notes {
+                let _ = write!(body, " <span class=\"meta\">({})</span>", escape(notes));
+            }
+            let _ = writeln!(body, "</li>");
+        }
+        let _ = writeln!(body, "</ul>");
+    }
+
+    if !recipe.equipment.is_empty() {
+        let _ = writeln!(body, "<h2>Equipment</h2>");
+        let _ = writeln!(body, "<p>{}</p>", escape(&recipe.equipment.join(", ")));
+    }
+
+    let steps = recipe.steps();
+    if !steps.is_empty() {
+        let _ = writeln!(body, "<h2>Instructions</h2>");
+        let _ = writeln!(body, "<ol>");
+        for step in steps {
+            let _ = writeln!(body, "<li>{}</li>", escape(step));
+        }
+        let _ = writeln!(body, "</ol>");
+    }
+
+    page(&recipe.name, title, &body)
+}
+
+fn tag_page(title: &str, tag: &str, recipes: &[&Recipe]) -> String {
+    let mut body = String::new();
+
+    let _ = writeln!(
+        body,
+        "<p><a href=\"../index.html\">{}</a></p>",
+        escape(title)
+    );
+    let _ = writeln!(body, "<h1>{}</h1>", escape(tag));
+    let _ = writeln!(body, "<ul>");
+    for recipe in recipes {
+        let _ = writeln!(body, "<li>{}</li>", recipe_link(recipe, "../"));
+    }
+    let _ = writeln!(body, "</ul>");
+
+    page(tag, title, &body)
+}
+
+/// A whole page around `body`, titled "heading - title"
+fn page(heading: &str, title: &str, body: &str) -> String {
+    let title = if heading == title {
+        escape(title)
+    } else {
+        format!("{} - {}", escape(heading), escape(title))
+    };
+    let mut out = String::new();
+
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(
+        out,
+        "<html><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width\"><title>{}</title><style>{}</style></head>",
+        title, STYLE
+    );
+    let _ = writeln!(out, "<body>");
+    out.push_str(body);
+    let _ = writeln!(out, "</body></html>");
+
+    out
+}
+
+/// A link to a recipe's page from a page `root` away from the site's root
+/// ("" or "../")
+fn recipe_link(recipe: &Recipe, root: &str) -> String {
+    format!(
+        "<a href=\"{}{}\">{}</a>",
+        root,
+        recipe_path(recipe),
+        escape(&recipe.name)
+    )
+}
+
+/// "recipes/3-pancakes-gf.html"; the ID keeps recipes with the same name apart
+fn recipe_path(recipe: &Recipe) -> String {
+    format!("recipes/{}-{}.html", recipe.id, slug(&recipe.name))
+}
+
+/// Lowercase letters and digits with runs of anything else as one dash,
+/// e.g. "Pancakes (GF)" becomes "pancakes-gf"
+fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let trimmed = slug.trim_end_matches('-');
+    if trimmed.is_empty() {
+        "untitled".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::{RecipeIngredient, Visibility};
+
+    fn recipe(id: i64, name: &str, tags: &[&str]) -> Recipe {
+        Recipe {
+            id,
+            name: name.to_string(),
+            instructions: Some("Whisk <gently>\n\nFry".to_string()),
+            servings: Some(4),
+            source_url: Some("https://example.com/?a=1&b=2".to_string()),
+            source_name: Some("Grandma".to_string()),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: 1,
+                ingredient_name: "flour".to_string(),
+                quantity_unit: "2 cups".to_string(),
+                notes: Some("sifted".to_string()),
+                steps: Vec::new(),
+            }],
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            nutrition: None,
+        }
+    }
+
+    #[test]
+    fn test_render_site() {
+        let recipes = vec![
+            recipe(3, "Pancakes (GF)", &["Breakfast", "gluten-free"]),
+            recipe(1, "Waffles & Syrup", &["breakfast", "Gluten free"]),
+        ];
+
+        let pages = render("Our <Cookbook>", &recipes);
+        let paths: Vec<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "index.html",
+                "recipes/3-pancakes-gf.html",
+                "recipes/1-waffles-syrup.html",
+                "tags/breakfast.html",
+                "tags/gluten-free.html",
+            ]
+        );
+
+        let index = &pages[0].html;
+        assert!(index.contains("<title>Our &lt;Cookbook&gt;</title>"));
+        assert!(index.contains("<a href=\"recipes/1-waffles-syrup.html\">Waffles &amp; Syrup</a>"));
+        assert!(index.contains(
+            "<a href=\"tags/gluten-free.html\">gluten-free</a> <span class=\"meta\">(2)</span>"
+        ));
+
+        let page = &pages[1].html;
+        assert!(page.contains("<title>Pancakes (GF) - Our &lt;Cookbook&gt;</title>"));
+        assert!(page.contains("<a href=\"../index.html\">"));
+        assert!(
+            page.contains(
+                "Serves 4 · From <a href=\"https://example.com/?a=1&amp;b=2\">Grandma</a>"
+            )
+        );
+        assert!(page.contains("<a href=\"../tags/breakfast.html\">Breakfast</a>"));
+        assert!(page.contains("<li>2 cups flour <span class=\"meta\">(sifted)</span></li>"));
+        assert!(page.contains("<ol>\n<li>Whisk &lt;gently&gt;</li>\n<li>Fry</li>\n</ol>"));
+
+        let tag = &pages[3].html;
+        assert!(tag.contains("<h1>Breakfast</h1>"));
+        assert!(tag.contains("<a href=\"../recipes/3-pancakes-gf.html\">"));
+        assert!(tag.contains("<a href=\"../recipes/1-waffles-syrup.html\">"));
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Pancakes (GF)"), "pancakes-gf");
+        assert_eq!(slug("  Crème brûlée!"), "crème-brûlée");
+        assert_eq!(slug("???"), "untitled");
+    }
+
+    #[test]
+    fn test_write_site() {
+        let dir = std::env::temp_dir().join(format!("feedme-site-{}", std::process::id()));
+        let pages = render("Cookbook", &[recipe(1, "Pancakes", &["breakfast"])]);
+
+        write(&dir, &pages).expect("Failed to write");
+        for page in &pages {
+            assert_eq!(
+                std::fs::read_to_string(dir.join(&page.path)).unwrap(),
+                page.html
+            );
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,824 @@
+//! Storage traits decoupling business logic from a concrete database
+//!
+//! The SQLite pool implements these by delegating to `crate::controllers`,
+//! so code written against the traits also runs on a hand-rolled mock in
+//! downstream tests. With the `postgres` feature a Postgres pool implements
+//! every trait too, with the gaps listed in `crate::postgres`.
+//!
+//! [`FeedMeStore`] bundles every trait for apps embedding the whole of
+//! FeedMe; anything implementing them all gets it for free. It covers the
+//! day-to-day surface: recipes, ingredients, tags, the pantry, meal plans,
+//! saved shopping lists, collections and the cook log. Reports, imports and
+//! exports, the trash, prices, nutrition lookups and the audit log aren't in
+//! it; call those controllers with the `SqlitePool` directly.
+
+use chrono::NaiveDate;
+use std::future::Future;
+
+use sqlx::SqlitePool;
+
+use crate::controllers;
+use crate::error::Result;
+use crate::models::api::{
+    Collection, CookableRecipe, MealPlan, PantryItem, Recipe, RecipeNote, SearchResult,
+    ShoppingList, ShoppingListItem,
+};
+use crate::models::{CollectionRecord, CookLogRecord, IngredientRecord, RecipeRecord};
+
+/// Persistence for ingredients
+pub trait IngredientStore: Send + Sync {
+    /// Create a new ingredient, returning its ID
+    fn create_ingredient(&self, name: &str) -> impl Future<Output = Result<i64>> + Send;
+
+    /// All ingredients ordered by name
+    fn get_all_ingredients(&self) -> impl Future<Output = Result<Vec<IngredientRecord>>> + Send;
+}
+
+/// Persistence for recipes and the queries built on them
+pub trait RecipeStore: Send + Sync {
+    /// Fetch a recipe with all of its ingredients
+    fn get_recipe(&self, recipe_id: i64) -> impl Future<Output = Result<Recipe>> + Send;
+
+    /// Create a recipe linked to existing ingredients, returning its ID
+    fn create_recipe(&self, recipe: &Recipe) -> impl Future<Output = Result<i64>> + Send;
+
+    /// Replace a recipe's name, instructions, servings, source and ingredients
+    fn update_recipe(
+        &self,
+        recipe_id: i64,
+        recipe: &Recipe,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Move a recipe to the trash
+    fn delete_recipe(&self, recipe_id: i64) -> impl Future<Output = Result<()>> + Send;
+
+    /// Every recipe outside the trash, ordered by ID
+    fn list_recipes(&self) -> impl Future<Output = Result<Vec<RecipeRecord>>> + Send;
+
+    /// Recipes matching every term of `query`, best match first
+    fn search_recipes(&self, query: &str)
+    -> impl Future<Output = Result<Vec<SearchResult>>> + Send;
+
+    /// Aggregate the ingredients of several recipes into one list
+    fn generate_shopping_list(
+        &self,
+        recipe_ids: &[i64],
+    ) -> impl Future<Output = Result<Vec<ShoppingListItem>>> + Send;
+}
+
+/// Persistence for pantry stock
+pub trait PantryStore: Send + Sync {
+    /// Add an item to the pantry, returning its ID
+    fn add_to_pantry(&self, item: &PantryItem) -> impl Future<Output = Result<i64>> + Send;
+
+    /// Everything in the pantry
+    fn list_pantry(&self) -> impl Future<Output = Result<Vec<PantryItem>>> + Send;
+
+    /// Change how much of a pantry item is left
+    fn update_pantry_amount(&self, id: i64, amount: f64)
+    -> impl Future<Output = Result<()>> + Send;
+
+    /// Remove a pantry item, returning whether it existed
+    fn remove_from_pantry(&self, id: i64) -> impl Future<Output = Result<bool>> + Send;
+
+    /// Recipes the pantry fully covers
+    fn cookable_recipes(&self) -> impl Future<Output = Result<Vec<CookableRecipe>>> + Send;
+}
+
+/// Persistence for recipe tags
+pub trait TagStore: Send + Sync {
+    /// Tag a recipe
+    fn add_recipe_tag(&self, recipe_id: i64, tag: &str) -> impl Future<Output = Result<()>> + Send;
+
+    /// Untag a recipe, returning whether it had the tag
+    fn remove_recipe_tag(
+        &self,
+        recipe_id: i64,
+        tag: &str,
+    ) -> impl Future<Output = Result<bool>> + Send;
+
+    /// A recipe's tags in alphabetical order
+    fn get_recipe_tags(&self, recipe_id: i64) -> impl Future<Output = Result<Vec<String>>> + Send;
+}
+
+/// Persistence for meal plans
+pub trait MealPlanStore: Send + Sync {
+    /// Save a meal plan with its meals, returning its ID
+    fn save_meal_plan(&self, plan: &MealPlan) -> impl Future<Output = Result<i64>> + Send;
+
+    /// Fetch a meal plan with its meals
+    fn get_meal_plan(&self, plan_id: i64) -> impl Future<Output = Result<MealPlan>> + Send;
+
+    /// Everything needed to cook a plan's meals
+    fn shopping_list_for_plan(
+        &self,
+        plan_id: i64,
+    ) -> impl Future<Output = Result<Vec<ShoppingListItem>>> + Send;
+}
+
+/// Persistence for shopping lists kept between trips
+pub trait ShoppingListStore: Send + Sync {
+    /// Save a shopping list with its items, returning its ID
+    fn save_shopping_list(&self, list: &ShoppingList) -> impl Future<Output = Result<i64>> + Send;
+
+    /// Fetch a saved shopping list with its items
+    fn get_shopping_list(&self, list_id: i64) -> impl Future<Output = Result<ShoppingList>> + Send;
+
+    /// Record the day a list was bought
+    fn mark_purchased(
+        &self,
+        list_id: i64,
+        purchased_on: NaiveDate,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Persistence for named groups of recipes
+pub trait CollectionStore: Send + Sync {
+    /// Create an empty collection, returning its ID
+    fn create_collection(&self, name: &str) -> impl Future<Output = Result<i64>> + Send;
+
+    /// Every collection ordered by name
+    fn list_collections(&self) -> impl Future<Output = Result<Vec<CollectionRecord>>> + Send;
+
+    /// Fetch a collection with its recipes
+    fn get_collection(&self, collection_id: i64)
+    -> impl Future<Output = Result<Collection>> + Send;
+
+    /// Put a recipe in a collection
+    fn add_to_collection(
+        &self,
+        collection_id: i64,
+        recipe_id: i64,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Take a recipe out of a collection, returning whether it was in it
+    fn remove_from_collection(
+        &self,
+        collection_id: i64,
+        recipe_id: i64,
+    ) -> impl Future<Output = Result<bool>> + Send;
+
+    /// Delete a collection (not its recipes), returning whether it existed
+    fn delete_collection(&self, collection_id: i64) -> impl Future<Output = Result<bool>> + Send;
+}
+
+/// Persistence for what was cooked and how it went
+pub trait CookLogStore: Send + Sync {
+    /// Record cooking a recipe, returning the log entry's ID
+    fn log_cook(
+        &self,
+        recipe_id: i64,
+        cooked_on: NaiveDate,
+        notes: Option<&str>,
+    ) -> impl Future<Output = Result<i64>> + Send;
+
+    /// Every time a recipe was cooked
+    fn get_cook_log(
+        &self,
+        recipe_id: i64,
+    ) -> impl Future<Output = Result<Vec<CookLogRecord>>> + Send;
+
+    /// Write a journal entry about one time a recipe was cooked
+    fn add_recipe_note(&self, note: &RecipeNote) -> impl Future<Output = Result<i64>> + Send;
+
+    /// Journal entries from every time a recipe was cooked
+    fn get_recipe_notes(
+        &self,
+        recipe_id: i64,
+    ) -> impl Future<Output = Result<Vec<RecipeNote>>> + Send;
+
+    /// Rate a recipe from 1 to 5
+    fn rate_recipe(&self, recipe_id: i64, rating: u8) -> impl Future<Output = Result<()>> + Send;
+
+    /// Average rating of a recipe, None when it hasn't been rated
+    fn get_average_rating(
+        &self,
+        recipe_id: i64,
+    ) -> impl Future<Output = Result<Option<f64>>> + Send;
+}
+
+/// Every store trait, for apps that embed FeedMe
+pub trait FeedMeStore:
+    IngredientStore
+    + RecipeStore
+    + PantryStore
+    + TagStore
+    + MealPlanStore
+    + ShoppingListStore
+    + CollectionStore
+    + CookLogStore
+{
+}
+
+impl<T> FeedMeStore for T where
+    T: IngredientStore
+        + RecipeStore
+        + PantryStore
+        + TagStore
+        + MealPlanStore
+        + ShoppingListStore
+        + CollectionStore
+        + CookLogStore
+{
+}
+
+impl IngredientStore for SqlitePool {
+    async fn create_ingredient(&self, name: &str) -> Result<i64> {
+        controllers::create_ingredient(self, name).await
+    }
+
+    async fn get_all_ingredients(&self) -> Result<Vec<IngredientRecord>> {
+        controllers::get_all_ingredients(self).await
+    }
+}
+
+impl RecipeStore for SqlitePool {
+    async fn get_recipe(&self, recipe_id: i64) -> Result<Recipe> {
+        controllers::get_recipe(self, recipe_id).await
+    }
+
+    async fn create_recipe(&self, recipe: &Recipe) -> Result<i64> {
+        controllers::create_recipe(self, recipe).await
+    }
+
+    async fn update_recipe(&self, recipe_id: i64, recipe: &Recipe) -> Result<()> {
+        controllers::update_recipe(self, recipe_id, recipe).await
+    }
+
+    async fn delete_recipe(&self, recipe_id: i64) -> Result<()> {
+        controllers::delete_recipe(self, recipe_id).await
+    }
+
+    async fn list_recipes(&self) -> Result<Vec<RecipeRecord>> {
+        controllers::list_recipes(self).await
+    }
+
+    async fn search_recipes(&self, query: &str) -> Result<Vec<SearchResult>> {
+        controllers::search_full_text(self, query).await
+    }
+
+    async fn generate_shopping_list(&self, recipe_ids: &[i64]) -> Result<Vec<ShoppingListItem>> {
+        controllers::generate_shopping_list(self, recipe_ids).await
+    }
+}
+
+impl PantryStore for SqlitePool {
+    async fn add_to_pantry(&self, item: &PantryItem) -> Result<i64> {
+        controllers::add_to_pantry(self, item).await
+    }
+
+    async fn list_pantry(&self) -> Result<Vec<PantryItem>> {
+        controllers::list_pantry(self).await
+    }
+
+    async fn update_pantry_amount(&self, id: i64, amount: f64) -> Result<()> {
+        controllers::update_pantry_amount(self, id, amount).await
+    }
+
+    async fn remove_from_pantry(&self, id: i64) -> Result<bool> {
+        controllers::remove_from_pantry(self, id).await
+    }
+
+    async fn cookable_recipes(&self) -> Result<Vec<CookableRecipe>> {
+        controllers::cookable_recipes(self).await
+    }
+}
+
+impl TagStore for SqlitePool {
+    async fn add_recipe_tag(&self, recipe_id: i64, tag: &str) -> Result<()> {
+        controllers::add_recipe_tag(self, recipe_id, tag).await
+    }
+
+    async fn remove_recipe_tag(&self, recipe_id: i64, tag: &str) -> Result<bool> {
+        controllers::remove_recipe_tag(self, recipe_id, tag).await
+    }
+
+    async fn get_recipe_tags(&self, recipe_id: i64) -> Result<Vec<String>> {
+        controllers::get_recipe_tags(self, recipe_id).await
+    }
+}
+
+impl MealPlanStore for SqlitePool {
+    async fn save_meal_plan(&self, plan: &MealPlan) -> Result<i64> {
+        controllers::save_meal_plan(self, plan).await
+    }
+
+    async fn get_meal_plan(&self, plan_id: i64) -> Result<MealPlan> {
+        controllers::get_meal_plan(self, plan_id).await
+    }
+
+    async fn shopping_list_for_plan(&self, plan_id: i64) -> Result<Vec<ShoppingListItem>> {
+        controllers::shopping_list_for_plan(self, plan_id).await
+    }
+}
+
+impl ShoppingListStore for SqlitePool {
+    async fn save_shopping_list(&self, list: &ShoppingList) -> Result<i64> {
+        controllers::save_shopping_list(self, list).await
+    }
+
+    async fn get_shopping_list(&self, list_id: i64) -> Result<ShoppingList> {
+        controllers::get_shopping_list(self, list_id).await
+    }
+
+    async fn mark_purchased(&self, list_id: i64, purchased_on: NaiveDate) -> Result<()> {
+        controllers::mark_purchased(self, list_id, purchased_on).await
+    }
+}
+
+impl CollectionStore for SqlitePool {
+    async fn create_collection(&self, name: &str) -> Result<i64> {
+        controllers::create_collection(self, name).await
+    }
+
+    async fn list_collections(&self) -> Result<Vec<CollectionRecord>> {
+        controllers::list_collections(self).await
+    }
+
+    async fn get_collection(&self, collection_id: i64) -> Result<Collection> {
+        controllers::get_collection(self, collection_id).await
+    }
+
+    async fn add_to_collection(&self, collection_id: i64, recipe_id: i64) -> Result<()> {
+        controllers::add_to_collection(self, collection_id, recipe_id).await
+    }
+
+    async fn remove_from_collection(&self, collection_id: i64, recipe_id: i64) -> Result<bool> {
+        controllers::remove_from_collection(self, collection_id, recipe_id).await
+    }
+
+    async fn delete_collection(&self, collection_id: i64) -> Result<bool> {
+        controllers::delete_collection(self, collection_id).await
+    }
+}
+
+impl CookLogStore for SqlitePool {
+    async fn log_cook(
+        &self,
+        recipe_id: i64,
+        cooked_on: NaiveDate,
+        notes: Option<&str>,
+    ) -> Result<i64> {
+        controllers::log_cook(self, recipe_id, cooked_on, notes).await
+    }
+
+    async fn get_cook_log(&self, recipe_id: i64) -> Result<Vec<CookLogRecord>> {
+        controllers::get_cook_log(self, recipe_id).await
+    }
+
+    async fn add_recipe_note(&self, note: &RecipeNote) -> Result<i64> {
+        controllers::add_recipe_note(self, note).await
+    }
+
+    async fn get_recipe_notes(&self, recipe_id: i64) -> Result<Vec<RecipeNote>> {
+        controllers::get_recipe_notes(self, recipe_id).await
+    }
+
+    async fn rate_recipe(&self, recipe_id: i64, rating: u8) -> Result<()> {
+        controllers::rate_recipe(self, recipe_id, rating).await
+    }
+
+    async fn get_average_rating(&self, recipe_id: i64) -> Result<Option<f64>> {
+        controllers::get_average_rating(self, recipe_id).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl IngredientStore for sqlx::PgPool {
+    async fn create_ingredient(&self, name: &str) -> Result<i64> {
+        crate::postgres::create_ingredient(self, name).await
+    }
+
+    async fn get_all_ingredients(&self) -> Result<Vec<IngredientRecord>> {
+        crate::postgres::get_all_ingredients(self).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl RecipeStore for sqlx::PgPool {
+    async fn get_recipe(&self, recipe_id: i64) -> Result<Recipe> {
+        crate::postgres::get_recipe(self, recipe_id).await
+    }
+
+    async fn create_recipe(&self, recipe: &Recipe) -> Result<i64> {
+        crate::postgres::create_recipe(self, recipe).await
+    }
+
+    async fn update_recipe(&self, recipe_id: i64, recipe: &Recipe) -> Result<()> {
+        crate::postgres::update_recipe(self, recipe_id, recipe).await
+    }
+
+    async fn delete_recipe(&self, recipe_id: i64) -> Result<()> {
+        crate::postgres::delete_recipe(self, recipe_id).await
+    }
+
+    async fn list_recipes(&self) -> Result<Vec<RecipeRecord>> {
+        crate::postgres::list_recipes(self).await
+    }
+
+    async fn search_recipes(&self, query: &str) -> Result<Vec<SearchResult>> {
+        crate::postgres::search_recipes(self, query).await
+    }
+
+    async fn generate_shopping_list(&self, recipe_ids: &[i64]) -> Result<Vec<ShoppingListItem>> {
+        crate::postgres::generate_shopping_list(self, recipe_ids).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PantryStore for sqlx::PgPool {
+    async fn add_to_pantry(&self, item: &PantryItem) -> Result<i64> {
+        crate::postgres::add_to_pantry(self, item).await
+    }
+
+    async fn list_pantry(&self) -> Result<Vec<PantryItem>> {
+        crate::postgres::list_pantry(self).await
+    }
+
+    async fn update_pantry_amount(&self, id: i64, amount: f64) -> Result<()> {
+        crate::postgres::update_pantry_amount(self, id, amount).await
+    }
+
+    async fn remove_from_pantry(&self, id: i64) -> Result<bool> {
+        crate::postgres::remove_from_pantry(self, id).await
+    }
+
+    async fn cookable_recipes(&self) -> Result<Vec<CookableRecipe>> {
+        crate::postgres::cookable_recipes(self).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl TagStore for sqlx::PgPool {
+    async fn add_recipe_tag(&self, recipe_id: i64, tag: &str) -> Result<()> {
+        crate::postgres::add_recipe_tag(self, recipe_id, tag).await
+    }
+
+    async fn remove_recipe_tag(&self, recipe_id: i64, tag: &str) -> Result<bool> {
+        crate::postgres::remove_recipe_tag(self, recipe_id, tag).await
+    }
+
+    async fn get_recipe_tags(&self, recipe_id: i64) -> Result<Vec<String>> {
+        crate::postgres::get_recipe_tags(self, recipe_id).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl MealPlanStore for sqlx::PgPool {
+    async fn save_meal_plan(&self, plan: &MealPlan) -> Result<i64> {
+        crate::postgres::save_meal_plan(self, plan).await
+    }
+
+    async fn get_meal_plan(&self, plan_id: i64) -> Result<MealPlan> {
+        crate::postgres::get_meal_plan(self, plan_id).await
+    }
+
+    async fn shopping_list_for_plan(&self, plan_id: i64) -> Result<Vec<ShoppingListItem>> {
+        crate::postgres::shopping_list_for_plan(self, plan_id).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl ShoppingListStore for sqlx::PgPool {
+    async fn save_shopping_list(&self, list: &ShoppingList) -> Result<i64> {
+        crate::postgres::save_shopping_list(self, list).await
+    }
+
+    async fn get_shopping_list(&self, list_id: i64) -> Result<ShoppingList> {
+        crate::postgres::get_shopping_list(self, list_id).await
+    }
+
+    async fn mark_purchased(&self, list_id: i64, purchased_on: NaiveDate) -> Result<()> {
+        crate::postgres::mark_purchased(self, list_id, purchased_on).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl CollectionStore for sqlx::PgPool {
+    async fn create_collection(&self, name: &str) -> Result<i64> {
+        crate::postgres::create_collection(self, name).await
+    }
+
+    async fn list_collections(&self) -> Result<Vec<CollectionRecord>> {
+        crate::postgres::list_collections(self).await
+    }
+
+    async fn get_collection(&self, collection_id: i64) -> Result<Collection> {
+        crate::postgres::get_collection(self, collection_id).await
+    }
+
+    async fn add_to_collection(&self, collection_id: i64, recipe_id: i64) -> Result<()> {
+        crate::postgres::add_to_collection(self, collection_id, recipe_id).await
+    }
+
+    async fn remove_from_collection(&self, collection_id: i64, recipe_id: i64) -> Result<bool> {
+        crate::postgres::remove_from_collection(self, collection_id, recipe_id).await
+    }
+
+    async fn delete_collection(&self, collection_id: i64) -> Result<bool> {
+        crate::postgres::delete_collection(self, collection_id).await
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl CookLogStore for sqlx::PgPool {
+    async fn log_cook(
+        &self,
+        recipe_id: i64,
+        cooked_on: NaiveDate,
+        notes: Option<&str>,
+    ) -> Result<i64> {
+        crate::postgres::log_cook(self, recipe_id, cooked_on, notes).await
+    }
+
+    async fn get_cook_log(&self, recipe_id: i64) -> Result<Vec<CookLogRecord>> {
+        crate::postgres::get_cook_log(self, recipe_id).await
+    }
+
+    async fn add_recipe_note(&self, note: &RecipeNote) -> Result<i64> {
+        crate::postgres::add_recipe_note(self, note).await
+    }
+
+    async fn get_recipe_notes(&self, recipe_id: i64) -> Result<Vec<RecipeNote>> {
+        crate::postgres::get_recipe_notes(self, recipe_id).await
+    }
+
+    async fn rate_recipe(&self, recipe_id: i64, rating: u8) -> Result<()> {
+        crate::postgres::rate_recipe(self, recipe_id, rating).await
+    }
+
+    async fn get_average_rating(&self, recipe_id: i64) -> Result<Option<f64>> {
+        crate::postgres::get_average_rating(self, recipe_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FeedMeError;
+    use crate::models::api::{RecipeIngredient, Visibility};
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+    use std::sync::Mutex;
+
+    /// Minimal in-memory store, standing in for a downstream mock
+    #[derive(Default)]
+    struct MockStore {
+        recipes: Mutex<Vec<Recipe>>,
+    }
+
+    impl RecipeStore for MockStore {
+        async fn get_recipe(&self, recipe_id: i64) -> Result<Recipe> {
+            self.recipes
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|r| r.id == recipe_id)
+                .cloned()
+                .ok_or(FeedMeError::RecipeNotFound(recipe_id))
+        }
+
+        async fn create_recipe(&self, recipe: &Recipe) -> Result<i64> {
+            let mut recipes = self.recipes.lock().unwrap();
+            let id = recipes.len() as i64 + 1;
+            recipes.push(Recipe {
+                id,
+                ..recipe.clone()
+            });
+            Ok(id)
+        }
+
+        async fn update_recipe(&self, recipe_id: i64, recipe: &Recipe) -> Result<()> {
+            let mut recipes = self.recipes.lock().unwrap();
+            let stored = recipes
+                .iter_mut()
+                .find(|r| r.id == recipe_id)
+                .ok_or(FeedMeError::RecipeNotFound(recipe_id))?;
+            *stored = Recipe {
+                id: recipe_id,
+                ..recipe.clone()
+            };
+            Ok(())
+        }
+
+        async fn delete_recipe(&self, recipe_id: i64) -> Result<()> {
+            let mut recipes = self.recipes.lock().unwrap();
+            let before = recipes.len();
+            recipes.retain(|r| r.id != recipe_id);
+            if recipes.len() == before {
+                return Err(FeedMeError::RecipeNotFound(recipe_id));
+            }
+            Ok(())
+        }
+
+        async fn list_recipes(&self) -> Result<Vec<RecipeRecord>> {
+            Ok(Vec::new())
+        }
+
+        async fn search_recipes(&self, _query: &str) -> Result<Vec<SearchResult>> {
+            Ok(Vec::new())
+        }
+
+        async fn generate_shopping_list(
+            &self,
+            _recipe_ids: &[i64],
+        ) -> Result<Vec<ShoppingListItem>> {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Business logic written once against the trait
+    async fn roundtrip_name(store: &impl RecipeStore, name: &str) -> Result<String> {
+        let recipe = Recipe {
+            id: 0,
+            name: name.to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let id = store.create_recipe(&recipe).await?;
+        Ok(store.get_recipe(id).await?.name)
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_sqlite_pool_implements_stores(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let flour_id = IngredientStore::create_ingredient(&pool, "flour")
+            .await
+            .expect("Failed to create flour");
+
+        let recipe = Recipe {
+            id: 0,
+            name: "Bread".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![RecipeIngredient {
+                ingredient_id: flour_id,
+                ingredient_name: "flour".to_string(),
+                quantity_unit: "3 cups".to_string(),
+                notes: None,
+                steps: Vec::new(),
+            }],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let recipe_id = RecipeStore::create_recipe(&pool, &recipe)
+            .await
+            .expect("Failed to create recipe");
+
+        let list = RecipeStore::generate_shopping_list(&pool, &[recipe_id])
+            .await
+            .expect("Failed to generate shopping list");
+
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].combined_quantity, "3 cups");
+        assert_eq!(
+            IngredientStore::get_all_ingredients(&pool)
+                .await
+                .expect("Failed to list ingredients")
+                .len(),
+            1
+        );
+    }
+
+    /// Cook a new recipe and tag it, using only the combined trait
+    async fn cook_and_tag(store: &impl FeedMeStore) -> Result<(usize, Vec<String>)> {
+        let recipe = Recipe {
+            id: 0,
+            name: "Omelette".to_string(),
+            instructions: None,
+            created_at: String::new(),
+            ingredients: vec![],
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        };
+        let id = store.create_recipe(&recipe).await?;
+        store
+            .log_cook(id, NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(), None)
+            .await?;
+        store.rate_recipe(id, 4).await?;
+        store.add_recipe_tag(id, "breakfast").await?;
+
+        Ok((
+            store.get_cook_log(id).await?.len(),
+            store.get_recipe_tags(id).await?,
+        ))
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_sqlite_pool_is_a_feedme_store(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let (cooked, tags) = cook_and_tag(&pool).await.expect("Failed to use store");
+
+        assert_eq!(cooked, 1);
+        assert_eq!(tags, vec!["breakfast"]);
+    }
+
+    /// Rename, file and shop for a recipe, then throw it out, using only the
+    /// combined trait
+    async fn file_and_discard(store: &impl FeedMeStore) -> Result<(Vec<String>, usize, usize)> {
+        let recipe = Recipe {
+            name: "Pancakes".to_string(),
+            ..Default::default()
+        };
+        let id = store.create_recipe(&recipe).await?;
+        let renamed = Recipe {
+            name: "Fluffy Pancakes".to_string(),
+            ..recipe
+        };
+        store.update_recipe(id, &renamed).await?;
+
+        let collection_id = store.create_collection("Brunch").await?;
+        store.add_to_collection(collection_id, id).await?;
+        let filed = store
+            .get_collection(collection_id)
+            .await?
+            .recipes
+            .into_iter()
+            .map(|recipe| recipe.name)
+            .collect();
+
+        let list_id = store
+            .save_shopping_list(&ShoppingList {
+                id: 0,
+                name: Some("Saturday".to_string()),
+                meal_plan_id: None,
+                purchased_on: None,
+                store: None,
+                items: Vec::new(),
+            })
+            .await?;
+        store
+            .mark_purchased(list_id, NaiveDate::from_ymd_opt(2025, 3, 1).unwrap())
+            .await?;
+        assert!(
+            store
+                .get_shopping_list(list_id)
+                .await?
+                .purchased_on
+                .is_some()
+        );
+
+        let found = store.search_recipes("fluffy").await?.len();
+        store.delete_recipe(id).await?;
+        Ok((filed, found, store.list_recipes().await?.len()))
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_sqlite_pool_covers_recipe_lifecycle(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        let (filed, found, left) = file_and_discard(&pool).await.expect("Failed to use store");
+
+        assert_eq!(filed, vec!["Fluffy Pancakes"]);
+        assert_eq!(found, 1);
+        assert_eq!(left, 0);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generic_logic_runs_on_any_store(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let mock = MockStore::default();
+
+        let from_sqlite = roundtrip_name(&pool, "Soup").await.expect("sqlite failed");
+        let from_mock = roundtrip_name(&mock, "Soup").await.expect("mock failed");
+
+        assert_eq!(from_sqlite, from_mock);
+        assert!(matches!(
+            mock.get_recipe(42).await,
+            Err(FeedMeError::RecipeNotFound(42))
+        ));
+    }
+}
@@ -0,0 +1,322 @@
+//! Temperatures mentioned in instructions ("bake at 350°F", "heat to 180 C")
+//!
+//! [`find_temperatures`] locates them and [`localize_temperatures`] rewrites
+//! them in the reader's measurement system, so "350°F" reads as "175°C" in
+//! metric. Like quantities, instructions are stored as written and only
+//! converted for display.
+
+use std::ops::Range;
+
+use crate::quantity::format_amount;
+use crate::units::UnitSystem;
+
+/// What a temperature is measured in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureScale {
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureScale {
+    /// The scale a measurement system reads temperatures in
+    pub fn of(system: UnitSystem) -> TemperatureScale {
+        match system {
+            UnitSystem::Metric => TemperatureScale::Celsius,
+            UnitSystem::Imperial => TemperatureScale::Fahrenheit,
+        }
+    }
+
+    /// "°C" or "°F"
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TemperatureScale::Celsius => "°C",
+            TemperatureScale::Fahrenheit => "°F",
+        }
+    }
+}
+
+/// A temperature found in some text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Temperature {
+    /// Where it is in the text, in bytes
+    pub span: Range<usize>,
+    pub degrees: f64,
+    /// Top of a range ("350-375°F")
+    pub max_degrees: Option<f64>,
+    pub scale: TemperatureScale,
+    /// How a range was joined ("-", " to "), kept when rewriting it
+    separator: Option<String>,
+}
+
+impl Temperature {
+    /// The same temperature in another scale, rounded the way a recipe
+    /// would write it: to 5 degrees for oven heats, to 1 below 100
+    pub fn to_scale(&self, scale: TemperatureScale) -> Temperature {
+        Temperature {
+            degrees: convert(self.degrees, self.scale, scale),
+            max_degrees: self
+                .max_degrees
+                .map(|degrees| convert(degrees, self.scale, scale)),
+            scale,
+            ..self.clone()
+        }
+    }
+}
+
+impl std::fmt::Display for Temperature {
+    /// "175°C" or "175-190°C"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_amount(self.degrees))?;
+        if let Some(max) = self.max_degrees {
+            let separator = self.separator.as_deref().unwrap_or("-");
+            write!(f, "{}{}", separator, format_amount(max))?;
+        }
+        write!(f, "{}", self.scale.symbol())
+    }
+}
+
+/// Every temperature in `text` that names its scale, in order
+/// Understands "350°F", "350 °F", "180ºC", "350 degrees F", "180 degrees
+/// Celsius", "350F" and ranges ("350-375°F", "180 to 200 C" is not: a bare
+/// letter after a space is too often a unit like "2 c flour")
+pub fn find_temperatures(text: &str) -> Vec<Temperature> {
+    let mut temperatures = Vec::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let starts_number = number(&text[i..]).is_some()
+            && !text[..i]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '.' || c == '/');
+        if !starts_number {
+            i += text[i..].chars().next().map_or(1, char::len_utf8);
+            continue;
+        }
+
+        match temperature_at(text, i) {
+            Some(temperature) => {
+                i = temperature.span.end;
+                temperatures.push(temperature);
+            }
+            None => i += number(&text[i..]).map_or(1, |(_, len)| len),
+        }
+    }
+
+    temperatures
+}
+
+/// Rewrite the temperatures in `text` in `system`'s scale; text without
+/// temperatures, or already in that scale, comes back unchanged
+pub fn localize_temperatures(text: &str, system: UnitSystem) -> String {
+    let scale = TemperatureScale::of(system);
+    let mut localized = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for temperature in find_temperatures(text) {
+        if temperature.scale == scale {
+            continue;
+        }
+        localized.push_str(&text[last..temperature.span.start]);
+        localized.push_str(&temperature.to_scale(scale).to_string());
+        last = temperature.span.end;
+    }
+    localized.push_str(&text[last..]);
+
+    localized
+}
+
+/// A temperature starting with the number at byte `start`
+fn temperature_at(text: &str, start: usize) -> Option<Temperature> {
+    let (degrees, len) = number(&text[start..])?;
+    let mut end = start + len;
+
+    let mut max_degrees = None;
+    let mut separator = None;
+    for joiner in ["-", "–", " - ", " to "] {
+        if let Some(rest) = text[end..].strip_prefix(joiner)
+            && let Some((max, len)) = number(rest)
+        {
+            max_degrees = Some(max);
+            separator = Some(joiner.to_string());
+            end += joiner.len() + len;
+            break;
+        }
+    }
+
+    let (scale, len) = scale(&text[end..])?;
+    Some(Temperature {
+        span: start..end + len,
+        degrees,
+        max_degrees,
+        scale,
+        separator,
+    })
+}
+
+/// A whole or decimal number, possibly negative, at the start of `text`,
+/// and its length
+fn number(text: &str) -> Option<(f64, usize)> {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let sign = usize::from(text.starts_with('-'));
+    let mut len = digits(&text[sign..]);
+    if len == 0 {
+        return None;
+    }
+    len += sign;
+    if let Some(fraction) = text[len..].strip_prefix('.') {
+        let fraction_len = digits(fraction);
+        if fraction_len > 0 {
+            len += 1 + fraction_len;
+        }
+    }
+
+    Some((text[..len].parse().ok()?, len))
+}
+
+/// The scale written at the start of `text` (after a number), and its length
+fn scale(text: &str) -> Option<(TemperatureScale, usize)> {
+    let spaced = text.starts_with(' ');
+    let mut len = usize::from(spaced);
+
+    let mut marked = false;
+    for marker in ["°", "º", "degrees ", "degree ", "deg "] {
+        if starts_with_ignore_case(&text[len..], marker) {
+            len += marker.len();
+            if text[len..].starts_with(' ') && !marker.ends_with(' ') {
+                len += 1;
+            }
+            marked = true;
+            break;
+        }
+    }
+
+    for (name, scale) in [
+        ("fahrenheit", TemperatureScale::Fahrenheit),
+        ("celsius", TemperatureScale::Celsius),
+        ("centigrade", TemperatureScale::Celsius),
+        ("f", TemperatureScale::Fahrenheit),
+        ("c", TemperatureScale::Celsius),
+    ] {
+        if !starts_with_ignore_case(&text[len..], name) {
+            continue;
+        }
+        // A bare letter only counts straight after the number, in capitals
+        if !marked && name.len() == 1 && (spaced || text[len..].starts_with(name)) {
+            return None;
+        }
+        let end = len + name.len();
+        if text[end..].starts_with(|c: char| c.is_alphanumeric()) {
+            return None;
+        }
+        return Some((scale, end));
+    }
+
+    None
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+fn convert(degrees: f64, from: TemperatureScale, to: TemperatureScale) -> f64 {
+    let converted = match (from, to) {
+        (TemperatureScale::Fahrenheit, TemperatureScale::Celsius) => (degrees - 32.0) * 5.0 / 9.0,
+        (TemperatureScale::Celsius, TemperatureScale::Fahrenheit) => degrees * 9.0 / 5.0 + 32.0,
+        _ => return degrees,
+    };
+
+    let rounded = if converted.abs() >= 100.0 {
+        (converted / 5.0).round() * 5.0
+    } else {
+        converted.round()
+    };
+    // Adding zero turns -0 into 0
+    rounded + 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(text: &str) -> Vec<(f64, Option<f64>, TemperatureScale, &str)> {
+        find_temperatures(text)
+            .into_iter()
+            .map(|t| (t.degrees, t.max_degrees, t.scale, &text[t.span]))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_temperatures() {
+        use TemperatureScale::*;
+
+        assert_eq!(
+            found("Bake at 350°F for 12 minutes"),
+            vec![(350.0, None, Fahrenheit, "350°F")]
+        );
+        assert_eq!(
+            found("Heat oil to 180 ºC, then 165 degrees Fahrenheit inside."),
+            vec![
+                (180.0, None, Celsius, "180 ºC"),
+                (165.0, None, Fahrenheit, "165 degrees Fahrenheit"),
+            ]
+        );
+        assert_eq!(
+            found("Roast at 200C or 375-400°F"),
+            vec![
+                (200.0, None, Celsius, "200C"),
+                (375.0, Some(400.0), Fahrenheit, "375-400°F"),
+            ]
+        );
+        assert_eq!(
+            found("Pour 62.5 ° c of water"),
+            vec![(62.5, None, Celsius, "62.5 ° c")]
+        );
+        assert_eq!(
+            found("Freeze at -18°C"),
+            vec![(-18.0, None, Celsius, "-18°C")]
+        );
+    }
+
+    #[test]
+    fn test_not_temperatures() {
+        for text in [
+            "Add 2 c flour",
+            "Add 2 C flour",
+            "Add 2c flour",
+            "Use 2 cups",
+            "Bake at 350° until golden",
+            "Preheat to gas mark 4",
+            "Stir in 1 5F0 packet",
+            "Mix 3 fresh eggs",
+        ] {
+            assert!(found(text).is_empty(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_localize_temperatures() {
+        assert_eq!(
+            localize_temperatures("Bake at 350°F, then 425 degrees F.", UnitSystem::Metric),
+            "Bake at 175°C, then 220°C."
+        );
+        assert_eq!(
+            localize_temperatures("Cook to 165°F; roast 375 to 400F", UnitSystem::Metric),
+            "Cook to 74°C; roast 190 to 205°C"
+        );
+        assert_eq!(
+            localize_temperatures("Heat to 180°C", UnitSystem::Imperial),
+            "Heat to 355°F"
+        );
+        assert_eq!(
+            localize_temperatures("Freeze at -18°C", UnitSystem::Imperial),
+            "Freeze at 0°F"
+        );
+        // Already in the reader's scale
+        assert_eq!(
+            localize_temperatures("Bake at 350°F", UnitSystem::Imperial),
+            "Bake at 350°F"
+        );
+    }
+}
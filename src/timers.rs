@@ -0,0 +1,323 @@
+//! Durations mentioned in instruction steps ("bake 12 minutes", "rest 1 hour")
+//!
+//! Instructions are stored as text, one step per line. [`step_timers`] finds
+//! the durations in each step so a cooking view can offer one-tap timers.
+//! Timers are derived whenever a recipe is loaded rather than stored, so they
+//! always match the instructions as last edited.
+
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+use crate::quantity::parse_quantity;
+
+/// Number words a duration may start with ("ten minutes")
+const NUMBER_WORDS: [(&str, f64); 14] = [
+    ("a", 1.0),
+    ("an", 1.0),
+    ("one", 1.0),
+    ("two", 2.0),
+    ("three", 3.0),
+    ("four", 4.0),
+    ("five", 5.0),
+    ("six", 6.0),
+    ("seven", 7.0),
+    ("eight", 8.0),
+    ("nine", 9.0),
+    ("ten", 10.0),
+    ("fifteen", 15.0),
+    ("twenty", 20.0),
+];
+
+/// Punctuation trimmed off the ends of words
+const PUNCTUATION: &[char] = &[',', '.', ';', ':', '(', ')', '!', '?', '"'];
+
+/// A duration found in an instruction step
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepTimer {
+    /// Instruction line the duration is in, from 0
+    pub step: usize,
+    /// The duration as written, e.g. "10-12 minutes"
+    pub text: String,
+    /// The duration, or the shortest of a range, in seconds
+    pub seconds: u64,
+    /// Longest of a range ("10-12 minutes"), in seconds
+    pub max_seconds: Option<u64>,
+}
+
+impl StepTimer {
+    /// How long to set the timer for: the shortest of a range, so the food
+    /// can be checked before it's overdone
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.seconds)
+    }
+}
+
+impl fmt::Display for StepTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step {}: {}", self.step + 1, self.text)
+    }
+}
+
+/// Every duration in a recipe's instructions, in order
+pub fn step_timers(instructions: &str) -> Vec<StepTimer> {
+    instructions
+        .lines()
+        .enumerate()
+        .flat_map(|(step, text)| {
+            parse_timers(text)
+                .into_iter()
+                .map(move |timer| StepTimer { step, ..timer })
+        })
+        .collect()
+}
+
+/// The durations in one step, with `step` set to 0
+/// Understands numbers, fractions and number words ("1 1/2 hours", "ten
+/// minutes", "half an hour"), ranges ("10-12 minutes", "5 to 7 mins") and
+/// compound durations ("1 hour 30 minutes", "an hour and a half")
+pub fn parse_timers(text: &str) -> Vec<StepTimer> {
+    let words = words(text);
+    let mut timers = Vec::new();
+    let mut i = 0;
+
+    while i < words.len() {
+        let Some(found) = duration_at(&words, i) else {
+            i += 1;
+            continue;
+        };
+
+        timers.push(StepTimer {
+            step: 0,
+            text: text[words[i].start..words[found.end - 1].end].to_string(),
+            seconds: found.seconds.round() as u64,
+            max_seconds: found.max_seconds.map(|seconds| seconds.round() as u64),
+        });
+        i = found.end;
+    }
+
+    timers
+}
+
+/// A countdown as shown on a timer: "12:00", "1:30:00"
+pub fn format_countdown(remaining: Duration) -> String {
+    let seconds = remaining.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+struct Word {
+    start: usize,
+    end: usize,
+    /// Lowercased, without surrounding punctuation
+    text: String,
+}
+
+/// Whitespace-separated words with their byte positions in `text`
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut offset = 0;
+
+    for raw in text.split_whitespace() {
+        let start = offset + text[offset..].find(raw).unwrap_or(0);
+        offset = start + raw.len();
+
+        let trimmed = raw.trim_matches(PUNCTUATION);
+        if trimmed.is_empty() {
+            continue;
+        }
+        let start = start + raw.find(trimmed).unwrap_or(0);
+        words.push(Word {
+            start,
+            end: start + trimmed.len(),
+            text: trimmed.to_lowercase(),
+        });
+    }
+
+    words
+}
+
+struct Found {
+    seconds: f64,
+    max_seconds: Option<f64>,
+    /// Index of the first word after the duration
+    end: usize,
+}
+
+/// Seconds in one of a time unit
+fn unit_seconds(word: &str) -> Option<f64> {
+    match word {
+        "sec" | "secs" | "second" | "seconds" => Some(1.0),
+        "min" | "mins" | "minute" | "minutes" => Some(60.0),
+        "hr" | "hrs" | "hour" | "hours" => Some(3600.0),
+        _ => None,
+    }
+}
+
+/// An amount written as one word: "12", "1/2", "½", "ten", or "12min" with
+/// the unit attached
+fn amount(word: &str) -> Option<(f64, Option<&str>)> {
+    if let Some((_, value)) = NUMBER_WORDS.iter().find(|(name, _)| *name == word) {
+        return Some((*value, None));
+    }
+
+    let parsed = parse_quantity(word)?;
+    let unit_start = word.len() - parsed.unit.as_deref().map_or(0, str::len);
+    let unit = Some(&word[unit_start..]).filter(|unit| !unit.is_empty());
+    Some((parsed.amount, unit))
+}
+
+/// The duration starting at word `i`, if there is one
+fn duration_at(words: &[Word], i: usize) -> Option<Found> {
+    let word = words[i].text.as_str();
+    let mut next = i + 1;
+
+    // "half an hour"
+    if word == "half" && matches!(words.get(next), Some(w) if w.text == "a" || w.text == "an") {
+        let per_unit = unit_seconds(&words.get(next + 1)?.text)?;
+        return Some(Found {
+            seconds: per_unit / 2.0,
+            max_seconds: None,
+            end: next + 2,
+        });
+    }
+
+    // "10-12 minutes" or "10 - 12", "10 to 12", "10 or 12"
+    let (mut low, mut high, mut unit) = match word.split_once(['-', '–']) {
+        Some((low, high)) => {
+            let (low, _) = amount(low)?;
+            let (high, unit) = amount(high)?;
+            (low, Some(high), unit.map(str::to_string))
+        }
+        None => {
+            let (low, unit) = amount(word)?;
+            (low, None, unit.map(str::to_string))
+        }
+    };
+    // "1 1/2 hours"
+    if high.is_none()
+        && unit.is_none()
+        && let Some(w) = words.get(next)
+        && !NUMBER_WORDS.iter().any(|(name, _)| *name == w.text)
+        && let Some((fraction, attached)) = amount(&w.text).filter(|(value, _)| *value < 1.0)
+    {
+        low += fraction;
+        unit = attached.map(str::to_string);
+        next += 1;
+    }
+    if high.is_none()
+        && unit.is_none()
+        && matches!(words.get(next), Some(w) if matches!(w.text.as_str(), "-" | "–" | "to" | "or"))
+        && let Some((value, attached)) = words.get(next + 1).and_then(|w| amount(&w.text))
+    {
+        high = Some(value);
+        unit = attached.map(str::to_string);
+        next += 2;
+    }
+
+    let per_unit = match unit {
+        Some(unit) => unit_seconds(&unit)?,
+        None => {
+            let per_unit = unit_seconds(&words.get(next)?.text)?;
+            next += 1;
+            per_unit
+        }
+    };
+
+    let mut seconds = low * per_unit;
+    if high.is_none() {
+        // "1 hour 30 minutes", "1 hour and 30 minutes", "an hour and a half"
+        let after_and = match words.get(next) {
+            Some(w) if w.text == "and" => next + 1,
+            _ => next,
+        };
+        if words.get(after_and).is_some_and(|w| w.text == "a")
+            && words.get(after_and + 1).is_some_and(|w| w.text == "half")
+        {
+            seconds += per_unit / 2.0;
+            next = after_and + 2;
+        } else if let Some(rest) = words
+            .get(after_and)
+            .and_then(|_| duration_at(words, after_and))
+            .filter(|rest| rest.max_seconds.is_none() && rest.seconds < per_unit)
+        {
+            seconds += rest.seconds;
+            next = rest.end;
+        }
+    }
+
+    Some(Found {
+        seconds,
+        max_seconds: high.map(|high| high * per_unit),
+        end: next,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("Bake 12 minutes.", "12 minutes", 720, None)]
+    #[case("Rest for 1 hour", "1 hour", 3600, None)]
+    #[case("Simmer 10-12 mins, stirring", "10-12 mins", 600, Some(720))]
+    #[case("Boil 5 to 7 minutes", "5 to 7 minutes", 300, Some(420))]
+    #[case("Whisk for 30 seconds", "30 seconds", 30, None)]
+    #[case("Chill (about 1 1/2 hours)", "1 1/2 hours", 5400, None)]
+    #[case("Proof ½ hour", "½ hour", 1800, None)]
+    #[case("Microwave 90sec", "90sec", 90, None)]
+    #[case("Braise 2 hours 15 minutes", "2 hours 15 minutes", 8100, None)]
+    #[case("Roast an hour and a half", "an hour and a half", 5400, None)]
+    #[case("Let it sit half an hour", "half an hour", 1800, None)]
+    #[case("Steep ten minutes", "ten minutes", 600, None)]
+    #[case("Wait a minute, then serve", "a minute", 60, None)]
+    fn test_parse_timers(
+        #[case] step: &str,
+        #[case] text: &str,
+        #[case] seconds: u64,
+        #[case] max_seconds: Option<u64>,
+    ) {
+        assert_eq!(
+            parse_timers(step),
+            vec![StepTimer {
+                step: 0,
+                text: text.to_string(),
+                seconds,
+                max_seconds,
+            }]
+        );
+    }
+
+    #[rstest]
+    #[case("Preheat the oven to 350°F")]
+    #[case("Add 2 cups flour")]
+    #[case("Cook a few minutes")]
+    #[case("Serve with 1 lemon wedge, minutes before eating")]
+    fn test_no_timers(#[case] step: &str) {
+        assert!(parse_timers(step).is_empty());
+    }
+
+    #[test]
+    fn test_step_timers() {
+        let timers = step_timers("Boil pasta 8 minutes.\nToss.\nBake 20 min, then rest 5 min");
+
+        let found: Vec<String> = timers.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            found,
+            vec!["step 1: 8 minutes", "step 3: 20 min", "step 3: 5 min"]
+        );
+        assert_eq!(timers[0].duration(), Duration::from_secs(480));
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(Duration::from_secs(720)), "12:00");
+        assert_eq!(format_countdown(Duration::from_secs(59)), "0:59");
+        assert_eq!(format_countdown(Duration::from_secs(5400)), "1:30:00");
+    }
+}
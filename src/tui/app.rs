@@ -3,8 +3,16 @@ use std::collections::HashMap;
 use crossterm::event::KeyCode;
 use indexmap::IndexMap;
 use ratatui::Frame;
+use sqlx::SqlitePool;
 
-use super::ingredient_states::RecipeName;
+use super::ingredient_states::{RecipeName, ResumeDraft};
+use crate::controllers::create_recipe_with;
+use crate::error::Result;
+use crate::models::api::{
+    Category, DraftIngredient, DraftStep, Recipe, RecipeDraft, RecipeIngredient, RecipeMetadata,
+    Visibility,
+};
+use crate::validation::{Warning, recipe_warnings};
 
 pub enum AppAction {
     Continue,      // Keep running
@@ -24,6 +32,27 @@ pub struct IngredientInfo {
     pub notes: String,
 }
 
+impl IngredientInfo {
+    /// The ingredient line as saved under `name`; a new ingredient has ID 0
+    /// until created and blank notes are left out
+    pub fn to_recipe_ingredient(&self, name: &str) -> RecipeIngredient {
+        RecipeIngredient {
+            ingredient_id: match self.status {
+                IngredientStatus::Existing(id) => id,
+                IngredientStatus::New => 0,
+            },
+            ingredient_name: name.to_string(),
+            quantity_unit: self.quantity_unit.clone(),
+            steps: Vec::new(),
+            notes: if self.notes.is_empty() {
+                None
+            } else {
+                Some(self.notes.clone())
+            },
+        }
+    }
+}
+
 pub struct RecipeApp {
     state: Box<dyn RecipeState>,
     context: RecipeContext,
@@ -31,6 +60,15 @@ pub struct RecipeApp {
 
 pub struct RecipeContext {
     pub name: String,
+    pub servings: Option<i64>,
+    /// Saved alongside the recipe by `save_recipe_context`, as `Recipe`
+    /// doesn't carry it
+    pub category: Option<Category>,
+    /// Tools the recipe needs, as typed
+    pub equipment: Vec<String>,
+    /// Where the recipe came from, given on the command line
+    pub source_url: Option<String>,
+    pub source_name: Option<String>,
     pub ingredients: IndexMap<String, IngredientInfo>,
     pub possible_ingredients: HashMap<String, i64>, // name -> id
     pub instructions: Vec<String>,
@@ -41,6 +79,11 @@ impl RecipeContext {
     pub fn new(possible_ingredients: HashMap<String, i64>) -> Self {
         Self {
             name: String::new(),
+            servings: None,
+            category: None,
+            equipment: Vec::new(),
+            source_url: None,
+            source_name: None,
             ingredients: IndexMap::new(),
             // TODO: Separate prep from instructions?
             instructions: Vec::new(),
@@ -48,6 +91,107 @@ impl RecipeContext {
             finished: false,
         }
     }
+
+    /// The recipe as entered so far; new ingredients have ID 0 until created
+    pub fn to_recipe(&self) -> Recipe {
+        Recipe {
+            id: 0,
+            name: self.name.clone(),
+            instructions: if self.instructions.is_empty() {
+                None
+            } else {
+                Some(self.instructions.join("\n"))
+            },
+            created_at: String::new(),
+            ingredients: self
+                .ingredients
+                .iter()
+                .map(|(name, info)| info.to_recipe_ingredient(name))
+                .collect(),
+            servings: self.servings,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: self.equipment.clone(),
+            visibility: Visibility::default(),
+            source_url: self.source_url.clone(),
+            source_name: self.source_name.clone(),
+            nutrition: None,
+        }
+    }
+
+    /// What's entered that `Recipe` doesn't carry, saved alongside it
+    pub fn to_metadata(&self) -> RecipeMetadata {
+        RecipeMetadata {
+            category: self.category,
+            ..Default::default()
+        }
+    }
+
+    /// What's been entered, to autosave as having reached `step`
+    pub fn to_draft(&self, step: DraftStep) -> RecipeDraft {
+        RecipeDraft {
+            step,
+            name: self.name.clone(),
+            servings: self.servings,
+            category: self.category,
+            equipment: self.equipment.clone(),
+            ingredients: self
+                .ingredients
+                .iter()
+                .map(|(name, info)| DraftIngredient {
+                    name: name.clone(),
+                    quantity_unit: info.quantity_unit.clone(),
+                    notes: info.notes.clone(),
+                })
+                .collect(),
+            instructions: self.instructions.clone(),
+        }
+    }
+
+    /// Pick a draft back up; its ingredients are matched against
+    /// `possible_ingredients` again, as they may have been added since
+    pub fn from_draft(draft: &RecipeDraft, possible_ingredients: HashMap<String, i64>) -> Self {
+        let ingredients = draft
+            .ingredients
+            .iter()
+            .map(|ingredient| {
+                let status = match possible_ingredients.get(&ingredient.name) {
+                    Some(&id) => IngredientStatus::Existing(id),
+                    None => IngredientStatus::New,
+                };
+                let info = IngredientInfo {
+                    status,
+                    quantity_unit: ingredient.quantity_unit.clone(),
+                    notes: ingredient.notes.clone(),
+                };
+                (ingredient.name.clone(), info)
+            })
+            .collect();
+
+        Self {
+            name: draft.name.clone(),
+            servings: draft.servings,
+            category: draft.category,
+            equipment: draft.equipment.clone(),
+            ingredients,
+            instructions: draft.instructions.clone(),
+            ..Self::new(possible_ingredients)
+        }
+    }
+
+    /// Likely mistakes to point out before saving
+    pub fn warnings(&self) -> Vec<Warning> {
+        recipe_warnings(&self.to_recipe())
+    }
+}
+
+/// Save an entered recipe, creating its new ingredients and setting its
+/// category in the same transaction (see `create_recipe_with`); returns the
+/// recipe ID
+pub async fn save_recipe_context(pool: &SqlitePool, context: &RecipeContext) -> Result<i64> {
+    create_recipe_with(pool, &context.to_recipe(), &context.to_metadata()).await
 }
 
 pub(crate) trait RecipeState {
@@ -57,6 +201,8 @@ pub(crate) trait RecipeState {
         key: KeyCode,
         context: &mut RecipeContext,
     ) -> Option<Box<dyn RecipeState>>;
+    /// Where the entry is, for autosaving; None when nothing is being entered
+    fn step(&self) -> Option<DraftStep>;
 }
 
 impl RecipeApp {
@@ -67,6 +213,19 @@ impl RecipeApp {
         }
     }
 
+    /// Start by offering to resume `draft`, left over from an earlier entry
+    pub fn resume(possible_ingredients: HashMap<String, i64>, draft: RecipeDraft) -> Self {
+        Self {
+            state: Box::new(ResumeDraft::new(draft)),
+            context: RecipeContext::new(possible_ingredients),
+        }
+    }
+
+    /// The entry so far, to autosave; None while asking whether to resume
+    pub fn draft(&self) -> Option<RecipeDraft> {
+        self.state.step().map(|step| self.context.to_draft(step))
+    }
+
     pub fn render(&self, frame: &mut Frame) {
         self.state.render(&self.context, frame);
     }
@@ -95,3 +254,288 @@ impl RecipeApp {
         self.context
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{get_all_ingredients, get_recipe};
+    use crate::error::FeedMeError;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    fn type_line(app: &mut RecipeApp, text: &str) -> AppAction {
+        for c in text.chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter)
+    }
+
+    #[test]
+    fn test_review_before_saving() {
+        let mut app = RecipeApp::new(HashMap::from([("salt".to_string(), 1)]));
+        type_line(&mut app, "Brine");
+        type_line(&mut app, ""); // servings
+        type_line(&mut app, ""); // category
+        type_line(&mut app, ""); // equipment
+        type_line(&mut app, "salt");
+        type_line(&mut app, ""); // quantity
+        type_line(&mut app, ""); // notes
+        type_line(&mut app, ""); // done with ingredients
+
+        // Finishing the steps opens the review instead of saving
+        assert!(matches!(type_line(&mut app, ""), AppAction::Continue));
+        assert!(matches!(
+            app.handle_key(KeyCode::Backspace),
+            AppAction::Continue
+        ));
+        type_line(&mut app, "Dissolve");
+        assert!(matches!(type_line(&mut app, ""), AppAction::Continue));
+        assert!(matches!(
+            app.handle_key(KeyCode::Enter),
+            AppAction::SaveAndExit
+        ));
+
+        let context = app.into_context();
+        let warnings: Vec<String> = context.warnings().iter().map(ToString::to_string).collect();
+        assert_eq!(warnings, vec!["salt: no quantity given"]);
+        let recipe = context.to_recipe();
+        assert_eq!(recipe.ingredients[0].ingredient_id, 1);
+        assert_eq!(recipe.instructions.as_deref(), Some("Dissolve"));
+        assert_eq!(recipe.servings, None);
+        assert_eq!(context.category, None);
+        assert!(recipe.equipment.is_empty());
+    }
+
+    #[test]
+    fn test_category_prompt() {
+        let mut app = RecipeApp::new(HashMap::new());
+        type_line(&mut app, "Brownies");
+        type_line(&mut app, "");
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Up);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.context.category, Some(Category::Breakfast));
+
+        let mut app = RecipeApp::new(HashMap::new());
+        type_line(&mut app, "Brownies");
+        type_line(&mut app, "");
+        app.handle_key(KeyCode::Char('4'));
+        assert_eq!(app.context.category, Some(Category::Dessert));
+        // On to the equipment and ingredients
+        type_line(&mut app, "");
+        type_line(&mut app, "");
+        type_line(&mut app, "");
+        assert!(matches!(
+            app.handle_key(KeyCode::Enter),
+            AppAction::SaveAndExit
+        ));
+    }
+
+    #[test]
+    fn test_servings_prompt() {
+        let mut app = RecipeApp::new(HashMap::new());
+        type_line(&mut app, "Chili");
+        type_line(&mut app, "a crowd");
+        type_line(&mut app, "0");
+        type_line(&mut app, "8");
+        type_line(&mut app, ""); // category
+        type_line(&mut app, ""); // equipment
+        type_line(&mut app, ""); // no ingredients
+        type_line(&mut app, ""); // no steps
+        app.handle_key(KeyCode::Enter);
+
+        assert_eq!(app.into_context().to_recipe().servings, Some(8));
+    }
+
+    #[test]
+    fn test_equipment_prompt() {
+        let mut app = RecipeApp::new(HashMap::new());
+        type_line(&mut app, "Bread");
+        type_line(&mut app, ""); // servings
+        type_line(&mut app, ""); // category
+        type_line(&mut app, "stand mixer, , loaf pan ,oven");
+        assert_eq!(
+            app.context.equipment,
+            vec!["stand mixer", "loaf pan", "oven"]
+        );
+        type_line(&mut app, ""); // no ingredients
+        type_line(&mut app, ""); // no steps
+        app.handle_key(KeyCode::Enter);
+
+        assert_eq!(app.into_context().to_recipe().equipment.len(), 3);
+    }
+
+    fn context(ingredients: &[(&str, IngredientStatus)]) -> RecipeContext {
+        let mut context = RecipeContext::new(HashMap::new());
+        context.name = "Brine".to_string();
+        context.category = Some(Category::Dinner);
+        context.source_url = Some("https://example.com/brine".to_string());
+        context.instructions = vec!["Dissolve".to_string()];
+        for &(name, status) in ingredients {
+            context.ingredients.insert(
+                name.to_string(),
+                IngredientInfo {
+                    status,
+                    quantity_unit: "1 cup".to_string(),
+                    notes: String::new(),
+                },
+            );
+        }
+        context
+    }
+
+    #[test]
+    fn test_resume_draft() {
+        let mut app = RecipeApp::new(HashMap::from([("salt".to_string(), 1)]));
+        assert!(app.draft().unwrap().is_empty());
+        type_line(&mut app, "Brine");
+        type_line(&mut app, "4"); // servings
+        type_line(&mut app, ""); // category
+        type_line(&mut app, ""); // equipment
+        type_line(&mut app, "water");
+        app.handle_key(KeyCode::Char('y'));
+        type_line(&mut app, "1 quart");
+        type_line(&mut app, "cold");
+        let draft = app.draft().unwrap();
+        assert_eq!(draft.step, DraftStep::Ingredients);
+        assert_eq!(draft.servings, Some(4));
+        assert_eq!(draft.ingredients[0].notes, "cold");
+
+        // "water" was created in the meantime
+        let possible = HashMap::from([("salt".to_string(), 1), ("water".to_string(), 2)]);
+        let mut resumed = RecipeApp::resume(possible.clone(), draft.clone());
+        assert_eq!(resumed.draft(), None);
+        resumed.handle_key(KeyCode::Char('y'));
+        assert_eq!(resumed.draft(), Some(draft.clone()));
+        type_line(&mut resumed, "salt");
+        type_line(&mut resumed, "1 cup");
+        type_line(&mut resumed, ""); // notes
+        type_line(&mut resumed, ""); // done with ingredients
+        type_line(&mut resumed, "Dissolve");
+        type_line(&mut resumed, "");
+        assert!(matches!(
+            resumed.handle_key(KeyCode::Enter),
+            AppAction::SaveAndExit
+        ));
+        let recipe = resumed.into_context().to_recipe();
+        assert_eq!(recipe.name, "Brine");
+        let ids: Vec<i64> = recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| ingredient.ingredient_id)
+            .collect();
+        assert_eq!(ids, vec![2, 1]);
+
+        let mut declined = RecipeApp::resume(possible, draft);
+        declined.handle_key(KeyCode::Char('n'));
+        assert!(declined.draft().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_to_recipe() {
+        let mut context = context(&[
+            ("salt", IngredientStatus::Existing(4)),
+            ("water", IngredientStatus::New),
+        ]);
+        context.servings = Some(2);
+        context.instructions.push("Chill".to_string());
+        context.ingredients["water"].notes = "warm".to_string();
+
+        let recipe = context.to_recipe();
+        assert_eq!(recipe.id, 0);
+        assert_eq!(recipe.name, "Brine");
+        assert_eq!(recipe.servings, Some(2));
+        assert_eq!(recipe.instructions.as_deref(), Some("Dissolve\nChill"));
+        assert_eq!(
+            recipe.source_url.as_deref(),
+            Some("https://example.com/brine")
+        );
+        assert_eq!(
+            recipe.ingredients,
+            vec![
+                RecipeIngredient {
+                    ingredient_id: 4,
+                    ingredient_name: "salt".to_string(),
+                    quantity_unit: "1 cup".to_string(),
+                    steps: Vec::new(),
+                    notes: None,
+                },
+                RecipeIngredient {
+                    ingredient_id: 0,
+                    ingredient_name: "water".to_string(),
+                    quantity_unit: "1 cup".to_string(),
+                    steps: Vec::new(),
+                    notes: Some("warm".to_string()),
+                },
+            ]
+        );
+        assert_eq!(context.to_metadata().category, Some(Category::Dinner));
+
+        context.instructions.clear();
+        assert_eq!(context.to_recipe().instructions, None);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_save_recipe_context(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let salt = crate::controllers::create_ingredient(&pool, "salt")
+            .await
+            .unwrap();
+
+        let recipe_id = save_recipe_context(
+            &pool,
+            &context(&[
+                ("salt", IngredientStatus::Existing(salt)),
+                ("water", IngredientStatus::New),
+            ]),
+        )
+        .await
+        .unwrap();
+
+        let recipe = get_recipe(&pool, recipe_id).await.unwrap();
+        let names: Vec<&str> = recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| ingredient.ingredient_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["salt", "water"]);
+        assert_eq!(
+            recipe.source_url.as_deref(),
+            Some("https://example.com/brine")
+        );
+        let category: Option<String> =
+            sqlx::query_scalar("SELECT category FROM recipes WHERE id = ?")
+                .bind(recipe_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(category.as_deref(), Some("dinner"));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_failed_save_creates_no_ingredients(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+
+        // The unknown ingredient ID fails the insert after "water" was created
+        let result = save_recipe_context(
+            &pool,
+            &context(&[
+                ("water", IngredientStatus::New),
+                ("salt", IngredientStatus::Existing(999)),
+            ]),
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(get_all_ingredients(&pool).await.unwrap().is_empty());
+
+        let unnamed = context(&[(" ", IngredientStatus::New)]);
+        let Err(FeedMeError::Validation(errors)) = save_recipe_context(&pool, &unnamed).await
+        else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field, "ingredients[0].ingredient_name");
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::models::api::{Recipe, RecipeIngredient};
+use crate::t;
+use crate::timers::{StepTimer, format_countdown, parse_timers};
+
+/// What the caller should do after a key press
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CookingAction {
+    Continue,
+    Exit,
+}
+
+/// A countdown started from a step
+struct RunningTimer {
+    step: usize,
+    text: String,
+    ends_at: Instant,
+}
+
+/// Step-by-step view of a recipe with countdown timers
+/// Time is passed in rather than read, so callers redraw on a tick and
+/// tests can move the clock
+pub struct CookingApp {
+    recipe: Recipe,
+    steps: Vec<String>,
+    current: usize,
+    running: Vec<RunningTimer>,
+    /// Ingredient emoji by ingredient ID
+    icons: HashMap<i64, String>,
+}
+
+impl CookingApp {
+    pub fn new(recipe: Recipe) -> Self {
+        let steps = recipe.steps().into_iter().map(str::to_string).collect();
+
+        Self {
+            recipe,
+            steps,
+            current: 0,
+            running: Vec::new(),
+            icons: HashMap::new(),
+        }
+    }
+
+    /// Show ingredient emoji (by ingredient ID) next to their names
+    pub fn with_icons(mut self, icons: HashMap<i64, String>) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Ingredients linked to the current step, in recipe order
+    /// Recipes without any step links fall back to the ingredients the step
+    /// mentions by name
+    fn step_ingredients(&self) -> Vec<&RecipeIngredient> {
+        let Some(step) = self.steps.get(self.current) else {
+            return Vec::new();
+        };
+        let ingredients = self.recipe.ingredients.iter();
+        if self
+            .recipe
+            .ingredients
+            .iter()
+            .any(|ingredient| !ingredient.steps.is_empty())
+        {
+            return ingredients
+                .filter(|ingredient| ingredient.steps.contains(&self.current))
+                .collect();
+        }
+
+        let step = step.to_lowercase();
+        ingredients
+            .filter(|ingredient| mentions(&step, &ingredient.ingredient_name))
+            .collect()
+    }
+
+    /// Durations in the current step
+    fn step_timers(&self) -> Vec<StepTimer> {
+        self.steps
+            .get(self.current)
+            .map(|step| parse_timers(step))
+            .unwrap_or_default()
+    }
+
+    /// Whether any started timer is still counting down
+    pub fn has_running_timers(&self, now: Instant) -> bool {
+        self.running.iter().any(|timer| timer.ends_at > now)
+    }
+
+    pub fn render(&self, frame: &mut Frame, now: Instant) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(8),
+                Constraint::Length(6),
+            ])
+            .split(frame.area());
+
+        let mut title = t!(
+            "cook-title",
+            recipe = self.recipe.name,
+            step = self.current + 1,
+            total = self.steps.len()
+        );
+        if !self.recipe.tags.is_empty() {
+            title = format!("{} [{}]", title, self.recipe.tags.join(", "));
+        }
+        let step = match self.steps.get(self.current) {
+            Some(step) => step.clone(),
+            None => t!("cook-no-steps"),
+        };
+        let step =
+            Paragraph::new(Line::from(step).style(Style::default().add_modifier(Modifier::BOLD)))
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(step, chunks[0]);
+
+        let ingredients: Vec<Line> = self
+            .step_ingredients()
+            .iter()
+            .map(|ingredient| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>12}  ", ingredient.quantity_unit),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(match self.icons.get(&ingredient.ingredient_id) {
+                        Some(icon) => format!("{} {}", icon, ingredient.ingredient_name),
+                        None => ingredient.ingredient_name.clone(),
+                    }),
+                ])
+            })
+            .collect();
+        let ingredients = Paragraph::new(ingredients).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("cook-ingredients")),
+        );
+        frame.render_widget(ingredients, chunks[1]);
+
+        let mut timers: Vec<Line> = self
+            .step_timers()
+            .iter()
+            .enumerate()
+            .map(|(i, timer)| Line::from(format!("[{}] {}", i + 1, timer.text)))
+            .collect();
+        timers.extend(self.running.iter().map(|timer| {
+            let remaining = timer.ends_at.saturating_duration_since(now);
+            let label = format!(
+                "{} ({})",
+                timer.text,
+                t!("cook-timer-step", step = timer.step + 1)
+            );
+            if remaining.is_zero() {
+                Line::from(format!("{}  {}", t!("cook-timer-done"), label))
+                    .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            } else {
+                Line::from(format!("{}  {}", format_countdown(remaining), label))
+            }
+        }));
+        let timers = Paragraph::new(timers).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("cook-timers")),
+        );
+        frame.render_widget(timers, chunks[2]);
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode, now: Instant) -> CookingAction {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => return CookingAction::Exit,
+            KeyCode::Right | KeyCode::Char('n') | KeyCode::Char(' ') | KeyCode::Enter => {
+                self.current = (self.current + 1).min(self.steps.len().saturating_sub(1));
+            }
+            KeyCode::Left | KeyCode::Char('p') | KeyCode::Backspace => {
+                self.current = self.current.saturating_sub(1);
+            }
+            KeyCode::Char('t') => self.start_timer(0, now),
+            KeyCode::Char(c @ '1'..='9') => {
+                self.start_timer(c as usize - '1' as usize, now);
+            }
+            // Clear timers that have gone off
+            KeyCode::Char('x') => self.running.retain(|timer| timer.ends_at > now),
+            _ => {}
+        }
+
+        CookingAction::Continue
+    }
+
+    /// Start the current step's `index`th timer, or restart it if running
+    fn start_timer(&mut self, index: usize, now: Instant) {
+        let Some(timer) = self.step_timers().into_iter().nth(index) else {
+            return;
+        };
+
+        self.running
+            .retain(|running| !(running.step == self.current && running.text == timer.text));
+        self.running.push(RunningTimer {
+            step: self.current,
+            text: timer.text.clone(),
+            ends_at: now + timer.duration(),
+        });
+        self.running.sort_by_key(|running| running.ends_at);
+    }
+
+    /// Time left on each started timer, soonest first
+    pub fn remaining(&self, now: Instant) -> Vec<(String, Duration)> {
+        self.running
+            .iter()
+            .map(|timer| {
+                (
+                    timer.text.clone(),
+                    timer.ends_at.saturating_duration_since(now),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Whether a lowercased step names an ingredient, by its full name or its
+/// last word ("canned tomatoes" matches "add the tomatoes")
+fn mentions(step: &str, ingredient_name: &str) -> bool {
+    let name = ingredient_name.to_lowercase();
+    contains_word(step, &name)
+        || name
+            .split_whitespace()
+            .last()
+            .is_some_and(|word| word.len() > 2 && contains_word(step, word))
+}
+
+/// Whether `word` appears in `text` as a whole word, allowing a plural "s"
+/// ("oil" isn't in "boil")
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let mut after = text[start + word.len()..].chars();
+        let next = match after.next() {
+            Some('s') => after.next(),
+            next => next,
+        };
+        !before.is_some_and(char::is_alphanumeric) && !next.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::Visibility;
+
+    fn recipe() -> Recipe {
+        Recipe {
+            id: 1,
+            name: "Pasta".to_string(),
+            instructions: Some(
+                "Boil the spaghetti 8-10 minutes\n\nWarm the garlic in olive oil for 2 minutes\nAdd the tomatoes, simmer 15 min"
+                    .to_string(),
+            ),
+            servings: Some(2),
+            ingredients: [
+                ("spaghetti", "200 g"),
+                ("garlic", "2 cloves"),
+                ("olive oil", "2 tbsp"),
+                ("canned tomatoes", "400 g"),
+                ("basil", "1 bunch"),
+            ]
+            .into_iter()
+            .map(|(name, quantity)| RecipeIngredient {
+                ingredient_id: 0,
+                ingredient_name: name.to_string(),
+                quantity_unit: quantity.to_string(),
+                notes: None,
+                steps: Vec::new(),
+            })
+            .collect(),
+            created_at: String::new(),
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+source_name: None,
+            nutrition: None,
+        }
+    }
+
+    fn names(app: &CookingApp) -> Vec<&str> {
+        app.step_ingredients()
+            .iter()
+            .map(|ingredient| ingredient.ingredient_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_steps_and_ingredients() {
+        let now = Instant::now();
+        let mut app = CookingApp::new(recipe());
+        assert_eq!(app.steps.len(), 3);
+        assert_eq!(names(&app), vec!["spaghetti"]);
+
+        app.handle_key(KeyCode::Right, now);
+        assert_eq!(names(&app), vec!["garlic", "olive oil"]);
+        app.handle_key(KeyCode::Right, now);
+        app.handle_key(KeyCode::Right, now);
+        assert_eq!(app.current, 2);
+        assert_eq!(names(&app), vec!["canned tomatoes"]);
+
+        app.handle_key(KeyCode::Left, now);
+        assert_eq!(app.current, 1);
+        assert_eq!(app.handle_key(KeyCode::Char('q'), now), CookingAction::Exit);
+    }
+
+    #[test]
+    fn test_linked_step_ingredients() {
+        let now = Instant::now();
+        let mut recipe = recipe();
+        // Salt the water, which the step doesn't name
+        recipe.ingredients[0].steps = vec![0];
+        recipe.ingredients[3].steps = vec![0, 2];
+        let mut app = CookingApp::new(recipe);
+        assert_eq!(names(&app), vec!["spaghetti", "canned tomatoes"]);
+
+        // Once a recipe has links, unlinked steps show nothing
+        app.handle_key(KeyCode::Right, now);
+        assert!(names(&app).is_empty());
+        app.handle_key(KeyCode::Right, now);
+        assert_eq!(names(&app), vec!["canned tomatoes"]);
+    }
+
+    #[test]
+    fn test_timers_count_down() {
+        let start = Instant::now();
+        let mut app = CookingApp::new(recipe());
+
+        app.handle_key(KeyCode::Char('t'), start);
+        app.handle_key(KeyCode::Right, start);
+        app.handle_key(KeyCode::Char('2'), start); // no second timer in this step
+        app.handle_key(KeyCode::Char('1'), start);
+
+        let later = start + Duration::from_secs(150);
+        assert_eq!(
+            app.remaining(later),
+            vec![
+                ("2 minutes".to_string(), Duration::ZERO),
+                ("8-10 minutes".to_string(), Duration::from_secs(330)),
+            ]
+        );
+        assert!(app.has_running_timers(later));
+
+        app.handle_key(KeyCode::Char('x'), later);
+        assert_eq!(app.remaining(later).len(), 1);
+        assert!(!app.has_running_timers(start + Duration::from_secs(480)));
+    }
+}
@@ -2,12 +2,15 @@ use crossterm::event::KeyCode;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
 };
 
 use super::app::{IngredientInfo, IngredientStatus, RecipeContext, RecipeState};
+use crate::models::api::{Category, DraftStep, RecipeDraft};
+use crate::t;
+use crate::validation::{Concern, Warning};
 
 pub(crate) struct RecipeName {
     current_input: String,
@@ -26,7 +29,7 @@ impl RecipeState for RecipeName {
         let block = Paragraph::new(self.current_input.as_str()).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Recipe Name (Enter to Continue)"),
+                .title(t!("importer-name-title")),
         );
         frame.render_widget(block, frame.area());
     }
@@ -48,11 +51,221 @@ impl RecipeState for RecipeName {
             KeyCode::Enter => {
                 context.name = self.current_input.clone();
                 self.current_input.clear();
+                Some(Box::new(RecipeServings::new()))
+            }
+            _ => None,
+        }
+    }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Name)
+    }
+}
+
+/// How many servings the recipe makes; left blank when unknown
+pub(crate) struct RecipeServings {
+    current_input: String,
+    error_message: Option<String>,
+}
+
+impl RecipeServings {
+    pub fn new() -> Self {
+        Self {
+            current_input: String::new(),
+            error_message: None,
+        }
+    }
+}
+
+impl RecipeState for RecipeServings {
+    fn render(&self, context: &RecipeContext, frame: &mut Frame) {
+        let title = match &self.error_message {
+            Some(error) => t!(
+                "importer-servings-error",
+                recipe = context.name,
+                error = error
+            ),
+            None => t!("importer-servings-title", recipe = context.name),
+        };
+
+        let block = Paragraph::new(self.current_input.as_str())
+            .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(block, frame.area());
+    }
+
+    fn handle_key(
+        &mut self,
+        key: KeyCode,
+        context: &mut RecipeContext,
+    ) -> Option<Box<dyn RecipeState>> {
+        match key {
+            KeyCode::Char(c) => {
+                self.current_input.push(c);
+                self.error_message = None;
+                None
+            }
+            KeyCode::Backspace => {
+                self.current_input.pop();
+                self.error_message = None;
+                None
+            }
+            KeyCode::Enter => {
+                let input = self.current_input.trim();
+                if input.is_empty() {
+                    context.servings = None;
+                } else {
+                    match input.parse::<i64>() {
+                        Ok(servings) if servings > 0 => context.servings = Some(servings),
+                        _ => {
+                            self.error_message = Some(format!("'{}' is not a whole number", input));
+                            self.current_input.clear();
+                            return None;
+                        }
+                    }
+                }
+                Some(Box::new(RecipeCategory::new()))
+            }
+            _ => None,
+        }
+    }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Servings)
+    }
+}
+
+/// Which meal the recipe is for, picked from a list; the first entry leaves
+/// it unsorted
+pub(crate) struct RecipeCategory {
+    selected: usize,
+}
+
+impl RecipeCategory {
+    pub fn new() -> Self {
+        Self { selected: 0 }
+    }
+
+    /// None, then every category
+    fn options() -> impl Iterator<Item = Option<Category>> {
+        std::iter::once(None).chain(Category::ALL.into_iter().map(Some))
+    }
+}
+
+impl RecipeState for RecipeCategory {
+    fn render(&self, context: &RecipeContext, frame: &mut Frame) {
+        let lines: Vec<Line> = Self::options()
+            .enumerate()
+            .map(|(i, category)| {
+                let label = match category {
+                    Some(category) => format!("{} {}", i, category),
+                    None => format!("  {}", t!("importer-category-none")),
+                };
+                if i == self.selected {
+                    Line::from(Span::styled(
+                        format!("> {}", label),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ))
+                } else {
+                    Line::from(format!("  {}", label))
+                }
+            })
+            .collect();
+
+        let block = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("importer-category-title", recipe = context.name)),
+        );
+        frame.render_widget(block, frame.area());
+    }
+
+    fn handle_key(
+        &mut self,
+        key: KeyCode,
+        context: &mut RecipeContext,
+    ) -> Option<Box<dyn RecipeState>> {
+        match key {
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(Category::ALL.len());
+                None
+            }
+            // Number keys pick a category straight away
+            KeyCode::Char(c @ '1'..='9') if c as usize - '0' as usize <= Category::ALL.len() => {
+                context.category = Some(Category::ALL[c as usize - '1' as usize]);
+                Some(Box::new(RecipeEquipment::new()))
+            }
+            KeyCode::Enter => {
+                context.category = Self::options().nth(self.selected).flatten();
+                Some(Box::new(RecipeEquipment::new()))
+            }
+            _ => None,
+        }
+    }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Category)
+    }
+}
+
+/// Tools the recipe needs, typed on one line separated by commas; left blank
+/// when nothing special is needed
+pub(crate) struct RecipeEquipment {
+    current_input: String,
+}
+
+impl RecipeEquipment {
+    pub fn new() -> Self {
+        Self {
+            current_input: String::new(),
+        }
+    }
+}
+
+impl RecipeState for RecipeEquipment {
+    fn render(&self, context: &RecipeContext, frame: &mut Frame) {
+        let block = Paragraph::new(self.current_input.as_str()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("importer-equipment-title", recipe = context.name)),
+        );
+        frame.render_widget(block, frame.area());
+    }
+
+    fn handle_key(
+        &mut self,
+        key: KeyCode,
+        context: &mut RecipeContext,
+    ) -> Option<Box<dyn RecipeState>> {
+        match key {
+            KeyCode::Char(c) => {
+                self.current_input.push(c);
+                None
+            }
+            KeyCode::Backspace => {
+                self.current_input.pop();
+                None
+            }
+            KeyCode::Enter => {
+                context.equipment = self
+                    .current_input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect();
                 Some(Box::new(IngredientList::new()))
             }
             _ => None,
         }
     }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Equipment)
+    }
 }
 
 pub(crate) struct IngredientList {
@@ -104,17 +317,18 @@ impl RecipeState for IngredientList {
         let ingredient_list = Paragraph::new(ingredient_lines).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Ingredients for {}", context.name)),
+                .title(t!("importer-ingredients-title", recipe = context.name)),
         );
         frame.render_widget(ingredient_list, chunks[0]);
 
         let title = if let Some(error) = &self.error_message {
-            format!("Enter ingredients for {} - ERROR: {}", context.name, error)
-        } else {
-            format!(
-                "Enter ingredients {} (Enter on empty to continue)",
-                context.name
+            t!(
+                "importer-ingredient-error",
+                recipe = context.name,
+                error = error
             )
+        } else {
+            t!("importer-ingredient-input", recipe = context.name)
         };
 
         let input = Paragraph::new(self.current_input.as_str())
@@ -167,6 +381,10 @@ impl RecipeState for IngredientList {
             _ => None,
         }
     }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Ingredients)
+    }
 }
 
 pub(crate) struct ConfirmIngredient {
@@ -181,12 +399,16 @@ impl ConfirmIngredient {
 
 impl RecipeState for ConfirmIngredient {
     fn render(&self, _context: &RecipeContext, frame: &mut Frame) {
-        let message = format!("Add new ingredient '{}'?\n\n(Y)es / (N)", self.ingredient);
+        let message = format!(
+            "{}\n\n{}",
+            t!("importer-confirm-prompt", ingredient = self.ingredient),
+            t!("importer-confirm-keys")
+        );
 
         let block = Paragraph::new(message).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Confirm New Ingredient"),
+                .title(t!("importer-confirm-title")),
         );
         frame.render_widget(block, frame.area());
     }
@@ -211,6 +433,10 @@ impl RecipeState for ConfirmIngredient {
             _ => None, // Ignore other keys
         }
     }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Ingredients)
+    }
 }
 
 pub(crate) struct IngredientQuantity {
@@ -234,7 +460,7 @@ impl RecipeState for IngredientQuantity {
         let input = Paragraph::new(self.current_input.as_str()).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Quantity for {}", self.ingredient)),
+                .title(t!("importer-quantity-title", ingredient = self.ingredient)),
         );
 
         frame.render_widget(input, frame.area());
@@ -265,6 +491,10 @@ impl RecipeState for IngredientQuantity {
             _ => None,
         }
     }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Ingredients)
+    }
 }
 
 pub(crate) struct IngredientNotes {
@@ -290,7 +520,7 @@ impl RecipeState for IngredientNotes {
         let input = Paragraph::new(self.current_input.as_str()).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Notes for {} (Enter to skip)", self.ingredient)),
+                .title(t!("importer-notes-title", ingredient = self.ingredient)),
         );
 
         frame.render_widget(input, frame.area());
@@ -325,6 +555,10 @@ impl RecipeState for IngredientNotes {
             _ => None,
         }
     }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Ingredients)
+    }
 }
 
 struct Instructions {
@@ -379,7 +613,7 @@ impl RecipeState for Instructions {
         let ingredient_list = Paragraph::new(ingredient_lines).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Ingredients for {}", context.name)),
+                .title(t!("importer-ingredients-title", recipe = context.name)),
         );
         frame.render_widget(ingredient_list, chunks[0]);
 
@@ -392,13 +626,16 @@ impl RecipeState for Instructions {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let instruction_list = Paragraph::new(instructions_text)
-            .block(Block::default().borders(Borders::ALL).title("Instructions"));
+        let instruction_list = Paragraph::new(instructions_text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("importer-instructions-title")),
+        );
         frame.render_widget(instruction_list, chunks[1]);
 
         // Render input
         let step_num = context.instructions.len() + 1;
-        let title = format!("Enter step {} (Enter on empty to finish)", step_num);
+        let title = t!("importer-step-input", step = step_num);
 
         let input = Paragraph::new(self.current_input.as_str())
             .block(Block::default().borders(Borders::ALL).title(title));
@@ -422,9 +659,8 @@ impl RecipeState for Instructions {
                 let instruction = self.current_input.clone();
 
                 if instruction.is_empty() {
-                    // Finished with instructions - signal to save
-                    context.finished = true;
-                    None
+                    // Finished with instructions - review before saving
+                    Some(Box::new(Review::new(context)))
                 } else {
                     context.instructions.push(instruction);
                     self.current_input.clear();
@@ -434,4 +670,162 @@ impl RecipeState for Instructions {
             _ => None,
         }
     }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Instructions)
+    }
+}
+
+/// Last look at the recipe, with any warnings, before saving
+struct Review {
+    warnings: Vec<Warning>,
+}
+
+impl Review {
+    fn new(context: &RecipeContext) -> Self {
+        Self {
+            warnings: context.warnings(),
+        }
+    }
+}
+
+impl RecipeState for Review {
+    fn render(&self, context: &RecipeContext, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Summary
+                Constraint::Min(1),    // Warnings
+                Constraint::Length(3), // Keys
+            ])
+            .split(frame.area());
+
+        let summary = Paragraph::new(t!(
+            "importer-review-summary",
+            ingredients = context.ingredients.len(),
+            steps = context.instructions.len()
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("importer-review-title", recipe = context.name)),
+        );
+        frame.render_widget(summary, chunks[0]);
+
+        let warning_lines: Vec<Line> = if self.warnings.is_empty() {
+            vec![Line::from(t!("importer-review-ok"))]
+        } else {
+            self.warnings
+                .iter()
+                .map(|warning| {
+                    Line::from(Span::styled(
+                        describe_warning(warning),
+                        Style::default().fg(Color::Yellow),
+                    ))
+                })
+                .collect()
+        };
+        let warning_list = Paragraph::new(warning_lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("importer-warnings-title")),
+        );
+        frame.render_widget(warning_list, chunks[1]);
+
+        let keys = Paragraph::new(t!("importer-review-keys"))
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(keys, chunks[2]);
+    }
+
+    fn handle_key(
+        &mut self,
+        key: KeyCode,
+        context: &mut RecipeContext,
+    ) -> Option<Box<dyn RecipeState>> {
+        match key {
+            KeyCode::Enter => {
+                // Signal to save
+                context.finished = true;
+                None
+            }
+            KeyCode::Backspace => Some(Box::new(Instructions::new())),
+            _ => None,
+        }
+    }
+
+    fn step(&self) -> Option<DraftStep> {
+        Some(DraftStep::Review)
+    }
+}
+
+/// A warning in the current locale
+fn describe_warning(warning: &Warning) -> String {
+    match warning.concern {
+        Concern::MissingQuantity => t!("warning-missing-quantity", subject = warning.subject),
+        Concern::LargeQuantity => t!("warning-large-quantity", subject = warning.subject),
+        Concern::LongStep { step, chars } => t!("warning-long-step", step = step, chars = chars),
+    }
+}
+
+/// Offered first when a draft was left from last time: pick it up where it
+/// was left, or start over
+pub(crate) struct ResumeDraft {
+    draft: RecipeDraft,
+}
+
+impl ResumeDraft {
+    pub fn new(draft: RecipeDraft) -> Self {
+        Self { draft }
+    }
+}
+
+impl RecipeState for ResumeDraft {
+    fn render(&self, _context: &RecipeContext, frame: &mut Frame) {
+        let message = format!(
+            "{}\n\n{}",
+            t!("importer-resume-prompt", recipe = self.draft.name),
+            t!("importer-resume-keys")
+        );
+
+        let block = Paragraph::new(message).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("importer-resume-title")),
+        );
+        frame.render_widget(block, frame.area());
+    }
+
+    fn handle_key(
+        &mut self,
+        key: KeyCode,
+        context: &mut RecipeContext,
+    ) -> Option<Box<dyn RecipeState>> {
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let possible_ingredients = std::mem::take(&mut context.possible_ingredients);
+                *context = RecipeContext::from_draft(&self.draft, possible_ingredients);
+                Some(resume_state(self.draft.step, context))
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => Some(Box::new(RecipeName::new())),
+            _ => None,
+        }
+    }
+
+    // Nothing is being entered until the draft is taken up or turned down
+    fn step(&self) -> Option<DraftStep> {
+        None
+    }
+}
+
+/// The state to carry on from `step` in, with `context` restored from a draft
+fn resume_state(step: DraftStep, context: &RecipeContext) -> Box<dyn RecipeState> {
+    match step {
+        DraftStep::Name => Box::new(RecipeName::new()),
+        DraftStep::Servings => Box::new(RecipeServings::new()),
+        DraftStep::Category => Box::new(RecipeCategory::new()),
+        DraftStep::Equipment => Box::new(RecipeEquipment::new()),
+        DraftStep::Ingredients => Box::new(IngredientList::new()),
+        DraftStep::Instructions => Box::new(Instructions::new()),
+        DraftStep::Review => Box::new(Review::new(context)),
+    }
 }
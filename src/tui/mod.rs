@@ -1,2 +1,5 @@
 pub mod app;
+pub mod cooking;
 mod ingredient_states;
+pub mod pantry;
+pub mod print;
@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::models::api::PantryItem;
+use crate::quantity::parse_quantity;
+use crate::t;
+
+/// Number of autocomplete suggestions shown while adding an item
+const SUGGESTION_LIMIT: usize = 5;
+
+/// What the caller should do after a key press
+/// The app never touches the database itself; the caller applies the change
+/// and hands back the refreshed pantry with `set_items`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PantryAction {
+    Continue,
+    Exit,
+    SetAmount {
+        id: i64,
+        amount: f64,
+    },
+    SetStaple {
+        id: i64,
+        is_staple: bool,
+    },
+    Remove {
+        id: i64,
+    },
+    /// `ingredient_id` is None for an ingredient that needs to be created
+    Add {
+        ingredient_name: String,
+        ingredient_id: Option<i64>,
+        amount: f64,
+        unit: Option<String>,
+    },
+}
+
+enum Mode {
+    Browse,
+    AddName {
+        input: String,
+    },
+    AddQuantity {
+        ingredient_name: String,
+        input: String,
+    },
+}
+
+pub struct PantryApp {
+    items: Vec<PantryItem>,
+    selected: usize,
+    mode: Mode,
+    possible_ingredients: HashMap<String, i64>, // name -> id
+    message: Option<String>,
+}
+
+impl PantryApp {
+    pub fn new(items: Vec<PantryItem>, possible_ingredients: HashMap<String, i64>) -> Self {
+        Self {
+            items,
+            selected: 0,
+            mode: Mode::Browse,
+            possible_ingredients,
+            message: None,
+        }
+    }
+
+    /// Replace the listed stock after the caller has applied an action
+    pub fn set_items(&mut self, items: Vec<PantryItem>) {
+        self.items = items;
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    /// Make a newly created ingredient available to autocomplete
+    pub fn add_possible_ingredient(&mut self, name: String, id: i64) {
+        self.possible_ingredients.insert(name, id);
+    }
+
+    /// Show an error from applying an action
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    /// Known ingredient names starting with `prefix`, case-insensitively
+    fn suggestions(&self, prefix: &str) -> Vec<&str> {
+        let prefix = prefix.to_lowercase();
+        let mut names: Vec<&str> = self
+            .possible_ingredients
+            .keys()
+            .filter(|name| name.to_lowercase().starts_with(&prefix))
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        names.truncate(SUGGESTION_LIMIT);
+        names
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(3)])
+            .split(frame.area());
+
+        let lines: Vec<Line> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let line = Line::from(item.to_string());
+                if i == self.selected && matches!(self.mode, Mode::Browse) {
+                    line.style(Style::default().add_modifier(Modifier::REVERSED))
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        let title = match &self.message {
+            Some(message) => t!("pantry-error-title", error = message),
+            None => t!("pantry-title"),
+        };
+        let list = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(list, chunks[0]);
+
+        let (input, title) = match &self.mode {
+            Mode::Browse => (String::new(), t!("pantry-select")),
+            Mode::AddName { input } => {
+                let suggestions = self.suggestions(input);
+                let title = if suggestions.is_empty() || input.is_empty() {
+                    t!("pantry-ingredient-input")
+                } else {
+                    t!(
+                        "pantry-ingredient-suggestions",
+                        suggestions = suggestions.join(", ")
+                    )
+                };
+                (input.clone(), title)
+            }
+            Mode::AddQuantity {
+                ingredient_name,
+                input,
+            } => (
+                input.clone(),
+                t!("pantry-quantity-input", ingredient = ingredient_name),
+            ),
+        };
+        let input =
+            Paragraph::new(input).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(input, chunks[1]);
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> PantryAction {
+        self.message = None;
+
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => self.handle_browse_key(key),
+            Mode::AddName { mut input } => {
+                match key {
+                    KeyCode::Char(c) => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Tab => {
+                        if let Some(name) = self.suggestions(&input).first() {
+                            input = name.to_string();
+                        }
+                    }
+                    KeyCode::Enter if !input.trim().is_empty() => {
+                        self.mode = Mode::AddQuantity {
+                            ingredient_name: input.trim().to_string(),
+                            input: String::new(),
+                        };
+                        return PantryAction::Continue;
+                    }
+                    KeyCode::Esc => return PantryAction::Continue,
+                    _ => {}
+                }
+                self.mode = Mode::AddName { input };
+                PantryAction::Continue
+            }
+            Mode::AddQuantity {
+                ingredient_name,
+                mut input,
+            } => {
+                match key {
+                    KeyCode::Char(c) => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Enter => match parse_quantity(&input) {
+                        Some(quantity) => {
+                            return PantryAction::Add {
+                                ingredient_id: self
+                                    .possible_ingredients
+                                    .get(&ingredient_name)
+                                    .copied(),
+                                ingredient_name,
+                                amount: quantity.amount,
+                                unit: quantity.unit,
+                            };
+                        }
+                        None => {
+                            self.message = Some(t!("pantry-bad-quantity", input = input));
+                        }
+                    },
+                    KeyCode::Esc => return PantryAction::Continue,
+                    _ => {}
+                }
+                self.mode = Mode::AddQuantity {
+                    ingredient_name,
+                    input,
+                };
+                PantryAction::Continue
+            }
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyCode) -> PantryAction {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => return PantryAction::Exit,
+            KeyCode::Char('a') => {
+                self.mode = Mode::AddName {
+                    input: String::new(),
+                };
+                return PantryAction::Continue;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.items.len() {
+                    self.selected += 1;
+                }
+                return PantryAction::Continue;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                return PantryAction::Continue;
+            }
+            _ => {}
+        }
+
+        let Some(item) = self.items.get(self.selected) else {
+            return PantryAction::Continue;
+        };
+
+        match key {
+            KeyCode::Char('+') => PantryAction::SetAmount {
+                id: item.id,
+                amount: item.amount + 1.0,
+            },
+            KeyCode::Char('-') => PantryAction::SetAmount {
+                id: item.id,
+                amount: (item.amount - 1.0).max(0.0),
+            },
+            KeyCode::Char('s') => PantryAction::SetStaple {
+                id: item.id,
+                is_staple: !item.is_staple,
+            },
+            KeyCode::Char('d') => PantryAction::Remove { id: item.id },
+            _ => PantryAction::Continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i64, name: &str, amount: f64) -> PantryItem {
+        PantryItem {
+            id,
+            ingredient_id: id,
+            ingredient_name: name.to_string(),
+            amount,
+            unit: None,
+            expires_on: None,
+            is_staple: false,
+            icon: None,
+            min_amount: None,
+        }
+    }
+
+    fn app() -> PantryApp {
+        PantryApp::new(
+            vec![item(1, "eggs", 6.0), item(2, "lemons", 0.5)],
+            HashMap::from([("garlic".to_string(), 3), ("ginger".to_string(), 4)]),
+        )
+    }
+
+    #[test]
+    fn test_browse_keys() {
+        let mut app = app();
+
+        assert_eq!(
+            app.handle_key(KeyCode::Char('+')),
+            PantryAction::SetAmount { id: 1, amount: 7.0 }
+        );
+        app.handle_key(KeyCode::Down);
+        assert_eq!(
+            app.handle_key(KeyCode::Char('-')),
+            PantryAction::SetAmount { id: 2, amount: 0.0 }
+        );
+        assert_eq!(
+            app.handle_key(KeyCode::Char('s')),
+            PantryAction::SetStaple {
+                id: 2,
+                is_staple: true
+            }
+        );
+        assert_eq!(
+            app.handle_key(KeyCode::Char('d')),
+            PantryAction::Remove { id: 2 }
+        );
+
+        app.set_items(vec![item(1, "eggs", 6.0)]);
+        assert_eq!(
+            app.handle_key(KeyCode::Char('d')),
+            PantryAction::Remove { id: 1 }
+        );
+        assert_eq!(app.handle_key(KeyCode::Char('q')), PantryAction::Exit);
+    }
+
+    #[test]
+    fn test_add_with_autocomplete() {
+        let mut app = app();
+
+        app.handle_key(KeyCode::Char('a'));
+        app.handle_key(KeyCode::Char('G'));
+        app.handle_key(KeyCode::Char('a'));
+        app.handle_key(KeyCode::Tab);
+        app.handle_key(KeyCode::Enter);
+        for c in "2 heads".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+
+        assert_eq!(
+            app.handle_key(KeyCode::Enter),
+            PantryAction::Add {
+                ingredient_name: "garlic".to_string(),
+                ingredient_id: Some(3),
+                amount: 2.0,
+                unit: Some("heads".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_add_rejects_unreadable_quantity() {
+        let mut app = app();
+
+        for key in [KeyCode::Char('a'), KeyCode::Char('x'), KeyCode::Enter] {
+            app.handle_key(key);
+        }
+        for c in "some".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+
+        assert_eq!(app.handle_key(KeyCode::Enter), PantryAction::Continue);
+        assert!(app.message.is_some());
+        app.handle_key(KeyCode::Backspace);
+        assert!(matches!(app.mode, Mode::AddQuantity { .. }));
+    }
+}
@@ -0,0 +1,301 @@
+//! Styled recipes printed straight to the terminal
+//!
+//! `feedme show --pretty` builds the same ratatui `Line`s and `Style`s the
+//! TUIs draw with, then writes them out as ANSI escape codes instead of taking
+//! over the screen, so the recipe stays in the scrollback.
+
+use crossterm::style::{Attribute, ContentStyle};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::models::api::Recipe;
+use crate::quantity::format_amount;
+
+/// Text attributes carried over from ratatui modifiers
+const ATTRIBUTES: [(Modifier, Attribute); 5] = [
+    (Modifier::BOLD, Attribute::Bold),
+    (Modifier::DIM, Attribute::Dim),
+    (Modifier::ITALIC, Attribute::Italic),
+    (Modifier::UNDERLINED, Attribute::Underlined),
+    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+];
+
+fn header(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        text.to_string(),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn dim(text: String) -> Span<'static> {
+    Span::styled(text, Style::default().add_modifier(Modifier::DIM))
+}
+
+/// A recipe laid out like `Recipe`'s Display, with bold headers and the
+/// ingredient quantities right-aligned in a column
+pub fn recipe_lines(recipe: &Recipe) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(
+        recipe.name.clone(),
+        Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+    ))];
+
+    let mut details = vec![format!("#{}", recipe.id)];
+    if let Some(servings) = recipe.servings {
+        details.push(format!("serves {}", servings));
+    }
+    if let Some(rating) = &recipe.rating {
+        details.push(format!("rated {}/5", format_amount(rating.average)));
+    }
+    details.push(format!("created {}", recipe.created_at));
+    lines.push(Line::from(dim(details.join(" · "))));
+    if !recipe.tags.is_empty() {
+        lines.push(Line::from(Span::styled(
+            recipe.tags.join(", "),
+            Style::default().fg(Color::Cyan),
+        )));
+    }
+    let source: Vec<&str> = [&recipe.source_name, &recipe.source_url]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    if !source.is_empty() {
+        lines.push(Line::from(dim(format!("from {}", source.join(" · ")))));
+    }
+
+    lines.push(Line::default());
+    lines.push(header("Ingredients"));
+    let width = recipe
+        .ingredients
+        .iter()
+        .map(|ingredient| ingredient.quantity_unit.chars().count())
+        .max()
+        .unwrap_or(0);
+    for ingredient in &recipe.ingredients {
+        let mut spans = vec![
+            Span::styled(
+                format!("  {:>width$}  ", ingredient.quantity_unit, width = width),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(ingredient.ingredient_name.clone()),
+        ];
+        if let Some(notes) = &ingredient.notes {
+            spans.push(Span::styled(
+                format!(" ({})", notes),
+                Style::default().add_modifier(Modifier::ITALIC | Modifier::DIM),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let steps: Vec<&str> = recipe
+        .instructions
+        .as_deref()
+        .unwrap_or("")
+        .lines()
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .collect();
+    if !steps.is_empty() {
+        lines.push(Line::default());
+        lines.push(header("Instructions"));
+        let width = steps.len().to_string().len();
+        for (i, step) in steps.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:>width$}. ", i + 1, width = width),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(step.to_string()),
+            ]));
+        }
+    }
+
+    if !recipe.timers.is_empty() {
+        lines.push(Line::default());
+        lines.push(header("Timers"));
+        for timer in &recipe.timers {
+            lines.push(Line::from(vec![
+                dim(format!("  step {}  ", timer.step + 1)),
+                Span::raw(timer.text.clone()),
+            ]));
+        }
+    }
+
+    if let Some(nutrition) = &recipe.nutrition {
+        lines.push(Line::default());
+        lines.push(header("Nutrition per serving"));
+        let facts = &nutrition.per_serving;
+        for (label, amount, unit) in [
+            ("Calories", facts.calories, "kcal"),
+            ("Protein", facts.protein_g, "g"),
+            ("Carbohydrates", facts.carbohydrates_g, "g"),
+            ("Fat", facts.fat_g, "g"),
+        ] {
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {:<14}", label)),
+                Span::styled(
+                    format!("{:>6} {}", format_amount(amount), unit),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        if !nutrition.missing.is_empty() {
+            lines.push(Line::from(dim(format!(
+                "  not counted: {}",
+                nutrition.missing.join(", ")
+            ))));
+        }
+    }
+
+    if !recipe.journal.is_empty() {
+        lines.push(Line::default());
+        lines.push(header("Journal"));
+        for note in &recipe.journal {
+            let mut spans = vec![
+                dim(format!("  {}  ", note.noted_on)),
+                Span::raw(note.text.clone()),
+            ];
+            if let Some(delta) = note.rating_delta {
+                let color = if delta < 0 { Color::Red } else { Color::Green };
+                spans.push(Span::styled(
+                    format!(" ({:+})", delta),
+                    Style::default().fg(color),
+                ));
+            }
+            if let Some(modifications) = &note.modifications {
+                spans.push(Span::styled(
+                    format!(" [changed: {}]", modifications),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    lines
+}
+
+/// Lines as text with ANSI escape codes, one per line
+/// Colors are left out when `$NO_COLOR` is set
+pub fn to_ansi(lines: &[Line]) -> String {
+    let mut out = String::new();
+    for line in lines {
+        for span in &line.spans {
+            out.push_str(&styled(&span.content, line.style.patch(span.style)));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn styled(text: &str, style: Style) -> String {
+    let mut content_style = ContentStyle::new();
+    content_style.foreground_color = style.fg.map(Into::into);
+    content_style.background_color = style.bg.map(Into::into);
+    for (modifier, attribute) in ATTRIBUTES {
+        if style.add_modifier.contains(modifier) {
+            content_style.attributes.set(attribute);
+        }
+    }
+
+    content_style.apply(text).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::{RecipeIngredient, RecipeNote, Visibility};
+    use crate::timers::step_timers;
+    use chrono::NaiveDate;
+
+    fn recipe() -> Recipe {
+        let instructions = "Boil the spaghetti 8 minutes\n\nToss with the sauce";
+        Recipe {
+            id: 3,
+            name: "Pasta".to_string(),
+            instructions: Some(instructions.to_string()),
+            servings: Some(2),
+            ingredients: [
+                ("spaghetti", "200 g", None),
+                ("garlic", "2 cloves", Some("minced")),
+            ]
+            .into_iter()
+            .map(|(name, quantity, notes)| RecipeIngredient {
+                ingredient_id: 0,
+                ingredient_name: name.to_string(),
+                quantity_unit: quantity.to_string(),
+                notes: notes.map(str::to_string),
+                steps: Vec::new(),
+            })
+            .collect(),
+            created_at: "2026-10-01".to_string(),
+            timers: step_timers(instructions),
+            journal: vec![RecipeNote {
+                id: 1,
+                cook_log_id: 1,
+                noted_on: NaiveDate::from_ymd_opt(2026, 10, 5).unwrap(),
+                text: "more garlic".to_string(),
+                rating_delta: Some(-1),
+                modifications: None,
+            }],
+            tags: vec!["pasta".to_string(), "weeknight".to_string()],
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: Some("https://example.com/pasta".to_string()),
+            source_name: Some("Example Kitchen".to_string()),
+            nutrition: None,
+        }
+    }
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_recipe_lines() {
+        assert_eq!(
+            plain(&recipe_lines(&recipe())),
+            vec![
+                "Pasta",
+                "#3 · serves 2 · created 2026-10-01",
+                "pasta, weeknight",
+                "from Example Kitchen · https://example.com/pasta",
+                "",
+                "Ingredients",
+                "     200 g  spaghetti",
+                "  2 cloves  garlic (minced)",
+                "",
+                "Instructions",
+                "  1. Boil the spaghetti 8 minutes",
+                "  2. Toss with the sauce",
+                "",
+                "Timers",
+                "  step 1  8 minutes",
+                "",
+                "Journal",
+                "  2026-10-05  more garlic (-1)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ansi_styles() {
+        let line = Line::from(vec![
+            Span::raw("plain "),
+            Span::styled("bold", Style::default().add_modifier(Modifier::BOLD)),
+        ]);
+
+        let ansi = to_ansi(&[line]);
+        assert!(ansi.starts_with("plain "));
+        assert!(ansi.contains("\u{1b}[1mbold"));
+        assert!(ansi.ends_with('\n'));
+    }
+}
@@ -0,0 +1,549 @@
+//! Canonical measurement units
+//!
+//! Recipes spell units every which way ("tbsp", "Tablespoons", "T"). `Unit`
+//! gives them one canonical form so quantities can be scaled, summed and
+//! converted; anything unrecognised is kept verbatim as `Unit::Freeform`.
+
+use serde::Deserialize;
+use std::fmt;
+
+/// What a unit measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitKind {
+    Volume,
+    Mass,
+    Count,
+    Freeform,
+}
+
+/// Measurement system quantities are displayed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    Metric,
+    /// US customary measures
+    Imperial,
+}
+
+/// How unit names are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitStyle {
+    /// "2 tbsp"
+    #[default]
+    Abbreviated,
+    /// "2 tablespoons"
+    Full,
+}
+
+impl UnitStyle {
+    /// The style `text` is written in ("tablespoons" is full, "tbsp" is not)
+    pub fn of(text: &str) -> UnitStyle {
+        let unit = Unit::parse(text);
+        let text = text.trim().to_lowercase();
+        if text != unit.abbreviation()
+            && (text == unit.full_name(false) || text == unit.full_name(true))
+        {
+            UnitStyle::Full
+        } else {
+            UnitStyle::Abbreviated
+        }
+    }
+}
+
+/// A measurement unit
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Unit {
+    // Volume
+    Millilitre,
+    Litre,
+    Teaspoon,
+    Tablespoon,
+    FluidOunce,
+    Cup,
+    Pint,
+    Quart,
+    Gallon,
+    // Mass
+    Gram,
+    Kilogram,
+    Ounce,
+    Pound,
+    /// A bare count ("3 eggs", "2 whole")
+    Count,
+    /// Anything else ("pinch", "clove"), lowercased
+    Freeform(String),
+}
+
+impl Unit {
+    /// Parse a unit from its common spellings and abbreviations
+    /// Never fails: unknown text becomes `Unit::Freeform`. Case only matters
+    /// for the cookbook shorthands "T" (tablespoon) and "t" (teaspoon)
+    pub fn parse(text: &str) -> Unit {
+        let text = text.trim();
+        match text {
+            "T" | "Tbs" | "Tbsp" => return Unit::Tablespoon,
+            "t" => return Unit::Teaspoon,
+            _ => {}
+        }
+
+        let lower = text.to_lowercase();
+        let normalized = lower.trim_end_matches('.');
+        match normalized {
+            "ml" | "millilitre" | "millilitres" | "milliliter" | "milliliters" => Unit::Millilitre,
+            "l" | "litre" | "litres" | "liter" | "liters" => Unit::Litre,
+            "tsp" | "tsps" | "teaspoon" | "teaspoons" => Unit::Teaspoon,
+            "tbsp" | "tbsps" | "tbs" | "tablespoon" | "tablespoons" => Unit::Tablespoon,
+            "fl oz" | "fl. oz" | "floz" | "fluid ounce" | "fluid ounces" => Unit::FluidOunce,
+            "c" | "cup" | "cups" => Unit::Cup,
+            "pt" | "pint" | "pints" => Unit::Pint,
+            "qt" | "quart" | "quarts" => Unit::Quart,
+            "gal" | "gallon" | "gallons" => Unit::Gallon,
+            "g" | "gr" | "gram" | "grams" | "gramme" | "grammes" => Unit::Gram,
+            "kg" | "kilo" | "kilos" | "kilogram" | "kilograms" => Unit::Kilogram,
+            "oz" | "ounce" | "ounces" => Unit::Ounce,
+            "lb" | "lbs" | "pound" | "pounds" => Unit::Pound,
+            "" | "whole" | "each" | "ea" | "piece" | "pieces" => Unit::Count,
+            _ => Unit::Freeform(lower),
+        }
+    }
+
+    pub fn kind(&self) -> UnitKind {
+        match self {
+            Unit::Millilitre
+            | Unit::Litre
+            | Unit::Teaspoon
+            | Unit::Tablespoon
+            | Unit::FluidOunce
+            | Unit::Cup
+            | Unit::Pint
+            | Unit::Quart
+            | Unit::Gallon => UnitKind::Volume,
+            Unit::Gram | Unit::Kilogram | Unit::Ounce | Unit::Pound => UnitKind::Mass,
+            Unit::Count => UnitKind::Count,
+            Unit::Freeform(_) => UnitKind::Freeform,
+        }
+    }
+
+    /// Size in millilitres, for volume units
+    pub fn millilitres(&self) -> Option<f64> {
+        Some(match self {
+            Unit::Millilitre => 1.0,
+            Unit::Litre => 1000.0,
+            Unit::Teaspoon => 4.92892,
+            Unit::Tablespoon => 14.7868,
+            Unit::FluidOunce => 29.5735,
+            Unit::Cup => 236.588,
+            Unit::Pint => 473.176,
+            Unit::Quart => 946.353,
+            Unit::Gallon => 3785.41,
+            _ => return None,
+        })
+    }
+
+    /// Size in grams, for mass units
+    pub fn grams(&self) -> Option<f64> {
+        Some(match self {
+            Unit::Gram => 1.0,
+            Unit::Kilogram => 1000.0,
+            Unit::Ounce => 28.3495,
+            Unit::Pound => 453.592,
+            _ => return None,
+        })
+    }
+
+    /// Convert an amount of this unit to `to`, if both measure the same thing
+    pub fn convert(&self, amount: f64, to: &Unit) -> Option<f64> {
+        if self == to {
+            return Some(amount);
+        }
+
+        match (self.kind(), to.kind()) {
+            (UnitKind::Volume, UnitKind::Volume) => {
+                Some(amount * self.millilitres()? / to.millilitres()?)
+            }
+            (UnitKind::Mass, UnitKind::Mass) => Some(amount * self.grams()? / to.grams()?),
+            _ => None,
+        }
+    }
+
+    /// Like `convert`, but also converts between volume and mass using the
+    /// ingredient's density in grams per millilitre
+    pub fn convert_with_density(
+        &self,
+        amount: f64,
+        to: &Unit,
+        grams_per_ml: Option<f64>,
+    ) -> Option<f64> {
+        if let Some(converted) = self.convert(amount, to) {
+            return Some(converted);
+        }
+
+        let density = grams_per_ml.filter(|density| *density > 0.0)?;
+        match (self.kind(), to.kind()) {
+            (UnitKind::Volume, UnitKind::Mass) => {
+                Some(amount * self.millilitres()? * density / to.grams()?)
+            }
+            (UnitKind::Mass, UnitKind::Volume) => {
+                Some(amount * self.grams()? / density / to.millilitres()?)
+            }
+            _ => None,
+        }
+    }
+
+    /// The canonical abbreviation, e.g. "tbsp"; the same for any amount
+    /// except cups, which pluralize
+    fn abbreviation(&self) -> &str {
+        match self {
+            Unit::Millilitre => "ml",
+            Unit::Litre => "l",
+            Unit::Teaspoon => "tsp",
+            Unit::Tablespoon => "tbsp",
+            Unit::FluidOunce => "fl oz",
+            Unit::Cup => "cup",
+            Unit::Pint => "pt",
+            Unit::Quart => "qt",
+            Unit::Gallon => "gal",
+            Unit::Gram => "g",
+            Unit::Kilogram => "kg",
+            Unit::Ounce => "oz",
+            Unit::Pound => "lb",
+            Unit::Count => "",
+            Unit::Freeform(text) => text,
+        }
+    }
+
+    fn full_name(&self, plural: bool) -> String {
+        let singular = match self {
+            Unit::Millilitre => "millilitre",
+            Unit::Litre => "litre",
+            Unit::Teaspoon => "teaspoon",
+            Unit::Tablespoon => "tablespoon",
+            Unit::FluidOunce => "fluid ounce",
+            Unit::Cup => "cup",
+            Unit::Pint => "pint",
+            Unit::Quart => "quart",
+            Unit::Gallon => "gallon",
+            Unit::Gram => "gram",
+            Unit::Kilogram => "kilogram",
+            Unit::Ounce => "ounce",
+            Unit::Pound => "pound",
+            // Freeform text is kept exactly as written
+            Unit::Count | Unit::Freeform(_) => return self.abbreviation().to_string(),
+        };
+
+        if plural {
+            format!("{}s", singular)
+        } else {
+            singular.to_string()
+        }
+    }
+
+    /// The unit's name for `amount` in `style`: "1 cup" but "2 cups",
+    /// "1/2 tablespoon" but "3 tablespoons"
+    pub fn name(&self, amount: f64, style: UnitStyle) -> String {
+        let plural = amount > 1.0;
+        match (style, self) {
+            (UnitStyle::Abbreviated, Unit::Cup) if plural => "cups".to_string(),
+            (UnitStyle::Abbreviated, unit) => unit.abbreviation().to_string(),
+            (UnitStyle::Full, unit) => unit.full_name(plural),
+        }
+    }
+
+    /// The system this unit belongs to, None for counts, freeform units and
+    /// spoons (which metric and imperial recipes share)
+    pub fn system(&self) -> Option<UnitSystem> {
+        match self {
+            Unit::Millilitre | Unit::Litre | Unit::Gram | Unit::Kilogram => {
+                Some(UnitSystem::Metric)
+            }
+            Unit::FluidOunce
+            | Unit::Cup
+            | Unit::Pint
+            | Unit::Quart
+            | Unit::Gallon
+            | Unit::Ounce
+            | Unit::Pound => Some(UnitSystem::Imperial),
+            Unit::Teaspoon | Unit::Tablespoon | Unit::Count | Unit::Freeform(_) => None,
+        }
+    }
+
+    /// Express an amount in `system`, picking a unit that suits its size
+    /// Units outside any system, or already in it, are returned unchanged
+    pub fn in_system(&self, amount: f64, system: UnitSystem) -> (f64, Unit) {
+        if self.system().is_none_or(|own| own == system) {
+            return (amount, self.clone());
+        }
+
+        let target = match (self.kind(), system) {
+            (UnitKind::Mass, UnitSystem::Metric) => {
+                let grams = amount * self.grams().unwrap_or(1.0);
+                if grams >= 1000.0 {
+                    Unit::Kilogram
+                } else {
+                    Unit::Gram
+                }
+            }
+            (UnitKind::Volume, UnitSystem::Metric) => {
+                let millilitres = amount * self.millilitres().unwrap_or(1.0);
+                if millilitres >= 1000.0 {
+                    Unit::Litre
+                } else {
+                    Unit::Millilitre
+                }
+            }
+            (UnitKind::Mass, UnitSystem::Imperial) => {
+                let grams = amount * self.grams().unwrap_or(1.0);
+                if grams >= Unit::Pound.grams().unwrap_or(1.0) {
+                    Unit::Pound
+                } else {
+                    Unit::Ounce
+                }
+            }
+            (UnitKind::Volume, UnitSystem::Imperial) => {
+                let millilitres = amount * self.millilitres().unwrap_or(1.0);
+                if millilitres >= Unit::Cup.millilitres().unwrap_or(1.0) / 4.0 {
+                    Unit::Cup
+                } else if millilitres >= Unit::Tablespoon.millilitres().unwrap_or(1.0) {
+                    Unit::Tablespoon
+                } else {
+                    Unit::Teaspoon
+                }
+            }
+            _ => return (amount, self.clone()),
+        };
+
+        match self.convert(amount, &target) {
+            Some(converted) => (converted, target),
+            None => (amount, self.clone()),
+        }
+    }
+}
+
+/// A user-defined unit, e.g. a "stick" (of butter) is 113 g
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomUnit {
+    /// Lowercased singular name
+    pub name: String,
+    /// How much of `unit` one of these is
+    pub amount: f64,
+    pub unit: Unit,
+}
+
+impl CustomUnit {
+    /// Whether `text` names this unit, singular or plural ("stick", "Sticks")
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.trim().to_lowercase();
+        text == self.name || text == self.plural()
+    }
+
+    /// The unit's name for `amount`: "1 stick" but "2 sticks"
+    pub fn name(&self, amount: f64) -> String {
+        if amount > 1.0 {
+            self.plural()
+        } else {
+            self.name.clone()
+        }
+    }
+
+    fn plural(&self) -> String {
+        if ["s", "x", "ch", "sh"]
+            .iter()
+            .any(|ending| self.name.ends_with(ending))
+        {
+            format!("{}es", self.name)
+        } else {
+            format!("{}s", self.name)
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    /// The canonical abbreviation; counts display as nothing ("3", not "3 each")
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+impl fmt::Display for UnitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            UnitKind::Volume => "volume",
+            UnitKind::Mass => "mass",
+            UnitKind::Count => "count",
+            UnitKind::Freeform => "freeform",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("tbsp", Unit::Tablespoon)]
+    #[case("Tablespoons", Unit::Tablespoon)]
+    #[case("T", Unit::Tablespoon)]
+    #[case("t", Unit::Teaspoon)]
+    #[case("tsp.", Unit::Teaspoon)]
+    #[case("fl oz", Unit::FluidOunce)]
+    #[case("Cups", Unit::Cup)]
+    #[case("KG", Unit::Kilogram)]
+    #[case("lbs", Unit::Pound)]
+    #[case("", Unit::Count)]
+    #[case("whole", Unit::Count)]
+    #[case("Pinch", Unit::Freeform("pinch".to_string()))]
+    fn test_parse(#[case] text: &str, #[case] expected: Unit) {
+        assert_eq!(Unit::parse(text), expected);
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for unit in [
+            Unit::Millilitre,
+            Unit::Litre,
+            Unit::Teaspoon,
+            Unit::Tablespoon,
+            Unit::FluidOunce,
+            Unit::Cup,
+            Unit::Pint,
+            Unit::Quart,
+            Unit::Gallon,
+            Unit::Gram,
+            Unit::Kilogram,
+            Unit::Ounce,
+            Unit::Pound,
+            Unit::Count,
+            Unit::Freeform("clove".to_string()),
+        ] {
+            assert_eq!(Unit::parse(&unit.to_string()), unit);
+        }
+    }
+
+    #[test]
+    fn test_custom_unit_names() {
+        let stick = CustomUnit {
+            name: "stick".to_string(),
+            amount: 113.0,
+            unit: Unit::Gram,
+        };
+        let glass = CustomUnit {
+            name: "glass".to_string(),
+            amount: 250.0,
+            unit: Unit::Millilitre,
+        };
+
+        assert!(stick.matches("Sticks"));
+        assert!(stick.matches(" stick "));
+        assert!(!stick.matches("stack"));
+        assert_eq!(stick.name(1.0), "stick");
+        assert_eq!(stick.name(2.0), "sticks");
+        assert_eq!(glass.name(3.0), "glasses");
+        assert!(glass.matches("glasses"));
+    }
+
+    #[rstest]
+    #[case(Unit::Cup, 1.0, Unit::Gram, Some(0.5), Some(118.294))]
+    #[case(Unit::Gram, 118.294, Unit::Cup, Some(0.5), Some(1.0))]
+    #[case(Unit::Kilogram, 1.0, Unit::Gram, None, Some(1000.0))]
+    #[case(Unit::Cup, 1.0, Unit::Gram, None, None)]
+    #[case(Unit::Cup, 1.0, Unit::Gram, Some(0.0), None)]
+    #[case(Unit::Count, 1.0, Unit::Gram, Some(0.5), None)]
+    fn test_convert_with_density(
+        #[case] from: Unit,
+        #[case] amount: f64,
+        #[case] to: Unit,
+        #[case] grams_per_ml: Option<f64>,
+        #[case] expected: Option<f64>,
+    ) {
+        let converted = from.convert_with_density(amount, &to, grams_per_ml);
+
+        assert_eq!(converted.is_some(), expected.is_some());
+        if let (Some(converted), Some(expected)) = (converted, expected) {
+            assert!((converted - expected).abs() < 1e-3);
+        }
+    }
+
+    #[rstest]
+    #[case(Unit::Cup, 1.0, UnitStyle::Abbreviated, "cup")]
+    #[case(Unit::Cup, 2.0, UnitStyle::Abbreviated, "cups")]
+    #[case(Unit::Cup, 0.5, UnitStyle::Full, "cup")]
+    #[case(Unit::Tablespoon, 2.0, UnitStyle::Abbreviated, "tbsp")]
+    #[case(Unit::Tablespoon, 2.0, UnitStyle::Full, "tablespoons")]
+    #[case(Unit::FluidOunce, 8.0, UnitStyle::Full, "fluid ounces")]
+    #[case(Unit::Gram, 1.0, UnitStyle::Full, "gram")]
+    #[case(Unit::Count, 3.0, UnitStyle::Full, "")]
+    #[case(Unit::Freeform("pinch".to_string()), 2.0, UnitStyle::Full, "pinch")]
+    fn test_name(
+        #[case] unit: Unit,
+        #[case] amount: f64,
+        #[case] style: UnitStyle,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(unit.name(amount, style), expected);
+    }
+
+    #[rstest]
+    #[case("tablespoons", UnitStyle::Full)]
+    #[case("Teaspoon", UnitStyle::Full)]
+    #[case("tbsp", UnitStyle::Abbreviated)]
+    #[case("g", UnitStyle::Abbreviated)]
+    #[case("pinch", UnitStyle::Abbreviated)]
+    fn test_style_of(#[case] text: &str, #[case] expected: UnitStyle) {
+        assert_eq!(UnitStyle::of(text), expected);
+    }
+
+    #[rstest]
+    #[case(Unit::Cup, 2.0, UnitSystem::Metric, 473.176, Unit::Millilitre)]
+    #[case(Unit::Gallon, 1.0, UnitSystem::Metric, 3.78541, Unit::Litre)]
+    #[case(Unit::Pound, 3.0, UnitSystem::Metric, 1.360776, Unit::Kilogram)]
+    #[case(Unit::Gram, 250.0, UnitSystem::Imperial, 8.81849, Unit::Ounce)]
+    #[case(Unit::Kilogram, 1.0, UnitSystem::Imperial, 2.20462, Unit::Pound)]
+    #[case(Unit::Millilitre, 500.0, UnitSystem::Imperial, 2.11338, Unit::Cup)]
+    #[case(
+        Unit::Millilitre,
+        30.0,
+        UnitSystem::Imperial,
+        2.02884,
+        Unit::Tablespoon
+    )]
+    #[case(Unit::Teaspoon, 1.0, UnitSystem::Metric, 1.0, Unit::Teaspoon)]
+    #[case(Unit::Gram, 100.0, UnitSystem::Metric, 100.0, Unit::Gram)]
+    #[case(Unit::Count, 2.0, UnitSystem::Imperial, 2.0, Unit::Count)]
+    fn test_in_system(
+        #[case] unit: Unit,
+        #[case] amount: f64,
+        #[case] system: UnitSystem,
+        #[case] expected_amount: f64,
+        #[case] expected_unit: Unit,
+    ) {
+        let (converted, converted_unit) = unit.in_system(amount, system);
+
+        assert_eq!(converted_unit, expected_unit);
+        assert!((converted - expected_amount).abs() < 1e-3);
+    }
+
+    #[rstest]
+    #[case(Unit::Kilogram, 1.0, Unit::Gram, Some(1000.0))]
+    #[case(Unit::Quart, 1.0, Unit::Pint, Some(2.0))]
+    #[case(Unit::Tablespoon, 1.0, Unit::Teaspoon, Some(3.0))]
+    #[case(Unit::Count, 3.0, Unit::Count, Some(3.0))]
+    #[case(Unit::Cup, 1.0, Unit::Gram, None)]
+    #[case(Unit::Freeform("pinch".to_string()), 1.0, Unit::Teaspoon, None)]
+    fn test_convert(
+        #[case] from: Unit,
+        #[case] amount: f64,
+        #[case] to: Unit,
+        #[case] expected: Option<f64>,
+    ) {
+        let converted = from.convert(amount, &to);
+
+        assert_eq!(converted.is_some(), expected.is_some());
+        if let (Some(converted), Some(expected)) = (converted, expected) {
+            assert!((converted - expected).abs() < 1e-3);
+        }
+    }
+}
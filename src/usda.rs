@@ -0,0 +1,264 @@
+//! Nutrition import from USDA FoodData Central
+//!
+//! Searches FDC's Foundation and SR Legacy foods (whose nutrients are given
+//! per 100 g, matching `ingredient_nutrition`) for an ingredient by name and
+//! stores the best match. Every search is remembered in `fdc_lookups` so an
+//! ingredient is only ever searched once, hit or miss.
+
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use crate::controllers::{get_ingredient_nutrition, set_ingredient_nutrition};
+use crate::error::{FeedMeError, Result};
+use crate::models::IngredientNutritionRecord;
+use crate::models::api::NutritionFacts;
+
+/// FDC food search endpoint
+pub const SEARCH_URL: &str = "https://api.nal.usda.gov/fdc/v1/foods/search";
+
+/// Candidate foods fetched per search; the first with an energy value is used
+const PAGE_SIZE: &str = "5";
+
+// FDC nutrient IDs
+const ENERGY_KCAL: [i64; 3] = [1008, 2047, 2048];
+const PROTEIN: i64 = 1003;
+const FAT: i64 = 1004;
+const CARBOHYDRATES: i64 = 1005;
+
+/// A food returned by FDC with its nutrients per 100 g
+#[derive(Debug, Clone, PartialEq)]
+pub struct FdcFood {
+    pub fdc_id: i64,
+    pub description: String,
+    pub per_100g: NutritionFacts,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    foods: Vec<SearchFood>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchFood {
+    fdc_id: i64,
+    description: String,
+    #[serde(default)]
+    food_nutrients: Vec<FoodNutrient>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FoodNutrient {
+    nutrient_id: i64,
+    value: Option<f64>,
+}
+
+impl SearchFood {
+    fn nutrient(&self, ids: &[i64]) -> Option<f64> {
+        ids.iter().find_map(|id| {
+            self.food_nutrients
+                .iter()
+                .find(|n| n.nutrient_id == *id)
+                .and_then(|n| n.value)
+        })
+    }
+
+    /// Foods without an energy value can't fill a nutrition row
+    fn into_food(self) -> Option<FdcFood> {
+        let per_100g = NutritionFacts {
+            calories: self.nutrient(&ENERGY_KCAL)?,
+            protein_g: self.nutrient(&[PROTEIN]).unwrap_or(0.0),
+            carbohydrates_g: self.nutrient(&[CARBOHYDRATES]).unwrap_or(0.0),
+            fat_g: self.nutrient(&[FAT]).unwrap_or(0.0),
+        };
+
+        Some(FdcFood {
+            fdc_id: self.fdc_id,
+            description: self.description,
+            per_100g,
+        })
+    }
+}
+
+/// Blocking FoodData Central client
+#[derive(Debug, Clone)]
+pub struct FdcClient {
+    api_key: String,
+    search_url: String,
+}
+
+impl FdcClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            search_url: SEARCH_URL.to_string(),
+        }
+    }
+
+    /// Point the client at another search endpoint (e.g. a local mirror)
+    pub fn with_search_url(mut self, url: impl Into<String>) -> Self {
+        self.search_url = url.into();
+        self
+    }
+
+    /// Best matching food for `query`, or None if nothing usable was found
+    pub fn search(&self, query: &str) -> Result<Option<FdcFood>> {
+        let response: SearchResponse = ureq::get(&self.search_url)
+            .query("api_key", &self.api_key)
+            .query("query", query)
+            .query("dataType", "Foundation,SR Legacy")
+            .query("pageSize", PAGE_SIZE)
+            .call()
+            .and_then(|mut response| response.body_mut().read_json())
+            .map_err(|e| FeedMeError::FdcLookup(format!("{}: {}", query, e)))?;
+
+        Ok(best_food(response))
+    }
+}
+
+fn best_food(response: SearchResponse) -> Option<FdcFood> {
+    response.foods.into_iter().find_map(SearchFood::into_food)
+}
+
+/// Import nutrition for an ingredient from FDC, searching by its name
+/// An ingredient that was searched before isn't searched again; the earlier
+/// result is returned instead. Existing nutrition is replaced, keeping its
+/// `grams_per_unit`. Returns the FDC ID used, or None if nothing matched
+pub async fn import_ingredient_nutrition(
+    pool: &SqlitePool,
+    client: &FdcClient,
+    ingredient_id: i64,
+) -> Result<Option<i64>> {
+    let cached: Option<Option<i64>> =
+        sqlx::query_scalar("SELECT fdc_id FROM fdc_lookups WHERE ingredient_id = ?")
+            .bind(ingredient_id)
+            .fetch_optional(pool)
+            .await?;
+    if let Some(fdc_id) = cached {
+        return Ok(fdc_id);
+    }
+
+    let name: String = sqlx::query_scalar("SELECT name FROM ingredients WHERE id = ?")
+        .bind(ingredient_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(FeedMeError::IngredientNotFound(ingredient_id))?;
+
+    let search = client.clone();
+    let food = tokio::task::spawn_blocking(move || search.search(&name))
+        .await
+        .map_err(|e| FeedMeError::FdcLookup(e.to_string()))??;
+
+    if let Some(food) = &food {
+        let grams_per_unit = get_ingredient_nutrition(pool, ingredient_id)
+            .await?
+            .and_then(|existing| existing.grams_per_unit);
+
+        set_ingredient_nutrition(
+            pool,
+            &IngredientNutritionRecord {
+                ingredient_id,
+                calories: food.per_100g.calories,
+                protein_g: food.per_100g.protein_g,
+                carbohydrates_g: food.per_100g.carbohydrates_g,
+                fat_g: food.per_100g.fat_g,
+                grams_per_unit,
+            },
+        )
+        .await?;
+    }
+
+    sqlx::query("INSERT INTO fdc_lookups (ingredient_id, fdc_id, description) VALUES (?, ?, ?)")
+        .bind(ingredient_id)
+        .bind(food.as_ref().map(|food| food.fdc_id))
+        .bind(food.as_ref().map(|food| &food.description))
+        .execute(pool)
+        .await?;
+
+    Ok(food.map(|food| food.fdc_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::create_ingredient;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+
+    #[test]
+    fn test_best_food_skips_foods_without_energy() {
+        let response: SearchResponse = serde_json::from_str(
+            r#"{
+                "foods": [
+                    {"fdcId": 1, "description": "Apples, raw, no energy", "foodNutrients": [
+                        {"nutrientId": 1003, "value": 0.3}
+                    ]},
+                    {"fdcId": 2, "description": "Apples, raw, with skin", "foodNutrients": [
+                        {"nutrientId": 1008, "value": 52.0},
+                        {"nutrientId": 1003, "value": 0.26},
+                        {"nutrientId": 1005, "value": 13.8},
+                        {"nutrientId": 1004, "value": 0.17}
+                    ]}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let food = best_food(response).expect("Should find a food");
+
+        assert_eq!(food.fdc_id, 2);
+        assert_eq!(food.per_100g.calories, 52.0);
+        assert_eq!(food.per_100g.carbohydrates_g, 13.8);
+    }
+
+    #[test]
+    fn test_best_food_empty_results() {
+        let response: SearchResponse = serde_json::from_str(r#"{"totalHits": 0}"#).unwrap();
+
+        assert_eq!(best_food(response), None);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_import_uses_cached_lookups(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let apple = create_ingredient(&pool, "apple").await.unwrap();
+        let kale = create_ingredient(&pool, "kale").await.unwrap();
+        sqlx::query(
+            "INSERT INTO fdc_lookups (ingredient_id, fdc_id) VALUES (?, 171688), (?, NULL)",
+        )
+        .bind(apple)
+        .bind(kale)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Nothing listens here, so any request would fail
+        let client = FdcClient::new("DEMO_KEY").with_search_url("http://127.0.0.1:9/search");
+
+        assert_eq!(
+            import_ingredient_nutrition(&pool, &client, apple)
+                .await
+                .unwrap(),
+            Some(171688)
+        );
+        assert_eq!(
+            import_ingredient_nutrition(&pool, &client, kale)
+                .await
+                .unwrap(),
+            None
+        );
+
+        let bread = create_ingredient(&pool, "bread").await.unwrap();
+        assert!(matches!(
+            import_ingredient_nutrition(&pool, &client, bread).await,
+            Err(FeedMeError::FdcLookup(_))
+        ));
+        assert!(matches!(
+            import_ingredient_nutrition(&pool, &client, 999).await,
+            Err(FeedMeError::IngredientNotFound(999))
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+//! Checks on user-supplied text before it is stored
+//!
+//! Controllers run names, notes and other free text through a [`Validator`],
+//! which collects every problem so a form can flag all bad fields at once.
+//! Failures surface as `FeedMeError::Validation`.
+//!
+//! Input that is allowed but probably a typo ("2000 cups") produces
+//! [`Warning`]s from [`recipe_warnings`] instead, for entry and import screens
+//! to show before saving.
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::error::{FeedMeError, Result};
+use crate::models::api::Recipe;
+use crate::quantity::parse_quantity;
+use crate::units::{Unit, UnitKind};
+
+/// Longest name (recipe, ingredient, tag, plan, ...) in characters
+pub const MAX_NAME_CHARS: usize = 200;
+
+/// Longest ingredient icon in characters, enough for emoji built from
+/// several code points
+pub const MAX_ICON_CHARS: usize = 8;
+
+/// Longest single line of free text (quantities, notes) in characters
+pub const MAX_LINE_CHARS: usize = 1_000;
+
+/// Longest multi-line text (instructions) in characters
+pub const MAX_TEXT_CHARS: usize = 50_000;
+
+/// Largest image attached to a recipe, in bytes (10 MiB)
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Most of one ingredient a recipe plausibly calls for, in grams (10 kg)
+pub const MAX_PLAUSIBLE_GRAMS: f64 = 10_000.0;
+
+/// Most of one ingredient a recipe plausibly calls for, in millilitres (10 l)
+pub const MAX_PLAUSIBLE_MILLILITRES: f64 = 10_000.0;
+
+/// Most of one ingredient a recipe plausibly calls for, in any other unit
+pub const MAX_PLAUSIBLE_COUNT: f64 = 100.0;
+
+/// Longest instruction step, in characters, before suggesting a split
+pub const MAX_STEP_CHARS: usize = 500;
+
+/// What is wrong with a field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Violation {
+    /// Empty or only whitespace
+    Empty,
+    /// Longer than `max` characters
+    TooLong { max: usize },
+    /// Contains a control character (line breaks are allowed in multi-line text)
+    ControlCharacter,
+    /// Refers to an item past the last of `count` (numbered from 0)
+    OutOfRange { count: usize },
+    /// Not an http(s) link
+    NotUrl,
+    /// Larger than `max_bytes`
+    TooLarge { max_bytes: usize },
+    /// Not a PNG, JPEG, GIF or WebP image
+    NotImage,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Empty => write!(f, "must not be empty"),
+            Violation::TooLong { max } => write!(f, "must be at most {} characters", max),
+            Violation::ControlCharacter => write!(f, "must not contain control characters"),
+            Violation::OutOfRange { count } => write!(f, "must be below {}", count),
+            Violation::NotUrl => write!(f, "must be an http:// or https:// link"),
+            Violation::TooLarge { max_bytes } => write!(f, "must be at most {} bytes", max_bytes),
+            Violation::NotImage => write!(f, "must be a PNG, JPEG, GIF or WebP image"),
+        }
+    }
+}
+
+/// A problem with one input field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Field path, e.g. "name" or "ingredients[2].quantity_unit"
+    pub field: String,
+    pub violation: Violation,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.violation)
+    }
+}
+
+/// Why a field looks suspicious
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Concern {
+    /// No quantity was given
+    MissingQuantity,
+    /// More than anyone would put in one recipe
+    LargeQuantity,
+    /// Step number `step` (from 1) is `chars` characters, past `MAX_STEP_CHARS`
+    LongStep { step: usize, chars: usize },
+}
+
+/// Something allowed but probably a mistake
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Warning {
+    /// Field path, e.g. "ingredients[2].quantity_unit" or "instructions[0]"
+    pub field: String,
+    /// What the field is to a person: the ingredient name or "step N"
+    pub subject: String,
+    pub concern: Concern,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.subject)?;
+        match &self.concern {
+            Concern::MissingQuantity => write!(f, "no quantity given"),
+            Concern::LargeQuantity => write!(f, "unusually large quantity"),
+            Concern::LongStep { chars, .. } => {
+                write!(f, "{} characters long; consider splitting it", chars)
+            }
+        }
+    }
+}
+
+/// Likely mistakes in a recipe about to be saved: ingredients with no or an
+/// implausibly large quantity, and instruction steps (lines) longer than
+/// `MAX_STEP_CHARS`
+pub fn recipe_warnings(recipe: &Recipe) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    for (i, ingredient) in recipe.ingredients.iter().enumerate() {
+        let field = format!("ingredients[{}].quantity_unit", i);
+        let subject = if ingredient.ingredient_name.is_empty() {
+            field.clone()
+        } else {
+            ingredient.ingredient_name.clone()
+        };
+
+        let concern = if ingredient.quantity_unit.trim().is_empty() {
+            Concern::MissingQuantity
+        } else if parse_quantity(&ingredient.quantity_unit).is_some_and(|parsed| {
+            implausible(
+                parsed.amount,
+                &Unit::parse(parsed.unit.as_deref().unwrap_or("")),
+            )
+        }) {
+            Concern::LargeQuantity
+        } else {
+            continue;
+        };
+
+        warnings.push(Warning {
+            field,
+            subject,
+            concern,
+        });
+    }
+
+    let steps = recipe.instructions.as_deref().unwrap_or("").lines();
+    for (i, step) in steps.enumerate() {
+        let chars = step.trim().chars().count();
+        if chars > MAX_STEP_CHARS {
+            warnings.push(Warning {
+                field: format!("instructions[{}]", i),
+                subject: format!("step {}", i + 1),
+                concern: Concern::LongStep { step: i + 1, chars },
+            });
+        }
+    }
+
+    warnings
+}
+
+fn implausible(amount: f64, unit: &Unit) -> bool {
+    match unit.kind() {
+        UnitKind::Mass => unit
+            .grams()
+            .is_some_and(|grams| amount * grams > MAX_PLAUSIBLE_GRAMS),
+        UnitKind::Volume => unit
+            .millilitres()
+            .is_some_and(|millilitres| amount * millilitres > MAX_PLAUSIBLE_MILLILITRES),
+        UnitKind::Count | UnitKind::Freeform => amount > MAX_PLAUSIBLE_COUNT,
+    }
+}
+
+/// Every field error, joined for `FeedMeError`'s message
+pub(crate) fn describe(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Collects field errors across a whole input
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A required single-line name; returns it trimmed
+    pub fn name<'a>(&mut self, field: impl Into<String>, value: &'a str) -> &'a str {
+        let field = field.into();
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            self.fail(field, Violation::Empty);
+        } else {
+            self.check(field, trimmed, MAX_NAME_CHARS, false);
+        }
+        trimmed
+    }
+
+    /// A required short symbol, such as an emoji; returns it trimmed
+    pub fn icon<'a>(&mut self, field: impl Into<String>, value: &'a str) -> &'a str {
+        let field = field.into();
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            self.fail(field, Violation::Empty);
+        } else {
+            self.check(field, trimmed, MAX_ICON_CHARS, false);
+        }
+        trimmed
+    }
+
+    /// An optional single line of text, such as a note
+    pub fn line(&mut self, field: impl Into<String>, value: Option<&str>) {
+        if let Some(value) = value {
+            self.check(field.into(), value, MAX_LINE_CHARS, false);
+        }
+    }
+
+    /// Optional multi-line text, such as instructions
+    pub fn text(&mut self, field: impl Into<String>, value: Option<&str>) {
+        if let Some(value) = value {
+            self.check(field.into(), value, MAX_TEXT_CHARS, true);
+        }
+    }
+
+    /// An optional web link, such as where a recipe came from
+    pub fn url(&mut self, field: impl Into<String>, value: Option<&str>) {
+        let Some(value) = value else {
+            return;
+        };
+        let field = field.into();
+        let lowercase = value.trim().to_lowercase();
+        if !(lowercase.starts_with("http://") || lowercase.starts_with("https://")) {
+            self.fail(field, Violation::NotUrl);
+        } else {
+            self.check(field, value, MAX_LINE_CHARS, false);
+        }
+    }
+
+    /// An image file's contents; returns its MIME type, told from the
+    /// first bytes rather than the file name
+    pub fn image(&mut self, field: impl Into<String>, data: &[u8]) -> Option<&'static str> {
+        let field = field.into();
+        let content_type = image_type(data);
+        if content_type.is_none() {
+            self.fail(field, Violation::NotImage);
+        } else if data.len() > MAX_IMAGE_BYTES {
+            self.fail(
+                field,
+                Violation::TooLarge {
+                    max_bytes: MAX_IMAGE_BYTES,
+                },
+            );
+        }
+        content_type
+    }
+
+    /// A position in a list of `count` items
+    pub fn index(&mut self, field: impl Into<String>, value: usize, count: usize) {
+        if value >= count {
+            self.fail(field.into(), Violation::OutOfRange { count });
+        }
+    }
+
+    /// `Err(FeedMeError::Validation)` listing every problem found, if any
+    pub fn finish(self) -> Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(FeedMeError::Validation(self.errors))
+        }
+    }
+
+    fn check(&mut self, field: String, value: &str, max: usize, multi_line: bool) {
+        if value.chars().count() > max {
+            self.fail(field, Violation::TooLong { max });
+        } else if value
+            .chars()
+            .any(|c| c.is_control() && !(multi_line && matches!(c, '\n' | '\r' | '\t')))
+        {
+            self.fail(field, Violation::ControlCharacter);
+        }
+    }
+
+    fn fail(&mut self, field: String, violation: Violation) {
+        self.errors.push(FieldError { field, violation });
+    }
+}
+
+/// MIME type of an image from its signature
+fn image_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api::{RecipeIngredient, Visibility};
+
+    fn violations(validator: Validator) -> Vec<(String, Violation)> {
+        match validator.finish() {
+            Ok(()) => Vec::new(),
+            Err(FeedMeError::Validation(errors)) => errors
+                .into_iter()
+                .map(|error| (error.field, error.violation))
+                .collect(),
+            Err(other) => panic!("unexpected error {other}"),
+        }
+    }
+
+    #[test]
+    fn test_name_is_trimmed_and_required() {
+        let mut validator = Validator::new();
+        assert_eq!(validator.name("name", "  Soup \t"), "Soup");
+        assert!(violations(validator).is_empty());
+
+        let mut validator = Validator::new();
+        validator.name("name", "   ");
+        validator.name("tag", "a\u{7}b");
+        validator.name("title", &"x".repeat(MAX_NAME_CHARS + 1));
+        assert_eq!(
+            violations(validator),
+            vec![
+                ("name".to_string(), Violation::Empty),
+                ("tag".to_string(), Violation::ControlCharacter),
+                (
+                    "title".to_string(),
+                    Violation::TooLong {
+                        max: MAX_NAME_CHARS
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_breaks_only_allowed_in_text() {
+        let mut validator = Validator::new();
+        validator.text("instructions", Some("Mix.\r\n\tBake."));
+        validator.line("note", None);
+        assert!(violations(validator).is_empty());
+
+        let mut validator = Validator::new();
+        validator.line("note", Some("first\nsecond"));
+        assert_eq!(
+            violations(validator),
+            vec![("note".to_string(), Violation::ControlCharacter)]
+        );
+    }
+
+    fn recipe(ingredients: &[(&str, &str)], instructions: &str) -> Recipe {
+        Recipe {
+            id: 0,
+            name: "Test".to_string(),
+            instructions: Some(instructions.to_string()),
+            created_at: String::new(),
+            ingredients: ingredients
+                .iter()
+                .map(|(name, quantity)| RecipeIngredient {
+                    ingredient_id: 0,
+                    ingredient_name: name.to_string(),
+                    quantity_unit: quantity.to_string(),
+                    notes: None,
+                    steps: Vec::new(),
+                })
+                .collect(),
+            servings: None,
+            timers: Vec::new(),
+            journal: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            equipment: Vec::new(),
+            visibility: Visibility::default(),
+            source_url: None,
+            source_name: None,
+            nutrition: None,
+        }
+    }
+
+    #[test]
+    fn test_recipe_warnings() {
+        let long_step = "stir ".repeat(120);
+        let recipe = recipe(
+            &[
+                ("flour", "500 g"),
+                ("water", "200 cups"),
+                ("salt", " "),
+                ("eggs", "300"),
+                ("sugar", "12 kg"),
+                ("pepper", "a pinch"),
+            ],
+            &format!("Mix.\n{}\nBake.", long_step),
+        );
+
+        let warnings: Vec<String> = recipe_warnings(&recipe)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            warnings,
+            vec![
+                "water: unusually large quantity",
+                "salt: no quantity given",
+                "eggs: unusually large quantity",
+                "sugar: unusually large quantity",
+                "step 2: 599 characters long; consider splitting it",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plausible_recipe_has_no_warnings() {
+        let recipe = recipe(
+            &[("stock", "2 l"), ("garlic", "6 cloves"), ("thyme", "")],
+            "Simmer.",
+        );
+
+        let warnings = recipe_warnings(&recipe);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "ingredients[2].quantity_unit");
+        assert_eq!(warnings[0].concern, Concern::MissingQuantity);
+    }
+
+    #[test]
+    fn test_error_message_lists_fields() {
+        let mut validator = Validator::new();
+        validator.name("name", "");
+        validator.line("ingredients[1].notes", Some("\u{0}"));
+
+        assert_eq!(
+            validator.finish().unwrap_err().to_string(),
+            "Invalid input: name must not be empty; ingredients[1].notes must not contain control characters"
+        );
+    }
+}
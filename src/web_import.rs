@@ -0,0 +1,868 @@
+//! Importing recipes from web pages
+//!
+//! Most recipe sites describe their recipes for search engines as schema.org
+//! `Recipe` JSON-LD, which `recipe_from_html` reads, so no per-site scraping
+//! is needed. `import_urls` works through a list of pages (such as an
+//! exported bookmarks folder) a few at a time, pausing between requests to
+//! the same site, and reports what became of each. Fetching pages needs the
+//! `web-import` feature.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::join_all;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::Instant;
+
+use crate::controllers::import_recipe;
+use crate::error::{FeedMeError, Result};
+use crate::models::api::{Recipe, RecipeIngredient, Visibility};
+use crate::units::{Unit, UnitKind};
+use crate::validation::{Warning, recipe_warnings};
+
+/// How hard `import_urls` may hit the web
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImportLimits {
+    /// Pages fetched at the same time
+    pub concurrency: usize,
+    /// Pause between two requests to the same site
+    pub per_domain_delay: Duration,
+}
+
+impl Default for ImportLimits {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            per_domain_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// What became of each page handed to `import_urls`, in the order given
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UrlImportReport {
+    /// Pages imported, with the new recipe's ID
+    pub imported: Vec<(String, i64)>,
+    /// Pages a recipe was already imported from
+    pub skipped: Vec<String>,
+    /// Pages that couldn't be fetched or held no usable recipe, with why
+    pub failed: Vec<(String, String)>,
+    /// Likely mistakes in the recipes imported, by page
+    pub recipe_warnings: Vec<(String, Vec<Warning>)>,
+}
+
+impl fmt::Display for UrlImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Imported {}, skipped {} already imported, {} failed",
+            self.imported.len(),
+            self.skipped.len(),
+            self.failed.len()
+        )?;
+        for (url, reason) in &self.failed {
+            writeln!(f, "  {}: {}", url, reason)?;
+        }
+        for (url, warnings) in &self.recipe_warnings {
+            writeln!(f, "Check {}:", url)?;
+            for warning in warnings {
+                writeln!(f, "  {}", warning)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The links in a list of pages, in order and without repeats
+/// Takes one URL per line (blank lines and `#` comments are skipped) or a
+/// browser's exported bookmarks file, whose links are read from `href`s
+pub fn read_url_list(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut urls = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let found = if is_web_link(line) {
+            vec![line.to_string()]
+        } else {
+            hrefs(line)
+        };
+        for url in found {
+            if seen.insert(url.clone()) {
+                urls.push(url);
+            }
+        }
+    }
+
+    urls
+}
+
+/// The `href="..."` links to web pages in an HTML line
+fn hrefs(line: &str) -> Vec<String> {
+    let lower = line.to_ascii_lowercase();
+    let mut links = Vec::new();
+    let mut rest = 0;
+
+    while let Some(start) = lower[rest..].find("href=\"") {
+        let start = rest + start + "href=\"".len();
+        let Some(len) = line[start..].find('"') else {
+            break;
+        };
+        let link = unescape(&line[start..start + len]);
+        if is_web_link(&link) {
+            links.push(link);
+        }
+        rest = start + len;
+    }
+
+    links
+}
+
+fn is_web_link(text: &str) -> bool {
+    let lower = text.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// The host a URL points at, lowercased, e.g. "example.com"
+pub fn domain(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// The recipe a page describes in schema.org JSON-LD, if any
+/// Ingredient lines are split into quantity, name and notes ("2 cups flour,
+/// sifted"); lines without an amount keep an empty quantity, which `feedme
+/// check` lists for review. The recipe isn't saved
+pub fn recipe_from_html(html: &str) -> Option<Recipe> {
+    json_ld_blocks(html)
+        .into_iter()
+        .filter_map(|block| serde_json::from_str::<Value>(block).ok())
+        .find_map(|value| find_recipe(&value).and_then(to_recipe))
+}
+
+/// The contents of every `<script type="application/ld+json">` element
+fn json_ld_blocks(html: &str) -> Vec<&str> {
+    // Lowercasing ASCII keeps byte offsets, so positions carry over to `html`
+    let lower = html.to_ascii_lowercase();
+    let mut blocks = Vec::new();
+    let mut rest = 0;
+
+    while let Some(start) = lower[rest..].find("<script") {
+        let start = rest + start;
+        let Some(open_end) = lower[start..].find('>').map(|end| start + end + 1) else {
+            break;
+        };
+        let Some(close) = lower[open_end..].find("</script").map(|end| open_end + end) else {
+            break;
+        };
+        if lower[start..open_end].contains("application/ld+json") {
+            blocks.push(&html[open_end..close]);
+        }
+        rest = close;
+    }
+
+    blocks
+}
+
+/// The first object typed `Recipe`, searching `@graph`s and nested values
+fn find_recipe(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Object(object) => {
+            let is_recipe = match object.get("@type") {
+                Some(Value::String(kind)) => kind == "Recipe",
+                Some(Value::Array(kinds)) => kinds.iter().any(|kind| kind == "Recipe"),
+                _ => false,
+            };
+            if is_recipe {
+                Some(value)
+            } else {
+                object.values().find_map(find_recipe)
+            }
+        }
+        Value::Array(values) => values.iter().find_map(find_recipe),
+        _ => None,
+    }
+}
+
+fn to_recipe(value: &Value) -> Option<Recipe> {
+    let name = plain_text(value.get("name")?.as_str()?);
+    if name.is_empty() {
+        return None;
+    }
+
+    let ingredients = value
+        .get("recipeIngredient")
+        .or_else(|| value.get("ingredients"))
+        .and_then(Value::as_array)
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(Value::as_str)
+                .filter_map(ingredient_from_line)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut steps = Vec::new();
+    if let Some(instructions) = value.get("recipeInstructions") {
+        collect_steps(instructions, &mut steps);
+    }
+
+    let source_name = ["publisher", "author"]
+        .into_iter()
+        .filter_map(|key| value.get(key))
+        .find_map(person_name);
+
+    Some(Recipe {
+        id: 0,
+        name,
+        instructions: (!steps.is_empty()).then(|| steps.join("\n")),
+        servings: value.get("recipeYield").and_then(servings),
+        source_url: None,
+        source_name,
+        ingredients,
+        tags: Vec::new(),
+        created_at: String::new(),
+        timers: Vec::new(),
+        journal: Vec::new(),
+        rating: None,
+        equipment: Vec::new(),
+        visibility: Visibility::default(),
+        nutrition: None,
+    })
+}
+
+/// Instruction text from a string, `HowToStep`s or `HowToSection`s, one
+/// step per line
+fn collect_steps(value: &Value, steps: &mut Vec<String>) {
+    match value {
+        Value::String(text) => {
+            // Some sites put every step in one string, as HTML paragraphs
+            let text = text
+                .replace("</p>", "\n")
+                .replace("<br>", "\n")
+                .replace("<br/>", "\n")
+                .replace("<br />", "\n");
+            steps.extend(text.lines().map(plain_text).filter(|step| !step.is_empty()));
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_steps(value, steps);
+            }
+        }
+        Value::Object(object) => {
+            if let Some(items) = object.get("itemListElement") {
+                collect_steps(items, steps);
+            } else if let Some(text) = object.get("text").or_else(|| object.get("name")) {
+                collect_steps(text, steps);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The first whole number in a yield: 4, "4", "Serves 4", ["4", "4 servings"]
+fn servings(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(number) => number.as_i64().filter(|&servings| servings > 0),
+        Value::String(text) => text
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|digits| !digits.is_empty())?
+            .parse()
+            .ok()
+            .filter(|&servings| servings > 0),
+        Value::Array(values) => values.iter().find_map(servings),
+        _ => None,
+    }
+}
+
+/// A name from `"Jane"`, `{"name": "Jane"}` or a list of either
+fn person_name(value: &Value) -> Option<String> {
+    let name = match value {
+        Value::String(name) => plain_text(name),
+        Value::Object(object) => plain_text(object.get("name")?.as_str()?),
+        Value::Array(values) => return values.iter().find_map(person_name),
+        _ => return None,
+    };
+    (!name.is_empty()).then_some(name)
+}
+
+/// Units written by name that `Unit` keeps as freeform text
+const COUNTED_UNITS: [&str; 20] = [
+    "pinch", "pinches", "dash", "dashes", "clove", "cloves", "can", "cans", "slice", "slices",
+    "stick", "sticks", "sprig", "sprigs", "bunch", "bunches", "handful", "handfuls", "package",
+    "packages",
+];
+
+/// Split "1 1/2 cups flour, sifted" into quantity "1 1/2 cups", name "flour"
+/// and notes "sifted"; None for a blank line
+fn ingredient_from_line(line: &str) -> Option<RecipeIngredient> {
+    let line = plain_text(line);
+    if line.is_empty() {
+        return None;
+    }
+
+    // The amount: digits, fractions and the spaces between them ("1 1/2")
+    let number_end = line
+        .find(|c: char| !(c.is_ascii_digit() || "./ ½⅓⅔¼¾⅛".contains(c)))
+        .unwrap_or(line.len());
+    let (mut quantity, mut rest) = if line[..number_end].trim().is_empty() {
+        (String::new(), line.as_str())
+    } else {
+        (line[..number_end].trim().to_string(), &line[number_end..])
+    };
+
+    // Then a unit, when the next word (or two, for "fl oz") is one
+    if !quantity.is_empty() {
+        let words: Vec<&str> = rest.split_whitespace().take(2).collect();
+        for len in [2, 1] {
+            let Some(unit) = words.get(..len).map(|words| words.join(" ")) else {
+                continue;
+            };
+            let known = !matches!(
+                Unit::parse(&unit).kind(),
+                UnitKind::Freeform | UnitKind::Count
+            );
+            if known || (len == 1 && COUNTED_UNITS.contains(&unit.to_lowercase().as_str())) {
+                quantity = format!("{} {}", quantity, unit);
+                let skip = rest.find(words[len - 1]).unwrap_or(0) + words[len - 1].len();
+                rest = &rest[skip..];
+                break;
+            }
+        }
+    }
+
+    // The name, with anything after a comma or in brackets as notes
+    let (name, mut notes) = match rest.split_once(',') {
+        Some((name, notes)) => (name.to_string(), vec![notes.trim().to_string()]),
+        None => (rest.to_string(), Vec::new()),
+    };
+    let mut bare = String::new();
+    let mut depth = 0;
+    let mut aside = String::new();
+    for c in name.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    notes.insert(0, aside.trim().to_string());
+                    aside.clear();
+                }
+            }
+            c if depth > 0 => aside.push(c),
+            c => bare.push(c),
+        }
+    }
+    let bare = bare.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = bare.strip_prefix("of ").unwrap_or(&bare).to_lowercase();
+    notes.retain(|note| !note.is_empty());
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(RecipeIngredient {
+        ingredient_id: 0,
+        ingredient_name: name,
+        quantity_unit: quantity,
+        notes: (!notes.is_empty()).then(|| notes.join("; ")),
+        steps: Vec::new(),
+    })
+}
+
+/// Text with HTML tags dropped, entities decoded and runs of whitespace
+/// collapsed
+fn plain_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    unescape(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decode the HTML entities recipe sites commonly leave in their JSON-LD
+fn unescape(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Import the recipe on every page in `urls`, fetching with `fetch`
+/// At most `limits.concurrency` pages are fetched at once and requests to
+/// the same site are `limits.per_domain_delay` apart. Pages a recipe was
+/// already imported from (by `source_url`) aren't fetched again, so an
+/// interrupted run can simply be repeated. A page that fails doesn't stop
+/// the others; it is listed in the report with the reason
+pub async fn import_urls<F>(
+    pool: &SqlitePool,
+    urls: &[String],
+    limits: ImportLimits,
+    fetch: F,
+) -> Result<UrlImportReport>
+where
+    F: Fn(&str) -> Result<String> + Send + Sync + 'static,
+{
+    let already: HashSet<String> = sqlx::query_scalar(
+        "SELECT source_url FROM recipes WHERE source_url IS NOT NULL AND deleted_at IS NULL",
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    let fetch = Arc::new(fetch);
+    let slots = Semaphore::new(limits.concurrency.max(1));
+    // When each site may next be asked for a page
+    let next_request: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+
+    let outcomes = join_all(urls.iter().map(|url| {
+        let fetch = Arc::clone(&fetch);
+        let (slots, next_request, already) = (&slots, &next_request, &already);
+        async move {
+            if already.contains(url) {
+                return Outcome::Skipped;
+            }
+
+            // Take a slot before booking a turn with the site: turns booked
+            // while waiting for a slot would all come due at once when
+            // slots free up, bunching requests to the site together
+            let Ok(_slot) = slots.acquire().await else {
+                return Outcome::Failed(FeedMeError::WebImport("cancelled".to_string()));
+            };
+            if let Some(domain) = domain(url) {
+                let turn = {
+                    let mut next_request = next_request.lock().await;
+                    let now = Instant::now();
+                    let turn = next_request.get(&domain).map_or(now, |&at| at.max(now));
+                    next_request.insert(domain, turn + limits.per_domain_delay);
+                    turn
+                };
+                tokio::time::sleep_until(turn).await;
+            }
+
+            match import_page(pool, url, fetch).await {
+                Ok((recipe_id, warnings)) => Outcome::Imported(recipe_id, warnings),
+                Err(err) => Outcome::Failed(err),
+            }
+        }
+    }))
+    .await;
+
+    let mut report = UrlImportReport::default();
+    for (url, outcome) in urls.iter().zip(outcomes) {
+        match outcome {
+            Outcome::Imported(recipe_id, warnings) => {
+                report.imported.push((url.clone(), recipe_id));
+                if !warnings.is_empty() {
+                    report.recipe_warnings.push((url.clone(), warnings));
+                }
+            }
+            Outcome::Skipped => report.skipped.push(url.clone()),
+            // Every page would fail the same way, e.g. without `web-import`
+            Outcome::Failed(err @ FeedMeError::Config(_)) => return Err(err),
+            Outcome::Failed(err) => {
+                tracing::warn!(url = %url, error = %err, "couldn't import recipe page");
+                report.failed.push((url.clone(), err.to_string()));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+enum Outcome {
+    Imported(i64, Vec<Warning>),
+    Skipped,
+    Failed(FeedMeError),
+}
+
+/// Import the recipe on one page, returning its ID and any likely mistakes
+async fn import_page<F>(pool: &SqlitePool, url: &str, fetch: Arc<F>) -> Result<(i64, Vec<Warning>)>
+where
+    F: Fn(&str) -> Result<String> + Send + Sync + 'static,
+{
+    let page = url.to_string();
+    let html = tokio::task::spawn_blocking(move || (*fetch)(&page))
+        .await
+        .map_err(|err| FeedMeError::WebImport(err.to_string()))??;
+
+    let mut recipe = recipe_from_html(&html)
+        .ok_or_else(|| FeedMeError::WebImport("no recipe found on the page".to_string()))?;
+    recipe.source_url = Some(url.to_string());
+    if recipe.source_name.is_none() {
+        recipe.source_name = domain(url);
+    }
+
+    let recipe_id = import_recipe(pool, &recipe).await?;
+
+    Ok((recipe_id, recipe_warnings(&recipe)))
+}
+
+/// Download a page's HTML
+#[cfg(feature = "web-import")]
+pub fn fetch_page(url: &str) -> Result<String> {
+    ureq::get(url)
+        .header("User-Agent", concat!("feedme/", env!("CARGO_PKG_VERSION")))
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .map_err(|err| FeedMeError::WebImport(err.to_string()))
+}
+
+#[cfg(not(feature = "web-import"))]
+pub fn fetch_page(_url: &str) -> Result<String> {
+    Err(FeedMeError::Config(
+        "importing from web pages requires building with `--features web-import`".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::get_recipe;
+    use crate::models::test_fixtures::test_db;
+    use rstest::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PAGE: &str = r#"<html><head>
+<script type="application/ld+json">{"@context": "https://schema.org", "@type": "Organization", "name": "Not a recipe"}</script>
+<SCRIPT type="application/ld+json">
+{"@context": "https://schema.org", "@graph": [
+  {"@type": "WebPage", "name": "Pancakes | Example Kitchen"},
+  {"@type": ["Recipe", "NewsArticle"],
+   "name": "Fluffy Pancakes &amp; Syrup",
+   "recipeYield": ["4", "4 servings"],
+   "publisher": {"@type": "Organization", "name": "Example Kitchen"},
+   "author": [{"@type": "Person", "name": "Jane"}],
+   "recipeIngredient": ["1 ½ cups all-purpose flour, sifted", "2 large eggs", "  "],
+   "recipeInstructions": [
+     {"@type": "HowToSection", "name": "Batter", "itemListElement": [
+       {"@type": "HowToStep", "text": "Whisk the <b>dry</b> ingredients."},
+       {"@type": "HowToStep", "text": "Beat in the eggs."}
+     ]},
+     "Fry at 350&#176;F until golden."
+   ]}
+]}
+</SCRIPT>
+</head><body>...</body></html>"#;
+
+    #[test]
+    fn test_recipe_from_html() {
+        let recipe = recipe_from_html(PAGE).expect("no recipe found");
+
+        assert_eq!(recipe.name, "Fluffy Pancakes & Syrup");
+        assert_eq!(recipe.servings, Some(4));
+        assert_eq!(recipe.source_name.as_deref(), Some("Example Kitchen"));
+        assert_eq!(
+            recipe.instructions.as_deref(),
+            Some("Whisk the dry ingredients.\nBeat in the eggs.\nFry at 350°F until golden.")
+        );
+        let ingredients: Vec<(&str, &str, Option<&str>)> = recipe
+            .ingredients
+            .iter()
+            .map(|i| {
+                (
+                    i.quantity_unit.as_str(),
+                    i.ingredient_name.as_str(),
+                    i.notes.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            ingredients,
+            vec![
+                ("1 ½ cups", "all-purpose flour", Some("sifted")),
+                ("2", "large eggs", None),
+            ]
+        );
+
+        assert!(recipe_from_html("<html><body>Just a blog post</body></html>").is_none());
+        assert!(recipe_from_html(r#"<script type="application/ld+json">{oops</script>"#).is_none());
+    }
+
+    #[test]
+    fn test_ingredient_from_line() {
+        let split = |line| {
+            let ingredient = ingredient_from_line(line).unwrap();
+            (
+                ingredient.quantity_unit,
+                ingredient.ingredient_name,
+                ingredient.notes,
+            )
+        };
+        let case = |quantity: &str, name: &str, notes: Option<&str>| {
+            (
+                quantity.to_string(),
+                name.to_string(),
+                notes.map(str::to_string),
+            )
+        };
+
+        assert_eq!(split("2 cups of Flour"), case("2 cups", "flour", None));
+        assert_eq!(
+            split("500g butter, softened"),
+            case("500 g", "butter", Some("softened"))
+        );
+        assert_eq!(split("1 1/2 fl oz rum"), case("1 1/2 fl oz", "rum", None));
+        assert_eq!(
+            split("3 cloves garlic, minced"),
+            case("3 cloves", "garlic", Some("minced"))
+        );
+        assert_eq!(
+            split("1 (14 oz) can tomatoes, drained"),
+            case("1", "can tomatoes", Some("14 oz; drained"))
+        );
+        assert_eq!(split("Salt, to taste"), case("", "salt", Some("to taste")));
+        assert!(ingredient_from_line(" <br> ").is_none());
+    }
+
+    #[test]
+    fn test_read_url_list() {
+        let text = r#"
+# weeknight ideas
+https://example.com/pancakes
+<DT><A HREF="https://cooking.test/stew?a=1&amp;b=2" ADD_DATE="1700000000">Stew</A>
+<DT><A HREF="javascript:void(0)">Bookmarklet</A>
+https://example.com/pancakes
+"#;
+
+        assert_eq!(
+            read_url_list(text),
+            vec![
+                "https://example.com/pancakes".to_string(),
+                "https://cooking.test/stew?a=1&b=2".to_string(),
+            ]
+        );
+        assert_eq!(
+            domain("https://user@Cooking.test:8080/stew?a=1").as_deref(),
+            Some("cooking.test")
+        );
+        assert_eq!(domain("not a link"), None);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_import_urls(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        sqlx::query(
+            "INSERT INTO recipes (name, source_url) VALUES ('Stew', 'https://b.test/stew')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let urls: Vec<String> = [
+            "https://a.test/pancakes",
+            "https://a.test/about",
+            "https://a.test/missing",
+            "https://b.test/stew",
+            "https://b.test/pancakes",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let fetching = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let (now, peak) = (Arc::clone(&fetching), Arc::clone(&most));
+        let fetch = move |url: &str| {
+            let running = now.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(running, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(20));
+            now.fetch_sub(1, Ordering::SeqCst);
+            match url {
+                "https://a.test/missing" => Err(FeedMeError::WebImport("404".to_string())),
+                "https://a.test/about" => Ok("<html>About us</html>".to_string()),
+                // Recipe names are unique, so b.test's version is named apart
+                "https://b.test/pancakes" => Ok(PAGE.replace("Fluffy", "Thin")),
+                _ => Ok(PAGE.to_string()),
+            }
+        };
+        let limits = ImportLimits {
+            concurrency: 1,
+            per_domain_delay: Duration::from_millis(50),
+        };
+
+        let started = std::time::Instant::now();
+        let report = import_urls(&pool, &urls, limits, fetch)
+            .await
+            .expect("Failed to import");
+
+        // Three pages from a.test are two delays apart at least
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(most.load(Ordering::SeqCst), 1);
+
+        let imported: Vec<&str> = report
+            .imported
+            .iter()
+            .map(|(url, _)| url.as_str())
+            .collect();
+        assert_eq!(
+            imported,
+            vec!["https://a.test/pancakes", "https://b.test/pancakes"]
+        );
+        assert_eq!(report.skipped, vec!["https://b.test/stew"]);
+        let failed: Vec<&str> = report.failed.iter().map(|(url, _)| url.as_str()).collect();
+        assert_eq!(
+            failed,
+            vec!["https://a.test/about", "https://a.test/missing"]
+        );
+        assert!(report.failed[0].1.contains("no recipe found"));
+        assert!(
+            report
+                .to_string()
+                .starts_with("Imported 2, skipped 1 already imported, 2 failed\n")
+        );
+
+        let recipe = get_recipe(&pool, report.imported[0].1).await.unwrap();
+        assert_eq!(recipe.name, "Fluffy Pancakes & Syrup");
+        assert_eq!(
+            recipe.source_url.as_deref(),
+            Some("https://a.test/pancakes")
+        );
+        assert_eq!(recipe.ingredients.len(), 2);
+
+        // Running again imports nothing twice
+        let again = import_urls(&pool, &urls[..1], ImportLimits::default(), |_: &str| {
+            Ok(PAGE.to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!(again.skipped, vec!["https://a.test/pancakes"]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_import_urls_reports_warnings(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        let page = r#"<script type="application/ld+json">
+{"@type": "Recipe", "name": "Popcorn", "recipeIngredient": ["1/2 cup kernels", "salt"]}
+</script>"#;
+        let urls = vec!["https://a.test/popcorn".to_string()];
+
+        let report = import_urls(&pool, &urls, ImportLimits::default(), move |_: &str| {
+            Ok(page.to_string())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(report.imported.len(), 1);
+        assert_eq!(report.recipe_warnings.len(), 1);
+        assert_eq!(report.recipe_warnings[0].0, "https://a.test/popcorn");
+        assert!(
+            report
+                .to_string()
+                .ends_with("Check https://a.test/popcorn:\n  salt: no quantity given\n")
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_import_urls_delay_with_busy_slots(#[future] test_db: SqlitePool) {
+        let pool = test_db.await;
+        // Two slow pages from other sites hold both slots while the pages
+        // from a.test wait for one
+        let urls: Vec<String> = [
+            "https://b.test/slow",
+            "https://c.test/slow",
+            "https://a.test/1",
+            "https://a.test/2",
+            "https://a.test/3",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let record = Arc::clone(&started);
+        let fetch = move |url: &str| {
+            record
+                .lock()
+                .unwrap()
+                .push((url.to_string(), std::time::Instant::now()));
+            if url.ends_with("/slow") {
+                std::thread::sleep(Duration::from_millis(150));
+            }
+            Ok("<html>About us</html>".to_string())
+        };
+        let limits = ImportLimits {
+            concurrency: 2,
+            per_domain_delay: Duration::from_millis(50),
+        };
+
+        let report = import_urls(&pool, &urls, limits, fetch)
+            .await
+            .expect("Failed to import");
+        assert_eq!(report.failed.len(), 5);
+
+        let started = started.lock().unwrap();
+        let same_site: Vec<std::time::Instant> = started
+            .iter()
+            .filter(|(url, _)| url.starts_with("https://a.test/"))
+            .map(|&(_, at)| at)
+            .collect();
+        assert_eq!(same_site.len(), 3);
+        for pair in same_site.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(45));
+        }
+    }
+}
@@ -1,5 +1,8 @@
-use feedme::controllers::{create_ingredient, create_recipe, get_recipe};
-use feedme::models::api::{Recipe, RecipeIngredient};
+use feedme::controllers::{
+    create_ingredient, create_recipe, generate_shopping_list, get_recipe, search_full_text,
+};
+use feedme::models::api::{Recipe, RecipeIngredient, Visibility};
+use feedme::seed::seed_demo_data;
 use sqlx::sqlite::SqlitePoolOptions;
 
 #[tokio::test]
@@ -52,32 +55,47 @@ async fn test_create_and_get_recipe_roundtrip() {
                 ingredient_name: "flour".to_string(),
                 quantity_unit: "2 cups".to_string(),
                 notes: Some("all-purpose".to_string()),
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: sugar_id,
                 ingredient_name: "sugar".to_string(),
                 quantity_unit: "1 cup".to_string(),
                 notes: None,
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: chocolate_id,
                 ingredient_name: "chocolate chips".to_string(),
                 quantity_unit: "2 cups".to_string(),
                 notes: Some("semi-sweet".to_string()),
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: butter_id,
                 ingredient_name: "butter".to_string(),
                 quantity_unit: "1 cup".to_string(),
                 notes: Some("softened".to_string()),
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: eggs_id,
                 ingredient_name: "eggs".to_string(),
                 quantity_unit: "2 whole".to_string(),
                 notes: None,
+                steps: Vec::new(),
             },
         ],
+        servings: None,
+        source_url: None,
+        source_name: None,
+        timers: Vec::new(),
+        journal: Vec::new(),
+        tags: Vec::new(),
+        equipment: Vec::new(),
+        visibility: Visibility::default(),
+        rating: None,
+        nutrition: None,
     };
 
     // Create the recipe
@@ -182,20 +200,33 @@ async fn test_create_multiple_recipes_with_shared_ingredients() {
                 ingredient_name: "flour".to_string(),
                 quantity_unit: "2 cups".to_string(),
                 notes: None,
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: eggs_id,
                 ingredient_name: "eggs".to_string(),
                 quantity_unit: "2 whole".to_string(),
                 notes: None,
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: milk_id,
                 ingredient_name: "milk".to_string(),
                 quantity_unit: "1 cup".to_string(),
                 notes: None,
+                steps: Vec::new(),
             },
         ],
+        servings: None,
+        timers: Vec::new(),
+        journal: Vec::new(),
+        tags: Vec::new(),
+        rating: None,
+        equipment: Vec::new(),
+        visibility: Visibility::default(),
+        source_url: None,
+        source_name: None,
+        nutrition: None,
     };
 
     let recipe1_id = create_recipe(&pool, &recipe1)
@@ -214,20 +245,33 @@ async fn test_create_multiple_recipes_with_shared_ingredients() {
                 ingredient_name: "flour".to_string(),
                 quantity_unit: "2.5 cups".to_string(),
                 notes: None,
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: eggs_id,
                 ingredient_name: "eggs".to_string(),
                 quantity_unit: "3 whole".to_string(),
                 notes: None,
+                steps: Vec::new(),
             },
             RecipeIngredient {
                 ingredient_id: butter_id,
                 ingredient_name: "butter".to_string(),
                 quantity_unit: "0.5 cup".to_string(),
                 notes: Some("melted".to_string()),
+                steps: Vec::new(),
             },
         ],
+        servings: None,
+        timers: Vec::new(),
+        journal: Vec::new(),
+        tags: Vec::new(),
+        rating: None,
+        equipment: Vec::new(),
+        visibility: Visibility::default(),
+        source_url: None,
+        source_name: None,
+        nutrition: None,
     };
 
     let recipe2_id = create_recipe(&pool, &recipe2)
@@ -262,3 +306,39 @@ async fn test_create_multiple_recipes_with_shared_ingredients() {
         "Should have 4 unique ingredients (flour, eggs, milk, butter)"
     );
 }
+
+#[tokio::test]
+async fn test_demo_dataset_supports_search_and_shopping_list() {
+    // Create an in-memory database with migrations
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create in-memory database");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to run migrations");
+
+    let recipe_ids = seed_demo_data(&pool)
+        .await
+        .expect("Failed to seed demo data");
+
+    // "lemon garlic" should find the chicken recipe
+    let results = search_full_text(&pool, "lemon garlic")
+        .await
+        .expect("Failed to search");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].name, "Lemon Garlic Chicken");
+
+    // Garlic and olive oil are shared between the chicken and the pasta
+    let shopping_list = generate_shopping_list(&pool, &recipe_ids[2..4])
+        .await
+        .expect("Failed to generate shopping list");
+    let garlic = shopping_list
+        .iter()
+        .find(|item| item.ingredient_name == "garlic")
+        .expect("Garlic not found");
+    assert_eq!(garlic.combined_quantity, "7 cloves");
+}